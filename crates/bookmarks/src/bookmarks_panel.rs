@@ -0,0 +1,300 @@
+use crate::{BookmarksPanelSettings, display_path, open_bookmark};
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use file_icons::FileIcons;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    Subscription, Task, WeakEntity, Window, actions,
+};
+use project::{
+    Fs, Project,
+    bookmark_store::{BookmarkEditAction, BookmarkEntry},
+};
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::sync::Arc;
+use ui::{ListItem, Tab, Tooltip, prelude::*};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+const BOOKMARKS_PANEL_KEY: &str = "BookmarksPanel";
+
+actions!(
+    bookmarks_panel,
+    [
+        /// Toggles focus on the bookmarks panel.
+        ToggleFocus
+    ]
+);
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<BookmarksPanel>(window, cx);
+        });
+    })
+    .detach();
+}
+
+/// A dock panel listing the project's bookmarks, grouped by file.
+pub struct BookmarksPanel {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    _subscription: Subscription,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedBookmarksPanel {
+    width: Option<Pixels>,
+}
+
+impl BookmarksPanel {
+    fn new(workspace: &mut Workspace, cx: &mut Context<Workspace>) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace_handle = cx.entity().downgrade();
+        cx.new(|cx| {
+            let bookmark_store = project.read(cx).bookmark_store();
+            Self {
+                workspace: workspace_handle,
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                pending_serialization: Task::ready(None),
+                _subscription: cx.observe(&bookmark_store, |_, _, cx| cx.notify()),
+            }
+        })
+    }
+
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            let serialized_panel = if let Some(panel) = cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(BOOKMARKS_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedBookmarksPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        BOOKMARKS_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedBookmarksPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn open(&mut self, entry: &BookmarkEntry, window: &mut Window, cx: &mut Context<Self>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                open_bookmark(workspace, entry, window, cx)
+            })
+            .log_err();
+    }
+
+    fn remove(&mut self, entry: &BookmarkEntry, cx: &mut Context<Self>) {
+        self.project
+            .read(cx)
+            .bookmark_store()
+            .update(cx, |bookmark_store, cx| {
+                bookmark_store.edit_bookmark(
+                    entry.buffer.clone(),
+                    entry.bookmark.position,
+                    BookmarkEditAction::Toggle,
+                    cx,
+                );
+            });
+    }
+
+    fn render_file_header(&self, entry: &BookmarkEntry, cx: &App) -> impl IntoElement {
+        let icon = FileIcons::get_icon(&entry.path, cx)
+            .map(|icon| Icon::from_path(icon).color(Color::Muted))
+            .unwrap_or_else(|| Icon::new(IconName::File).color(Color::Muted));
+        h_flex()
+            .px_2()
+            .pt_2()
+            .gap_1p5()
+            .child(icon.size(IconSize::Small))
+            .child(
+                Label::new(display_path(&self.project, &entry.path, cx))
+                    .size(LabelSize::Small)
+                    .truncate(),
+            )
+    }
+
+    fn render_bookmark(
+        &self,
+        ix: usize,
+        entry: BookmarkEntry,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let label = entry
+            .bookmark
+            .label
+            .as_ref()
+            .map(|label| label.to_string())
+            .unwrap_or_default();
+        let remove_entry = entry.clone();
+
+        ListItem::new(ix)
+            .inset(true)
+            .start_slot(
+                Label::new(format!("{}", entry.row + 1))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(Label::new(label).size(LabelSize::Small).truncate())
+            .end_hover_slot(
+                IconButton::new(("remove-bookmark", ix), IconName::Close)
+                    .icon_size(IconSize::XSmall)
+                    .tooltip(Tooltip::text("Remove Bookmark"))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.remove(&remove_entry, cx);
+                    })),
+            )
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.open(&entry, window, cx);
+            }))
+    }
+}
+
+impl Render for BookmarksPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entries = self
+            .project
+            .read(cx)
+            .bookmark_store()
+            .read(cx)
+            .all_bookmarks(cx);
+
+        v_flex()
+            .key_context("BookmarksPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    // Match the height of the tab bar so they line up.
+                    .h(Tab::container_height(cx))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Bookmarks")),
+            )
+            .map(|this| {
+                if entries.is_empty() {
+                    return this.child(
+                        v_flex().p_4().child(
+                            Label::new("Bookmark a line with editor: toggle bookmark.")
+                                .color(Color::Muted),
+                        ),
+                    );
+                }
+
+                let mut list = v_flex().id("bookmarks").flex_1().overflow_y_scroll().pb_2();
+                let mut previous_path = None;
+                for (ix, entry) in entries.into_iter().enumerate() {
+                    if previous_path.as_ref() != Some(&entry.path) {
+                        list = list.child(self.render_file_header(&entry, cx));
+                        previous_path = Some(entry.path.clone());
+                    }
+                    list = list.child(self.render_bookmark(ix, entry, cx));
+                }
+                this.child(list)
+            })
+    }
+}
+
+impl Focusable for BookmarksPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for BookmarksPanel {}
+
+impl Panel for BookmarksPanel {
+    fn persistent_name() -> &'static str {
+        "BookmarksPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        BookmarksPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file::<BookmarksPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| BookmarksPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        BookmarksPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Bookmark)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Bookmarks Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        4
+    }
+}
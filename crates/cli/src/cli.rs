@@ -14,6 +14,8 @@ pub enum CliRequest {
         paths: Vec<String>,
         urls: Vec<String>,
         diff_paths: Vec<[String; 2]>,
+        /// The base, local, remote and merged files of a three-way merge.
+        merge_paths: Option<[String; 4]>,
         wait: bool,
         open_new_workspace: Option<bool>,
         env: Option<HashMap<String, String>>,
@@ -92,6 +92,11 @@ struct Args {
     /// Pairs of file paths to diff. Can be specified multiple times.
     #[arg(long, action = clap::ArgAction::Append, num_args = 2, value_names = ["OLD_PATH", "NEW_PATH"])]
     diff: Vec<String>,
+    /// Resolve a three-way merge of LOCAL and REMOTE, whose common ancestor is
+    /// BASE, into MERGED. With `--wait`, exits with a failure status if
+    /// conflicts remain, so that it can be used as `git mergetool`.
+    #[arg(long, num_args = 4, value_names = ["BASE", "LOCAL", "REMOTE", "MERGED"])]
+    merge: Vec<String>,
     /// Uninstall Zed from user system
     #[cfg(all(
         any(target_os = "linux", target_os = "macos"),
@@ -243,6 +248,7 @@ fn main() -> Result<()> {
     let mut paths = vec![];
     let mut urls = vec![];
     let mut diff_paths = vec![];
+    let mut merge_paths = None;
     let mut stdin_tmp_file: Option<fs::File> = None;
     let mut anonymous_fd_tmp_files = vec![];
 
@@ -253,6 +259,15 @@ fn main() -> Result<()> {
         ]);
     }
 
+    if let [base, local, remote, merged] = args.merge.as_slice() {
+        merge_paths = Some([
+            parse_path_with_position(base)?,
+            parse_path_with_position(local)?,
+            parse_path_with_position(remote)?,
+            parse_path_with_position(merged)?,
+        ]);
+    }
+
     for path in args.paths_with_position.iter() {
         if path.starts_with("zed://")
             || path.starts_with("http://")
@@ -292,6 +307,7 @@ fn main() -> Result<()> {
                 paths,
                 urls,
                 diff_paths,
+                merge_paths,
                 wait: args.wait,
                 open_new_workspace,
                 env,
@@ -16,7 +16,7 @@ use editor::{
 };
 use fs::Fs;
 use futures::{StreamExt, lock::Mutex};
-use gpui::{App, Rgba, TestAppContext, UpdateGlobal, VisualContext, VisualTestContext};
+use gpui::{App, Entity, Rgba, TestAppContext, UpdateGlobal, VisualContext, VisualTestContext};
 use indoc::indoc;
 use language::{
     FakeLspAdapter,
@@ -24,6 +24,7 @@ use language::{
 };
 use project::{
     ProjectPath, SERVER_PROGRESS_THROTTLE_TIMEOUT,
+    bookmark_store::BookmarkEditAction,
     lsp_store::lsp_ext_command::{ExpandedMacro, LspExtExpandMacro},
     project_settings::{InlineBlameSettings, ProjectSettings},
 };
@@ -3766,6 +3767,117 @@ async fn test_add_breakpoints(cx_a: &mut TestAppContext, cx_b: &mut TestAppConte
     assert_eq!(breakpoints_a, breakpoints_b);
 }
 
+#[gpui::test]
+async fn test_bookmarks_sync_with_guests(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let executor = cx_a.executor();
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    cx_a.update(editor::init);
+    cx_b.update(editor::init);
+    client_a
+        .fs()
+        .insert_tree(
+            "/a",
+            json!({
+                "test.txt": "one\ntwo\nthree\nfour\nfive",
+            }),
+        )
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_path = ProjectPath {
+        worktree_id,
+        path: Arc::from(Path::new(&"test.txt")),
+    };
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let (workspace_b, cx_b) = client_b.build_workspace(&project_b, cx_b);
+
+    let editor_a = workspace_a
+        .update_in(cx_a, |workspace, window, cx| {
+            workspace.open_path(project_path.clone(), None, true, window, cx)
+        })
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let editor_b = workspace_b
+        .update_in(cx_b, |workspace, window, cx| {
+            workspace.open_path(project_path.clone(), None, true, window, cx)
+        })
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+
+    let bookmarked_rows = |editor: &Entity<Editor>, cx: &mut VisualTestContext| {
+        editor.update(cx, |editor, cx| {
+            editor
+                .bookmark_store()
+                .unwrap()
+                .read(cx)
+                .all_bookmarks(cx)
+                .into_iter()
+                .map(|entry| (entry.row, entry.bookmark.label))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // The host's bookmarks are sent to the guest.
+    editor_a.update_in(cx_a, |editor, window, cx| {
+        editor.toggle_bookmark(&editor::actions::ToggleBookmark, window, cx);
+    });
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    assert_eq!(bookmarked_rows(&editor_a, cx_a), vec![(0, None)]);
+    assert_eq!(bookmarked_rows(&editor_b, cx_b), vec![(0, None)]);
+
+    // The guest's edits go through the host, which sends the result back.
+    editor_b.update_in(cx_b, |editor, window, cx| {
+        editor.move_to_end(&MoveToEnd, window, cx);
+        editor.toggle_bookmark(&editor::actions::ToggleBookmark, window, cx);
+        let anchor = editor
+            .snapshot(window, cx)
+            .buffer_snapshot
+            .anchor_after(Point::new(0, 0));
+        editor.edit_bookmark_at_anchor(
+            anchor,
+            BookmarkEditAction::SetLabel(Some("start".into())),
+            cx,
+        );
+    });
+    cx_b.run_until_parked();
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    let expected = vec![(0, Some(Arc::from("start"))), (4, None)];
+    assert_eq!(bookmarked_rows(&editor_a, cx_a), expected);
+    assert_eq!(bookmarked_rows(&editor_b, cx_b), expected);
+
+    // Removing the last bookmarks of a file clears them for the guest too.
+    editor_b.update_in(cx_b, |editor, window, cx| {
+        editor.toggle_bookmark(&editor::actions::ToggleBookmark, window, cx);
+    });
+    cx_b.run_until_parked();
+    cx_a.run_until_parked();
+    editor_a.update_in(cx_a, |editor, window, cx| {
+        editor.toggle_bookmark(&editor::actions::ToggleBookmark, window, cx);
+    });
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    assert!(bookmarked_rows(&editor_a, cx_a).is_empty());
+    assert!(bookmarked_rows(&editor_b, cx_b).is_empty());
+}
+
 #[gpui::test]
 async fn test_client_can_query_lsp_ext(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
     let mut server = TestServer::start(cx_a.executor()).await;
@@ -259,6 +259,59 @@ pub struct SpawnNearestTask {
     pub reveal: task::RevealStrategy,
 }
 
+/// Starts recording a keyboard macro, or stops the recording in progress.
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct ToggleMacroRecording {
+    /// The name to save the macro under, so that it can be replayed after
+    /// other macros are recorded.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Replays a keyboard macro.
+#[derive(PartialEq, Clone, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = editor)]
+#[serde(deny_unknown_fields)]
+pub struct PlayMacro {
+    /// The name of the macro to replay. Defaults to the last recorded macro.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// How many times to replay the macro.
+    #[serde(default = "default_macro_times")]
+    pub times: usize,
+    /// Where to replay the macro.
+    #[serde(default)]
+    pub mode: MacroPlaybackMode,
+}
+
+impl Default for PlayMacro {
+    fn default() -> Self {
+        Self {
+            name: None,
+            times: default_macro_times(),
+            mode: MacroPlaybackMode::default(),
+        }
+    }
+}
+
+fn default_macro_times() -> usize {
+    1
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MacroPlaybackMode {
+    /// Replays the macro once from the current selections.
+    #[default]
+    Once,
+    /// Replays the macro separately for each selection.
+    EachCursor,
+    /// Replays the macro separately at the start of each selected line.
+    EachLine,
+}
+
 #[derive(Clone, PartialEq, Action)]
 #[action(no_json, no_register)]
 pub struct DiffClipboardWithSelectionData {
@@ -465,10 +518,14 @@ actions!(
         GoToImplementation,
         /// Goes to implementation in a split pane.
         GoToImplementationSplit,
+        /// Goes to the next bookmark in the editor.
+        GoToNextBookmark,
         /// Goes to the next change in the file.
         GoToNextChange,
         /// Goes to the parent module of the current file.
         GoToParentModule,
+        /// Goes to the previous bookmark in the editor.
+        GoToPreviousBookmark,
         /// Goes to the previous change in the file.
         GoToPreviousChange,
         /// Goes to the type definition of the symbol at cursor.
@@ -682,6 +739,8 @@ actions!(
         Tab,
         /// Removes a tab character or outdents.
         Backtab,
+        /// Toggles a bookmark at the current line.
+        ToggleBookmark,
         /// Toggles a breakpoint at the current line.
         ToggleBreakpoint,
         /// Toggles the case of selected text.
@@ -692,6 +751,8 @@ actions!(
         EnableBreakpoint,
         /// Edits the log message for a breakpoint.
         EditLogBreakpoint,
+        /// Edits the label of the bookmark at the current line.
+        EditBookmarkLabel,
         /// Toggles automatic signature help.
         ToggleAutoSignatureHelp,
         /// Toggles inline git blame display.
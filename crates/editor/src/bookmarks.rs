@@ -0,0 +1,471 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use collections::{BTreeMap, HashMap, HashSet};
+use gpui::{
+    App, ClickEvent, Context, Entity, FocusHandle, Focusable, IntoElement, Render, TextStyle,
+    WeakEntity, Window,
+};
+use language::{Buffer, Point};
+use multi_buffer::{Anchor, MultiBuffer, MultiBufferRow, MultiBufferSnapshot, ToPoint as _};
+use parking_lot::Mutex;
+use project::bookmark_store::{
+    Bookmark, BookmarkEditAction, BookmarkStore, BookmarkStoreEvent, SerializedBookmark,
+};
+use settings::Settings as _;
+use text::Bias;
+use theme::ThemeSettings;
+use ui::{IconButton, IconSize, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::Workspace;
+
+use crate::{
+    DisplayPoint, DisplayRow, Editor, EditorElement, EditorMode, EditorStyle, SelectionEffects,
+    actions::{EditBookmarkLabel, GoToNextBookmark, GoToPreviousBookmark, ToggleBookmark},
+    display_map::{
+        BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, EditorMargins, ToDisplayPoint,
+    },
+    persistence::DB,
+    scroll::Autoscroll,
+};
+
+/// Restores each local workspace's bookmarks when it's opened, and persists
+/// them whenever they change.
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, cx| {
+        let Some(workspace_id) = workspace.database_id() else {
+            return;
+        };
+        let project = workspace.project().read(cx);
+        if project.is_via_collab() {
+            return;
+        }
+        let bookmark_store = project.bookmark_store();
+
+        let mut serialized = BTreeMap::<Arc<Path>, Vec<SerializedBookmark>>::default();
+        for (path, row, label) in DB.get_bookmarks(workspace_id).log_err().unwrap_or_default() {
+            serialized
+                .entry(Arc::from(path))
+                .or_default()
+                .push(SerializedBookmark {
+                    row,
+                    label: label.map(Arc::from),
+                });
+        }
+        bookmark_store
+            .update(cx, |bookmark_store, cx| {
+                bookmark_store.with_serialized_bookmarks(serialized, cx)
+            })
+            .detach_and_log_err(cx);
+
+        cx.subscribe(
+            &bookmark_store,
+            move |_, bookmark_store, _: &BookmarkStoreEvent, cx| {
+                let bookmarks = bookmark_store
+                    .read(cx)
+                    .all_serialized_bookmarks(cx)
+                    .into_iter()
+                    .flat_map(|(path, bookmarks)| {
+                        bookmarks.into_iter().map(move |bookmark| {
+                            (
+                                path.to_path_buf(),
+                                bookmark.row,
+                                bookmark.label.map(|label| label.to_string()),
+                            )
+                        })
+                    })
+                    .collect();
+                cx.background_spawn(
+                    async move { DB.save_bookmarks(workspace_id, bookmarks).await },
+                )
+                .detach_and_log_err(cx);
+            },
+        )
+        .detach();
+    })
+    .detach();
+}
+
+impl Editor {
+    pub fn bookmark_store(&self) -> Option<Entity<BookmarkStore>> {
+        self.bookmark_store.clone()
+    }
+
+    /// Returns the bookmarks in the given rows of the multibuffer, along with
+    /// their positions in it.
+    fn bookmarks_in_range(
+        &self,
+        range: Range<Point>,
+        snapshot: &MultiBufferSnapshot,
+        cx: &App,
+    ) -> Vec<(Anchor, Bookmark)> {
+        let (Some(bookmark_store), Some(project)) = (&self.bookmark_store, &self.project) else {
+            return Vec::new();
+        };
+
+        let mut bookmarks = Vec::new();
+        for (buffer_snapshot, range, excerpt_id) in snapshot.range_to_buffer_ranges(range) {
+            let Some(buffer) = project
+                .read(cx)
+                .buffer_for_id(buffer_snapshot.remote_id(), cx)
+            else {
+                continue;
+            };
+            let range =
+                buffer_snapshot.anchor_before(range.start)..buffer_snapshot.anchor_after(range.end);
+            bookmarks.extend(
+                bookmark_store
+                    .read(cx)
+                    .bookmarks(&buffer, Some(range), buffer_snapshot, cx)
+                    .into_iter()
+                    .map(|bookmark| {
+                        let anchor = Anchor::in_buffer(
+                            excerpt_id,
+                            buffer_snapshot.remote_id(),
+                            bookmark.position,
+                        );
+                        (anchor, bookmark)
+                    }),
+            );
+        }
+        bookmarks
+    }
+
+    /// Returns the bookmarks to show in the gutter for the given display rows.
+    pub(crate) fn active_bookmarks(
+        &self,
+        range: Range<DisplayRow>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> HashMap<DisplayRow, (Anchor, Bookmark)> {
+        if self.bookmark_store.is_none() {
+            return HashMap::default();
+        }
+
+        let snapshot = self.snapshot(window, cx);
+        let range = snapshot.display_point_to_point(DisplayPoint::new(range.start, 0), Bias::Left)
+            ..snapshot.display_point_to_point(DisplayPoint::new(range.end, 0), Bias::Right);
+        self.bookmarks_in_range(range, &snapshot.buffer_snapshot, cx)
+            .into_iter()
+            .map(|(anchor, bookmark)| {
+                let row = anchor
+                    .to_point(&snapshot.buffer_snapshot)
+                    .to_display_point(&snapshot)
+                    .row();
+                (row, (anchor, bookmark))
+            })
+            .collect()
+    }
+
+    pub(crate) fn bookmark_at_row(&self, row: u32, cx: &App) -> Option<Bookmark> {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let range = Point::new(row, 0)..Point::new(row, snapshot.line_len(MultiBufferRow(row)));
+        self.bookmarks_in_range(range, &snapshot, cx)
+            .into_iter()
+            .map(|(_, bookmark)| bookmark)
+            .next()
+    }
+
+    pub fn toggle_bookmark(
+        &mut self,
+        _: &ToggleBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for anchor in self.bookmark_anchors_at_cursors(window, cx) {
+            self.edit_bookmark_at_anchor(anchor, BookmarkEditAction::Toggle, cx);
+        }
+    }
+
+    pub fn edit_bookmark_label(
+        &mut self,
+        _: &EditBookmarkLabel,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(anchor) = self
+            .bookmark_anchors_at_cursors(window, cx)
+            .into_iter()
+            .next()
+        {
+            self.add_bookmark_label_block(anchor, window, cx);
+        }
+    }
+
+    fn bookmark_anchors_at_cursors(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<Anchor> {
+        let snapshot = self.snapshot(window, cx);
+        let rows = self
+            .selections
+            .disjoint_anchors()
+            .iter()
+            .map(|selection| selection.head().to_point(&snapshot.buffer_snapshot).row)
+            .collect::<HashSet<_>>();
+        rows.into_iter()
+            .map(|row| snapshot.buffer_snapshot.anchor_after(Point::new(row, 0)))
+            .collect()
+    }
+
+    pub fn edit_bookmark_at_anchor(
+        &mut self,
+        position: Anchor,
+        action: BookmarkEditAction,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let Some(buffer_id) = position
+            .buffer_id
+            .or_else(|| snapshot.buffer_id_for_excerpt(position.excerpt_id))
+        else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).buffer(buffer_id) else {
+            return;
+        };
+
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.edit_bookmark(buffer, position.text_anchor, action, cx);
+        });
+        cx.notify();
+    }
+
+    pub fn go_to_next_bookmark(
+        &mut self,
+        _: &GoToNextBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark(true, window, cx);
+    }
+
+    pub fn go_to_previous_bookmark(
+        &mut self,
+        _: &GoToPreviousBookmark,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.go_to_bookmark(false, window, cx);
+    }
+
+    /// Moves the cursor to the closest bookmark after (or before) it, wrapping
+    /// around the end of the editor.
+    fn go_to_bookmark(&mut self, next: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let cursor_row = self.selections.newest::<Point>(cx).head().row;
+        let mut rows = self
+            .bookmarks_in_range(Point::zero()..snapshot.max_point(), &snapshot, cx)
+            .into_iter()
+            .map(|(anchor, _)| anchor.to_point(&snapshot).row)
+            .collect::<Vec<_>>();
+        rows.sort_unstable();
+        rows.dedup();
+
+        let target_row = if next {
+            rows.iter()
+                .find(|row| **row > cursor_row)
+                .or_else(|| rows.first())
+        } else {
+            rows.iter()
+                .rev()
+                .find(|row| **row < cursor_row)
+                .or_else(|| rows.last())
+        };
+        let Some(&row) = target_row else {
+            return;
+        };
+
+        self.change_selections(
+            SelectionEffects::scroll(Autoscroll::center()),
+            window,
+            cx,
+            |selections| {
+                selections.select_ranges([Point::new(row, 0)..Point::new(row, 0)]);
+            },
+        );
+    }
+
+    pub(crate) fn render_bookmark(
+        &self,
+        position: Anchor,
+        row: DisplayRow,
+        bookmark: &Bookmark,
+        cx: &mut Context<Self>,
+    ) -> IconButton {
+        let tooltip = bookmark.label.as_ref().map_or_else(
+            || SharedString::from("Bookmark"),
+            |label| label.to_string().into(),
+        );
+        let focus_handle = self.focus_handle.clone();
+
+        IconButton::new(("bookmark_indicator", row.0 as usize), IconName::Bookmark)
+            .icon_size(IconSize::XSmall)
+            .size(ui::ButtonSize::None)
+            .icon_color(Color::Accent)
+            .style(ButtonStyle::Transparent)
+            .on_click(cx.listener(move |editor, _: &ClickEvent, window, cx| {
+                window.focus(&editor.focus_handle(cx));
+                editor.edit_bookmark_at_anchor(position, BookmarkEditAction::Toggle, cx);
+            }))
+            .on_right_click(cx.listener(move |editor, event: &ClickEvent, window, cx| {
+                editor.set_breakpoint_context_menu(
+                    row,
+                    Some(position),
+                    event.position(),
+                    window,
+                    cx,
+                );
+            }))
+            .tooltip(move |window, cx| {
+                Tooltip::with_meta_in(
+                    tooltip.clone(),
+                    Some(&ToggleBookmark),
+                    "Click to remove, right-click for more options.",
+                    &focus_handle,
+                    window,
+                    cx,
+                )
+            })
+    }
+
+    pub(crate) fn add_bookmark_label_block(
+        &mut self,
+        anchor: Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let row = anchor.to_point(&self.buffer.read(cx).snapshot(cx)).row;
+        let label = self
+            .bookmark_at_row(row, cx)
+            .and_then(|bookmark| bookmark.label)
+            .map(|label| label.to_string())
+            .unwrap_or_default();
+        let weak_editor = cx.weak_entity();
+        let prompt = cx.new(|cx| BookmarkLabelPrompt::new(weak_editor, anchor, label, window, cx));
+
+        let cloned_prompt = prompt.clone();
+        let blocks = vec![BlockProperties {
+            style: BlockStyle::Sticky,
+            placement: BlockPlacement::Above(anchor),
+            height: Some(3),
+            render: Arc::new(move |cx| {
+                *cloned_prompt.read(cx).editor_margins.lock() = *cx.margins;
+                cloned_prompt.clone().into_any_element()
+            }),
+            priority: 0,
+        }];
+
+        window.focus(&prompt.focus_handle(cx));
+        let block_ids = self.insert_blocks(blocks, None, cx);
+        prompt.update(cx, |prompt, _| prompt.block_ids.extend(block_ids));
+    }
+}
+
+/// An inline prompt for naming the bookmark on a line.
+struct BookmarkLabelPrompt {
+    prompt: Entity<Editor>,
+    editor: WeakEntity<Editor>,
+    anchor: Anchor,
+    block_ids: HashSet<CustomBlockId>,
+    editor_margins: Arc<Mutex<EditorMargins>>,
+}
+
+impl BookmarkLabelPrompt {
+    fn new(
+        editor: WeakEntity<Editor>,
+        anchor: Anchor,
+        label: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let buffer = cx.new(|cx| Buffer::local(label, cx));
+        let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+        let prompt = cx.new(|cx| {
+            let mut prompt = Editor::new(EditorMode::SingleLine, buffer, None, window, cx);
+            prompt.set_show_cursor_when_unfocused(false, cx);
+            prompt.set_placeholder_text("Bookmark label", cx);
+            prompt.select_all(&Default::default(), window, cx);
+            prompt
+        });
+
+        Self {
+            prompt,
+            editor,
+            anchor,
+            block_ids: HashSet::default(),
+            editor_margins: Arc::new(Mutex::new(EditorMargins::default())),
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let label = self.prompt.read(cx).text(cx);
+        let label = label.trim();
+        let label = (!label.is_empty()).then(|| Arc::from(label));
+        self.editor
+            .update(cx, |editor, cx| {
+                editor.edit_bookmark_at_anchor(
+                    self.anchor,
+                    BookmarkEditAction::SetLabel(label),
+                    cx,
+                );
+                editor.remove_blocks(self.block_ids.clone(), None, cx);
+                cx.focus_self(window);
+            })
+            .log_err();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| {
+                editor.remove_blocks(self.block_ids.clone(), None, cx);
+                cx.focus_self(window);
+            })
+            .log_err();
+    }
+}
+
+impl Render for BookmarkLabelPrompt {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let gutter_dimensions = self.editor_margins.lock().gutter;
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.buffer_font.family.clone(),
+            font_fallbacks: settings.buffer_font.fallbacks.clone(),
+            font_size: settings.buffer_font_size(cx).into(),
+            font_weight: settings.buffer_font.weight,
+            line_height: relative(settings.buffer_line_height.value()),
+            ..Default::default()
+        };
+
+        h_flex()
+            .key_context("Editor")
+            .bg(cx.theme().colors().editor_background)
+            .border_y_1()
+            .border_color(cx.theme().status().info_border)
+            .size_full()
+            .py(window.line_height() / 2.5)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .child(h_flex().w(gutter_dimensions.full_width() + (gutter_dimensions.margin / 2.0)))
+            .child(div().flex_1().child(EditorElement::new(
+                &self.prompt,
+                EditorStyle {
+                    background: cx.theme().colors().editor_background,
+                    local_player: cx.theme().players().local(),
+                    text: text_style,
+                    ..Default::default()
+                },
+            )))
+    }
+}
+
+impl Focusable for BookmarkLabelPrompt {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.prompt.focus_handle(cx)
+    }
+}
@@ -14,6 +14,7 @@
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behavior.
 pub mod actions;
 mod blink_manager;
+mod bookmarks;
 mod clangd_ext;
 pub mod code_context_menus;
 pub mod display_map;
@@ -28,6 +29,7 @@ mod indent_guides;
 mod inlay_hint_cache;
 pub mod items;
 mod jsx_tag_auto_close;
+mod keyboard_macros;
 mod linked_editing_ranges;
 mod lsp_colors;
 mod lsp_ext;
@@ -38,6 +40,8 @@ mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod snippet_variables;
+mod sticky_scroll;
 pub mod tasks;
 
 #[cfg(test)]
@@ -64,6 +68,7 @@ pub use element::{
 pub use git::blame::BlameRenderer;
 pub use hover_popover::hover_markdown_style;
 pub use items::MAX_TAB_TITLE_LEN;
+pub use keyboard_macros::MacroRecordingIndicator;
 pub use lsp::CompletionContext;
 pub use lsp_ext::lsp_tasks;
 pub use multi_buffer::{
@@ -150,6 +155,7 @@ use project::{
     BreakpointWithPosition, CodeAction, Completion, CompletionIntent, CompletionResponse,
     CompletionSource, DisableAiSettings, DocumentHighlight, InlayHint, Location, LocationLink,
     PrepareRenameResponse, Project, ProjectItem, ProjectPath, ProjectTransaction, TaskSourceKind,
+    bookmark_store::{BookmarkEditAction, BookmarkStore},
     debugger::breakpoint_store::Breakpoint,
     debugger::{
         breakpoint_store::{
@@ -172,7 +178,8 @@ use selections_collection::{
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsLocation, SettingsStore, update_settings_file};
 use smallvec::{SmallVec, smallvec};
-use snippet::Snippet;
+use snippet::{Snippet, Transform};
+use snippet_variables::SnippetVariables;
 use std::{
     any::TypeId,
     borrow::Cow,
@@ -189,6 +196,7 @@ use std::{
     sync::Arc,
     time::{Duration, Instant},
 };
+use sticky_scroll::StickyScopesCache;
 use sum_tree::TreeMap;
 use task::{ResolvedTask, RunnableTag, TaskTemplate, TaskVariables};
 use text::{BufferId, FromAnchor, OffsetUtf16, Rope};
@@ -343,6 +351,8 @@ pub fn init(cx: &mut App) {
     workspace::register_project_item::<Editor>(cx);
     workspace::FollowableViewRegistry::register::<Editor>(cx);
     workspace::register_serializable_item::<Editor>(cx);
+    bookmarks::init(cx);
+    keyboard_macros::init(cx);
 
     cx.observe_new(
         |workspace: &mut Workspace, _: Option<&mut Window>, _cx: &mut Context<Workspace>| {
@@ -1140,6 +1150,7 @@ pub struct Editor {
     tasks: BTreeMap<(BufferId, BufferRow), RunnableTasks>,
     tasks_update_task: Option<Task<()>>,
     breakpoint_store: Option<Entity<BreakpointStore>>,
+    bookmark_store: Option<Entity<BookmarkStore>>,
     gutter_breakpoint_indicator: (Option<PhantomBreakpointIndicator>, Option<Task<()>>),
     hovered_diff_hunk_row: Option<DisplayRow>,
     pull_diagnostics_task: Task<()>,
@@ -1148,6 +1159,7 @@ pub struct Editor {
     breadcrumb_header: Option<String>,
     focused_block: Option<FocusedBlock>,
     next_scroll_position: NextScrollCursorCenterTopBottom,
+    sticky_scopes_cache: StickyScopesCache,
     addons: HashMap<TypeId, Box<dyn Addon>>,
     registered_buffers: HashMap<BufferId, OpenLspBufferHandle>,
     load_diff_task: Option<Shared<Task<()>>>,
@@ -1498,6 +1510,12 @@ struct SnippetState {
     ranges: Vec<Vec<Range<Anchor>>>,
     active_index: usize,
     choices: Vec<Option<Vec<String>>>,
+    /// For each tabstop, the ranges whose text is derived from it.
+    transforms: Vec<Vec<(Range<Anchor>, Transform)>>,
+    /// The transaction on top of the undo stack after the last undo or redo.
+    /// Until another edit is made, the snippet's text came from the history,
+    /// so transforms aren't applied or merged into that transaction.
+    restored_transaction: Option<TransactionId>,
 }
 
 #[doc(hidden)]
@@ -1986,6 +2004,10 @@ impl Editor {
             (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).breakpoint_store()),
             _ => None,
         };
+        let bookmark_store = match (&mode, project.as_ref()) {
+            (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).bookmark_store()),
+            _ => None,
+        };
 
         let mut code_action_providers = Vec::new();
         let mut load_uncommitted_diff = None;
@@ -2143,6 +2165,7 @@ impl Editor {
             tasks: BTreeMap::default(),
 
             breakpoint_store,
+            bookmark_store,
             gutter_breakpoint_indicator: (None, None),
             hovered_diff_hunk_row: None,
             _subscriptions: (!is_minimap)
@@ -2180,6 +2203,7 @@ impl Editor {
             breadcrumb_header: None,
             focused_block: None,
             next_scroll_position: NextScrollCursorCenterTopBottom::default(),
+            sticky_scopes_cache: StickyScopesCache::default(),
             addons: HashMap::default(),
             registered_buffers: HashMap::default(),
             _scroll_cursor_center_top_bottom_task: Task::ready(()),
@@ -2212,6 +2236,13 @@ impl Editor {
                     cx.notify();
                 }));
         }
+        if let Some(bookmarks) = editor.bookmark_store.as_ref() {
+            editor
+                ._subscriptions
+                .push(cx.observe(bookmarks, |_, _, cx| {
+                    cx.notify();
+                }));
+        }
         editor.tasks_update_task = Some(editor.refresh_runnables(window, cx));
         editor._subscriptions.extend(project_subscriptions);
 
@@ -5800,6 +5831,13 @@ impl Editor {
         cx.stop_propagation();
 
         let buffer_handle = completions_menu.buffer.clone();
+        let selected_text = {
+            let snapshot = self.buffer.read(cx).snapshot(cx);
+            let selection = self.selections.newest::<usize>(cx);
+            snapshot
+                .text_for_range(selection.range())
+                .collect::<String>()
+        };
 
         let CompletionEdit {
             new_text,
@@ -5810,6 +5848,7 @@ impl Editor {
             intent,
             &buffer_handle,
             &completions_menu.initial_position.text_anchor,
+            selected_text,
             cx,
         );
 
@@ -7988,6 +8027,17 @@ impl Editor {
             BreakpointState::Disabled => Some("Enable"),
         });
 
+        let bookmark_msgs = self.bookmark_store.is_some().then(|| {
+            let bookmark = self.bookmark_at_row(row, cx);
+            if bookmark.is_some() {
+                ("Remove Bookmark", "Edit Bookmark Label")
+            } else {
+                ("Set Bookmark", "Set Bookmark Label")
+            }
+        });
+        let bookmark_anchor = anchor;
+        let bookmark_editor = weak_editor.clone();
+
         let (anchor, breakpoint) =
             breakpoint.unwrap_or_else(|| (anchor, Arc::new(Breakpoint::new_standard())));
 
@@ -8093,6 +8143,30 @@ impl Editor {
                         })
                         .log_err();
                 })
+                .when_some(bookmark_msgs, |this, (toggle_msg, label_msg)| {
+                    this.separator()
+                        .entry(toggle_msg, None, {
+                            let weak_editor = bookmark_editor.clone();
+                            move |_window, cx| {
+                                weak_editor
+                                    .update(cx, |this, cx| {
+                                        this.edit_bookmark_at_anchor(
+                                            bookmark_anchor,
+                                            BookmarkEditAction::Toggle,
+                                            cx,
+                                        );
+                                    })
+                                    .log_err();
+                            }
+                        })
+                        .entry(label_msg, None, move |window, cx| {
+                            bookmark_editor
+                                .update(cx, |this, cx| {
+                                    this.add_bookmark_label_block(bookmark_anchor, window, cx);
+                                })
+                                .log_err();
+                        })
+                })
         })
     }
 
@@ -9526,6 +9600,7 @@ impl Editor {
             is_end_tabstop: bool,
             ranges: Vec<Range<T>>,
             choices: Option<Vec<String>>,
+            transforms: Vec<(Range<T>, Transform)>,
         }
 
         let tabstops = self.buffer.update(cx, |buffer, cx| {
@@ -9548,30 +9623,40 @@ impl Editor {
                     let is_end_tabstop = tabstop.ranges.first().map_or(false, |tabstop| {
                         tabstop.is_empty() && tabstop.start == snippet.text.len() as isize
                     });
+                    let anchor_ranges = |tabstop_range: &Range<isize>| {
+                        let tabstop_range = tabstop_range.clone();
+                        let mut delta = 0_isize;
+                        insertion_ranges.iter().map(move |insertion_range| {
+                            let insertion_start = insertion_range.start as isize + delta;
+                            delta += snippet.text.len() as isize - insertion_range.len() as isize;
+
+                            let start = ((insertion_start + tabstop_range.start) as usize)
+                                .min(snapshot.len());
+                            let end = ((insertion_start + tabstop_range.end) as usize)
+                                .min(snapshot.len());
+                            snapshot.anchor_before(start)..snapshot.anchor_after(end)
+                        })
+                    };
                     let mut tabstop_ranges = tabstop
                         .ranges
                         .iter()
-                        .flat_map(|tabstop_range| {
-                            let mut delta = 0_isize;
-                            insertion_ranges.iter().map(move |insertion_range| {
-                                let insertion_start = insertion_range.start as isize + delta;
-                                delta +=
-                                    snippet.text.len() as isize - insertion_range.len() as isize;
-
-                                let start = ((insertion_start + tabstop_range.start) as usize)
-                                    .min(snapshot.len());
-                                let end = ((insertion_start + tabstop_range.end) as usize)
-                                    .min(snapshot.len());
-                                snapshot.anchor_before(start)..snapshot.anchor_after(end)
-                            })
-                        })
+                        .flat_map(&anchor_ranges)
                         .collect::<Vec<_>>();
                     tabstop_ranges.sort_unstable_by(|a, b| a.start.cmp(&b.start, snapshot));
+                    let transforms = tabstop
+                        .transforms
+                        .iter()
+                        .flat_map(|transformed| {
+                            anchor_ranges(&transformed.range)
+                                .map(|range| (range, transformed.transform.clone()))
+                        })
+                        .collect();
 
                     Tabstop {
                         is_end_tabstop,
                         ranges: tabstop_ranges,
                         choices: tabstop.choices.clone(),
+                        transforms,
                     }
                 })
                 .collect::<Vec<_>>()
@@ -9597,15 +9682,19 @@ impl Editor {
                     .map(|tabstop| tabstop.choices.clone())
                     .collect();
 
-                let ranges = tabstops
-                    .into_iter()
-                    .map(|tabstop| tabstop.ranges)
-                    .collect::<Vec<_>>();
+                let mut ranges = Vec::with_capacity(tabstops.len());
+                let mut transforms = Vec::with_capacity(tabstops.len());
+                for tabstop in tabstops {
+                    ranges.push(tabstop.ranges);
+                    transforms.push(tabstop.transforms);
+                }
 
                 self.snippet_stack.push(SnippetState {
                     active_index: 0,
                     ranges,
                     choices,
+                    transforms,
+                    restored_transaction: None,
                 });
             }
 
@@ -9672,6 +9761,67 @@ impl Editor {
         Ok(())
     }
 
+    /// Updates the text derived from each tabstop of the active snippet, such
+    /// as `${1/(.*)/${1:/upcase}/}`, after the tabstop is edited.
+    fn update_snippet_transforms(&mut self, cx: &mut Context<Self>) {
+        let Some(snippet) = self.snippet_stack.last() else {
+            return;
+        };
+        // Fold the update into the edit that caused it, so that they're undone
+        // together. Undo and redo don't cause edits of their own, and with
+        // nothing left to undo the snippet itself was undone.
+        let Some(last_transaction) = self.buffer.read(cx).last_transaction_id(cx) else {
+            return;
+        };
+        if snippet.restored_transaction == Some(last_transaction) {
+            return;
+        }
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        for (ranges, transforms) in snippet.ranges.iter().zip(&snippet.transforms) {
+            let Some(source) = ranges.first().filter(|_| !transforms.is_empty()) else {
+                continue;
+            };
+            let value = snapshot.text_for_range(source.clone()).collect::<String>();
+            for (range, transform) in transforms {
+                let new_text = transform.apply(&value);
+                if snapshot.text_for_range(range.clone()).collect::<String>() != new_text {
+                    edits.push((range.clone(), new_text));
+                }
+            }
+        }
+        if edits.is_empty() {
+            return;
+        }
+
+        self.buffer.update(cx, |buffer, cx| {
+            buffer.start_transaction(cx);
+            buffer.edit(edits, None, cx);
+            // The update may already have been grouped with the edit.
+            if let Some(transaction) = buffer.end_transaction(cx)
+                && transaction != last_transaction
+            {
+                buffer.merge_transactions(transaction, last_transaction, cx);
+            }
+        });
+    }
+
+    /// Marks the snippet's text as restored by an undo or redo. The next edit
+    /// isn't grouped into the restored transaction, so that its transforms are
+    /// applied again.
+    fn snippet_restored_from_history(&mut self, cx: &mut Context<Self>) {
+        if self.snippet_stack.is_empty() {
+            return;
+        }
+        let last_transaction = self.buffer.update(cx, |buffer, cx| {
+            buffer.finalize_last_transaction(cx);
+            buffer.last_transaction_id(cx)
+        });
+        if let Some(snippet) = self.snippet_stack.last_mut() {
+            snippet.restored_transaction = last_transaction;
+        }
+    }
+
     pub fn move_to_next_snippet_tabstop(
         &mut self,
         window: &mut Window,
@@ -12297,6 +12447,7 @@ impl Editor {
         self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
 
         if let Some(transaction_id) = self.buffer.update(cx, |buffer, cx| buffer.undo(cx)) {
+            self.snippet_restored_from_history(cx);
             if let Some((selections, _)) =
                 self.selection_history.transaction(transaction_id).cloned()
             {
@@ -12327,6 +12478,7 @@ impl Editor {
         self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
 
         if let Some(transaction_id) = self.buffer.update(cx, |buffer, cx| buffer.redo(cx)) {
+            self.snippet_restored_from_history(cx);
             if let Some((_, Some(selections))) =
                 self.selection_history.transaction(transaction_id).cloned()
             {
@@ -19982,6 +20134,7 @@ impl Editor {
                         });
                     }
                 }
+                self.update_snippet_transforms(cx);
                 cx.emit(EditorEvent::BufferEdited);
                 cx.emit(SearchEvent::MatchesInvalidated);
 
@@ -21159,6 +21312,7 @@ fn process_completion_for_edit(
     intent: CompletionIntent,
     buffer: &Entity<Buffer>,
     cursor_position: &text::Anchor,
+    selected_text: String,
     cx: &mut Context<Editor>,
 ) -> CompletionEdit {
     let buffer = buffer.read(cx);
@@ -21182,7 +21336,10 @@ fn process_completion_for_edit(
                 }
             }
         }
-        match Snippet::parse(&snippet_source).log_err() {
+        let variables = SnippetVariables::new(buffer, *cursor_position, selected_text);
+        match Snippet::parse_with_variables(&snippet_source, |name| variables.resolve(name, cx))
+            .log_err()
+        {
             Some(parsed_snippet) => (Some(parsed_snippet.clone()), parsed_snippet.text),
             None => (None, completion.new_text.clone()),
         }
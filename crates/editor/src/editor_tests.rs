@@ -3,6 +3,7 @@ use crate::{
     JoinLines,
     code_context_menus::CodeContextMenu,
     edit_prediction_tests::FakeEditPredictionProvider,
+    editor_settings::StickyScrollContent,
     linked_editing_ranges::LinkedEditingRanges,
     scroll::scroll_amount::ScrollAmount,
     test::{
@@ -9448,6 +9449,87 @@ async fn test_snippets(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_snippet_transforms(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        ˇ
+        ˇ
+    "});
+    cx.update_editor(|editor, window, cx| {
+        let snippet =
+            Snippet::parse("const ${1:name}: &str = \"${1/(.*)/${1:/upcase}/}\";$0").unwrap();
+        let insertion_ranges = editor
+            .selections
+            .all(cx)
+            .iter()
+            .map(|s| s.range().clone())
+            .collect::<Vec<_>>();
+        editor
+            .insert_snippet(&insertion_ranges, snippet, window, cx)
+            .unwrap();
+    });
+    cx.assert_editor_state(indoc! {"
+        const «nameˇ»: &str = "NAME";
+        const «nameˇ»: &str = "NAME";
+    "});
+
+    // Text derived from a tabstop is updated as it's edited.
+    cx.simulate_keystrokes("m a x _ l e n");
+    cx.assert_editor_state(indoc! {"
+        const max_lenˇ: &str = "MAX_LEN";
+        const max_lenˇ: &str = "MAX_LEN";
+    "});
+
+    cx.update_editor(|editor, window, cx| assert!(editor.move_to_next_snippet_tabstop(window, cx)));
+    cx.assert_editor_state(indoc! {"
+        const max_len: &str = "MAX_LEN";ˇ
+        const max_len: &str = "MAX_LEN";ˇ
+    "});
+}
+
+#[gpui::test]
+async fn test_snippet_transforms_undo_and_redo(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("ˇ\n");
+    cx.update_editor(|editor, window, cx| {
+        let snippet =
+            Snippet::parse("const ${1:name}: &str = \"${1/(.*)/${1:/upcase}/}\";$0").unwrap();
+        let insertion_ranges = editor
+            .selections
+            .all(cx)
+            .iter()
+            .map(|s| s.range().clone())
+            .collect::<Vec<_>>();
+        editor
+            .insert_snippet(&insertion_ranges, snippet, window, cx)
+            .unwrap();
+        editor.finalize_last_transaction(cx);
+    });
+
+    // The derived text is undone and redone along with the edit that caused it.
+    cx.simulate_keystrokes("m a x");
+    cx.assert_editor_state("const maxˇ: &str = \"MAX\";\n");
+    cx.update_editor(|editor, window, cx| editor.undo(&Undo, window, cx));
+    cx.assert_editor_state("const «nameˇ»: &str = \"NAME\";\n");
+    cx.update_editor(|editor, window, cx| editor.redo(&Redo, window, cx));
+    cx.assert_editor_state("const maxˇ: &str = \"MAX\";\n");
+
+    // Edits after an undo keep updating the derived text, without being
+    // folded into the transaction the undo restored.
+    cx.update_editor(|editor, window, cx| editor.undo(&Undo, window, cx));
+    cx.simulate_keystrokes("i d");
+    cx.assert_editor_state("const idˇ: &str = \"ID\";\n");
+    cx.update_editor(|editor, window, cx| editor.undo(&Undo, window, cx));
+    cx.assert_editor_state("const «nameˇ»: &str = \"NAME\";\n");
+}
+
 #[gpui::test]
 async fn test_snippet_indentation(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -21304,6 +21386,177 @@ async fn test_breakpoint_enabling_and_disabling(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_bookmark_toggling_and_labels(cx: &mut TestAppContext) {
+    let (editor, cx) =
+        build_bookmark_editor("First line\nSecond line\nThird line\nFourth line", cx).await;
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+        editor.move_to_end(&MoveToEnd, window, cx);
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmarked_rows(&editor, cx), vec![(0, None), (3, None)]);
+
+    // Labelling a bookmarked row keeps the bookmark, and labelling an unmarked row adds one.
+    editor.update_in(cx, |editor, window, cx| {
+        for (row, label) in [(3, "later"), (1, "start here")] {
+            let anchor = editor
+                .snapshot(window, cx)
+                .buffer_snapshot
+                .anchor_after(Point::new(row, 0));
+            editor.edit_bookmark_at_anchor(
+                anchor,
+                BookmarkEditAction::SetLabel(Some(label.into())),
+                cx,
+            );
+        }
+    });
+    assert_eq!(
+        bookmarked_rows(&editor, cx),
+        vec![
+            (0, None),
+            (1, Some(Arc::from("start here"))),
+            (3, Some(Arc::from("later")))
+        ]
+    );
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.move_to_beginning(&MoveToBeginning, window, cx);
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+        editor.move_to_end(&MoveToEnd, window, cx);
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(
+        bookmarked_rows(&editor, cx),
+        vec![(1, Some(Arc::from("start here")))]
+    );
+}
+
+#[gpui::test]
+async fn test_bookmarks_follow_edits(cx: &mut TestAppContext) {
+    let (editor, cx) = build_bookmark_editor("one\ntwo\nthree\nfour", cx).await;
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(2, 1)..Point::new(2, 1)])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmarked_rows(&editor, cx), vec![(2, None)]);
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.insert("zero\nhalf\n", window, cx);
+    });
+    assert_eq!(
+        editor.update(cx, |editor, cx| editor.text(cx)),
+        "zero\nhalf\none\ntwo\nthree\nfour"
+    );
+    assert_eq!(bookmarked_rows(&editor, cx), vec![(4, None)]);
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(1, 0)..Point::new(3, 0)])
+        });
+        editor.backspace(&Backspace, window, cx);
+    });
+    assert_eq!(bookmarked_rows(&editor, cx), vec![(2, None)]);
+
+    // Edits below the bookmark don't move it.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.move_to_end(&MoveToEnd, window, cx);
+        editor.insert("\nfive", window, cx);
+    });
+    assert_eq!(
+        editor.update(cx, |editor, cx| editor.text(cx)),
+        "zero\ntwo\nthree\nfour\nfive"
+    );
+    assert_eq!(bookmarked_rows(&editor, cx), vec![(2, None)]);
+}
+
+#[gpui::test]
+async fn test_bookmark_navigation_wraps_around(cx: &mut TestAppContext) {
+    let (editor, cx) = build_bookmark_editor("one\ntwo\nthree\nfour\nfive", cx).await;
+
+    editor.update_in(cx, |editor, window, cx| {
+        for row in [1, 3] {
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.select_ranges([Point::new(row, 0)..Point::new(row, 0)])
+            });
+            editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+        }
+        editor.move_to_beginning(&MoveToBeginning, window, cx);
+    });
+
+    let mut rows = Vec::new();
+    for _ in 0..3 {
+        editor.update_in(cx, |editor, window, cx| {
+            editor.go_to_next_bookmark(&actions::GoToNextBookmark, window, cx);
+            rows.push(editor.selections.newest::<Point>(cx).head().row);
+        });
+    }
+    assert_eq!(rows, vec![1, 3, 1]);
+
+    rows.clear();
+    for _ in 0..3 {
+        editor.update_in(cx, |editor, window, cx| {
+            editor.go_to_previous_bookmark(&actions::GoToPreviousBookmark, window, cx);
+            rows.push(editor.selections.newest::<Point>(cx).head().row);
+        });
+    }
+    assert_eq!(rows, vec![3, 1, 3]);
+}
+
+async fn build_bookmark_editor<'a>(
+    text: &str,
+    cx: &'a mut TestAppContext,
+) -> (Entity<Editor>, &'a mut VisualTestContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/a"), json!({ "main.rs": text }))
+        .await;
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, "main.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    cx.add_window_view(|window, cx| {
+        Editor::new(
+            EditorMode::full(),
+            MultiBuffer::build_from_buffer(buffer, cx),
+            Some(project),
+            window,
+            cx,
+        )
+    })
+}
+
+fn bookmarked_rows(
+    editor: &Entity<Editor>,
+    cx: &mut VisualTestContext,
+) -> Vec<(u32, Option<Arc<str>>)> {
+    editor.update(cx, |editor, cx| {
+        editor
+            .bookmark_store()
+            .unwrap()
+            .read(cx)
+            .all_bookmarks(cx)
+            .into_iter()
+            .map(|entry| (entry.row, entry.bookmark.label))
+            .collect()
+    })
+}
+
 #[gpui::test]
 async fn test_rename_with_duplicate_edits(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -24071,6 +24324,93 @@ async fn test_newline_replacement_in_single_line(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_sticky_scopes(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::LANGUAGE.into()),
+    )
+    .with_outline_query(indoc! {r#"
+        (impl_item "impl" @context type: (_) @name) @item
+        (function_item "fn" @context name: (_) @name) @item
+    "#})
+    .unwrap();
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(Arc::new(language)), cx));
+    cx.set_state(indoc! {"
+        impl Foo {
+            fn bar() {
+                let a = 1;
+                let b = 2;
+                let c = 3;
+            }
+        }ˇ
+    "});
+    cx.run_until_parked();
+
+    let sticky_scopes = |cx: &mut EditorTestContext, scroll_top: f32| {
+        cx.update_editor(|editor, window, cx| {
+            let snapshot = editor.snapshot(window, cx);
+            editor
+                .sticky_scopes(&snapshot, scroll_top, cx)
+                .into_iter()
+                .map(|scope| (scope.text, scope.indent))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    assert_eq!(sticky_scopes(&mut cx, 0.), Vec::new());
+    assert_eq!(
+        sticky_scopes(&mut cx, 3.),
+        vec![("impl Foo".to_string(), 0), ("fn bar".to_string(), 4)]
+    );
+    // The function ends on the line its header would cover.
+    assert_eq!(
+        sticky_scopes(&mut cx, 5.),
+        vec![("impl Foo".to_string(), 0)]
+    );
+
+    cx.update(|_, cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.sticky_scroll = Some(StickyScrollContent {
+                    enabled: Some(true),
+                    max_depth: Some(1),
+                });
+            });
+        });
+    });
+    assert_eq!(
+        sticky_scopes(&mut cx, 3.),
+        vec![("impl Foo".to_string(), 0)]
+    );
+
+    // Edits invalidate the scopes computed for the same viewport.
+    cx.update(|_, cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.sticky_scroll = None;
+            });
+        });
+    });
+    assert_eq!(
+        sticky_scopes(&mut cx, 3.),
+        vec![("impl Foo".to_string(), 0), ("fn bar".to_string(), 4)]
+    );
+    cx.update_buffer(|buffer, cx| {
+        buffer.edit([(Point::new(1, 7)..Point::new(1, 10), "baz")], None, cx)
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        sticky_scopes(&mut cx, 3.),
+        vec![("impl Foo".to_string(), 0), ("fn baz".to_string(), 4)]
+    );
+}
+
 #[track_caller]
 fn extract_color_inlays(editor: &Editor, cx: &App) -> Vec<Rgba> {
     editor
@@ -24080,3 +24420,133 @@ fn extract_color_inlays(editor: &Editor, cx: &App) -> Vec<Rgba> {
         .map(Rgba::from)
         .collect()
 }
+
+#[gpui::test]
+async fn test_keyboard_macro_playback_per_line(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        cx.bind_keys([gpui::KeyBinding::new("ctrl-f", MoveRight, Some("Editor"))]);
+    });
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("ˇab\ncd\nef");
+    cx.update_editor(|editor, window, cx| {
+        editor.toggle_macro_recording(&ToggleMacroRecording { name: None }, window, cx);
+    });
+    cx.simulate_keystrokes("- ctrl-f");
+    cx.update_editor(|editor, window, cx| {
+        editor.toggle_macro_recording(&ToggleMacroRecording { name: None }, window, cx);
+    });
+    cx.assert_editor_state("-aˇb\ncd\nef");
+
+    cx.set_state("ab\n«cd\nefˇ»");
+    cx.update_editor(|editor, window, cx| {
+        editor.play_macro(
+            &PlayMacro {
+                mode: MacroPlaybackMode::EachLine,
+                ..PlayMacro::default()
+            },
+            window,
+            cx,
+        );
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state("ab\n-cˇd\n-eˇf");
+
+    // Replaying once with a repeat count applies the macro at the cursor.
+    cx.set_state("ˇabc");
+    cx.update_editor(|editor, window, cx| {
+        editor.play_macro(
+            &PlayMacro {
+                times: 2,
+                ..PlayMacro::default()
+            },
+            window,
+            cx,
+        );
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state("-a-bˇc");
+}
+
+#[gpui::test]
+async fn test_keyboard_macro_records_actions_without_key_bindings(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Actions dispatched from menus or the command palette are recorded like
+    // the ones dispatched by key bindings.
+    cx.set_state("ˇab\ncd");
+    cx.dispatch_action(ToggleMacroRecording { name: None });
+    cx.simulate_keystrokes("-");
+    cx.dispatch_action(MoveRight);
+    cx.dispatch_action(ToggleMacroRecording { name: None });
+    cx.run_until_parked();
+    cx.assert_editor_state("-aˇb\ncd");
+
+    cx.set_state("ab\nˇcd");
+    cx.dispatch_action(PlayMacro::default());
+    cx.run_until_parked();
+    cx.assert_editor_state("ab\n-cˇd");
+}
+
+#[gpui::test]
+async fn test_named_keyboard_macros_are_persisted(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    init_test(cx_a, |_| {});
+    cx_a.update(|cx| {
+        cx.bind_keys([gpui::KeyBinding::new("ctrl-f", MoveRight, Some("Editor"))]);
+    });
+    let mut cx = EditorTestContext::new(cx_a).await;
+
+    let name = Some("test-named-macro".to_string());
+    cx.set_state("ˇab");
+    cx.dispatch_action(ToggleMacroRecording { name: name.clone() });
+    cx.simulate_keystrokes("- ctrl-f");
+    cx.dispatch_action(ToggleMacroRecording { name: None });
+    cx.run_until_parked();
+
+    // Recording another macro replaces the last one, but not the named one.
+    cx.dispatch_action(ToggleMacroRecording { name: None });
+    cx.simulate_keystrokes("+");
+    cx.dispatch_action(ToggleMacroRecording { name: None });
+    cx.run_until_parked();
+
+    cx.set_state("ˇcd");
+    cx.dispatch_action(PlayMacro {
+        name: name.clone(),
+        ..PlayMacro::default()
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state("-cˇd");
+    cx.dispatch_action(PlayMacro::default());
+    cx.run_until_parked();
+    cx.assert_editor_state("-c+ˇd");
+
+    let saved = db::kvp::KEY_VALUE_STORE
+        .read_kvp("editor_keyboard_macros")
+        .unwrap()
+        .unwrap();
+    let saved = serde_json::from_str::<serde_json::Value>(&saved).unwrap();
+    assert_eq!(
+        saved["named"]["test-named-macro"],
+        json!([
+            { "insert": { "text": "-" } },
+            { "action": { "name": "editor::MoveRight" } }
+        ])
+    );
+
+    // Named macros are loaded again after a restart.
+    init_test(cx_b, |_| {});
+    let mut cx = EditorTestContext::new(cx_b).await;
+    cx.run_until_parked();
+    cx.set_state("ˇef");
+    cx.dispatch_action(PlayMacro {
+        name,
+        ..PlayMacro::default()
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state("-eˇf");
+}
@@ -29,6 +29,7 @@ use crate::{
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MenuPosition},
     scroll::{ActiveScrollbarState, ScrollbarThumbState, scroll_amount::ScrollAmount},
+    sticky_scroll::StickyScope,
 };
 use buffer_diff::{DiffHunkStatus, DiffHunkStatusKind};
 use collections::{BTreeMap, HashMap};
@@ -45,7 +46,7 @@ use gpui::{
     HitboxBehavior, Hsla, InteractiveElement, IntoElement, IsZero, Keystroke, Length,
     ModifiersChangedEvent, MouseButton, MouseClickEvent, MouseDownEvent, MouseMoveEvent,
     MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta, ScrollHandle, ScrollWheelEvent,
-    ShapedLine, SharedString, Size, StatefulInteractiveElement, Style, Styled, TextRun,
+    ShapedLine, SharedString, Size, StatefulInteractiveElement, Style, Styled, StyledText, TextRun,
     TextStyleRefinement, WeakEntity, Window, anchored, deferred, div, fill, linear_color_stop,
     linear_gradient, outline, point, px, quad, relative, size, solid_background, transparent_black,
 };
@@ -575,6 +576,12 @@ impl EditorElement {
         register_action(editor, window, Editor::open_selections_in_multibuffer);
         register_action(editor, window, Editor::toggle_breakpoint);
         register_action(editor, window, Editor::edit_log_breakpoint);
+        register_action(editor, window, Editor::toggle_bookmark);
+        register_action(editor, window, Editor::edit_bookmark_label);
+        register_action(editor, window, Editor::go_to_next_bookmark);
+        register_action(editor, window, Editor::go_to_previous_bookmark);
+        register_action(editor, window, Editor::toggle_macro_recording);
+        register_action(editor, window, Editor::play_macro);
         register_action(editor, window, Editor::enable_breakpoint);
         register_action(editor, window, Editor::disable_breakpoint);
     }
@@ -2835,6 +2842,73 @@ impl EditorElement {
         })
     }
 
+    fn layout_bookmarks(
+        &self,
+        line_height: Pixels,
+        range: Range<DisplayRow>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        display_hunks: &[(DisplayDiffHunk, Option<Hitbox>)],
+        snapshot: &EditorSnapshot,
+        breakpoints: Option<
+            &HashMap<DisplayRow, (Anchor, Breakpoint, Option<BreakpointSessionState>)>,
+        >,
+        row_infos: &[RowInfo],
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            editor
+                .active_bookmarks(range.clone(), window, cx)
+                .into_iter()
+                .filter_map(|(display_row, (anchor, bookmark))| {
+                    // Breakpoints take precedence over bookmarks on the same line.
+                    if breakpoints.is_some_and(|breakpoints| breakpoints.contains_key(&display_row))
+                    {
+                        return None;
+                    }
+                    if row_infos
+                        .get((display_row.0.saturating_sub(range.start.0)) as usize)
+                        .is_some_and(|row_info| {
+                            row_info.expand_info.is_some()
+                                || row_info
+                                    .diff_status
+                                    .is_some_and(|status| status.is_deleted())
+                        })
+                    {
+                        return None;
+                    }
+
+                    if range.start > display_row || range.end < display_row {
+                        return None;
+                    }
+
+                    let row =
+                        MultiBufferRow(DisplayPoint::new(display_row, 0).to_point(&snapshot).row);
+                    if snapshot.is_line_folded(row) {
+                        return None;
+                    }
+
+                    let button = editor.render_bookmark(anchor, display_row, &bookmark, cx);
+
+                    let button = prepaint_gutter_button(
+                        button,
+                        display_row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_pixel_position,
+                        gutter_hitbox,
+                        display_hunks,
+                        window,
+                        cx,
+                    );
+                    Some(button)
+                })
+                .collect_vec()
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_run_indicators(
         &self,
@@ -3992,6 +4066,57 @@ impl EditorElement {
         }
     }
 
+    fn layout_sticky_scopes(
+        &self,
+        scopes: Vec<StickyScope>,
+        line_height: Pixels,
+        em_width: Pixels,
+        text_hitbox: &Hitbox,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<AnyElement> {
+        if scopes.is_empty() {
+            return None;
+        }
+
+        let colors = cx.theme().colors();
+        let hover_background = colors.editor_active_line_background;
+        let mut element = v_flex()
+            .id("sticky_scopes")
+            .occlude()
+            .w(text_hitbox.size.width)
+            .bg(colors.editor_background)
+            .border_b_1()
+            .border_color(colors.border_variant)
+            .children(scopes.into_iter().enumerate().map(|(ix, scope)| {
+                let editor = self.editor.clone();
+                let anchor = scope.anchor;
+                h_flex()
+                    .id(("sticky_scope", ix))
+                    .h(line_height)
+                    .pl(em_width * scope.indent as f32)
+                    .cursor_pointer()
+                    .hover(move |style| style.bg(hover_background))
+                    .child(
+                        StyledText::new(scope.text)
+                            .with_default_highlights(&self.style.text, scope.highlights),
+                    )
+                    .on_click(move |_, window, cx| {
+                        editor.update(cx, |editor, cx| {
+                            editor.jump_to_sticky_scope(anchor, window, cx);
+                        });
+                    })
+            }))
+            .into_any_element();
+
+        let size = size(
+            AvailableSpace::Definite(text_hitbox.size.width),
+            AvailableSpace::MinContent,
+        );
+        element.prepaint_as_root(text_hitbox.origin, size, window, cx);
+        Some(element)
+    }
+
     fn layout_sticky_buffer_header(
         &self,
         StickyHeaderExcerpt { excerpt }: StickyHeaderExcerpt<'_>,
@@ -5642,6 +5767,10 @@ impl EditorElement {
                 }
             });
 
+            for bookmark in layout.bookmarks.iter_mut() {
+                bookmark.paint(window, cx);
+            }
+
             for breakpoint in layout.breakpoints.iter_mut() {
                 breakpoint.paint(window, cx);
             }
@@ -8467,6 +8596,24 @@ impl Element for EditorElement {
                         })
                     });
 
+                    let sticky_scopes = if is_minimap {
+                        None
+                    } else {
+                        let scopes = self.editor.update(cx, |editor, cx| {
+                            editor.sticky_scopes(&snapshot, scroll_position.y, cx)
+                        });
+                        window.with_element_namespace("sticky_scopes", |window| {
+                            self.layout_sticky_scopes(
+                                scopes,
+                                line_height,
+                                em_width,
+                                &text_hitbox,
+                                window,
+                                cx,
+                            )
+                        })
+                    };
+
                     let start_buffer_row =
                         MultiBufferRow(start_anchor.to_point(&snapshot.buffer_snapshot).row);
                     let end_buffer_row =
@@ -8749,6 +8896,19 @@ impl Element for EditorElement {
                     let show_breakpoints = snapshot
                         .show_breakpoints
                         .unwrap_or(gutter_settings.breakpoints);
+                    let bookmarks = self.layout_bookmarks(
+                        line_height,
+                        start_row..end_row,
+                        scroll_pixel_position,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        &display_hunks,
+                        &snapshot,
+                        show_breakpoints.then_some(&breakpoint_rows),
+                        &row_infos,
+                        window,
+                        cx,
+                    );
                     let breakpoints = if show_breakpoints {
                         self.layout_breakpoints(
                             line_height,
@@ -8949,12 +9109,14 @@ impl Element for EditorElement {
                         diff_hunk_controls,
                         mouse_context_menu,
                         test_indicators,
+                        bookmarks,
                         breakpoints,
                         crease_toggles,
                         crease_trailers,
                         tab_invisible,
                         space_invisible,
                         sticky_buffer_header,
+                        sticky_scopes,
                         expand_toggles,
                     }
                 })
@@ -9019,6 +9181,12 @@ impl Element for EditorElement {
                         });
                     }
 
+                    window.with_element_namespace("sticky_scopes", |window| {
+                        if let Some(mut sticky_scopes) = layout.sticky_scopes.take() {
+                            sticky_scopes.paint(window, cx)
+                        }
+                    });
+
                     window.with_element_namespace("blocks", |window| {
                         if let Some(mut sticky_header) = layout.sticky_buffer_header.take() {
                             sticky_header.paint(window, cx)
@@ -9123,6 +9291,7 @@ pub struct EditorLayout {
     visible_cursors: Vec<CursorLayout>,
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     test_indicators: Vec<AnyElement>,
+    bookmarks: Vec<AnyElement>,
     breakpoints: Vec<AnyElement>,
     crease_toggles: Vec<Option<AnyElement>>,
     expand_toggles: Vec<Option<(AnyElement, gpui::Point<Pixels>)>>,
@@ -9133,6 +9302,7 @@ pub struct EditorLayout {
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
     sticky_buffer_header: Option<AnyElement>,
+    sticky_scopes: Option<AnyElement>,
     document_colors: Option<(DocumentColorsRenderMode, Vec<(Range<DisplayPoint>, Hsla)>)>,
 }
 
@@ -1403,6 +1403,20 @@ fn clip_ranges<'a>(
         .collect()
 }
 
+/// Structural replacements are expanded from the syntax tree of the buffer
+/// containing the match, so they are only supported within a single excerpt.
+fn structural_replacement_for(
+    snapshot: &MultiBufferSnapshot,
+    range: &Range<Anchor>,
+    query: &SearchQuery,
+) -> Option<String> {
+    let buffer_ranges = snapshot.range_to_buffer_ranges(range.clone());
+    let [(buffer, range, _)] = buffer_ranges.as_slice() else {
+        return None;
+    };
+    query.replacement_for_range(buffer, range.clone())
+}
+
 impl EventEmitter<SearchEvent> for Editor {}
 
 impl Editor {
@@ -1521,6 +1535,7 @@ impl SearchableItem for Editor {
                 case: true,
                 word: true,
                 regex: true,
+                syntax: true,
                 replacement: false,
                 selection: false,
                 find_in_results: true,
@@ -1530,6 +1545,7 @@ impl SearchableItem for Editor {
                 case: true,
                 word: true,
                 regex: true,
+                syntax: true,
                 replacement: true,
                 selection: true,
                 find_in_results: false,
@@ -1602,6 +1618,14 @@ impl SearchableItem for Editor {
     ) {
         let text = self.buffer.read(cx);
         let text = text.snapshot(cx);
+        if query.is_structural() {
+            if let Some(replacement) = structural_replacement_for(&text, identifier, query) {
+                self.transact(window, cx, |this, _, cx| {
+                    this.edit([(identifier.clone(), Arc::from(replacement))], cx);
+                });
+            }
+            return;
+        }
         let text = text.text_for_range(identifier.clone()).collect::<Vec<_>>();
         let text: Cow<_> = if text.len() == 1 {
             text.first().cloned().unwrap().into()
@@ -1628,6 +1652,12 @@ impl SearchableItem for Editor {
         let mut edits = vec![];
 
         for m in matches {
+            if query.is_structural() {
+                if let Some(replacement) = structural_replacement_for(&text, m, query) {
+                    edits.push((m.clone(), Arc::from(replacement)));
+                }
+                continue;
+            }
             let text = text.text_for_range(m.clone()).collect::<Vec<_>>();
 
             let text: Cow<_> = if text.len() == 1 {
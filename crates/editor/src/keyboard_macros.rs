@@ -0,0 +1,486 @@
+//! Keyboard macros that work the same with or without vim mode.
+//!
+//! A macro is recorded from the actions dispatched while an editor is focused,
+//! whether by key bindings, menus or the command palette, along with the text
+//! typed into editors. The last recorded macro and any named ones are
+//! persisted, so that they can be bound to keys with `editor::PlayMacro`.
+use std::{collections::VecDeque, fmt, ops::Range};
+
+use collections::{BTreeMap, BTreeSet};
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{
+    Action, App, AppContext as _, Context, Entity, Global, IntoElement, ParentElement, Render,
+    Styled, WeakEntity, Window, div,
+};
+use language::Point;
+use multi_buffer::Anchor;
+use serde::{Deserialize, Serialize};
+use ui::{Color, Icon, IconName, IconSize, Label, LabelCommon, LabelSize, h_flex};
+use util::ResultExt as _;
+use workspace::{StatusItemView, item::ItemHandle};
+
+use crate::{
+    Editor, EditorEvent, SelectionEffects,
+    actions::{MacroPlaybackMode, PlayMacro, ToggleMacroRecording},
+};
+
+const MACROS_KEY: &str = "editor_keyboard_macros";
+
+/// Guards against playback that never ends, such as a macro replayed for each
+/// of a huge number of lines.
+const MAX_PLAYBACK_STEPS: usize = 100_000;
+
+/// Something that happened while a macro was being recorded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum MacroStep {
+    /// A dispatched action. Actions can't be serialized, so the input that is
+    /// persisted is the one it can be rebuilt from, if there is one.
+    Action {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        input: Option<serde_json::Value>,
+        /// The action as it was dispatched, for macros recorded since Zed started.
+        #[serde(skip)]
+        action: Option<RecordedAction>,
+    },
+    /// Text typed into an editor.
+    Insert {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+struct RecordedAction(Box<dyn Action>);
+
+impl Clone for RecordedAction {
+    fn clone(&self) -> Self {
+        Self(self.0.boxed_clone())
+    }
+}
+
+impl PartialEq for RecordedAction {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.partial_eq(other.0.as_ref())
+    }
+}
+
+impl fmt::Debug for RecordedAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct SavedMacros {
+    #[serde(default)]
+    last: Vec<MacroStep>,
+    #[serde(default)]
+    named: BTreeMap<String, Vec<MacroStep>>,
+}
+
+struct Recording {
+    name: Option<String>,
+    steps: Vec<MacroStep>,
+}
+
+enum PlaybackStep {
+    Step(MacroStep),
+    /// Selects the range that the following steps should apply to.
+    Select(Range<Anchor>),
+    /// Remembers where the selections ended up after the preceding steps.
+    Collect,
+    /// Selects every remembered range.
+    RestoreCollected,
+}
+
+struct Playback {
+    editor: WeakEntity<Editor>,
+    steps: VecDeque<PlaybackStep>,
+    collected: Vec<Range<Anchor>>,
+}
+
+#[derive(Default)]
+struct KeyboardMacros {
+    saved: SavedMacros,
+    recording: Option<Recording>,
+    playback: Option<Playback>,
+    /// Typed text is replayed into whichever editor is focused at the time,
+    /// which may not be the one the macro was started in.
+    focused_editor: Option<WeakEntity<Editor>>,
+}
+
+impl Global for KeyboardMacros {}
+
+pub(crate) fn init(cx: &mut App) {
+    cx.set_global(KeyboardMacros::default());
+
+    cx.spawn(async move |cx| {
+        let saved = cx
+            .background_spawn(async move { KEY_VALUE_STORE.read_kvp(MACROS_KEY) })
+            .await
+            .log_err()
+            .flatten()
+            .and_then(|json| serde_json::from_str::<SavedMacros>(&json).log_err());
+        if let Some(saved) = saved {
+            cx.update_global::<KeyboardMacros, _>(|macros, _| {
+                // Macros recorded while loading take precedence.
+                if macros.saved.last.is_empty() {
+                    macros.saved.last = saved.last;
+                }
+                for (name, steps) in saved.named {
+                    macros.saved.named.entry(name).or_insert(steps);
+                }
+            })
+            .log_err();
+        }
+    })
+    .detach();
+
+    cx.observe_actions(KeyboardMacros::observe_action).detach();
+
+    cx.observe_new(|editor: &mut Editor, _, cx| {
+        // Only text typed into full editors is recorded, and not the text typed
+        // into pickers, such as the command palette's query.
+        if !editor.mode().is_full() {
+            return;
+        }
+        let editor = cx.weak_entity();
+        cx.subscribe_self(move |_, event: &EditorEvent, cx| match event {
+            EditorEvent::Focused => {
+                cx.global_mut::<KeyboardMacros>().focused_editor = Some(editor.clone());
+            }
+            EditorEvent::InputHandled {
+                utf16_range_to_replace,
+                text,
+            } => {
+                cx.global_mut::<KeyboardMacros>()
+                    .observe_insertion(text.to_string(), utf16_range_to_replace.clone());
+            }
+            _ => {}
+        })
+        .detach();
+    })
+    .detach();
+}
+
+impl KeyboardMacros {
+    fn observe_action(action: &dyn Action, window: &mut Window, cx: &mut App) {
+        let Some(macros) = cx.try_global::<Self>() else {
+            return;
+        };
+        if macros.recording.is_none() || macros.playback.is_some() {
+            return;
+        }
+        if action.as_any().is::<ToggleMacroRecording>() || action.as_any().is::<PlayMacro>() {
+            return;
+        }
+        if Self::focused_editor(window, cx).is_none() {
+            return;
+        }
+
+        let input = Self::action_input(action, window, cx);
+        let Some(recording) = &mut cx.global_mut::<Self>().recording else {
+            return;
+        };
+        recording.steps.push(MacroStep::Action {
+            name: action.name().to_string(),
+            input,
+            action: Some(RecordedAction(action.boxed_clone())),
+        });
+
+        // Actions that move focus out of the editor, such as opening the
+        // command palette, are dropped again. The action that ends up being
+        // run from there is recorded by itself.
+        let step_ix = recording.steps.len() - 1;
+        window.defer(cx, move |window, cx| {
+            if Self::focused_editor(window, cx).is_some() {
+                return;
+            }
+            if let Some(recording) = &mut cx.global_mut::<Self>().recording
+                && step_ix < recording.steps.len()
+            {
+                recording.steps.remove(step_ix);
+            }
+        });
+    }
+
+    /// The editor that typed text goes to, if it's focused.
+    fn focused_editor(window: &Window, cx: &App) -> Option<Entity<Editor>> {
+        cx.global::<Self>()
+            .focused_editor
+            .as_ref()?
+            .upgrade()
+            .filter(|editor| editor.read(cx).is_focused(window))
+    }
+
+    /// Returns the input that the action can be rebuilt from by name. Actions
+    /// without input don't need any, and the input of other actions is taken
+    /// from a key binding for the same action, if there is one.
+    fn action_input(action: &dyn Action, window: &Window, cx: &App) -> Option<serde_json::Value> {
+        if cx
+            .build_action(action.name(), None)
+            .is_ok_and(|built| built.partial_eq(action))
+        {
+            return None;
+        }
+        window
+            .bindings_for_action(action)
+            .into_iter()
+            .find_map(|binding| binding.action_input())
+            .and_then(|input| serde_json::from_str(&input).log_err())
+    }
+
+    fn observe_insertion(&mut self, text: String, utf16_range_to_replace: Option<Range<isize>>) {
+        if self.playback.is_some() {
+            return;
+        }
+        if let Some(recording) = &mut self.recording {
+            recording.steps.push(MacroStep::Insert {
+                text,
+                utf16_range_to_replace,
+            });
+        }
+    }
+
+    fn finish_recording(&mut self, recording: Recording, cx: &mut App) {
+        if let Some(name) = recording.name {
+            self.saved.named.insert(name, recording.steps.clone());
+        }
+        self.saved.last = recording.steps;
+
+        let Some(json) = serde_json::to_string(&self.saved).log_err() else {
+            return;
+        };
+        cx.background_spawn(async move {
+            KEY_VALUE_STORE
+                .write_kvp(MACROS_KEY.to_string(), json)
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn play_next(window: &mut Window, cx: &mut App) {
+        let Some(playback) = &mut cx.global_mut::<Self>().playback else {
+            return;
+        };
+        let Some(step) = playback.steps.pop_front() else {
+            cx.global_mut::<Self>().playback = None;
+            return;
+        };
+        let editor = playback.editor.clone();
+
+        match step {
+            PlaybackStep::Step(MacroStep::Action {
+                action: Some(action),
+                ..
+            }) => window.dispatch_action(action.0, cx),
+            PlaybackStep::Step(MacroStep::Action { name, input, .. }) => {
+                match cx.build_action(&name, input) {
+                    Ok(action) => window.dispatch_action(action, cx),
+                    Err(error) => log::error!("failed to replay action {name} from macro: {error}"),
+                }
+            }
+            PlaybackStep::Step(MacroStep::Insert {
+                text,
+                utf16_range_to_replace,
+            }) => {
+                let focused_editor = cx
+                    .global::<Self>()
+                    .focused_editor
+                    .as_ref()
+                    .and_then(WeakEntity::upgrade);
+                if let Some(editor) = focused_editor.or_else(|| editor.upgrade()) {
+                    editor.update(cx, |editor, cx| {
+                        editor.replay_insert_event(&text, utf16_range_to_replace, window, cx);
+                    });
+                }
+            }
+            PlaybackStep::Select(range) => {
+                editor
+                    .update(cx, |editor, cx| {
+                        editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+                            s.select_anchor_ranges([range])
+                        });
+                    })
+                    .log_err();
+            }
+            PlaybackStep::Collect => {
+                if let Some(ranges) = editor
+                    .read_with(cx, |editor, _| {
+                        editor
+                            .selections
+                            .disjoint_anchor_ranges()
+                            .collect::<Vec<_>>()
+                    })
+                    .log_err()
+                    && let Some(playback) = &mut cx.global_mut::<Self>().playback
+                {
+                    playback.collected.extend(ranges);
+                }
+            }
+            PlaybackStep::RestoreCollected => {
+                let collected = cx
+                    .global_mut::<Self>()
+                    .playback
+                    .as_mut()
+                    .map(|playback| std::mem::take(&mut playback.collected))
+                    .unwrap_or_default();
+                editor
+                    .update(cx, |editor, cx| {
+                        editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+                            s.select_anchor_ranges(collected)
+                        });
+                    })
+                    .log_err();
+            }
+        }
+
+        window.defer(cx, Self::play_next);
+    }
+}
+
+impl Editor {
+    pub fn toggle_macro_recording(
+        &mut self,
+        action: &ToggleMacroRecording,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.update_global::<KeyboardMacros, _>(|macros, cx| {
+            if let Some(recording) = macros.recording.take() {
+                macros.finish_recording(recording, cx);
+            } else {
+                macros.recording = Some(Recording {
+                    name: action.name.clone(),
+                    steps: Vec::new(),
+                });
+            }
+        });
+        cx.notify();
+    }
+
+    pub fn play_macro(&mut self, action: &PlayMacro, window: &mut Window, cx: &mut Context<Self>) {
+        let macros = cx.global::<KeyboardMacros>();
+        if macros.recording.is_some() || macros.playback.is_some() {
+            return;
+        }
+        let steps = match &action.name {
+            Some(name) => macros.saved.named.get(name),
+            None => Some(&macros.saved.last),
+        };
+        let Some(steps) = steps.filter(|steps| !steps.is_empty()).cloned() else {
+            return;
+        };
+
+        let times = action.times.max(1);
+        let repetitions = steps
+            .iter()
+            .cycle()
+            .take(steps.len().saturating_mul(times))
+            .cloned()
+            .map(PlaybackStep::Step);
+        let targets = match action.mode {
+            MacroPlaybackMode::Once => None,
+            MacroPlaybackMode::EachCursor => {
+                Some(self.selections.disjoint_anchor_ranges().collect::<Vec<_>>())
+            }
+            MacroPlaybackMode::EachLine => Some(self.selected_line_starts(cx)),
+        };
+        let steps = match targets {
+            None => repetitions.collect::<VecDeque<_>>(),
+            Some(targets) => {
+                let mut steps = VecDeque::new();
+                for target in targets {
+                    steps.push_back(PlaybackStep::Select(target));
+                    steps.extend(repetitions.clone());
+                    steps.push_back(PlaybackStep::Collect);
+                    if steps.len() > MAX_PLAYBACK_STEPS {
+                        break;
+                    }
+                }
+                steps.push_back(PlaybackStep::RestoreCollected);
+                steps
+            }
+        };
+        if steps.len() > MAX_PLAYBACK_STEPS {
+            log::error!("not replaying macro, as it would take over {MAX_PLAYBACK_STEPS} steps");
+            return;
+        }
+
+        cx.global_mut::<KeyboardMacros>().playback = Some(Playback {
+            editor: cx.weak_entity(),
+            steps,
+            collected: Vec::new(),
+        });
+        window.defer(cx, KeyboardMacros::play_next);
+    }
+
+    /// Returns an empty range at the start of each line that's touched by a
+    /// selection.
+    fn selected_line_starts(&self, cx: &mut Context<Self>) -> Vec<Range<Anchor>> {
+        let mut rows = BTreeSet::new();
+        for selection in self.selections.all::<Point>(cx) {
+            let mut end_row = selection.end.row;
+            // A selection ending at the start of a line doesn't include it.
+            if selection.end.column == 0 && end_row > selection.start.row {
+                end_row -= 1;
+            }
+            rows.extend(selection.start.row..=end_row);
+        }
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        rows.into_iter()
+            .map(|row| {
+                let anchor = snapshot.anchor_before(Point::new(row, 0));
+                anchor..anchor
+            })
+            .collect()
+    }
+}
+
+/// Shows in the status bar that a macro is being recorded.
+pub struct MacroRecordingIndicator;
+
+impl MacroRecordingIndicator {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        cx.observe_global::<KeyboardMacros>(|_, cx| cx.notify())
+            .detach();
+        Self
+    }
+}
+
+impl Render for MacroRecordingIndicator {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let recording = cx
+            .try_global::<KeyboardMacros>()
+            .and_then(|macros| macros.recording.as_ref());
+        let Some(recording) = recording else {
+            return div();
+        };
+        let label = match &recording.name {
+            Some(name) => format!("Recording macro \u{201c}{name}\u{201d}"),
+            None => "Recording macro".to_string(),
+        };
+        div().child(
+            h_flex()
+                .gap_1()
+                .child(
+                    Icon::new(IconName::Circle)
+                        .size(IconSize::XSmall)
+                        .color(Color::Error),
+                )
+                .child(Label::new(label).size(LabelSize::Small)),
+        )
+    }
+}
+
+impl StatusItemView for MacroRecordingIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+}
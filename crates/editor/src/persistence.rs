@@ -113,6 +113,13 @@ define_connection!(
     //   start: usize,
     //   end: usize,
     // )
+    //
+    // editor_bookmarks(
+    //   workspace_id: usize,
+    //   path: PathBuf,
+    //   row: u32,
+    //   label: Option<String>,
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> = &[
         sql! (
             CREATE TABLE editors(
@@ -188,6 +195,18 @@ define_connection!(
                 ON DELETE CASCADE
             ) STRICT;
         ),
+        sql! (
+            CREATE TABLE editor_bookmarks (
+                workspace_id INTEGER NOT NULL,
+                path BLOB NOT NULL,
+                row INTEGER NOT NULL,
+                label TEXT,
+                PRIMARY KEY(workspace_id, path, row),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        ),
     ];
 );
 
@@ -270,6 +289,40 @@ impl EditorDb {
         }
     }
 
+    query! {
+        pub fn get_bookmarks(
+            workspace_id: WorkspaceId
+        ) -> Result<Vec<(PathBuf, u32, Option<String>)>> {
+            SELECT path, row, label
+            FROM editor_bookmarks
+            WHERE workspace_id = ?
+            ORDER BY path, row
+        }
+    }
+
+    pub async fn save_bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+        bookmarks: Vec<(PathBuf, u32, Option<String>)>,
+    ) -> Result<()> {
+        log::debug!("Saving bookmarks for workspace {workspace_id:?}");
+        self.write(move |conn| {
+            conn.with_savepoint("update_bookmarks", || {
+                conn.exec_bound(sql!(DELETE FROM editor_bookmarks WHERE workspace_id = ?))?(
+                    workspace_id,
+                )?;
+                for (path, row, label) in bookmarks {
+                    conn.exec_bound(sql!(
+                        INSERT OR IGNORE INTO editor_bookmarks (workspace_id, path, row, label)
+                        VALUES (?1, ?2, ?3, ?4)
+                    ))?((workspace_id, path, row, label))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+
     pub async fn save_editor_selections(
         &self,
         editor_id: ItemId,
@@ -454,4 +507,45 @@ mod tests {
             .unwrap();
         assert_eq!(have, serialized_editor);
     }
+
+    #[gpui::test]
+    async fn test_save_and_get_bookmarks() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        assert_eq!(DB.get_bookmarks(workspace_id).unwrap(), Vec::new());
+
+        let bookmarks = vec![
+            (PathBuf::from("/project/a.rs"), 4, None),
+            (
+                PathBuf::from("/project/a.rs"),
+                1,
+                Some("entry point".to_owned()),
+            ),
+            (PathBuf::from("/project/b.rs"), 0, None),
+        ];
+        DB.save_bookmarks(workspace_id, bookmarks).await.unwrap();
+        assert_eq!(
+            DB.get_bookmarks(workspace_id).unwrap(),
+            vec![
+                (
+                    PathBuf::from("/project/a.rs"),
+                    1,
+                    Some("entry point".to_owned())
+                ),
+                (PathBuf::from("/project/a.rs"), 4, None),
+                (PathBuf::from("/project/b.rs"), 0, None),
+            ]
+        );
+
+        // Saving replaces all of the workspace's bookmarks.
+        DB.save_bookmarks(
+            workspace_id,
+            vec![(PathBuf::from("/project/b.rs"), 2, None)],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            DB.get_bookmarks(workspace_id).unwrap(),
+            vec![(PathBuf::from("/project/b.rs"), 2, None)]
+        );
+    }
 }
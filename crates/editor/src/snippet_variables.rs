@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use gpui::App;
+use language::{Buffer, BufferSnapshot, Point};
+
+/// Resolves the variables of a snippet, such as `$TM_FILENAME`, at the
+/// position it's being inserted at.
+pub(crate) struct SnippetVariables {
+    buffer: BufferSnapshot,
+    position: Point,
+    selected_text: String,
+}
+
+impl SnippetVariables {
+    pub fn new(buffer: &Buffer, position: impl language::ToPoint, selected_text: String) -> Self {
+        let buffer = buffer.snapshot();
+        let position = position.to_point(&buffer);
+        Self {
+            buffer,
+            position,
+            selected_text,
+        }
+    }
+
+    pub fn resolve(&self, name: &str, cx: &App) -> Option<String> {
+        let now = chrono::Local::now();
+        match name {
+            "TM_SELECTED_TEXT" | "SELECTION" => {
+                Some(self.selected_text.clone()).filter(|text| !text.is_empty())
+            }
+            "TM_CURRENT_LINE" => Some(
+                self.buffer
+                    .text_for_range(
+                        Point::new(self.position.row, 0)
+                            ..Point::new(
+                                self.position.row,
+                                self.buffer.line_len(self.position.row),
+                            ),
+                    )
+                    .collect(),
+            ),
+            "TM_CURRENT_WORD" => {
+                let (range, _) = self.buffer.surrounding_word(self.position, false);
+                Some(self.buffer.text_for_range(range).collect::<String>())
+                    .filter(|word| !word.trim().is_empty())
+            }
+            "TM_LINE_INDEX" => Some(self.position.row.to_string()),
+            "TM_LINE_NUMBER" => Some((self.position.row + 1).to_string()),
+            "TM_FILENAME" => Some(self.buffer.file()?.file_name(cx).to_string_lossy().into()),
+            "TM_FILENAME_BASE" => {
+                let file_name = self.buffer.file()?.file_name(cx);
+                Some(Path::new(file_name).file_stem()?.to_string_lossy().into())
+            }
+            "TM_DIRECTORY" => Some(self.abs_path(cx)?.parent()?.to_string_lossy().into()),
+            "TM_FILEPATH" => Some(self.abs_path(cx)?.to_string_lossy().into()),
+            "RELATIVE_FILEPATH" => Some(self.buffer.file()?.path().to_string_lossy().into()),
+            "WORKSPACE_NAME" => {
+                let file = project::File::from_dyn(self.buffer.file())?;
+                Some(file.worktree.read(cx).root_name().to_string())
+            }
+            "WORKSPACE_FOLDER" => {
+                let file = project::File::from_dyn(self.buffer.file())?;
+                Some(file.worktree.read(cx).abs_path().to_string_lossy().into())
+            }
+            "CLIPBOARD" => cx.read_from_clipboard()?.text(),
+            "CURRENT_YEAR" => Some(now.format("%Y").to_string()),
+            "CURRENT_YEAR_SHORT" => Some(now.format("%y").to_string()),
+            "CURRENT_MONTH" => Some(now.format("%m").to_string()),
+            "CURRENT_MONTH_NAME" => Some(now.format("%B").to_string()),
+            "CURRENT_MONTH_NAME_SHORT" => Some(now.format("%b").to_string()),
+            "CURRENT_DATE" => Some(now.format("%d").to_string()),
+            "CURRENT_DAY_NAME" => Some(now.format("%A").to_string()),
+            "CURRENT_DAY_NAME_SHORT" => Some(now.format("%a").to_string()),
+            "CURRENT_HOUR" => Some(now.format("%H").to_string()),
+            "CURRENT_MINUTE" => Some(now.format("%M").to_string()),
+            "CURRENT_SECOND" => Some(now.format("%S").to_string()),
+            "CURRENT_SECONDS_UNIX" => Some(now.timestamp().to_string()),
+            "CURRENT_TIMEZONE_OFFSET" => Some(now.format("%:z").to_string()),
+            "RANDOM" => Some(format!("{:06}", rand::random::<u32>() % 1_000_000)),
+            "RANDOM_HEX" => Some(format!("{:06x}", rand::random::<u32>() & 0xff_ffff)),
+            "UUID" => Some(uuid::Uuid::new_v4().to_string()),
+            "BLOCK_COMMENT_START" => Some(self.block_comment()?.start.to_string()),
+            "BLOCK_COMMENT_END" => Some(self.block_comment()?.end.to_string()),
+            "LINE_COMMENT" => {
+                let scope = self.buffer.language_scope_at(self.position)?;
+                Some(
+                    scope
+                        .line_comment_prefixes()
+                        .first()?
+                        .trim_end()
+                        .to_string(),
+                )
+            }
+            _ => None,
+        }
+    }
+
+    fn abs_path(&self, cx: &App) -> Option<std::path::PathBuf> {
+        Some(self.buffer.file()?.as_local()?.abs_path(cx))
+    }
+
+    fn block_comment(&self) -> Option<language::BlockCommentConfig> {
+        let scope = self.buffer.language_scope_at(self.position)?;
+        scope.block_comment().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+
+    #[gpui::test]
+    fn test_snippet_variables(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("fn main() {\n    let value = 1;\n}\n", cx));
+        cx.update(|cx| {
+            let variables =
+                SnippetVariables::new(buffer.read(cx), Point::new(1, 9), "selected".into());
+            assert_eq!(
+                variables.resolve("TM_CURRENT_LINE", cx).as_deref(),
+                Some("    let value = 1;")
+            );
+            assert_eq!(
+                variables.resolve("TM_CURRENT_WORD", cx).as_deref(),
+                Some("value")
+            );
+            assert_eq!(
+                variables.resolve("TM_LINE_NUMBER", cx).as_deref(),
+                Some("2")
+            );
+            assert_eq!(
+                variables.resolve("TM_SELECTED_TEXT", cx).as_deref(),
+                Some("selected")
+            );
+            assert_eq!(variables.resolve("RANDOM", cx).map(|s| s.len()), Some(6));
+            // Buffers without a file have no file name.
+            assert_eq!(variables.resolve("TM_FILENAME", cx), None);
+        });
+    }
+}
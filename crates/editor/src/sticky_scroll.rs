@@ -0,0 +1,153 @@
+use std::ops::Range;
+
+use gpui::{App, Context, HighlightStyle, SharedString, Window};
+use language::{IndentKind, Point};
+use multi_buffer::{Anchor, MultiBufferRow, ToPoint as _};
+use settings::Settings as _;
+use theme::ActiveTheme as _;
+
+use crate::{
+    DisplayPoint, DisplayRow, Editor, EditorSettings, EditorSnapshot, SelectionEffects,
+    scroll::Autoscroll,
+};
+
+/// The header of a scope that encloses the top of the viewport, pinned there
+/// while its body is scrolled through.
+#[derive(Clone)]
+pub(crate) struct StickyScope {
+    pub anchor: Anchor,
+    /// The indentation of the scope's first line, in columns.
+    pub indent: u32,
+    pub text: String,
+    pub highlights: Vec<(Range<usize>, HighlightStyle)>,
+}
+
+/// The scopes computed for the last frame, which are reused until the buffer
+/// is edited or reparsed, or the viewport starts on another row.
+#[derive(Default)]
+pub(crate) struct StickyScopesCache {
+    key: Option<StickyScopesKey>,
+    scopes: Vec<StickyScope>,
+}
+
+#[derive(PartialEq)]
+struct StickyScopesKey {
+    edit_count: usize,
+    non_text_state_update_count: usize,
+    top_row: u32,
+    max_depth: usize,
+    theme: SharedString,
+}
+
+impl Editor {
+    /// Returns the outline items enclosing the first visible row, outermost
+    /// first, that start above the viewport.
+    pub(crate) fn sticky_scopes(
+        &mut self,
+        snapshot: &EditorSnapshot,
+        scroll_top: f32,
+        cx: &App,
+    ) -> Vec<StickyScope> {
+        let settings = EditorSettings::get_global(cx).sticky_scroll;
+        if !settings.enabled
+            || settings.max_depth == 0
+            || !self.mode.is_full()
+            || !self.buffer.read(cx).is_singleton()
+        {
+            return Vec::new();
+        }
+
+        let buffer = &snapshot.buffer_snapshot;
+        let top_row = DisplayPoint::new(DisplayRow(scroll_top as u32), 0)
+            .to_point(&snapshot.display_snapshot)
+            .row;
+        let key = StickyScopesKey {
+            edit_count: buffer.edit_count(),
+            non_text_state_update_count: buffer.non_text_state_update_count(),
+            top_row,
+            max_depth: settings.max_depth,
+            theme: cx.theme().name.clone(),
+        };
+        if self.sticky_scopes_cache.key.as_ref() != Some(&key) {
+            self.sticky_scopes_cache = StickyScopesCache {
+                scopes: Self::compute_sticky_scopes(snapshot, top_row, settings.max_depth, cx),
+                key: Some(key),
+            };
+        }
+        self.sticky_scopes_cache.scopes.clone()
+    }
+
+    fn compute_sticky_scopes(
+        snapshot: &EditorSnapshot,
+        top_row: u32,
+        max_depth: usize,
+        cx: &App,
+    ) -> Vec<StickyScope> {
+        let buffer = &snapshot.buffer_snapshot;
+        let Some((_, items)) =
+            buffer.symbols_containing(Point::new(top_row, 0), Some(cx.theme().syntax()))
+        else {
+            return Vec::new();
+        };
+        let tab_size = buffer
+            .language_settings_at(Point::new(top_row, 0), cx)
+            .tab_size
+            .get();
+
+        let mut scopes = Vec::<StickyScope>::new();
+        let mut last_start_row = None;
+        for item in items {
+            if scopes.len() >= max_depth {
+                break;
+            }
+            // Each pinned header covers a line, so the next scope must still
+            // be open on the line below the headers pinned so far.
+            let covered_row = top_row + scopes.len() as u32;
+            let start_row = item.range.start.to_point(buffer).row;
+            let end_row = item.range.end.to_point(buffer).row;
+            if start_row >= covered_row || end_row <= covered_row {
+                continue;
+            }
+            if last_start_row == Some(start_row) {
+                continue;
+            }
+            last_start_row = Some(start_row);
+
+            let indent_size = buffer.indent_size_for_line(MultiBufferRow(start_row));
+            let indent = match indent_size.kind {
+                IndentKind::Space => indent_size.len,
+                IndentKind::Tab => indent_size.len * tab_size,
+            };
+            scopes.push(StickyScope {
+                anchor: buffer.anchor_after(Point::new(start_row, 0)),
+                indent,
+                text: item.text,
+                highlights: item.highlight_ranges,
+            });
+        }
+        scopes
+    }
+
+    /// Scrolls the given scope's header to the top of the viewport and moves
+    /// the cursor to it.
+    pub(crate) fn jump_to_sticky_scope(
+        &mut self,
+        anchor: Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = self.snapshot(window, cx);
+        let point = anchor.to_point(&snapshot.buffer_snapshot);
+        let indent = snapshot
+            .buffer_snapshot
+            .indent_size_for_line(MultiBufferRow(point.row))
+            .len;
+        let position = Point::new(point.row, indent);
+        self.change_selections(
+            SelectionEffects::scroll(Autoscroll::top()),
+            window,
+            cx,
+            |selections| selections.select_ranges([position..position]),
+        );
+    }
+}
@@ -0,0 +1,360 @@
+mod active_buffer_encoding;
+
+pub use active_buffer_encoding::ActiveBufferEncoding;
+use collections::HashSet;
+use editor::Editor;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ParentElement,
+    Render, Styled, WeakEntity, Window, actions,
+};
+use language::{Buffer, Encoding};
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(
+    encoding_selector,
+    [
+        /// Toggles the encoding selector modal.
+        Toggle,
+        /// Decodes the active buffer's file again using a different encoding.
+        ReopenWithEncoding,
+        /// Saves the active buffer's file using a different encoding.
+        SaveWithEncoding
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(EncodingSelector::register).detach();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EncodingAction {
+    Reopen,
+    Save,
+}
+
+impl EncodingAction {
+    fn label(&self) -> &'static str {
+        match self {
+            EncodingAction::Reopen => "Reopen with Encoding",
+            EncodingAction::Save => "Save with Encoding",
+        }
+    }
+}
+
+pub struct EncodingSelector {
+    picker: Entity<Picker<EncodingSelectorDelegate>>,
+}
+
+impl EncodingSelector {
+    fn register(
+        workspace: &mut Workspace,
+        _window: Option<&mut Window>,
+        _: &mut Context<Workspace>,
+    ) {
+        workspace.register_action(move |workspace, _: &Toggle, window, cx| {
+            Self::toggle(workspace, None, window, cx);
+        });
+        // Only offered while the active buffer can be reopened, so it doesn't show up in the
+        // command palette for buffers where it would do nothing.
+        workspace.register_action_renderer(|div, workspace, _, cx| {
+            let can_reopen =
+                active_buffer(workspace, cx).is_some_and(|buffer| can_reopen(&buffer, cx));
+            div.when(can_reopen, |div| {
+                div.on_action(
+                    cx.listener(|workspace, _: &ReopenWithEncoding, window, cx| {
+                        Self::toggle(workspace, Some(EncodingAction::Reopen), window, cx);
+                    }),
+                )
+            })
+        });
+        workspace.register_action(move |workspace, _: &SaveWithEncoding, window, cx| {
+            Self::toggle(workspace, Some(EncodingAction::Save), window, cx);
+        });
+    }
+
+    fn toggle(
+        workspace: &mut Workspace,
+        action: Option<EncodingAction>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<()> {
+        let buffer = active_buffer(workspace, cx)?;
+        let can_reopen = can_reopen(&buffer, cx);
+        if action == Some(EncodingAction::Reopen) && !can_reopen {
+            return None;
+        }
+        let project = workspace.project().clone();
+
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            EncodingSelector::new(buffer, project, action, can_reopen, window, cx)
+        });
+        Some(())
+    }
+
+    fn new(
+        buffer: Entity<Buffer>,
+        project: Entity<Project>,
+        action: Option<EncodingAction>,
+        can_reopen: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = EncodingSelectorDelegate::new(
+            cx.entity().downgrade(),
+            buffer,
+            project,
+            action,
+            can_reopen,
+        );
+
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+fn active_buffer(workspace: &Workspace, cx: &App) -> Option<Entity<Buffer>> {
+    let (_, buffer, _) = workspace
+        .active_item(cx)?
+        .act_as::<Editor>(cx)?
+        .read(cx)
+        .active_excerpt(cx)?;
+    Some(buffer)
+}
+
+/// Reopening re-reads the file from the local disk, which buffers of remote projects and
+/// guests of a shared project can't do.
+fn can_reopen(buffer: &Entity<Buffer>, cx: &App) -> bool {
+    buffer
+        .read(cx)
+        .file()
+        .is_some_and(|file| file.disk_state().exists() && file.as_local().is_some())
+}
+
+impl Render for EncodingSelector {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("EncodingSelector")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for EncodingSelector {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for EncodingSelector {}
+impl ModalView for EncodingSelector {}
+
+pub struct EncodingSelectorDelegate {
+    encoding_selector: WeakEntity<EncodingSelector>,
+    buffer: Entity<Buffer>,
+    project: Entity<Project>,
+    action: Option<EncodingAction>,
+    actions: Vec<EncodingAction>,
+    encodings: Vec<Encoding>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl EncodingSelectorDelegate {
+    fn new(
+        encoding_selector: WeakEntity<EncodingSelector>,
+        buffer: Entity<Buffer>,
+        project: Entity<Project>,
+        action: Option<EncodingAction>,
+        can_reopen: bool,
+    ) -> Self {
+        let mut actions = vec![EncodingAction::Save];
+        if can_reopen {
+            actions.insert(0, EncodingAction::Reopen);
+        }
+        let mut this = Self {
+            encoding_selector,
+            buffer,
+            project,
+            action,
+            actions,
+            encodings: Encoding::all(),
+            candidates: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        this.update_candidates();
+        this
+    }
+
+    fn update_candidates(&mut self) {
+        self.candidates = if self.action.is_some() {
+            self.encodings
+                .iter()
+                .enumerate()
+                .map(|(ix, encoding)| StringMatchCandidate::new(ix, &encoding.to_string()))
+                .collect()
+        } else {
+            self.actions
+                .iter()
+                .enumerate()
+                .map(|(ix, action)| StringMatchCandidate::new(ix, action.label()))
+                .collect()
+        };
+        self.matches.clear();
+        self.selected_index = 0;
+    }
+
+    fn apply(&self, action: EncodingAction, encoding: Encoding, cx: &mut App) {
+        let buffer = self.buffer.clone();
+        self.project.update(cx, |project, cx| {
+            buffer.update(cx, |buffer, cx| buffer.set_encoding(encoding, cx));
+            match action {
+                EncodingAction::Reopen => project
+                    .reload_buffers(HashSet::from_iter([buffer]), true, cx)
+                    .detach_and_log_err(cx),
+                // Untitled buffers pick the encoding up when they are first saved.
+                EncodingAction::Save if buffer.read(cx).file().is_some() => {
+                    project.save_buffer(buffer, cx).detach_and_log_err(cx)
+                }
+                EncodingAction::Save => {}
+            }
+        });
+    }
+}
+
+impl PickerDelegate for EncodingSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        match self.action {
+            Some(EncodingAction::Reopen) => "Reopen with encoding…".into(),
+            Some(EncodingAction::Save) => "Save with encoding…".into(),
+            None => "Select an action…".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(candidate_id) = self
+            .matches
+            .get(self.selected_index)
+            .map(|m| m.candidate_id)
+        else {
+            return;
+        };
+        match self.action {
+            Some(action) => {
+                if let Some(encoding) = self.encodings.get(candidate_id).copied() {
+                    self.apply(action, encoding, cx);
+                }
+                self.dismissed(window, cx);
+            }
+            None => {
+                self.action = self.actions.get(candidate_id).copied();
+                self.update_candidates();
+                cx.defer_in(window, |picker, window, cx| {
+                    picker.set_query("", window, cx);
+                    picker.refresh_placeholder(window, cx);
+                    picker.refresh(window, cx);
+                });
+            }
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.encoding_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let mut label = mat.string.clone();
+        if self.action.is_some()
+            && self.encodings.get(mat.candidate_id) == Some(&self.buffer.read(cx).encoding())
+        {
+            label.push_str(" (current)");
+        }
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(label, mat.positions.clone())),
+        )
+    }
+}
@@ -13,6 +13,7 @@ use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use language::LanguageConfig;
 use reqwest_client::ReqwestClient;
 use rpc::ExtensionProvides;
+use theme_importer::ThemeFormat;
 use tree_sitter::{Language, Query, WasmStore};
 
 #[derive(Parser, Debug)]
@@ -75,6 +76,8 @@ async fn main() -> Result<()> {
         .await
         .context("failed to compile extension")?;
 
+    import_themes(&mut manifest, &extension_path, &scratch_dir)?;
+
     let grammars = test_grammars(&manifest, &extension_path, &mut wasm_store)?;
     test_languages(&manifest, &extension_path, &grammars)?;
     test_themes(&manifest, &extension_path, fs.clone()).await?;
@@ -167,7 +170,15 @@ async fn copy_extension_resources(
 ) -> Result<()> {
     fs::create_dir_all(output_dir).context("failed to create output dir")?;
 
-    let manifest_toml = toml::to_string(&manifest).context("failed to serialize manifest")?;
+    // Themes are archived in the `themes` directory, whatever their source
+    // paths, which may be outside the extension for imported themes.
+    let mut archived_manifest = manifest.clone();
+    for theme_path in &mut archived_manifest.themes {
+        *theme_path =
+            Path::new("themes").join(theme_path.file_name().context("invalid theme path")?);
+    }
+    let manifest_toml =
+        toml::to_string(&archived_manifest).context("failed to serialize manifest")?;
     fs::write(output_dir.join("extension.toml"), &manifest_toml)
         .context("failed to write extension.toml")?;
 
@@ -375,6 +386,43 @@ fn test_languages(
     Ok(())
 }
 
+/// Converts the extension's themes that are in other formats, such as base16
+/// schemes or TextMate themes, into Zed themes that are packaged in their place.
+fn import_themes(
+    manifest: &mut ExtensionManifest,
+    extension_path: &Path,
+    scratch_dir: &Path,
+) -> Result<()> {
+    let imported_themes_dir = scratch_dir.join("imported_themes");
+    for theme_path in &mut manifest.themes {
+        // JSON themes are already Zed themes.
+        let format = match ThemeFormat::from_path(theme_path) {
+            Some(ThemeFormat::VsCode) | None => continue,
+            Some(format) => format,
+        };
+
+        log::info!("importing theme {}", theme_path.display());
+        let imported = theme_importer::import_theme(&extension_path.join(&theme_path), format)
+            .with_context(|| format!("failed to import theme '{}'", theme_path.display()))?;
+        if !imported.report.is_empty() {
+            log::warn!(
+                "theme '{}' was imported with omissions:\n{}",
+                theme_path.display(),
+                imported.report
+            );
+        }
+
+        let file_name = theme_path.file_stem().context("invalid theme path")?;
+        let output_path = imported_themes_dir.join(file_name).with_extension("json");
+        fs::create_dir_all(&imported_themes_dir)?;
+        fs::write(&output_path, imported.to_json()?)
+            .with_context(|| format!("failed to write {output_path:?}"))?;
+        *theme_path = output_path;
+    }
+
+    Ok(())
+}
+
 async fn test_themes(
     manifest: &ExtensionManifest,
     extension_path: &Path,
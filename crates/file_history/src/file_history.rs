@@ -0,0 +1,403 @@
+mod history_view;
+mod store;
+mod timeline;
+
+use anyhow::Result;
+use editor::Editor;
+use fs::Fs;
+use gpui::{App, AppContext as _, Context, Entity, Global, actions};
+use language::{Buffer, BufferEvent};
+use project::{PathChange, Project, ProjectPath, buffer_store::BufferStoreEvent};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use workspace::Workspace;
+
+pub use history_view::FileHistoryView;
+pub use store::{FileHistoryStore, RetentionPolicy, Snapshot, SnapshotReason};
+pub use timeline::FileHistoryTimeline;
+
+actions!(
+    file_history,
+    [
+        /// Shows the saved snapshots of the active file.
+        ShowTimeline,
+        /// Replaces the file's contents with the snapshot being compared against.
+        RestoreSnapshot
+    ]
+);
+
+/// Settings for the local history of file contents.
+#[derive(Clone, Debug)]
+pub struct FileHistorySettings {
+    pub enabled: bool,
+    pub max_snapshots_per_file: usize,
+    pub max_age_days: u64,
+    pub max_file_size_kb: usize,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct FileHistorySettingsContent {
+    /// Whether to keep snapshots of files when they are opened in an editor,
+    /// saved, or changed on disk.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// How many snapshots to keep for each file.
+    ///
+    /// Default: 50
+    pub max_snapshots_per_file: Option<usize>,
+    /// How many days to keep snapshots for. The latest snapshot of a file is
+    /// always kept.
+    ///
+    /// Default: 30
+    pub max_age_days: Option<u64>,
+    /// Files larger than this many kilobytes aren't snapshotted.
+    ///
+    /// Default: 1024
+    pub max_file_size_kb: Option<usize>,
+}
+
+impl Settings for FileHistorySettings {
+    const KEY: Option<&'static str> = Some("file_history");
+
+    type FileContent = FileHistorySettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> Result<Self> {
+        let content: FileHistorySettingsContent = sources.json_merge()?;
+        Ok(Self {
+            enabled: content.enabled.unwrap_or(true),
+            max_snapshots_per_file: content.max_snapshots_per_file.unwrap_or(50),
+            max_age_days: content.max_age_days.unwrap_or(30),
+            max_file_size_kb: content.max_file_size_kb.unwrap_or(1024),
+        })
+    }
+
+    fn import_from_vscode(vscode: &settings::VsCodeSettings, current: &mut Self::FileContent) {
+        vscode.bool_setting("workbench.localHistory.enabled", &mut current.enabled);
+        vscode.usize_setting(
+            "workbench.localHistory.maxFileEntries",
+            &mut current.max_snapshots_per_file,
+        );
+        vscode.usize_setting(
+            "workbench.localHistory.maxFileSize",
+            &mut current.max_file_size_kb,
+        );
+    }
+}
+
+impl FileHistorySettings {
+    pub fn retention_policy(&self) -> RetentionPolicy {
+        RetentionPolicy {
+            max_snapshots: self.max_snapshots_per_file,
+            max_age: Duration::from_secs(self.max_age_days * 24 * 60 * 60),
+            max_file_size: self.max_file_size_kb * 1024,
+        }
+    }
+}
+
+struct GlobalFileHistory(Arc<FileHistoryStore>);
+
+impl Global for GlobalFileHistory {}
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+    FileHistorySettings::register(cx);
+    let store = Arc::new(FileHistoryStore::new(fs, paths::file_history_dir().clone()));
+    cx.set_global(GlobalFileHistory(store.clone()));
+
+    // Snapshots are otherwise only pruned when a file gets a new one.
+    let policy = FileHistorySettings::get_global(cx).retention_policy();
+    cx.background_spawn(async move { store.prune_all(SystemTime::now(), &policy).await })
+        .detach_and_log_err(cx);
+
+    cx.observe_new(|project: &mut Project, _, cx| {
+        if project.is_local() {
+            track_buffers(project.buffer_store().clone(), cx);
+            track_worktree_changes(cx);
+        }
+    })
+    .detach();
+
+    // Buffers are also opened for language servers, searches and other project-wide
+    // operations, so only the files the user opens get a snapshot of their original contents.
+    cx.observe_new(|editor: &mut Editor, _, cx| {
+        if !editor.mode().is_full()
+            || !editor
+                .project
+                .as_ref()
+                .is_some_and(|project| project.read(cx).is_local())
+        {
+            return;
+        }
+        if let Some(buffer) = editor.buffer().read(cx).as_singleton() {
+            record_snapshot(&buffer, SnapshotReason::Opened, cx);
+        }
+    })
+    .detach();
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ShowTimeline, window, cx| {
+            FileHistoryTimeline::toggle(workspace, window, cx);
+        });
+    })
+    .detach();
+}
+
+pub fn store(cx: &App) -> Option<Arc<FileHistoryStore>> {
+    cx.try_global::<GlobalFileHistory>()
+        .map(|history| history.0.clone())
+}
+
+fn track_buffers(
+    buffer_store: Entity<project::buffer_store::BufferStore>,
+    cx: &mut Context<Project>,
+) {
+    cx.subscribe(&buffer_store, |_, _, event, cx| {
+        if let BufferStoreEvent::BufferAdded(buffer) = event {
+            cx.subscribe(buffer, |_, buffer, event, cx| match event {
+                BufferEvent::Saved => record_snapshot(&buffer, SnapshotReason::Saved, cx),
+                BufferEvent::Reloaded => {
+                    record_snapshot(&buffer, SnapshotReason::ChangedOnDisk, cx)
+                }
+                _ => {}
+            })
+            .detach();
+        }
+    })
+    .detach();
+}
+
+/// Snapshots tracked files that change on disk while they aren't open, e.g.
+/// after `git checkout`. Open files are snapshotted when their buffer reloads.
+fn track_worktree_changes(cx: &mut Context<Project>) {
+    cx.subscribe_self(|project, event, cx| {
+        let project::Event::WorktreeUpdatedEntries(worktree_id, changes) = event else {
+            return;
+        };
+        let settings = FileHistorySettings::get_global(cx);
+        if !settings.enabled {
+            return;
+        }
+        let policy = settings.retention_policy();
+        let Some(store) = store(cx) else {
+            return;
+        };
+        let Some(worktree) = project.worktree_for_id(*worktree_id, cx) else {
+            return;
+        };
+        let buffer_store = project.buffer_store().read(cx);
+        let worktree = worktree.read(cx);
+        let paths = changes
+            .iter()
+            .filter(|(path, _, change)| {
+                matches!(change, PathChange::Updated | PathChange::AddedOrUpdated)
+                    && buffer_store
+                        .get_by_path(&ProjectPath {
+                            worktree_id: *worktree_id,
+                            path: path.clone(),
+                        })
+                        .is_none()
+            })
+            .filter_map(|(path, _, _)| worktree.absolutize(path).ok())
+            .collect::<Vec<_>>();
+        if paths.is_empty() {
+            return;
+        }
+        cx.background_spawn(async move {
+            for path in paths {
+                store
+                    .record_from_disk(
+                        &path,
+                        SnapshotReason::ChangedOnDisk,
+                        SystemTime::now(),
+                        &policy,
+                    )
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    })
+    .detach();
+}
+
+fn record_snapshot(buffer: &Entity<Buffer>, reason: SnapshotReason, cx: &mut App) {
+    let settings = FileHistorySettings::get_global(cx);
+    if !settings.enabled {
+        return;
+    }
+    let policy = settings.retention_policy();
+    let Some(store) = store(cx) else {
+        return;
+    };
+    let buffer = buffer.read(cx);
+    let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+        return;
+    };
+    if !file.disk_state().exists() || buffer.len() > policy.max_file_size {
+        return;
+    }
+
+    let path = file.abs_path(cx);
+    let text = buffer.text();
+    cx.background_spawn(async move {
+        store
+            .record(&path, &text, reason, SystemTime::now(), &policy)
+            .await
+    })
+    .detach_and_log_err(cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::path::Path;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_snapshots_are_taken_for_opened_saved_and_reloaded_files(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({ "opened.txt": "one", "background.txt": "two" }),
+        )
+        .await;
+        init_test(fs.clone(), cx);
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+
+        // Buffers opened without an editor, e.g. by a project search, aren't snapshotted.
+        let background = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/project/background.txt"), cx)
+            })
+            .await
+            .unwrap();
+        assert!(
+            snapshot_reasons(path!("/project/background.txt"), cx)
+                .await
+                .is_empty()
+        );
+
+        let opened = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/project/opened.txt"), cx)
+            })
+            .await
+            .unwrap();
+        assert!(
+            snapshot_reasons(path!("/project/opened.txt"), cx)
+                .await
+                .is_empty()
+        );
+        cx.add_window(|window, cx| {
+            Editor::for_buffer(opened.clone(), Some(project.clone()), window, cx)
+        });
+        assert_eq!(
+            snapshot_reasons(path!("/project/opened.txt"), cx).await,
+            [SnapshotReason::Opened]
+        );
+
+        // Saving and reloading are snapshotted for every buffer.
+        for buffer in [&opened, &background] {
+            buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "edited ")], None, cx));
+            project
+                .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+                .await
+                .unwrap();
+        }
+        fs.save(
+            path!("/project/background.txt").as_ref(),
+            &"changed on disk".into(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            snapshot_reasons(path!("/project/opened.txt"), cx).await,
+            [SnapshotReason::Saved, SnapshotReason::Opened]
+        );
+        assert_eq!(
+            snapshot_reasons(path!("/project/background.txt"), cx).await,
+            [SnapshotReason::ChangedOnDisk, SnapshotReason::Saved]
+        );
+        assert_eq!(
+            background.read_with(cx, |buffer, _| buffer.text()),
+            "changed on disk"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_tracked_files_are_snapshotted_when_changed_while_closed(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({ "tracked.txt": "one", "untracked.txt": "one" }),
+        )
+        .await;
+        init_test(fs.clone(), cx);
+        let _project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let store = cx.update(|cx| store(cx).unwrap());
+        let policy = cx.update(|cx| FileHistorySettings::get_global(cx).retention_policy());
+        store
+            .record(
+                Path::new(path!("/project/tracked.txt")),
+                "one",
+                SnapshotReason::Opened,
+                SystemTime::now(),
+                &policy,
+            )
+            .await
+            .unwrap();
+
+        for path in [
+            path!("/project/tracked.txt"),
+            path!("/project/untracked.txt"),
+        ] {
+            fs.save(path.as_ref(), &"two".into(), Default::default())
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            snapshot_reasons(path!("/project/tracked.txt"), cx).await,
+            [SnapshotReason::ChangedOnDisk, SnapshotReason::Opened]
+        );
+        assert!(
+            snapshot_reasons(path!("/project/untracked.txt"), cx)
+                .await
+                .is_empty()
+        );
+    }
+
+    async fn snapshot_reasons(path: &str, cx: &mut TestAppContext) -> Vec<SnapshotReason> {
+        cx.run_until_parked();
+        let store = cx.update(|cx| store(cx).unwrap());
+        store
+            .snapshots(Path::new(path))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|snapshot| snapshot.reason)
+            .collect()
+    }
+
+    fn init_test(fs: Arc<FakeFs>, cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            init(fs, cx);
+        });
+    }
+}
@@ -0,0 +1,386 @@
+use anyhow::Result;
+use buffer_diff::{BufferDiff, BufferDiffSnapshot, DiffHunkStatus};
+use editor::{Anchor, Editor, EditorEvent, MultiBuffer, ToPoint as _};
+use futures::{FutureExt, select_biased};
+use gpui::{
+    AnyElement, AnyView, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter,
+    FocusHandle, Focusable, IntoElement, Pixels, Render, Task, Window,
+};
+use language::Buffer;
+use project::Project;
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    pin::pin,
+    sync::Arc,
+    time::Duration,
+};
+use time::OffsetDateTime;
+use ui::{Tooltip, prelude::*};
+use util::paths::PathExt;
+use workspace::{
+    Item, ItemNavHistory, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, ItemEvent, SaveOptions, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+use crate::{RestoreSnapshot, Snapshot, timeline::local_timezone};
+
+const RECALCULATE_DIFF_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Shows how a file has changed since one of its snapshots. The file's buffer
+/// stays editable, and hunks can be restored to the snapshot's contents.
+pub struct FileHistoryView {
+    diff_editor: Entity<Editor>,
+    buffer: Entity<Buffer>,
+    snapshot_text: String,
+    title: SharedString,
+    path: SharedString,
+    buffer_changes_tx: watch::Sender<()>,
+    _recalculate_diff_task: Task<Result<()>>,
+}
+
+impl FileHistoryView {
+    pub fn open(
+        workspace: &mut Workspace,
+        buffer: Entity<Buffer>,
+        snapshot: Snapshot,
+        snapshot_text: String,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        let snapshot_buffer = cx.new(|cx| {
+            let mut snapshot_buffer = Buffer::local(snapshot_text.clone(), cx);
+            snapshot_buffer.set_language(buffer.read(cx).language().cloned(), cx);
+            snapshot_buffer
+        });
+        let buffer_snapshot = buffer.read(cx).text_snapshot();
+        let diff = cx.new(|cx| BufferDiff::new(&buffer_snapshot, cx));
+
+        cx.spawn_in(window, async move |workspace, cx| {
+            update_diff(&diff, &buffer, &snapshot_buffer, cx).await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let view = cx.new(|cx| {
+                    FileHistoryView::new(
+                        buffer,
+                        snapshot_buffer,
+                        &snapshot,
+                        snapshot_text,
+                        diff,
+                        project,
+                        window,
+                        cx,
+                    )
+                });
+                workspace.active_pane().update(cx, |pane, cx| {
+                    pane.add_item(Box::new(view), true, true, None, window, cx);
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn new(
+        buffer: Entity<Buffer>,
+        snapshot_buffer: Entity<Buffer>,
+        snapshot: &Snapshot,
+        snapshot_text: String,
+        diff: Entity<BufferDiff>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+            multibuffer.add_diff(diff.clone(), cx);
+            multibuffer
+        });
+        let diff_editor = cx.new(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer, Some(project), window, cx);
+            editor.start_temporary_diff_override();
+            editor.disable_diagnostics(cx);
+            editor.set_expand_all_diff_hunks(cx);
+            editor.set_render_diff_hunk_controls(Arc::new(render_restore_hunk_control), cx);
+            editor
+        });
+
+        let (buffer_changes_tx, mut buffer_changes_rx) = watch::channel(());
+        cx.subscribe(&buffer, move |this, _, event, _| match event {
+            language::BufferEvent::Edited | language::BufferEvent::LanguageChanged => {
+                this.buffer_changes_tx.send(()).ok();
+            }
+            _ => {}
+        })
+        .detach();
+
+        let file = buffer.read(cx).file();
+        let file_name = file
+            .map(|file| file.file_name(cx).to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".into());
+        let full_path = file
+            .map(|file| file.full_path(cx).compact().to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".into());
+        let time = snapshot_time(snapshot);
+
+        Self {
+            diff_editor,
+            buffer: buffer.clone(),
+            snapshot_text,
+            title: format!("{file_name} @ {time}").into(),
+            path: format!("{full_path} ({} {time})", snapshot.reason.label()).into(),
+            buffer_changes_tx,
+            _recalculate_diff_task: cx.spawn(async move |_, cx| {
+                while buffer_changes_rx.recv().await.is_ok() {
+                    loop {
+                        let mut timer = cx
+                            .background_executor()
+                            .timer(RECALCULATE_DIFF_DEBOUNCE)
+                            .fuse();
+                        let mut recv = pin!(buffer_changes_rx.recv().fuse());
+                        select_biased! {
+                            _ = timer => break,
+                            _ = recv => continue,
+                        }
+                    }
+                    update_diff(&diff, &buffer, &snapshot_buffer, cx).await?;
+                }
+                Ok(())
+            }),
+        }
+    }
+
+    fn restore_snapshot(&mut self, _: &RestoreSnapshot, _: &mut Window, cx: &mut Context<Self>) {
+        restore(self.buffer.clone(), self.snapshot_text.clone(), cx).detach_and_log_err(cx);
+    }
+}
+
+/// Replaces the buffer's contents with a snapshot's as a single undoable edit.
+pub(crate) fn restore(buffer: Entity<Buffer>, text: String, cx: &mut App) -> Task<Result<()>> {
+    let diff = buffer.read(cx).diff(text, cx);
+    cx.spawn(async move |cx| {
+        let diff = diff.await;
+        buffer.update(cx, |buffer, cx| {
+            buffer.finalize_last_transaction();
+            buffer.apply_diff(diff, cx);
+            buffer.finalize_last_transaction();
+        })
+    })
+}
+
+fn snapshot_time(snapshot: &Snapshot) -> String {
+    let Ok(timestamp) = OffsetDateTime::from_unix_timestamp(snapshot.timestamp as i64) else {
+        return String::new();
+    };
+    time_format::format_localized_timestamp(
+        timestamp,
+        OffsetDateTime::now_utc(),
+        local_timezone(),
+        time_format::TimestampFormat::EnhancedAbsolute,
+    )
+}
+
+async fn update_diff(
+    diff: &Entity<BufferDiff>,
+    buffer: &Entity<Buffer>,
+    snapshot_buffer: &Entity<Buffer>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    let buffer_snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+    let base_snapshot = snapshot_buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+    let base_text = base_snapshot.text();
+
+    let diff_snapshot = cx
+        .update(|cx| {
+            BufferDiffSnapshot::new_with_base_buffer(
+                buffer_snapshot.text.clone(),
+                Some(Arc::new(base_text)),
+                base_snapshot,
+                cx,
+            )
+        })?
+        .await;
+
+    diff.update(cx, |diff, cx| {
+        diff.set_snapshot(diff_snapshot, &buffer_snapshot.text, cx);
+    })?;
+    Ok(())
+}
+
+fn render_restore_hunk_control(
+    row: u32,
+    _status: &DiffHunkStatus,
+    hunk_range: Range<Anchor>,
+    _is_created_file: bool,
+    line_height: Pixels,
+    editor: &Entity<Editor>,
+    _window: &mut Window,
+    cx: &mut App,
+) -> AnyElement {
+    let editor = editor.clone();
+    h_flex()
+        .h(line_height)
+        .mr_1()
+        .px_0p5()
+        .pb_1()
+        .border_x_1()
+        .border_b_1()
+        .border_color(cx.theme().colors().border_variant)
+        .rounded_b_lg()
+        .bg(cx.theme().colors().editor_background)
+        .child(
+            Button::new(("restore", row as u64), "Restore")
+                .tooltip(Tooltip::text("Restore this hunk from the snapshot"))
+                .on_click(move |_, window, cx| {
+                    editor.update(cx, |editor, cx| {
+                        let snapshot = editor.snapshot(window, cx);
+                        let point = hunk_range.start.to_point(&snapshot.buffer_snapshot);
+                        editor.restore_hunks_in_ranges(vec![point..point], window, cx);
+                    });
+                }),
+        )
+        .into_any_element()
+}
+
+impl EventEmitter<EditorEvent> for FileHistoryView {}
+
+impl Focusable for FileHistoryView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.diff_editor.focus_handle(cx)
+    }
+}
+
+impl Item for FileHistoryView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, _: &App) -> SharedString {
+        self.title.clone()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(self.path.clone())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("File History Diff Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.diff_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.diff_editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.diff_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.diff_editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.diff_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.diff_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.diff_editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.diff_editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.diff_editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.diff_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+}
+
+impl Render for FileHistoryView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .key_context("FileHistoryView")
+            .on_action(cx.listener(Self::restore_snapshot))
+            .size_full()
+            .child(self.diff_editor.clone())
+    }
+}
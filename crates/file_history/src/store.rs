@@ -0,0 +1,427 @@
+use anyhow::{Context as _, Result};
+use fs::{Fs, RemoveOptions};
+use futures::{StreamExt as _, lock::Mutex};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use util::ResultExt as _;
+
+const INDEX_FILE_NAME: &str = "index.json";
+
+/// Why a snapshot was taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotReason {
+    /// The file was opened, capturing its contents before any edits.
+    Opened,
+    /// The buffer was saved.
+    Saved,
+    /// The file changed on disk, outside of Zed.
+    ChangedOnDisk,
+}
+
+impl SnapshotReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SnapshotReason::Opened => "Opened",
+            SnapshotReason::Saved => "Saved",
+            SnapshotReason::ChangedOnDisk => "Changed on disk",
+        }
+    }
+}
+
+/// A recorded version of a file's contents.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The SHA-256 of the contents, which is also the name of the file they're stored in.
+    pub digest: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub reason: SnapshotReason,
+    pub len: usize,
+}
+
+/// Limits on how much history is kept for each file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub max_snapshots: usize,
+    pub max_age: Duration,
+    pub max_file_size: usize,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct SnapshotIndex {
+    path: PathBuf,
+    /// Oldest first.
+    snapshots: Vec<Snapshot>,
+}
+
+/// Stores snapshots of files in a content-addressed directory per file,
+/// alongside an index of when each snapshot was taken.
+pub struct FileHistoryStore {
+    fs: Arc<dyn Fs>,
+    root: PathBuf,
+    index_lock: Mutex<()>,
+}
+
+impl FileHistoryStore {
+    pub fn new(fs: Arc<dyn Fs>, root: PathBuf) -> Self {
+        Self {
+            fs,
+            root,
+            index_lock: Mutex::new(()),
+        }
+    }
+
+    fn file_dir(&self, path: &Path) -> PathBuf {
+        self.root.join(digest(path.to_string_lossy().as_bytes()))
+    }
+
+    async fn read_index(&self, dir: &Path) -> Result<SnapshotIndex> {
+        let index_path = dir.join(INDEX_FILE_NAME);
+        if !self.fs.is_file(&index_path).await {
+            return Ok(SnapshotIndex::default());
+        }
+        let content = self.fs.load(&index_path).await?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("parsing file history index {index_path:?}"))
+    }
+
+    /// Records a snapshot of the file's contents, unless they match the latest
+    /// snapshot or exceed the size limit. Returns the new snapshot, if any.
+    pub async fn record(
+        &self,
+        path: &Path,
+        text: &str,
+        reason: SnapshotReason,
+        now: SystemTime,
+        policy: &RetentionPolicy,
+    ) -> Result<Option<Snapshot>> {
+        if text.len() > policy.max_file_size {
+            return Ok(None);
+        }
+
+        let _guard = self.index_lock.lock().await;
+        let dir = self.file_dir(path);
+        let mut index = self.read_index(&dir).await?;
+        let digest = digest(text.as_bytes());
+        if index
+            .snapshots
+            .last()
+            .is_some_and(|snapshot| snapshot.digest == digest)
+        {
+            return Ok(None);
+        }
+
+        self.fs.create_dir(&dir).await?;
+        let blob_path = dir.join(&digest);
+        if !self.fs.is_file(&blob_path).await {
+            self.fs.write(&blob_path, text.as_bytes()).await?;
+        }
+
+        let snapshot = Snapshot {
+            digest,
+            timestamp: now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+            reason,
+            len: text.len(),
+        };
+        index.path = path.to_path_buf();
+        index.snapshots.push(snapshot.clone());
+        let pruned = prune(&mut index.snapshots, now, policy);
+        self.write_index(&dir, &index, pruned).await?;
+
+        Ok(Some(snapshot))
+    }
+
+    /// Records a snapshot of the file's current contents on disk, if the file
+    /// already has history. Files that aren't valid UTF-8 are skipped.
+    pub async fn record_from_disk(
+        &self,
+        path: &Path,
+        reason: SnapshotReason,
+        now: SystemTime,
+        policy: &RetentionPolicy,
+    ) -> Result<Option<Snapshot>> {
+        if !self
+            .fs
+            .is_file(&self.file_dir(path).join(INDEX_FILE_NAME))
+            .await
+        {
+            return Ok(None);
+        }
+        let Some(metadata) = self.fs.metadata(path).await? else {
+            return Ok(None);
+        };
+        if metadata.is_dir || metadata.len as usize > policy.max_file_size {
+            return Ok(None);
+        }
+        let Ok(text) = self.fs.load(path).await else {
+            return Ok(None);
+        };
+        self.record(path, &text, reason, now, policy).await
+    }
+
+    /// Applies the retention policy to every file in the store, so that old
+    /// snapshots of files that are no longer edited are dropped too.
+    pub async fn prune_all(&self, now: SystemTime, policy: &RetentionPolicy) -> Result<()> {
+        if !self.fs.is_dir(&self.root).await {
+            return Ok(());
+        }
+        let _guard = self.index_lock.lock().await;
+        let mut dirs = self.fs.read_dir(&self.root).await?;
+        while let Some(dir) = dirs.next().await {
+            let dir = dir?;
+            if !self.fs.is_dir(&dir).await {
+                continue;
+            }
+            let Some(mut index) = self.read_index(&dir).await.log_err() else {
+                continue;
+            };
+            let pruned = prune(&mut index.snapshots, now, policy);
+            if !pruned.is_empty() {
+                self.write_index(&dir, &index, pruned).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the index and removes the contents of pruned snapshots that
+    /// aren't referenced by any retained snapshot.
+    async fn write_index(
+        &self,
+        dir: &Path,
+        index: &SnapshotIndex,
+        pruned: Vec<Snapshot>,
+    ) -> Result<()> {
+        self.fs
+            .atomic_write(
+                dir.join(INDEX_FILE_NAME),
+                serde_json::to_string_pretty(index)?,
+            )
+            .await?;
+
+        let retained = index
+            .snapshots
+            .iter()
+            .map(|snapshot| snapshot.digest.as_str())
+            .collect::<HashSet<_>>();
+        for pruned in pruned {
+            if !retained.contains(pruned.digest.as_str()) {
+                self.fs
+                    .remove_file(
+                        &dir.join(&pruned.digest),
+                        RemoveOptions {
+                            recursive: false,
+                            ignore_if_not_exists: true,
+                        },
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the snapshots recorded for a file, newest first.
+    pub async fn snapshots(&self, path: &Path) -> Result<Vec<Snapshot>> {
+        let mut index = self.read_index(&self.file_dir(path)).await?;
+        index.snapshots.reverse();
+        Ok(index.snapshots)
+    }
+
+    pub async fn load(&self, path: &Path, snapshot: &Snapshot) -> Result<String> {
+        let blob_path = self.file_dir(path).join(&snapshot.digest);
+        self.fs
+            .load(&blob_path)
+            .await
+            .with_context(|| format!("loading snapshot {} of {path:?}", snapshot.digest))
+    }
+}
+
+fn digest(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Drops the oldest snapshots beyond the count limit and any older than the
+/// age limit, always keeping the latest one. Returns what was dropped.
+fn prune(
+    snapshots: &mut Vec<Snapshot>,
+    now: SystemTime,
+    policy: &RetentionPolicy,
+) -> Vec<Snapshot> {
+    let cutoff = now
+        .checked_sub(policy.max_age)
+        .and_then(|cutoff| cutoff.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |cutoff| cutoff.as_secs());
+    let keep_from = snapshots
+        .iter()
+        .position(|snapshot| snapshot.timestamp >= cutoff)
+        .unwrap_or(snapshots.len())
+        .max(snapshots.len().saturating_sub(policy.max_snapshots.max(1)))
+        .min(snapshots.len().saturating_sub(1));
+    snapshots.drain(..keep_from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use std::path::Path;
+
+    fn policy() -> RetentionPolicy {
+        RetentionPolicy {
+            max_snapshots: 3,
+            max_age: Duration::from_secs(60 * 60),
+            max_file_size: 1024,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_record_and_load(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let store = FileHistoryStore::new(fs.clone(), PathBuf::from("/history"));
+        let path = Path::new("/project/.env");
+        let start = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let at = |minutes: u64| start + Duration::from_secs(minutes * 60);
+
+        let first = store
+            .record(path, "one", SnapshotReason::Opened, at(0), &policy())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            store
+                .record(path, "one", SnapshotReason::Saved, at(1), &policy())
+                .await
+                .unwrap(),
+            None,
+            "unchanged contents aren't recorded again"
+        );
+        assert_eq!(
+            store
+                .record(
+                    path,
+                    &"x".repeat(2048),
+                    SnapshotReason::Saved,
+                    at(1),
+                    &policy()
+                )
+                .await
+                .unwrap(),
+            None,
+            "files over the size limit are skipped"
+        );
+        store
+            .record(path, "two", SnapshotReason::Saved, at(2), &policy())
+            .await
+            .unwrap();
+        store
+            .record(
+                path,
+                "three",
+                SnapshotReason::ChangedOnDisk,
+                at(3),
+                &policy(),
+            )
+            .await
+            .unwrap();
+
+        let snapshots = store.snapshots(path).await.unwrap();
+        assert_eq!(
+            snapshots
+                .iter()
+                .map(|snapshot| snapshot.reason)
+                .collect::<Vec<_>>(),
+            [
+                SnapshotReason::ChangedOnDisk,
+                SnapshotReason::Saved,
+                SnapshotReason::Opened
+            ]
+        );
+        assert_eq!(store.load(path, &snapshots[0]).await.unwrap(), "three");
+        assert_eq!(store.load(path, &first).await.unwrap(), "one");
+        assert!(
+            store
+                .snapshots(Path::new("/project/other"))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Going over the count limit drops the oldest snapshot and its contents.
+        store
+            .record(path, "four", SnapshotReason::Saved, at(4), &policy())
+            .await
+            .unwrap();
+        let snapshots = store.snapshots(path).await.unwrap();
+        assert_eq!(snapshots.len(), 3);
+        assert!(store.load(path, &first).await.is_err());
+
+        // Snapshots past the age limit are dropped, but the latest is always kept.
+        store
+            .record(path, "five", SnapshotReason::Saved, at(24 * 60), &policy())
+            .await
+            .unwrap();
+        let snapshots = store.snapshots(path).await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(store.load(path, &snapshots[0]).await.unwrap(), "five");
+    }
+
+    #[gpui::test]
+    async fn test_record_from_disk_and_prune_all(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/project",
+            serde_json::json!({ "tracked.txt": "one", "untracked.txt": "one" }),
+        )
+        .await;
+        let store = FileHistoryStore::new(fs.clone(), PathBuf::from("/history"));
+        let tracked = Path::new("/project/tracked.txt");
+        let untracked = Path::new("/project/untracked.txt");
+        let start = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let at = |minutes: u64| start + Duration::from_secs(minutes * 60);
+
+        store
+            .record(tracked, "one", SnapshotReason::Opened, at(0), &policy())
+            .await
+            .unwrap();
+        fs.save(tracked, &"two".into(), Default::default())
+            .await
+            .unwrap();
+        fs.save(untracked, &"two".into(), Default::default())
+            .await
+            .unwrap();
+        for path in [tracked, untracked] {
+            store
+                .record_from_disk(path, SnapshotReason::ChangedOnDisk, at(1), &policy())
+                .await
+                .unwrap();
+        }
+        let snapshots = store.snapshots(tracked).await.unwrap();
+        assert_eq!(
+            snapshots
+                .iter()
+                .map(|snapshot| snapshot.reason)
+                .collect::<Vec<_>>(),
+            [SnapshotReason::ChangedOnDisk, SnapshotReason::Opened]
+        );
+        assert_eq!(store.load(tracked, &snapshots[0]).await.unwrap(), "two");
+        assert!(
+            store.snapshots(untracked).await.unwrap().is_empty(),
+            "files without history aren't tracked from disk"
+        );
+
+        // Pruning the whole store applies the age limit to files that weren't touched since.
+        let first = snapshots[1].clone();
+        store.prune_all(at(24 * 60), &policy()).await.unwrap();
+        let snapshots = store.snapshots(tracked).await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(store.load(tracked, &snapshots[0]).await.unwrap(), "two");
+        assert!(store.load(tracked, &first).await.is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::Context as _;
+use editor::Editor;
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Task,
+    WeakEntity, Window,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use std::{path::PathBuf, sync::Arc};
+use time::{OffsetDateTime, UtcOffset};
+use ui::{ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::{FileHistoryView, Snapshot, history_view};
+
+/// A modal listing the snapshots of the active file, newest first.
+pub struct FileHistoryTimeline {
+    picker: Entity<Picker<FileHistoryTimelineDelegate>>,
+}
+
+impl FileHistoryTimeline {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<()> {
+        let store = crate::store(cx)?;
+        let editor = workspace.active_item(cx)?.act_as::<Editor>(cx)?;
+        let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+        let path = buffer.read(cx).file()?.as_local()?.abs_path(cx);
+        let workspace_handle = workspace.weak_handle();
+
+        let snapshots = cx.background_spawn({
+            let path = path.clone();
+            async move { store.snapshots(&path).await }
+        });
+        cx.spawn_in(window, async move |workspace, cx| {
+            let snapshots = snapshots.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                workspace.toggle_modal(window, cx, |window, cx| {
+                    let delegate = FileHistoryTimelineDelegate {
+                        timeline: cx.entity().downgrade(),
+                        workspace: workspace_handle,
+                        buffer,
+                        path,
+                        snapshots,
+                        selected_index: 0,
+                        timezone: local_timezone(),
+                    };
+                    let picker =
+                        cx.new(|cx| Picker::nonsearchable_uniform_list(delegate, window, cx));
+                    Self { picker }
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+        Some(())
+    }
+}
+
+pub(crate) fn local_timezone() -> UtcOffset {
+    UtcOffset::from_whole_seconds(chrono::Local::now().offset().local_minus_utc())
+        .unwrap_or(UtcOffset::UTC)
+}
+
+impl Render for FileHistoryTimeline {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("FileHistoryTimeline")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for FileHistoryTimeline {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for FileHistoryTimeline {}
+impl ModalView for FileHistoryTimeline {}
+
+pub struct FileHistoryTimelineDelegate {
+    timeline: WeakEntity<FileHistoryTimeline>,
+    workspace: WeakEntity<Workspace>,
+    buffer: Entity<Buffer>,
+    path: PathBuf,
+    snapshots: Vec<Snapshot>,
+    selected_index: usize,
+    timezone: UtcOffset,
+}
+
+impl PickerDelegate for FileHistoryTimelineDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select a snapshot to compare…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No snapshots of this file".into())
+    }
+
+    fn match_count(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(snapshot) = self.snapshots.get(self.selected_index).cloned() else {
+            return;
+        };
+        let Some(store) = crate::store(cx) else {
+            return;
+        };
+        let path = self.path.clone();
+        let buffer = self.buffer.clone();
+        let workspace = self.workspace.clone();
+        let text = cx.background_spawn({
+            let path = path.clone();
+            let snapshot = snapshot.clone();
+            async move { store.load(&path, &snapshot).await }
+        });
+        // Secondary confirmation restores the snapshot without reviewing the diff first.
+        cx.spawn_in(window, async move |_, cx| {
+            let text = text.await?;
+            if secondary {
+                cx.update(|_, cx| history_view::restore(buffer, text, cx))?
+                    .await
+            } else {
+                let workspace = workspace.upgrade().context("workspace was dropped")?;
+                workspace.update_in(cx, |workspace, window, cx| {
+                    FileHistoryView::open(workspace, buffer, snapshot, text, window, cx)
+                })?;
+                Ok(())
+            }
+        })
+        .detach_and_log_err(cx);
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.timeline
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        _query: String,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        Task::ready(())
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let snapshot = self.snapshots.get(ix)?;
+        let timestamp = OffsetDateTime::from_unix_timestamp(snapshot.timestamp as i64).ok()?;
+        let time = time_format::format_localized_timestamp(
+            timestamp,
+            OffsetDateTime::now_utc(),
+            self.timezone,
+            time_format::TimestampFormat::EnhancedAbsolute,
+        );
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .justify_between()
+                        .child(Label::new(time))
+                        .child(
+                            Label::new(snapshot.reason.label())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+}
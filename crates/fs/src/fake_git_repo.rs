@@ -4,6 +4,7 @@ use collections::{HashMap, HashSet};
 use futures::future::{self, BoxFuture, join_all};
 use git::{
     blame::Blame,
+    history_search::{HistorySearchMatch, HistorySearchQuery},
     repository::{
         AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, GitRepository,
         GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
@@ -354,6 +355,13 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn search_history(
+        &self,
+        _query: HistorySearchQuery,
+    ) -> BoxFuture<'_, Result<Vec<HistorySearchMatch>>> {
+        unimplemented!()
+    }
+
     fn stage_paths(
         &self,
         paths: Vec<RepoPath>,
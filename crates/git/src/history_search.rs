@@ -0,0 +1,362 @@
+use crate::repository::{CommitSummary, RepoPath};
+use anyhow::{Context as _, Result};
+use gpui::SharedString;
+use regex::{Regex, RegexBuilder};
+
+/// Separates commits in the output of `git log`.
+const COMMIT_SEPARATOR: char = '\x1e';
+const DEFAULT_LIMIT: usize = 100;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HistorySearchMode {
+    /// Find commits that change the number of occurrences of a string
+    /// (`git log -S`).
+    #[default]
+    Pickaxe,
+    /// Find commits whose added or removed lines match a regex
+    /// (`git log -G`).
+    Regex,
+}
+
+/// A search through the commit history of a repository.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HistorySearchQuery {
+    pub query: String,
+    pub mode: HistorySearchMode,
+    pub case_sensitive: bool,
+    /// Only search changes to these paths. Searches every path when empty.
+    pub paths: Vec<RepoPath>,
+    /// The branch or revision to search the history of. Defaults to `HEAD`.
+    pub revision: Option<String>,
+    /// Only search commits after this date, in any format `git log` accepts.
+    pub since: Option<String>,
+    /// Only search commits before this date, in any format `git log` accepts.
+    pub until: Option<String>,
+    /// The maximum number of commits to return. Defaults to 100.
+    pub limit: Option<usize>,
+}
+
+impl HistorySearchQuery {
+    /// The arguments to pass to `git` to run this search.
+    ///
+    /// Fails for revisions that `git` would parse as an option, since the
+    /// query may come from a collaborator.
+    pub fn args(&self) -> Result<Vec<String>> {
+        let revision = self
+            .revision
+            .clone()
+            .filter(|revision| !revision.is_empty())
+            .unwrap_or_else(|| "HEAD".to_string());
+        anyhow::ensure!(!revision.starts_with('-'), "invalid revision {revision:?}");
+
+        let mut args = vec![
+            "--no-optional-locks".to_string(),
+            "log".to_string(),
+            format!("--format={COMMIT_SEPARATOR}%H%x00%P%x00%at%x00%an%x00%s"),
+            "--patch".to_string(),
+            "--no-color".to_string(),
+            "--no-ext-diff".to_string(),
+            "--unified=3".to_string(),
+            format!("--max-count={}", self.limit.unwrap_or(DEFAULT_LIMIT)),
+        ];
+        match self.mode {
+            HistorySearchMode::Pickaxe => args.push(format!("-S{}", self.query)),
+            HistorySearchMode::Regex => args.push(format!("-G{}", self.query)),
+        }
+        if !self.case_sensitive {
+            args.push("--regexp-ignore-case".to_string());
+        }
+        if let Some(since) = &self.since {
+            args.push(format!("--since={since}"));
+        }
+        if let Some(until) = &self.until {
+            args.push(format!("--until={until}"));
+        }
+        args.push(revision);
+        args.push("--".to_string());
+        args.extend(self.paths.iter().map(|path| path.to_string()));
+        Ok(args)
+    }
+
+    /// A regex matching the changed lines that this query is looking for.
+    fn line_matcher(&self) -> Option<Regex> {
+        let pattern = match self.mode {
+            HistorySearchMode::Pickaxe => regex::escape(&self.query),
+            HistorySearchMode::Regex => self.query.clone(),
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+            .ok()
+    }
+}
+
+/// A commit that matched a history search.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistorySearchMatch {
+    pub commit: CommitSummary,
+    pub author_name: SharedString,
+    /// The hunks of the commit's diff that contain the search query.
+    pub hunks: Vec<HistoryHunk>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryHunk {
+    pub path: RepoPath,
+    /// The 1-based line at which the hunk starts before the commit.
+    pub old_start: u32,
+    /// The 1-based line at which the hunk starts after the commit.
+    pub new_start: u32,
+    /// The hunk's header and lines, as they appear in the patch.
+    pub text: String,
+}
+
+/// Parses the output of `git log` run with [`HistorySearchQuery::args`],
+/// keeping only the hunks whose added or removed lines match the query.
+///
+/// `git log -S` matches on the number of occurrences in a whole file, so a
+/// commit may match without any single hunk containing the query. All of its
+/// hunks are kept in that case.
+pub fn parse_history_search(
+    output: &str,
+    query: &HistorySearchQuery,
+) -> Result<Vec<HistorySearchMatch>> {
+    let matcher = query.line_matcher();
+    let mut matches = Vec::new();
+    for record in output.split(COMMIT_SEPARATOR).skip(1) {
+        let (header, patch) = record.split_once('\n').unwrap_or((record, ""));
+        let mut fields = header.split('\0');
+        let mut next_field = || fields.next().context("unexpected git-log output");
+        let sha = next_field()?;
+        let has_parent = !next_field()?.is_empty();
+        let commit_timestamp = next_field()?
+            .parse()
+            .with_context(|| format!("invalid timestamp for commit {sha}"))?;
+        let author_name = next_field()?;
+        let subject = next_field()?;
+
+        let hunks = parse_hunks(patch);
+        let matching_hunks = hunks
+            .iter()
+            .filter(|hunk| {
+                matcher.as_ref().is_none_or(|matcher| {
+                    hunk.text.lines().skip(1).any(|line| {
+                        (line.starts_with('+') || line.starts_with('-'))
+                            && matcher.is_match(&line[1..])
+                    })
+                })
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        matches.push(HistorySearchMatch {
+            commit: CommitSummary {
+                sha: SharedString::from(sha.to_string()),
+                subject: SharedString::from(subject.to_string()),
+                commit_timestamp,
+                has_parent,
+            },
+            author_name: SharedString::from(author_name.to_string()),
+            hunks: if matching_hunks.is_empty() {
+                hunks
+            } else {
+                matching_hunks
+            },
+        });
+    }
+    Ok(matches)
+}
+
+fn parse_hunks(patch: &str) -> Vec<HistoryHunk> {
+    let mut hunks = Vec::<HistoryHunk>::new();
+    let mut old_path = None;
+    let mut path = None;
+    let mut in_hunk = false;
+    for line in patch.lines() {
+        if line.starts_with("diff --git ") {
+            old_path = None;
+            path = None;
+            in_hunk = false;
+        } else if !in_hunk && let Some(old) = line.strip_prefix("--- ") {
+            old_path = old.strip_prefix("a/").map(RepoPath::from);
+        } else if !in_hunk && let Some(new) = line.strip_prefix("+++ ") {
+            // Deleted files only have an old path.
+            path = new
+                .strip_prefix("b/")
+                .map(RepoPath::from)
+                .or(old_path.take());
+        } else if line.starts_with("@@ ") {
+            let Some(path) = path.clone() else {
+                continue;
+            };
+            let Some((old_start, new_start)) = parse_hunk_header(line) else {
+                continue;
+            };
+            in_hunk = true;
+            hunks.push(HistoryHunk {
+                path,
+                old_start,
+                new_start,
+                text: line.to_string(),
+            });
+        } else if in_hunk
+            && let Some(hunk) = hunks.last_mut()
+            && line.starts_with([' ', '+', '-', '\\'])
+        {
+            hunk.text.push('\n');
+            hunk.text.push_str(line);
+        } else {
+            in_hunk = false;
+        }
+    }
+    hunks
+}
+
+/// Parses the start lines out of a hunk header such as `@@ -12,7 +12,9 @@`.
+fn parse_hunk_header(line: &str) -> Option<(u32, u32)> {
+    let mut ranges = line.strip_prefix("@@ ")?.split(' ');
+    let old = ranges.next()?.strip_prefix('-')?;
+    let new = ranges.next()?.strip_prefix('+')?;
+    let start = |range: &str| range.split(',').next()?.parse().ok();
+    Some((start(old)?, start(new)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use unindent::Unindent as _;
+
+    fn log_output() -> String {
+        "
+        \x1e1111111111111111111111111111111111111111\x002222222222222222222222222222222222222222\x001700000000\x00Ada\x00Rename compute_total
+
+        diff --git a/src/lib.rs b/src/lib.rs
+        index 3333333..4444444 100644
+        --- a/src/lib.rs
+        +++ b/src/lib.rs
+        @@ -1,3 +1,3 @@
+        -fn compute_total() {}
+        +fn total() {}
+         fn other() {}
+        @@ -20,2 +20,2 @@ fn main() {
+        -    unrelated();
+        +    changed();
+        diff --git a/src/old.rs b/src/old.rs
+        deleted file mode 100644
+        index 5555555..0000000
+        --- a/src/old.rs
+        +++ /dev/null
+        @@ -1 +0,0 @@
+        -compute_total();
+        \x1e2222222222222222222222222222222222222222\x00\x001600000000\x00Grace\x00Initial commit
+
+        diff --git a/src/lib.rs b/src/lib.rs
+        new file mode 100644
+        index 0000000..3333333
+        --- /dev/null
+        +++ b/src/lib.rs
+        @@ -0,0 +1,2 @@
+        +fn COMPUTE_TOTAL() {}
+        +fn other() {}
+        "
+        .unindent()
+    }
+
+    #[test]
+    fn test_parse_history_search() {
+        let query = HistorySearchQuery {
+            query: "compute_total".into(),
+            case_sensitive: true,
+            ..Default::default()
+        };
+        let matches = parse_history_search(&log_output(), &query).unwrap();
+        assert_eq!(matches.len(), 2);
+
+        assert_eq!(
+            matches[0].commit,
+            CommitSummary {
+                sha: "1111111111111111111111111111111111111111".into(),
+                subject: "Rename compute_total".into(),
+                commit_timestamp: 1700000000,
+                has_parent: true,
+            }
+        );
+        assert_eq!(matches[0].author_name, "Ada");
+        assert_eq!(
+            matches[0]
+                .hunks
+                .iter()
+                .map(|hunk| (hunk.path.to_string(), hunk.old_start, hunk.new_start))
+                .collect::<Vec<_>>(),
+            vec![("src/lib.rs".into(), 1, 1), ("src/old.rs".into(), 1, 0)]
+        );
+        assert_eq!(
+            matches[0].hunks[0].text,
+            "@@ -1,3 +1,3 @@\n-fn compute_total() {}\n+fn total() {}\n fn other() {}"
+        );
+
+        // No hunk matches case-sensitively, so all of them are kept.
+        assert!(!matches[1].commit.has_parent);
+        assert_eq!(matches[1].hunks.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_history_search_case_insensitive_regex() {
+        let query = HistorySearchQuery {
+            query: r"compute_\w+\(".into(),
+            mode: HistorySearchMode::Regex,
+            case_sensitive: false,
+            ..Default::default()
+        };
+        let matches = parse_history_search(&log_output(), &query).unwrap();
+        assert_eq!(matches[0].hunks.len(), 2);
+        assert_eq!(
+            matches[1].hunks[0].text.lines().nth(1),
+            Some("+fn COMPUTE_TOTAL() {}")
+        );
+    }
+
+    #[test]
+    fn test_history_search_args() {
+        let query = HistorySearchQuery {
+            query: "needle".into(),
+            mode: HistorySearchMode::Regex,
+            case_sensitive: false,
+            paths: vec![RepoPath::from("src")],
+            revision: Some("main".into()),
+            since: Some("2 weeks ago".into()),
+            until: None,
+            limit: Some(10),
+        };
+        let args = query.args().unwrap();
+        assert_eq!(
+            &args[args.len() - 7..],
+            &[
+                "--max-count=10",
+                "-Gneedle",
+                "--regexp-ignore-case",
+                "--since=2 weeks ago",
+                "main",
+                "--",
+                "src",
+            ][..]
+        );
+    }
+
+    #[test]
+    fn test_history_search_args_reject_option_revisions() {
+        let query = HistorySearchQuery {
+            query: "needle".into(),
+            revision: Some("--output=/tmp/overwritten".into()),
+            ..Default::default()
+        };
+        assert!(query.args().is_err());
+
+        let query = HistorySearchQuery {
+            revision: Some("-p".into()),
+            ..query
+        };
+        assert!(query.args().is_err());
+    }
+}
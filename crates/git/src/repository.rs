@@ -1,4 +1,5 @@
 use crate::commit::parse_git_diff_name_status;
+use crate::history_search::{HistorySearchMatch, HistorySearchQuery, parse_history_search};
 use crate::status::{GitStatus, StatusCode};
 use crate::{Oid, SHORT_SHA_LENGTH};
 use anyhow::{Context as _, Result, anyhow, bail};
@@ -364,6 +365,13 @@ pub trait GitRepository: Send + Sync {
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>>;
 
+    /// Searches the commit history for changes matching the query
+    /// (`git log -S` or `git log -G`).
+    fn search_history(
+        &self,
+        query: HistorySearchQuery,
+    ) -> BoxFuture<'_, Result<Vec<HistorySearchMatch>>>;
+
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
     /// worktree's gitdir within the main repository (typically `.git/worktrees/<name>`).
     fn path(&self) -> PathBuf;
@@ -1114,6 +1122,30 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn search_history(
+        &self,
+        query: HistorySearchQuery,
+    ) -> BoxFuture<'_, Result<Vec<HistorySearchMatch>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args(query.args()?)
+                    .output()
+                    .await?;
+
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to run git log:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                parse_history_search(&String::from_utf8_lossy(&output.stdout), &query)
+            })
+            .boxed()
+    }
+
     fn diff(&self, diff: DiffType) -> BoxFuture<'_, Result<String>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.git_binary_path.clone();
@@ -1,6 +1,6 @@
 use anyhow::{Context as _, Result};
 use buffer_diff::{BufferDiff, BufferDiffSnapshot};
-use editor::{Editor, EditorEvent, MultiBuffer, SelectionEffects};
+use editor::{Editor, EditorEvent, MultiBuffer, SelectionEffects, scroll::Autoscroll};
 use git::repository::{CommitDetails, CommitDiff, CommitSummary, RepoPath};
 use gpui::{
     AnyElement, AnyView, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter,
@@ -39,9 +39,10 @@ struct GitBlob {
     is_deleted: bool,
 }
 
-struct CommitMetadataFile {
-    title: Arc<Path>,
-    worktree_id: WorktreeId,
+/// A file for buffers that don't exist on disk, named after their contents.
+pub(crate) struct CommitMetadataFile {
+    pub(crate) title: Arc<Path>,
+    pub(crate) worktree_id: WorktreeId,
 }
 
 const COMMIT_METADATA_NAMESPACE: u32 = 0;
@@ -54,6 +55,32 @@ impl CommitView {
         workspace: WeakEntity<Workspace>,
         window: &mut Window,
         cx: &mut App,
+    ) {
+        Self::open_at(commit, None, repo, workspace, window, cx)
+    }
+
+    /// Opens the commit, scrolled to the given 1-based line of a file as of
+    /// the commit.
+    pub fn open_at_line(
+        commit: CommitSummary,
+        path: RepoPath,
+        line: u32,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let row = line.saturating_sub(1);
+        Self::open_at(commit, Some((path, row)), repo, workspace, window, cx)
+    }
+
+    fn open_at(
+        commit: CommitSummary,
+        target: Option<(RepoPath, u32)>,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
     ) {
         let commit_diff = repo
             .update(cx, |repo, _| repo.load_commit_diff(commit.sha.to_string()))
@@ -76,6 +103,7 @@ impl CommitView {
                             CommitView::new(
                                 commit_details,
                                 commit_diff,
+                                target.clone(),
                                 repo,
                                 project.clone(),
                                 window,
@@ -92,6 +120,15 @@ impl CommitView {
                             });
                             if let Some(ix) = ix {
                                 pane.activate_item(ix, true, true, window, cx);
+                                if let Some((path, row)) = target
+                                    && let Some(commit_view) = pane
+                                        .item_for_index(ix)
+                                        .and_then(|item| item.downcast::<CommitView>())
+                                {
+                                    commit_view.update(cx, |commit_view, cx| {
+                                        commit_view.scroll_to(&path, row, window, cx);
+                                    });
+                                }
                                 return;
                             } else {
                                 pane.add_item(Box::new(commit_view), true, true, None, window, cx);
@@ -106,6 +143,7 @@ impl CommitView {
     fn new(
         commit: CommitDetails,
         commit_diff: CommitDiff,
+        target: Option<(RepoPath, u32)>,
         repository: Entity<Repository>,
         project: Entity<Project>,
         window: &mut Window,
@@ -160,7 +198,7 @@ impl CommitView {
             });
         }
 
-        cx.spawn(async move |this, mut cx| {
+        cx.spawn_in(window, async move |this, cx| {
             for file in commit_diff.files {
                 let is_deleted = file.new_text.is_none();
                 let new_text = file.new_text.unwrap_or_default();
@@ -173,17 +211,18 @@ impl CommitView {
                             .or(first_worktree_id)
                     })?
                     .context("project has no worktrees")?;
+                let repo_path = file.path.clone();
                 let file = Arc::new(GitBlob {
                     path: file.path.clone(),
                     is_deleted,
                     worktree_id,
                 }) as Arc<dyn language::File>;
 
-                let buffer = build_buffer(new_text, file, &language_registry, &mut cx).await?;
+                let buffer = build_buffer(new_text, file, &language_registry, cx).await?;
                 let buffer_diff =
-                    build_buffer_diff(old_text, &buffer, &language_registry, &mut cx).await?;
+                    build_buffer_diff(old_text, &buffer, &language_registry, cx).await?;
 
-                this.update(cx, |this, cx| {
+                this.update_in(cx, |this, window, cx| {
                     this.multibuffer.update(cx, |multibuffer, cx| {
                         let snapshot = buffer.read(cx).snapshot();
                         let diff = buffer_diff.read(cx);
@@ -201,6 +240,11 @@ impl CommitView {
                         );
                         multibuffer.add_diff(buffer_diff, cx);
                     });
+                    if let Some((path, row)) = &target
+                        && *path == repo_path
+                    {
+                        this.scroll_to(path, *row, window, cx);
+                    }
                 })?;
             }
             anyhow::Ok(())
@@ -213,6 +257,30 @@ impl CommitView {
             multibuffer,
         }
     }
+
+    fn scroll_to(&self, path: &RepoPath, row: u32, window: &mut Window, cx: &mut Context<Self>) {
+        let multibuffer = self.multibuffer.read(cx);
+        let Some(buffer) = multibuffer.all_buffers().into_iter().find(|buffer| {
+            buffer
+                .read(cx)
+                .file()
+                .is_some_and(|file| file.path().as_ref() == &**path)
+        }) else {
+            return;
+        };
+        let Some(anchor) = multibuffer.buffer_point_to_anchor(&buffer, Point::new(row, 0), cx)
+        else {
+            return;
+        };
+        self.editor.update(cx, |editor, cx| {
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::top_relative(3)),
+                window,
+                cx,
+                |selections| selections.select_anchor_ranges([anchor..anchor]),
+            );
+        });
+    }
 }
 
 impl language::File for GitBlob {
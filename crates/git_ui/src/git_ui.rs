@@ -32,6 +32,8 @@ mod conflict_view;
 pub mod file_diff_view;
 pub mod git_panel;
 mod git_panel_settings;
+pub mod history_search;
+pub mod merge_view;
 pub mod onboarding;
 pub mod picker_prompt;
 pub mod project_diff;
@@ -63,6 +65,7 @@ pub fn init(cx: &mut App) {
         git_panel::register(workspace);
         repository_selector::register(workspace);
         branch_picker::register(workspace);
+        history_search::register(workspace);
 
         let project = workspace.project().read(cx);
         if project.is_read_only(cx) {
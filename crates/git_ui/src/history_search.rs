@@ -0,0 +1,601 @@
+//! Searches the commit history of a repository for changes that add or remove
+//! some text, showing the matching hunks of each commit in a multibuffer.
+use crate::commit_view::{CommitMetadataFile, CommitView};
+use collections::HashMap;
+use editor::{Editor, EditorElement, EditorEvent, EditorStyle, MultiBuffer};
+use git::{
+    history_search::{HistoryHunk, HistorySearchMatch, HistorySearchMode, HistorySearchQuery},
+    repository::{CommitSummary, RepoPath},
+};
+use gpui::{
+    AnyElement, AnyView, App, Entity, EventEmitter, FocusHandle, Focusable, SharedString, Task,
+    TextStyle, WeakEntity, Window, actions,
+};
+use language::{Buffer, BufferId, Capability, LineEnding, Point, TextBuffer};
+use multi_buffer::PathKey;
+use project::git_store::Repository;
+use settings::Settings as _;
+use std::{any::TypeId, fmt::Write as _, ops::Range, path::PathBuf, sync::Arc};
+use theme::ThemeSettings;
+use ui::{IconButtonShape, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Item, Workspace,
+    item::{ItemEvent, TabContentParams},
+    searchable::{SearchableItem as _, SearchableItemHandle},
+};
+use zed_actions::git::SearchHistory;
+
+actions!(
+    history_search,
+    [
+        /// Runs the history search with the current query and filters.
+        Search,
+        /// Opens the commit under the cursor, scrolled to the matching hunk.
+        OpenCommit,
+        /// Toggles between searching for a string and for a regex.
+        ToggleRegex,
+        /// Toggles case-sensitive matching.
+        ToggleCaseSensitive
+    ]
+);
+
+const MATCH_NAMESPACE: u32 = 0;
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(HistorySearch::deploy);
+}
+
+pub struct HistorySearch {
+    workspace: WeakEntity<Workspace>,
+    repository: WeakEntity<Repository>,
+    repository_name: SharedString,
+    query_editor: Entity<Editor>,
+    paths_editor: Entity<Editor>,
+    revision_editor: Entity<Editor>,
+    since_editor: Entity<Editor>,
+    until_editor: Entity<Editor>,
+    mode: HistorySearchMode,
+    case_sensitive: bool,
+    multibuffer: Entity<MultiBuffer>,
+    results_editor: Entity<Editor>,
+    results: HashMap<BufferId, CommitResult>,
+    status: SearchStatus,
+    pending_search: Option<Task<()>>,
+    focus_handle: FocusHandle,
+}
+
+/// The hunks of a matching commit, as laid out in its results buffer.
+struct CommitResult {
+    commit: CommitSummary,
+    hunks: Vec<(Range<u32>, HistoryHunk)>,
+}
+
+enum SearchStatus {
+    Idle,
+    Searching,
+    Done { commit_count: usize },
+    Failed(SharedString),
+}
+
+impl HistorySearch {
+    fn deploy(
+        workspace: &mut Workspace,
+        action: &SearchHistory,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        let query = action.query.clone().or_else(|| {
+            let editor = workspace.active_item_as::<Editor>(cx)?;
+            let query = editor.update(cx, |editor, cx| editor.query_suggestion(window, cx));
+            (!query.is_empty()).then_some(query)
+        });
+
+        let history_search = if let Some(existing) = workspace
+            .item_of_type::<Self>(cx)
+            .filter(|existing| existing.read(cx).repository == repository.downgrade())
+        {
+            workspace.activate_item(&existing, true, true, window, cx);
+            existing
+        } else {
+            let workspace_handle = cx.weak_entity();
+            let history_search = cx.new(|cx| Self::new(repository, workspace_handle, window, cx));
+            workspace.add_item_to_active_pane(
+                Box::new(history_search.clone()),
+                None,
+                true,
+                window,
+                cx,
+            );
+            history_search
+        };
+
+        history_search.update(cx, |history_search, cx| {
+            if let Some(query) = query {
+                history_search.query_editor.update(cx, |editor, cx| {
+                    editor.set_text(query, window, cx);
+                });
+                history_search.search(cx);
+            }
+            window.focus(&history_search.query_editor.focus_handle(cx));
+        });
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let input = |placeholder: &'static str, window: &mut Window, cx: &mut Context<Self>| {
+            cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text(placeholder, cx);
+                editor
+            })
+        };
+        let query_editor = input("Search history…", window, cx);
+        let paths_editor = input("Paths, e.g. src/main.rs, docs", window, cx);
+        let revision_editor = input("Branch (default: HEAD)", window, cx);
+        let since_editor = input("Since, e.g. 2 weeks ago", window, cx);
+        let until_editor = input("Until, e.g. 2024-01-01", window, cx);
+
+        let multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadOnly));
+        let results_editor = cx.new(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer.clone(), None, window, cx);
+            editor.set_read_only(true);
+            editor.disable_inline_diagnostics();
+            editor
+        });
+        cx.subscribe(&results_editor, |_, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone())
+        })
+        .detach();
+
+        Self {
+            workspace,
+            repository_name: repository.read(cx).display_name(),
+            repository: repository.downgrade(),
+            query_editor,
+            paths_editor,
+            revision_editor,
+            since_editor,
+            until_editor,
+            mode: HistorySearchMode::default(),
+            case_sensitive: false,
+            multibuffer,
+            results_editor,
+            results: HashMap::default(),
+            status: SearchStatus::Idle,
+            pending_search: None,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn build_query(&self, cx: &App) -> Option<HistorySearchQuery> {
+        let text = |editor: &Entity<Editor>| {
+            let text = editor.read(cx).text(cx).trim().to_string();
+            (!text.is_empty()).then_some(text)
+        };
+        Some(HistorySearchQuery {
+            // Leading and trailing whitespace may be part of what's searched for.
+            query: Some(self.query_editor.read(cx).text(cx))
+                .filter(|query| !query.trim().is_empty())?,
+            mode: self.mode,
+            case_sensitive: self.case_sensitive,
+            paths: text(&self.paths_editor)
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+                .map(RepoPath::from)
+                .collect(),
+            revision: text(&self.revision_editor),
+            since: text(&self.since_editor),
+            until: text(&self.until_editor),
+            limit: None,
+        })
+    }
+
+    fn search(&mut self, cx: &mut Context<Self>) {
+        let Some(query) = self.build_query(cx) else {
+            return;
+        };
+        let Some(search) = self
+            .repository
+            .update(cx, |repository, _| repository.search_history(query))
+            .ok()
+        else {
+            return;
+        };
+        self.status = SearchStatus::Searching;
+        self.pending_search = Some(cx.spawn(async move |this, cx| {
+            let result = search.await.map_err(anyhow::Error::from).and_then(|r| r);
+            this.update(cx, |this, cx| {
+                this.pending_search = None;
+                match result {
+                    Ok(matches) => this.show_matches(matches, cx),
+                    Err(error) => {
+                        this.status = SearchStatus::Failed(error.to_string().into());
+                        cx.notify();
+                    }
+                }
+            })
+            .log_err();
+        }));
+        cx.notify();
+    }
+
+    fn show_matches(&mut self, matches: Vec<HistorySearchMatch>, cx: &mut Context<Self>) {
+        self.status = SearchStatus::Done {
+            commit_count: matches.len(),
+        };
+        self.results.clear();
+        self.multibuffer
+            .update(cx, |multibuffer, cx| multibuffer.clear(cx));
+
+        let worktree_id = self.workspace.upgrade().and_then(|workspace| {
+            let project = workspace.read(cx).project().read(cx);
+            let worktree = project.worktrees(cx).next()?;
+            Some(worktree.read(cx).id())
+        });
+
+        for (ix, search_match) in matches.into_iter().enumerate() {
+            let (text, hunks) = format_match(&search_match);
+            let file = worktree_id.map(|worktree_id| {
+                Arc::new(CommitMetadataFile {
+                    title: PathBuf::from(format_title(&search_match)).into(),
+                    worktree_id,
+                }) as Arc<dyn language::File>
+            });
+            let buffer = cx.new(|cx| {
+                let buffer = TextBuffer::new_normalized(
+                    0,
+                    cx.entity_id().as_non_zero_u64().into(),
+                    LineEnding::default(),
+                    text.into(),
+                );
+                Buffer::build(buffer, file, Capability::ReadOnly)
+            });
+            let excerpt_ranges = hunks
+                .iter()
+                .map(|(rows, _)| Point::new(rows.start, 0)..Point::new(rows.end, 0))
+                .collect::<Vec<_>>();
+            self.multibuffer.update(cx, |multibuffer, cx| {
+                multibuffer.set_excerpts_for_path(
+                    PathKey::namespaced(MATCH_NAMESPACE, PathBuf::from(ix.to_string()).into()),
+                    buffer.clone(),
+                    excerpt_ranges,
+                    0,
+                    cx,
+                );
+            });
+            self.results.insert(
+                buffer.read(cx).remote_id(),
+                CommitResult {
+                    commit: search_match.commit,
+                    hunks,
+                },
+            );
+        }
+        cx.notify();
+    }
+
+    fn open_commit(&mut self, _: &OpenCommit, window: &mut Window, cx: &mut Context<Self>) {
+        let cursor = self.results_editor.update(cx, |editor, cx| {
+            editor.selections.newest::<Point>(cx).head()
+        });
+        let Some((buffer, point, _)) = self.multibuffer.read(cx).point_to_buffer_point(cursor, cx)
+        else {
+            return;
+        };
+        let Some(result) = self.results.get(&buffer.read(cx).remote_id()) else {
+            return;
+        };
+        let Some((rows, hunk)) = result
+            .hunks
+            .iter()
+            .find(|(rows, _)| rows.contains(&point.row))
+        else {
+            return;
+        };
+        let line = line_in_commit(hunk, point.row - rows.start);
+        CommitView::open_at_line(
+            result.commit.clone(),
+            hunk.path.clone(),
+            line,
+            self.repository.clone(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn toggle_regex(&mut self, _: &ToggleRegex, _: &mut Window, cx: &mut Context<Self>) {
+        self.mode = match self.mode {
+            HistorySearchMode::Pickaxe => HistorySearchMode::Regex,
+            HistorySearchMode::Regex => HistorySearchMode::Pickaxe,
+        };
+        self.search(cx);
+        cx.notify();
+    }
+
+    fn toggle_case_sensitive(
+        &mut self,
+        _: &ToggleCaseSensitive,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.case_sensitive = !self.case_sensitive;
+        self.search(cx);
+        cx.notify();
+    }
+
+    fn render_input(&self, editor: &Entity<Editor>, cx: &Context<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.buffer_font.family.clone(),
+            font_features: settings.buffer_font.features.clone(),
+            font_fallbacks: settings.buffer_font.fallbacks.clone(),
+            font_size: rems(0.875).into(),
+            font_weight: settings.buffer_font.weight,
+            line_height: relative(1.3),
+            ..TextStyle::default()
+        };
+        h_flex()
+            .min_w_32()
+            .flex_1()
+            .h_8()
+            .px_2()
+            .py_1()
+            .border_1()
+            .border_color(cx.theme().colors().border)
+            .rounded_lg()
+            .child(EditorElement::new(
+                editor,
+                EditorStyle {
+                    background: cx.theme().colors().toolbar_background,
+                    local_player: cx.theme().players().local(),
+                    text: text_style,
+                    ..EditorStyle::default()
+                },
+            ))
+    }
+
+    fn render_status(&self) -> Option<impl IntoElement> {
+        let (message, color) = match &self.status {
+            SearchStatus::Idle => return None,
+            SearchStatus::Searching => ("Searching history…".into(), Color::Muted),
+            SearchStatus::Done { commit_count: 0 } => ("No matching commits".into(), Color::Muted),
+            SearchStatus::Done { commit_count: 1 } => ("1 matching commit".into(), Color::Muted),
+            SearchStatus::Done { commit_count } => (
+                SharedString::from(format!("{commit_count} matching commits")),
+                Color::Muted,
+            ),
+            SearchStatus::Failed(error) => (error.clone(), Color::Error),
+        };
+        Some(Label::new(message).size(LabelSize::Small).color(color))
+    }
+}
+
+/// Formats the hunks of a match as the text of its results buffer, returning
+/// the rows that each hunk occupies.
+fn format_match(search_match: &HistorySearchMatch) -> (String, Vec<(Range<u32>, HistoryHunk)>) {
+    let mut text = String::new();
+    let mut hunks = Vec::with_capacity(search_match.hunks.len());
+    let mut row = 0;
+    for hunk in &search_match.hunks {
+        let start = row;
+        writeln!(&mut text, "{}", hunk.path.display()).unwrap();
+        writeln!(&mut text, "{}", hunk.text).unwrap();
+        row += 1 + hunk.text.lines().count() as u32;
+        hunks.push((start..row, hunk.clone()));
+    }
+    (text, hunks)
+}
+
+fn format_title(search_match: &HistorySearchMatch) -> String {
+    let commit = &search_match.commit;
+    let short_sha = commit.sha.get(0..7).unwrap_or(&*commit.sha);
+    format!(
+        "{short_sha} {} ({})",
+        commit.subject.replace('/', "∕"),
+        search_match.author_name
+    )
+}
+
+/// Returns the 1-based line of the file as of the commit that the given row
+/// of a formatted hunk corresponds to. Rows 0 and 1 hold the hunk's path and
+/// header.
+fn line_in_commit(hunk: &HistoryHunk, row: u32) -> u32 {
+    let lines_before = hunk
+        .text
+        .lines()
+        .skip(1)
+        .take(row.saturating_sub(2) as usize)
+        .filter(|line| !line.starts_with(['-', '\\']))
+        .count() as u32;
+    hunk.new_start + lines_before
+}
+
+impl EventEmitter<EditorEvent> for HistorySearch {}
+
+impl Focusable for HistorySearch {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HistorySearch {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        let query = self.query_editor.read(cx).text(cx);
+        if query.is_empty() {
+            "History Search".into()
+        } else {
+            format!("History: {}", util::truncate_and_trailoff(&query, 20)).into()
+        }
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(format!("Search history of {}", self.repository_name).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Git History Search Opened")
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.results_editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.results_editor.clone()))
+    }
+}
+
+impl Render for HistorySearch {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        let query_row = h_flex()
+            .w_full()
+            .gap_2()
+            .child(self.render_input(&self.query_editor, cx))
+            .child(
+                IconButton::new("history-search-case-sensitive", IconName::CaseSensitive)
+                    .shape(IconButtonShape::Square)
+                    .toggle_state(self.case_sensitive)
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |window, cx| {
+                            Tooltip::for_action_in(
+                                "Match Case",
+                                &ToggleCaseSensitive,
+                                &focus_handle,
+                                window,
+                                cx,
+                            )
+                        }
+                    })
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_case_sensitive(&ToggleCaseSensitive, window, cx);
+                    })),
+            )
+            .child(
+                IconButton::new("history-search-regex", IconName::Regex)
+                    .shape(IconButtonShape::Square)
+                    .toggle_state(self.mode == HistorySearchMode::Regex)
+                    .tooltip({
+                        let focus_handle = focus_handle.clone();
+                        move |window, cx| {
+                            Tooltip::for_action_in(
+                                "Use Regular Expression (git log -G)",
+                                &ToggleRegex,
+                                &focus_handle,
+                                window,
+                                cx,
+                            )
+                        }
+                    })
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_regex(&ToggleRegex, window, cx);
+                    })),
+            );
+        let filter_row = h_flex()
+            .w_full()
+            .gap_2()
+            .child(self.render_input(&self.paths_editor, cx))
+            .child(self.render_input(&self.revision_editor, cx))
+            .child(self.render_input(&self.since_editor, cx))
+            .child(self.render_input(&self.until_editor, cx));
+
+        v_flex()
+            .key_context("HistorySearch")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::toggle_regex))
+            .on_action(cx.listener(Self::toggle_case_sensitive))
+            .child(
+                v_flex()
+                    .p_2()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .bg(cx.theme().colors().toolbar_background)
+                    .on_action(cx.listener(|this, _: &menu::Confirm, _, cx| this.search(cx)))
+                    .on_action(cx.listener(|this, _: &Search, _, cx| this.search(cx)))
+                    .child(query_row)
+                    .child(filter_row)
+                    .children(self.render_status()),
+            )
+            .child(
+                div()
+                    .key_context("HistorySearchResults")
+                    .flex_1()
+                    .size_full()
+                    .on_action(cx.listener(Self::open_commit))
+                    .child(self.results_editor.clone()),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_in_commit() {
+        let hunk = HistoryHunk {
+            path: RepoPath::from("src/lib.rs"),
+            old_start: 10,
+            new_start: 12,
+            text: "@@ -10,4 +12,4 @@\n context\n-removed\n+added\n context".into(),
+        };
+        // The path and header rows map to the start of the hunk.
+        assert_eq!(line_in_commit(&hunk, 0), 12);
+        assert_eq!(line_in_commit(&hunk, 1), 12);
+        assert_eq!(line_in_commit(&hunk, 2), 12);
+        // Removed lines don't exist in the commit, so they map to the next line.
+        assert_eq!(line_in_commit(&hunk, 3), 13);
+        assert_eq!(line_in_commit(&hunk, 4), 13);
+        assert_eq!(line_in_commit(&hunk, 5), 14);
+    }
+}
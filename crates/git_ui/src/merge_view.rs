@@ -0,0 +1,708 @@
+//! MergeView provides a UI for resolving a three-way merge, such as one
+//! started by `git mergetool` or `jj resolve`.
+
+use anyhow::Result;
+use buffer_diff::BufferDiff;
+use editor::{Editor, EditorEvent, MultiBuffer};
+use gpui::{
+    AnyElement, AnyView, App, AppContext as _, Context, Entity, EventEmitter, FocusHandle,
+    Focusable, IntoElement, Render, Subscription, Task, Window,
+};
+use language::{Buffer, line_diff};
+use project::{ConflictSet, Project};
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use ui::{SharedString, prelude::*};
+use util::paths::PathExt as _;
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, ItemEvent, SaveOptions, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+use crate::file_diff_view::build_buffer_diff;
+
+pub struct MergeView {
+    local_editor: Entity<Editor>,
+    result_editor: Entity<Editor>,
+    remote_editor: Entity<Editor>,
+    result_buffer: Entity<Buffer>,
+    _conflict_set: Entity<ConflictSet>,
+    _result_editor_subscription: Subscription,
+}
+
+impl MergeView {
+    /// Opens a merge of the `local` and `remote` versions of a file, which
+    /// share the common ancestor `base`, writing the result to `merged`.
+    ///
+    /// If `merged` doesn't contain conflict markers yet, it's overwritten with
+    /// the merge of the three versions.
+    pub fn open(
+        base_path: PathBuf,
+        local_path: PathBuf,
+        remote_path: PathBuf,
+        merged_path: PathBuf,
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let workspace = workspace.weak_handle();
+        window.spawn(cx, async move |cx| {
+            let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+            let base_buffer = project
+                .update(cx, |project, cx| project.open_local_buffer(&base_path, cx))?
+                .await?;
+            let local_buffer = project
+                .update(cx, |project, cx| project.open_local_buffer(&local_path, cx))?
+                .await?;
+            let remote_buffer = project
+                .update(cx, |project, cx| {
+                    project.open_local_buffer(&remote_path, cx)
+                })?
+                .await?;
+            let result_buffer = project
+                .update(cx, |project, cx| {
+                    project.open_local_buffer(&merged_path, cx)
+                })?
+                .await?;
+
+            let merged_text = cx.update(|_, cx| {
+                if has_conflict_markers(&result_buffer.read(cx).text()) {
+                    return None;
+                }
+                let file_name = |path: &Path| {
+                    path.file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+                };
+                Some(merge_texts(
+                    &base_buffer.read(cx).text(),
+                    &local_buffer.read(cx).text(),
+                    &remote_buffer.read(cx).text(),
+                    &file_name(&local_path),
+                    &file_name(&remote_path),
+                ))
+            })?;
+            if let Some(merged_text) = merged_text {
+                // Write the conflicts to disk right away, so that closing the
+                // view without resolving them doesn't report success.
+                result_buffer.update(cx, |buffer, cx| {
+                    buffer.set_text(merged_text, cx);
+                })?;
+                project
+                    .update(cx, |project, cx| {
+                        project.save_buffer(result_buffer.clone(), cx)
+                    })?
+                    .await?;
+            }
+
+            let local_diff = build_buffer_diff(&base_buffer, &local_buffer, cx).await?;
+            let remote_diff = build_buffer_diff(&base_buffer, &remote_buffer, cx).await?;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let merge_view = cx.new(|cx| {
+                    MergeView::new(
+                        (local_buffer, local_diff),
+                        (remote_buffer, remote_diff),
+                        result_buffer,
+                        project.clone(),
+                        window,
+                        cx,
+                    )
+                });
+
+                let pane = workspace.active_pane();
+                pane.update(cx, |pane, cx| {
+                    pane.add_item(Box::new(merge_view.clone()), true, true, None, window, cx);
+                });
+
+                merge_view
+            })
+        })
+    }
+
+    fn new(
+        (local_buffer, local_diff): (Entity<Buffer>, Entity<BufferDiff>),
+        (remote_buffer, remote_diff): (Entity<Buffer>, Entity<BufferDiff>),
+        result_buffer: Entity<Buffer>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let local_editor = Self::side_editor(local_buffer, local_diff, project.clone(), window, cx);
+        let remote_editor =
+            Self::side_editor(remote_buffer, remote_diff, project.clone(), window, cx);
+
+        // The conflict set has to exist before the result editor is created,
+        // so that the editor shows the controls for resolving each conflict.
+        let conflict_set = project.update(cx, |project, cx| {
+            project.git_store().update(cx, |git_store, cx| {
+                git_store.open_merge_conflict_set(result_buffer.clone(), cx)
+            })
+        });
+        let result_editor = cx
+            .new(|cx| Editor::for_buffer(result_buffer.clone(), Some(project.clone()), window, cx));
+        let result_editor_subscription =
+            cx.subscribe(&result_editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone());
+            });
+
+        Self {
+            local_editor,
+            result_editor,
+            remote_editor,
+            result_buffer,
+            _conflict_set: conflict_set,
+            _result_editor_subscription: result_editor_subscription,
+        }
+    }
+
+    /// A read-only editor showing how one side of the merge differs from the
+    /// common ancestor.
+    fn side_editor(
+        buffer: Entity<Buffer>,
+        diff: Entity<BufferDiff>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<Editor> {
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer = MultiBuffer::singleton(buffer, cx);
+            multibuffer.add_diff(diff, cx);
+            multibuffer
+        });
+        cx.new(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer, Some(project), window, cx);
+            editor.set_read_only(true);
+            editor.start_temporary_diff_override();
+            editor.disable_diagnostics(cx);
+            editor.set_expand_all_diff_hunks(cx);
+            editor.set_render_diff_hunk_controls(
+                Arc::new(|_, _, _, _, _, _, _, _| gpui::Empty.into_any_element()),
+                cx,
+            );
+            editor
+        })
+    }
+
+    fn render_pane(&self, title: &'static str, editor: &Entity<Editor>, cx: &Context<Self>) -> Div {
+        let path = editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .and_then(|buffer| {
+                let file = buffer.read(cx).file()?;
+                Some(file.full_path(cx).compact().to_string_lossy().into_owned())
+            })
+            .unwrap_or_default();
+
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new(title).size(LabelSize::Small))
+                    .child(
+                        Label::new(path)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    ),
+            )
+            .child(editor.clone())
+    }
+}
+
+/// Returns whether the text still contains the markers of an unresolved
+/// conflict.
+pub fn has_conflict_markers(text: &str) -> bool {
+    let mut in_conflict = false;
+    for line in text.lines() {
+        if line.starts_with("<<<<<<< ") {
+            in_conflict = true;
+        } else if in_conflict && line.starts_with(">>>>>>> ") {
+            return true;
+        }
+    }
+    false
+}
+
+/// Merges the changes that `local` and `remote` make to `base`, line by line.
+///
+/// Changes that overlap or touch are written as a conflict, using the given
+/// labels for its markers, unless both sides made the same change.
+fn merge_texts(
+    base: &str,
+    local: &str,
+    remote: &str,
+    local_label: &str,
+    remote_label: &str,
+) -> String {
+    let base_rows = RowOffsets::new(base);
+    let local_rows = RowOffsets::new(local);
+    let remote_rows = RowOffsets::new(remote);
+    let local_hunks = line_diff(base, local);
+    let remote_hunks = line_diff(base, remote);
+
+    let mut result = String::new();
+    let (mut local_ix, mut remote_ix) = (0, 0);
+    // How many rows each side has gained or lost before the current row.
+    let (mut local_delta, mut remote_delta) = (0i64, 0i64);
+    let mut base_row = 0;
+    loop {
+        let start = match (local_hunks.get(local_ix), remote_hunks.get(remote_ix)) {
+            (None, None) => break,
+            (Some((local, _)), None) => local.start,
+            (None, Some((remote, _))) => remote.start,
+            (Some((local, _)), Some((remote, _))) => local.start.min(remote.start),
+        };
+        result.push_str(&base[base_rows.range(base_row..start)]);
+
+        // Group every hunk that overlaps or touches the ones before it.
+        let (local_start_ix, remote_start_ix) = (local_ix, remote_ix);
+        let mut end = start;
+        loop {
+            if let Some((base_range, _)) = local_hunks.get(local_ix)
+                && base_range.start <= end
+            {
+                end = end.max(base_range.end);
+                local_ix += 1;
+            } else if let Some((base_range, _)) = remote_hunks.get(remote_ix)
+                && base_range.start <= end
+            {
+                end = end.max(base_range.end);
+                remote_ix += 1;
+            } else {
+                break;
+            }
+        }
+
+        let local_group = &local_hunks[local_start_ix..local_ix];
+        let remote_group = &remote_hunks[remote_start_ix..remote_ix];
+        let local_text = &local[local_rows.range(side_range(start..end, local_delta, local_group))];
+        let remote_text =
+            &remote[remote_rows.range(side_range(start..end, remote_delta, remote_group))];
+        local_delta += row_delta(local_group);
+        remote_delta += row_delta(remote_group);
+
+        if remote_group.is_empty() || local_text == remote_text {
+            result.push_str(local_text);
+        } else if local_group.is_empty() {
+            result.push_str(remote_text);
+        } else {
+            result.push_str(&format!("<<<<<<< {local_label}\n"));
+            push_line(&mut result, local_text);
+            result.push_str("=======\n");
+            push_line(&mut result, remote_text);
+            result.push_str(&format!(">>>>>>> {remote_label}\n"));
+        }
+        base_row = end;
+    }
+    result.push_str(&base[base_rows.range(base_row..base_rows.len())]);
+    result
+}
+
+/// The rows of one side of the merge that replace the given rows of the base.
+fn side_range(
+    base_range: Range<u32>,
+    delta: i64,
+    hunks: &[(Range<u32>, Range<u32>)],
+) -> Range<u32> {
+    let start = base_range.start as i64 + delta;
+    let end = base_range.end as i64 + delta + row_delta(hunks);
+    start as u32..end as u32
+}
+
+fn row_delta(hunks: &[(Range<u32>, Range<u32>)]) -> i64 {
+    hunks
+        .iter()
+        .map(|(base, side)| side.len() as i64 - base.len() as i64)
+        .sum()
+}
+
+fn push_line(result: &mut String, text: &str) {
+    result.push_str(text);
+    if !text.is_empty() && !text.ends_with('\n') {
+        result.push('\n');
+    }
+}
+
+/// The byte offsets at which each row of a text starts.
+struct RowOffsets {
+    starts: Vec<usize>,
+    len: usize,
+}
+
+impl RowOffsets {
+    fn new(text: &str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(
+            text.match_indices('\n')
+                .map(|(ix, _)| ix + 1)
+                .filter(|&start| start < text.len()),
+        );
+        Self {
+            starts,
+            len: text.len(),
+        }
+    }
+
+    fn len(&self) -> u32 {
+        if self.len == 0 {
+            0
+        } else {
+            self.starts.len() as u32
+        }
+    }
+
+    fn range(&self, rows: Range<u32>) -> Range<usize> {
+        let offset = |row: u32| self.starts.get(row as usize).copied().unwrap_or(self.len);
+        offset(rows.start)..offset(rows.end)
+    }
+}
+
+impl EventEmitter<EditorEvent> for MergeView {}
+
+impl Focusable for MergeView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.result_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        let file_name = self
+            .result_buffer
+            .read(cx)
+            .file()
+            .and_then(|file| {
+                Some(
+                    file.full_path(cx)
+                        .file_name()?
+                        .to_string_lossy()
+                        .to_string(),
+                )
+            })
+            .unwrap_or_else(|| "untitled".into());
+        format!("Merge {file_name}").into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let path = self
+            .result_buffer
+            .read(cx)
+            .file()
+            .map(|file| file.full_path(cx).compact().to_string_lossy().to_string())?;
+        Some(format!("Merge {path}").into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge View Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.result_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.result_editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.result_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.result_editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.result_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.result_editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for editor in [&self.local_editor, &self.result_editor, &self.remote_editor] {
+            editor.update(cx, |editor, cx| {
+                editor.added_to_workspace(workspace, window, cx)
+            });
+        }
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.result_editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+}
+
+impl Render for MergeView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .size_full()
+            .child(
+                self.render_pane("Local", &self.local_editor, cx)
+                    .border_r_1()
+                    .border_color(cx.theme().colors().border),
+            )
+            .child(
+                self.render_pane("Result", &self.result_editor, cx)
+                    .border_r_1()
+                    .border_color(cx.theme().colors().border),
+            )
+            .child(self.render_pane("Remote", &self.remote_editor, cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Fs, Project};
+    use settings::{Settings, SettingsStore};
+    use unindent::Unindent as _;
+    use util::path;
+    use workspace::Workspace;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init_settings(cx);
+            theme::ThemeSettings::register(cx)
+        });
+    }
+
+    #[test]
+    fn test_merge_texts() {
+        let base = "
+            one
+            two
+            three
+            four
+            five
+            six
+            seven
+        "
+        .unindent();
+        let local = "
+            one
+            TWO
+            three
+            four
+            FIVE
+            six
+            seven
+            eight
+        "
+        .unindent();
+        let remote = "
+            zero
+            one
+            two
+            three
+            four
+            5
+            six
+            seven
+            eight
+        "
+        .unindent();
+
+        // Changes to different lines are merged, and identical ones are kept once.
+        assert_eq!(
+            merge_texts(&base, &local, &remote, "local", "remote"),
+            "
+            zero
+            one
+            TWO
+            three
+            four
+            <<<<<<< local
+            FIVE
+            =======
+            5
+            >>>>>>> remote
+            six
+            seven
+            eight
+            "
+            .unindent()
+        );
+
+        assert_eq!(merge_texts(&base, &local, &base, "local", "remote"), local);
+        assert_eq!(
+            merge_texts("a\nb", "a\nc", "a\nd", "l", "r"),
+            "a\n<<<<<<< l\nc\n=======\nd\n>>>>>>> r\n"
+        );
+    }
+
+    #[test]
+    fn test_has_conflict_markers() {
+        assert!(has_conflict_markers(
+            "a\n<<<<<<< ours\nb\n=======\nc\n>>>>>>> theirs\nd\n"
+        ));
+        assert!(!has_conflict_markers("a\n=======\nb\n"));
+        assert!(!has_conflict_markers(">>>>>>> theirs\n<<<<<<< ours\n"));
+    }
+
+    #[gpui::test]
+    async fn test_merge_view_writes_conflicts(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/test"),
+            serde_json::json!({
+                "base.txt": "one\ntwo\nthree\n",
+                "local.txt": "one\nlocal\nthree\n",
+                "remote.txt": "one\nremote\nthree\n",
+                "merged.txt": "",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), [path!("/test").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let merge_view = workspace
+            .update_in(cx, |workspace, window, cx| {
+                MergeView::open(
+                    path!("/test/base.txt").into(),
+                    path!("/test/local.txt").into(),
+                    path!("/test/remote.txt").into(),
+                    path!("/test/merged.txt").into(),
+                    workspace,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+
+        let merged = fs.load(path!("/test/merged.txt").as_ref()).await.unwrap();
+        assert_eq!(
+            merged,
+            "one\n<<<<<<< local.txt\nlocal\n=======\nremote\n>>>>>>> remote.txt\nthree\n"
+        );
+        assert!(has_conflict_markers(&merged));
+
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            assert_eq!(merge_view.tab_content_text(0, cx), "Merge merged.txt");
+            merge_view.result_editor.update(cx, |editor, cx| {
+                editor.set_text("one\nlocal\nremote\nthree\n", window, cx);
+            });
+            assert!(merge_view.is_dirty(cx));
+        });
+
+        merge_view
+            .update_in(cx, |merge_view, window, cx| {
+                merge_view.save(SaveOptions::default(), project.clone(), window, cx)
+            })
+            .await
+            .unwrap();
+        let merged = fs.load(path!("/test/merged.txt").as_ref()).await.unwrap();
+        assert!(!has_conflict_markers(&merged));
+    }
+}
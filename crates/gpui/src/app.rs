@@ -232,6 +232,8 @@ type Handler = Box<dyn FnMut(&mut App) -> bool + 'static>;
 type Listener = Box<dyn FnMut(&dyn Any, &mut App) -> bool + 'static>;
 pub(crate) type KeystrokeObserver =
     Box<dyn FnMut(&KeystrokeEvent, &mut Window, &mut App) -> bool + 'static>;
+pub(crate) type ActionObserver =
+    Box<dyn FnMut(&dyn Action, &mut Window, &mut App) -> bool + 'static>;
 type QuitHandler = Box<dyn FnOnce(&mut App) -> LocalBoxFuture<'static, ()> + 'static>;
 type WindowClosedHandler = Box<dyn FnMut(&mut App)>;
 type ReleaseListener = Box<dyn FnOnce(&mut dyn Any, &mut App) + 'static>;
@@ -273,6 +275,7 @@ pub struct App {
     pub(crate) event_listeners: SubscriberSet<EntityId, (TypeId, Listener)>,
     pub(crate) keystroke_observers: SubscriberSet<(), KeystrokeObserver>,
     pub(crate) keystroke_interceptors: SubscriberSet<(), KeystrokeObserver>,
+    pub(crate) action_observers: SubscriberSet<(), ActionObserver>,
     pub(crate) keyboard_layout_observers: SubscriberSet<(), Handler>,
     pub(crate) release_listeners: SubscriberSet<EntityId, ReleaseListener>,
     pub(crate) global_observers: SubscriberSet<TypeId, Handler>,
@@ -346,6 +349,7 @@ impl App {
                 release_listeners: SubscriberSet::new(),
                 keystroke_observers: SubscriberSet::new(),
                 keystroke_interceptors: SubscriberSet::new(),
+                action_observers: SubscriberSet::new(),
                 keyboard_layout_observers: SubscriberSet::new(),
                 global_observers: SubscriberSet::new(),
                 quit_observers: SubscriberSet::new(),
@@ -1327,6 +1331,31 @@ impl App {
         )
     }
 
+    /// Register a callback to be invoked when an action is dispatched in any window, before any
+    /// of its listeners run. Unlike [`App::observe_keystrokes`], this includes actions that weren't
+    /// dispatched by a key binding, such as the ones chosen from menus or the command palette.
+    pub fn observe_actions(
+        &mut self,
+        mut f: impl FnMut(&dyn Action, &mut Window, &mut App) + 'static,
+    ) -> Subscription {
+        fn inner(
+            action_observers: &SubscriberSet<(), ActionObserver>,
+            handler: ActionObserver,
+        ) -> Subscription {
+            let (subscription, activate) = action_observers.insert((), handler);
+            activate();
+            subscription
+        }
+
+        inner(
+            &mut self.action_observers,
+            Box::new(move |action, window, cx| {
+                f(action, window, cx);
+                true
+            }),
+        )
+    }
+
     /// Register a callback to be invoked when a keystroke is received by the application
     /// in any window. Note that this fires _before_ all other action and event mechanisms have resolved
     /// unlike [`App::observe_keystrokes`] which fires after. This means that `cx.stop_propagation` calls
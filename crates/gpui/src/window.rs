@@ -3891,6 +3891,10 @@ impl Window {
     ) {
         let dispatch_path = self.rendered_frame.dispatch_tree.dispatch_path(node_id);
 
+        cx.action_observers
+            .clone()
+            .retain(&(), |callback| (callback)(action, self, cx));
+
         // Capture phase for global actions.
         cx.propagate_event = true;
         if let Some(mut global_listeners) = cx
@@ -47,6 +47,7 @@ pub enum IconName {
     BoltFilled,
     Book,
     BookCopy,
+    Bookmark,
     CaseSensitive,
     Chat,
     Check,
@@ -168,6 +169,7 @@ pub enum IconName {
     PlayFilled,
     Plus,
     Power,
+    PreserveCase,
     Public,
     PullRequest,
     Quote,
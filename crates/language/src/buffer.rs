@@ -100,6 +100,9 @@ pub struct Buffer {
     /// The version vector when this buffer was last loaded from
     /// or saved to disk.
     saved_version: clock::Global,
+    /// The character encoding of the file on disk. The buffer's text is
+    /// always UTF-8, so this is only used when loading and saving.
+    encoding: Encoding,
     preview_version: clock::Global,
     transaction_depth: usize,
     was_dirty_before_starting_transaction: Option<bool>,
@@ -528,6 +531,26 @@ pub enum CharKind {
     Word,
 }
 
+/// A kind of region that isn't code, as classified by a language's highlights query.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum SyntaxRegion {
+    /// A comment, including doc comments.
+    Comment,
+    /// A string literal.
+    String,
+}
+
+impl SyntaxRegion {
+    fn for_capture_name(name: &str) -> Option<Self> {
+        let kind = name.split('.').next()?;
+        match kind {
+            "comment" => Some(Self::Comment),
+            "string" => Some(Self::String),
+            _ => None,
+        }
+    }
+}
+
 /// A runnable is a set of data about a region that could be resolved into a task
 pub struct Runnable {
     pub tags: SmallVec<[RunnableTag; 1]>,
@@ -839,6 +862,7 @@ impl Buffer {
         this.text.set_line_ending(proto::deserialize_line_ending(
             rpc::proto::LineEnding::from_i32(message.line_ending).context("missing line_ending")?,
         ));
+        this.encoding = proto::deserialize_encoding(message.encoding.as_ref());
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
         Ok(this)
@@ -853,6 +877,7 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            encoding: Some(proto::serialize_encoding(self.encoding)),
         }
     }
 
@@ -930,6 +955,7 @@ impl Buffer {
         Self {
             saved_mtime,
             saved_version: buffer.version(),
+            encoding: Encoding::default(),
             preview_version: buffer.version(),
             reload_task: None,
             transaction_depth: 0,
@@ -1226,6 +1252,28 @@ impl Buffer {
         self.saved_mtime
     }
 
+    /// The character encoding that the buffer's file is decoded from and encoded to.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Changes the encoding used for the buffer's file. This doesn't touch the
+    /// buffer's text; call [`Buffer::reload`] to decode the file again.
+    pub fn set_encoding(&mut self, encoding: Encoding, cx: &mut Context<Self>) {
+        if self.encoding != encoding {
+            self.encoding = encoding;
+            cx.notify();
+        }
+    }
+
+    /// Changes the line ending the buffer is saved with.
+    pub fn set_line_ending(&mut self, line_ending: LineEnding, cx: &mut Context<Self>) {
+        if self.line_ending() != line_ending {
+            self.text.set_line_ending(line_ending);
+            cx.notify();
+        }
+    }
+
     /// Assign a language to the buffer.
     pub fn set_language(&mut self, language: Option<Arc<Language>>, cx: &mut Context<Self>) {
         self.non_text_state_update_count += 1;
@@ -1282,16 +1330,20 @@ impl Buffer {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
         self.reload_task = Some(cx.spawn(async move |this, cx| {
-            let Some((new_mtime, new_text)) = this.update(cx, |this, cx| {
+            let Some((new_mtime, new_bytes, encoding)) = this.update(cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
 
-                Some((file.disk_state().mtime(), file.load(cx)))
+                Some((
+                    file.disk_state().mtime(),
+                    file.load_bytes(cx),
+                    this.encoding,
+                ))
             })?
             else {
                 return Ok(());
             };
 
-            let new_text = new_text.await?;
+            let new_text = encoding.decode(&new_bytes.await?);
             let diff = this
                 .update(cx, |this, cx| this.diff(new_text.clone(), cx))?
                 .await;
@@ -3207,6 +3259,45 @@ impl BufferSnapshot {
         (captures, highlight_maps)
     }
 
+    /// Returns the comments and string literals intersecting the given range, in the
+    /// order they start. Nested regions, like an escape sequence within a string, are
+    /// returned alongside the regions containing them.
+    pub fn syntax_regions(&self, range: Range<usize>) -> Vec<(Range<usize>, SyntaxRegion)> {
+        let mut captures = self.syntax.captures(range, &self.text, |grammar| {
+            grammar.highlights_query.as_ref()
+        });
+        let regions_by_capture = captures
+            .grammars()
+            .iter()
+            .map(|grammar| {
+                grammar
+                    .highlights_query
+                    .as_ref()
+                    .map(|query| {
+                        query
+                            .capture_names()
+                            .iter()
+                            .map(|name| SyntaxRegion::for_capture_name(name))
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        let mut regions = Vec::new();
+        while let Some(capture) = captures.peek() {
+            if let Some(region) = regions_by_capture[capture.grammar_index]
+                .get(capture.index as usize)
+                .copied()
+                .flatten()
+            {
+                regions.push((capture.node.byte_range(), region));
+            }
+            captures.advance();
+        }
+        regions
+    }
+
     /// Iterates over chunks of text in the given range of the buffer. Text is chunked
     /// in an arbitrary way due to being stored in a [`Rope`](text::Rope). The text is also
     /// returned in chunks where each chunk has a single syntax highlighting style and
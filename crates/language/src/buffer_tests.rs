@@ -2668,6 +2668,54 @@ fn test_language_at_for_markdown_code_block(cx: &mut App) {
     });
 }
 
+#[gpui::test]
+fn test_syntax_regions(cx: &mut App) {
+    init_settings(cx, |_| {});
+
+    cx.new(|cx| {
+        let language = Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_highlights_query(
+            r#"
+                (line_comment) @comment
+                (string_literal) @string
+                (escape_sequence) @string.escape
+                (identifier) @variable
+            "#,
+        )
+        .unwrap();
+
+        let text = r#"
+            // greet
+            const S: &str = "hi\n";
+        "#
+        .unindent();
+
+        let buffer = Buffer::local(text.clone(), cx).with_language(Arc::new(language), cx);
+        let regions = buffer
+            .snapshot()
+            .syntax_regions(0..text.len())
+            .into_iter()
+            .map(|(range, region)| (text[range].trim_end().to_string(), region))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            regions,
+            &[
+                ("// greet".to_string(), SyntaxRegion::Comment),
+                ("\"hi\\n\"".to_string(), SyntaxRegion::String),
+                ("\\n".to_string(), SyntaxRegion::String),
+            ]
+        );
+
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_serialization(cx: &mut gpui::App) {
     let mut now = Instant::now();
@@ -1,11 +1,14 @@
 //! Provides `language`-related settings.
 
-use crate::{File, Language, LanguageName, LanguageServerName};
+use crate::{Encoding, File, Language, LanguageName, LanguageServerName};
 use anyhow::Result;
 use collections::{FxHashMap, HashMap, HashSet};
 use ec4rs::{
     Properties as EditorconfigProperties,
-    property::{FinalNewline, IndentSize, IndentStyle, TabWidth, TrimTrailingWs},
+    property::{
+        Charset, EndOfLine, FinalNewline, IndentSize, IndentStyle, MaxLineLen, TabWidth,
+        TrimTrailingWs,
+    },
 };
 use globset::{Glob, GlobMatcher, GlobSet, GlobSetBuilder};
 use gpui::{App, Modifiers};
@@ -17,7 +20,8 @@ use serde::{
 };
 
 use settings::{
-    ParameterizedJsonSchema, Settings, SettingsLocation, SettingsSources, SettingsStore,
+    ParameterizedJsonSchema, Settings, SettingsLayer, SettingsLocation, SettingsSources,
+    SettingsStore,
 };
 use shellexpand;
 use std::{borrow::Cow, num::NonZeroU32, path::Path, slice, sync::Arc};
@@ -94,6 +98,8 @@ pub struct LanguageSettings {
     /// Whether or not to ensure there's a single newline at the end of a buffer
     /// when saving it.
     pub ensure_final_newline_on_save: bool,
+    /// Which line ending to use when saving a buffer.
+    pub line_ending: LineEndingSetting,
     /// How to perform a buffer format.
     pub formatter: SelectedFormatter,
     /// Zed's Prettier integration settings.
@@ -465,6 +471,11 @@ pub struct LanguageSettingsContent {
     /// Default: true
     #[serde(default)]
     pub ensure_final_newline_on_save: Option<bool>,
+    /// Which line ending to use when saving a buffer.
+    ///
+    /// Default: detect
+    #[serde(default)]
+    pub line_ending: Option<LineEndingSetting>,
     /// How to perform a buffer format.
     ///
     /// Default: auto
@@ -665,6 +676,19 @@ pub enum SoftWrap {
     Bounded,
 }
 
+/// Controls which line ending a buffer is saved with.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LineEndingSetting {
+    /// Keep the line ending that was detected when the file was loaded.
+    #[default]
+    Detect,
+    /// Save lines ending with `\n`.
+    Lf,
+    /// Save lines ending with `\r\n`.
+    Crlf,
+}
+
 /// Controls the behavior of formatting files when they are saved.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FormatOnSave {
@@ -1125,16 +1149,27 @@ impl AllLanguageSettings {
 }
 
 fn merge_with_editorconfig(settings: &mut LanguageSettings, cfg: &EditorconfigProperties) {
-    let tab_size = cfg.get::<IndentSize>().ok().and_then(|v| match v {
-        IndentSize::Value(u) => NonZeroU32::new(u as u32),
-        IndentSize::UseTabWidth => cfg.get::<TabWidth>().ok().and_then(|w| match w {
-            TabWidth::Value(u) => NonZeroU32::new(u as u32),
-        }),
+    merge_settings(settings, &editorconfig_settings_content(cfg));
+}
+
+/// Translates the EditorConfig properties that resolve for a file into language settings,
+/// leaving the settings that no property covers unset.
+pub fn editorconfig_settings_content(cfg: &EditorconfigProperties) -> LanguageSettingsContent {
+    let tab_width = cfg.get::<TabWidth>().ok().and_then(|w| match w {
+        TabWidth::Value(u) => NonZeroU32::new(u as u32),
     });
+    let tab_size = match cfg.get::<IndentSize>() {
+        Ok(IndentSize::Value(u)) => NonZeroU32::new(u as u32),
+        Ok(IndentSize::UseTabWidth) | Err(_) => tab_width,
+    };
     let hard_tabs = cfg
         .get::<IndentStyle>()
         .map(|v| v.eq(&IndentStyle::Tabs))
         .ok();
+    let preferred_line_length = cfg.get::<MaxLineLen>().ok().and_then(|v| match v {
+        MaxLineLen::Value(u) => u32::try_from(u).ok(),
+        MaxLineLen::Off => None,
+    });
     let ensure_final_newline_on_save = cfg
         .get::<FinalNewline>()
         .map(|v| match v {
@@ -1147,21 +1182,112 @@ fn merge_with_editorconfig(settings: &mut LanguageSettings, cfg: &EditorconfigPr
             TrimTrailingWs::Value(b) => b,
         })
         .ok();
-    fn merge<T>(target: &mut T, value: Option<T>) {
-        if let Some(value) = value {
-            *target = value;
+    // Zed can't save files with lone `\r` line endings, so `end_of_line = cr` is ignored.
+    let line_ending = cfg.get::<EndOfLine>().ok().and_then(|v| match v {
+        EndOfLine::Lf => Some(LineEndingSetting::Lf),
+        EndOfLine::CrLf => Some(LineEndingSetting::Crlf),
+        EndOfLine::Cr => None,
+    });
+    LanguageSettingsContent {
+        tab_size,
+        hard_tabs,
+        preferred_line_length,
+        ensure_final_newline_on_save,
+        remove_trailing_whitespace_on_save,
+        line_ending,
+        ..LanguageSettingsContent::default()
+    }
+}
+
+/// Returns the encoding that the EditorConfig `charset` property asks files to be saved with.
+pub fn editorconfig_encoding(cfg: &EditorconfigProperties) -> Option<Encoding> {
+    let (label, bom) = match cfg.get::<Charset>().ok()? {
+        Charset::Latin1 => ("latin1", false),
+        Charset::Utf8 => ("utf-8", false),
+        Charset::Utf8Bom => ("utf-8", true),
+        Charset::Utf16Le => ("utf-16le", true),
+        Charset::Utf16Be => ("utf-16be", true),
+    };
+    Some(Encoding::from_label(label)?.with_bom(bom))
+}
+
+/// Where the effective value of a language setting was configured.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LanguageSettingSource {
+    /// A top-level setting in a settings file.
+    Settings(SettingsLayer),
+    /// A setting in a settings file's `languages` entry for the file's language.
+    Language(SettingsLayer, LanguageName),
+    /// A property from the `.editorconfig` files that apply to the file.
+    Editorconfig,
+}
+
+impl std::fmt::Display for LanguageSettingSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LanguageSettingSource::Settings(layer) => write!(f, "{layer}"),
+            LanguageSettingSource::Language(layer, language) => {
+                write!(f, "{} language settings in {layer}", language.as_ref())
+            }
+            LanguageSettingSource::Editorconfig => write!(f, "EditorConfig"),
         }
     }
-    merge(&mut settings.tab_size, tab_size);
-    merge(&mut settings.hard_tabs, hard_tabs);
-    merge(
-        &mut settings.remove_trailing_whitespace_on_save,
-        remove_trailing_whitespace_on_save,
-    );
-    merge(
-        &mut settings.ensure_final_newline_on_save,
-        ensure_final_newline_on_save,
-    );
+}
+
+/// The effective value of a language setting for a file, and where it was configured.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EffectiveLanguageSetting {
+    pub key: String,
+    pub value: serde_json::Value,
+    pub source: LanguageSettingSource,
+}
+
+/// Resolves each language setting for a file the same way [`AllLanguageSettings::language`]
+/// does, recording which settings file or `.editorconfig` supplied the winning value.
+pub fn effective_language_settings(
+    language: Option<&LanguageName>,
+    location: Option<SettingsLocation>,
+    cx: &App,
+) -> Vec<EffectiveLanguageSetting> {
+    let store = cx.global::<SettingsStore>();
+    let mut sources = Vec::new();
+    for (layer, value) in store.settings_layers(location, cx) {
+        sources.push((LanguageSettingSource::Settings(layer.clone()), value));
+        if let Some(language) = language {
+            if let Some(language_value) = value
+                .get("languages")
+                .and_then(|languages| languages.get(language.as_ref()))
+            {
+                sources.push((
+                    LanguageSettingSource::Language(layer, language.clone()),
+                    language_value,
+                ));
+            }
+        }
+    }
+    let editorconfig = location
+        .and_then(|location| store.editorconfig_properties(location.worktree_id, location.path))
+        .and_then(|properties| {
+            serde_json::to_value(editorconfig_settings_content(&properties)).ok()
+        });
+    if let Some(editorconfig) = &editorconfig {
+        sources.push((LanguageSettingSource::Editorconfig, editorconfig));
+    }
+
+    let Ok(serde_json::Value::Object(keys)) =
+        serde_json::to_value(LanguageSettingsContent::default())
+    else {
+        return Vec::new();
+    };
+    keys.into_iter()
+        .filter_map(|(key, _)| {
+            let (source, value) = sources.iter().rev().find_map(|(source, value)| {
+                let value = value.get(&key).filter(|value| !value.is_null())?;
+                Some((source.clone(), value.clone()))
+            })?;
+            Some(EffectiveLanguageSetting { key, value, source })
+        })
+        .collect()
 }
 
 /// The kind of an inlay hint.
@@ -1420,6 +1546,12 @@ impl settings::Settings for AllLanguageSettings {
             "files.insertFinalNewline",
             &mut d.ensure_final_newline_on_save,
         );
+        vscode.enum_setting("files.eol", &mut d.line_ending, |s| match s {
+            "\n" => Some(LineEndingSetting::Lf),
+            "\r\n" => Some(LineEndingSetting::Crlf),
+            "auto" => Some(LineEndingSetting::Detect),
+            _ => None,
+        });
         vscode.bool_setting("editor.inlineSuggest.enabled", &mut d.show_edit_predictions);
         vscode.enum_setting("editor.renderWhitespace", &mut d.show_whitespaces, |s| {
             Some(match s {
@@ -1551,6 +1683,7 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.ensure_final_newline_on_save,
         src.ensure_final_newline_on_save,
     );
+    merge(&mut settings.line_ending, src.line_ending);
     merge(
         &mut settings.enable_language_server,
         src.enable_language_server,
@@ -29,6 +29,22 @@ pub fn serialize_line_ending(message: text::LineEnding) -> proto::LineEnding {
     }
 }
 
+/// Deserializes a [`text::Encoding`] from the RPC representation, falling back
+/// to UTF-8 for peers that don't send one.
+pub fn deserialize_encoding(message: Option<&proto::FileEncoding>) -> text::Encoding {
+    message
+        .and_then(|message| Some(text::Encoding::from_label(&message.name)?.with_bom(message.bom)))
+        .unwrap_or_default()
+}
+
+/// Serializes a [`text::Encoding`] to be sent over RPC.
+pub fn serialize_encoding(encoding: text::Encoding) -> proto::FileEncoding {
+    proto::FileEncoding {
+        name: encoding.name().to_string(),
+        bom: encoding.has_bom(),
+    }
+}
+
 /// Serializes a [`crate::Operation`] to be sent over RPC.
 pub fn serialize_operation(operation: &crate::Operation) -> proto::Operation {
     proto::Operation {
@@ -0,0 +1,126 @@
+use std::fmt::Write as _;
+
+use editor::{Editor, MultiBuffer};
+use gpui::{App, AppContext as _, Entity};
+use language::{
+    Buffer,
+    language_settings::{
+        EffectiveLanguageSetting, editorconfig_encoding, effective_language_settings,
+    },
+};
+use settings::{SettingsLocation, SettingsStore};
+use ui::{Context, Window};
+use util::ResultExt as _;
+use workspace::{
+    Toast, Workspace,
+    notifications::{NotificationId, NotifyTaskExt as _},
+};
+use zed_actions::OpenEffectiveSettings;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(open_effective_settings);
+    })
+    .detach();
+}
+
+fn open_effective_settings(
+    workspace: &mut Workspace,
+    _: &OpenEffectiveSettings,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some((_, buffer, _)) = workspace
+        .active_item_as::<Editor>(cx)
+        .and_then(|editor| editor.read(cx).active_excerpt(cx))
+    else {
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<OpenEffectiveSettings>(),
+                "Open a file to see its effective settings",
+            )
+            .autohide(),
+            cx,
+        );
+        return;
+    };
+
+    let (title, report) = effective_settings_report(&buffer, cx);
+    let markdown = workspace
+        .app_state()
+        .languages
+        .language_for_name("Markdown");
+    cx.spawn_in(window, async move |workspace, cx| {
+        let markdown = markdown.await.log_err();
+        workspace.update_in(cx, |workspace, window, cx| {
+            let buffer = cx.new(|cx| {
+                let buffer = Buffer::local(report, cx);
+                match markdown {
+                    Some(markdown) => buffer.with_language(markdown, cx),
+                    None => buffer,
+                }
+            });
+            let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx).with_title(title.clone()));
+            let editor = cx.new(|cx| {
+                let mut editor = Editor::for_multibuffer(buffer, None, window, cx);
+                editor.set_read_only(true);
+                editor.set_breadcrumb_header(title);
+                editor
+            });
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+        })
+    })
+    .detach_and_notify_err(window, cx);
+}
+
+/// Renders the language settings that apply to the buffer as Markdown, with the settings file
+/// or `.editorconfig` that supplied each value.
+fn effective_settings_report(buffer: &Entity<Buffer>, cx: &App) -> (String, String) {
+    let buffer = buffer.read(cx);
+    let language = buffer.language().map(|language| language.name());
+    let file = buffer.file();
+    let location = file.map(|file| SettingsLocation {
+        worktree_id: file.worktree_id(cx),
+        path: file.path().as_ref(),
+    });
+    let file_name = file
+        .map(|file| file.path().to_string_lossy().into_owned())
+        .unwrap_or_else(|| "untitled".to_string());
+
+    let title = format!("Effective Settings: {file_name}");
+    let mut report = format!("# Effective Settings for `{file_name}`\n\n");
+    if let Some(language) = &language {
+        writeln!(report, "Language: {}", language.as_ref()).ok();
+    }
+    let encoding_source = location
+        .and_then(|location| {
+            cx.global::<SettingsStore>()
+                .editorconfig_properties(location.worktree_id, location.path)
+        })
+        .and_then(|properties| editorconfig_encoding(&properties))
+        .map_or(
+            "detected from the file's contents",
+            |_| "requested by .editorconfig unless the file has a byte order mark",
+        );
+    writeln!(
+        report,
+        "\nEncoding: {} ({encoding_source})",
+        buffer.encoding().name()
+    )
+    .ok();
+    report.push_str("\n| Setting | Value | Source |\n| --- | --- | --- |\n");
+    for setting in effective_language_settings(language.as_ref(), location, cx) {
+        write_setting_row(&mut report, &setting);
+    }
+    (title, report)
+}
+
+fn write_setting_row(report: &mut String, setting: &EffectiveLanguageSetting) {
+    let value = setting.value.to_string().replace('|', "\\|");
+    writeln!(
+        report,
+        "| `{}` | `{value}` | {} |",
+        setting.key, setting.source
+    )
+    .ok();
+}
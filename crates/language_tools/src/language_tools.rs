@@ -1,3 +1,4 @@
+mod effective_settings;
 mod key_context_view;
 mod lsp_log;
 pub mod lsp_tool;
@@ -17,6 +18,7 @@ pub fn init(cx: &mut App) {
     lsp_log::init(cx);
     syntax_tree_view::init(cx);
     key_context_view::init(cx);
+    effective_settings::init(cx);
 }
 
 fn get_or_create_tool<T>(
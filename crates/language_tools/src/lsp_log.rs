@@ -1283,6 +1283,7 @@ impl SearchableItem for LspLogView {
             case: true,
             word: true,
             regex: true,
+            syntax: false,
             find_in_results: false,
             // LSP log is read-only.
             replacement: false,
@@ -30,6 +30,7 @@ mod ai_setup_page;
 mod basics_page;
 mod editing_page;
 mod theme_preview;
+mod vscode_keymap_import;
 mod welcome;
 
 pub struct OnBoardingFeatureFlag {}
@@ -56,6 +57,24 @@ pub struct ImportCursorSettings {
     pub skip_prompt: bool,
 }
 
+/// Imports keybindings from Visual Studio Code.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = zed)]
+#[serde(deny_unknown_fields)]
+pub struct ImportVsCodeKeymap {
+    #[serde(default)]
+    pub skip_prompt: bool,
+}
+
+/// Imports keybindings from Cursor editor.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = zed)]
+#[serde(deny_unknown_fields)]
+pub struct ImportCursorKeymap {
+    #[serde(default)]
+    pub skip_prompt: bool,
+}
+
 pub const FIRST_OPEN: &str = "first_open";
 
 actions!(
@@ -179,6 +198,46 @@ pub fn init(cx: &mut App) {
                 })
                 .detach();
         });
+
+        workspace.register_action(|_workspace, action: &ImportVsCodeKeymap, window, cx| {
+            let fs = <dyn Fs>::global(cx);
+            let action = *action;
+
+            let workspace = cx.weak_entity();
+
+            window
+                .spawn(cx, async move |cx: &mut AsyncWindowContext| {
+                    vscode_keymap_import::handle_import_vscode_keymap(
+                        workspace,
+                        VsCodeSettingsSource::VsCode,
+                        action.skip_prompt,
+                        fs,
+                        cx,
+                    )
+                    .await
+                })
+                .detach();
+        });
+
+        workspace.register_action(|_workspace, action: &ImportCursorKeymap, window, cx| {
+            let fs = <dyn Fs>::global(cx);
+            let action = *action;
+
+            let workspace = cx.weak_entity();
+
+            window
+                .spawn(cx, async move |cx: &mut AsyncWindowContext| {
+                    vscode_keymap_import::handle_import_vscode_keymap(
+                        workspace,
+                        VsCodeSettingsSource::Cursor,
+                        action.skip_prompt,
+                        fs,
+                        cx,
+                    )
+                    .await
+                })
+                .detach();
+        });
     })
     .detach();
 
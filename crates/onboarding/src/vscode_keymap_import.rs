@@ -0,0 +1,259 @@
+use std::{fmt::Write as _, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use editor::{Editor, MultiBuffer};
+use fs::Fs;
+use gpui::{Action as _, AppContext as _, AsyncWindowContext, WeakEntity};
+use notifications::status_toast::{StatusToast, ToastIcon};
+use serde_json::json;
+use settings::{
+    KeymapFile, KeymapFileLoadResult, SettingsStore, VsCodeKeybindings, VsCodeKeymapImport,
+    VsCodeSettings, VsCodeSettingsSource, append_top_level_array_value_in_json_text,
+};
+use ui::prelude::*;
+use util::ResultExt as _;
+use workspace::Workspace;
+
+/// Imports the keybindings from the VS Code or Cursor `keybindings.json` into the user keymap,
+/// then opens a report of the bindings and settings that couldn't be migrated.
+pub async fn handle_import_vscode_keymap(
+    workspace: WeakEntity<Workspace>,
+    source: VsCodeSettingsSource,
+    skip_prompt: bool,
+    fs: Arc<dyn Fs>,
+    cx: &mut AsyncWindowContext,
+) {
+    use util::truncate_and_remove_front;
+
+    let keybindings = match VsCodeKeybindings::load_user_keybindings(source, fs.clone()).await {
+        Ok(keybindings) => keybindings,
+        Err(err) => {
+            zlog::error!("{err}");
+            let _ = cx.prompt(
+                gpui::PromptLevel::Info,
+                &format!("Could not find or load a {source} keybindings file"),
+                None,
+                &["Ok"],
+            );
+            return;
+        }
+    };
+    let mut import = keybindings.translate();
+    let already_bound = KeymapFile::load_keymap_file(&fs)
+        .await
+        .ok()
+        .and_then(|contents| KeymapFile::parse(&contents).ok())
+        .map_or(0, |keymap| import.remove_existing_bindings(&keymap));
+    let Ok(validation_errors) = cx.update(|_, cx| remove_invalid_bindings(&mut import, cx)) else {
+        return;
+    };
+
+    if !skip_prompt {
+        let prompt = cx.prompt(
+            gpui::PromptLevel::Warning,
+            &format!(
+                "Importing {} keybindings will add {} bindings to your keymap. \
+                Will import keybindings from {}",
+                source,
+                import.imported_count(),
+                truncate_and_remove_front(&keybindings.path.to_string_lossy(), 128),
+            ),
+            None,
+            &["Ok", "Cancel"],
+        );
+        let result = cx.spawn(async move |_| prompt.await.ok()).await;
+        if result != Some(0) {
+            return;
+        }
+    }
+
+    let unimported_settings = match VsCodeSettings::load_user_settings(source, fs.clone()).await {
+        Ok(vscode_settings) => cx
+            .update(|_, cx| {
+                cx.global::<SettingsStore>()
+                    .unimported_vscode_settings(&vscode_settings)
+            })
+            .unwrap_or_default(),
+        Err(_) => Vec::new(),
+    };
+
+    let tab_size = cx
+        .update(|_, cx| cx.global::<SettingsStore>().json_tab_size())
+        .unwrap_or(2);
+    let result = append_to_keymap(&import, tab_size, &fs).await;
+    if let Err(err) = &result {
+        zlog::error!("Failed to import {source} keybindings: {err:?}");
+    }
+    zlog::info!(
+        "Imported {} {source} keybindings from {}",
+        import.imported_count(),
+        keybindings.path.display()
+    );
+
+    let report = migration_report(
+        source,
+        &import,
+        already_bound,
+        validation_errors.as_deref(),
+        &unimported_settings,
+    );
+    workspace
+        .update_in(cx, |workspace, window, cx| {
+            let toast = match result {
+                Ok(()) => StatusToast::new(
+                    format!(
+                        "Imported {} of your {source} keybindings.",
+                        import.imported_count()
+                    ),
+                    cx,
+                    |this, _| {
+                        this.icon(ToastIcon::new(IconName::Check).color(Color::Success))
+                            .dismiss_button(true)
+                    },
+                ),
+                Err(_) => StatusToast::new(
+                    "Failed to import keybindings. See log for details",
+                    cx,
+                    |this, _| {
+                        this.icon(ToastIcon::new(IconName::Close).color(Color::Error))
+                            .action("Open Log", |window, cx| {
+                                window.dispatch_action(workspace::OpenLog.boxed_clone(), cx)
+                            })
+                            .dismiss_button(true)
+                    },
+                ),
+            };
+            workspace.toggle_status_toast(toast, cx);
+            open_migration_report(workspace, report, source, window, cx);
+        })
+        .ok();
+}
+
+/// Loads each translated binding like the user keymap would, dropping the ones that fail so they
+/// aren't written to the keymap. Returns the errors the keymap loader reports.
+fn remove_invalid_bindings(import: &mut VsCodeKeymapImport, cx: &App) -> Option<String> {
+    let mut errors = Vec::new();
+    for (context, bindings) in &mut import.sections {
+        bindings.retain(|keystrokes, action| {
+            let section = json!([{ "context": context, "bindings": { keystrokes: action } }]);
+            match KeymapFile::load(&section.to_string(), cx) {
+                KeymapFileLoadResult::Success { .. } => true,
+                KeymapFileLoadResult::SomeFailedToLoad { error_message, .. } => {
+                    errors.push(error_message.0);
+                    false
+                }
+                KeymapFileLoadResult::JsonParseFailure { error } => {
+                    errors.push(error.to_string());
+                    false
+                }
+            }
+        });
+    }
+    import.sections.retain(|_, bindings| !bindings.is_empty());
+    (!errors.is_empty()).then(|| errors.join("\n\n"))
+}
+
+async fn append_to_keymap(
+    import: &VsCodeKeymapImport,
+    tab_size: usize,
+    fs: &Arc<dyn Fs>,
+) -> Result<()> {
+    let mut keymap_contents = KeymapFile::load_keymap_file(fs)
+        .await
+        .context("Failed to load keymap file")?;
+    if keymap_contents.trim().is_empty() {
+        keymap_contents = "[]".to_string();
+    }
+    for section in import.keymap_sections() {
+        let (range, replacement) =
+            append_top_level_array_value_in_json_text(&keymap_contents, &section, tab_size)?;
+        keymap_contents.replace_range(range, &replacement);
+    }
+    fs.write(paths::keymap_file().as_path(), keymap_contents.as_bytes())
+        .await
+        .context("Failed to write keymap file")
+}
+
+fn migration_report(
+    source: VsCodeSettingsSource,
+    import: &VsCodeKeymapImport,
+    already_bound: usize,
+    validation_errors: Option<&str>,
+    unimported_settings: &[String],
+) -> String {
+    let mut report = format!("# {source} Migration Report\n\n");
+    write!(
+        report,
+        "Imported {} keybindings into your keymap. {} keybindings couldn't be translated.",
+        import.imported_count(),
+        import.skipped.len()
+    )
+    .ok();
+    if already_bound > 0 {
+        write!(
+            report,
+            " {already_bound} keybindings were already in your keymap."
+        )
+        .ok();
+    }
+    report.push_str("\n\n");
+
+    if !import.skipped.is_empty() {
+        report.push_str("## Keybindings that weren't imported\n\n");
+        for skipped in &import.skipped {
+            write!(report, "- `{}` `{}`", skipped.key, skipped.command).ok();
+            if let Some(when) = &skipped.when {
+                write!(report, " when `{when}`").ok();
+            }
+            writeln!(report, ": {}", skipped.reason).ok();
+        }
+        report.push('\n');
+    }
+
+    if let Some(validation_errors) = validation_errors {
+        report.push_str("## Keybindings that failed to load\n\n");
+        report.push_str(validation_errors.trim());
+        report.push_str("\n\n");
+    }
+
+    if !unimported_settings.is_empty() {
+        report.push_str("## Settings that weren't imported\n\n");
+        for setting in unimported_settings {
+            writeln!(report, "- `{setting}`").ok();
+        }
+    }
+
+    report
+}
+
+fn open_migration_report(
+    workspace: &mut Workspace,
+    report: String,
+    source: VsCodeSettingsSource,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let title = format!("{source} Migration Report");
+    let markdown = workspace
+        .app_state()
+        .languages
+        .language_for_name("Markdown");
+    cx.spawn_in(window, async move |workspace, cx| {
+        let markdown = markdown.await.log_err();
+        workspace.update_in(cx, |workspace, window, cx| {
+            let project = workspace.project().clone();
+            let buffer = project.update(cx, |project, cx| {
+                project.create_local_buffer(&report, markdown, cx)
+            });
+            let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx).with_title(title.clone()));
+            let editor = cx.new(|cx| {
+                let mut editor = Editor::for_multibuffer(buffer, Some(project), window, cx);
+                editor.set_read_only(true);
+                editor.set_breadcrumb_header(title);
+                editor
+            });
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
@@ -219,6 +219,12 @@ pub fn keymap_file() -> &'static PathBuf {
     KEYMAP_FILE.get_or_init(|| config_dir().join("keymap.json"))
 }
 
+/// Returns the path to the `vimrc` file.
+pub fn vimrc_file() -> &'static PathBuf {
+    static VIMRC_FILE: OnceLock<PathBuf> = OnceLock::new();
+    VIMRC_FILE.get_or_init(|| config_dir().join("vimrc"))
+}
+
 /// Returns the path to the `keymap_backup.json` file.
 pub fn keymap_backup_file() -> &'static PathBuf {
     static KEYMAP_FILE: OnceLock<PathBuf> = OnceLock::new();
@@ -368,6 +374,30 @@ pub fn agent_servers_dir() -> &'static PathBuf {
     AGENT_SERVERS_DIR.get_or_init(|| data_dir().join("agent_servers"))
 }
 
+/// Returns the path to the file history directory.
+///
+/// This is where snapshots of files taken on open, save and external change are stored.
+pub fn file_history_dir() -> &'static PathBuf {
+    static FILE_HISTORY_DIR: OnceLock<PathBuf> = OnceLock::new();
+    FILE_HISTORY_DIR.get_or_init(|| data_dir().join("file_history"))
+}
+
+/// Returns the path to the dev containers directory.
+///
+/// This is where files that Zed generates for dev containers, such as compose overrides, are stored.
+pub fn dev_containers_dir() -> &'static PathBuf {
+    static DEV_CONTAINERS_DIR: OnceLock<PathBuf> = OnceLock::new();
+    DEV_CONTAINERS_DIR.get_or_init(|| data_dir().join("dev_containers"))
+}
+
+/// Returns the path to the search index directory.
+///
+/// This is where the trigram indices used to speed up project search are stored.
+pub fn search_index_dir() -> &'static PathBuf {
+    static SEARCH_INDEX_DIR: OnceLock<PathBuf> = OnceLock::new();
+    SEARCH_INDEX_DIR.get_or_init(|| data_dir().join("search_index"))
+}
+
 /// Returns the path to the Copilot directory.
 pub fn copilot_dir() -> &'static PathBuf {
     static COPILOT_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -462,6 +492,24 @@ pub fn cursor_settings_file_paths() -> Vec<PathBuf> {
     paths
 }
 
+/// Returns candidate paths for the vscode user keybindings file
+pub fn vscode_keybindings_file_paths() -> Vec<PathBuf> {
+    let mut paths = vscode_user_data_paths();
+    for path in paths.iter_mut() {
+        path.push("User/keybindings.json");
+    }
+    paths
+}
+
+/// Returns candidate paths for the cursor user keybindings file
+pub fn cursor_keybindings_file_paths() -> Vec<PathBuf> {
+    let mut paths = cursor_user_data_paths();
+    for path in paths.iter_mut() {
+        path.push("User/keybindings.json");
+    }
+    paths
+}
+
 fn vscode_user_data_paths() -> Vec<PathBuf> {
     // https://github.com/microsoft/vscode/blob/23e7148cdb6d8a27f0109ff77e5b1e019f8da051/src/vs/platform/environment/node/userDataPath.ts#L45
     const VSCODE_PRODUCT_NAMES: &[&str] = &[
@@ -0,0 +1,534 @@
+//! Bookmarks are user-placed markers on lines of a file. They're anchored to the
+//! buffer, so they follow the text they were placed on as the file is edited.
+use anyhow::{Context as _, Result};
+use collections::BTreeMap;
+use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task};
+use language::{
+    Buffer, BufferEvent, DiskState,
+    proto::{deserialize_anchor, serialize_anchor},
+};
+use rpc::{AnyProtoClient, TypedEnvelope, proto};
+use std::{mem, ops::Range, path::Path, sync::Arc};
+use text::{Point, ToPoint as _};
+use util::ResultExt as _;
+
+use crate::{
+    Project, ProjectPath,
+    buffer_store::BufferStore,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bookmark {
+    pub position: text::Anchor,
+    pub label: Option<Arc<str>>,
+}
+
+impl Bookmark {
+    fn to_proto(&self) -> proto::Bookmark {
+        proto::Bookmark {
+            position: Some(serialize_anchor(&self.position)),
+            label: self.label.as_ref().map(|label| label.to_string()),
+        }
+    }
+
+    fn from_proto(bookmark: proto::Bookmark) -> Option<Self> {
+        Some(Self {
+            position: deserialize_anchor(bookmark.position?)?,
+            label: bookmark.label.map(Arc::from),
+        })
+    }
+}
+
+/// A bookmark as stored in the workspace database, where anchors can't be kept.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerializedBookmark {
+    pub row: u32,
+    pub label: Option<Arc<str>>,
+}
+
+/// A bookmark along with the file it's in and its current row, as shown in
+/// bookmark lists.
+#[derive(Clone, Debug)]
+pub struct BookmarkEntry {
+    pub path: Arc<Path>,
+    pub buffer: Entity<Buffer>,
+    pub row: u32,
+    pub bookmark: Bookmark,
+}
+
+pub enum BookmarkEditAction {
+    /// Removes the bookmark on the given row, or adds one if there isn't any.
+    Toggle,
+    /// Sets the label of the bookmark on the given row, adding one if needed.
+    SetLabel(Option<Arc<str>>),
+}
+
+pub enum BookmarkStoreEvent {
+    BookmarksUpdated(Arc<Path>),
+    BookmarksCleared,
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
+
+struct BookmarksInFile {
+    buffer: Entity<Buffer>,
+    /// Sorted by position.
+    bookmarks: Vec<Bookmark>,
+    _subscription: Subscription,
+}
+
+impl BookmarksInFile {
+    fn new(buffer: Entity<Buffer>, cx: &mut Context<BookmarkStore>) -> Self {
+        let subscription = cx.subscribe(&buffer, |this, buffer, event, cx| match event {
+            BufferEvent::FileHandleChanged => this.buffer_file_changed(buffer, cx),
+            // Rows may have moved since the bookmarks were last persisted.
+            BufferEvent::Saved => {
+                if let Some(path) = BookmarkStore::abs_path_from_buffer(&buffer, cx) {
+                    cx.emit(BookmarkStoreEvent::BookmarksUpdated(path));
+                }
+            }
+            _ => {}
+        });
+        Self {
+            buffer,
+            bookmarks: Vec::new(),
+            _subscription: subscription,
+        }
+    }
+
+    fn sort(&mut self, cx: &App) {
+        let snapshot = self.buffer.read(cx).text_snapshot();
+        self.bookmarks
+            .sort_by(|a, b| a.position.cmp(&b.position, &snapshot));
+    }
+}
+
+#[derive(Clone)]
+enum BookmarkStoreMode {
+    Local {
+        worktree_store: Entity<WorktreeStore>,
+        buffer_store: Entity<BufferStore>,
+    },
+    Remote {
+        upstream_client: AnyProtoClient,
+        upstream_project_id: u64,
+    },
+}
+
+pub struct BookmarkStore {
+    bookmarks: BTreeMap<Arc<Path>, BookmarksInFile>,
+    /// Persisted bookmarks in files that aren't in any worktree of the project yet.
+    pending_bookmarks: BTreeMap<Arc<Path>, Vec<SerializedBookmark>>,
+    downstream_client: Option<(AnyProtoClient, u64)>,
+    mode: BookmarkStoreMode,
+    _subscription: Option<Subscription>,
+}
+
+impl BookmarkStore {
+    pub fn init(client: &AnyProtoClient) {
+        client.add_entity_request_handler(Self::handle_toggle_bookmark);
+        client.add_entity_message_handler(Self::handle_bookmarks_for_file);
+    }
+
+    pub fn local(
+        worktree_store: Entity<WorktreeStore>,
+        buffer_store: Entity<BufferStore>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&worktree_store, |this, _, event, cx| {
+            if let WorktreeStoreEvent::WorktreeAdded(_) = event {
+                this.restore_pending_bookmarks(cx).detach_and_log_err(cx);
+            }
+        });
+        Self {
+            bookmarks: BTreeMap::default(),
+            pending_bookmarks: BTreeMap::default(),
+            downstream_client: None,
+            mode: BookmarkStoreMode::Local {
+                worktree_store,
+                buffer_store,
+            },
+            _subscription: Some(subscription),
+        }
+    }
+
+    pub(crate) fn remote(upstream_project_id: u64, upstream_client: AnyProtoClient) -> Self {
+        Self {
+            bookmarks: BTreeMap::default(),
+            pending_bookmarks: BTreeMap::default(),
+            downstream_client: None,
+            mode: BookmarkStoreMode::Remote {
+                upstream_client,
+                upstream_project_id,
+            },
+            _subscription: None,
+        }
+    }
+
+    pub(crate) fn shared(&mut self, project_id: u64, downstream_client: AnyProtoClient) {
+        self.downstream_client = Some((downstream_client, project_id));
+        self.broadcast();
+    }
+
+    pub(crate) fn unshared(&mut self, cx: &mut Context<Self>) {
+        self.downstream_client.take();
+        cx.notify();
+    }
+
+    pub(crate) fn broadcast(&self) {
+        for path in self.bookmarks.keys() {
+            self.send_bookmarks_for_file(path);
+        }
+    }
+
+    fn send_bookmarks_for_file(&self, path: &Arc<Path>) {
+        let Some((client, project_id)) = &self.downstream_client else {
+            return;
+        };
+        let bookmarks = self
+            .bookmarks
+            .get(path)
+            .map(|bookmarks| bookmarks.bookmarks.iter().map(Bookmark::to_proto).collect())
+            .unwrap_or_default();
+        client
+            .send(proto::BookmarksForFile {
+                project_id: *project_id,
+                path: path.to_string_lossy().to_string(),
+                bookmarks,
+            })
+            .log_err();
+    }
+
+    async fn handle_bookmarks_for_file(
+        this: Entity<Project>,
+        message: TypedEnvelope<proto::BookmarksForFile>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        let bookmark_store = this.read_with(&cx, |this, _| this.bookmark_store())?;
+        let path = Arc::<Path>::from(Path::new(&message.payload.path));
+        if message.payload.bookmarks.is_empty() {
+            return bookmark_store.update(&mut cx, |this, cx| {
+                if this.bookmarks.remove(&path).is_some() {
+                    cx.emit(BookmarkStoreEvent::BookmarksUpdated(path));
+                    cx.notify();
+                }
+            });
+        }
+
+        let buffer = this
+            .update(&mut cx, |this, cx| {
+                let project_path = this.project_path_for_absolute_path(&path, cx)?;
+                Some(this.open_buffer(project_path, cx))
+            })?
+            .context("invalid project path for bookmarks")?
+            .await?;
+
+        bookmark_store.update(&mut cx, |this, cx| {
+            let bookmarks_in_file = this
+                .bookmarks
+                .entry(path.clone())
+                .or_insert_with(|| BookmarksInFile::new(buffer, cx));
+            bookmarks_in_file.bookmarks = message
+                .payload
+                .bookmarks
+                .into_iter()
+                .filter_map(Bookmark::from_proto)
+                .collect();
+            bookmarks_in_file.sort(cx);
+            cx.emit(BookmarkStoreEvent::BookmarksUpdated(path));
+            cx.notify();
+        })
+    }
+
+    async fn handle_toggle_bookmark(
+        this: Entity<Project>,
+        message: TypedEnvelope<proto::ToggleBookmark>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let bookmark_store = this.read_with(&cx, |this, _| this.bookmark_store())?;
+        let buffer = this
+            .update(&mut cx, |this, cx| {
+                let path =
+                    this.project_path_for_absolute_path(Path::new(&message.payload.path), cx)?;
+                this.buffer_store().read(cx).get_by_path(&path)
+            })?
+            .context("could not find buffer for bookmark")?;
+        let bookmark = message
+            .payload
+            .bookmark
+            .and_then(Bookmark::from_proto)
+            .context("invalid bookmark in request")?;
+        let action = if message.payload.set_label {
+            BookmarkEditAction::SetLabel(bookmark.label)
+        } else {
+            BookmarkEditAction::Toggle
+        };
+
+        bookmark_store.update(&mut cx, |this, cx| {
+            this.edit_bookmark(buffer, bookmark.position, action, cx);
+        })?;
+        Ok(proto::Ack {})
+    }
+
+    pub fn abs_path_from_buffer(buffer: &Entity<Buffer>, cx: &App) -> Option<Arc<Path>> {
+        worktree::File::from_dyn(buffer.read(cx).file())
+            .and_then(|file| file.worktree.read(cx).absolutize(&file.path).ok())
+            .map(Arc::<Path>::from)
+    }
+
+    /// Adds, removes or relabels the bookmark on the row of `position`.
+    pub fn edit_bookmark(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        action: BookmarkEditAction,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(abs_path) = Self::abs_path_from_buffer(&buffer, cx) else {
+            return;
+        };
+
+        if let BookmarkStoreMode::Remote {
+            upstream_client,
+            upstream_project_id,
+        } = &self.mode
+        {
+            let (label, set_label) = match action {
+                BookmarkEditAction::Toggle => (None, false),
+                BookmarkEditAction::SetLabel(label) => (label, true),
+            };
+            let request = upstream_client.request(proto::ToggleBookmark {
+                project_id: *upstream_project_id,
+                path: abs_path.to_string_lossy().to_string(),
+                bookmark: Some(Bookmark { position, label }.to_proto()),
+                set_label,
+            });
+            cx.background_spawn(async move { request.await.map(|_| ()) })
+                .detach_and_log_err(cx);
+            return;
+        }
+
+        let snapshot = buffer.read(cx).text_snapshot();
+        let row = position.to_point(&snapshot).row;
+        let bookmarks_in_file = self
+            .bookmarks
+            .entry(abs_path.clone())
+            .or_insert_with(|| BookmarksInFile::new(buffer, cx));
+        let existing_ix = bookmarks_in_file
+            .bookmarks
+            .iter()
+            .position(|bookmark| bookmark.position.to_point(&snapshot).row == row);
+
+        match (action, existing_ix) {
+            (BookmarkEditAction::Toggle, Some(ix)) => {
+                bookmarks_in_file.bookmarks.remove(ix);
+            }
+            (BookmarkEditAction::Toggle, None) => {
+                bookmarks_in_file.bookmarks.push(Bookmark {
+                    position: snapshot.anchor_before(Point::new(row, 0)),
+                    label: None,
+                });
+            }
+            (BookmarkEditAction::SetLabel(label), Some(ix)) => {
+                bookmarks_in_file.bookmarks[ix].label = label;
+            }
+            (BookmarkEditAction::SetLabel(label), None) => {
+                bookmarks_in_file.bookmarks.push(Bookmark {
+                    position: snapshot.anchor_before(Point::new(row, 0)),
+                    label,
+                });
+            }
+        }
+        bookmarks_in_file.sort(cx);
+
+        if bookmarks_in_file.bookmarks.is_empty() {
+            self.bookmarks.remove(&abs_path);
+        }
+        self.send_bookmarks_for_file(&abs_path);
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated(abs_path));
+        cx.notify();
+    }
+
+    pub fn clear_bookmarks(&mut self, cx: &mut Context<Self>) {
+        let paths = self.bookmarks.keys().cloned().collect::<Vec<_>>();
+        self.bookmarks.clear();
+        for path in &paths {
+            self.send_bookmarks_for_file(path);
+        }
+        cx.emit(BookmarkStoreEvent::BookmarksCleared);
+        cx.notify();
+    }
+
+    fn buffer_file_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let entity_id = buffer.entity_id();
+        let Some(old_path) = self
+            .bookmarks
+            .iter()
+            .find(|(_, bookmarks)| bookmarks.buffer.entity_id() == entity_id)
+            .map(|(path, _)| path.clone())
+        else {
+            return;
+        };
+
+        let deleted = buffer
+            .read(cx)
+            .file()
+            .is_none_or(|file| file.disk_state() == DiskState::Deleted);
+        let new_path = Self::abs_path_from_buffer(&buffer, cx);
+        if !deleted && new_path.as_ref() == Some(&old_path) {
+            return;
+        }
+
+        let bookmarks = self.bookmarks.remove(&old_path);
+        if let Some((bookmarks, new_path)) = bookmarks.zip(new_path).filter(|_| !deleted) {
+            self.bookmarks.insert(new_path.clone(), bookmarks);
+            self.send_bookmarks_for_file(&new_path);
+            cx.emit(BookmarkStoreEvent::BookmarksUpdated(new_path));
+        }
+        self.send_bookmarks_for_file(&old_path);
+        cx.emit(BookmarkStoreEvent::BookmarksUpdated(old_path));
+        cx.notify();
+    }
+
+    /// Returns the bookmarks in the given range of the buffer, in order.
+    pub fn bookmarks(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Option<Range<text::Anchor>>,
+        snapshot: &text::BufferSnapshot,
+        cx: &App,
+    ) -> Vec<Bookmark> {
+        let Some(bookmarks) =
+            Self::abs_path_from_buffer(buffer, cx).and_then(|path| self.bookmarks.get(&path))
+        else {
+            return Vec::new();
+        };
+        bookmarks
+            .bookmarks
+            .iter()
+            .filter(|bookmark| {
+                range.as_ref().is_none_or(|range| {
+                    bookmark.position.cmp(&range.start, snapshot).is_ge()
+                        && bookmark.position.cmp(&range.end, snapshot).is_le()
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns every bookmark in the project, ordered by path and then row.
+    pub fn all_bookmarks(&self, cx: &App) -> Vec<BookmarkEntry> {
+        let mut entries = Vec::new();
+        for (path, bookmarks) in &self.bookmarks {
+            let snapshot = bookmarks.buffer.read(cx).text_snapshot();
+            entries.extend(bookmarks.bookmarks.iter().map(|bookmark| BookmarkEntry {
+                path: path.clone(),
+                buffer: bookmarks.buffer.clone(),
+                row: bookmark.position.to_point(&snapshot).row,
+                bookmark: bookmark.clone(),
+            }));
+        }
+        entries
+    }
+
+    /// Returns every bookmark in a form that can be persisted, including those
+    /// that haven't been restored yet.
+    pub fn all_serialized_bookmarks(
+        &self,
+        cx: &App,
+    ) -> BTreeMap<Arc<Path>, Vec<SerializedBookmark>> {
+        let mut serialized = self.pending_bookmarks.clone();
+        serialized.extend(self.bookmarks.iter().map(|(path, bookmarks)| {
+            let snapshot = bookmarks.buffer.read(cx).text_snapshot();
+            let serialized = bookmarks
+                .bookmarks
+                .iter()
+                .map(|bookmark| SerializedBookmark {
+                    row: bookmark.position.to_point(&snapshot).row,
+                    label: bookmark.label.clone(),
+                })
+                .collect();
+            (path.clone(), serialized)
+        }));
+        serialized
+    }
+
+    /// Restores bookmarks loaded from the workspace database. Bookmarks in files
+    /// outside of the project's worktrees are kept until a worktree containing
+    /// them is added.
+    pub fn with_serialized_bookmarks(
+        &mut self,
+        bookmarks: BTreeMap<Arc<Path>, Vec<SerializedBookmark>>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if let BookmarkStoreMode::Remote { .. } = self.mode {
+            return Task::ready(Ok(()));
+        }
+        self.pending_bookmarks.extend(
+            bookmarks
+                .into_iter()
+                .filter(|(_, bookmarks)| !bookmarks.is_empty()),
+        );
+        self.restore_pending_bookmarks(cx)
+    }
+
+    fn restore_pending_bookmarks(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let BookmarkStoreMode::Local {
+            worktree_store,
+            buffer_store,
+        } = &self.mode
+        else {
+            return Task::ready(Ok(()));
+        };
+
+        let mut buffers_to_open = Vec::new();
+        self.pending_bookmarks.retain(|path, bookmarks| {
+            let Some((worktree, relative_path)) = worktree_store.read(cx).find_worktree(path, cx)
+            else {
+                return true;
+            };
+            let project_path = ProjectPath {
+                worktree_id: worktree.read(cx).id(),
+                path: relative_path.into(),
+            };
+            let buffer = buffer_store.update(cx, |buffer_store, cx| {
+                buffer_store.open_buffer(project_path, cx)
+            });
+            buffers_to_open.push((path.clone(), mem::take(bookmarks), buffer));
+            false
+        });
+
+        cx.spawn(async move |this, cx| {
+            for (path, serialized, buffer) in buffers_to_open {
+                let Ok(buffer) = buffer.await else {
+                    log::error!("failed to open {path:?} to restore its bookmarks");
+                    continue;
+                };
+
+                this.update(cx, |this, cx| {
+                    let snapshot = buffer.read(cx).text_snapshot();
+                    let max_row = snapshot.max_point().row;
+                    let bookmarks_in_file = this
+                        .bookmarks
+                        .entry(path.clone())
+                        .or_insert_with(|| BookmarksInFile::new(buffer, cx));
+                    bookmarks_in_file.bookmarks.extend(
+                        serialized
+                            .into_iter()
+                            .filter(|bookmark| bookmark.row <= max_row)
+                            .map(|bookmark| Bookmark {
+                                position: snapshot.anchor_before(Point::new(bookmark.row, 0)),
+                                label: bookmark.label,
+                            }),
+                    );
+                    bookmarks_in_file.sort(cx);
+                    this.send_bookmarks_for_file(&path);
+                    cx.emit(BookmarkStoreEvent::BookmarksUpdated(path));
+                    cx.notify();
+                })?;
+            }
+            Ok(())
+        })
+    }
+}
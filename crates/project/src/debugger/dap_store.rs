@@ -256,10 +256,16 @@ impl DapStore {
                     let binary = DebugAdapterBinary::from_proto(response)?;
                     let (mut ssh_command, envs, path_style) =
                         ssh_client.read_with(cx, |ssh, _| {
-                            let (SshArgs { arguments, envs }, path_style) =
-                                ssh.ssh_info().context("SSH arguments not found")?;
+                            let (
+                                SshArgs {
+                                    arguments,
+                                    envs,
+                                    command,
+                                },
+                                path_style,
+                            ) = ssh.ssh_info().context("SSH arguments not found")?;
                             anyhow::Ok((
-                                SshCommand { arguments },
+                                SshCommand { arguments, command },
                                 envs.unwrap_or_default(),
                                 path_style,
                             ))
@@ -267,6 +273,10 @@ impl DapStore {
 
                     let mut connection = None;
                     if let Some(c) = binary.connection {
+                        anyhow::ensure!(
+                            ssh_command.command.is_none(),
+                            "Debug adapters that connect over TCP aren't supported when connecting with a command"
+                        );
                         let local_bind_addr = Ipv4Addr::LOCALHOST;
                         let port =
                             dap::transport::TcpTransport::unused_port(local_bind_addr).await?;
@@ -22,6 +22,7 @@ use futures::{
 use git::{
     BuildPermalinkParams, GitHostingProviderRegistry, WORK_DIRECTORY_REPO_PATH,
     blame::Blame,
+    history_search::{HistoryHunk, HistorySearchMatch, HistorySearchMode, HistorySearchQuery},
     parse_git_remote_url,
     repository::{
         Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
@@ -427,6 +428,7 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_commit);
         client.add_entity_request_handler(Self::handle_reset);
         client.add_entity_request_handler(Self::handle_show);
+        client.add_entity_request_handler(Self::handle_search_history);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
         client.add_entity_request_handler(Self::handle_checkout_files);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
@@ -807,6 +809,30 @@ impl GitStore {
         conflict_set
     }
 
+    /// Opens the conflict set for a buffer that holds the result of a merge
+    /// outside of git, such as one started by `jj resolve`, so that its
+    /// conflict markers are shown regardless of the file's git status.
+    pub fn open_merge_conflict_set(
+        &mut self,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Entity<ConflictSet> {
+        let conflict_set = self.open_conflict_set(buffer.clone(), cx);
+        let has_conflict_changed = conflict_set.update(cx, |conflict_set, cx| {
+            conflict_set.is_merge_result = true;
+            conflict_set.set_has_conflict(true, cx)
+        });
+        if has_conflict_changed
+            && let Some(git_state) = self.diffs.get(&buffer.read(cx).remote_id())
+        {
+            let buffer_snapshot = buffer.read(cx).text_snapshot();
+            git_state.update(cx, |state, cx| {
+                let _ = state.reparse_conflict_markers(buffer_snapshot, cx);
+            });
+        }
+        conflict_set
+    }
+
     pub fn project_path_git_status(
         &self,
         project_path: &ProjectPath,
@@ -1156,7 +1182,8 @@ impl GitStore {
                         if let Some(conflict_set) = &diff.conflict_set {
                             let conflict_status_changed =
                                 conflict_set.update(cx, |conflict_set, cx| {
-                                    let has_conflict = repo_snapshot.has_conflict(&repo_path);
+                                    let has_conflict = conflict_set.is_merge_result
+                                        || repo_snapshot.has_conflict(&repo_path);
                                     conflict_set.set_has_conflict(has_conflict, cx)
                                 })?;
                             if conflict_status_changed {
@@ -1952,6 +1979,25 @@ impl GitStore {
         })
     }
 
+    async fn handle_search_history(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitSearchHistory>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitSearchHistoryResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let query = proto_to_history_search_query(envelope.payload);
+
+        let matches = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.search_history(query)
+            })?
+            .await??;
+        Ok(proto::GitSearchHistoryResponse {
+            matches: matches.iter().map(history_search_match_to_proto).collect(),
+        })
+    }
+
     async fn handle_load_commit_diff(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::LoadCommitDiff>,
@@ -3457,6 +3503,32 @@ impl Repository {
         })
     }
 
+    pub fn search_history(
+        &mut self,
+        query: HistorySearchQuery,
+    ) -> oneshot::Receiver<Result<Vec<HistorySearchMatch>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.search_history(query).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(history_search_query_to_proto(
+                            project_id.0,
+                            id.to_proto(),
+                            query,
+                        ))
+                        .await?;
+                    Ok(response
+                        .matches
+                        .into_iter()
+                        .map(proto_to_history_search_match)
+                        .collect())
+                }
+            }
+        })
+    }
+
     pub fn load_commit_diff(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDiff>> {
         let id = self.id;
         self.send_job(None, move |git_repo, cx| async move {
@@ -4830,6 +4902,95 @@ fn proto_to_commit_details(proto: &proto::GitCommitDetails) -> CommitDetails {
     }
 }
 
+fn history_search_query_to_proto(
+    project_id: u64,
+    repository_id: u64,
+    query: HistorySearchQuery,
+) -> proto::GitSearchHistory {
+    proto::GitSearchHistory {
+        project_id,
+        repository_id,
+        query: query.query,
+        mode: match query.mode {
+            HistorySearchMode::Pickaxe => proto::git_search_history::Mode::Pickaxe,
+            HistorySearchMode::Regex => proto::git_search_history::Mode::Regex,
+        }
+        .into(),
+        case_sensitive: query.case_sensitive,
+        paths: query
+            .paths
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect(),
+        revision: query.revision,
+        since: query.since,
+        until: query.until,
+        limit: query.limit.map(|limit| limit as u64),
+    }
+}
+
+fn proto_to_history_search_query(proto: proto::GitSearchHistory) -> HistorySearchQuery {
+    HistorySearchQuery {
+        mode: match proto.mode() {
+            proto::git_search_history::Mode::Pickaxe => HistorySearchMode::Pickaxe,
+            proto::git_search_history::Mode::Regex => HistorySearchMode::Regex,
+        },
+        query: proto.query,
+        case_sensitive: proto.case_sensitive,
+        paths: proto
+            .paths
+            .iter()
+            .map(|path| RepoPath::from_str(path))
+            .collect(),
+        revision: proto.revision,
+        since: proto.since,
+        until: proto.until,
+        limit: proto.limit.map(|limit| limit as usize),
+    }
+}
+
+fn history_search_match_to_proto(search_match: &HistorySearchMatch) -> proto::HistorySearchMatch {
+    proto::HistorySearchMatch {
+        sha: search_match.commit.sha.to_string(),
+        subject: search_match.commit.subject.to_string(),
+        commit_timestamp: search_match.commit.commit_timestamp,
+        has_parent: search_match.commit.has_parent,
+        author_name: search_match.author_name.to_string(),
+        hunks: search_match
+            .hunks
+            .iter()
+            .map(|hunk| proto::HistoryHunk {
+                path: hunk.path.to_string_lossy().to_string(),
+                old_start: hunk.old_start,
+                new_start: hunk.new_start,
+                text: hunk.text.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn proto_to_history_search_match(proto: proto::HistorySearchMatch) -> HistorySearchMatch {
+    HistorySearchMatch {
+        commit: git::repository::CommitSummary {
+            sha: proto.sha.into(),
+            subject: proto.subject.into(),
+            commit_timestamp: proto.commit_timestamp,
+            has_parent: proto.has_parent,
+        },
+        author_name: proto.author_name.into(),
+        hunks: proto
+            .hunks
+            .into_iter()
+            .map(|hunk| HistoryHunk {
+                path: RepoPath::from_str(&hunk.path),
+                old_start: hunk.old_start,
+                new_start: hunk.new_start,
+                text: hunk.text,
+            })
+            .collect(),
+    }
+}
+
 async fn compute_snapshot(
     id: RepositoryId,
     work_directory_abs_path: Arc<Path>,
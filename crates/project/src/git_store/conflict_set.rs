@@ -4,6 +4,9 @@ use text::{Anchor, BufferId, OffsetRangeExt as _};
 
 pub struct ConflictSet {
     pub has_conflict: bool,
+    /// Whether the buffer holds the result of a merge, whose conflict markers
+    /// are parsed even if git doesn't consider the file to be conflicted.
+    pub(crate) is_merge_result: bool,
     pub snapshot: ConflictSetSnapshot,
 }
 
@@ -129,6 +132,7 @@ impl ConflictSet {
     pub fn new(buffer_id: BufferId, has_conflict: bool, _: &mut Context<Self>) -> Self {
         Self {
             has_conflict,
+            is_merge_result: false,
             snapshot: ConflictSetSnapshot {
                 buffer_id,
                 conflicts: Default::default(),
@@ -48,7 +48,8 @@ use language::{
     PointUtf16, TextBufferSnapshot, ToOffset, ToPointUtf16, Transaction, Unclipped,
     WorkspaceFoldersContent,
     language_settings::{
-        FormatOnSave, Formatter, LanguageSettings, SelectedFormatter, language_settings,
+        FormatOnSave, Formatter, LanguageSettings, LineEndingSetting, SelectedFormatter,
+        language_settings,
     },
     point_to_lsp,
     proto::{
@@ -1261,6 +1262,18 @@ impl LocalLspStore {
             })?;
         }
 
+        let line_ending = match settings.line_ending {
+            LineEndingSetting::Detect => None,
+            LineEndingSetting::Lf => Some(LineEnding::Unix),
+            LineEndingSetting::Crlf => Some(LineEnding::Windows),
+        };
+        if let Some(line_ending) = line_ending {
+            zlog::trace!(logger => "normalizing line endings");
+            buffer.handle.update(cx, |buffer, cx| {
+                buffer.set_line_ending(line_ending, cx);
+            })?;
+        }
+
         // Formatter for `code_actions_on_format` that runs before
         // the rest of the formatters
         let mut code_actions_on_format_formatter = None;
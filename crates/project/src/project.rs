@@ -1,3 +1,4 @@
+pub mod bookmark_store;
 pub mod buffer_store;
 mod color_extractor;
 pub mod connection_manager;
@@ -12,6 +13,7 @@ mod manifest_tree;
 pub mod prettier_store;
 pub mod project_settings;
 pub mod search;
+pub mod search_index;
 mod task_inventory;
 pub mod task_store;
 pub mod terminals;
@@ -41,6 +43,7 @@ pub use git_store::{
 pub use manifest_tree::ManifestTree;
 
 use anyhow::{Context as _, Result, anyhow};
+use bookmark_store::BookmarkStore;
 use buffer_store::{BufferStore, BufferStoreEvent};
 use client::{
     Client, Collaborator, PendingEntitySubscription, ProjectId, TypedEnvelope, UserStore, proto,
@@ -75,8 +78,8 @@ use gpui::{
 };
 use language::{
     Buffer, BufferEvent, Capability, CodeLabel, CursorShape, Language, LanguageName,
-    LanguageRegistry, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList, Transaction,
-    Unclipped, language_settings::InlayHintKind, proto::split_operations,
+    LanguageRegistry, ParseStatus, PointUtf16, ToOffset, ToPointUtf16, Toolchain, ToolchainList,
+    Transaction, Unclipped, language_settings::InlayHintKind, proto::split_operations,
 };
 use lsp::{
     CodeActionKind, CompletionContext, CompletionItemKind, DocumentHighlightKind, InsertTextMode,
@@ -96,6 +99,7 @@ use rpc::{
 };
 use search::{SearchInputKind, SearchQuery, SearchResult};
 use search_history::SearchHistory;
+use search_index::SearchIndexStore;
 use settings::{InvalidSettingsError, Settings, SettingsLocation, SettingsSources, SettingsStore};
 use smol::channel::Receiver;
 use snippet::Snippet;
@@ -177,6 +181,8 @@ pub struct Project {
     dap_store: Entity<DapStore>,
 
     breakpoint_store: Entity<BreakpointStore>,
+    bookmark_store: Entity<BookmarkStore>,
+    search_index_store: Option<Entity<SearchIndexStore>>,
     client: Arc<client::Client>,
     join_project_response_message_id: u32,
     task_store: Entity<TaskStore>,
@@ -1016,6 +1022,7 @@ impl Project {
         ToolchainStore::init(&client);
         DapStore::init(&client, cx);
         BreakpointStore::init(&client);
+        BookmarkStore::init(&client);
         context_server_store::init(cx);
     }
 
@@ -1059,6 +1066,10 @@ impl Project {
 
             let breakpoint_store =
                 cx.new(|_| BreakpointStore::local(worktree_store.clone(), buffer_store.clone()));
+            let bookmark_store =
+                cx.new(|cx| BookmarkStore::local(worktree_store.clone(), buffer_store.clone(), cx));
+            let search_index_store =
+                cx.new(|cx| SearchIndexStore::local(worktree_store.clone(), fs.clone(), cx));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_local(
@@ -1160,6 +1171,8 @@ impl Project {
                 fs,
                 ssh_client: None,
                 breakpoint_store,
+                bookmark_store,
+                search_index_store: Some(search_index_store),
                 dap_store,
 
                 buffers_needing_diff: Default::default(),
@@ -1272,6 +1285,9 @@ impl Project {
 
             let breakpoint_store =
                 cx.new(|_| BreakpointStore::remote(SSH_PROJECT_ID, ssh_proto.clone()));
+            // Bookmarks are kept on the client, since the server has no use for them.
+            let bookmark_store =
+                cx.new(|cx| BookmarkStore::local(worktree_store.clone(), buffer_store.clone(), cx));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_ssh(
@@ -1298,6 +1314,8 @@ impl Project {
                 lsp_store,
                 context_server_store,
                 breakpoint_store,
+                bookmark_store,
+                search_index_store: None,
                 dap_store,
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
@@ -1471,6 +1489,7 @@ impl Project {
 
         let breakpoint_store =
             cx.new(|_| BreakpointStore::remote(remote_id, client.clone().into()))?;
+        let bookmark_store = cx.new(|_| BookmarkStore::remote(remote_id, client.clone().into()))?;
         let dap_store = cx.new(|cx| {
             DapStore::new_collab(
                 remote_id,
@@ -1588,6 +1607,8 @@ impl Project {
                     replica_id,
                 },
                 breakpoint_store,
+                bookmark_store,
+                search_index_store: None,
                 dap_store: dap_store.clone(),
                 git_store: git_store.clone(),
                 buffers_needing_diff: Default::default(),
@@ -1782,6 +1803,15 @@ impl Project {
         self.breakpoint_store.clone()
     }
 
+    pub fn bookmark_store(&self) -> Entity<BookmarkStore> {
+        self.bookmark_store.clone()
+    }
+
+    /// The store maintaining this project's search indices, if it's local.
+    pub fn search_index_store(&self) -> Option<Entity<SearchIndexStore>> {
+        self.search_index_store.clone()
+    }
+
     pub fn active_debug_session(&self, cx: &App) -> Option<(Entity<Session>, ActiveStackFrame)> {
         let active_position = self.breakpoint_store.read(cx).active_position()?;
         let session = self
@@ -1819,6 +1849,20 @@ impl Project {
         self.ssh_client.clone()
     }
 
+    /// Lists the TCP ports that processes on the remote host are listening on.
+    pub fn remote_listening_ports(&self, cx: &App) -> Task<Result<Vec<proto::ListeningPort>>> {
+        let Some(ssh_client) = self.ssh_client.as_ref() else {
+            return Task::ready(Err(anyhow!("not a remote project")));
+        };
+        let request = ssh_client
+            .read(cx)
+            .proto_client()
+            .request(proto::ListListeningPorts {
+                project_id: SSH_PROJECT_ID,
+            });
+        cx.background_spawn(async move { Ok(request.await?.ports) })
+    }
+
     pub fn user_store(&self) -> Entity<UserStore> {
         self.user_store.clone()
     }
@@ -2247,6 +2291,9 @@ impl Project {
             self.client
                 .subscribe_to_entity(project_id)?
                 .set_entity(&self.breakpoint_store, &mut cx.to_async()),
+            self.client
+                .subscribe_to_entity(project_id)?
+                .set_entity(&self.bookmark_store, &mut cx.to_async()),
             self.client
                 .subscribe_to_entity(project_id)?
                 .set_entity(&self.git_store, &mut cx.to_async()),
@@ -2264,6 +2311,9 @@ impl Project {
         self.breakpoint_store.update(cx, |breakpoint_store, _| {
             breakpoint_store.shared(project_id, self.client.clone().into())
         });
+        self.bookmark_store.update(cx, |bookmark_store, _| {
+            bookmark_store.shared(project_id, self.client.clone().into())
+        });
         self.dap_store.update(cx, |dap_store, cx| {
             dap_store.shared(project_id, self.client.clone().into(), cx);
         });
@@ -2366,6 +2416,9 @@ impl Project {
             self.breakpoint_store.update(cx, |breakpoint_store, cx| {
                 breakpoint_store.unshared(cx);
             });
+            self.bookmark_store.update(cx, |bookmark_store, cx| {
+                bookmark_store.unshared(cx);
+            });
             self.dap_store.update(cx, |dap_store, cx| {
                 dap_store.unshared(cx);
             });
@@ -3864,6 +3917,14 @@ impl Project {
                 let mut chunk_results = Vec::with_capacity(matching_buffer_chunk.len());
                 for buffer in matching_buffer_chunk {
                     let query = query.clone();
+                    if query.requires_syntax_tree() {
+                        // Buffers that were just opened may not have been parsed yet.
+                        let mut parse_status =
+                            buffer.read_with(cx, |buffer, _| buffer.parse_status())?;
+                        while *parse_status.borrow() != ParseStatus::Idle {
+                            parse_status.changed().await?;
+                        }
+                    }
                     let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
                     chunk_results.push(cx.background_spawn(async move {
                         let ranges = query
@@ -4477,6 +4538,7 @@ impl Project {
                 buffer_store.forget_shared_buffers_for(&collaborator.peer_id);
             });
             this.breakpoint_store.read(cx).broadcast();
+            this.bookmark_store.read(cx).broadcast();
             cx.emit(Event::CollaboratorJoined(collaborator.peer_id));
             this.collaborators
                 .insert(collaborator.peer_id, collaborator);
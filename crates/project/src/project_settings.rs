@@ -79,6 +79,10 @@ pub struct ProjectSettings {
     /// Configuration for session-related features
     #[serde(default)]
     pub session: SessionSettings,
+
+    /// Configuration for the on-disk index used to speed up project search
+    #[serde(default)]
+    pub search_index: SearchIndexSettings,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -570,6 +574,16 @@ impl Default for SessionSettings {
     }
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SearchIndexSettings {
+    /// Whether to maintain a trigram index of the files in each local worktree,
+    /// so that project search only reads files that may contain a match.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+}
+
 impl Settings for ProjectSettings {
     const KEY: Option<&'static str> = None;
 
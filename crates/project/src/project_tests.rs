@@ -1,13 +1,19 @@
 #![allow(clippy::format_collect)]
 
 use crate::{
-    Event, git_store::StatusEntry, task_inventory::TaskContexts, task_store::TaskSettingsLocation,
+    Event,
+    bookmark_store::{BookmarkEditAction, BookmarkStore, SerializedBookmark},
+    git_store::StatusEntry,
+    search::SyntaxFilter,
+    task_inventory::TaskContexts,
+    task_store::TaskSettingsLocation,
     *,
 };
 use buffer_diff::{
     BufferDiffEvent, CALCULATE_DIFF_TASK, DiffHunkSecondaryStatus, DiffHunkStatus,
     DiffHunkStatusKind, assert_hunks,
 };
+use collections::BTreeMap;
 use fs::FakeFs;
 use futures::{StreamExt, future};
 use git::{
@@ -20,9 +26,13 @@ use gpui::{App, BackgroundExecutor, SemanticVersion, UpdateGlobal};
 use http_client::Url;
 use itertools::Itertools;
 use language::{
-    Diagnostic, DiagnosticEntry, DiagnosticSet, DiagnosticSourceKind, DiskState, FakeLspAdapter,
-    LanguageConfig, LanguageMatcher, LanguageName, LineEnding, OffsetRangeExt, Point, ToPoint,
-    language_settings::{AllLanguageSettings, LanguageSettingsContent, language_settings},
+    Diagnostic, DiagnosticEntry, DiagnosticSet, DiagnosticSourceKind, DiskState, Encoding,
+    FakeLspAdapter, LanguageConfig, LanguageMatcher, LanguageName, LineEnding, OffsetRangeExt,
+    Point, ToPoint,
+    language_settings::{
+        AllLanguageSettings, LanguageSettingSource, LanguageSettingsContent, LineEndingSetting,
+        effective_language_settings, language_settings,
+    },
     tree_sitter_rust, tree_sitter_typescript,
 };
 use lsp::{
@@ -35,6 +45,7 @@ use postage::stream::Stream as _;
 use pretty_assertions::{assert_eq, assert_matches};
 use rand::{Rng as _, rngs::StdRng};
 use serde_json::json;
+use settings::SettingsLayer;
 #[cfg(not(windows))]
 use std::os;
 use std::{env, mem, num::NonZeroU32, ops::Range, str::FromStr, sync::OnceLock, task::Poll};
@@ -217,6 +228,169 @@ async fn test_editorconfig_support(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_editorconfig_property_coverage(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".editorconfig": r#"
+            root = true
+            [*]
+                max_line_length = 100
+                end_of_line = crlf
+            [src/*.rs]
+                max_line_length = off
+                indent_size = tab
+                tab_width = 6
+            [*.txt]
+                end_of_line = cr
+                charset = latin1
+            [*.{bin,md}]
+                charset = utf-8
+            "#,
+            ".zed": {
+                "settings.json": r#"{
+                    "preferred_line_length": 120,
+                    "languages": { "Rust": { "tab_size": 8 } }
+                }"#,
+            },
+            "main.rs": "fn main() {}\n",
+            "src": {
+                "lib.rs": "fn lib() {}\n",
+                "nested": {
+                    "mod.rs": "fn nested() {}\n",
+                },
+            },
+        }),
+    )
+    .await;
+    fs.insert_file(path!("/dir/utf8.txt"), "caf\u{e9}\n".as_bytes().to_vec())
+        .await;
+    fs.insert_file(path!("/dir/bom.txt"), b"\xEF\xBB\xBFcaf\xC3\xA9\n".to_vec())
+        .await;
+    fs.insert_file(
+        path!("/dir/binary.bin"),
+        b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\xff".to_vec(),
+    )
+    .await;
+    fs.insert_file(path!("/dir/invalid.md"), b"caf\xE9 cr\xE8me\n".to_vec())
+        .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+    cx.executor().run_until_parked();
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+
+    cx.update(|cx| {
+        let rust = LanguageName::new("Rust");
+        let location = |path: &'static str| SettingsLocation {
+            worktree_id,
+            path: Path::new(path),
+        };
+        let settings_for = |path: &'static str| {
+            let location = Some(location(path));
+            AllLanguageSettings::get(location, cx)
+                .language(location, Some(&rust), cx)
+                .into_owned()
+        };
+        let source_of = |path: &'static str, key: &str| {
+            effective_language_settings(Some(&rust), Some(location(path)), cx)
+                .into_iter()
+                .find(|setting| setting.key == key)
+                .map(|setting| (setting.value, setting.source))
+        };
+
+        let settings_main = settings_for("main.rs");
+        let settings_lib = settings_for("src/lib.rs");
+        let settings_nested = settings_for("src/nested/mod.rs");
+        let settings_txt = settings_for("utf8.txt");
+
+        // max_line_length and end_of_line override the project settings
+        assert_eq!(settings_main.preferred_line_length, 100);
+        assert_eq!(settings_main.line_ending, LineEndingSetting::Crlf);
+        assert_eq!(settings_main.tab_size, NonZeroU32::new(8).unwrap());
+
+        // Globs with a slash are relative to the .editorconfig's directory
+        assert_eq!(settings_lib.tab_size, NonZeroU32::new(6).unwrap());
+        assert_eq!(settings_nested.tab_size, NonZeroU32::new(8).unwrap());
+        assert_eq!(settings_nested.preferred_line_length, 100);
+
+        // "max_line_length = off" leaves the settings' value in place
+        assert_eq!(settings_lib.preferred_line_length, 120);
+
+        // "end_of_line = cr" can't be honored, so the line ending isn't changed
+        assert_eq!(settings_txt.line_ending, LineEndingSetting::Detect);
+
+        assert_eq!(
+            source_of("src/lib.rs", "tab_size"),
+            Some((json!(6), LanguageSettingSource::Editorconfig))
+        );
+        assert_eq!(
+            source_of("main.rs", "tab_size"),
+            Some((
+                json!(8),
+                LanguageSettingSource::Language(
+                    SettingsLayer::Project(Path::new("").into()),
+                    rust.clone()
+                )
+            ))
+        );
+        assert_eq!(
+            source_of("src/lib.rs", "preferred_line_length"),
+            Some((
+                json!(120),
+                LanguageSettingSource::Settings(SettingsLayer::Project(Path::new("").into()))
+            ))
+        );
+    });
+
+    // "charset" decides how files are decoded, unless they start with a byte order mark
+    let utf8 = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/utf8.txt"), cx)
+        })
+        .await
+        .unwrap();
+    let bom = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/bom.txt"), cx)
+        })
+        .await
+        .unwrap();
+    utf8.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "caf\u{c3}\u{a9}\n");
+        assert_eq!(buffer.encoding().name(), "windows-1252");
+    });
+    bom.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "caf\u{e9}\n");
+        assert_eq!(buffer.encoding().name(), "UTF-8");
+        assert!(buffer.encoding().has_bom());
+    });
+
+    // Files that don't decode in the charset are still detected, and binary files still fail to open
+    let invalid = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/invalid.md"), cx)
+        })
+        .await
+        .unwrap();
+    invalid.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "caf\u{e9} cr\u{e8}me\n");
+        assert_eq!(buffer.encoding().name(), "windows-1252");
+    });
+    let binary = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/binary.bin"), cx)
+        })
+        .await;
+    assert!(binary.is_err());
+}
+
 #[gpui::test]
 async fn test_git_provider_project_setting(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3628,6 +3802,81 @@ async fn test_save_file(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_save_file_with_encoding(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({})).await;
+    fs.insert_file(path!("/dir/latin1.txt"), b"caf\xE9\n".to_vec())
+        .await;
+    fs.insert_file(path!("/dir/utf16.txt"), b"\xFF\xFEh\0i\0".to_vec())
+        .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let latin1 = project
+        .update(cx, |p, cx| {
+            p.open_local_buffer(path!("/dir/latin1.txt"), cx)
+        })
+        .await
+        .unwrap();
+    let utf16 = project
+        .update(cx, |p, cx| p.open_local_buffer(path!("/dir/utf16.txt"), cx))
+        .await
+        .unwrap();
+    latin1.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "caf\u{e9}\n");
+        assert_eq!(buffer.encoding().name(), "windows-1252");
+        buffer.edit([(0..0, "cr\u{e8}me ")], None, cx);
+    });
+    utf16.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "hi");
+        assert_eq!(buffer.encoding().name(), "UTF-16LE");
+        assert!(buffer.encoding().has_bom());
+        buffer.edit([(2..2, "!")], None, cx);
+    });
+
+    for buffer in [&latin1, &utf16] {
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        fs.load_bytes(Path::new(path!("/dir/latin1.txt")))
+            .await
+            .unwrap(),
+        b"cr\xE8me caf\xE9\n"
+    );
+    assert_eq!(
+        fs.load_bytes(Path::new(path!("/dir/utf16.txt")))
+            .await
+            .unwrap(),
+        b"\xFF\xFEh\0i\0!\0"
+    );
+
+    // Characters that the encoding can't represent fail the save rather than being dropped.
+    latin1.update(cx, |buffer, cx| buffer.edit([(0..0, "\u{3042}")], None, cx));
+    assert!(
+        project
+            .update(cx, |project, cx| project.save_buffer(latin1.clone(), cx))
+            .await
+            .is_err()
+    );
+
+    latin1.update(cx, |buffer, cx| {
+        buffer.set_encoding(Encoding::default(), cx);
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(latin1.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load(Path::new(path!("/dir/latin1.txt"))).await.unwrap(),
+        "\u{3042}cr\u{e8}me caf\u{e9}\n"
+    );
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_save_file_spawns_language_server(cx: &mut gpui::TestAppContext) {
     // Issue: #24349
@@ -5111,6 +5360,132 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_search_with_index(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "const ONE: usize = 1;",
+            "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+            "three.rs": "const THREE: usize = one::ONE + two::TWO;",
+            "four.rs": "fn four() -> usize { one::ONE + three::THREE }",
+            "notes.txt": "Two roads diverged in a yellow wood",
+            "src": {
+                "lib.rs": "pub fn compute_total(items: &[u32]) -> u32 { items.iter().sum() }",
+            },
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+    let text = |query: &str, whole_word: bool, case_sensitive: bool| {
+        SearchQuery::text(
+            query,
+            whole_word,
+            case_sensitive,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap()
+    };
+    let regex = |query: &str, case_sensitive: bool| {
+        SearchQuery::regex(
+            query,
+            false,
+            case_sensitive,
+            false,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap()
+    };
+    let queries = || {
+        vec![
+            text("TWO", false, true),
+            text("two", false, false),
+            text("one", true, false),
+            text("yellow wood", false, true),
+            text("::", false, true),
+            regex(r"(THREE|compute_\w+)", true),
+            regex(r"fn \w+\(", false),
+            regex(r"(?<=one::)ONE", true),
+        ]
+    };
+    let set_index_enabled = |enabled: bool, cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<project_settings::ProjectSettings>(cx, |settings| {
+                    settings.search_index.enabled = enabled;
+                });
+            });
+        });
+        cx.run_until_parked();
+    };
+
+    // Searching with the index must find exactly what searching without it does.
+    let mut expected = Vec::new();
+    for query in queries() {
+        expected.push(search(&project, query, cx).await.unwrap());
+    }
+    set_index_enabled(true, cx);
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    assert!(project.read_with(cx, |project, cx| {
+        project
+            .search_index_store()
+            .unwrap()
+            .read(cx)
+            .index_for_worktree(worktree_id)
+            .is_some()
+    }));
+    for (query, expected) in queries().into_iter().zip(&expected) {
+        assert_eq!(&search(&project, query, cx).await.unwrap(), expected);
+    }
+
+    // The index follows changes on disk.
+    fs.save(
+        path!("/dir/one.rs").as_ref(),
+        &"const ONE: usize = two::TWO - 1; fn helper() {}".into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    fs.remove_file(path!("/dir/notes.txt").as_ref(), Default::default())
+        .await
+        .unwrap();
+    fs.insert_file(
+        path!("/dir/src/main.rs"),
+        b"fn main() { compute_total(&[]); }".to_vec(),
+    )
+    .await;
+    cx.run_until_parked();
+
+    let mut results_with_index = Vec::new();
+    for query in queries() {
+        results_with_index.push(search(&project, query, cx).await.unwrap());
+    }
+    set_index_enabled(false, cx);
+    let mut expected = Vec::new();
+    for query in queries() {
+        expected.push(search(&project, query, cx).await.unwrap());
+    }
+    assert_eq!(results_with_index, expected);
+    assert!(
+        expected[0].contains_key(path!("dir/one.rs")),
+        "the edited file should match"
+    );
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -5893,6 +6268,149 @@ async fn test_search_with_unicode(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_structural_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "fn a() { foo(1, 2); foo(x); bar(1, 2); }",
+            "two.rs": "fn b() { foo(y, y); }",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let structural = |pattern: &str| {
+        SearchQuery::structural(
+            pattern,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap()
+    };
+
+    assert_eq!(
+        search(&project, structural("foo($X, $$$REST)"), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([
+            (path!("dir/one.rs").to_string(), vec![9..18]),
+            (path!("dir/two.rs").to_string(), vec![9..18]),
+        ])
+    );
+    assert_eq!(
+        search(&project, structural("foo($X, $X)"), cx)
+            .await
+            .unwrap(),
+        HashMap::from_iter([(path!("dir/two.rs").to_string(), vec![9..18])])
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/one.rs"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    let query = structural("$F($X, $Y)").with_replacement("$F($Y, $X)".to_string());
+    assert_eq!(
+        query.replacement_for_range(&snapshot, 9..18).as_deref(),
+        Some("foo(2, 1)")
+    );
+    assert_eq!(
+        query.replacement_for_range(&snapshot, 28..37).as_deref(),
+        Some("bar(2, 1)")
+    );
+}
+
+#[gpui::test]
+async fn test_search_with_syntax_filter(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "one.rs": "// TODO: one\nconst TODO: &str = \"TODO\";",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(Arc::new(
+        Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_highlights_query(
+            r#"
+            (line_comment) @comment
+            (string_literal) @string
+            "#,
+        )
+        .unwrap(),
+    ));
+
+    let query = |syntax_filter: SyntaxFilter| {
+        SearchQuery::text(
+            "TODO",
+            false,
+            true,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap()
+        .with_syntax_filter(syntax_filter)
+    };
+
+    assert_eq!(
+        search(
+            &project,
+            query(SyntaxFilter {
+                code: true,
+                ..Default::default()
+            }),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([(path!("dir/one.rs").to_string(), vec![19..23])])
+    );
+    assert_eq!(
+        search(
+            &project,
+            query(SyntaxFilter {
+                comments: true,
+                strings: true,
+                ..Default::default()
+            }),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([(path!("dir/one.rs").to_string(), vec![3..7, 33..37])])
+    );
+}
+
 #[gpui::test]
 async fn test_create_entry(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -9240,3 +9758,189 @@ async fn test_find_project_path_abs(
         );
     });
 }
+
+#[gpui::test]
+async fn test_bookmarks_follow_renamed_and_deleted_files(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "one\ntwo\nthree\n",
+            "b.rs": "four\nfive\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let bookmark_store = project.read_with(cx, |project, _| project.bookmark_store());
+
+    for path in [path!("/dir/a.rs"), path!("/dir/b.rs")] {
+        let buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(path, cx))
+            .await
+            .unwrap();
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            let position = buffer.read(cx).anchor_before(Point::new(1, 0));
+            bookmark_store.edit_bookmark(buffer, position, BookmarkEditAction::Toggle, cx);
+        });
+    }
+    assert_eq!(
+        bookmarked_rows(&bookmark_store, cx),
+        [
+            (PathBuf::from(path!("/dir/a.rs")), 1),
+            (PathBuf::from(path!("/dir/b.rs")), 1)
+        ]
+    );
+
+    fs.rename(
+        path!("/dir/a.rs").as_ref(),
+        path!("/dir/c.rs").as_ref(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(
+        bookmarked_rows(&bookmark_store, cx),
+        [
+            (PathBuf::from(path!("/dir/b.rs")), 1),
+            (PathBuf::from(path!("/dir/c.rs")), 1)
+        ]
+    );
+
+    fs.remove_file(path!("/dir/b.rs").as_ref(), Default::default())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(
+        bookmarked_rows(&bookmark_store, cx),
+        [(PathBuf::from(path!("/dir/c.rs")), 1)]
+    );
+}
+
+#[gpui::test]
+async fn test_restoring_serialized_bookmarks(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/root"),
+        json!({
+            "dir": { "a.rs": "one\ntwo\nthree\n" },
+            "other": { "b.rs": "four\nfive\n" },
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), [path!("/root/dir").as_ref()], cx).await;
+    let bookmark_store = project.read_with(cx, |project, _| project.bookmark_store());
+
+    let a_path = Arc::<Path>::from(Path::new(path!("/root/dir/a.rs")));
+    let b_path = Arc::<Path>::from(Path::new(path!("/root/other/b.rs")));
+    let serialized = BTreeMap::from_iter([
+        (
+            a_path.clone(),
+            vec![
+                SerializedBookmark {
+                    row: 2,
+                    label: Some("todo".into()),
+                },
+                // The file has since been truncated, so this one is dropped.
+                SerializedBookmark {
+                    row: 10,
+                    label: None,
+                },
+            ],
+        ),
+        (
+            b_path.clone(),
+            vec![SerializedBookmark {
+                row: 1,
+                label: None,
+            }],
+        ),
+    ]);
+    bookmark_store
+        .update(cx, |bookmark_store, cx| {
+            bookmark_store.with_serialized_bookmarks(serialized, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        bookmarked_rows(&bookmark_store, cx),
+        [(PathBuf::from(path!("/root/dir/a.rs")), 2)]
+    );
+
+    // Bookmarks outside of the project are kept until their worktree is added.
+    let expected = BTreeMap::from_iter([
+        (
+            a_path,
+            vec![SerializedBookmark {
+                row: 2,
+                label: Some("todo".into()),
+            }],
+        ),
+        (
+            b_path,
+            vec![SerializedBookmark {
+                row: 1,
+                label: None,
+            }],
+        ),
+    ]);
+    assert_eq!(
+        bookmark_store.read_with(cx, |bookmark_store, cx| bookmark_store
+            .all_serialized_bookmarks(cx)),
+        expected
+    );
+    project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/root/other"), true, cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(
+        bookmarked_rows(&bookmark_store, cx),
+        [
+            (PathBuf::from(path!("/root/dir/a.rs")), 2),
+            (PathBuf::from(path!("/root/other/b.rs")), 1)
+        ]
+    );
+
+    // Reopening the project with the persisted bookmarks puts them back where they were.
+    let serialized = bookmark_store.read_with(cx, |bookmark_store, cx| {
+        bookmark_store.all_serialized_bookmarks(cx)
+    });
+    let reopened = Project::test(
+        fs.clone(),
+        [path!("/root/dir").as_ref(), path!("/root/other").as_ref()],
+        cx,
+    )
+    .await;
+    let reopened_bookmark_store = reopened.read_with(cx, |project, _| project.bookmark_store());
+    reopened_bookmark_store
+        .update(cx, |bookmark_store, cx| {
+            bookmark_store.with_serialized_bookmarks(serialized, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        reopened_bookmark_store.read_with(cx, |bookmark_store, cx| bookmark_store
+            .all_serialized_bookmarks(cx)),
+        expected
+    );
+}
+
+fn bookmarked_rows(
+    bookmark_store: &Entity<BookmarkStore>,
+    cx: &gpui::TestAppContext,
+) -> Vec<(PathBuf, u32)> {
+    bookmark_store.read_with(cx, |bookmark_store, cx| {
+        bookmark_store
+            .all_bookmarks(cx)
+            .into_iter()
+            .map(|entry| (entry.path.to_path_buf(), entry.row))
+            .collect()
+    })
+}
@@ -1,9 +1,12 @@
+mod structural;
+
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use anyhow::Result;
 use client::proto;
+use convert_case::{Case, Casing};
 use fancy_regex::{Captures, Regex, RegexBuilder};
 use gpui::Entity;
-use language::{Buffer, BufferSnapshot, CharKind};
+use language::{Buffer, BufferSnapshot, CharKind, SyntaxRegion};
 use smol::future::yield_now;
 use std::{
     borrow::Cow,
@@ -15,6 +18,8 @@ use std::{
 use text::Anchor;
 use util::paths::PathMatcher;
 
+pub use structural::StructuralPattern;
+
 #[derive(Debug)]
 pub enum SearchResult {
     Buffer {
@@ -31,6 +36,30 @@ pub enum SearchInputKind {
     Exclude,
 }
 
+/// The kinds of syntax that a search is restricted to. A search with no
+/// kinds set matches anywhere.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SyntaxFilter {
+    /// Match text outside of comments and strings.
+    pub code: bool,
+    pub comments: bool,
+    pub strings: bool,
+}
+
+impl SyntaxFilter {
+    pub fn is_empty(&self) -> bool {
+        !(self.code || self.comments || self.strings)
+    }
+
+    fn allows(&self, region: Option<SyntaxRegion>) -> bool {
+        match region {
+            None => self.code,
+            Some(SyntaxRegion::Comment) => self.comments,
+            Some(SyntaxRegion::String) => self.strings,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SearchInputs {
     query: Arc<str>,
@@ -38,6 +67,8 @@ pub struct SearchInputs {
     files_to_exclude: PathMatcher,
     match_full_paths: bool,
     buffers: Option<Vec<Entity<Buffer>>>,
+    syntax_filter: SyntaxFilter,
+    preserve_case: bool,
 }
 
 impl SearchInputs {
@@ -75,6 +106,13 @@ pub enum SearchQuery {
         one_match_per_line: bool,
         inner: SearchInputs,
     },
+
+    Structural {
+        pattern: Arc<StructuralPattern>,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 static WORD_MATCH_TEST: LazyLock<Regex> = LazyLock::new(|| {
@@ -124,6 +162,8 @@ impl SearchQuery {
             files_to_include,
             match_full_paths,
             buffers,
+            syntax_filter: SyntaxFilter::default(),
+            preserve_case: false,
         };
         Ok(Self::Text {
             search,
@@ -179,6 +219,8 @@ impl SearchQuery {
             files_to_include,
             match_full_paths,
             buffers,
+            syntax_filter: SyntaxFilter::default(),
+            preserve_case: false,
         };
         Ok(Self::Regex {
             regex,
@@ -192,6 +234,37 @@ impl SearchQuery {
         })
     }
 
+    /// Create a structural query, matching syntax trees against a code pattern
+    /// with `$NAME` and `$$$NAME` metavariables.
+    ///
+    /// `match_full_paths` has the same meaning as for [`SearchQuery::regex`].
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+        match_full_paths: bool,
+        buffers: Option<Vec<Entity<Buffer>>>,
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let pattern = StructuralPattern::new(&query)?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            match_full_paths,
+            buffers,
+            syntax_filter: SyntaxFilter::default(),
+            preserve_case: false,
+        };
+        Ok(Self::Structural {
+            pattern: Arc::new(pattern),
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     pub fn from_proto(message: proto::SearchQuery) -> Result<Self> {
         let files_to_include = if message.files_to_include.is_empty() {
             message
@@ -217,7 +290,21 @@ impl SearchQuery {
             message.files_to_exclude
         };
 
-        if message.regex {
+        let syntax_filter = SyntaxFilter {
+            code: message.in_code,
+            comments: message.in_comments,
+            strings: message.in_strings,
+        };
+        let query = if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                PathMatcher::new(files_to_include)?,
+                PathMatcher::new(files_to_exclude)?,
+                message.match_full_paths,
+                None, // search opened only don't need search remote
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -240,7 +327,8 @@ impl SearchQuery {
                 false,
                 None, // search opened only don't need search remote
             )
-        }
+        }?;
+        Ok(query.with_syntax_filter(syntax_filter))
     }
 
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
@@ -252,6 +340,10 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
@@ -259,12 +351,42 @@ impl SearchQuery {
         }
     }
 
+    /// Restricts matches to the given kinds of syntax, as classified by the
+    /// highlights query of each buffer's language.
+    pub fn with_syntax_filter(mut self, syntax_filter: SyntaxFilter) -> Self {
+        match &mut self {
+            Self::Text { inner, .. }
+            | Self::Regex { inner, .. }
+            | Self::Structural { inner, .. } => {
+                inner.syntax_filter = syntax_filter;
+            }
+        }
+        self
+    }
+
+    /// Adapts replacements to the casing of each match they replace, so that
+    /// replacing `foo_bar` with `baz qux` in `FooBar` produces `BazQux`.
+    pub fn with_preserve_case(mut self, preserve_case: bool) -> Self {
+        match &mut self {
+            Self::Text { inner, .. }
+            | Self::Regex { inner, .. }
+            | Self::Structural { inner, .. } => {
+                inner.preserve_case = preserve_case;
+            }
+        }
+        self
+    }
+
     pub fn to_proto(&self) -> proto::SearchQuery {
         let files_to_include = self.files_to_include().sources().to_vec();
         let files_to_exclude = self.files_to_exclude().sources().to_vec();
         proto::SearchQuery {
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
+            in_code: self.syntax_filter().code,
+            in_comments: self.syntax_filter().comments,
+            in_strings: self.syntax_filter().strings,
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
@@ -314,19 +436,20 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            Self::Structural { pattern, .. } => pattern.might_match(reader),
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
-        match self {
+        let replacement = match self {
             SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
             SearchQuery::Regex {
                 regex, replacement, ..
@@ -348,6 +471,33 @@ impl SearchQuery {
                     None
                 }
             }
+            // Structural replacements depend on the syntax tree around the match.
+            SearchQuery::Structural { .. } => None,
+        }?;
+        if self.preserve_case() {
+            Some(Cow::Owned(preserve_case(text, &replacement)))
+        } else {
+            Some(replacement)
+        }
+    }
+
+    /// Replaces the search hit at the given range of the buffer if replacement is set.
+    /// Unlike [`SearchQuery::replacement_for`], this supports structural queries.
+    pub fn replacement_for_range(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+    ) -> Option<String> {
+        match self {
+            SearchQuery::Structural {
+                pattern,
+                replacement,
+                ..
+            } => pattern.replacement_for(buffer, range, replacement.as_deref()?),
+            _ => {
+                let text = buffer.text_for_range(range).collect::<String>();
+                self.replacement_for(&text).map(Cow::into_owned)
+            }
         }
     }
 
@@ -362,6 +512,17 @@ impl SearchQuery {
             return Default::default();
         }
 
+        if let Self::Structural { pattern, .. } = self {
+            let range = subrange.unwrap_or(0..buffer.len());
+            let offset = range.start;
+            let matches = pattern
+                .search(buffer, range)
+                .into_iter()
+                .map(|range| range.start - offset..range.end - offset)
+                .collect();
+            return self.filter_by_syntax(buffer, offset, matches);
+        }
+
         let range_offset = subrange.as_ref().map(|r| r.start).unwrap_or(0);
         let rope = if let Some(range) = subrange {
             buffer.as_rope().slice(range)
@@ -446,9 +607,50 @@ impl SearchQuery {
                     }
                 }
             }
+            Self::Structural { .. } => {}
+        }
+
+        self.filter_by_syntax(buffer, range_offset, matches)
+    }
+
+    /// Removes the matches that aren't in the kinds of syntax this query is
+    /// restricted to. Matches spanning several kinds are always removed.
+    fn filter_by_syntax(
+        &self,
+        buffer: &BufferSnapshot,
+        range_offset: usize,
+        matches: Vec<Range<usize>>,
+    ) -> Vec<Range<usize>> {
+        let filter = self.syntax_filter();
+        if filter.is_empty() {
+            return matches;
         }
+        let (Some(start), Some(end)) = (
+            matches.iter().map(|range| range.start).min(),
+            matches.iter().map(|range| range.end).max(),
+        ) else {
+            return matches;
+        };
 
+        let regions = buffer.syntax_regions(range_offset + start..range_offset + end);
         matches
+            .into_iter()
+            .filter(|range| {
+                let range = range_offset + range.start..range_offset + range.end;
+                let mut intersects_region = false;
+                for (region_range, region) in &regions {
+                    if region_range.start >= range.end && !range.is_empty() {
+                        break;
+                    }
+                    if region_range.start <= range.start && range.end <= region_range.end {
+                        return filter.allows(Some(*region));
+                    }
+                    intersects_region |=
+                        region_range.start < range.end && range.start < region_range.end;
+                }
+                !intersects_region && filter.allows(None)
+            })
+            .collect()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -463,6 +665,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -470,6 +673,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => true,
         }
     }
 
@@ -481,6 +685,9 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
         }
     }
 
@@ -488,6 +695,24 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
+    pub fn syntax_filter(&self) -> SyntaxFilter {
+        self.as_inner().syntax_filter
+    }
+
+    pub fn preserve_case(&self) -> bool {
+        self.as_inner().preserve_case
+    }
+
+    /// Whether matching this query depends on the syntax tree of the buffers
+    /// being searched, so that they need to be parsed first.
+    pub fn requires_syntax_tree(&self) -> bool {
+        self.is_structural() || !self.syntax_filter().is_empty()
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -531,24 +756,68 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 
     /// Whether this search should replace only one match per line, instead of
     /// all matches.
-    /// Returns `None` for text searches, as only regex searches support this
+    /// Returns `None` for text and structural searches, as only regex searches support this
     /// option.
     pub fn one_match_per_line(&self) -> Option<bool> {
         match self {
             Self::Regex {
                 one_match_per_line, ..
             } => Some(*one_match_per_line),
-            Self::Text { .. } => None,
+            Self::Text { .. } | Self::Structural { .. } => None,
         }
     }
 }
 
+/// Rewrites `replacement` in the casing of `matched`. Single words only affect
+/// the case of the replacement's letters, while identifiers made of several
+/// words also determine how the replacement's words are joined.
+fn preserve_case(matched: &str, replacement: &str) -> String {
+    let has_uppercase = matched.chars().any(char::is_uppercase);
+    let has_lowercase = matched.chars().any(char::is_lowercase);
+    if !matched.to_case(Case::Snake).contains('_') {
+        let mut chars = matched.chars();
+        let first_is_uppercase = chars.next().is_some_and(char::is_uppercase);
+        return if has_uppercase && !has_lowercase && matched.chars().count() > 1 {
+            replacement.to_uppercase()
+        } else if has_lowercase && !has_uppercase {
+            replacement.to_lowercase()
+        } else if first_is_uppercase && !chars.any(char::is_uppercase) {
+            let mut replacement_chars = replacement.chars();
+            replacement_chars
+                .next()
+                .map(|first| first.to_uppercase().chain(replacement_chars).collect())
+                .unwrap_or_default()
+        } else {
+            replacement.to_string()
+        };
+    }
+
+    [
+        Case::UpperSnake,
+        Case::UpperKebab,
+        Case::Upper,
+        Case::Snake,
+        Case::Kebab,
+        Case::Lower,
+        Case::Camel,
+        Case::UpperCamel,
+        Case::Train,
+        Case::Title,
+        Case::Sentence,
+    ]
+    .into_iter()
+    .find(|case| matched.is_case(*case))
+    .map_or_else(|| replacement.to_string(), |case| replacement.to_case(case))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -574,6 +843,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_preserve_case() {
+        for (matched, expected) in [
+            ("foo", "baz qux"),
+            ("FOO", "BAZ QUX"),
+            ("Foo", "Baz qux"),
+            ("foo_bar", "baz_qux"),
+            ("FOO_BAR", "BAZ_QUX"),
+            ("foo-bar", "baz-qux"),
+            ("fooBar", "bazQux"),
+            ("FooBar", "BazQux"),
+            ("Foo Bar", "Baz Qux"),
+            ("foo bar", "baz qux"),
+            ("Foo bar", "Baz qux"),
+        ] {
+            assert_eq!(
+                preserve_case(matched, "baz qux"),
+                expected,
+                "matching {matched:?}"
+            );
+        }
+        assert_eq!(preserve_case("FooBar", "BAZ_QUX"), "BazQux");
+        assert_eq!(preserve_case("foo_bar", "bazQux"), "baz_qux");
+    }
+
     #[test]
     fn path_matcher_creation_for_globs() {
         for invalid_glob in ["dir/[].txt", "dir/[a-z.txt", "dir/{file"] {
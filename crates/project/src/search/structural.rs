@@ -0,0 +1,394 @@
+//! Structural search matches code against patterns written in the language
+//! being searched, rather than against its text.
+//!
+//! A pattern such as `foo($X, $$$REST)` is parsed with the grammar of each
+//! buffer it's searched in, and matches any syntax node with the same shape.
+//! `$NAME` stands for exactly one node, and `$$$NAME` for any number of
+//! sibling nodes. A metavariable that appears more than once must capture the
+//! same text each time, except for `$_`, which never captures. Replacement
+//! templates can refer to the captured metavariables by name.
+use aho_corasick::AhoCorasick;
+use collections::HashMap;
+use language::{BufferSnapshot, LanguageName, Node, with_parser};
+use parking_lot::Mutex;
+use regex::{Captures, Regex};
+use std::{
+    ops::Range,
+    sync::{Arc, LazyLock},
+};
+
+static METAVARIABLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$(\$\$)?([A-Z_][A-Z0-9_]*)").expect("invalid metavariable regex")
+});
+
+/// The name of the metavariable that matches without capturing.
+const WILDCARD: &str = "_";
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Metavariable {
+    Single(Arc<str>),
+    Multiple(Arc<str>),
+}
+
+#[derive(Debug)]
+enum PatternNode {
+    Metavariable(Metavariable),
+    Leaf {
+        kind: u16,
+        text: String,
+    },
+    Node {
+        kind: u16,
+        children: Vec<PatternNode>,
+    },
+}
+
+impl PatternNode {
+    fn kind(&self) -> Option<u16> {
+        match self {
+            PatternNode::Metavariable(_) => None,
+            PatternNode::Leaf { kind, .. } | PatternNode::Node { kind, .. } => Some(*kind),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct StructuralPattern {
+    /// The pattern with each metavariable replaced by a placeholder
+    /// identifier, so that it parses as code.
+    parseable: String,
+    placeholders: HashMap<String, Metavariable>,
+    /// The longest literal word in the pattern, which every matching file
+    /// must contain.
+    literal: Option<AhoCorasick>,
+    /// The pattern compiled for each language it has been searched in, or
+    /// `None` if it doesn't parse in that language.
+    compiled: Mutex<HashMap<LanguageName, Option<Arc<PatternNode>>>>,
+}
+
+impl StructuralPattern {
+    pub fn new(pattern: &str) -> anyhow::Result<Self> {
+        let mut placeholders = HashMap::default();
+        let parseable = METAVARIABLE
+            .replace_all(pattern, |captures: &Captures| {
+                let multiple = captures.get(1).is_some();
+                let name: Arc<str> = Arc::from(&captures[2]);
+                let (placeholder, metavariable) = if multiple {
+                    (
+                        format!("zed_mvs_{name}"),
+                        Metavariable::Multiple(name.clone()),
+                    )
+                } else {
+                    (format!("zed_mv_{name}"), Metavariable::Single(name.clone()))
+                };
+                placeholders.insert(placeholder.clone(), metavariable);
+                placeholder
+            })
+            .into_owned();
+
+        let literal = METAVARIABLE
+            .replace_all(pattern, " ")
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .max_by_key(|word| word.len())
+            .filter(|word| word.len() > 1)
+            .map(|word| AhoCorasick::new([word]))
+            .transpose()?;
+
+        Ok(Self {
+            parseable,
+            placeholders,
+            literal,
+            compiled: Mutex::default(),
+        })
+    }
+
+    /// Returns whether the given text could contain a match, without parsing it.
+    pub fn might_match(&self, text: impl std::io::Read) -> anyhow::Result<bool> {
+        let Some(literal) = &self.literal else {
+            return Ok(true);
+        };
+        match literal.stream_find_iter(text).next() {
+            Some(Ok(_)) => Ok(true),
+            Some(Err(err)) => Err(err.into()),
+            None => Ok(false),
+        }
+    }
+
+    fn compiled(&self, language: &language::Language) -> Option<Arc<PatternNode>> {
+        self.compiled
+            .lock()
+            .entry(language.name())
+            .or_insert_with(|| self.compile(language).map(Arc::new))
+            .clone()
+    }
+
+    fn compile(&self, language: &language::Language) -> Option<PatternNode> {
+        let grammar = language.grammar()?;
+        let tree = with_parser(|parser| {
+            parser.set_language(&grammar.ts_language).ok()?;
+            parser.parse(&self.parseable, None)
+        })?;
+
+        let start = self.parseable.len() - self.parseable.trim_start().len();
+        let end = self.parseable.trim_end().len();
+        if start >= end {
+            return None;
+        }
+
+        // Parsing a fragment wraps it in nodes like `source_file` or
+        // `expression_statement` (or `ERROR`, when it isn't valid on its own),
+        // so the pattern is the innermost node that still spans all of it.
+        let mut node = tree.root_node();
+        loop {
+            if self
+                .placeholders
+                .contains_key(&self.parseable[node.byte_range()])
+            {
+                break;
+            }
+            let mut cursor = node.walk();
+            let mut children = node
+                .children(&mut cursor)
+                .filter(|child| !child.is_extra() && child.start_byte() < child.end_byte());
+            let Some(child) = children.next() else {
+                break;
+            };
+            if children.next().is_some() || child.start_byte() > start || child.end_byte() < end {
+                break;
+            }
+            node = child;
+        }
+
+        let pattern = self.pattern_node(node);
+        // A lone metavariable would match every node in the file.
+        pattern.kind()?;
+        Some(pattern)
+    }
+
+    fn pattern_node(&self, node: Node) -> PatternNode {
+        let text = &self.parseable[node.byte_range()];
+        if let Some(metavariable) = self.placeholders.get(text) {
+            return PatternNode::Metavariable(metavariable.clone());
+        }
+
+        let mut cursor = node.walk();
+        let children = significant_children(node, &mut cursor);
+        if children.is_empty() {
+            PatternNode::Leaf {
+                kind: node.kind_id(),
+                text: text.to_string(),
+            }
+        } else {
+            PatternNode::Node {
+                kind: node.kind_id(),
+                children: children
+                    .into_iter()
+                    .map(|child| self.pattern_node(child))
+                    .collect(),
+            }
+        }
+    }
+
+    /// Returns the ranges of the nodes matching this pattern. Matches don't
+    /// overlap: nodes within a match aren't searched.
+    pub fn search(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut matches = Vec::new();
+        for layer in buffer.syntax_layers() {
+            let Some(pattern) = self.compiled(layer.language) else {
+                continue;
+            };
+            let matcher = Matcher { buffer };
+
+            let mut cursor = layer.node().walk();
+            'walk: loop {
+                let node = cursor.node();
+                let mut descend = node.start_byte() < range.end && node.end_byte() > range.start;
+                if descend
+                    && node.start_byte() >= range.start
+                    && node.end_byte() <= range.end
+                    && pattern.kind() == Some(node.kind_id())
+                    && matcher.matches(&pattern, node).is_some()
+                {
+                    matches.push(node.byte_range());
+                    descend = false;
+                }
+
+                if descend && cursor.goto_first_child() {
+                    continue;
+                }
+                while !cursor.goto_next_sibling() {
+                    if !cursor.goto_parent() {
+                        break 'walk;
+                    }
+                }
+            }
+        }
+
+        matches.sort_by_key(|range| (range.start, std::cmp::Reverse(range.end)));
+        let mut last_end = 0;
+        matches.retain(|range| {
+            let keep = range.start >= last_end;
+            if keep {
+                last_end = range.end;
+            }
+            keep
+        });
+        matches
+    }
+
+    /// Expands the replacement template for the match at the given range,
+    /// substituting the text captured by each metavariable.
+    pub fn replacement_for(
+        &self,
+        buffer: &BufferSnapshot,
+        range: Range<usize>,
+        template: &str,
+    ) -> Option<String> {
+        let bindings = buffer.syntax_layers().find_map(|layer| {
+            let pattern = self.compiled(layer.language)?;
+            let matcher = Matcher { buffer };
+            let mut node = layer
+                .node()
+                .descendant_for_byte_range(range.start, range.end)?;
+            loop {
+                if node.byte_range() != range {
+                    return None;
+                }
+                if pattern.kind() == Some(node.kind_id()) {
+                    if let Some(bindings) = matcher.matches(&pattern, node) {
+                        return Some(bindings);
+                    }
+                }
+                node = node.parent()?;
+            }
+        })?;
+
+        let replacement = METAVARIABLE.replace_all(template, |captures: &Captures| {
+            let name = &captures[2];
+            if name == WILDCARD {
+                return captures[0].to_string();
+            }
+            let metavariable = if captures.get(1).is_some() {
+                Metavariable::Multiple(Arc::from(name))
+            } else {
+                Metavariable::Single(Arc::from(name))
+            };
+            match bindings.get(&metavariable) {
+                Some(range) => buffer.text_for_range(range.clone()).collect(),
+                None => captures[0].to_string(),
+            }
+        });
+        Some(replacement.into_owned())
+    }
+}
+
+type Bindings = HashMap<Metavariable, Range<usize>>;
+
+struct Matcher<'a> {
+    buffer: &'a BufferSnapshot,
+}
+
+impl Matcher<'_> {
+    fn matches(&self, pattern: &PatternNode, node: Node) -> Option<Bindings> {
+        let mut bindings = Bindings::default();
+        self.match_node(pattern, node, &mut bindings)
+            .then_some(bindings)
+    }
+
+    fn match_node(&self, pattern: &PatternNode, node: Node, bindings: &mut Bindings) -> bool {
+        match pattern {
+            PatternNode::Metavariable(metavariable) => {
+                self.capture(metavariable, node.byte_range(), bindings)
+            }
+            PatternNode::Leaf { kind, text } => {
+                *kind == node.kind_id()
+                    && node.byte_range().len() == text.len()
+                    && self.text(node.byte_range()) == *text
+            }
+            PatternNode::Node { kind, children } => {
+                if *kind != node.kind_id() {
+                    return false;
+                }
+                let mut cursor = node.walk();
+                let nodes = significant_children(node, &mut cursor);
+                self.match_children(children, &nodes, bindings)
+            }
+        }
+    }
+
+    fn match_children(
+        &self,
+        patterns: &[PatternNode],
+        nodes: &[Node],
+        bindings: &mut Bindings,
+    ) -> bool {
+        let Some((pattern, rest)) = patterns.split_first() else {
+            return nodes.is_empty();
+        };
+
+        if let PatternNode::Metavariable(metavariable @ Metavariable::Multiple(_)) = pattern {
+            for count in 0..=nodes.len() {
+                let range = match (nodes.first(), count.checked_sub(1)) {
+                    (Some(first), Some(last)) => first.start_byte()..nodes[last].end_byte(),
+                    (Some(first), None) => first.start_byte()..first.start_byte(),
+                    (None, _) => 0..0,
+                };
+                let mut attempt = bindings.clone();
+                if self.capture(metavariable, range, &mut attempt)
+                    && self.match_children(rest, &nodes[count..], &mut attempt)
+                {
+                    *bindings = attempt;
+                    return true;
+                }
+            }
+            return false;
+        }
+
+        let Some((node, nodes)) = nodes.split_first() else {
+            return false;
+        };
+        let mut attempt = bindings.clone();
+        if self.match_node(pattern, *node, &mut attempt)
+            && self.match_children(rest, nodes, &mut attempt)
+        {
+            *bindings = attempt;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn capture(
+        &self,
+        metavariable: &Metavariable,
+        range: Range<usize>,
+        bindings: &mut Bindings,
+    ) -> bool {
+        let (Metavariable::Single(name) | Metavariable::Multiple(name)) = metavariable;
+        if name.as_ref() == WILDCARD {
+            return true;
+        }
+        match bindings.get(metavariable) {
+            Some(captured) => self.text(captured.clone()) == self.text(range),
+            None => {
+                bindings.insert(metavariable.clone(), range);
+                true
+            }
+        }
+    }
+
+    fn text(&self, range: Range<usize>) -> String {
+        self.buffer.text_for_range(range).collect()
+    }
+}
+
+/// Returns the children of a node that a pattern has to account for, leaving
+/// out comments and nodes inserted by the parser to recover from errors.
+fn significant_children<'a>(
+    node: Node<'a>,
+    cursor: &mut language::TreeCursor<'a>,
+) -> Vec<Node<'a>> {
+    node.children(cursor)
+        .filter(|child| !child.is_extra() && !child.is_missing())
+        .collect()
+}
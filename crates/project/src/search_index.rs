@@ -0,0 +1,340 @@
+//! An optional trigram index of the files in each local worktree, which lets
+//! project search skip files that can't contain a match without reading them.
+//! Each index maps trigrams to the files containing them, so a search only
+//! intersects the posting lists of its trigrams instead of checking every file.
+//!
+//! The index is kept up to date from worktree events and persisted across
+//! restarts. It's only ever used to rule files out: a file that changed since
+//! it was indexed is always searched, so results are the same with or without
+//! the index.
+mod persistence;
+mod trigrams;
+
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use fs::Fs;
+use futures::{
+    FutureExt as _, StreamExt as _,
+    channel::mpsc,
+    future::{self, Shared},
+};
+use gpui::{AppContext as _, AsyncApp, Context, Entity, Subscription, Task, WeakEntity};
+use parking_lot::RwLock;
+use settings::{Settings as _, SettingsStore};
+use std::{mem, path::Path, sync::Arc};
+use util::ResultExt as _;
+use worktree::{Entry, Worktree, WorktreeId};
+
+use crate::{
+    project_settings::ProjectSettings,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
+};
+use persistence::{IndexedFileUpdate, SearchIndexDb};
+
+pub use trigrams::{FileId, Trigram, TrigramIndex, TrigramMatches, TrigramQuery, trigrams};
+
+/// Larger files are never indexed, and so are always searched.
+const MAX_INDEXED_FILE_SIZE: u64 = 10 * 1024 * 1024;
+const MAX_CONCURRENT_FILE_READS: usize = 64;
+/// How many bytes of file contents may be read at once while indexing.
+const MAX_CONCURRENT_READ_BYTES: u64 = 64 * 1024 * 1024;
+const INDEX_BATCH_SIZE: usize = 512;
+
+struct WorktreeIndex {
+    index: Arc<RwLock<TrigramIndex>>,
+    changed_paths_tx: mpsc::UnboundedSender<Arc<Path>>,
+    _maintain: Task<()>,
+}
+
+pub struct SearchIndexStore {
+    worktree_store: Entity<WorktreeStore>,
+    fs: Arc<dyn Fs>,
+    enabled: bool,
+    db: Option<Shared<Task<Option<SearchIndexDb>>>>,
+    indices: HashMap<WorktreeId, WorktreeIndex>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl SearchIndexStore {
+    pub fn local(
+        worktree_store: Entity<WorktreeStore>,
+        fs: Arc<dyn Fs>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let this = cx.weak_entity();
+        worktree_store.update(cx, |worktree_store, _| {
+            worktree_store.set_search_index_store(this);
+        });
+        let subscriptions = vec![
+            cx.subscribe(&worktree_store, Self::on_worktree_store_event),
+            cx.observe_global::<SettingsStore>(|this, cx| {
+                let enabled = ProjectSettings::get_global(cx).search_index.enabled;
+                this.set_enabled(enabled, cx);
+            }),
+        ];
+        let mut this = Self {
+            worktree_store,
+            fs,
+            enabled: false,
+            db: None,
+            indices: HashMap::default(),
+            _subscriptions: subscriptions,
+        };
+        this.set_enabled(ProjectSettings::get_global(cx).search_index.enabled, cx);
+        this
+    }
+
+    /// Returns the index of the given worktree, if it's being indexed.
+    pub fn index_for_worktree(&self, worktree_id: WorktreeId) -> Option<Arc<RwLock<TrigramIndex>>> {
+        self.indices
+            .get(&worktree_id)
+            .map(|worktree_index| worktree_index.index.clone())
+    }
+
+    fn set_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        if self.enabled == enabled {
+            return;
+        }
+        self.enabled = enabled;
+        if enabled {
+            let worktrees = self.worktree_store.read(cx).worktrees().collect::<Vec<_>>();
+            for worktree in worktrees {
+                self.index_worktree(worktree, cx);
+            }
+        } else {
+            self.indices.clear();
+        }
+    }
+
+    fn on_worktree_store_event(
+        &mut self,
+        _: Entity<WorktreeStore>,
+        event: &WorktreeStoreEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            WorktreeStoreEvent::WorktreeAdded(worktree) if self.enabled => {
+                self.index_worktree(worktree.clone(), cx);
+            }
+            WorktreeStoreEvent::WorktreeRemoved(_, worktree_id)
+            | WorktreeStoreEvent::WorktreeReleased(_, worktree_id) => {
+                self.indices.remove(worktree_id);
+            }
+            WorktreeStoreEvent::WorktreeUpdatedEntries(worktree_id, changes) => {
+                if let Some(worktree_index) = self.indices.get(worktree_id) {
+                    for (path, _, _) in changes.iter() {
+                        worktree_index
+                            .changed_paths_tx
+                            .unbounded_send(path.clone())
+                            .ok();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn db(&mut self, cx: &mut Context<Self>) -> Shared<Task<Option<SearchIndexDb>>> {
+        let fs = self.fs.clone();
+        self.db
+            .get_or_insert_with(|| {
+                cx.background_spawn(async move {
+                    // Tests run against a fake file system, whose contents
+                    // shouldn't outlive them.
+                    if fs.is_fake() {
+                        return None;
+                    }
+                    let dir = paths::search_index_dir();
+                    fs.create_dir(dir).await.log_err()?;
+                    let path = dir.join("index.db");
+                    SearchIndexDb::open(&path.to_string_lossy(), true)
+                        .await
+                        .log_err()
+                })
+                .shared()
+            })
+            .clone()
+    }
+
+    fn index_worktree(&mut self, worktree: Entity<Worktree>, cx: &mut Context<Self>) {
+        let (worktree_id, scan_complete) = {
+            let worktree = worktree.read(cx);
+            let Some(local_worktree) = worktree.as_local() else {
+                return;
+            };
+            if !worktree.is_visible() {
+                return;
+            }
+            (worktree.id(), local_worktree.scan_complete())
+        };
+
+        let index = Arc::new(RwLock::new(TrigramIndex::default()));
+        let (changed_paths_tx, changed_paths_rx) = mpsc::unbounded();
+        let db = self.db(cx);
+        let maintain = cx.spawn({
+            let index = index.clone();
+            let fs = self.fs.clone();
+            let worktree = worktree.downgrade();
+            async move |_, cx| {
+                scan_complete.await;
+                let db = db.await;
+                Self::maintain_index(worktree, index, db, changed_paths_rx, fs, cx)
+                    .await
+                    .log_err();
+            }
+        });
+        self.indices.insert(
+            worktree_id,
+            WorktreeIndex {
+                index,
+                changed_paths_tx,
+                _maintain: maintain,
+            },
+        );
+    }
+
+    async fn maintain_index(
+        worktree: WeakEntity<Worktree>,
+        index: Arc<RwLock<TrigramIndex>>,
+        db: Option<SearchIndexDb>,
+        mut changed_paths_rx: mpsc::UnboundedReceiver<Arc<Path>>,
+        fs: Arc<dyn Fs>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let snapshot = worktree.read_with(cx, |worktree, _| worktree.snapshot())?;
+
+        // Reconcile the persisted index with the worktree as it is now, which
+        // reindexes files that changed while Zed wasn't running.
+        cx.background_spawn({
+            let index = index.clone();
+            let db = db.clone();
+            let fs = fs.clone();
+            async move {
+                if let Some(db) = &db
+                    && let Some(persisted) = db.load(snapshot.abs_path()).log_err()
+                {
+                    *index.write() = persisted;
+                }
+                let paths = snapshot
+                    .files(false, 0)
+                    .map(|entry| entry.path.clone())
+                    .chain(index.read().paths().cloned())
+                    .collect::<HashSet<_>>();
+                Self::update_index(&snapshot, paths, &index, db.as_ref(), &fs).await;
+            }
+        })
+        .await;
+
+        while let Some(path) = changed_paths_rx.next().await {
+            let mut paths = HashSet::from_iter([path]);
+            while let Ok(Some(path)) = changed_paths_rx.try_next() {
+                paths.insert(path);
+            }
+            let snapshot = worktree.read_with(cx, |worktree, _| worktree.snapshot())?;
+            cx.background_spawn({
+                let index = index.clone();
+                let db = db.clone();
+                let fs = fs.clone();
+                async move {
+                    Self::update_index(&snapshot, paths, &index, db.as_ref(), &fs).await;
+                }
+            })
+            .await;
+        }
+        Ok(())
+    }
+
+    /// Brings the index up to date with the given paths of the snapshot,
+    /// reading any files that changed since they were indexed and dropping
+    /// ones that no longer exist.
+    async fn update_index(
+        snapshot: &worktree::Snapshot,
+        paths: HashSet<Arc<Path>>,
+        index: &RwLock<TrigramIndex>,
+        db: Option<&SearchIndexDb>,
+        fs: &Arc<dyn Fs>,
+    ) {
+        let mut to_index = Vec::new();
+        let mut removed = Vec::new();
+        {
+            let mut index = index.write();
+            for path in paths {
+                match snapshot.entry_for_path(&path) {
+                    Some(entry) if entry.is_dir() => {}
+                    Some(entry) if is_indexable(entry) => {
+                        if let Some(mtime) = entry.mtime
+                            && !index.is_current(&entry.path, Some(mtime))
+                        {
+                            to_index.push((entry.path.clone(), mtime, entry.size));
+                        }
+                    }
+                    _ => removed.extend(index.remove(&path)),
+                }
+            }
+        }
+        to_index.sort_unstable_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+        let worktree_path = snapshot.abs_path().clone();
+        let mut to_index = to_index.into_iter().peekable();
+        let mut indexed = Vec::new();
+        while to_index.peek().is_some() {
+            // Read files concurrently, but bound how much of their contents is held in
+            // memory at once.
+            let mut reads = Vec::new();
+            let mut read_bytes = 0;
+            while let Some((_, _, size)) = to_index.peek()
+                && reads.len() < MAX_CONCURRENT_FILE_READS
+                && (reads.is_empty() || read_bytes + size <= MAX_CONCURRENT_READ_BYTES)
+            {
+                read_bytes += size;
+                let (path, mtime, _) = to_index.next().unwrap();
+                let abs_path = worktree_path.join(&path);
+                reads.push(async move {
+                    let content = fs
+                        .load_bytes(&abs_path)
+                        .await
+                        .log_with_level(log::Level::Debug)?;
+                    Some((path, mtime, Arc::<[Trigram]>::from(trigrams(&content))))
+                });
+            }
+
+            let files = future::join_all(reads).await;
+            {
+                let mut index = index.write();
+                for (path, mtime, trigrams) in files.into_iter().flatten() {
+                    let id = index.insert(path.clone(), mtime, &trigrams);
+                    indexed.push(IndexedFileUpdate {
+                        path,
+                        id,
+                        mtime,
+                        trigrams,
+                    });
+                }
+            }
+            if let Some(db) = db
+                && (indexed.len() >= INDEX_BATCH_SIZE || to_index.peek().is_none())
+            {
+                db.save(
+                    worktree_path.clone(),
+                    mem::take(&mut indexed),
+                    mem::take(&mut removed),
+                )
+                .await
+                .log_err();
+            }
+        }
+        if let Some(db) = db
+            && !removed.is_empty()
+        {
+            db.save(worktree_path, Vec::new(), removed).await.log_err();
+        }
+    }
+}
+
+fn is_indexable(entry: &Entry) -> bool {
+    entry.is_file()
+        && !entry.is_fifo
+        && !entry.is_ignored
+        && !entry.is_external
+        && entry.size <= MAX_INDEXED_FILE_SIZE
+}
@@ -0,0 +1,222 @@
+use anyhow::Result;
+use fs::MTime;
+use sqlez::{domain::Domain, thread_safe_connection::ThreadSafeConnection};
+use std::{path::Path, sync::Arc};
+
+use super::trigrams::{FileId, Trigram, TrigramIndex};
+
+const DB_INITIALIZE_QUERY: &str = "PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;";
+
+/// A file that was read into the index, along with its sorted trigrams.
+pub struct IndexedFileUpdate {
+    pub path: Arc<Path>,
+    pub id: FileId,
+    pub mtime: MTime,
+    pub trigrams: Arc<[Trigram]>,
+}
+
+struct SearchIndexDomain;
+
+impl Domain for SearchIndexDomain {
+    fn name() -> &'static str {
+        "search_index"
+    }
+
+    fn migrations() -> &'static [&'static str] {
+        &["CREATE TABLE indexed_worktrees(
+            worktree_id INTEGER PRIMARY KEY,
+            worktree_path BLOB NOT NULL UNIQUE
+        ) STRICT;
+
+        CREATE TABLE indexed_files(
+            worktree_id INTEGER NOT NULL,
+            path BLOB NOT NULL,
+            file_id INTEGER NOT NULL,
+            mtime_seconds INTEGER NOT NULL,
+            mtime_nanos INTEGER NOT NULL,
+            PRIMARY KEY(worktree_id, path)
+        ) STRICT;
+
+        CREATE TABLE trigram_postings(
+            worktree_id INTEGER NOT NULL,
+            trigram INTEGER NOT NULL,
+            file_id INTEGER NOT NULL,
+            PRIMARY KEY(worktree_id, trigram, file_id)
+        ) STRICT, WITHOUT ROWID;
+
+        CREATE INDEX trigram_postings_by_file ON trigram_postings(worktree_id, file_id);"]
+    }
+}
+
+/// The postings of every indexed worktree, keyed by the worktree's absolute path.
+///
+/// Each posting is its own row, so that indexing a file only writes the postings of
+/// that file.
+#[derive(Clone)]
+pub struct SearchIndexDb(ThreadSafeConnection);
+
+impl SearchIndexDb {
+    /// Opens the database at the given path, or an in-memory database with
+    /// the given name when `persistent` is false.
+    pub async fn open(uri: &str, persistent: bool) -> Result<Self> {
+        let connection = ThreadSafeConnection::builder::<SearchIndexDomain>(uri, persistent)
+            .with_db_initialization_query(DB_INITIALIZE_QUERY)
+            .build()
+            .await?;
+        Ok(Self(connection))
+    }
+
+    pub fn load(&self, worktree_path: &Path) -> Result<TrigramIndex> {
+        let Some(worktree_id) = self.0.select_row_bound::<&Path, i64>(
+            "SELECT worktree_id FROM indexed_worktrees WHERE worktree_path = ?",
+        )?(worktree_path)?
+        else {
+            return Ok(TrigramIndex::default());
+        };
+        let files = self.0.select_bound::<i64, (Arc<Path>, FileId, u64, u32)>(
+            "SELECT path, file_id, mtime_seconds, mtime_nanos
+             FROM indexed_files
+             WHERE worktree_id = ?",
+        )?(worktree_id)?;
+        let postings = self.0.select_bound::<i64, (Trigram, FileId)>(
+            "SELECT trigram, file_id
+             FROM trigram_postings
+             WHERE worktree_id = ?
+             ORDER BY trigram, file_id",
+        )?(worktree_id)?;
+        Ok(TrigramIndex::from_persisted(
+            files.into_iter().map(|(path, id, seconds, nanos)| {
+                (path, id, MTime::from_seconds_and_nanos(seconds, nanos))
+            }),
+            postings,
+        ))
+    }
+
+    /// Replaces the postings of the given files, and removes the files at the removed
+    /// paths.
+    pub async fn save(
+        &self,
+        worktree_path: Arc<Path>,
+        files: Vec<IndexedFileUpdate>,
+        removed: Vec<Arc<Path>>,
+    ) -> Result<()> {
+        self.0
+            .write(move |connection| {
+                connection.with_savepoint("save_search_index", || {
+                    connection.exec_bound::<&Path>(
+                        "INSERT OR IGNORE INTO indexed_worktrees (worktree_path) VALUES (?)",
+                    )?(&worktree_path)?;
+                    let worktree_id = connection.select_row_bound::<&Path, i64>(
+                        "SELECT worktree_id FROM indexed_worktrees WHERE worktree_path = ?",
+                    )?(&worktree_path)?
+                    .ok_or_else(|| anyhow::anyhow!("search index worktree wasn't saved"))?;
+
+                    let mut delete_postings = connection.exec_bound::<(i64, Arc<Path>)>(
+                        "DELETE FROM trigram_postings
+                         WHERE worktree_id = ?1 AND file_id IN (
+                             SELECT file_id FROM indexed_files WHERE worktree_id = ?1 AND path = ?2
+                         )",
+                    )?;
+                    let mut delete_file = connection.exec_bound::<(i64, Arc<Path>)>(
+                        "DELETE FROM indexed_files WHERE worktree_id = ? AND path = ?",
+                    )?;
+                    for path in removed.iter().chain(files.iter().map(|file| &file.path)) {
+                        delete_postings((worktree_id, path.clone()))?;
+                        delete_file((worktree_id, path.clone()))?;
+                    }
+
+                    let mut insert_file = connection
+                        .exec_bound::<(i64, Arc<Path>, FileId, u64, u32)>(
+                            "INSERT INTO indexed_files
+                             (worktree_id, path, file_id, mtime_seconds, mtime_nanos)
+                             VALUES (?, ?, ?, ?, ?)",
+                        )?;
+                    let mut insert_posting = connection.exec_bound::<(i64, Trigram, FileId)>(
+                        "INSERT OR IGNORE INTO trigram_postings (worktree_id, trigram, file_id)
+                         VALUES (?, ?, ?)",
+                    )?;
+                    for file in &files {
+                        let Some((seconds, nanos)) =
+                            file.mtime.to_seconds_and_nanos_for_persistence()
+                        else {
+                            continue;
+                        };
+                        insert_file((worktree_id, file.path.clone(), file.id, seconds, nanos))?;
+                        for trigram in file.trigrams.iter() {
+                            insert_posting((worktree_id, *trigram, file.id))?;
+                        }
+                    }
+                    Ok(())
+                })
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_index::{TrigramQuery, trigrams};
+
+    #[gpui::test]
+    async fn test_save_and_load() {
+        let db = SearchIndexDb::open("test_save_and_load", false)
+            .await
+            .unwrap();
+        let worktree_path = Arc::<Path>::from(Path::new("/project"));
+        let mtime = MTime::from_seconds_and_nanos(100, 0);
+        let file = |path: &str, id: FileId, content: &[u8]| IndexedFileUpdate {
+            path: Path::new(path).into(),
+            id,
+            mtime,
+            trigrams: trigrams(content).into(),
+        };
+        let may_match = |index: &TrigramIndex, path: &str, query: &[u8]| {
+            let query = TrigramQuery::AnyOf(vec![trigrams(query)]);
+            index.may_match(Path::new(path), Some(mtime), &index.matches(&query))
+        };
+
+        db.save(
+            worktree_path.clone(),
+            vec![
+                file("a.rs", 0, b"fn compute_total()"),
+                file("b.rs", 1, b"let total = 0;"),
+                file("c.rs", 2, b"fn main()"),
+            ],
+            Vec::new(),
+        )
+        .await
+        .unwrap();
+        let index = db.load(&worktree_path).unwrap();
+        assert!(may_match(&index, "a.rs", b"compute_total"));
+        assert!(!may_match(&index, "b.rs", b"compute_total"));
+        assert!(may_match(&index, "b.rs", b"total"));
+        assert!(
+            db.load(Path::new("/other"))
+                .unwrap()
+                .paths()
+                .next()
+                .is_none()
+        );
+
+        // Reindexed files replace their old postings, and removed files lose theirs.
+        db.save(
+            worktree_path.clone(),
+            vec![file("a.rs", 3, b"fn compute_sum()")],
+            vec![Path::new("b.rs").into()],
+        )
+        .await
+        .unwrap();
+        let index = db.load(&worktree_path).unwrap();
+        assert!(!may_match(&index, "a.rs", b"compute_total"));
+        assert!(may_match(&index, "a.rs", b"compute_sum"));
+        assert!(!may_match(&index, "c.rs", b"total"));
+        assert_eq!(
+            index
+                .paths()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>(),
+            ["a.rs", "c.rs"]
+        );
+    }
+}
@@ -0,0 +1,435 @@
+use collections::{BTreeMap, HashMap, HashSet};
+use fs::MTime;
+use regex_syntax::hir::literal::{ExtractKind, Extractor};
+use std::{ops::Bound, path::Path, sync::Arc};
+
+use crate::search::SearchQuery;
+
+/// Three consecutive bytes of a file, ASCII-lowercased and packed into the low
+/// 24 bits.
+pub type Trigram = u32;
+
+/// Returns the sorted, deduplicated trigrams of the given bytes.
+///
+/// Bytes are ASCII-lowercased so that one index can serve both case-sensitive
+/// and case-insensitive queries.
+pub fn trigrams(content: &[u8]) -> Vec<Trigram> {
+    let mut trigrams = content
+        .windows(3)
+        .map(|window| {
+            u32::from_be_bytes([
+                0,
+                window[0].to_ascii_lowercase(),
+                window[1].to_ascii_lowercase(),
+                window[2].to_ascii_lowercase(),
+            ])
+        })
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    trigrams.sort_unstable();
+    trigrams
+}
+
+/// The trigrams a file must contain to possibly match a search query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrigramQuery {
+    /// Every file may match, so the index can't be used to skip any.
+    All,
+    /// A file may only match if it contains every trigram of at least one of
+    /// these sets. Each set is sorted.
+    AnyOf(Vec<Vec<Trigram>>),
+}
+
+impl TrigramQuery {
+    pub fn new(query: &SearchQuery) -> Self {
+        match query {
+            SearchQuery::Text { .. } => Self::from_literals([query.as_str().as_bytes()]),
+            SearchQuery::Regex {
+                regex,
+                case_sensitive,
+                ..
+            } => match required_literals(regex.as_str(), *case_sensitive) {
+                Some(literals) => Self::from_literals(literals.iter().map(Vec::as_slice)),
+                None => Self::All,
+            },
+            // Structural patterns match syntax trees, whose text may be spelled
+            // in many ways.
+            SearchQuery::Structural { .. } => Self::All,
+        }
+    }
+
+    /// Builds a query matching the files that contain any of the literals.
+    fn from_literals<'a>(literals: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        let mut sets = Vec::new();
+        for literal in literals {
+            let set = trigrams(literal);
+            if set.is_empty() {
+                return Self::All;
+            }
+            sets.push(set);
+        }
+        Self::AnyOf(sets)
+    }
+
+    pub fn is_all(&self) -> bool {
+        matches!(self, Self::All)
+    }
+
+    /// Whether a file with the given sorted trigrams may match.
+    pub fn matches(&self, file_trigrams: &[Trigram]) -> bool {
+        match self {
+            Self::All => true,
+            Self::AnyOf(sets) => sets.iter().any(|set| {
+                set.iter()
+                    .all(|trigram| file_trigrams.binary_search(trigram).is_ok())
+            }),
+        }
+    }
+}
+
+/// Returns literals such that every match of the regex contains at least one
+/// of them, or `None` if there's no such set that's small enough to be useful.
+///
+/// Patterns using features that only `fancy_regex` supports, such as
+/// lookaround or backreferences, don't parse and yield `None`.
+fn required_literals(pattern: &str, case_sensitive: bool) -> Option<Vec<Vec<u8>>> {
+    let hir = regex_syntax::ParserBuilder::new()
+        .case_insensitive(!case_sensitive)
+        .build()
+        .parse(pattern)
+        .ok()?;
+
+    // Every match starts with one of the prefixes and ends with one of the
+    // suffixes, so either set will do. Prefer the one whose shortest literal
+    // is longest, as it yields the most selective trigrams.
+    [ExtractKind::Prefix, ExtractKind::Suffix]
+        .into_iter()
+        .filter_map(|kind| {
+            let seq = Extractor::new().kind(kind).extract(&hir);
+            let literals = seq
+                .literals()?
+                .iter()
+                .map(|literal| literal.as_bytes().to_vec())
+                .collect::<Vec<_>>();
+            let shortest = literals.iter().map(Vec::len).min().unwrap_or(usize::MAX);
+            Some((shortest, literals))
+        })
+        .max_by_key(|(shortest, _)| *shortest)
+        .map(|(_, literals)| literals)
+}
+
+/// Identifies an indexed version of a file. Reindexing a file gives it a new id, so ids
+/// only ever grow.
+pub type FileId = u32;
+
+struct IndexedFile {
+    id: FileId,
+    mtime: MTime,
+}
+
+/// An inverted index from each trigram to the files of a worktree that contain it, as of
+/// the modification time each file was read at.
+///
+/// A file whose current modification time differs from the indexed one is always treated
+/// as a possible match, so a lagging index never hides results.
+#[derive(Default)]
+pub struct TrigramIndex {
+    /// Ordered by path, so that the files beneath a directory are contiguous.
+    files: BTreeMap<Arc<Path>, IndexedFile>,
+    /// The ids of the files containing each trigram, in ascending order. Ids of files that
+    /// were removed or reindexed since are only dropped when the index is compacted.
+    postings: HashMap<Trigram, Vec<FileId>>,
+    next_file_id: FileId,
+    /// How many files were removed or reindexed since the index was last compacted.
+    stale_files: usize,
+}
+
+/// The indexed files that may match a query, as of when it was run against the index.
+pub struct TrigramMatches {
+    /// `None` when every file may match.
+    files: Option<HashSet<FileId>>,
+    /// Files indexed after the query ran have an id of at least this.
+    next_file_id: FileId,
+}
+
+impl TrigramMatches {
+    fn contains(&self, id: FileId) -> bool {
+        id >= self.next_file_id || self.files.as_ref().is_none_or(|files| files.contains(&id))
+    }
+}
+
+impl TrigramIndex {
+    /// Rebuilds an index from the files and postings it was persisted as.
+    pub fn from_persisted(
+        files: impl IntoIterator<Item = (Arc<Path>, FileId, MTime)>,
+        postings: impl IntoIterator<Item = (Trigram, FileId)>,
+    ) -> Self {
+        let mut index = Self::default();
+        for (path, id, mtime) in files {
+            index.next_file_id = index.next_file_id.max(id + 1);
+            index.files.insert(path, IndexedFile { id, mtime });
+        }
+        let indexed_ids = index.indexed_ids();
+        for (trigram, id) in postings {
+            if indexed_ids.contains(&id) {
+                index.postings.entry(trigram).or_default().push(id);
+            }
+        }
+        for ids in index.postings.values_mut() {
+            ids.sort_unstable();
+            ids.dedup();
+        }
+        index
+    }
+
+    /// Indexes the file at the given path by its sorted trigrams, replacing any earlier
+    /// version of it. Returns the id the file was indexed under.
+    pub fn insert(&mut self, path: Arc<Path>, mtime: MTime, trigrams: &[Trigram]) -> FileId {
+        let id = self.next_file_id;
+        self.next_file_id += 1;
+        for trigram in trigrams {
+            self.postings.entry(*trigram).or_default().push(id);
+        }
+        if self.files.insert(path, IndexedFile { id, mtime }).is_some() {
+            self.files_became_stale(1);
+        }
+        id
+    }
+
+    /// Removes the file at the given path, along with any files beneath it.
+    /// Returns the paths that were removed.
+    pub fn remove(&mut self, path: &Path) -> Vec<Arc<Path>> {
+        let removed = self
+            .files
+            .range::<Path, _>((Bound::Included(path), Bound::Unbounded))
+            .map(|(indexed_path, _)| indexed_path)
+            .take_while(|indexed_path| indexed_path.starts_with(path))
+            .cloned()
+            .collect::<Vec<_>>();
+        for path in &removed {
+            self.files.remove(path);
+        }
+        self.files_became_stale(removed.len());
+        removed
+    }
+
+    /// Compacts the postings once they're mostly made up of files that are no longer
+    /// indexed.
+    fn files_became_stale(&mut self, count: usize) {
+        self.stale_files += count;
+        if self.stale_files > self.files.len() {
+            let indexed_ids = self.indexed_ids();
+            self.postings.retain(|_, ids| {
+                ids.retain(|id| indexed_ids.contains(id));
+                !ids.is_empty()
+            });
+            self.stale_files = 0;
+        }
+    }
+
+    fn indexed_ids(&self) -> HashSet<FileId> {
+        self.files.values().map(|file| file.id).collect()
+    }
+
+    /// Whether the file at the given path is indexed as of the given
+    /// modification time.
+    pub fn is_current(&self, path: &Path, mtime: Option<MTime>) -> bool {
+        self.files
+            .get(path)
+            .is_some_and(|file| Some(file.mtime) == mtime)
+    }
+
+    /// Returns the indexed files that may match the query, by intersecting the postings of
+    /// the trigrams in each of its sets.
+    pub fn matches(&self, query: &TrigramQuery) -> TrigramMatches {
+        let files = match query {
+            TrigramQuery::All => None,
+            TrigramQuery::AnyOf(sets) => Some(
+                sets.iter()
+                    .flat_map(|set| self.files_containing_all(set))
+                    .collect(),
+            ),
+        };
+        TrigramMatches {
+            files,
+            next_file_id: self.next_file_id,
+        }
+    }
+
+    fn files_containing_all(&self, trigrams: &[Trigram]) -> Vec<FileId> {
+        let mut postings = Vec::with_capacity(trigrams.len());
+        for trigram in trigrams {
+            match self.postings.get(trigram) {
+                Some(ids) => postings.push(ids.as_slice()),
+                None => return Vec::new(),
+            }
+        }
+        postings.sort_unstable_by_key(|ids| ids.len());
+        let Some((shortest, rest)) = postings.split_first() else {
+            return Vec::new();
+        };
+        shortest
+            .iter()
+            .copied()
+            .filter(|id| rest.iter().all(|ids| ids.binary_search(id).is_ok()))
+            .collect()
+    }
+
+    /// Whether the file at the given path, last modified at `mtime`, may be one of the
+    /// matches of a query.
+    pub fn may_match(&self, path: &Path, mtime: Option<MTime>, matches: &TrigramMatches) -> bool {
+        match self.files.get(path) {
+            Some(file) if Some(file.mtime) == mtime => matches.contains(file.id),
+            _ => true,
+        }
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &Arc<Path>> {
+        self.files.keys()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::paths::PathMatcher;
+
+    fn text_query(query: &str, case_sensitive: bool) -> TrigramQuery {
+        TrigramQuery::new(
+            &SearchQuery::text(
+                query,
+                false,
+                case_sensitive,
+                false,
+                PathMatcher::default(),
+                PathMatcher::default(),
+                false,
+                None,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn regex_query(query: &str, case_sensitive: bool) -> TrigramQuery {
+        TrigramQuery::new(
+            &SearchQuery::regex(
+                query,
+                false,
+                case_sensitive,
+                false,
+                false,
+                PathMatcher::default(),
+                PathMatcher::default(),
+                false,
+                None,
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_text_queries() {
+        let file = trigrams(b"fn main() {\n    println!(\"Hello\");\n}\n");
+
+        assert!(text_query("println", true).matches(&file));
+        assert!(text_query("HELLO", false).matches(&file));
+        // The index is case-insensitive, so case-sensitive queries may
+        // yield extra candidates, but never fewer.
+        assert!(text_query("HELLO", true).matches(&file));
+        assert!(!text_query("goodbye", true).matches(&file));
+        assert_eq!(text_query("fn", true), TrigramQuery::All);
+        assert_eq!(text_query("", true), TrigramQuery::All);
+    }
+
+    #[test]
+    fn test_regex_queries() {
+        let file = trigrams(b"let value = compute_total(items);\n");
+
+        assert!(regex_query(r"compute_\w+\(", true).matches(&file));
+        assert!(regex_query(r"(total|sum)\(", true).matches(&file));
+        assert!(!regex_query(r"(average|sum)\(", true).matches(&file));
+        assert!(regex_query(r"VALUE\s*=", false).matches(&file));
+        assert!(!regex_query(r"missing\s*=", false).matches(&file));
+
+        assert_eq!(regex_query(r"\w+", true), TrigramQuery::All);
+        assert_eq!(regex_query(r"a.b", true), TrigramQuery::All);
+        // Lookaround is only supported by fancy_regex.
+        assert_eq!(regex_query(r"(?<=let )value", true), TrigramQuery::All);
+    }
+
+    #[test]
+    fn test_stale_files_always_match() {
+        let mut index = TrigramIndex::default();
+        let path: Arc<Path> = Path::new("src/main.rs").into();
+        let indexed_at = MTime::from_seconds_and_nanos(100, 0);
+        index.insert(path.clone(), indexed_at, &trigrams(b"hello world"));
+
+        let matches = index.matches(&TrigramQuery::AnyOf(vec![trigrams(b"goodbye")]));
+        assert!(!index.may_match(&path, Some(indexed_at), &matches));
+        assert!(index.may_match(&path, Some(MTime::from_seconds_and_nanos(101, 0)), &matches));
+        assert!(index.may_match(Path::new("src/lib.rs"), Some(indexed_at), &matches));
+
+        // Files indexed after the query ran weren't considered by it.
+        let reindexed_at = MTime::from_seconds_and_nanos(102, 0);
+        index.insert(path.clone(), reindexed_at, &trigrams(b"hello again"));
+        assert!(index.may_match(&path, Some(reindexed_at), &matches));
+
+        assert_eq!(index.remove(Path::new("src")), vec![path.clone()]);
+        assert!(index.may_match(&path, Some(reindexed_at), &matches));
+    }
+
+    #[test]
+    fn test_postings() {
+        let mut index = TrigramIndex::default();
+        let mtime = MTime::from_seconds_and_nanos(100, 0);
+        let paths = ["a.rs", "b.rs", "c.rs"].map(|path| Arc::<Path>::from(Path::new(path)));
+        index.insert(paths[0].clone(), mtime, &trigrams(b"fn compute_total()"));
+        index.insert(paths[1].clone(), mtime, &trigrams(b"fn compute_average()"));
+        index.insert(paths[2].clone(), mtime, &trigrams(b"let total = 0;"));
+        let matching = |index: &TrigramIndex, query: &TrigramQuery| {
+            let matches = index.matches(query);
+            paths
+                .iter()
+                .filter(|path| index.may_match(path, Some(mtime), &matches))
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let query = TrigramQuery::AnyOf(vec![trigrams(b"compute_"), trigrams(b"total")]);
+        assert_eq!(matching(&index, &query), ["a.rs", "b.rs", "c.rs"]);
+        let query = TrigramQuery::AnyOf(vec![trigrams(b"compute_total")]);
+        assert_eq!(matching(&index, &query), ["a.rs"]);
+        assert_eq!(
+            matching(&index, &TrigramQuery::All),
+            ["a.rs", "b.rs", "c.rs"]
+        );
+
+        // Reindexing a file replaces its postings.
+        let id = index.insert(paths[0].clone(), mtime, &trigrams(b"fn compute_sum()"));
+        assert_eq!(matching(&index, &query), Vec::<String>::new());
+        let query = TrigramQuery::AnyOf(vec![trigrams(b"compute_sum")]);
+        assert_eq!(matching(&index, &query), ["a.rs"]);
+
+        // Once most postings belong to files that are no longer indexed, they're dropped.
+        index.remove(&paths[1]);
+        index.remove(&paths[2]);
+        assert_eq!(index.stale_files, 0);
+        assert!(index.postings.values().all(|ids| ids == &[id]));
+
+        let restored = TrigramIndex::from_persisted(
+            index
+                .files
+                .iter()
+                .map(|(path, file)| (path.clone(), file.id, file.mtime)),
+            index
+                .postings
+                .iter()
+                .flat_map(|(trigram, ids)| ids.iter().map(|id| (*trigram, *id))),
+        );
+        assert!(restored.may_match(&paths[0], Some(mtime), &restored.matches(&query)));
+        let query = TrigramQuery::AnyOf(vec![trigrams(b"total")]);
+        assert!(!restored.may_match(&paths[0], Some(mtime), &restored.matches(&query)));
+        assert_eq!(restored.next_file_id, index.next_file_id);
+    }
+}
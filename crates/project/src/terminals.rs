@@ -41,6 +41,8 @@ pub enum TerminalKind {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SshCommand {
     pub arguments: Vec<String>,
+    /// For connections that run a command instead of ssh, that command.
+    pub command: Option<Vec<String>>,
 }
 
 impl SshCommand {
@@ -82,10 +84,18 @@ impl Project {
     pub fn ssh_details(&self, cx: &App) -> Option<SshDetails> {
         if let Some(ssh_client) = &self.ssh_client {
             let ssh_client = ssh_client.read(cx);
-            if let Some((SshArgs { arguments, envs }, path_style)) = ssh_client.ssh_info() {
+            if let Some((
+                SshArgs {
+                    arguments,
+                    envs,
+                    command,
+                },
+                path_style,
+            )) = ssh_client.ssh_info()
+            {
                 return Some(SshDetails {
                     host: ssh_client.connection_options().host.clone(),
-                    ssh_command: SshCommand { arguments },
+                    ssh_command: SshCommand { arguments, command },
                     envs,
                     path_style,
                 });
@@ -283,6 +293,9 @@ impl Project {
                         // so we set it to a default that does not break the highlighting via ssh.
                         env.entry("TERM".to_string())
                             .or_insert_with(|| "xterm-256color".to_string());
+                        // Lets the remote server tell which processes were started in
+                        // terminals, to list the ports they listen on.
+                        env.insert("ZED_TERM".to_string(), "true".to_string());
 
                         let (program, args) = wrap_for_ssh(
                             &ssh_command,
@@ -314,6 +327,8 @@ impl Project {
                     full_label: spawn_task.full_label,
                     label: spawn_task.label,
                     command_label: spawn_task.command_label,
+                    command: spawn_task.command.clone(),
+                    args: spawn_task.args.clone(),
                     hide: spawn_task.hide,
                     status: TaskStatus::Running,
                     show_summary: spawn_task.show_summary,
@@ -341,6 +356,7 @@ impl Project {
                         log::debug!("Connecting to a remote server: {ssh_command:?}");
                         env.entry("TERM".to_string())
                             .or_insert_with(|| "xterm-256color".to_string());
+                        env.insert("ZED_TERM".to_string(), "true".to_string());
                         let (program, args) = wrap_for_ssh(
                             &ssh_command,
                             spawn_task
@@ -637,6 +653,10 @@ pub fn wrap_for_ssh(
         };
         let args = args.iter().filter_map(|arg| shlex::try_quote(arg).ok());
         command.into_iter().chain(args).join(" ")
+    } else if ssh_command.command.is_some() {
+        // Commands that we can't ask for a terminal still need the shell to
+        // be told that it's interactive.
+        "exec ${SHELL:-sh} -il".to_string()
     } else {
         "exec ${SHELL:-sh} -l".to_string()
     };
@@ -672,6 +692,17 @@ pub fn wrap_for_ssh(
     } else {
         format!("cd; {env_changes} {to_run}")
     };
+    if let Some((program, arguments)) = ssh_command
+        .command
+        .as_ref()
+        .and_then(|command| command.split_first())
+    {
+        // Unlike ssh, commands pass their arguments on as they are, rather
+        // than as a command line for the remote shell.
+        let mut args = with_tty(arguments);
+        args.extend(["sh".to_string(), "-c".to_string(), commands]);
+        return (program.clone(), args);
+    }
     let shell_invocation = format!("sh -c {}", shlex::try_quote(&commands).unwrap());
 
     let program = "ssh".to_string();
@@ -682,6 +713,24 @@ pub fn wrap_for_ssh(
     (program, args)
 }
 
+/// Asks a command like `docker exec -i` to allocate a terminal, as ssh's `-t`
+/// does, by adding `-t` after its `-i` flag. Commands without that flag are
+/// left as they are.
+fn with_tty(arguments: &[String]) -> Vec<String> {
+    let mut arguments = arguments.to_vec();
+    let has_tty = arguments
+        .iter()
+        .any(|argument| matches!(argument.as_str(), "-t" | "--tty" | "-it" | "-ti"));
+    if !has_tty
+        && let Some(ix) = arguments
+            .iter()
+            .position(|argument| matches!(argument.as_str(), "-i" | "--interactive"))
+    {
+        arguments.insert(ix + 1, "-t".to_string());
+    }
+    arguments
+}
+
 fn add_environment_path(env: &mut HashMap<String, String>, new_path: &Path) -> Result<()> {
     let mut env_paths = vec![new_path.to_path_buf()];
     if let Some(path) = env.get("PATH").or(env::var("PATH").ok().as_ref()) {
@@ -734,4 +783,80 @@ mod tests {
         }
         assert_eq!(env.get("OTHER").unwrap(), "aaa");
     }
+
+    #[test]
+    fn test_wrap_for_command_connection() {
+        let ssh_command = super::SshCommand {
+            arguments: Vec::new(),
+            command: Some(vec![
+                "docker".into(),
+                "exec".into(),
+                "-i".into(),
+                "dev".into(),
+            ]),
+        };
+        let (program, args) = super::wrap_for_ssh(
+            &ssh_command,
+            Some((&"ls".to_string(), &vec!["-l".to_string()])),
+            Some(std::path::Path::new("/work")),
+            HashMap::default(),
+            None,
+            util::paths::PathStyle::Posix,
+        );
+        assert_eq!(program, "docker");
+        assert_eq!(
+            args,
+            vec![
+                "exec",
+                "-i",
+                "-t",
+                "dev",
+                "sh",
+                "-c",
+                "cd \"/work\";  ls -l"
+            ]
+        );
+
+        // The login shell gets a terminal too.
+        let (_, args) = super::wrap_for_ssh(
+            &ssh_command,
+            None,
+            None,
+            HashMap::default(),
+            None,
+            util::paths::PathStyle::Posix,
+        );
+        assert_eq!(
+            args,
+            vec![
+                "exec",
+                "-i",
+                "-t",
+                "dev",
+                "sh",
+                "-c",
+                "cd;  exec ${SHELL:-sh} -il"
+            ]
+        );
+
+        // Commands that already allocate one are left alone.
+        let ssh_command = super::SshCommand {
+            arguments: Vec::new(),
+            command: Some(vec![
+                "docker".into(),
+                "exec".into(),
+                "-it".into(),
+                "dev".into(),
+            ]),
+        };
+        let (_, args) = super::wrap_for_ssh(
+            &ssh_command,
+            None,
+            None,
+            HashMap::default(),
+            None,
+            util::paths::PathStyle::Posix,
+        );
+        assert_eq!(&args[..3], ["exec", "-it", "dev"]);
+    }
 }
@@ -15,6 +15,7 @@ use futures::{
 use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EntityId, EventEmitter, Task, WeakEntity,
 };
+use parking_lot::RwLock;
 use postage::oneshot;
 use rpc::{
     AnyProtoClient, ErrorExt, TypedEnvelope,
@@ -34,7 +35,11 @@ use worktree::{
     WorktreeSettings,
 };
 
-use crate::{ProjectPath, search::SearchQuery};
+use crate::{
+    ProjectPath,
+    search::SearchQuery,
+    search_index::{SearchIndexStore, TrigramIndex, TrigramQuery},
+};
 
 struct MatchingEntry {
     worktree_path: Arc<Path>,
@@ -62,6 +67,7 @@ pub struct WorktreeStore {
     #[allow(clippy::type_complexity)]
     loading_worktrees:
         HashMap<SanitizedPath, Shared<Task<Result<Entity<Worktree>, Arc<anyhow::Error>>>>>,
+    search_index_store: Option<WeakEntity<SearchIndexStore>>,
     state: WorktreeStoreState,
 }
 
@@ -96,6 +102,7 @@ impl WorktreeStore {
             worktrees: Vec::new(),
             worktrees_reordered: false,
             retain_worktrees,
+            search_index_store: None,
             state: WorktreeStoreState::Local { fs },
         }
     }
@@ -113,6 +120,7 @@ impl WorktreeStore {
             worktrees: Vec::new(),
             worktrees_reordered: false,
             retain_worktrees,
+            search_index_store: None,
             state: WorktreeStoreState::Remote {
                 upstream_client,
                 upstream_project_id,
@@ -650,6 +658,12 @@ impl WorktreeStore {
         }
     }
 
+    /// Sets the store whose indices are used to skip files that can't match
+    /// a search.
+    pub(crate) fn set_search_index_store(&mut self, store: WeakEntity<SearchIndexStore>) {
+        self.search_index_store = Some(store);
+    }
+
     /// search over all worktrees and return buffers that *might* match the search.
     pub fn find_search_candidates(
         &self,
@@ -659,11 +673,20 @@ impl WorktreeStore {
         fs: Arc<dyn Fs>,
         cx: &Context<Self>,
     ) -> Receiver<ProjectPath> {
+        let trigram_query = TrigramQuery::new(&query);
+        let search_index_store = self
+            .search_index_store
+            .as_ref()
+            .and_then(|store| store.upgrade())
+            .filter(|_| !trigram_query.is_all());
         let snapshots = self
             .visible_worktrees(cx)
             .filter_map(|tree| {
                 let tree = tree.read(cx);
-                Some((tree.snapshot(), tree.as_local()?.settings()))
+                let index = search_index_store
+                    .as_ref()
+                    .and_then(|store| store.read(cx).index_for_worktree(tree.id()));
+                Some((tree.snapshot(), tree.as_local()?.settings(), index))
             })
             .collect::<Vec<_>>();
 
@@ -688,6 +711,7 @@ impl WorktreeStore {
                     snapshots,
                     open_entries,
                     query,
+                    trigram_query,
                     filter_tx,
                     output_tx,
                 )
@@ -811,13 +835,22 @@ impl WorktreeStore {
 
     async fn find_candidate_paths(
         fs: Arc<dyn Fs>,
-        snapshots: Vec<(worktree::Snapshot, WorktreeSettings)>,
+        snapshots: Vec<(
+            worktree::Snapshot,
+            WorktreeSettings,
+            Option<Arc<RwLock<TrigramIndex>>>,
+        )>,
         open_entries: HashSet<ProjectEntryId>,
         query: SearchQuery,
+        trigram_query: TrigramQuery,
         filter_tx: Sender<MatchingEntry>,
         output_tx: Sender<oneshot::Receiver<ProjectPath>>,
     ) -> Result<()> {
-        for (snapshot, settings) in snapshots {
+        for (snapshot, settings, index) in snapshots {
+            let index = index.map(|index| {
+                let matches = index.read().matches(&trigram_query);
+                (index, matches)
+            });
             for entry in snapshot.entries(query.include_ignored(), 0) {
                 if entry.is_dir() && entry.is_ignored {
                     if !settings.is_path_excluded(&entry.path) {
@@ -851,9 +884,19 @@ impl WorktreeStore {
                     }
                 }
 
+                let is_open = open_entries.contains(&entry.id);
+                // Open buffers may have unsaved edits that the index hasn't seen.
+                if !is_open
+                    && index.as_ref().is_some_and(|(index, matches)| {
+                        !index.read().may_match(&entry.path, entry.mtime, matches)
+                    })
+                {
+                    continue;
+                }
+
                 let (mut tx, rx) = oneshot::channel();
 
-                if open_entries.contains(&entry.id) {
+                if is_open {
                     tx.send(ProjectPath {
                         worktree_id: snapshot.id(),
                         path: entry.path.clone(),
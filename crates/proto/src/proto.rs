@@ -248,6 +248,8 @@ messages!(
     (SyncExtensionsResponse, Background),
     (BreakpointsForFile, Background),
     (ToggleBreakpoint, Foreground),
+    (BookmarksForFile, Background),
+    (ToggleBookmark, Foreground),
     (SynchronizeBuffers, Foreground),
     (SynchronizeBuffersResponse, Foreground),
     (SynchronizeContexts, Foreground),
@@ -290,6 +292,10 @@ messages!(
     (GitCheckoutFiles, Background),
     (GitShow, Background),
     (GitCommitDetails, Background),
+    (GitSearchHistory, Background),
+    (GitSearchHistoryResponse, Background),
+    (ListListeningPorts, Background),
+    (ListListeningPortsResponse, Background),
     (SetIndexText, Background),
     (Push, Background),
     (Fetch, Background),
@@ -467,6 +473,8 @@ request_messages!(
     (InstallExtension, Ack),
     (RegisterBufferWithLanguageServers, Ack),
     (GitShow, GitCommitDetails),
+    (GitSearchHistory, GitSearchHistoryResponse),
+    (ListListeningPorts, ListListeningPortsResponse),
     (GitReset, Ack),
     (GitCheckoutFiles, Ack),
     (SetIndexText, Ack),
@@ -481,6 +489,7 @@ request_messages!(
     (GitDiff, GitDiffResponse),
     (GitInit, Ack),
     (ToggleBreakpoint, Ack),
+    (ToggleBookmark, Ack),
     (GetDebugAdapterBinary, DebugAdapterBinary),
     (RunDebugLocators, DebugRequest),
     (GetDocumentDiagnostics, GetDocumentDiagnosticsResponse),
@@ -594,9 +603,11 @@ entity_messages!(
     ActivateToolchain,
     ActiveToolchain,
     GetPathMetadata,
+    ListListeningPorts,
     CancelLanguageServerWork,
     RegisterBufferWithLanguageServers,
     GitShow,
+    GitSearchHistory,
     GitReset,
     GitCheckoutFiles,
     SetIndexText,
@@ -613,6 +624,8 @@ entity_messages!(
     GitInit,
     BreakpointsForFile,
     ToggleBreakpoint,
+    BookmarksForFile,
+    ToggleBookmark,
     RunDebugLocators,
     GetDebugAdapterBinary,
     LogToDebugConsole,
@@ -0,0 +1,962 @@
+//! Dev Containers: reopens a project in the container that its
+//! `.devcontainer/devcontainer.json` describes, connecting to the container by
+//! running `docker exec`.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use extension_host::ExtensionStore;
+use fs::Fs;
+use futures::future::BoxFuture;
+use gpui::{App, Context, SharedString, Window, actions};
+use serde::Deserialize;
+use settings::{Settings as _, update_settings_file};
+use workspace::{
+    OpenOptions, Toast, Workspace,
+    notifications::{DetachAndPromptErr, NotificationId},
+};
+
+use crate::{
+    open_ssh_project,
+    ssh_connections::{SshConnection, SshProject, SshSettings},
+};
+
+actions!(
+    projects,
+    [
+        /// Reopens the project in the dev container described by its
+        /// `.devcontainer/devcontainer.json`.
+        ReopenInContainer
+    ]
+);
+
+/// Marks containers with the project folder they were created for, so that
+/// they're reused when the project is reopened.
+const LOCAL_FOLDER_LABEL: &str = "dev.zed.devcontainer.local_folder";
+
+/// Keeps containers started from an image running, as their own command may
+/// exit straight away.
+const KEEP_ALIVE_SCRIPT: &str = "trap 'exit 0' TERM; sleep infinity & wait";
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &ReopenInContainer, window, cx| {
+        reopen_in_container(workspace, window, cx);
+    });
+}
+
+/// Runs the container CLI, such as `docker` or `podman`.
+pub trait ContainerRunner: Send + Sync {
+    /// The program that's run, which is also used to connect to the container.
+    fn program(&self) -> String;
+    /// Runs the program with the given arguments, returning its stdout.
+    fn run(&self, args: Vec<String>) -> BoxFuture<'static, Result<String>>;
+}
+
+pub struct CliRunner {
+    program: String,
+}
+
+impl CliRunner {
+    pub fn new(program: String) -> Self {
+        Self { program }
+    }
+}
+
+impl ContainerRunner for CliRunner {
+    fn program(&self) -> String {
+        self.program.clone()
+    }
+
+    fn run(&self, args: Vec<String>) -> BoxFuture<'static, Result<String>> {
+        log::debug!("running {} {:?}", self.program, args);
+        let output = util::command::new_smol_command(&self.program)
+            .args(&args)
+            .output();
+        let program = self.program.clone();
+        Box::pin(async move {
+            let output = output
+                .await
+                .with_context(|| format!("failed to run {program}"))?;
+            anyhow::ensure!(
+                output.status.success(),
+                "{program} {} failed: {}",
+                args.first().map(String::as_str).unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        })
+    }
+}
+
+/// The parts of a `devcontainer.json` that Zed understands.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevContainerConfig {
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub build: Option<BuildConfig>,
+    /// The deprecated spelling of `build.dockerfile`.
+    #[serde(rename = "dockerFile")]
+    pub docker_file: Option<String>,
+    /// The deprecated spelling of `build.context`.
+    pub context: Option<String>,
+    pub docker_compose_file: Option<OneOrMany>,
+    pub service: Option<String>,
+    pub workspace_folder: Option<String>,
+    #[serde(default)]
+    pub forward_ports: Vec<ForwardPort>,
+    pub post_create_command: Option<LifecycleCommand>,
+    #[serde(default)]
+    pub container_env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub remote_env: BTreeMap<String, String>,
+    pub remote_user: Option<String>,
+    #[serde(default)]
+    pub run_args: Vec<String>,
+    #[serde(default)]
+    pub customizations: Customizations,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildConfig {
+    pub dockerfile: Option<String>,
+    pub context: Option<String>,
+    #[serde(default)]
+    pub args: BTreeMap<String, String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ForwardPort {
+    Port(u16),
+    /// A port of another service, like `"db:5432"`.
+    Service(String),
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum LifecycleCommand {
+    /// Run by a shell.
+    Shell(String),
+    /// Run without a shell.
+    Args(Vec<String>),
+    /// Several commands, which other tools may run in parallel.
+    Named(BTreeMap<String, LifecycleCommand>),
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+pub struct Customizations {
+    #[serde(default)]
+    pub zed: ZedCustomizations,
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
+pub struct ZedCustomizations {
+    /// The IDs of extensions to install.
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// Settings to write to the server's settings file in the container.
+    pub settings: Option<serde_json::Value>,
+}
+
+/// Where a dev container comes from.
+#[derive(Debug, PartialEq)]
+pub enum ContainerSource {
+    Image(String),
+    Dockerfile {
+        dockerfile: PathBuf,
+        context: PathBuf,
+        args: BTreeMap<String, String>,
+    },
+    Compose {
+        files: Vec<PathBuf>,
+        service: String,
+    },
+}
+
+impl DevContainerConfig {
+    pub fn parse(text: &str) -> Result<Self> {
+        settings::parse_json_with_comments(text).context("invalid devcontainer.json")
+    }
+
+    /// Returns where the container comes from, resolving paths relative to
+    /// the directory that `devcontainer.json` is in.
+    pub fn source(&self, config_dir: &Path) -> Result<ContainerSource> {
+        if let Some(files) = &self.docker_compose_file {
+            let files = match files {
+                OneOrMany::One(file) => vec![config_dir.join(file)],
+                OneOrMany::Many(files) => files.iter().map(|file| config_dir.join(file)).collect(),
+            };
+            let service = self
+                .service
+                .clone()
+                .context("`service` is required with `dockerComposeFile`")?;
+            return Ok(ContainerSource::Compose { files, service });
+        }
+
+        let build = self.build.as_ref();
+        let dockerfile = build
+            .and_then(|build| build.dockerfile.as_ref())
+            .or(self.docker_file.as_ref());
+        if let Some(dockerfile) = dockerfile {
+            let context = build
+                .and_then(|build| build.context.as_ref())
+                .or(self.context.as_ref())
+                .map_or(".", String::as_str);
+            return Ok(ContainerSource::Dockerfile {
+                dockerfile: config_dir.join(dockerfile),
+                context: config_dir.join(context),
+                args: build.map(|build| build.args.clone()).unwrap_or_default(),
+            });
+        }
+
+        let image = self
+            .image
+            .clone()
+            .context("devcontainer.json needs an `image`, a Dockerfile or `dockerComposeFile`")?;
+        Ok(ContainerSource::Image(image))
+    }
+}
+
+impl LifecycleCommand {
+    /// Returns the programs to run, along with their arguments.
+    fn commands(&self) -> Vec<Vec<String>> {
+        match self {
+            Self::Shell(script) => vec![vec!["sh".into(), "-c".into(), script.clone()]],
+            Self::Args(args) => vec![args.clone()],
+            Self::Named(commands) => commands.values().flat_map(Self::commands).collect(),
+        }
+    }
+}
+
+/// How a dev container is found again when connecting to it, as its ID
+/// changes whenever it's recreated.
+#[derive(Debug, PartialEq)]
+pub enum ContainerLookup {
+    /// A container started from an image, by its label.
+    Label(String),
+    /// A compose service, along with the arguments that select its project.
+    Compose {
+        compose_args: Vec<String>,
+        service: String,
+    },
+}
+
+/// A running dev container.
+#[derive(Debug, PartialEq)]
+pub struct DevContainer {
+    pub container_id: String,
+    pub lookup: ContainerLookup,
+    pub workspace_folder: String,
+    pub remote_user: Option<String>,
+    pub remote_env: BTreeMap<String, String>,
+    /// Whether the container was just created, rather than being reused.
+    pub created: bool,
+}
+
+impl DevContainer {
+    fn exec_options(&self, interactive: bool, workdir: Option<&str>) -> Vec<String> {
+        let mut args = Vec::new();
+        if interactive {
+            args.push("-i".into());
+        }
+        if let Some(workdir) = workdir {
+            args.extend(["-w".into(), workdir.into()]);
+        }
+        if let Some(user) = &self.remote_user {
+            args.extend(["-u".into(), user.clone()]);
+        }
+        for (name, value) in &self.remote_env {
+            args.extend(["-e".into(), format!("{name}={value}")]);
+        }
+        args
+    }
+
+    fn exec_args(&self, interactive: bool, workdir: Option<&str>) -> Vec<String> {
+        let mut args = vec!["exec".to_string()];
+        args.extend(self.exec_options(interactive, workdir));
+        args.push(self.container_id.clone());
+        args
+    }
+
+    /// The command that the remote server is installed and run through.
+    ///
+    /// It starts the container and looks up its ID on every connection, then
+    /// runs `exec` with the options that follow it, putting the ID in place of
+    /// the first `--`. The options stay separate arguments, so that `-t` can
+    /// still be added after `-i` for terminals.
+    pub fn connect_command(&self, runner: &dyn ContainerRunner) -> Result<Vec<String>> {
+        let program = runner.program();
+        let program = program.as_str();
+        let quote = |args: &[&str]| {
+            shlex::try_join(args.iter().copied()).context("quoting the container command")
+        };
+        let lookup = match &self.lookup {
+            ContainerLookup::Label(label) => {
+                let label = format!("label={label}");
+                format!(
+                    "id=$({} | head -n 1) && [ -n \"$id\" ] && {} \"$id\" >/dev/null",
+                    quote(&[program, "ps", "-aq", "--filter", label.as_str()])?,
+                    quote(&[program, "start"])?,
+                )
+            }
+            ContainerLookup::Compose {
+                compose_args,
+                service,
+            } => {
+                let compose = std::iter::once(program)
+                    .chain(compose_args.iter().map(String::as_str))
+                    .collect::<Vec<_>>();
+                let mut start = compose.clone();
+                start.extend(["start", service.as_str()]);
+                let mut ps = compose;
+                ps.extend(["ps", "-q", service.as_str()]);
+                format!(
+                    "{} >/dev/null && id=$({}) && [ -n \"$id\" ]",
+                    quote(&start)?,
+                    quote(&ps)?,
+                )
+            }
+        };
+        let script = format!(
+            "{lookup} || {{ echo \"couldn't start the dev container\" >&2; exit 1; }}; \
+             found=; for arg do shift; \
+             if [ -z \"$found\" ] && [ \"$arg\" = -- ]; then found=1; set -- \"$@\" \"$id\"; \
+             else set -- \"$@\" \"$arg\"; fi; done; \
+             exec {} \"$@\"",
+            quote(&[program, "exec"])?,
+        );
+
+        let mut command = vec!["sh".to_string(), "-c".into(), script, "sh".into()];
+        command.extend(self.exec_options(true, None));
+        command.push("--".into());
+        Ok(command)
+    }
+}
+
+/// Looks for the project's `devcontainer.json`.
+pub async fn find_config(fs: &dyn Fs, project_root: &Path) -> Option<PathBuf> {
+    for path in [".devcontainer/devcontainer.json", ".devcontainer.json"] {
+        let path = project_root.join(path);
+        if fs.is_file(&path).await {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Starts the project's dev container, creating it if there isn't one yet.
+///
+/// Files that Zed generates for the container, such as a compose file
+/// publishing `forwardPorts`, are written to `state_dir`.
+pub async fn up(
+    runner: &dyn ContainerRunner,
+    fs: &dyn Fs,
+    config: &DevContainerConfig,
+    config_dir: &Path,
+    project_root: &Path,
+    state_dir: &Path,
+) -> Result<DevContainer> {
+    let project_name = project_name(project_root);
+    let (container_id, lookup, created, workspace_folder) = match config.source(config_dir)? {
+        ContainerSource::Compose { files, service } => {
+            let mut compose = vec!["compose".to_string()];
+            for file in files {
+                compose.extend(["-f".into(), file.to_string_lossy().into_owned()]);
+            }
+            let ports = compose_ports(&config.forward_ports, &service);
+            if !ports.is_empty() {
+                // Compose files are YAML, which JSON is a subset of.
+                let ports_file = state_dir.join(format!(
+                    "compose-ports{}.json",
+                    image_name(&project_root.to_string_lossy())
+                ));
+                fs.create_dir(state_dir).await?;
+                fs.atomic_write(
+                    ports_file.clone(),
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "services": ports
+                            .into_iter()
+                            .map(|(service, ports)| (service, serde_json::json!({ "ports": ports })))
+                            .collect::<serde_json::Map<_, _>>()
+                    }))?,
+                )
+                .await?;
+                compose.extend(["-f".into(), ports_file.to_string_lossy().into_owned()]);
+            }
+            let mut ps = compose.clone();
+            ps.extend(["ps".into(), "-q".into(), service.clone()]);
+            let mut compose_up = compose.clone();
+            compose_up.extend(["up".into(), "-d".into()]);
+            let existing = runner.run(ps.clone()).await?;
+            runner.run(compose_up).await?;
+            let container_id = first_line(&runner.run(ps).await?)
+                .context("the compose service isn't running after `compose up`")?;
+            let workspace_folder = config.workspace_folder.clone().unwrap_or("/".into());
+            (
+                container_id,
+                ContainerLookup::Compose {
+                    compose_args: compose,
+                    service,
+                },
+                first_line(&existing).is_none(),
+                workspace_folder,
+            )
+        }
+        source => {
+            let workspace_folder = config
+                .workspace_folder
+                .clone()
+                .unwrap_or_else(|| format!("/workspaces/{project_name}"));
+            let label = format!("{LOCAL_FOLDER_LABEL}={}", project_root.display());
+            let existing = runner
+                .run(vec![
+                    "ps".into(),
+                    "-aq".into(),
+                    "--filter".into(),
+                    format!("label={label}"),
+                ])
+                .await?;
+            if let Some(container_id) = first_line(&existing) {
+                runner
+                    .run(vec!["start".into(), container_id.clone()])
+                    .await?;
+                (
+                    container_id,
+                    ContainerLookup::Label(label),
+                    false,
+                    workspace_folder,
+                )
+            } else {
+                let image = match source {
+                    ContainerSource::Dockerfile {
+                        dockerfile,
+                        context,
+                        args,
+                    } => {
+                        let tag = format!("zed-dev-container-{}", image_name(&project_name));
+                        let mut build = vec![
+                            "build".to_string(),
+                            "-f".into(),
+                            dockerfile.to_string_lossy().into_owned(),
+                            "-t".into(),
+                            tag.clone(),
+                        ];
+                        for (name, value) in args {
+                            build.extend(["--build-arg".into(), format!("{name}={value}")]);
+                        }
+                        build.push(context.to_string_lossy().into_owned());
+                        runner.run(build).await?;
+                        tag
+                    }
+                    ContainerSource::Image(image) => image,
+                    ContainerSource::Compose { .. } => unreachable!(),
+                };
+
+                let mut run = vec![
+                    "run".to_string(),
+                    "-d".into(),
+                    "--label".into(),
+                    label.clone(),
+                    "-v".into(),
+                    format!("{}:{workspace_folder}", project_root.display()),
+                    "-w".into(),
+                    workspace_folder.clone(),
+                ];
+                for port in &config.forward_ports {
+                    match port {
+                        ForwardPort::Port(port) => {
+                            run.extend(["-p".into(), format!("127.0.0.1:{port}:{port}")]);
+                        }
+                        ForwardPort::Service(port) => {
+                            log::warn!("not forwarding {port}, as it's not in a compose project");
+                        }
+                    }
+                }
+                for (name, value) in &config.container_env {
+                    run.extend(["-e".into(), format!("{name}={value}")]);
+                }
+                run.extend(config.run_args.iter().cloned());
+                run.extend([image, "sh".into(), "-c".into(), KEEP_ALIVE_SCRIPT.into()]);
+                let container_id = first_line(&runner.run(run).await?)
+                    .context("no container ID in the output of `run`")?;
+                (
+                    container_id,
+                    ContainerLookup::Label(label),
+                    true,
+                    workspace_folder,
+                )
+            }
+        }
+    };
+
+    let container = DevContainer {
+        container_id,
+        lookup,
+        workspace_folder,
+        remote_user: config.remote_user.clone(),
+        remote_env: config.remote_env.clone(),
+        created,
+    };
+    if container.created {
+        if let Some(settings) = &config.customizations.zed.settings {
+            let script = "dir=\"${XDG_CONFIG_HOME:-$HOME/.config}/zed\" && mkdir -p \"$dir\" \
+                && printf '%s' \"$1\" > \"$dir/settings.json\"";
+            let mut args = container.exec_args(false, None);
+            args.extend([
+                "sh".into(),
+                "-c".into(),
+                script.into(),
+                "sh".into(),
+                serde_json::to_string_pretty(settings)?,
+            ]);
+            runner.run(args).await.context("writing Zed settings")?;
+        }
+        if let Some(post_create_command) = &config.post_create_command {
+            for command in post_create_command.commands() {
+                let mut args = container.exec_args(false, Some(&container.workspace_folder));
+                args.extend(command);
+                runner
+                    .run(args)
+                    .await
+                    .context("running postCreateCommand")?;
+            }
+        }
+    }
+    Ok(container)
+}
+
+/// Returns the ports to publish on `127.0.0.1` for each compose service.
+/// Numeric ports belong to the dev container's own service.
+fn compose_ports(forward_ports: &[ForwardPort], service: &str) -> BTreeMap<String, Vec<String>> {
+    let mut ports = BTreeMap::<String, Vec<String>>::new();
+    for port in forward_ports {
+        let (service, port) = match port {
+            ForwardPort::Port(port) => (service, *port),
+            ForwardPort::Service(port) => {
+                match port
+                    .split_once(':')
+                    .and_then(|(service, port)| Some((service, port.parse().ok()?)))
+                {
+                    Some(service_port) => service_port,
+                    None => {
+                        log::warn!("not forwarding {port:?}, as it isn't `service:port`");
+                        continue;
+                    }
+                }
+            }
+        };
+        ports
+            .entry(service.to_string())
+            .or_default()
+            .push(format!("127.0.0.1:{port}:{port}"));
+    }
+    ports
+}
+
+fn first_line(output: &str) -> Option<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(ToString::to_string)
+}
+
+fn project_name(project_root: &Path) -> String {
+    project_root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "workspace".into())
+}
+
+/// Image names may only contain lowercase letters, digits and separators.
+fn image_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+struct DevContainerToast;
+
+fn reopen_in_container(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().read(cx);
+    let project_root = project
+        .is_local()
+        .then(|| project.visible_worktrees(cx).next())
+        .flatten()
+        .map(|worktree| worktree.read(cx).abs_path().to_path_buf());
+    let Some(window_handle) = window.window_handle().downcast::<Workspace>() else {
+        return;
+    };
+    let app_state = workspace.app_state().clone();
+    let runner = CliRunner::new(
+        SshSettings::get_global(cx)
+            .dev_container_cli
+            .clone()
+            .unwrap_or_else(|| "docker".into()),
+    );
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let project_root =
+            project_root.context("Only local projects can be reopened in a container")?;
+        let fs = app_state.fs.clone();
+        let config_path = find_config(fs.as_ref(), &project_root)
+            .await
+            .context("No .devcontainer/devcontainer.json in the project")?;
+        let config = DevContainerConfig::parse(&fs.load(&config_path).await?)?;
+        let config_dir = config_path.parent().unwrap_or(&project_root);
+
+        let toast_id = NotificationId::unique::<DevContainerToast>();
+        workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(Toast::new(toast_id.clone(), "Starting dev container…"), cx)
+        })?;
+        let container = up(
+            &runner,
+            fs.as_ref(),
+            &config,
+            config_dir,
+            &project_root,
+            paths::dev_containers_dir(),
+        )
+        .await;
+        workspace.update(cx, |workspace, cx| workspace.dismiss_toast(&toast_id, cx))?;
+        let container = container?;
+
+        let name = config
+            .name
+            .clone()
+            .unwrap_or_else(|| project_name(&project_root));
+        let connection = SshConnection {
+            nickname: Some(format!("{name} (Dev Container)")),
+            host: SharedString::from(name),
+            command: Some(container.connect_command(&runner)?),
+            projects: BTreeSet::from_iter([SshProject {
+                paths: vec![container.workspace_folder.clone()],
+            }]),
+            ..SshConnection::default()
+        };
+        cx.update(|_, cx| {
+            ExtensionStore::global(cx).update(cx, |store, cx| {
+                for extension_id in &config.customizations.zed.extensions {
+                    if !store
+                        .installed_extensions()
+                        .contains_key(extension_id.as_str())
+                    {
+                        store.install_latest_extension(extension_id.as_str().into(), cx);
+                    }
+                }
+            });
+            save_connection(fs.clone(), connection.clone(), cx);
+        })?;
+
+        open_ssh_project(
+            connection.into(),
+            vec![PathBuf::from(container.workspace_folder)],
+            app_state,
+            OpenOptions {
+                replace_window: Some(window_handle),
+                ..OpenOptions::default()
+            },
+            cx,
+        )
+        .await
+    })
+    .detach_and_prompt_err("Failed to reopen in container", window, cx, |_, _, _| None);
+}
+
+/// Saves the connection, so that the container is listed with the other
+/// remote servers, replacing the one saved when it was last opened.
+fn save_connection(fs: Arc<dyn Fs>, connection: SshConnection, cx: &App) {
+    update_settings_file::<SshSettings>(fs, cx, move |settings, _| {
+        let connections = settings.ssh_connections.get_or_insert_default();
+        if let Some(existing) = connections
+            .iter_mut()
+            .find(|existing| existing.host == connection.host && existing.command.is_some())
+        {
+            existing.command = connection.command;
+            existing.projects.extend(connection.projects);
+        } else {
+            connections.push(connection);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use std::sync::Mutex;
+
+    struct FakeRunner {
+        calls: Mutex<Vec<String>>,
+        respond: fn(&[String]) -> Result<String>,
+    }
+
+    impl FakeRunner {
+        fn new(respond: fn(&[String]) -> Result<String>) -> Self {
+            Self {
+                calls: Mutex::default(),
+                respond,
+            }
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl ContainerRunner for FakeRunner {
+        fn program(&self) -> String {
+            "docker".into()
+        }
+
+        fn run(&self, args: Vec<String>) -> BoxFuture<'static, Result<String>> {
+            self.calls.lock().unwrap().push(args.join(" "));
+            let result = (self.respond)(&args);
+            Box::pin(async move { result })
+        }
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = DevContainerConfig::parse(
+            r#"{
+                // Comments and trailing commas are allowed.
+                "name": "App",
+                "build": { "dockerfile": "Dockerfile", "context": "..", "args": { "V": "1" } },
+                "forwardPorts": [3000, "db:5432"],
+                "postCreateCommand": { "a": "npm install", "b": ["make", "setup"] },
+                "remoteEnv": { "FOO": "bar" },
+                "customizations": {
+                    "zed": { "extensions": ["toml"], "settings": { "tab_size": 2 } },
+                    "vscode": { "extensions": ["rust-lang.rust-analyzer"] },
+                },
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.name.as_deref(), Some("App"));
+        assert_eq!(
+            config.forward_ports,
+            vec![
+                ForwardPort::Port(3000),
+                ForwardPort::Service("db:5432".into())
+            ]
+        );
+        assert_eq!(
+            config.post_create_command.unwrap().commands(),
+            vec![
+                vec!["sh".to_string(), "-c".into(), "npm install".into()],
+                vec!["make".to_string(), "setup".into()],
+            ]
+        );
+        assert_eq!(config.customizations.zed.extensions, vec!["toml"]);
+        assert_eq!(
+            config.customizations.zed.settings,
+            Some(serde_json::json!({ "tab_size": 2 }))
+        );
+
+        let config = DevContainerConfig::parse(
+            r#"{ "build": { "dockerfile": "Dockerfile", "context": "..", "args": { "V": "1" } } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.source(Path::new("/app/.devcontainer")).unwrap(),
+            ContainerSource::Dockerfile {
+                dockerfile: PathBuf::from("/app/.devcontainer/Dockerfile"),
+                context: PathBuf::from("/app/.devcontainer/.."),
+                args: BTreeMap::from_iter([("V".to_string(), "1".to_string())]),
+            }
+        );
+
+        let config =
+            DevContainerConfig::parse(r#"{ "dockerComposeFile": ["a.yml", "b.yml"] }"#).unwrap();
+        assert!(config.source(Path::new("/app")).is_err());
+
+        assert!(
+            DevContainerConfig::parse("{}")
+                .unwrap()
+                .source(Path::new("/app"))
+                .is_err()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_up_from_image(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let config = DevContainerConfig::parse(
+            r#"{
+                "image": "rust:1",
+                "forwardPorts": [8080],
+                "remoteUser": "dev",
+                "remoteEnv": { "FOO": "bar" },
+                "postCreateCommand": "cargo fetch",
+            }"#,
+        )
+        .unwrap();
+
+        let runner = FakeRunner::new(|args| match args[0].as_str() {
+            "run" => Ok("abc123\n".into()),
+            _ => Ok(String::new()),
+        });
+        let container = up(
+            &runner,
+            fs.as_ref(),
+            &config,
+            Path::new("/src/app"),
+            Path::new("/src/app"),
+            Path::new("/state"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            container,
+            DevContainer {
+                container_id: "abc123".into(),
+                lookup: ContainerLookup::Label(format!("{LOCAL_FOLDER_LABEL}=/src/app")),
+                workspace_folder: "/workspaces/app".into(),
+                remote_user: Some("dev".into()),
+                remote_env: BTreeMap::from_iter([("FOO".to_string(), "bar".to_string())]),
+                created: true,
+            }
+        );
+        assert_eq!(
+            runner.calls(),
+            vec![
+                format!("ps -aq --filter label={LOCAL_FOLDER_LABEL}=/src/app"),
+                format!(
+                    "run -d --label {LOCAL_FOLDER_LABEL}=/src/app -v /src/app:/workspaces/app \
+                     -w /workspaces/app -p 127.0.0.1:8080:8080 rust:1 sh -c {KEEP_ALIVE_SCRIPT}"
+                ),
+                "exec -w /workspaces/app -u dev -e FOO=bar abc123 sh -c cargo fetch".into(),
+            ]
+        );
+        let connect_command = container.connect_command(&runner).unwrap();
+        assert_eq!(
+            connect_command[3..],
+            ["sh", "-i", "-u", "dev", "-e", "FOO=bar", "--"]
+        );
+        assert!(connect_command[2].starts_with("id=$(docker ps -aq --filter "));
+        assert!(connect_command[2].contains(LOCAL_FOLDER_LABEL));
+        assert!(!connect_command.contains(&"abc123".to_string()));
+
+        // An existing container is started again, without rerunning
+        // `postCreateCommand`.
+        let runner = FakeRunner::new(|args| match args[0].as_str() {
+            "ps" => Ok("def456\n".into()),
+            _ => Ok(String::new()),
+        });
+        let container = up(
+            &runner,
+            fs.as_ref(),
+            &config,
+            Path::new("/src/app"),
+            Path::new("/src/app"),
+            Path::new("/state"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(container.container_id, "def456");
+        assert!(!container.created);
+        assert_eq!(
+            runner.calls(),
+            vec![
+                format!("ps -aq --filter label={LOCAL_FOLDER_LABEL}=/src/app"),
+                "start def456".into(),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_up_from_compose(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let config = DevContainerConfig::parse(
+            r#"{
+                "dockerComposeFile": "compose.yml",
+                "service": "app",
+                "workspaceFolder": "/code",
+                "forwardPorts": [3000, "db:5432", "db"],
+            }"#,
+        )
+        .unwrap();
+
+        let runner = FakeRunner::new(|args| {
+            if args.contains(&"up".to_string()) {
+                Ok(String::new())
+            } else {
+                Ok("svc789\n".into())
+            }
+        });
+        let container = up(
+            &runner,
+            fs.as_ref(),
+            &config,
+            Path::new("/src/app/.devcontainer"),
+            Path::new("/src/app"),
+            Path::new("/state"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(container.container_id, "svc789");
+        assert_eq!(container.workspace_folder, "/code");
+        // The service was already running.
+        assert!(!container.created);
+        let compose = "compose -f /src/app/.devcontainer/compose.yml \
+                       -f /state/compose-ports-src-app.json";
+        assert_eq!(
+            runner.calls(),
+            vec![
+                format!("{compose} ps -q app"),
+                format!("{compose} up -d"),
+                format!("{compose} ps -q app"),
+            ]
+        );
+
+        // Ports are published through a compose file of their own, as the
+        // service's ports can't be given to `compose up`.
+        let ports: serde_json::Value = serde_json::from_str(
+            &fs.load(Path::new("/state/compose-ports-src-app.json"))
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            ports,
+            serde_json::json!({
+                "services": {
+                    "app": { "ports": ["127.0.0.1:3000:3000"] },
+                    "db": { "ports": ["127.0.0.1:5432:5432"] },
+                }
+            })
+        );
+
+        // The container is looked up through its compose project when connecting.
+        let connect_command = container.connect_command(&runner).unwrap();
+        assert!(connect_command[2].starts_with(&format!(
+            "docker {compose} start app >/dev/null && id=$(docker {compose} ps -q app)"
+        )));
+        assert!(!connect_command.contains(&"svc789".to_string()));
+    }
+}
@@ -0,0 +1,542 @@
+use crate::ssh_connections::SshSettings;
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, Task,
+    WeakEntity, Window, actions,
+};
+use project::{Fs, Project};
+use remote::{SshConnectionOptions, ssh_session::SshPortForwardOption};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources, update_settings_file};
+use std::{net::IpAddr, sync::Arc, time::Duration};
+use ui::{ListItem, Tab, Tooltip, prelude::*};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+const PORTS_PANEL_KEY: &str = "PortsPanel";
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+actions!(
+    ports_panel,
+    [
+        /// Toggles focus on the ports panel.
+        ToggleFocus
+    ]
+);
+
+pub(crate) fn init(cx: &mut App) {
+    PortsPanelSettings::register(cx);
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<PortsPanel>(window, cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PortsPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct PortsPanelSettingsContent {
+    /// Whether to show the panel button in the status bar of remote projects.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Where to dock the panel.
+    ///
+    /// Default: right
+    pub dock: Option<DockPosition>,
+    /// Default width of the panel in pixels.
+    ///
+    /// Default: 300
+    pub default_width: Option<f32>,
+}
+
+impl Settings for PortsPanelSettings {
+    const KEY: Option<&'static str> = Some("ports_panel");
+
+    type FileContent = PortsPanelSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> Result<Self> {
+        sources.json_merge()
+    }
+
+    fn import_from_vscode(_vscode: &settings::VsCodeSettings, _current: &mut Self::FileContent) {}
+}
+
+/// A dock panel for remote projects, listing the ports that processes on the
+/// remote are listening on, and the ones forwarded to this machine.
+pub struct PortsPanel {
+    project: Entity<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    listening: Vec<proto::ListeningPort>,
+    forwards: Vec<SshPortForwardOption>,
+    error: Option<SharedString>,
+    poll_task: Task<Result<()>>,
+    pending_serialization: Task<Option<()>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedPortsPanel {
+    width: Option<Pixels>,
+}
+
+impl PortsPanel {
+    fn new(workspace: &mut Workspace, cx: &mut Context<Workspace>) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        cx.new(|cx| {
+            // Forwards configured for the connection were set up when connecting.
+            let forwards = project
+                .read(cx)
+                .ssh_client()
+                .and_then(|client| client.read(cx).connection_options().port_forwards)
+                .unwrap_or_default();
+            Self {
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                listening: Vec::new(),
+                forwards,
+                error: None,
+                poll_task: Task::ready(Ok(())),
+                pending_serialization: Task::ready(None),
+            }
+        })
+    }
+
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            let serialized_panel = if let Some(panel) = cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(PORTS_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedPortsPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        PORTS_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedPortsPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    /// Lists the remote's ports every few seconds, while the panel is open.
+    fn poll_listening_ports(&mut self, cx: &mut Context<Self>) {
+        self.poll_task = cx.spawn(async move |this, cx| {
+            loop {
+                let ports = this
+                    .update(cx, |this, cx| {
+                        this.project.read(cx).remote_listening_ports(cx)
+                    })?
+                    .await;
+                this.update(cx, |this, cx| {
+                    match ports {
+                        Ok(ports) => this.listening = ports,
+                        Err(error) => this.error = Some(error.to_string().into()),
+                    }
+                    cx.notify();
+                })?;
+                cx.background_executor().timer(POLL_INTERVAL).await;
+            }
+        });
+    }
+
+    fn connection_options(&self, cx: &App) -> Option<SshConnectionOptions> {
+        self.project.read(cx).ssh_connection_options(cx)
+    }
+
+    fn forward(&mut self, port: &proto::ListeningPort, cx: &mut Context<Self>) {
+        let Some(ssh_client) = self.project.read(cx).ssh_client() else {
+            return;
+        };
+        let forward = SshPortForwardOption {
+            local_host: None,
+            local_port: port.port as u16,
+            remote_host: forward_host(&port.address),
+            remote_port: port.port as u16,
+        };
+        let task = ssh_client.read(cx).forward_port(forward.clone(), cx);
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(()) => {
+                        this.error = None;
+                        this.forwards.push(forward);
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn stop(&mut self, forward: &SshPortForwardOption, cx: &mut Context<Self>) {
+        let Some(ssh_client) = self.project.read(cx).ssh_client() else {
+            return;
+        };
+        let forward = forward.clone();
+        let task = ssh_client.read(cx).cancel_port_forward(forward.clone(), cx);
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(()) => {
+                        this.error = None;
+                        this.forwards.retain(|existing| existing != &forward);
+                    }
+                    Err(error) => this.error = Some(error.to_string().into()),
+                }
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn is_saved(&self, forward: &SshPortForwardOption, cx: &App) -> bool {
+        let Some(options) = self.connection_options(cx) else {
+            return false;
+        };
+        SshSettings::get_global(cx)
+            .connection_options_for(options.host, options.port, options.username)
+            .port_forwards
+            .is_some_and(|forwards| forwards.contains(forward))
+    }
+
+    /// Adds the forward to this host's `port_forwards` setting, so that it's set
+    /// up whenever connecting, or removes it from there.
+    fn toggle_saved(&mut self, forward: &SshPortForwardOption, cx: &mut Context<Self>) {
+        let Some(options) = self.connection_options(cx) else {
+            return;
+        };
+        let forward = forward.clone();
+        update_settings_file::<SshSettings>(self.fs.clone(), cx, move |settings, _| {
+            let Some(connection) = settings.ssh_connections.iter_mut().flatten().find(|conn| {
+                conn.host == options.host
+                    && conn.username == options.username
+                    && conn.port == options.port
+            }) else {
+                return;
+            };
+            let forwards = connection.port_forwards.get_or_insert_default();
+            if let Some(ix) = forwards.iter().position(|existing| existing == &forward) {
+                forwards.remove(ix);
+            } else {
+                forwards.push(forward);
+            }
+        });
+    }
+
+    fn render_forward(
+        &self,
+        ix: usize,
+        forward: &SshPortForwardOption,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let is_saved = self.is_saved(forward, cx);
+        let url = format!(
+            "http://{}:{}",
+            forward.local_host.as_deref().unwrap_or("localhost"),
+            forward.local_port
+        );
+        let save_forward = forward.clone();
+        let stop_forward = forward.clone();
+
+        ListItem::new(("forward", ix))
+            .inset(true)
+            .start_slot(Label::new(forward.local_port.to_string()).size(LabelSize::Small))
+            .child(
+                Label::new(format!(
+                    "{}:{}",
+                    forward.remote_host.as_deref().unwrap_or("localhost"),
+                    forward.remote_port
+                ))
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .truncate(),
+            )
+            .end_slot(
+                h_flex()
+                    .gap_0p5()
+                    .child(
+                        IconButton::new(("open-forward", ix), IconName::ArrowUpRight)
+                            .icon_size(IconSize::XSmall)
+                            .tooltip(Tooltip::text("Open in Browser"))
+                            .on_click(move |_, _, cx| cx.open_url(&url)),
+                    )
+                    .child(
+                        IconButton::new(("save-forward", ix), IconName::Pin)
+                            .icon_size(IconSize::XSmall)
+                            .toggle_state(is_saved)
+                            .tooltip(Tooltip::text(if is_saved {
+                                "Don't Forward When Connecting"
+                            } else {
+                                "Forward When Connecting"
+                            }))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.toggle_saved(&save_forward, cx);
+                            })),
+                    )
+                    .child(
+                        IconButton::new(("stop-forward", ix), IconName::Close)
+                            .icon_size(IconSize::XSmall)
+                            .tooltip(Tooltip::text("Stop Forwarding"))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.stop(&stop_forward, cx);
+                            })),
+                    ),
+            )
+    }
+
+    fn render_listening_port(
+        &self,
+        ix: usize,
+        port: &proto::ListeningPort,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let forward_port = port.clone();
+
+        ListItem::new(("listening", ix))
+            .inset(true)
+            .start_slot(Label::new(port.port.to_string()).size(LabelSize::Small))
+            .child(
+                Label::new(port.process.clone().unwrap_or_else(|| port.address.clone()))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .truncate(),
+            )
+            .end_slot(
+                Button::new(("forward-port", ix), "Forward")
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.forward(&forward_port, cx);
+                    })),
+            )
+    }
+}
+
+/// The host to forward to on the remote, for a socket listening on `address`.
+fn forward_host(address: &str) -> Option<String> {
+    match address.parse::<IpAddr>() {
+        Ok(ip) if ip.is_loopback() || ip.is_unspecified() => None,
+        Ok(IpAddr::V6(ip)) => Some(format!("[{ip}]")),
+        _ => Some(address.to_string()),
+    }
+}
+
+fn render_section_header(label: &'static str) -> impl IntoElement {
+    h_flex()
+        .px_2()
+        .pt_2()
+        .child(Label::new(label).size(LabelSize::Small).color(Color::Muted))
+}
+
+impl Render for PortsPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let forwards = self.forwards.clone();
+        let listening = self
+            .listening
+            .iter()
+            .filter(|port| {
+                !forwards
+                    .iter()
+                    .any(|forward| forward.remote_port as u32 == port.port)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .key_context("PortsPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    // Match the height of the tab bar so they line up.
+                    .h(Tab::container_height(cx))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Ports")),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    h_flex()
+                        .px_2()
+                        .pt_2()
+                        .child(Label::new(error).size(LabelSize::Small).color(Color::Error)),
+                )
+            })
+            .child(
+                v_flex()
+                    .id("ports")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .pb_2()
+                    .child(render_section_header("Forwarded"))
+                    .map(|this| {
+                        if forwards.is_empty() {
+                            return this.child(
+                                v_flex().px_2().py_1().child(
+                                    Label::new("No ports are forwarded.")
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                ),
+                            );
+                        }
+                        this.children(
+                            forwards
+                                .iter()
+                                .enumerate()
+                                .map(|(ix, forward)| self.render_forward(ix, forward, cx)),
+                        )
+                    })
+                    .child(render_section_header("Listening on the Remote"))
+                    .map(|this| {
+                        if listening.is_empty() {
+                            return this.child(
+                                v_flex().px_2().py_1().child(
+                                    Label::new(
+                                        "Servers started in the remote's terminals and tasks are listed here.",
+                                    )
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                                ),
+                            );
+                        }
+                        this.children(
+                            listening
+                                .iter()
+                                .enumerate()
+                                .map(|(ix, port)| self.render_listening_port(ix, port, cx)),
+                        )
+                    }),
+            )
+    }
+}
+
+impl Focusable for PortsPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for PortsPanel {}
+
+impl Panel for PortsPanel {
+    fn persistent_name() -> &'static str {
+        "PortsPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        PortsPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        update_settings_file::<PortsPanelSettings>(self.fs.clone(), cx, move |settings, _| {
+            settings.dock = Some(position)
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| PortsPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn set_active(&mut self, active: bool, _: &mut Window, cx: &mut Context<Self>) {
+        if active {
+            self.poll_listening_ports(cx);
+        } else {
+            self.poll_task = Task::ready(Ok(()));
+        }
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        (PortsPanelSettings::get_global(cx).button && self.enabled(cx)).then_some(IconName::Server)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Ports Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        10
+    }
+
+    fn enabled(&self, cx: &App) -> bool {
+        self.project.read(cx).is_via_ssh()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_host() {
+        assert_eq!(forward_host("127.0.0.1"), None);
+        assert_eq!(forward_host("0.0.0.0"), None);
+        assert_eq!(forward_host("::"), None);
+        assert_eq!(forward_host("::1"), None);
+        assert_eq!(forward_host("10.0.0.5"), Some("10.0.0.5".into()));
+        assert_eq!(forward_host("fd00::5"), Some("[fd00::5]".into()));
+    }
+}
@@ -1,8 +1,11 @@
+mod dev_container;
 pub mod disconnected_overlay;
+mod ports_panel;
 mod remote_servers;
 mod ssh_config;
 mod ssh_connections;
 
+pub use ports_panel::PortsPanel;
 pub use ssh_connections::{is_connecting_over_ssh, open_ssh_project};
 
 use disconnected_overlay::DisconnectedOverlay;
@@ -33,6 +36,7 @@ use zed_actions::{OpenRecent, OpenRemote};
 
 pub fn init(cx: &mut App) {
     SshSettings::register(cx);
+    ports_panel::init(cx);
     cx.on_action(|open_recent: &OpenRecent, cx| {
         let create_new_window = open_recent.create_new_window;
         with_active_or_new_workspace(cx, move |workspace, window, cx| {
@@ -65,6 +69,8 @@ pub fn init(cx: &mut App) {
     });
 
     cx.observe_new(DisconnectedOverlay::register).detach();
+    cx.observe_new(|workspace: &mut Workspace, _, _| dev_container::register(workspace))
+        .detach();
 }
 
 pub struct RecentProjects {
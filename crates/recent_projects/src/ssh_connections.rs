@@ -34,6 +34,8 @@ pub struct SshSettings {
     /// Whether to read ~/.ssh/config for ssh connection sources.
     #[serde(default = "default_true")]
     pub read_ssh_config: bool,
+    /// The container CLI used to start dev containers. Defaults to `docker`.
+    pub dev_container_cli: Option<String>,
 }
 
 impl SshSettings {
@@ -58,6 +60,7 @@ impl SshSettings {
                     username,
                     port_forwards: conn.port_forwards,
                     password: None,
+                    command: conn.command,
                 };
             }
         }
@@ -94,6 +97,13 @@ pub struct SshConnection {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub port_forwards: Option<Vec<SshPortForwardOption>>,
+
+    /// Connect by running this command instead of ssh, such as
+    /// `["docker", "exec", "-i", "my-container"]`. The command is passed the
+    /// program to run and its arguments, and must connect their stdio to its
+    /// own. `host` is then only used to identify the connection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<Vec<String>>,
 }
 
 impl From<SshConnection> for SshConnectionOptions {
@@ -107,6 +117,7 @@ impl From<SshConnection> for SshConnectionOptions {
             nickname: val.nickname,
             upload_binary_over_ssh: val.upload_binary_over_ssh.unwrap_or_default(),
             port_forwards: val.port_forwards,
+            command: val.command,
         }
     }
 }
@@ -120,6 +131,10 @@ pub struct SshProject {
 pub struct RemoteSettingsContent {
     pub ssh_connections: Option<Vec<SshConnection>>,
     pub read_ssh_config: Option<bool>,
+    /// The container CLI used to start dev containers, such as `podman`.
+    ///
+    /// Default: "docker"
+    pub dev_container_cli: Option<String>,
 }
 
 impl Settings for SshSettings {
@@ -0,0 +1,321 @@
+//! Connects to a remote by running a command that connects its stdio to a
+//! program on the remote, such as `docker exec -i <container>` or
+//! `kubectl exec -i <pod> --`, instead of ssh.
+use crate::{
+    shell_script,
+    ssh_session::{
+        RemoteConnection, RemoteShell, SshArgs, SshClientDelegate, SshConnectionOptions,
+        SshPlatform, multiplex, parse_platform, start_proxy_command,
+    },
+};
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use futures::channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender};
+use gpui::{App, AppContext as _, AsyncApp, Task};
+use itertools::Itertools;
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use smol::process::{self, Stdio};
+use std::{
+    iter,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering::SeqCst},
+    },
+};
+use util::paths::{PathStyle, RemotePathBuf};
+
+pub(crate) struct CommandRemoteConnection {
+    connection_options: SshConnectionOptions,
+    command: Vec<String>,
+    remote_binary_path: Option<RemotePathBuf>,
+    platform: SshPlatform,
+    killed: AtomicBool,
+}
+
+impl CommandRemoteConnection {
+    pub(crate) async fn new(
+        connection_options: SshConnectionOptions,
+        delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        delegate.set_status(Some("Connecting"), cx);
+
+        let command = connection_options
+            .command
+            .clone()
+            .filter(|command| !command.is_empty())
+            .context("no command to connect with")?;
+        let uname = run_command(&command, "sh", &["-c", "uname -sm"])
+            .await
+            .context("Failed to connect to host")?;
+        let platform = parse_platform(&uname)?;
+
+        let mut this = Self {
+            connection_options,
+            command,
+            remote_binary_path: None,
+            platform,
+            killed: AtomicBool::new(false),
+        };
+
+        let (release_channel, version, commit) = cx.update(|cx| {
+            (
+                ReleaseChannel::global(cx),
+                AppVersion::global(cx),
+                AppCommitSha::try_global(cx),
+            )
+        })?;
+        this.remote_binary_path = Some(
+            this.ensure_server_binary(&delegate, release_channel, version, commit, cx)
+                .await?,
+        );
+
+        Ok(this)
+    }
+}
+
+/// Runs a shell script on the remote, with piped stdio.
+//
+// Unlike ssh, the command passes its arguments on as they are, so the script
+// only needs to be quoted for `sh`.
+fn shell_command(command: &[String], script: &str) -> process::Command {
+    log::debug!("{} sh -c {:?}", command.join(" "), script);
+    let mut process = util::command::new_smol_command(&command[0]);
+    process
+        .args(&command[1..])
+        .args(["sh", "-c", script])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    process
+}
+
+async fn run_command(command: &[String], program: &str, args: &[&str]) -> Result<String> {
+    let to_run = iter::once(&program)
+        .chain(args.iter())
+        .map(|token| shlex::try_quote(token).unwrap())
+        .join(" ");
+    let output = shell_command(command, &format!("cd; {to_run}"))
+        .output()
+        .await?;
+    anyhow::ensure!(
+        output.status.success(),
+        "failed to run command: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for CommandRemoteConnection {
+    async fn kill(&self) -> Result<()> {
+        // There's no process to kill between commands, as each one runs on
+        // its own.
+        self.killed.store(true, SeqCst);
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        self.killed.load(SeqCst)
+    }
+
+    fn ssh_args(&self) -> SshArgs {
+        SshArgs {
+            arguments: Vec::new(),
+            envs: None,
+            command: Some(self.command.clone()),
+        }
+    }
+
+    fn connection_options(&self) -> SshConnectionOptions {
+        self.connection_options.clone()
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: RemotePathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let mut archive = util::command::new_smol_command("tar");
+        archive
+            .arg("-C")
+            .arg(&src_path)
+            .args(["-cf", "-", "."])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut extract = shell_command(
+            &self.command,
+            &shell_script!(
+                "cd; mkdir -p {dest_path} && tar -xf - -C {dest_path}",
+                dest_path = &dest_path.to_string(),
+            ),
+        );
+
+        cx.background_spawn(async move {
+            let mut archive = archive.spawn()?;
+            let mut extract = extract.spawn()?;
+            let mut archive_stdout = archive.stdout.take().context("no tar stdout")?;
+            let mut extract_stdin = extract.stdin.take().context("no remote stdin")?;
+            futures::io::copy(&mut archive_stdout, &mut extract_stdin).await?;
+            drop(extract_stdin);
+
+            let archive_output = archive.output().await?;
+            let extract_output = extract.output().await?;
+            anyhow::ensure!(
+                archive_output.status.success() && extract_output.status.success(),
+                "failed to upload directory {} -> {}: {}{}",
+                src_path.display(),
+                dest_path.to_string(),
+                String::from_utf8_lossy(&archive_output.stderr),
+                String::from_utf8_lossy(&extract_output.stderr)
+            );
+
+            Ok(())
+        })
+    }
+
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn SshClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_path) = &self.remote_binary_path else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+        let start_proxy_command =
+            start_proxy_command(remote_binary_path, &unique_identifier, reconnect);
+
+        let proxy_process =
+            match shell_command(&self.command, &format!("cd; {start_proxy_command}"))
+                // IMPORTANT: we kill this process when we drop the task that uses it.
+                .kill_on_drop(true)
+                .spawn()
+            {
+                Ok(process) => process,
+                Err(error) => {
+                    return Task::ready(Err(anyhow!("failed to spawn remote server: {}", error)));
+                }
+            };
+
+        multiplex(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            &cx,
+        )
+    }
+
+    fn path_style(&self) -> PathStyle {
+        PathStyle::Posix
+    }
+}
+
+#[async_trait(?Send)]
+impl RemoteShell for CommandRemoteConnection {
+    async fn run_command(&self, program: &str, args: &[&str]) -> Result<String> {
+        run_command(&self.command, program, args).await
+    }
+
+    fn platform(&self) -> SshPlatform {
+        self.platform
+    }
+
+    async fn upload_file(&self, src_path: &Path, dest_path: &RemotePathBuf) -> Result<()> {
+        log::debug!("uploading file {:?} to {:?}", src_path, dest_path);
+        let mut file = smol::fs::File::open(src_path).await?;
+        let mut process = shell_command(
+            &self.command,
+            &shell_script!("cd; cat > {dest_path}", dest_path = &dest_path.to_string()),
+        )
+        .spawn()?;
+        let mut stdin = process.stdin.take().context("no remote stdin")?;
+        futures::io::copy(&mut file, &mut stdin).await?;
+        drop(stdin);
+
+        let output = process.output().await?;
+        anyhow::ensure!(
+            output.status.success(),
+            "failed to upload file {} -> {}: {}",
+            src_path.display(),
+            dest_path.to_string(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_command_transport(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        // `env` runs the program it's given locally, standing in for a command
+        // like `docker exec -i`.
+        let connection = CommandRemoteConnection {
+            connection_options: SshConnectionOptions::default(),
+            command: vec!["env".into()],
+            remote_binary_path: None,
+            platform: SshPlatform {
+                os: "linux",
+                arch: "x86_64",
+            },
+            killed: AtomicBool::new(false),
+        };
+
+        let output = connection
+            .run_command("echo", &["it's", "quoted"])
+            .await
+            .unwrap();
+        assert_eq!(output, "it's quoted\n");
+        assert!(connection.run_command("false", &[]).await.is_err());
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_path = temp_dir.path().join("src");
+        std::fs::create_dir_all(src_path.join("nested")).unwrap();
+        std::fs::write(src_path.join("nested/file.txt"), "contents").unwrap();
+
+        let dest_file = temp_dir.path().join("uploaded.txt");
+        connection
+            .upload_file(
+                &src_path.join("nested/file.txt"),
+                &RemotePathBuf::new(dest_file.clone(), PathStyle::Posix),
+            )
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&dest_file).unwrap(), "contents");
+
+        let dest_dir = temp_dir.path().join("dest");
+        cx.update(|cx| {
+            connection.upload_directory(
+                src_path.clone(),
+                RemotePathBuf::new(dest_dir.clone(), PathStyle::Posix),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dest_dir.join("nested/file.txt")).unwrap(),
+            "contents"
+        );
+
+        assert!(!connection.has_been_killed());
+        connection.kill().await.unwrap();
+        assert!(connection.has_been_killed());
+    }
+}
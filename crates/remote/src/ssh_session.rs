@@ -1,4 +1,5 @@
 use crate::{
+    command_transport::CommandRemoteConnection,
     json_log::LogRecord,
     protocol::{
         MESSAGE_LEN_SIZE, MessageId, message_len_from_buffer, read_message_with_len, write_message,
@@ -78,6 +79,19 @@ pub struct SshPortForwardOption {
     pub remote_port: u16,
 }
 
+impl SshPortForwardOption {
+    /// The `-L` argument that sets up this forward.
+    pub fn ssh_arg(&self) -> String {
+        format!(
+            "-L{}:{}:{}:{}",
+            self.local_host.as_deref().unwrap_or("localhost"),
+            self.local_port,
+            self.remote_host.as_deref().unwrap_or("localhost"),
+            self.remote_port
+        )
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct SshConnectionOptions {
     pub host: String,
@@ -89,11 +103,17 @@ pub struct SshConnectionOptions {
 
     pub nickname: Option<String>,
     pub upload_binary_over_ssh: bool,
+    /// Connect by running this command instead of ssh, such as
+    /// `docker exec -i <container>`. It's passed the program to run on the
+    /// remote and its arguments, and must connect their stdio to its own.
+    pub command: Option<Vec<String>>,
 }
 
 pub struct SshArgs {
     pub arguments: Vec<String>,
     pub envs: Option<HashMap<String, String>>,
+    /// For connections that run a command instead of ssh, that command.
+    pub command: Option<Vec<String>>,
 }
 
 #[macro_export]
@@ -243,6 +263,7 @@ impl SshConnectionOptions {
             password: None,
             nickname: None,
             upload_binary_over_ssh: false,
+            command: None,
         })
     }
 
@@ -266,21 +287,7 @@ impl SshConnectionOptions {
         let mut args = self.args.iter().flatten().cloned().collect::<Vec<String>>();
 
         if let Some(forwards) = &self.port_forwards {
-            args.extend(forwards.iter().map(|pf| {
-                let local_host = match &pf.local_host {
-                    Some(host) => host,
-                    None => "localhost",
-                };
-                let remote_host = match &pf.remote_host {
-                    Some(host) => host,
-                    None => "localhost",
-                };
-
-                format!(
-                    "-L{}:{}:{}:{}",
-                    local_host, pf.local_port, remote_host, pf.remote_port
-                )
-            }));
+            args.extend(forwards.iter().map(SshPortForwardOption::ssh_arg));
         }
 
         args
@@ -384,6 +391,37 @@ impl SshSocket {
         command
     }
 
+    /// Sends a control command, such as `forward` or `cancel`, for a port
+    /// forward to the master process.
+    #[cfg(not(target_os = "windows"))]
+    fn port_forward_command(
+        &self,
+        control: &str,
+        forward: &SshPortForwardOption,
+    ) -> process::Command {
+        let mut command = util::command::new_smol_command("ssh");
+        command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .args(self.port_forward_args(control, forward));
+        command
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn port_forward_args(&self, control: &str, forward: &SshPortForwardOption) -> Vec<String> {
+        vec![
+            "-o".to_string(),
+            "ControlMaster=no".to_string(),
+            "-o".to_string(),
+            format!("ControlPath={}", self.socket_path.display()),
+            "-O".to_string(),
+            control.to_string(),
+            forward.ssh_arg(),
+            self.connection_options.ssh_url(),
+        ]
+    }
+
     async fn run_command(&self, program: &str, args: &[&str]) -> Result<String> {
         let output = self.ssh_command(program, args).output().await?;
         anyhow::ensure!(
@@ -426,6 +464,7 @@ impl SshSocket {
                 self.connection_options.ssh_url(),
             ],
             envs: None,
+            command: None,
         }
     }
 
@@ -434,39 +473,45 @@ impl SshSocket {
         SshArgs {
             arguments: vec![self.connection_options.ssh_url()],
             envs: Some(self.envs.clone()),
+            command: None,
         }
     }
 
     async fn platform(&self) -> Result<SshPlatform> {
         let uname = self.run_command("sh", &["-c", "uname -sm"]).await?;
-        let Some((os, arch)) = uname.split_once(" ") else {
-            anyhow::bail!("unknown uname: {uname:?}")
-        };
+        parse_platform(&uname)
+    }
+}
 
-        let os = match os.trim() {
-            "Darwin" => "macos",
-            "Linux" => "linux",
-            _ => anyhow::bail!(
-                "Prebuilt remote servers are not yet available for {os:?}. See https://zed.dev/docs/remote-development"
-            ),
-        };
-        // exclude armv5,6,7 as they are 32-bit.
-        let arch = if arch.starts_with("armv8")
-            || arch.starts_with("armv9")
-            || arch.starts_with("arm64")
-            || arch.starts_with("aarch64")
-        {
-            "aarch64"
-        } else if arch.starts_with("x86") {
-            "x86_64"
-        } else {
-            anyhow::bail!(
-                "Prebuilt remote servers are not yet available for {arch:?}. See https://zed.dev/docs/remote-development"
-            )
-        };
+/// Parses the output of `uname -sm` on the remote.
+pub(crate) fn parse_platform(uname: &str) -> Result<SshPlatform> {
+    let Some((os, arch)) = uname.split_once(" ") else {
+        anyhow::bail!("unknown uname: {uname:?}")
+    };
 
-        Ok(SshPlatform { os, arch })
-    }
+    let os = match os.trim() {
+        "Darwin" => "macos",
+        "Linux" => "linux",
+        _ => anyhow::bail!(
+            "Prebuilt remote servers are not yet available for {os:?}. See https://zed.dev/docs/remote-development"
+        ),
+    };
+    // exclude armv5,6,7 as they are 32-bit.
+    let arch = if arch.starts_with("armv8")
+        || arch.starts_with("armv9")
+        || arch.starts_with("arm64")
+        || arch.starts_with("aarch64")
+    {
+        "aarch64"
+    } else if arch.starts_with("x86") {
+        "x86_64"
+    } else {
+        anyhow::bail!(
+            "Prebuilt remote servers are not yet available for {arch:?}. See https://zed.dev/docs/remote-development"
+        )
+    };
+
+    Ok(SshPlatform { os, arch })
 }
 
 const MAX_MISSED_HEARTBEATS: usize = 5;
@@ -1164,6 +1209,22 @@ impl SshRemoteClient {
         connection.upload_directory(src_path, dest_path, cx)
     }
 
+    pub fn forward_port(&self, forward: SshPortForwardOption, cx: &App) -> Task<Result<()>> {
+        let state = self.state.lock();
+        let Some(connection) = state.as_ref().and_then(|state| state.ssh_connection()) else {
+            return Task::ready(Err(anyhow!("no ssh connection")));
+        };
+        connection.forward_port(forward, cx)
+    }
+
+    pub fn cancel_port_forward(&self, forward: SshPortForwardOption, cx: &App) -> Task<Result<()>> {
+        let state = self.state.lock();
+        let Some(connection) = state.as_ref().and_then(|state| state.ssh_connection()) else {
+            return Task::ready(Err(anyhow!("no ssh connection")));
+        };
+        connection.cancel_port_forward(forward, cx)
+    }
+
     pub fn proto_client(&self) -> AnyProtoClient {
         self.client.clone().into()
     }
@@ -1319,9 +1380,15 @@ impl ConnectionPool {
                 let opts = opts.clone();
                 let delegate = delegate.clone();
                 async move |cx| {
-                    let connection = SshRemoteConnection::new(opts.clone(), delegate, cx)
-                        .await
-                        .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>);
+                    let connection = if opts.command.is_some() {
+                        CommandRemoteConnection::new(opts.clone(), delegate, cx)
+                            .await
+                            .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                    } else {
+                        SshRemoteConnection::new(opts.clone(), delegate, cx)
+                            .await
+                            .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                    };
 
                     cx.update_global(|pool: &mut Self, _| {
                         debug_assert!(matches!(
@@ -1359,7 +1426,7 @@ impl From<SshRemoteClient> for AnyProtoClient {
 }
 
 #[async_trait(?Send)]
-trait RemoteConnection: Send + Sync {
+pub(crate) trait RemoteConnection: Send + Sync {
     fn start_proxy(
         &self,
         unique_identifier: String,
@@ -1383,6 +1450,19 @@ trait RemoteConnection: Send + Sync {
     fn ssh_args(&self) -> SshArgs;
     fn connection_options(&self) -> SshConnectionOptions;
     fn path_style(&self) -> PathStyle;
+    /// Starts forwarding a local port to the remote over this connection.
+    fn forward_port(&self, _forward: SshPortForwardOption, _cx: &App) -> Task<Result<()>> {
+        Task::ready(Err(anyhow!(
+            "port forwarding isn't supported on this connection"
+        )))
+    }
+    /// Stops a forward started with `forward_port`, or configured when
+    /// connecting.
+    fn cancel_port_forward(&self, _forward: SshPortForwardOption, _cx: &App) -> Task<Result<()>> {
+        Task::ready(Err(anyhow!(
+            "port forwarding isn't supported on this connection"
+        )))
+    }
 
     #[cfg(any(test, feature = "test-support"))]
     fn simulate_disconnect(&self, _: &AsyncApp) {}
@@ -1462,6 +1542,42 @@ impl RemoteConnection for SshRemoteConnection {
         })
     }
 
+    #[cfg(not(target_os = "windows"))]
+    fn forward_port(&self, forward: SshPortForwardOption, cx: &App) -> Task<Result<()>> {
+        let output = self
+            .socket
+            .port_forward_command("forward", &forward)
+            .output();
+        cx.background_spawn(async move {
+            let output = output.await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "failed to forward port {}: {}",
+                forward.local_port,
+                String::from_utf8_lossy(&output.stderr)
+            );
+            Ok(())
+        })
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn cancel_port_forward(&self, forward: SshPortForwardOption, cx: &App) -> Task<Result<()>> {
+        let output = self
+            .socket
+            .port_forward_command("cancel", &forward)
+            .output();
+        cx.background_spawn(async move {
+            let output = output.await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "failed to stop forwarding port {}: {}",
+                forward.local_port,
+                String::from_utf8_lossy(&output.stderr)
+            );
+            Ok(())
+        })
+    }
+
     fn start_proxy(
         &self,
         unique_identifier: String,
@@ -1474,33 +1590,11 @@ impl RemoteConnection for SshRemoteConnection {
     ) -> Task<Result<i32>> {
         delegate.set_status(Some("Starting proxy"), cx);
 
-        let Some(remote_binary_path) = self.remote_binary_path.clone() else {
+        let Some(remote_binary_path) = &self.remote_binary_path else {
             return Task::ready(Err(anyhow!("Remote binary path not set")));
         };
-
-        let mut start_proxy_command = shell_script!(
-            "exec {binary_path} proxy --identifier {identifier}",
-            binary_path = &remote_binary_path.to_string(),
-            identifier = &unique_identifier,
-        );
-
-        if let Some(rust_log) = std::env::var("RUST_LOG").ok() {
-            start_proxy_command = format!(
-                "RUST_LOG={} {}",
-                shlex::try_quote(&rust_log).unwrap(),
-                start_proxy_command
-            )
-        }
-        if let Some(rust_backtrace) = std::env::var("RUST_BACKTRACE").ok() {
-            start_proxy_command = format!(
-                "RUST_BACKTRACE={} {}",
-                shlex::try_quote(&rust_backtrace).unwrap(),
-                start_proxy_command
-            )
-        }
-        if reconnect {
-            start_proxy_command.push_str(" --reconnect");
-        }
+        let start_proxy_command =
+            start_proxy_command(remote_binary_path, &unique_identifier, reconnect);
 
         let ssh_proxy_process = match self
             .socket
@@ -1515,7 +1609,7 @@ impl RemoteConnection for SshRemoteConnection {
             }
         };
 
-        Self::multiplex(
+        multiplex(
             ssh_proxy_process,
             incoming_tx,
             outgoing_rx,
@@ -1529,6 +1623,38 @@ impl RemoteConnection for SshRemoteConnection {
     }
 }
 
+/// Returns the shell command that starts the remote server's proxy.
+pub(crate) fn start_proxy_command(
+    remote_binary_path: &RemotePathBuf,
+    unique_identifier: &str,
+    reconnect: bool,
+) -> String {
+    let mut start_proxy_command = shell_script!(
+        "exec {binary_path} proxy --identifier {identifier}",
+        binary_path = &remote_binary_path.to_string(),
+        identifier = unique_identifier,
+    );
+
+    if let Some(rust_log) = std::env::var("RUST_LOG").ok() {
+        start_proxy_command = format!(
+            "RUST_LOG={} {}",
+            shlex::try_quote(&rust_log).unwrap(),
+            start_proxy_command
+        )
+    }
+    if let Some(rust_backtrace) = std::env::var("RUST_BACKTRACE").ok() {
+        start_proxy_command = format!(
+            "RUST_BACKTRACE={} {}",
+            shlex::try_quote(&rust_backtrace).unwrap(),
+            start_proxy_command
+        )
+    }
+    if reconnect {
+        start_proxy_command.push_str(" --reconnect");
+    }
+    start_proxy_command
+}
+
 impl SshRemoteConnection {
     async fn new(
         connection_options: SshConnectionOptions,
@@ -1660,108 +1786,164 @@ impl SshRemoteConnection {
 
         Ok(this)
     }
+}
 
-    fn multiplex(
-        mut ssh_proxy_process: Child,
-        incoming_tx: UnboundedSender<Envelope>,
-        mut outgoing_rx: UnboundedReceiver<Envelope>,
-        mut connection_activity_tx: Sender<()>,
-        cx: &AsyncApp,
-    ) -> Task<Result<i32>> {
-        let mut child_stderr = ssh_proxy_process.stderr.take().unwrap();
-        let mut child_stdout = ssh_proxy_process.stdout.take().unwrap();
-        let mut child_stdin = ssh_proxy_process.stdin.take().unwrap();
-
-        let mut stdin_buffer = Vec::new();
-        let mut stdout_buffer = Vec::new();
-        let mut stderr_buffer = Vec::new();
-        let mut stderr_offset = 0;
-
-        let stdin_task = cx.background_spawn(async move {
-            while let Some(outgoing) = outgoing_rx.next().await {
-                write_message(&mut child_stdin, &mut stdin_buffer, outgoing).await?;
-            }
-            anyhow::Ok(())
-        });
+#[async_trait(?Send)]
+impl RemoteShell for SshRemoteConnection {
+    async fn run_command(&self, program: &str, args: &[&str]) -> Result<String> {
+        self.socket.run_command(program, args).await
+    }
 
-        let stdout_task = cx.background_spawn({
-            let mut connection_activity_tx = connection_activity_tx.clone();
-            async move {
-                loop {
-                    stdout_buffer.resize(MESSAGE_LEN_SIZE, 0);
-                    let len = child_stdout.read(&mut stdout_buffer).await?;
+    fn platform(&self) -> SshPlatform {
+        self.ssh_platform
+    }
 
-                    if len == 0 {
-                        return anyhow::Ok(());
-                    }
+    async fn upload_file(&self, src_path: &Path, dest_path: &RemotePathBuf) -> Result<()> {
+        log::debug!("uploading file {:?} to {:?}", src_path, dest_path);
+        let mut command = util::command::new_smol_command("scp");
+        let output = self
+            .socket
+            .ssh_options(&mut command)
+            .args(
+                self.socket
+                    .connection_options
+                    .port
+                    .map(|port| vec!["-P".to_string(), port.to_string()])
+                    .unwrap_or_default(),
+            )
+            .arg(src_path)
+            .arg(format!(
+                "{}:{}",
+                self.socket.connection_options.scp_url(),
+                dest_path.to_string()
+            ))
+            .output()
+            .await?;
 
-                    if len < MESSAGE_LEN_SIZE {
-                        child_stdout.read_exact(&mut stdout_buffer[len..]).await?;
-                    }
+        anyhow::ensure!(
+            output.status.success(),
+            "failed to upload file {} -> {}: {}",
+            src_path.display(),
+            dest_path.to_string(),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(())
+    }
+}
 
-                    let message_len = message_len_from_buffer(&stdout_buffer);
-                    let envelope =
-                        read_message_with_len(&mut child_stdout, &mut stdout_buffer, message_len)
-                            .await?;
-                    connection_activity_tx.try_send(()).ok();
-                    incoming_tx.unbounded_send(envelope).ok();
-                }
-            }
-        });
+/// Forwards messages between the client and the stdio of a process that's
+/// running the remote server's proxy.
+pub(crate) fn multiplex(
+    mut ssh_proxy_process: Child,
+    incoming_tx: UnboundedSender<Envelope>,
+    mut outgoing_rx: UnboundedReceiver<Envelope>,
+    mut connection_activity_tx: Sender<()>,
+    cx: &AsyncApp,
+) -> Task<Result<i32>> {
+    let mut child_stderr = ssh_proxy_process.stderr.take().unwrap();
+    let mut child_stdout = ssh_proxy_process.stdout.take().unwrap();
+    let mut child_stdin = ssh_proxy_process.stdin.take().unwrap();
+
+    let mut stdin_buffer = Vec::new();
+    let mut stdout_buffer = Vec::new();
+    let mut stderr_buffer = Vec::new();
+    let mut stderr_offset = 0;
+
+    let stdin_task = cx.background_spawn(async move {
+        while let Some(outgoing) = outgoing_rx.next().await {
+            write_message(&mut child_stdin, &mut stdin_buffer, outgoing).await?;
+        }
+        anyhow::Ok(())
+    });
 
-        let stderr_task: Task<anyhow::Result<()>> = cx.background_spawn(async move {
+    let stdout_task = cx.background_spawn({
+        let mut connection_activity_tx = connection_activity_tx.clone();
+        async move {
             loop {
-                stderr_buffer.resize(stderr_offset + 1024, 0);
+                stdout_buffer.resize(MESSAGE_LEN_SIZE, 0);
+                let len = child_stdout.read(&mut stdout_buffer).await?;
 
-                let len = child_stderr
-                    .read(&mut stderr_buffer[stderr_offset..])
-                    .await?;
                 if len == 0 {
                     return anyhow::Ok(());
                 }
 
-                stderr_offset += len;
-                let mut start_ix = 0;
-                while let Some(ix) = stderr_buffer[start_ix..stderr_offset]
-                    .iter()
-                    .position(|b| b == &b'\n')
-                {
-                    let line_ix = start_ix + ix;
-                    let content = &stderr_buffer[start_ix..line_ix];
-                    start_ix = line_ix + 1;
-                    if let Ok(record) = serde_json::from_slice::<LogRecord>(content) {
-                        record.log(log::logger())
-                    } else {
-                        eprintln!("(remote) {}", String::from_utf8_lossy(content));
-                    }
+                if len < MESSAGE_LEN_SIZE {
+                    child_stdout.read_exact(&mut stdout_buffer[len..]).await?;
                 }
-                stderr_buffer.drain(0..start_ix);
-                stderr_offset -= start_ix;
 
+                let message_len = message_len_from_buffer(&stdout_buffer);
+                let envelope =
+                    read_message_with_len(&mut child_stdout, &mut stdout_buffer, message_len)
+                        .await?;
                 connection_activity_tx.try_send(()).ok();
+                incoming_tx.unbounded_send(envelope).ok();
             }
-        });
+        }
+    });
 
-        cx.background_spawn(async move {
-            let result = futures::select! {
-                result = stdin_task.fuse() => {
-                    result.context("stdin")
-                }
-                result = stdout_task.fuse() => {
-                    result.context("stdout")
-                }
-                result = stderr_task.fuse() => {
-                    result.context("stderr")
+    let stderr_task: Task<anyhow::Result<()>> = cx.background_spawn(async move {
+        loop {
+            stderr_buffer.resize(stderr_offset + 1024, 0);
+
+            let len = child_stderr
+                .read(&mut stderr_buffer[stderr_offset..])
+                .await?;
+            if len == 0 {
+                return anyhow::Ok(());
+            }
+
+            stderr_offset += len;
+            let mut start_ix = 0;
+            while let Some(ix) = stderr_buffer[start_ix..stderr_offset]
+                .iter()
+                .position(|b| b == &b'\n')
+            {
+                let line_ix = start_ix + ix;
+                let content = &stderr_buffer[start_ix..line_ix];
+                start_ix = line_ix + 1;
+                if let Ok(record) = serde_json::from_slice::<LogRecord>(content) {
+                    record.log(log::logger())
+                } else {
+                    eprintln!("(remote) {}", String::from_utf8_lossy(content));
                 }
-            };
+            }
+            stderr_buffer.drain(0..start_ix);
+            stderr_offset -= start_ix;
 
-            let status = ssh_proxy_process.status().await?.code().unwrap_or(1);
-            match result {
-                Ok(_) => Ok(status),
-                Err(error) => Err(error),
+            connection_activity_tx.try_send(()).ok();
+        }
+    });
+
+    cx.background_spawn(async move {
+        let result = futures::select! {
+            result = stdin_task.fuse() => {
+                result.context("stdin")
             }
-        })
-    }
+            result = stdout_task.fuse() => {
+                result.context("stdout")
+            }
+            result = stderr_task.fuse() => {
+                result.context("stderr")
+            }
+        };
+
+        let status = ssh_proxy_process.status().await?.code().unwrap_or(1);
+        match result {
+            Ok(_) => Ok(status),
+            Err(error) => Err(error),
+        }
+    })
+}
+
+/// Runs commands on a remote host, which is all that installing the remote
+/// server there needs.
+#[async_trait(?Send)]
+pub(crate) trait RemoteShell: RemoteConnection {
+    /// Runs a program on the remote from its home directory, returning its
+    /// stdout.
+    async fn run_command(&self, program: &str, args: &[&str]) -> Result<String>;
+    async fn upload_file(&self, src_path: &Path, dest_path: &RemotePathBuf) -> Result<()>;
+    fn platform(&self) -> SshPlatform;
 
     #[allow(unused)]
     async fn ensure_server_binary(
@@ -1787,7 +1969,7 @@ impl SshRemoteConnection {
         );
         let dst_path = RemotePathBuf::new(
             paths::remote_server_dir_relative().join(binary_name),
-            self.ssh_path_style,
+            self.path_style(),
         );
 
         let build_remote_server = std::env::var("ZED_BUILD_REMOTE_SERVER").ok();
@@ -1800,7 +1982,7 @@ impl SshRemoteConnection {
                     std::process::id(),
                     src_path.file_name().unwrap().to_string_lossy()
                 )),
-                self.ssh_path_style,
+                self.path_style(),
             );
             self.upload_local_server_binary(&src_path, &tmp_path, delegate, cx)
                 .await?;
@@ -1810,7 +1992,6 @@ impl SshRemoteConnection {
         }
 
         if self
-            .socket
             .run_command(&dst_path.to_string(), &["version"])
             .await
             .is_ok()
@@ -1835,11 +2016,11 @@ impl SshRemoteConnection {
                 dst_path.to_string(),
                 std::process::id()
             )),
-            self.ssh_path_style,
+            self.path_style(),
         );
-        if !self.socket.connection_options.upload_binary_over_ssh {
+        if !self.connection_options().upload_binary_over_ssh {
             if let Some((url, body)) = delegate
-                .get_download_params(self.ssh_platform, release_channel, wanted_version, cx)
+                .get_download_params(self.platform(), release_channel, wanted_version, cx)
                 .await?
             {
                 match self
@@ -1862,7 +2043,7 @@ impl SshRemoteConnection {
         }
 
         let src_path = delegate
-            .download_server_binary_locally(self.ssh_platform, release_channel, wanted_version, cx)
+            .download_server_binary_locally(self.platform(), release_channel, wanted_version, cx)
             .await?;
         self.upload_local_server_binary(&src_path, &tmp_path_gz, delegate, cx)
             .await?;
@@ -1880,21 +2061,19 @@ impl SshRemoteConnection {
         cx: &mut AsyncApp,
     ) -> Result<()> {
         if let Some(parent) = tmp_path_gz.parent() {
-            self.socket
-                .run_command(
-                    "sh",
-                    &[
-                        "-c",
-                        &shell_script!("mkdir -p {parent}", parent = parent.to_string().as_ref()),
-                    ],
-                )
-                .await?;
+            self.run_command(
+                "sh",
+                &[
+                    "-c",
+                    &shell_script!("mkdir -p {parent}", parent = parent.to_string().as_ref()),
+                ],
+            )
+            .await?;
         }
 
         delegate.set_status(Some("Downloading remote development server on host"), cx);
 
         match self
-            .socket
             .run_command(
                 "curl",
                 &[
@@ -1915,12 +2094,11 @@ impl SshRemoteConnection {
         {
             Ok(_) => {}
             Err(e) => {
-                if self.socket.run_command("which", &["curl"]).await.is_ok() {
+                if self.run_command("which", &["curl"]).await.is_ok() {
                     return Err(e);
                 }
 
                 match self
-                    .socket
                     .run_command(
                         "wget",
                         &[
@@ -1937,7 +2115,7 @@ impl SshRemoteConnection {
                 {
                     Ok(_) => {}
                     Err(e) => {
-                        if self.socket.run_command("which", &["wget"]).await.is_ok() {
+                        if self.run_command("which", &["wget"]).await.is_ok() {
                             return Err(e);
                         } else {
                             anyhow::bail!("Neither curl nor wget is available");
@@ -1958,15 +2136,14 @@ impl SshRemoteConnection {
         cx: &mut AsyncApp,
     ) -> Result<()> {
         if let Some(parent) = tmp_path_gz.parent() {
-            self.socket
-                .run_command(
-                    "sh",
-                    &[
-                        "-c",
-                        &shell_script!("mkdir -p {parent}", parent = parent.to_string().as_ref()),
-                    ],
-                )
-                .await?;
+            self.run_command(
+                "sh",
+                &[
+                    "-c",
+                    &shell_script!("mkdir -p {parent}", parent = parent.to_string().as_ref()),
+                ],
+            )
+            .await?;
         }
 
         let src_stat = fs::metadata(&src_path).await?;
@@ -2010,39 +2187,7 @@ impl SshRemoteConnection {
                 dst_path = &dst_path.to_string()
             )
         };
-        self.socket.run_command("sh", &["-c", &script]).await?;
-        Ok(())
-    }
-
-    async fn upload_file(&self, src_path: &Path, dest_path: &RemotePathBuf) -> Result<()> {
-        log::debug!("uploading file {:?} to {:?}", src_path, dest_path);
-        let mut command = util::command::new_smol_command("scp");
-        let output = self
-            .socket
-            .ssh_options(&mut command)
-            .args(
-                self.socket
-                    .connection_options
-                    .port
-                    .map(|port| vec!["-P".to_string(), port.to_string()])
-                    .unwrap_or_default(),
-            )
-            .arg(src_path)
-            .arg(format!(
-                "{}:{}",
-                self.socket.connection_options.scp_url(),
-                dest_path.to_string()
-            ))
-            .output()
-            .await?;
-
-        anyhow::ensure!(
-            output.status.success(),
-            "failed to upload file {} -> {}: {}",
-            src_path.display(),
-            dest_path.to_string(),
-            String::from_utf8_lossy(&output.stderr)
-        );
+        self.run_command("sh", &["-c", &script]).await?;
         Ok(())
     }
 
@@ -2071,11 +2216,11 @@ impl SshRemoteConnection {
 
         let triple = format!(
             "{}-{}",
-            self.ssh_platform.arch,
-            match self.ssh_platform.os {
+            self.platform().arch,
+            match self.platform().os {
                 "linux" => "unknown-linux-musl",
                 "macos" => "apple-darwin",
-                _ => anyhow::bail!("can't cross compile for: {:?}", self.ssh_platform),
+                _ => anyhow::bail!("can't cross compile for: {:?}", self.platform()),
             }
         );
         let mut rust_flags = match std::env::var("RUSTFLAGS") {
@@ -2086,15 +2231,15 @@ impl SshRemoteConnection {
                 String::new()
             }
         };
-        if self.ssh_platform.os == "linux" {
+        if self.platform().os == "linux" {
             rust_flags.push_str(" -C target-feature=+crt-static");
         }
         if build_remote_server.contains("mold") {
             rust_flags.push_str(" -C link-arg=-fuse-ld=mold");
         }
 
-        if self.ssh_platform.arch == std::env::consts::ARCH
-            && self.ssh_platform.os == std::env::consts::OS
+        if self.platform().arch == std::env::consts::ARCH
+            && self.platform().os == std::env::consts::OS
         {
             delegate.set_status(Some("Building remote server binary from source"), cx);
             log::info!("building remote server binary from source");
@@ -2626,6 +2771,7 @@ mod fake {
             SshArgs {
                 arguments: Vec::new(),
                 envs: None,
+                command: None,
             }
         }
 
@@ -2724,3 +2870,45 @@ mod fake {
         fn set_status(&self, _: Option<&str>, _: &mut AsyncApp) {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_port_forward_args() {
+        let socket = SshSocket::new(
+            SshConnectionOptions {
+                host: "example.com".to_string(),
+                username: Some("me".to_string()),
+                port: Some(2222),
+                ..Default::default()
+            },
+            PathBuf::from("/tmp/zed-ssh/ssh.sock"),
+        )
+        .unwrap();
+        let forward = SshPortForwardOption {
+            local_host: None,
+            local_port: 8080,
+            remote_host: Some("127.0.0.1".to_string()),
+            remote_port: 3000,
+        };
+
+        for control in ["forward", "cancel"] {
+            assert_eq!(
+                socket.port_forward_args(control, &forward),
+                [
+                    "-o",
+                    "ControlMaster=no",
+                    "-o",
+                    "ControlPath=/tmp/zed-ssh/ssh.sock",
+                    "-O",
+                    control,
+                    "-Llocalhost:8080:127.0.0.1:3000",
+                    "ssh://me@example.com:2222",
+                ]
+            );
+        }
+    }
+}
@@ -16,6 +16,7 @@ use project::{
     git_store::GitStore,
     project_settings::SettingsObserver,
     search::SearchQuery,
+    search_index::SearchIndexStore,
     task_store::TaskStore,
     worktree_store::WorktreeStore,
 };
@@ -39,6 +40,7 @@ pub struct HeadlessProject {
     pub session: AnyProtoClient,
     pub worktree_store: Entity<WorktreeStore>,
     pub buffer_store: Entity<BufferStore>,
+    pub search_index_store: Entity<SearchIndexStore>,
     pub lsp_store: Entity<LspStore>,
     pub task_store: Entity<TaskStore>,
     pub dap_store: Entity<DapStore>,
@@ -103,6 +105,11 @@ impl HeadlessProject {
             buffer_store
         });
 
+        // Searches from the client run against this store's worktrees, so
+        // they're served from the index here too.
+        let search_index_store =
+            cx.new(|cx| SearchIndexStore::local(worktree_store.clone(), fs.clone(), cx));
+
         let breakpoint_store =
             cx.new(|_| BreakpointStore::local(worktree_store.clone(), buffer_store.clone()));
 
@@ -225,6 +232,7 @@ impl HeadlessProject {
 
         client.add_request_handler(cx.weak_entity(), Self::handle_list_remote_directory);
         client.add_request_handler(cx.weak_entity(), Self::handle_get_path_metadata);
+        client.add_request_handler(cx.weak_entity(), Self::handle_list_listening_ports);
         client.add_request_handler(cx.weak_entity(), Self::handle_shutdown_remote_server);
         client.add_request_handler(cx.weak_entity(), Self::handle_ping);
 
@@ -265,6 +273,7 @@ impl HeadlessProject {
             fs,
             worktree_store,
             buffer_store,
+            search_index_store,
             lsp_store,
             task_store,
             dap_store,
@@ -644,6 +653,17 @@ impl HeadlessProject {
         })
     }
 
+    pub async fn handle_list_listening_ports(
+        _this: Entity<Self>,
+        _envelope: TypedEnvelope<proto::ListListeningPorts>,
+        cx: AsyncApp,
+    ) -> Result<proto::ListListeningPortsResponse> {
+        let ports = cx
+            .background_spawn(async move { crate::listening_ports::listening_ports() })
+            .await?;
+        Ok(proto::ListListeningPortsResponse { ports })
+    }
+
     pub async fn handle_shutdown_remote_server(
         _this: Entity<Self>,
         _envelope: TypedEnvelope<proto::ShutdownRemoteServer>,
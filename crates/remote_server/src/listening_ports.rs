@@ -0,0 +1,309 @@
+//! Finds the TCP ports that processes on this host are listening on, so that
+//! the client can offer to forward them.
+use anyhow::Result;
+use rpc::proto;
+
+/// Returns the ports listened on by the processes started in terminals and
+/// tasks, and by their descendants.
+#[cfg(target_os = "linux")]
+pub fn listening_ports() -> Result<Vec<proto::ListeningPort>> {
+    let processes = linux::terminal_processes();
+    listening_ports_of(|pid| processes.contains(&pid))
+}
+
+#[cfg(target_os = "linux")]
+fn listening_ports_of(is_listed: impl Fn(u32) -> bool) -> Result<Vec<proto::ListeningPort>> {
+    use anyhow::Context as _;
+    use std::os::unix::fs::MetadataExt as _;
+
+    let uid = std::fs::metadata("/proc/self")
+        .context("reading /proc/self")?
+        .uid();
+    let mut sockets = Vec::new();
+    for table in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        // tcp6 doesn't exist when IPv6 is disabled.
+        if let Ok(contents) = std::fs::read_to_string(table) {
+            sockets.extend(
+                linux::parse_listening_sockets(&contents)
+                    .into_iter()
+                    .filter(|socket| socket.uid == uid),
+            );
+        }
+    }
+
+    let owners = linux::socket_owners();
+    let mut ports = sockets
+        .into_iter()
+        .filter_map(|socket| {
+            let owner = owners.get(&socket.inode)?;
+            is_listed(owner.pid).then(|| proto::ListeningPort {
+                address: socket.address.to_string(),
+                port: socket.port as u32,
+                process: Some(owner.name.clone()),
+            })
+        })
+        .collect::<Vec<_>>();
+    // Servers often listen on both IPv4 and IPv6, which only needs one forward.
+    ports.sort_by(|a, b| a.port.cmp(&b.port).then_with(|| a.address.cmp(&b.address)));
+    ports.dedup_by_key(|port| port.port);
+    Ok(ports)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn listening_ports() -> Result<Vec<proto::ListeningPort>> {
+    anyhow::bail!("listing ports is only supported on Linux hosts")
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        collections::{HashMap, HashSet},
+        net::IpAddr,
+        path::PathBuf,
+    };
+
+    /// The `st` column's value for sockets in the `LISTEN` state.
+    const TCP_LISTEN: &str = "0A";
+
+    /// Set in the environment of terminals and tasks, including those that
+    /// are started on the remote over SSH.
+    const TERMINAL_ENV_VAR: &[u8] = b"ZED_TERM=true";
+
+    pub(super) struct SocketOwner {
+        pub pid: u32,
+        pub name: String,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub(super) struct ListeningSocket {
+        pub address: IpAddr,
+        pub port: u16,
+        pub uid: u32,
+        pub inode: u64,
+    }
+
+    /// Parses the listening sockets out of `/proc/net/tcp` or `/proc/net/tcp6`.
+    pub(super) fn parse_listening_sockets(table: &str) -> Vec<ListeningSocket> {
+        table
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                // sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode
+                let fields = line.split_whitespace().collect::<Vec<_>>();
+                if *fields.get(3)? != TCP_LISTEN {
+                    return None;
+                }
+                let (address, port) = fields.get(1)?.split_once(':')?;
+                Some(ListeningSocket {
+                    address: parse_address(address)?,
+                    port: u16::from_str_radix(port, 16).ok()?,
+                    uid: fields.get(7)?.parse().ok()?,
+                    inode: fields.get(9)?.parse().ok()?,
+                })
+            })
+            .collect()
+    }
+
+    /// The kernel writes addresses as the hex of each 32-bit word, in host
+    /// byte order.
+    fn parse_address(hex: &str) -> Option<IpAddr> {
+        let mut bytes = Vec::with_capacity(16);
+        for word in hex.as_bytes().chunks(8) {
+            let word = u32::from_str_radix(std::str::from_utf8(word).ok()?, 16).ok()?;
+            bytes.extend(word.to_ne_bytes());
+        }
+        match bytes.len() {
+            4 => Some(IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?)),
+            16 => Some(IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?)),
+            _ => None,
+        }
+    }
+
+    /// Returns the processes started in terminals and tasks, along with all of
+    /// their descendants, which may have cleared their environment.
+    pub(super) fn terminal_processes() -> HashSet<u32> {
+        let mut parents = HashMap::new();
+        let mut roots = HashSet::new();
+        for (pid, path) in processes() {
+            if let Some(parent) = std::fs::read_to_string(path.join("stat"))
+                .ok()
+                .and_then(|stat| parse_parent_pid(&stat))
+            {
+                parents.insert(pid, parent);
+            }
+            if std::fs::read(path.join("environ")).is_ok_and(|environ| {
+                environ
+                    .split(|byte| *byte == 0)
+                    .any(|var| var == TERMINAL_ENV_VAR)
+            }) {
+                roots.insert(pid);
+            }
+        }
+        process_tree(&parents, roots)
+    }
+
+    /// Reads the parent's pid out of `/proc/<pid>/stat`. The process name
+    /// comes before it, in parentheses, and may contain spaces or parentheses
+    /// itself.
+    fn parse_parent_pid(stat: &str) -> Option<u32> {
+        let (_, rest) = stat.rsplit_once(')')?;
+        // state ppid ...
+        rest.split_whitespace().nth(1)?.parse().ok()
+    }
+
+    /// Extends the given roots with every process descending from them.
+    fn process_tree(parents: &HashMap<u32, u32>, roots: HashSet<u32>) -> HashSet<u32> {
+        let mut tree = roots;
+        for &pid in parents.keys() {
+            let mut ancestors = Vec::new();
+            let mut ancestor = Some(pid);
+            while let Some(process) = ancestor {
+                if tree.contains(&process) {
+                    tree.extend(ancestors);
+                    break;
+                }
+                ancestors.push(process);
+                ancestor = parents.get(&process).copied().filter(|parent| *parent != 0);
+            }
+        }
+        tree
+    }
+
+    /// Maps socket inodes to the process that has them open, for the
+    /// processes we're allowed to inspect.
+    pub(super) fn socket_owners() -> HashMap<u64, SocketOwner> {
+        let mut owners = HashMap::new();
+        for (pid, path) in processes() {
+            let Ok(fds) = std::fs::read_dir(path.join("fd")) else {
+                continue;
+            };
+
+            let mut name = None;
+            for fd in fds.flatten() {
+                let Some(inode) = std::fs::read_link(fd.path()).ok().and_then(|target| {
+                    target
+                        .to_str()?
+                        .strip_prefix("socket:[")?
+                        .strip_suffix(']')?
+                        .parse()
+                        .ok()
+                }) else {
+                    continue;
+                };
+                let name = name.get_or_insert_with(|| {
+                    std::fs::read_to_string(path.join("comm"))
+                        .map(|comm| comm.trim().to_string())
+                        .unwrap_or_default()
+                });
+                owners.insert(
+                    inode,
+                    SocketOwner {
+                        pid,
+                        name: name.clone(),
+                    },
+                );
+            }
+        }
+        owners
+    }
+
+    /// Lists the pid and `/proc` directory of each running process.
+    fn processes() -> Vec<(u32, PathBuf)> {
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let pid = entry.file_name().to_str()?.parse().ok()?;
+                Some((pid, entry.path()))
+            })
+            .collect()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::net::{Ipv4Addr, Ipv6Addr, TcpListener};
+
+        #[test]
+        #[cfg(target_endian = "little")]
+        fn test_parse_listening_sockets() {
+            let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   \
+                0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 4242 1 0000000000000000 100 0 0 10 0\n   \
+                1: 0100007F:8E3C 0100007F:1F90 01 00000000:00000000 00:00000000 00000000  1000        0 4243 1 0000000000000000 20 4 30 10 -1\n";
+            assert_eq!(
+                parse_listening_sockets(tcp),
+                vec![ListeningSocket {
+                    address: Ipv4Addr::LOCALHOST.into(),
+                    port: 8080,
+                    uid: 1000,
+                    inode: 4242,
+                }]
+            );
+
+            let tcp6 = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   \
+                0: 00000000000000000000000001000000:0BB8 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 77 1 0000000000000000 100 0 0 10 0\n";
+            assert_eq!(
+                parse_listening_sockets(tcp6),
+                vec![ListeningSocket {
+                    address: Ipv6Addr::LOCALHOST.into(),
+                    port: 3000,
+                    uid: 0,
+                    inode: 77,
+                }]
+            );
+        }
+
+        #[test]
+        fn test_parse_parent_pid() {
+            assert_eq!(
+                parse_parent_pid("4242 (node) S 4200 4242 4200 34816 4242 4194304"),
+                Some(4200)
+            );
+            assert_eq!(
+                parse_parent_pid("4243 (my (server) 1) R 4242 4243 4200 34816"),
+                Some(4242)
+            );
+            assert_eq!(parse_parent_pid("4244 (truncated"), None);
+        }
+
+        #[test]
+        fn test_process_tree() {
+            // 1 -> 10 (terminal) -> 11 -> 12, 1 -> 20 (terminal), 1 -> 30 -> 31
+            let parents = HashMap::from_iter([
+                (1, 0),
+                (10, 1),
+                (11, 10),
+                (12, 11),
+                (20, 1),
+                (30, 1),
+                (31, 30),
+            ]);
+            let mut tree = process_tree(&parents, HashSet::from_iter([10, 20]))
+                .into_iter()
+                .collect::<Vec<_>>();
+            tree.sort();
+            assert_eq!(tree, [10, 11, 12, 20]);
+        }
+
+        #[test]
+        fn test_listening_ports_on_loopback() {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let this_process = std::process::id();
+
+            let ports = super::super::listening_ports_of(|pid| pid == this_process).unwrap();
+            let found = ports
+                .iter()
+                .find(|listening| listening.port == port as u32)
+                .expect("the listener should be found");
+            assert_eq!(found.address, "127.0.0.1");
+            assert!(found.process.is_some());
+
+            // Ports of processes outside of terminals and tasks aren't listed.
+            let ports = super::super::listening_ports_of(|pid| pid != this_process).unwrap();
+            assert!(ports.iter().all(|listening| listening.port != port as u32));
+        }
+    }
+}
@@ -2,8 +2,9 @@ mod registrar;
 
 use crate::{
     FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
-    SelectAllMatches, SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleRegex,
-    ToggleReplace, ToggleSelection, ToggleWholeWord, search_bar::render_nav_button,
+    SelectAllMatches, SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleInCode,
+    ToggleInComments, ToggleInStrings, TogglePreserveCase, ToggleRegex, ToggleReplace,
+    ToggleSelection, ToggleStructural, ToggleWholeWord, search_bar::render_nav_button,
 };
 use any_vec::AnyVec;
 use anyhow::Context as _;
@@ -295,7 +296,34 @@ impl Render for BufferSearchBar {
                                             this.toggle_regex(&ToggleRegex, window, cx)
                                         }),
                                     )
-                                })),
+                                }))
+                                .children(supported_options.syntax.then(|| {
+                                    self.render_search_option_button(
+                                        SearchOptions::STRUCTURAL,
+                                        focus_handle.clone(),
+                                        cx.listener(|this, _, window, cx| {
+                                            this.toggle_structural(&ToggleStructural, window, cx)
+                                        }),
+                                    )
+                                }))
+                                .when(supported_options.syntax, |this| {
+                                    this.children(
+                                        [
+                                            SearchOptions::IN_CODE,
+                                            SearchOptions::IN_COMMENTS,
+                                            SearchOptions::IN_STRINGS,
+                                        ]
+                                        .map(|option| {
+                                            self.render_search_option_button(
+                                                option,
+                                                focus_handle.clone(),
+                                                cx.listener(move |this, _, window, cx| {
+                                                    this.toggle_search_option(option, window, cx)
+                                                }),
+                                            )
+                                        }),
+                                    )
+                                }),
                         )
                     }),
             )
@@ -432,11 +460,15 @@ impl Render for BufferSearchBar {
             h_flex()
                 .gap_2()
                 .child(
-                    input_base_styles(replacement_border).child(self.render_text_input(
-                        &self.replacement_editor,
-                        None,
-                        cx,
-                    )),
+                    input_base_styles(replacement_border)
+                        .child(self.render_text_input(&self.replacement_editor, None, cx))
+                        .child(self.render_search_option_button(
+                            SearchOptions::PRESERVE_CASE,
+                            focus_handle.clone(),
+                            cx.listener(|this, _, window, cx| {
+                                this.toggle_search_option(SearchOptions::PRESERVE_CASE, window, cx)
+                            }),
+                        )),
                 )
                 .child(
                     h_flex()
@@ -626,6 +658,33 @@ impl BufferSearchBar {
                 this.toggle_regex(action, window, cx);
             }
         }));
+        registrar.register_handler(ForDeployed(
+            |this, action: &ToggleStructural, window, cx| {
+                if this.supported_options(cx).syntax {
+                    this.toggle_structural(action, window, cx);
+                }
+            },
+        ));
+        registrar.register_handler(ForDeployed(|this, _: &TogglePreserveCase, window, cx| {
+            if this.supported_options(cx).replacement {
+                this.toggle_search_option(SearchOptions::PRESERVE_CASE, window, cx);
+            }
+        }));
+        registrar.register_handler(ForDeployed(|this, _: &ToggleInCode, window, cx| {
+            if this.supported_options(cx).syntax {
+                this.toggle_search_option(SearchOptions::IN_CODE, window, cx);
+            }
+        }));
+        registrar.register_handler(ForDeployed(|this, _: &ToggleInComments, window, cx| {
+            if this.supported_options(cx).syntax {
+                this.toggle_search_option(SearchOptions::IN_COMMENTS, window, cx);
+            }
+        }));
+        registrar.register_handler(ForDeployed(|this, _: &ToggleInStrings, window, cx| {
+            if this.supported_options(cx).syntax {
+                this.toggle_search_option(SearchOptions::IN_STRINGS, window, cx);
+            }
+        }));
         registrar.register_handler(ForDeployed(|this, action: &ToggleSelection, window, cx| {
             if this.supported_options(cx).selection {
                 this.toggle_selection(action, window, cx);
@@ -998,7 +1057,7 @@ impl BufferSearchBar {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        self.search_options.toggle(search_option);
+        self.search_options.toggle_option(search_option);
         self.default_options = self.search_options;
         drop(self.update_matches(false, window, cx));
         self.adjust_query_regex_language(cx);
@@ -1236,6 +1295,15 @@ impl BufferSearchBar {
         self.toggle_search_option(SearchOptions::REGEX, window, cx)
     }
 
+    fn toggle_structural(
+        &mut self,
+        _: &ToggleStructural,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_search_option(SearchOptions::STRUCTURAL, window, cx)
+    }
+
     fn clear_active_searchable_item_matches(&mut self, window: &mut Window, cx: &mut App) {
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
             self.active_match_index = None;
@@ -1289,7 +1357,29 @@ impl BufferSearchBar {
                 {
                     search
                 } else {
-                    if self.search_options.contains(SearchOptions::REGEX) {
+                    if self.search_options.contains(SearchOptions::STRUCTURAL) {
+                        match SearchQuery::structural(
+                            query,
+                            false,
+                            Default::default(),
+                            Default::default(),
+                            false,
+                            None,
+                        ) {
+                            Ok(query) => query
+                                .with_replacement(self.replacement(cx))
+                                .with_syntax_filter(self.search_options.syntax_filter())
+                                .with_preserve_case(
+                                    self.search_options.contains(SearchOptions::PRESERVE_CASE),
+                                ),
+                            Err(e) => {
+                                self.query_error = Some(e.to_string());
+                                self.clear_active_searchable_item_matches(window, cx);
+                                cx.notify();
+                                return done_rx;
+                            }
+                        }
+                    } else if self.search_options.contains(SearchOptions::REGEX) {
                         match SearchQuery::regex(
                             query,
                             self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -1302,7 +1392,12 @@ impl BufferSearchBar {
                             false,
                             None,
                         ) {
-                            Ok(query) => query.with_replacement(self.replacement(cx)),
+                            Ok(query) => query
+                                .with_replacement(self.replacement(cx))
+                                .with_syntax_filter(self.search_options.syntax_filter())
+                                .with_preserve_case(
+                                    self.search_options.contains(SearchOptions::PRESERVE_CASE),
+                                ),
                             Err(e) => {
                                 self.query_error = Some(e.to_string());
                                 self.clear_active_searchable_item_matches(window, cx);
@@ -1321,7 +1416,12 @@ impl BufferSearchBar {
                             false,
                             None,
                         ) {
-                            Ok(query) => query.with_replacement(self.replacement(cx)),
+                            Ok(query) => query
+                                .with_replacement(self.replacement(cx))
+                                .with_syntax_filter(self.search_options.syntax_filter())
+                                .with_preserve_case(
+                                    self.search_options.contains(SearchOptions::PRESERVE_CASE),
+                                ),
                             Err(e) => {
                                 self.query_error = Some(e.to_string());
                                 self.clear_active_searchable_item_matches(window, cx);
@@ -1,7 +1,8 @@
 use crate::{
     BufferSearchBar, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext,
-    SearchOptions, SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
-    ToggleRegex, ToggleReplace, ToggleWholeWord, buffer_search::Deploy,
+    SearchOptions, SelectNextMatch, SelectPreviousMatch, ToggleCaseSensitive, ToggleInCode,
+    ToggleInComments, ToggleInStrings, ToggleIncludeIgnored, TogglePreserveCase, ToggleRegex,
+    ToggleReplace, ToggleStructural, ToggleWholeWord, buffer_search::Deploy,
 };
 use anyhow::Context as _;
 use collections::{HashMap, HashSet};
@@ -44,6 +45,7 @@ use workspace::{
     item::{BreadcrumbText, Item, ItemEvent, ItemHandle, SaveOptions},
     searchable::{Direction, SearchableItem, SearchableItemHandle},
 };
+use zed_actions::git::SearchHistory;
 
 actions!(
     project_search,
@@ -94,6 +96,36 @@ pub fn init(cx: &mut App) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, window, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, window, cx);
         });
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleStructural, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::STRUCTURAL, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &TogglePreserveCase, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::PRESERVE_CASE, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleInCode, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::IN_CODE, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleInComments, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::IN_COMMENTS, window, cx);
+            },
+        );
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleInStrings, window, cx| {
+                search_bar.toggle_search_option(SearchOptions::IN_STRINGS, window, cx);
+            },
+        );
         register_workspace_action(
             workspace,
             move |search_bar, action: &ToggleReplace, window, cx| {
@@ -666,7 +698,7 @@ impl ProjectSearchView {
     }
 
     fn toggle_search_option(&mut self, option: SearchOptions, cx: &mut Context<Self>) {
-        self.search_options.toggle(option);
+        self.search_options.toggle_option(option);
         ActiveSettings::update_global(cx, |settings, cx| {
             settings.0.insert(
                 self.entity.read(cx).project.downgrade(),
@@ -1204,7 +1236,35 @@ impl ProjectSearchView {
             .count()
             > 1;
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+            match SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+                match_full_paths,
+                open_buffers,
+            ) {
+                Ok(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error {
+                        cx.notify();
+                    }
+                    self.query_error = None;
+
+                    Some(query)
+                }
+                Err(e) => {
+                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                    if should_mark_error {
+                        cx.notify();
+                    }
+                    self.query_error = Some(e.to_string());
+
+                    None
+                }
+            }
+        } else if self.search_options.contains(SearchOptions::REGEX) {
             match SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -1271,7 +1331,11 @@ impl ProjectSearchView {
         if query.as_ref().is_some_and(|query| query.is_empty()) {
             return None;
         }
-        query
+        query.map(|query| {
+            query
+                .with_syntax_filter(self.search_options.syntax_filter())
+                .with_preserve_case(self.search_options.contains(SearchOptions::PRESERVE_CASE))
+        })
     }
 
     fn open_buffers(&self, cx: &mut Context<Self>) -> Vec<Entity<Buffer>> {
@@ -2012,6 +2076,13 @@ impl Render for ProjectSearchBar {
                         cx.listener(|this, _, window, cx| {
                             this.toggle_search_option(SearchOptions::REGEX, window, cx);
                         }),
+                    ))
+                    .child(SearchOptions::STRUCTURAL.as_button(
+                        self.is_option_enabled(SearchOptions::STRUCTURAL, cx),
+                        focus_handle.clone(),
+                        cx.listener(|this, _, window, cx| {
+                            this.toggle_search_option(SearchOptions::STRUCTURAL, window, cx);
+                        }),
                     )),
             );
 
@@ -2069,6 +2140,22 @@ impl Render for ProjectSearchBar {
                             )
                         }
                     }),
+            )
+            .child(
+                IconButton::new("project-search-history", IconName::HistoryRerun)
+                    .shape(IconButtonShape::Square)
+                    .on_click({
+                        let query = search.query_editor.read(cx).text(cx);
+                        move |_, window, cx| {
+                            window.dispatch_action(
+                                Box::new(SearchHistory {
+                                    query: (!query.is_empty()).then(|| query.clone()),
+                                }),
+                                cx,
+                            );
+                        }
+                    })
+                    .tooltip(Tooltip::text("Search Git History")),
             );
 
         let limit_reached = search.entity.read(cx).limit_reached;
@@ -2169,11 +2256,18 @@ impl Render for ProjectSearchBar {
             .child(h_flex().min_w_64().child(mode_column).child(matches_column));
 
         let replace_line = search.replace_enabled.then(|| {
-            let replace_column = input_base_styles(BaseStyle::SingleInput, InputPanel::Replacement)
-                .child(self.render_text_input(&search.replacement_editor, cx));
-
             let focus_handle = search.replacement_editor.read(cx).focus_handle(cx);
 
+            let replace_column = input_base_styles(BaseStyle::SingleInput, InputPanel::Replacement)
+                .child(self.render_text_input(&search.replacement_editor, cx))
+                .child(SearchOptions::PRESERVE_CASE.as_button(
+                    search.search_options.contains(SearchOptions::PRESERVE_CASE),
+                    focus_handle.clone(),
+                    cx.listener(|this, _, window, cx| {
+                        this.toggle_search_option(SearchOptions::PRESERVE_CASE, window, cx);
+                    }),
+                ));
+
             let replace_actions =
                 h_flex()
                     .min_w_64()
@@ -2290,6 +2384,22 @@ impl Render for ProjectSearchBar {
                                     );
                                 }),
                             ),
+                        )
+                        .children(
+                            [
+                                SearchOptions::IN_CODE,
+                                SearchOptions::IN_COMMENTS,
+                                SearchOptions::IN_STRINGS,
+                            ]
+                            .map(|option| {
+                                option.as_button(
+                                    search.search_options.contains(option),
+                                    focus_handle.clone(),
+                                    cx.listener(move |this, _, window, cx| {
+                                        this.toggle_search_option(option, window, cx);
+                                    }),
+                                )
+                            }),
                         ),
                 )
         });
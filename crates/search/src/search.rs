@@ -2,7 +2,7 @@ use bitflags::bitflags;
 pub use buffer_search::BufferSearchBar;
 use editor::SearchSettings;
 use gpui::{Action, App, FocusHandle, IntoElement, actions};
-use project::search::SearchQuery;
+use project::search::{SearchQuery, SyntaxFilter};
 pub use project_search::ProjectSearchView;
 use ui::{ButtonStyle, IconButton, IconButtonShape};
 use ui::{Tooltip, prelude::*};
@@ -33,8 +33,18 @@ actions!(
         ToggleIncludeIgnored,
         /// Toggles regular expression mode.
         ToggleRegex,
+        /// Toggles structural search, matching code against tree-sitter patterns.
+        ToggleStructural,
+        /// Toggles matching in code, outside of comments and strings.
+        ToggleInCode,
+        /// Toggles matching in comments.
+        ToggleInComments,
+        /// Toggles matching in string literals.
+        ToggleInStrings,
         /// Toggles the replace interface.
         ToggleReplace,
+        /// Toggles adapting replacements to the casing of each match.
+        TogglePreserveCase,
         /// Toggles searching within selection only.
         ToggleSelection,
         /// Selects the next search match.
@@ -58,13 +68,18 @@ actions!(
 
 bitflags! {
     #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
-    pub struct SearchOptions: u8 {
+    pub struct SearchOptions: u16 {
         const NONE = 0b000;
         const WHOLE_WORD = 0b001;
         const CASE_SENSITIVE = 0b010;
         const INCLUDE_IGNORED = 0b100;
         const REGEX = 0b1000;
         const ONE_MATCH_PER_LINE = 0b100000;
+        const STRUCTURAL = 0b1000000;
+        const IN_CODE = 0b10000000;
+        const IN_COMMENTS = 0b100000000;
+        const IN_STRINGS = 0b1000000000;
+        const PRESERVE_CASE = 0b10000000000;
         /// If set, reverse direction when finding the active match
         const BACKWARDS = 0b10000;
     }
@@ -77,6 +92,11 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => "Match Case Sensitively",
             SearchOptions::INCLUDE_IGNORED => "Also search files ignored by configuration",
             SearchOptions::REGEX => "Use Regular Expressions",
+            SearchOptions::STRUCTURAL => "Use Structural Patterns",
+            SearchOptions::IN_CODE => "Match in Code",
+            SearchOptions::IN_COMMENTS => "Match in Comments",
+            SearchOptions::IN_STRINGS => "Match in Strings",
+            SearchOptions::PRESERVE_CASE => "Preserve Case",
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -87,6 +107,11 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => ui::IconName::CaseSensitive,
             SearchOptions::INCLUDE_IGNORED => ui::IconName::Sliders,
             SearchOptions::REGEX => ui::IconName::Regex,
+            SearchOptions::STRUCTURAL => ui::IconName::Code,
+            SearchOptions::IN_CODE => ui::IconName::FileCode,
+            SearchOptions::IN_COMMENTS => ui::IconName::Chat,
+            SearchOptions::IN_STRINGS => ui::IconName::Quote,
+            SearchOptions::PRESERVE_CASE => ui::IconName::PreserveCase,
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -97,10 +122,26 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => Box::new(ToggleCaseSensitive),
             SearchOptions::INCLUDE_IGNORED => Box::new(ToggleIncludeIgnored),
             SearchOptions::REGEX => Box::new(ToggleRegex),
+            SearchOptions::STRUCTURAL => Box::new(ToggleStructural),
+            SearchOptions::IN_CODE => Box::new(ToggleInCode),
+            SearchOptions::IN_COMMENTS => Box::new(ToggleInComments),
+            SearchOptions::IN_STRINGS => Box::new(ToggleInStrings),
+            SearchOptions::PRESERVE_CASE => Box::new(TogglePreserveCase),
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
 
+    /// Toggles the given option. Regular expressions and structural patterns
+    /// are different query syntaxes, so enabling one disables the other.
+    pub fn toggle_option(&mut self, option: SearchOptions) {
+        self.toggle(option);
+        if option == SearchOptions::REGEX && self.contains(SearchOptions::REGEX) {
+            self.remove(SearchOptions::STRUCTURAL);
+        } else if option == SearchOptions::STRUCTURAL && self.contains(SearchOptions::STRUCTURAL) {
+            self.remove(SearchOptions::REGEX);
+        }
+    }
+
     pub fn none() -> SearchOptions {
         SearchOptions::NONE
     }
@@ -111,9 +152,25 @@ impl SearchOptions {
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
         options.set(SearchOptions::REGEX, query.is_regex());
+        options.set(SearchOptions::STRUCTURAL, query.is_structural());
+        let syntax_filter = query.syntax_filter();
+        options.set(SearchOptions::IN_CODE, syntax_filter.code);
+        options.set(SearchOptions::IN_COMMENTS, syntax_filter.comments);
+        options.set(SearchOptions::IN_STRINGS, syntax_filter.strings);
+        options.set(SearchOptions::PRESERVE_CASE, query.preserve_case());
         options
     }
 
+    /// The kinds of syntax that matches are restricted to. When none of them
+    /// are enabled, matches aren't restricted.
+    pub fn syntax_filter(&self) -> SyntaxFilter {
+        SyntaxFilter {
+            code: self.contains(SearchOptions::IN_CODE),
+            comments: self.contains(SearchOptions::IN_COMMENTS),
+            strings: self.contains(SearchOptions::IN_STRINGS),
+        }
+    }
+
     pub fn from_settings(settings: &SearchSettings) -> SearchOptions {
         let mut options = SearchOptions::NONE;
         options.set(SearchOptions::WHOLE_WORD, settings.whole_word);
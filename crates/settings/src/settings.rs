@@ -4,8 +4,10 @@ mod key_equivalents;
 mod keymap_file;
 mod settings_file;
 mod settings_json;
+mod settings_profile;
 mod settings_store;
 mod vscode_import;
+mod vscode_keymap_import;
 
 use gpui::{App, Global};
 use rust_embed::RustEmbed;
@@ -21,11 +23,16 @@ pub use keymap_file::{
 };
 pub use settings_file::*;
 pub use settings_json::*;
+pub use settings_profile::{
+    SettingsProfileActivationContext, SettingsProfileActivationRule, SettingsProfileWorktree,
+    matching_settings_profile,
+};
 pub use settings_store::{
-    InvalidSettingsError, LocalSettingsKind, Settings, SettingsLocation, SettingsSources,
-    SettingsStore,
+    InvalidSettingsError, LocalSettingsKind, Settings, SettingsLayer, SettingsLocation,
+    SettingsSources, SettingsStore,
 };
 pub use vscode_import::{VsCodeSettings, VsCodeSettingsSource};
+pub use vscode_keymap_import::{SkippedVsCodeKeybinding, VsCodeKeybindings, VsCodeKeymapImport};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ActiveSettingsProfileName(pub String);
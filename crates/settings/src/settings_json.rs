@@ -86,6 +86,111 @@ pub fn update_value_in_json_text<'a>(
     }
 }
 
+/// Sets the value at `key_path`, or removes it when `new_value` is `None`,
+/// keeping the comments and formatting of the rest of the text. New keys are
+/// added after the last key of their object, so that they don't end up under
+/// a comment describing another key.
+pub fn update_value_at_key_path_in_json_text(
+    text: &mut String,
+    key_path: &[&str],
+    tab_size: usize,
+    new_value: Option<&Value>,
+) {
+    if new_value.is_none() {
+        let value = parse_json_with_comments::<Value>(text).unwrap_or_default();
+        if key_path
+            .iter()
+            .try_fold(&value, |value, key| value.get(key))
+            .is_none()
+        {
+            return;
+        }
+    }
+    let (range, replacement) = new_value
+        .and_then(|new_value| append_value_in_json_object(text, key_path, tab_size, new_value))
+        .unwrap_or_else(|| replace_value_in_json_text(text, key_path, tab_size, new_value, None));
+    text.replace_range(range, &replacement);
+}
+
+/// Adds a key that isn't in the text after the last key of the innermost
+/// object on `key_path`. Returns `None` when the key already exists, or when
+/// that object is empty or written on a single line.
+fn append_value_in_json_object(
+    text: &str,
+    key_path: &[&str],
+    tab_size: usize,
+    new_value: &Value,
+) -> Option<(Range<usize>, String)> {
+    let mut parser = tree_sitter::Parser::new();
+    parser
+        .set_language(&tree_sitter_json::LANGUAGE.into())
+        .ok()?;
+    let syntax_tree = parser.parse(text, None)?;
+    let root = syntax_tree.root_node();
+    let mut object = root
+        .named_children(&mut root.walk())
+        .find(|node| node.kind() == "object")?;
+
+    let mut depth = 0;
+    while depth < key_path.len() {
+        let key = format!("\"{}\"", key_path[depth]);
+        let Some(value) = object
+            .named_children(&mut object.walk())
+            .filter(|node| node.kind() == "pair")
+            .find(|pair| {
+                pair.child_by_field_name("key")
+                    .and_then(|key| text.get(key.byte_range()))
+                    == Some(key.as_str())
+            })
+            .and_then(|pair| pair.child_by_field_name("value"))
+        else {
+            break;
+        };
+        if depth + 1 == key_path.len() || value.kind() != "object" {
+            return None;
+        }
+        object = value;
+        depth += 1;
+    }
+
+    let last_pair = object
+        .named_children(&mut object.walk())
+        .filter(|node| node.kind() == "pair")
+        .last()?;
+    let line_start = text[..last_pair.start_byte()].rfind('\n')? + 1;
+    let indent = &text[line_start..last_pair.start_byte()];
+    if !indent.trim().is_empty() {
+        return None;
+    }
+
+    // Keep whatever follows the last pair on its line, such as a comment, on that line.
+    let rest = &text[last_pair.end_byte()..];
+    let rest = &rest[..rest.find(['\r', '\n']).unwrap_or(rest.len())];
+    let trailing_comma = rest.trim_start().starts_with(',');
+    let rest = if trailing_comma {
+        rest.replacen(',', "", 1)
+    } else {
+        rest.to_string()
+    };
+    let after_comma = rest.trim();
+    if !after_comma.is_empty() && !after_comma.starts_with("//") {
+        return None;
+    }
+
+    let mut new_value = new_value.clone();
+    for key in key_path[depth + 1..].iter().rev() {
+        new_value = serde_json::json!({ key.to_string(): new_value });
+    }
+    let new_value = to_pretty_json(&new_value, tab_size, indent.len());
+    let range = last_pair.end_byte()..last_pair.end_byte() + rest.len() + trailing_comma as usize;
+    let replacement = format!(
+        ",{rest}\n{indent}\"{}\": {new_value}{}",
+        key_path[depth],
+        if trailing_comma { "," } else { "" }
+    );
+    Some((range, replacement))
+}
+
 /// * `replace_key` - When an exact key match according to `key_path` is found, replace the key with `replace_key` if `Some`.
 fn replace_value_in_json_text(
     text: &str,
@@ -1411,6 +1516,88 @@ mod tests {
         );
     }
 
+    #[test]
+    fn update_value_at_key_path() {
+        let mut text = r#"{
+            // The editor font.
+            "buffer_font_size": 15,
+            "git": {
+                "inline_blame": {
+                    "enabled": true
+                }
+            }
+        }"#
+        .unindent();
+
+        update_value_at_key_path_in_json_text(
+            &mut text,
+            &["git", "inline_blame", "enabled"],
+            4,
+            Some(&json!(false)),
+        );
+        update_value_at_key_path_in_json_text(
+            &mut text,
+            &["git", "inline_blame", "delay_ms"],
+            4,
+            Some(&json!(100)),
+        );
+        update_value_at_key_path_in_json_text(&mut text, &["vim_mode"], 4, Some(&json!(true)));
+        pretty_assertions::assert_eq!(
+            text,
+            r#"{
+                // The editor font.
+                "buffer_font_size": 15,
+                "git": {
+                    "inline_blame": {
+                        "enabled": false,
+                        "delay_ms": 100
+                    }
+                },
+                "vim_mode": true
+            }"#
+            .unindent()
+        );
+
+        update_value_at_key_path_in_json_text(&mut text, &["buffer_font_size"], 4, None);
+        update_value_at_key_path_in_json_text(&mut text, &["tab_size"], 4, None);
+        pretty_assertions::assert_eq!(
+            text,
+            r#"{
+                // The editor font.
+                "git": {
+                    "inline_blame": {
+                        "enabled": false,
+                        "delay_ms": 100
+                    }
+                },
+                "vim_mode": true
+            }"#
+            .unindent()
+        );
+
+        // Comments after the last key stay on its line, and trailing commas are kept.
+        let mut text = r#"{
+            "tab_size": 2, // Two spaces.
+        }"#
+        .unindent();
+        update_value_at_key_path_in_json_text(
+            &mut text,
+            &["theme", "mode"],
+            4,
+            Some(&json!("dark")),
+        );
+        pretty_assertions::assert_eq!(
+            text,
+            r#"{
+                "tab_size": 2, // Two spaces.
+                "theme": {
+                    "mode": "dark"
+                },
+            }"#
+            .unindent()
+        );
+    }
+
     #[test]
     fn array_append() {
         #[track_caller]
@@ -0,0 +1,269 @@
+use std::path::{Path, PathBuf};
+
+use globset::{Glob, GlobMatcher};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::Value;
+use util::ResultExt as _;
+
+/// The key, inside of a settings profile, that holds its activation rules.
+pub const ACTIVATE_WHEN_KEY: &str = "activate_when";
+
+/// A set of conditions that automatically activates a settings profile.
+///
+/// All of the conditions that are present must hold for the rule to match.
+/// `path` and `file_exists` must hold for the same worktree.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SettingsProfileActivationRule {
+    /// A glob matched against the absolute path of a worktree, such as `~/work/**`.
+    pub path: Option<String>,
+    /// A path, relative to the root of a worktree, that must exist, such as `Cargo.toml`.
+    pub file_exists: Option<String>,
+    /// A glob matched against the host of a remote project.
+    pub remote_host: Option<String>,
+    /// A glob matched against the URL of a git remote, such as `*github.com/acme/*`.
+    pub git_remote_url: Option<String>,
+}
+
+/// What settings profile activation rules are evaluated against.
+#[derive(Clone, Debug, Default)]
+pub struct SettingsProfileActivationContext {
+    pub worktrees: Vec<SettingsProfileWorktree>,
+    pub remote_host: Option<String>,
+    pub git_remote_urls: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SettingsProfileWorktree {
+    pub abs_path: PathBuf,
+    /// The paths named by `file_exists` conditions that exist in this worktree.
+    pub existing_files: Vec<PathBuf>,
+}
+
+impl SettingsProfileActivationRule {
+    pub fn matches(&self, context: &SettingsProfileActivationContext) -> bool {
+        if self.path.is_none()
+            && self.file_exists.is_none()
+            && self.remote_host.is_none()
+            && self.git_remote_url.is_none()
+        {
+            return false;
+        }
+
+        if let Some(remote_host) = &self.remote_host {
+            let Some(host) = &context.remote_host else {
+                return false;
+            };
+            if !glob_matches(remote_host, host) {
+                return false;
+            }
+        }
+
+        if let Some(git_remote_url) = &self.git_remote_url {
+            if !context
+                .git_remote_urls
+                .iter()
+                .any(|url| glob_matches(git_remote_url, url))
+            {
+                return false;
+            }
+        }
+
+        if self.path.is_none() && self.file_exists.is_none() {
+            return true;
+        }
+
+        let path_matcher = self.path.as_deref().and_then(|path| {
+            let path = expand_home_dir(path);
+            Glob::new(&path.to_string_lossy())
+                .log_err()
+                .map(|glob| glob.compile_matcher())
+        });
+        if self.path.is_some() && path_matcher.is_none() {
+            return false;
+        }
+
+        context.worktrees.iter().any(|worktree| {
+            let path_matches = path_matcher
+                .as_ref()
+                .is_none_or(|matcher| worktree_path_matches(matcher, &worktree.abs_path));
+            let file_exists = self.file_exists.as_ref().is_none_or(|file| {
+                worktree
+                    .existing_files
+                    .iter()
+                    .any(|existing| existing == Path::new(file))
+            });
+            path_matches && file_exists
+        })
+    }
+}
+
+/// Reads the activation rules of each profile in the given user settings, in the order the
+/// profiles are defined.
+pub fn settings_profile_activation_rules(
+    raw_user_settings: &Value,
+) -> Vec<(String, Vec<SettingsProfileActivationRule>)> {
+    let Some(profiles) = raw_user_settings.get("profiles").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+
+    profiles
+        .iter()
+        .filter_map(|(name, profile)| {
+            let rules = profile.get(ACTIVATE_WHEN_KEY)?;
+            let rules = serde_json::from_value::<Vec<SettingsProfileActivationRule>>(rules.clone())
+                .map_err(|error| {
+                    anyhow::anyhow!(
+                        "invalid activation rules for settings profile {name:?}: {error}"
+                    )
+                })
+                .log_err()?;
+            Some((name.clone(), rules))
+        })
+        .collect()
+}
+
+/// Returns the first profile with a rule that matches the given context.
+pub fn matching_settings_profile(
+    rules: &[(String, Vec<SettingsProfileActivationRule>)],
+    context: &SettingsProfileActivationContext,
+) -> Option<String> {
+    rules
+        .iter()
+        .find(|(_, rules)| rules.iter().any(|rule| rule.matches(context)))
+        .map(|(name, _)| name.clone())
+}
+
+fn glob_matches(pattern: &str, candidate: &str) -> bool {
+    Glob::new(pattern)
+        .log_err()
+        .is_some_and(|glob| glob.compile_matcher().is_match(candidate))
+}
+
+/// A worktree matches a path glob when either it, or one of its ancestors, does. This lets
+/// `~/work/client-a` match worktrees anywhere under that directory.
+fn worktree_path_matches(matcher: &GlobMatcher, abs_path: &Path) -> bool {
+    abs_path.ancestors().any(|path| matcher.is_match(path))
+}
+
+fn expand_home_dir(path: &str) -> PathBuf {
+    if path == "~" {
+        util::paths::home_dir().clone()
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        util::paths::home_dir().join(rest)
+    } else {
+        PathBuf::from(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules(value: Value) -> Vec<(String, Vec<SettingsProfileActivationRule>)> {
+        settings_profile_activation_rules(&json!({ "profiles": value }))
+    }
+
+    #[test]
+    fn test_matching_settings_profile() {
+        let rules = rules(json!({
+            "Presenting": {
+                "buffer_font_size": 20
+            },
+            "Client A": {
+                "tab_size": 2,
+                "activate_when": [
+                    { "path": "/work/client-a" },
+                    { "git_remote_url": "*github.com/client-a/*" }
+                ]
+            },
+            "Rust": {
+                "activate_when": [
+                    { "file_exists": "Cargo.toml" }
+                ]
+            },
+            "Build box": {
+                "activate_when": [
+                    { "remote_host": "build-*", "path": "/srv/**" }
+                ]
+            }
+        }));
+        assert_eq!(
+            rules
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            ["Client A", "Rust", "Build box"]
+        );
+
+        let worktree = |path: &str, files: &[&str]| SettingsProfileWorktree {
+            abs_path: PathBuf::from(path),
+            existing_files: files.iter().map(PathBuf::from).collect(),
+        };
+
+        let context = SettingsProfileActivationContext {
+            worktrees: vec![worktree("/work/client-a/api", &["Cargo.toml"])],
+            ..Default::default()
+        };
+        assert_eq!(
+            matching_settings_profile(&rules, &context).as_deref(),
+            Some("Client A")
+        );
+
+        let context = SettingsProfileActivationContext {
+            worktrees: vec![worktree("/work/client-b", &["Cargo.toml"])],
+            ..Default::default()
+        };
+        assert_eq!(
+            matching_settings_profile(&rules, &context).as_deref(),
+            Some("Rust")
+        );
+
+        let context = SettingsProfileActivationContext {
+            worktrees: vec![worktree("/work/client-b", &["package.json"])],
+            git_remote_urls: vec!["git@github.com:client-a/site.git".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            matching_settings_profile(&rules, &context).as_deref(),
+            Some("Client A")
+        );
+
+        let context = SettingsProfileActivationContext {
+            worktrees: vec![worktree("/srv/app", &[])],
+            remote_host: Some("build-01".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            matching_settings_profile(&rules, &context).as_deref(),
+            Some("Build box")
+        );
+
+        let context = SettingsProfileActivationContext {
+            worktrees: vec![worktree("/home/app", &[])],
+            remote_host: Some("build-01".into()),
+            ..Default::default()
+        };
+        assert_eq!(matching_settings_profile(&rules, &context), None);
+    }
+
+    #[test]
+    fn test_invalid_activation_rules_are_ignored() {
+        let rules = rules(json!({
+            "Broken": { "activate_when": [{ "unknown_condition": true }] },
+            "Empty": { "activate_when": [{}] }
+        }));
+        assert_eq!(rules.len(), 1);
+
+        let context = SettingsProfileActivationContext {
+            worktrees: vec![SettingsProfileWorktree {
+                abs_path: PathBuf::from("/anywhere"),
+                existing_files: Vec::new(),
+            }],
+            ..Default::default()
+        };
+        assert_eq!(matching_settings_profile(&rules, &context), None);
+    }
+}
@@ -31,8 +31,9 @@ use util::{
 pub type EditorconfigProperties = ec4rs::Properties;
 
 use crate::{
-    ActiveSettingsProfileName, ParameterizedJsonSchema, SettingsJsonSchemaParams, VsCodeSettings,
-    WorktreeId, parse_json_with_comments, update_value_in_json_text,
+    ActiveSettingsProfileName, ParameterizedJsonSchema, SettingsJsonSchemaParams,
+    SettingsProfileActivationRule, VsCodeSettings, WorktreeId, parse_json_with_comments,
+    settings_profile, update_value_at_key_path_in_json_text, update_value_in_json_text,
 };
 
 /// A value that can be defined as a user setting.
@@ -184,6 +185,49 @@ pub struct SettingsLocation<'a> {
     pub path: &'a Path,
 }
 
+/// One of the settings files that are merged, in order, to produce a setting's value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SettingsLayer {
+    Default,
+    Global,
+    Extensions,
+    User,
+    ReleaseChannel,
+    OperatingSystem,
+    Profile(String),
+    Server,
+    /// The `.zed/settings.json` in the given worktree-relative directory.
+    Project(Arc<Path>),
+}
+
+impl std::fmt::Display for SettingsLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsLayer::Default => write!(f, "default settings"),
+            SettingsLayer::Global => write!(f, "global settings"),
+            SettingsLayer::Extensions => write!(f, "extension settings"),
+            SettingsLayer::User => write!(f, "user settings"),
+            SettingsLayer::ReleaseChannel => write!(
+                f,
+                "user settings for the {} channel",
+                release_channel::RELEASE_CHANNEL.dev_name()
+            ),
+            SettingsLayer::OperatingSystem => {
+                write!(f, "user settings for {}", env::consts::OS)
+            }
+            SettingsLayer::Profile(name) => write!(f, "settings profile \"{name}\""),
+            SettingsLayer::Server => write!(f, "server settings"),
+            SettingsLayer::Project(directory) => write!(
+                f,
+                "project settings in {}",
+                directory
+                    .join(local_settings_file_relative_path())
+                    .display()
+            ),
+        }
+    }
+}
+
 /// A set of strongly-typed setting values defined via multiple config files.
 pub struct SettingsStore {
     setting_values: HashMap<TypeId, Box<dyn AnySettingValue>>,
@@ -449,6 +493,69 @@ impl SettingsStore {
             .map(|s| s.as_str())
     }
 
+    /// Get the activation rules of each configured settings profile, in the order the profiles
+    /// are defined.
+    pub fn settings_profile_activation_rules(
+        &self,
+    ) -> Vec<(String, Vec<SettingsProfileActivationRule>)> {
+        settings_profile::settings_profile_activation_rules(&self.raw_user_settings)
+    }
+
+    /// Get the raw JSON value of each settings file that applies at the given location, in the
+    /// order in which they're merged. Later layers override earlier ones.
+    pub fn settings_layers(
+        &self,
+        location: Option<SettingsLocation>,
+        cx: &App,
+    ) -> Vec<(SettingsLayer, &Value)> {
+        let mut layers = vec![(SettingsLayer::Default, &self.raw_default_settings)];
+        if let Some(global) = &self.raw_global_settings {
+            layers.push((SettingsLayer::Global, global));
+        }
+        layers.push((SettingsLayer::Extensions, &self.raw_extension_settings));
+        layers.push((SettingsLayer::User, &self.raw_user_settings));
+        if let Some(release_settings) = self
+            .raw_user_settings
+            .get(release_channel::RELEASE_CHANNEL.dev_name())
+        {
+            layers.push((SettingsLayer::ReleaseChannel, release_settings));
+        }
+        if let Some(os_settings) = self.raw_user_settings.get(env::consts::OS) {
+            layers.push((SettingsLayer::OperatingSystem, os_settings));
+        }
+        if let Some(active_profile) = cx.try_global::<ActiveSettingsProfileName>() {
+            if let Some(profile_settings) = self
+                .raw_user_settings
+                .get("profiles")
+                .and_then(|profiles| profiles.get(&active_profile.0))
+            {
+                layers.push((
+                    SettingsLayer::Profile(active_profile.0.clone()),
+                    profile_settings,
+                ));
+            }
+        }
+        if let Some(server) = &self.raw_server_settings {
+            layers.push((SettingsLayer::Server, server));
+        }
+        if let Some(location) = location {
+            for ((root_id, directory_path), local_settings) in &self.raw_local_settings {
+                if *root_id == location.worktree_id && location.path.starts_with(directory_path) {
+                    layers.push((
+                        SettingsLayer::Project(directory_path.clone()),
+                        local_settings,
+                    ));
+                }
+            }
+        }
+        layers
+    }
+
+    /// Get the default settings as a raw JSON value.
+    pub fn raw_default_settings(&self) -> &Value {
+        &self.raw_default_settings
+    }
+
     /// Access the raw JSON value of the global settings.
     pub fn raw_global_settings(&self) -> Option<&Value> {
         self.raw_global_settings.as_ref()
@@ -518,33 +625,61 @@ impl SettingsStore {
                     let new_text = cx.read_global(|store: &SettingsStore, cx| {
                         store.new_text_for_update::<T>(old_text, |content| update(content, cx))
                     })?;
-                    let settings_path = paths::settings_file().as_path();
-                    if fs.is_file(settings_path).await {
-                        let resolved_path =
-                            fs.canonicalize(settings_path).await.with_context(|| {
-                                format!("Failed to canonicalize settings path {:?}", settings_path)
-                            })?;
-
-                        fs.atomic_write(resolved_path.clone(), new_text)
-                            .await
-                            .with_context(|| {
-                                format!("Failed to write settings to file {:?}", resolved_path)
-                            })?;
-                    } else {
-                        fs.atomic_write(settings_path.to_path_buf(), new_text)
-                            .await
-                            .with_context(|| {
-                                format!("Failed to write settings to file {:?}", settings_path)
-                            })?;
-                    }
+                    Self::write_settings(&fs, new_text).await
+                }
+                .boxed_local()
+            }))
+            .ok();
+    }
 
-                    anyhow::Ok(())
+    /// Sets the value at `key_path` in the user's settings file, or removes it
+    /// when `value` is `None`. Unlike `update_settings_file`, this works from
+    /// the raw JSON, for editing settings without knowing their types.
+    pub fn update_settings_file_at_key_path(
+        &self,
+        fs: Arc<dyn Fs>,
+        key_path: Vec<String>,
+        value: Option<Value>,
+    ) {
+        self.setting_file_updates_tx
+            .unbounded_send(Box::new(move |cx: AsyncApp| {
+                async move {
+                    let mut new_text = Self::load_settings(&fs).await?;
+                    let tab_size =
+                        cx.read_global(|store: &SettingsStore, _| store.json_tab_size())?;
+                    let key_path = key_path.iter().map(String::as_str).collect::<Vec<_>>();
+                    update_value_at_key_path_in_json_text(
+                        &mut new_text,
+                        &key_path,
+                        tab_size,
+                        value.as_ref(),
+                    );
+                    Self::write_settings(&fs, new_text).await
                 }
                 .boxed_local()
             }))
             .ok();
     }
 
+    async fn write_settings(fs: &Arc<dyn Fs>, new_text: String) -> Result<()> {
+        let settings_path = paths::settings_file().as_path();
+        if fs.is_file(settings_path).await {
+            let resolved_path = fs.canonicalize(settings_path).await.with_context(|| {
+                format!("Failed to canonicalize settings path {:?}", settings_path)
+            })?;
+
+            fs.atomic_write(resolved_path.clone(), new_text)
+                .await
+                .with_context(|| format!("Failed to write settings to file {:?}", resolved_path))?;
+        } else {
+            fs.atomic_write(settings_path.to_path_buf(), new_text)
+                .await
+                .with_context(|| format!("Failed to write settings to file {:?}", settings_path))?;
+        }
+
+        anyhow::Ok(())
+    }
+
     pub fn import_vscode_settings(
         &self,
         fs: Arc<dyn Fs>,
@@ -632,6 +767,16 @@ impl SettingsStore {
         new_text
     }
 
+    /// Returns the VS Code settings that no registered setting imports.
+    pub fn unimported_vscode_settings(&self, vscode: &VsCodeSettings) -> Vec<String> {
+        self.get_vscode_edits("{}".to_string(), vscode);
+        vscode
+            .unread_keys()
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+
     /// Updates the value of a setting in a JSON file, returning a list
     /// of edits to apply to the JSON file.
     pub fn edits_for_update<T: Settings>(
@@ -1072,7 +1217,7 @@ impl SettingsStore {
         let zed_settings_ref = add_new_subschema(&mut generator, ZED_SETTINGS, combined_schema);
 
         // add `ZedSettingsOverride` which is the same as `ZedSettings` except that unknown
-        // fields are rejected. This is used for release stage settings and profiles.
+        // fields are rejected. This is used for release stage and operating system settings.
         let mut zed_settings_override = zed_settings_ref.clone();
         zed_settings_override.insert("unevaluatedProperties".to_string(), false.into());
         let zed_settings_override_ref = add_new_subschema(
@@ -1081,6 +1226,25 @@ impl SettingsStore {
             zed_settings_override.to_value(),
         );
 
+        // Profiles may also hold the rules that activate them automatically.
+        let activation_rules_schema =
+            generator.subschema_for::<Vec<SettingsProfileActivationRule>>();
+        let settings_profile_ref = add_new_subschema(
+            &mut generator,
+            "SettingsProfile",
+            json!({
+                "allOf": [
+                    zed_settings_ref,
+                    {
+                        "properties": {
+                            (settings_profile::ACTIVATE_WHEN_KEY): activation_rules_schema
+                        }
+                    }
+                ],
+                "unevaluatedProperties": false
+            }),
+        );
+
         // Remove `"additionalProperties": false` added by `DefaultDenyUnknownFields` so that
         // unknown fields can be handled by the root schema and `ZedSettingsOverride`.
         let mut definitions = generator.take_definitions(true);
@@ -1117,7 +1281,7 @@ impl SettingsStore {
                         "profiles": {
                             "type": "object",
                             "description": "Configures any number of settings profiles.",
-                            "additionalProperties": zed_settings_override_ref
+                            "additionalProperties": settings_profile_ref
                         }
                     }
                 }
@@ -1292,16 +1456,20 @@ impl SettingsStore {
         for (directory_with_config, _, parsed_editorconfig) in
             self.local_editorconfig_settings(for_worktree)
         {
-            if !for_path.starts_with(&directory_with_config) {
-                properties.use_fallbacks();
-                return Some(properties);
-            }
+            let Ok(path_in_directory) = for_path.strip_prefix(&directory_with_config) else {
+                continue;
+            };
             let parsed_editorconfig = parsed_editorconfig?;
             if parsed_editorconfig.is_root {
                 properties = EditorconfigProperties::new();
             }
+            // Section globs are relative to the directory containing the `.editorconfig`,
+            // so match them against the file's path from that directory.
+            let path_in_directory = Path::new("/").join(path_in_directory);
             for section in parsed_editorconfig.sections {
-                section.apply_to(&mut properties, for_path).log_err()?;
+                section
+                    .apply_to(&mut properties, &path_in_directory)
+                    .log_err()?;
             }
         }
 
@@ -2036,6 +2204,28 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_unimported_vscode_settings(cx: &mut App) {
+        let mut store = SettingsStore::new(cx);
+        store.register_setting::<UserSettings>(cx);
+        store.register_setting::<JournalSettings>(cx);
+
+        let vscode = VsCodeSettings::from_str(
+            r#"{
+                "user.age": 37,
+                "time_format": "12",
+                "workbench.colorTheme": "Monokai",
+                "editor.minimap.enabled": false
+            }"#,
+            VsCodeSettingsSource::VsCode,
+        )
+        .unwrap();
+        assert_eq!(
+            store.unimported_vscode_settings(&vscode),
+            ["editor.minimap.enabled", "workbench.colorTheme"]
+        );
+    }
+
     fn check_vscode_import(
         store: &mut SettingsStore,
         old: String,
@@ -2,7 +2,7 @@ use anyhow::{Context as _, Result, anyhow};
 use fs::Fs;
 use paths::{cursor_settings_file_paths, vscode_settings_file_paths};
 use serde_json::{Map, Value};
-use std::{path::Path, rc::Rc, sync::Arc};
+use std::{cell::RefCell, collections::BTreeSet, path::Path, rc::Rc, sync::Arc};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum VsCodeSettingsSource {
@@ -23,6 +23,8 @@ pub struct VsCodeSettings {
     pub source: VsCodeSettingsSource,
     pub path: Rc<Path>,
     content: Map<String, Value>,
+    /// The keys that settings have read while importing, used to report the ones nothing read.
+    read_keys: RefCell<BTreeSet<String>>,
 }
 
 impl VsCodeSettings {
@@ -32,6 +34,7 @@ impl VsCodeSettings {
             source,
             path: Path::new("/example-path/Code/User/settings.json").into(),
             content: serde_json_lenient::from_str(content)?,
+            read_keys: RefCell::default(),
         })
     }
 
@@ -74,11 +77,30 @@ impl VsCodeSettings {
             source,
             path: path.into(),
             content,
+            read_keys: RefCell::default(),
         })
     }
 
+    fn get(&self, key: &str) -> Option<&Value> {
+        self.read_keys.borrow_mut().insert(key.to_string());
+        self.content.get(key)
+    }
+
+    /// Returns the settings in the file that no Zed setting has read, sorted by key.
+    pub fn unread_keys(&self) -> Vec<&str> {
+        let read_keys = self.read_keys.borrow();
+        let mut keys = self
+            .content
+            .keys()
+            .filter(|key| !read_keys.contains(key.as_str()))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        keys
+    }
+
     pub fn read_value(&self, setting: &str) -> Option<&Value> {
-        if let Some(value) = self.content.get(setting) {
+        if let Some(value) = self.get(setting) {
             return Some(value);
         }
         // TODO: maybe check if it's in [platform] settings for current platform as a fallback
@@ -95,37 +117,37 @@ impl VsCodeSettings {
     }
 
     pub fn string_setting(&self, key: &str, setting: &mut Option<String>) {
-        if let Some(s) = self.content.get(key).and_then(Value::as_str) {
+        if let Some(s) = self.get(key).and_then(Value::as_str) {
             *setting = Some(s.to_owned())
         }
     }
 
     pub fn bool_setting(&self, key: &str, setting: &mut Option<bool>) {
-        if let Some(s) = self.content.get(key).and_then(Value::as_bool) {
+        if let Some(s) = self.get(key).and_then(Value::as_bool) {
             *setting = Some(s)
         }
     }
 
     pub fn u32_setting(&self, key: &str, setting: &mut Option<u32>) {
-        if let Some(s) = self.content.get(key).and_then(Value::as_u64) {
+        if let Some(s) = self.get(key).and_then(Value::as_u64) {
             *setting = Some(s as u32)
         }
     }
 
     pub fn u64_setting(&self, key: &str, setting: &mut Option<u64>) {
-        if let Some(s) = self.content.get(key).and_then(Value::as_u64) {
+        if let Some(s) = self.get(key).and_then(Value::as_u64) {
             *setting = Some(s)
         }
     }
 
     pub fn usize_setting(&self, key: &str, setting: &mut Option<usize>) {
-        if let Some(s) = self.content.get(key).and_then(Value::as_u64) {
+        if let Some(s) = self.get(key).and_then(Value::as_u64) {
             *setting = Some(s.try_into().unwrap())
         }
     }
 
     pub fn f32_setting(&self, key: &str, setting: &mut Option<f32>) {
-        if let Some(s) = self.content.get(key).and_then(Value::as_f64) {
+        if let Some(s) = self.get(key).and_then(Value::as_f64) {
             *setting = Some(s as f32)
         }
     }
@@ -136,7 +158,7 @@ impl VsCodeSettings {
         setting: &mut Option<T>,
         f: impl FnOnce(&str) -> Option<T>,
     ) {
-        if let Some(s) = self.content.get(key).and_then(Value::as_str).and_then(f) {
+        if let Some(s) = self.get(key).and_then(Value::as_str).and_then(f) {
             *setting = Some(s)
         }
     }
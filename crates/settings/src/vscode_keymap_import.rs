@@ -0,0 +1,831 @@
+use anyhow::{Context as _, Result, anyhow};
+use collections::{HashSet, IndexMap};
+use fs::Fs;
+use paths::{cursor_keybindings_file_paths, vscode_keybindings_file_paths};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::{path::Path, rc::Rc, sync::Arc};
+
+use crate::{KeymapFile, VsCodeSettingsSource};
+
+/// VS Code commands and the Zed actions they translate to.
+const COMMAND_MAPPINGS: &[(&str, &str)] = &[
+    // Clipboard and history
+    ("editor.action.clipboardCopyAction", "editor::Copy"),
+    ("editor.action.clipboardCutAction", "editor::Cut"),
+    ("editor.action.clipboardPasteAction", "editor::Paste"),
+    ("undo", "editor::Undo"),
+    ("redo", "editor::Redo"),
+    ("cursorUndo", "editor::UndoSelection"),
+    // Selection and multi-cursor
+    ("editor.action.selectAll", "editor::SelectAll"),
+    ("expandLineSelection", "editor::SelectLine"),
+    ("editor.action.selectHighlights", "editor::SelectAllMatches"),
+    (
+        "editor.action.insertCursorAbove",
+        "editor::AddSelectionAbove",
+    ),
+    (
+        "editor.action.insertCursorBelow",
+        "editor::AddSelectionBelow",
+    ),
+    (
+        "editor.action.smartSelect.expand",
+        "editor::SelectLargerSyntaxNode",
+    ),
+    (
+        "editor.action.smartSelect.shrink",
+        "editor::SelectSmallerSyntaxNode",
+    ),
+    // Cursor movement
+    ("cursorHome", "editor::MoveToBeginningOfLine"),
+    ("cursorEnd", "editor::MoveToEndOfLine"),
+    ("cursorTop", "editor::MoveToBeginning"),
+    ("cursorBottom", "editor::MoveToEnd"),
+    ("cursorWordLeft", "editor::MoveToPreviousWordStart"),
+    ("cursorWordRight", "editor::MoveToNextWordEnd"),
+    (
+        "editor.action.jumpToBracket",
+        "editor::MoveToEnclosingBracket",
+    ),
+    // Editing
+    ("deleteWordLeft", "editor::DeleteToPreviousWordStart"),
+    ("deleteWordRight", "editor::DeleteToNextWordEnd"),
+    ("editor.action.deleteLines", "editor::DeleteLine"),
+    ("editor.action.moveLinesUpAction", "editor::MoveLineUp"),
+    ("editor.action.moveLinesDownAction", "editor::MoveLineDown"),
+    ("editor.action.copyLinesUpAction", "editor::DuplicateLineUp"),
+    (
+        "editor.action.copyLinesDownAction",
+        "editor::DuplicateLineDown",
+    ),
+    ("editor.action.insertLineAfter", "editor::NewlineBelow"),
+    ("editor.action.insertLineBefore", "editor::NewlineAbove"),
+    ("editor.action.indentLines", "editor::Indent"),
+    ("editor.action.outdentLines", "editor::Outdent"),
+    ("editor.action.joinLines", "editor::JoinLines"),
+    ("editor.action.commentLine", "editor::ToggleComments"),
+    (
+        "editor.action.transformToUppercase",
+        "editor::ConvertToUpperCase",
+    ),
+    (
+        "editor.action.transformToLowercase",
+        "editor::ConvertToLowerCase",
+    ),
+    (
+        "editor.action.sortLinesAscending",
+        "editor::SortLinesCaseSensitive",
+    ),
+    ("editor.action.toggleWordWrap", "editor::ToggleSoftWrap"),
+    // Folding
+    ("editor.fold", "editor::Fold"),
+    ("editor.unfold", "editor::UnfoldLines"),
+    ("editor.toggleFold", "editor::ToggleFold"),
+    ("editor.foldAll", "editor::FoldAll"),
+    ("editor.unfoldAll", "editor::UnfoldAll"),
+    // Language features
+    ("editor.action.formatDocument", "editor::Format"),
+    ("editor.action.rename", "editor::Rename"),
+    ("editor.action.revealDefinition", "editor::GoToDefinition"),
+    (
+        "editor.action.goToImplementation",
+        "editor::GoToImplementation",
+    ),
+    (
+        "editor.action.goToTypeDefinition",
+        "editor::GoToTypeDefinition",
+    ),
+    ("editor.action.goToReferences", "editor::FindAllReferences"),
+    (
+        "references-view.findReferences",
+        "editor::FindAllReferences",
+    ),
+    ("editor.action.quickFix", "editor::ToggleCodeActions"),
+    ("editor.action.triggerSuggest", "editor::ShowCompletions"),
+    (
+        "editor.action.triggerParameterHints",
+        "editor::ShowSignatureHelp",
+    ),
+    ("editor.action.showHover", "editor::Hover"),
+    ("editor.action.marker.next", "editor::GoToDiagnostic"),
+    (
+        "editor.action.marker.prev",
+        "editor::GoToPreviousDiagnostic",
+    ),
+    (
+        "editor.action.inlineSuggest.commit",
+        "editor::AcceptEditPrediction",
+    ),
+    // Search
+    ("actions.find", "buffer_search::Deploy"),
+    (
+        "editor.action.startFindReplaceAction",
+        "buffer_search::DeployReplace",
+    ),
+    ("workbench.action.findInFiles", "pane::DeploySearch"),
+    ("workbench.view.search", "pane::DeploySearch"),
+    // Navigation and pickers
+    ("workbench.action.quickOpen", "file_finder::Toggle"),
+    ("workbench.action.showCommands", "command_palette::Toggle"),
+    ("workbench.action.gotoLine", "go_to_line::Toggle"),
+    ("workbench.action.gotoSymbol", "outline::Toggle"),
+    ("workbench.action.showAllSymbols", "project_symbols::Toggle"),
+    ("workbench.action.navigateBack", "pane::GoBack"),
+    ("workbench.action.navigateForward", "pane::GoForward"),
+    ("workbench.action.selectTheme", "theme_selector::Toggle"),
+    ("workbench.action.tasks.runTask", "task::Spawn"),
+    // Files and editors
+    ("workbench.action.files.save", "workspace::Save"),
+    ("workbench.action.files.saveAll", "workspace::SaveAll"),
+    (
+        "workbench.action.files.newUntitledFile",
+        "workspace::NewFile",
+    ),
+    ("workbench.action.files.openFile", "workspace::Open"),
+    ("workbench.action.newWindow", "workspace::NewWindow"),
+    ("workbench.action.closeWindow", "workspace::CloseWindow"),
+    (
+        "workbench.action.closeActiveEditor",
+        "pane::CloseActiveItem",
+    ),
+    ("workbench.action.closeAllEditors", "pane::CloseAllItems"),
+    (
+        "workbench.action.reopenClosedEditor",
+        "pane::ReopenClosedItem",
+    ),
+    ("workbench.action.nextEditor", "pane::ActivateNextItem"),
+    (
+        "workbench.action.previousEditor",
+        "pane::ActivatePreviousItem",
+    ),
+    ("workbench.action.splitEditor", "pane::SplitRight"),
+    ("workbench.action.splitEditorDown", "pane::SplitDown"),
+    // Layout
+    (
+        "workbench.action.toggleSidebarVisibility",
+        "workspace::ToggleLeftDock",
+    ),
+    (
+        "workbench.action.togglePanel",
+        "workspace::ToggleBottomDock",
+    ),
+    (
+        "workbench.action.toggleMaximizedPanel",
+        "workspace::ToggleZoom",
+    ),
+    ("workbench.action.toggleFullScreen", "zed::ToggleFullScreen"),
+    ("workbench.view.explorer", "project_panel::ToggleFocus"),
+    ("workbench.view.scm", "git_panel::ToggleFocus"),
+    (
+        "workbench.action.terminal.toggleTerminal",
+        "terminal_panel::ToggleFocus",
+    ),
+    ("workbench.action.terminal.new", "workspace::NewTerminal"),
+    // Application
+    ("workbench.action.openSettings", "zed::OpenSettings"),
+    (
+        "workbench.action.openGlobalKeybindings",
+        "zed::OpenKeymapEditor",
+    ),
+    ("workbench.action.zoomIn", "zed::IncreaseBufferFontSize"),
+    ("workbench.action.zoomOut", "zed::DecreaseBufferFontSize"),
+    ("workbench.action.zoomReset", "zed::ResetBufferFontSize"),
+    ("workbench.action.quit", "zed::Quit"),
+];
+
+/// VS Code `when` clause context keys and the Zed key context predicates they translate to.
+const CONTEXT_MAPPINGS: &[(&str, &str)] = &[
+    ("editorFocus", "Editor"),
+    ("editorTextFocus", "Editor && mode == full"),
+    ("textInputFocus", "Editor"),
+    ("inputFocus", "Editor"),
+    ("suggestWidgetVisible", "Editor && showing_completions"),
+    ("parameterHintsVisible", "Editor && showing_signature_help"),
+    ("renameInputVisible", "Editor && renaming"),
+    ("inlineSuggestionVisible", "Editor && edit_prediction"),
+    ("findWidgetVisible", "BufferSearchBar"),
+    ("terminalFocus", "Terminal"),
+    ("filesExplorerFocus", "ProjectPanel"),
+    ("explorerViewletFocus", "ProjectPanel"),
+    ("explorerViewletVisible", "ProjectPanel"),
+    ("inQuickOpen", "Picker"),
+    ("searchViewletFocus", "ProjectSearchBar"),
+    ("isMac", "os == macos"),
+    ("isLinux", "os == linux"),
+    ("isWindows", "os == windows"),
+];
+
+/// VS Code language identifiers and the file extension Zed's key context exposes for them.
+const LANGUAGE_EXTENSIONS: &[(&str, &str)] = &[
+    ("c", "c"),
+    ("cpp", "cpp"),
+    ("css", "css"),
+    ("go", "go"),
+    ("html", "html"),
+    ("java", "java"),
+    ("javascript", "js"),
+    ("javascriptreact", "jsx"),
+    ("json", "json"),
+    ("markdown", "md"),
+    ("python", "py"),
+    ("ruby", "rb"),
+    ("rust", "rs"),
+    ("shellscript", "sh"),
+    ("toml", "toml"),
+    ("typescript", "ts"),
+    ("typescriptreact", "tsx"),
+    ("yaml", "yaml"),
+];
+
+#[derive(Clone, Debug, Deserialize)]
+struct VsCodeKeybinding {
+    key: String,
+    command: String,
+    #[serde(default)]
+    when: Option<String>,
+    #[serde(default)]
+    args: Option<Value>,
+}
+
+/// The contents of a VS Code `keybindings.json` file.
+pub struct VsCodeKeybindings {
+    pub source: VsCodeSettingsSource,
+    pub path: Rc<Path>,
+    bindings: Vec<VsCodeKeybinding>,
+}
+
+/// A VS Code keybinding that couldn't be translated into a Zed keybinding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkippedVsCodeKeybinding {
+    pub key: String,
+    pub command: String,
+    pub when: Option<String>,
+    pub reason: String,
+}
+
+/// The result of translating VS Code keybindings into a Zed keymap.
+#[derive(Debug, Default)]
+pub struct VsCodeKeymapImport {
+    /// The translated bindings, grouped by their Zed key context predicate.
+    pub sections: IndexMap<String, IndexMap<String, Value>>,
+    pub skipped: Vec<SkippedVsCodeKeybinding>,
+}
+
+impl VsCodeKeymapImport {
+    pub fn imported_count(&self) -> usize {
+        self.sections.values().map(|bindings| bindings.len()).sum()
+    }
+
+    /// Removes the bindings that are already in `keymap` with the same context, so importing
+    /// again doesn't add them twice. Returns how many were removed.
+    pub fn remove_existing_bindings(&mut self, keymap: &KeymapFile) -> usize {
+        let existing = keymap
+            .sections()
+            .flat_map(|section| {
+                section.bindings().map(move |(keystrokes, action)| {
+                    (
+                        section.context.as_str(),
+                        keystrokes.as_str(),
+                        action.to_string(),
+                    )
+                })
+            })
+            .collect::<HashSet<_>>();
+        let count = self.imported_count();
+        for (context, bindings) in &mut self.sections {
+            bindings.retain(|keystrokes, action| {
+                let action = action.as_str().unwrap_or_default().to_string();
+                !existing.contains(&(context.as_str(), keystrokes.as_str(), action))
+            });
+        }
+        self.sections.retain(|_, bindings| !bindings.is_empty());
+        count - self.imported_count()
+    }
+
+    /// Returns the translated bindings as keymap sections, in the format of `keymap.json`.
+    pub fn keymap_sections(&self) -> Vec<Value> {
+        self.sections
+            .iter()
+            .map(|(context, bindings)| {
+                if context.is_empty() {
+                    json!({ "bindings": bindings })
+                } else {
+                    json!({ "context": context, "bindings": bindings })
+                }
+            })
+            .collect()
+    }
+}
+
+impl VsCodeKeybindings {
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn from_str(content: &str, source: VsCodeSettingsSource) -> Result<Self> {
+        Ok(Self {
+            source,
+            path: Path::new("/example-path/Code/User/keybindings.json").into(),
+            bindings: serde_json_lenient::from_str(content)?,
+        })
+    }
+
+    pub async fn load_user_keybindings(
+        source: VsCodeSettingsSource,
+        fs: Arc<dyn Fs>,
+    ) -> Result<Self> {
+        let candidate_paths = match source {
+            VsCodeSettingsSource::VsCode => vscode_keybindings_file_paths(),
+            VsCodeSettingsSource::Cursor => cursor_keybindings_file_paths(),
+        };
+        let mut path = None;
+        for candidate_path in candidate_paths.iter() {
+            if fs.is_file(candidate_path).await {
+                path = Some(candidate_path.clone());
+            }
+        }
+        let Some(path) = path else {
+            return Err(anyhow!(
+                "No keybindings file found, expected to find it in one of the following paths:\n{}",
+                candidate_paths
+                    .into_iter()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        };
+        let content = fs.load(&path).await.with_context(|| {
+            format!(
+                "Error loading {} keybindings file from {}",
+                source,
+                path.display()
+            )
+        })?;
+        let bindings = serde_json_lenient::from_str(&content).with_context(|| {
+            format!(
+                "Error parsing {} keybindings file from {}",
+                source,
+                path.display()
+            )
+        })?;
+        Ok(Self {
+            source,
+            path: path.into(),
+            bindings,
+        })
+    }
+
+    /// Translates the keybindings into Zed keymap sections, recording the ones that can't be
+    /// translated.
+    pub fn translate(&self) -> VsCodeKeymapImport {
+        let mut import = VsCodeKeymapImport::default();
+        for binding in &self.bindings {
+            match translate_keybinding(binding) {
+                Ok(Some((context, keystrokes, action))) => {
+                    import
+                        .sections
+                        .entry(context)
+                        .or_default()
+                        .insert(keystrokes, action);
+                }
+                Ok(None) => {}
+                Err(reason) => import.skipped.push(SkippedVsCodeKeybinding {
+                    key: binding.key.clone(),
+                    command: binding.command.clone(),
+                    when: binding.when.clone(),
+                    reason,
+                }),
+            }
+        }
+        import
+    }
+}
+
+/// Returns the context, keystrokes and action of the Zed binding, or `None` when the binding is
+/// never active.
+fn translate_keybinding(
+    binding: &VsCodeKeybinding,
+) -> Result<Option<(String, String, Value)>, String> {
+    if binding.command.starts_with('-') {
+        return Err("removes a VS Code default binding, which has no equivalent in Zed".into());
+    }
+    if binding.args.is_some() {
+        return Err("command arguments can't be translated".into());
+    }
+    let action = COMMAND_MAPPINGS
+        .iter()
+        .find(|(command, _)| *command == binding.command)
+        .map(|(_, action)| *action)
+        .ok_or_else(|| "no equivalent Zed action".to_string())?;
+    let keystrokes = translate_keystrokes(&binding.key)?;
+    let context = match binding.when.as_deref().map(str::trim) {
+        None | Some("") => WhenClause::True,
+        Some(when) => translate_when_clause(when)?,
+    };
+    let context = match context {
+        WhenClause::False => return Ok(None),
+        WhenClause::True => String::new(),
+        WhenClause::Predicate(predicate) => predicate,
+    };
+    Ok(Some((
+        context,
+        keystrokes,
+        Value::String(action.to_string()),
+    )))
+}
+
+/// Translates VS Code keystrokes such as `ctrl+k ctrl+c` into Zed keystrokes such as
+/// `ctrl-k ctrl-c`.
+fn translate_keystrokes(key: &str) -> Result<String, String> {
+    key.split_whitespace()
+        .map(translate_keystroke)
+        .collect::<Result<Vec<_>, _>>()
+        .map(|keystrokes| keystrokes.join(" "))
+}
+
+fn translate_keystroke(chord: &str) -> Result<String, String> {
+    let chord = chord.to_lowercase();
+    let (modifiers, key) = match chord.strip_suffix("++") {
+        Some(modifiers) => (modifiers, "+"),
+        None => chord.rsplit_once('+').unwrap_or(("", chord.as_str())),
+    };
+
+    let mut keystroke = String::new();
+    for modifier in modifiers.split('+').filter(|modifier| !modifier.is_empty()) {
+        let modifier = match modifier {
+            "ctrl" | "shift" | "alt" | "cmd" | "win" => modifier,
+            "meta" => "super",
+            _ => return Err(format!("unknown modifier `{modifier}`")),
+        };
+        keystroke.push_str(modifier);
+        keystroke.push('-');
+    }
+
+    let key = match key {
+        "" => return Err("missing key".into()),
+        "pausebreak" | "capslock" | "contextmenu" => {
+            return Err(format!("key `{key}` isn't supported"));
+        }
+        _ if key.starts_with('[') && key.len() > 1 => {
+            return Err(format!("scan code `{key}` isn't supported"));
+        }
+        _ if key.starts_with("numpad") || key.starts_with("oem_") || key.starts_with("abnt_") => {
+            return Err(format!("key `{key}` isn't supported"));
+        }
+        _ => key,
+    };
+    keystroke.push_str(key);
+    Ok(keystroke)
+}
+
+#[derive(Debug, PartialEq)]
+enum WhenClause {
+    True,
+    False,
+    Predicate(String),
+}
+
+/// Translates a VS Code `when` clause into a Zed key context predicate.
+fn translate_when_clause(when: &str) -> Result<WhenClause, String> {
+    let tokens = tokenize_when_clause(when)?;
+    let mut parser = WhenClauseParser {
+        tokens: &tokens,
+        position: 0,
+    };
+    let clause = parser.parse_or()?;
+    if parser.position < tokens.len() {
+        return Err(format!(
+            "unexpected `{}` in `when` clause",
+            tokens[parser.position]
+        ));
+    }
+    Ok(clause)
+}
+
+fn tokenize_when_clause(when: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = when.char_indices().peekable();
+    while let Some((start, char)) = chars.next() {
+        match char {
+            _ if char.is_whitespace() => {}
+            '(' | ')' => tokens.push(char.to_string()),
+            '&' | '|' | '=' | '!' | '<' | '>' => {
+                let mut operator = char.to_string();
+                if let Some((_, next)) = chars.next_if(|(_, next)| "&|=~".contains(*next)) {
+                    operator.push(next);
+                }
+                match operator.as_str() {
+                    "&&" | "||" | "==" | "!=" | "!" => tokens.push(operator),
+                    _ => return Err(format!("operator `{operator}` isn't supported")),
+                }
+            }
+            '\'' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) => break,
+                        Some((_, char)) => value.push(char),
+                        None => return Err("unterminated string in `when` clause".into()),
+                    }
+                }
+                tokens.push(value);
+            }
+            _ => {
+                let mut end = start + char.len_utf8();
+                while let Some((ix, char)) =
+                    chars.next_if(|(_, next)| !next.is_whitespace() && !"()&|=!<>'".contains(*next))
+                {
+                    end = ix + char.len_utf8();
+                }
+                tokens.push(when[start..end].to_string());
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct WhenClauseParser<'a> {
+    tokens: &'a [String],
+    position: usize,
+}
+
+impl WhenClauseParser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<&str> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(token)
+    }
+
+    fn parse_or(&mut self) -> Result<WhenClause, String> {
+        let mut clause = self.parse_and()?;
+        while self.peek() == Some("||") {
+            self.position += 1;
+            let right = self.parse_and()?;
+            clause = match (clause, right) {
+                (WhenClause::True, _) | (_, WhenClause::True) => WhenClause::True,
+                (WhenClause::False, other) | (other, WhenClause::False) => other,
+                (WhenClause::Predicate(left), WhenClause::Predicate(right)) => {
+                    WhenClause::Predicate(format!("{left} || {right}"))
+                }
+            };
+        }
+        Ok(clause)
+    }
+
+    fn parse_and(&mut self) -> Result<WhenClause, String> {
+        let mut clause = self.parse_unary()?;
+        while self.peek() == Some("&&") {
+            self.position += 1;
+            let right = self.parse_unary()?;
+            clause = match (clause, right) {
+                (WhenClause::False, _) | (_, WhenClause::False) => WhenClause::False,
+                (WhenClause::True, other) | (other, WhenClause::True) => other,
+                (WhenClause::Predicate(left), WhenClause::Predicate(right)) => {
+                    WhenClause::Predicate(format!("{} && {}", group(&left), group(&right)))
+                }
+            };
+        }
+        Ok(clause)
+    }
+
+    fn parse_unary(&mut self) -> Result<WhenClause, String> {
+        if self.peek() == Some("!") {
+            self.position += 1;
+            return Ok(match self.parse_unary()? {
+                WhenClause::True => WhenClause::False,
+                WhenClause::False => WhenClause::True,
+                WhenClause::Predicate(predicate) => {
+                    WhenClause::Predicate(format!("!{}", group(&predicate)))
+                }
+            });
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<WhenClause, String> {
+        let token = self
+            .next()
+            .ok_or_else(|| "unexpected end of `when` clause".to_string())?
+            .to_string();
+        if token == "(" {
+            let clause = self.parse_or()?;
+            if self.next() != Some(")") {
+                return Err("expected `)` in `when` clause".into());
+            }
+            return Ok(match clause {
+                WhenClause::Predicate(predicate) => WhenClause::Predicate(format!("({predicate})")),
+                clause => clause,
+            });
+        }
+
+        let comparison = match self.peek() {
+            Some(operator @ ("==" | "!=")) => {
+                let equals = operator == "==";
+                self.position += 1;
+                let value = self
+                    .next()
+                    .ok_or_else(|| format!("missing value to compare `{token}` with"))?;
+                Some((equals, value.to_string()))
+            }
+            Some("in" | "not") => {
+                return Err(format!("`in` comparisons on `{token}` aren't supported"));
+            }
+            _ => None,
+        };
+
+        let predicate = match (token.as_str(), comparison) {
+            ("true", None) => return Ok(WhenClause::True),
+            ("false", None) => return Ok(WhenClause::False),
+            ("editorLangId", Some((equals, language))) => {
+                let extension = LANGUAGE_EXTENSIONS
+                    .iter()
+                    .find(|(id, _)| *id == language)
+                    .map(|(_, extension)| *extension)
+                    .ok_or_else(|| format!("unknown language `{language}`"))?;
+                let operator = if equals { "==" } else { "!=" };
+                format!("Editor && extension {operator} {extension}")
+            }
+            ("resourceExtname", Some((equals, extension))) => {
+                let extension = extension.trim_start_matches('.');
+                let operator = if equals { "==" } else { "!=" };
+                format!("extension {operator} {extension}")
+            }
+            (_, Some(_)) => return Err(format!("comparisons on `{token}` aren't supported")),
+            (key, None) => CONTEXT_MAPPINGS
+                .iter()
+                .find(|(vscode_key, _)| *vscode_key == key)
+                .map(|(_, predicate)| predicate.to_string())
+                .ok_or_else(|| format!("unknown context key `{key}`"))?,
+        };
+        Ok(WhenClause::Predicate(predicate))
+    }
+}
+
+/// Wraps a compound predicate in parentheses, so it can be combined with other predicates.
+fn group(predicate: &str) -> String {
+    let is_grouped = predicate.starts_with('(') && predicate.ends_with(')');
+    if predicate.contains(' ') && !is_grouped {
+        format!("({predicate})")
+    } else {
+        predicate.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_translate_keystrokes() {
+        assert_eq!(
+            translate_keystrokes("ctrl+shift+p").unwrap(),
+            "ctrl-shift-p"
+        );
+        assert_eq!(
+            translate_keystrokes("Ctrl+K Ctrl+C").unwrap(),
+            "ctrl-k ctrl-c"
+        );
+        assert_eq!(translate_keystrokes("cmd++").unwrap(), "cmd-+");
+        assert_eq!(translate_keystrokes("ctrl+-").unwrap(), "ctrl--");
+        assert_eq!(translate_keystrokes("meta+f1").unwrap(), "super-f1");
+        assert!(translate_keystrokes("ctrl+numpad_add").is_err());
+        assert!(translate_keystrokes("ctrl+[KeyA]").is_err());
+    }
+
+    #[test]
+    fn test_translate_when_clause() {
+        let predicate = |when| match translate_when_clause(when).unwrap() {
+            WhenClause::Predicate(predicate) => predicate,
+            clause => panic!("expected a predicate, got {clause:?}"),
+        };
+
+        assert_eq!(predicate("editorTextFocus"), "Editor && mode == full");
+        assert_eq!(
+            predicate("terminalFocus || inQuickOpen"),
+            "Terminal || Picker"
+        );
+        assert_eq!(
+            predicate("editorTextFocus && !suggestWidgetVisible"),
+            "(Editor && mode == full) && !(Editor && showing_completions)"
+        );
+        assert_eq!(
+            predicate("editorFocus && (isMac || isLinux)"),
+            "Editor && (os == macos || os == linux)"
+        );
+        assert_eq!(
+            predicate("editorLangId == 'rust'"),
+            "Editor && extension == rs"
+        );
+        assert_eq!(
+            predicate("editorFocus && resourceExtname == .md"),
+            "Editor && (extension == md)"
+        );
+        assert_eq!(predicate("editorFocus && true"), "Editor");
+        assert_eq!(
+            translate_when_clause("terminalFocus && false"),
+            Ok(WhenClause::False)
+        );
+
+        assert!(translate_when_clause("vim.active").is_err());
+        assert!(translate_when_clause("resourceFilename =~ /test/").is_err());
+        assert!(translate_when_clause("editorLangId in supportedLanguages").is_err());
+    }
+
+    #[test]
+    fn test_translate_keybindings() {
+        let keybindings = VsCodeKeybindings::from_str(
+            r#"[
+                // Comments are allowed
+                { "key": "ctrl+shift+k", "command": "editor.action.deleteLines", "when": "editorTextFocus" },
+                { "key": "ctrl+p", "command": "workbench.action.quickOpen" },
+                { "key": "ctrl+d", "command": "-editor.action.addSelectionToNextFindMatch" },
+                { "key": "ctrl+alt+t", "command": "workbench.action.tasks.runTask", "args": "build" },
+                { "key": "ctrl+k ctrl+z", "command": "some.extension.command" },
+                { "key": "ctrl+`", "command": "workbench.action.terminal.toggleTerminal", "when": "vim.active" },
+                { "key": "ctrl+j", "command": "workbench.action.togglePanel", "when": "false" },
+                { "key": "ctrl+shift+up", "command": "editor.action.moveLinesUpAction", "when": "editorTextFocus" }
+            ]"#,
+            VsCodeSettingsSource::VsCode,
+        )
+        .unwrap();
+
+        let import = keybindings.translate();
+        assert_eq!(import.imported_count(), 3);
+        assert_eq!(
+            import.keymap_sections(),
+            vec![
+                json!({
+                    "context": "Editor && mode == full",
+                    "bindings": {
+                        "ctrl-shift-k": "editor::DeleteLine",
+                        "ctrl-shift-up": "editor::MoveLineUp"
+                    }
+                }),
+                json!({ "bindings": { "ctrl-p": "file_finder::Toggle" } }),
+            ]
+        );
+        assert_eq!(
+            import
+                .skipped
+                .iter()
+                .map(|skipped| (skipped.command.as_str(), skipped.reason.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "-editor.action.addSelectionToNextFindMatch",
+                    "removes a VS Code default binding, which has no equivalent in Zed"
+                ),
+                (
+                    "workbench.action.tasks.runTask",
+                    "command arguments can't be translated"
+                ),
+                ("some.extension.command", "no equivalent Zed action"),
+                (
+                    "workbench.action.terminal.toggleTerminal",
+                    "unknown context key `vim.active`"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_existing_bindings() {
+        let keybindings = VsCodeKeybindings::from_str(
+            r#"[
+                { "key": "ctrl+shift+k", "command": "editor.action.deleteLines", "when": "editorTextFocus" },
+                { "key": "ctrl+shift+up", "command": "editor.action.moveLinesUpAction", "when": "editorTextFocus" },
+                { "key": "ctrl+p", "command": "workbench.action.quickOpen" }
+            ]"#,
+            VsCodeSettingsSource::VsCode,
+        )
+        .unwrap();
+        let keymap = KeymapFile::parse(
+            r#"[
+                {
+                    "context": "Editor && mode == full",
+                    "bindings": { "ctrl-shift-k": "editor::DeleteLine" }
+                },
+                {
+                    "bindings": {
+                        "ctrl-shift-up": "editor::MoveLineUp",
+                        "ctrl-p": "file_finder::Toggle"
+                    }
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let mut import = keybindings.translate();
+        assert_eq!(import.remove_existing_bindings(&keymap), 2);
+        assert_eq!(
+            import.keymap_sections(),
+            vec![json!({
+                "context": "Editor && mode == full",
+                "bindings": { "ctrl-shift-up": "editor::MoveLineUp" }
+            })]
+        );
+    }
+}
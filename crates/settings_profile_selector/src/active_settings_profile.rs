@@ -0,0 +1,228 @@
+use gpui::{
+    App, Context, Entity, Global, IntoElement, ParentElement, Render, Subscription, WeakEntity,
+    Window, div,
+};
+use project::{Project, git_store::GitStoreEvent};
+use settings::{
+    ActiveSettingsProfileName, SettingsProfileActivationContext, SettingsProfileActivationRule,
+    SettingsProfileWorktree, SettingsStore, matching_settings_profile,
+};
+use std::path::{Path, PathBuf};
+use ui::{Button, ButtonCommon, Clickable, FluentBuilder, LabelSize, Tooltip};
+use workspace::{StatusItemView, Workspace, item::ItemHandle};
+use zed_actions::settings_profile_selector::Toggle;
+
+use crate::toggle_settings_profile_selector;
+
+/// Tracks which profile the activation rules picked, so that choosing a profile by hand isn't
+/// undone until a different profile's rules match.
+#[derive(Default)]
+struct SettingsProfileActivation {
+    last_match: Option<String>,
+    overridden_match: Option<Option<String>>,
+}
+
+impl Global for SettingsProfileActivation {}
+
+/// Keeps the profile picked by hand in the selector active until the activation rules match
+/// a different profile.
+pub(crate) fn record_manual_selection(cx: &mut App) {
+    let activation = cx.default_global::<SettingsProfileActivation>();
+    activation.overridden_match = Some(activation.last_match.clone());
+}
+
+/// Shows the active settings profile in the status bar, and activates profiles whose rules
+/// match the workspace's project while its window is active.
+///
+/// Settings profiles apply to every window, so the rules are only checked while a single
+/// workspace window is open. With more windows open, the active profile is left as it is.
+pub struct ActiveSettingsProfile {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    /// The activation rules of each profile, parsed when the user settings change.
+    rules: Vec<(String, Vec<SettingsProfileActivationRule>)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ActiveSettingsProfile {
+    pub fn new(workspace: &Workspace, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let project = workspace.project().clone();
+        let git_store = project.read(cx).git_store().clone();
+        let subscriptions = vec![
+            cx.observe_window_activation(window, |this, window, cx| {
+                this.activate_matching_profile(window, cx);
+            }),
+            cx.subscribe_in(&project, window, |this, _, event, window, cx| match event {
+                project::Event::WorktreeAdded(_) | project::Event::WorktreeRemoved(_) => {
+                    this.activate_matching_profile(window, cx)
+                }
+                project::Event::WorktreeUpdatedEntries(_, changes) => {
+                    if changes
+                        .iter()
+                        .any(|(path, _, _)| this.is_file_exists_path(path))
+                    {
+                        this.activate_matching_profile(window, cx)
+                    }
+                }
+                _ => {}
+            }),
+            cx.subscribe_in(
+                &git_store,
+                window,
+                |this, _, event, window, cx| match event {
+                    GitStoreEvent::RepositoryAdded(_)
+                    | GitStoreEvent::RepositoryRemoved(_)
+                    | GitStoreEvent::RepositoryUpdated(..) => {
+                        this.activate_matching_profile(window, cx)
+                    }
+                    _ => {}
+                },
+            ),
+            cx.observe_global_in::<SettingsStore>(window, |this, window, cx| {
+                this.rules = cx
+                    .global::<SettingsStore>()
+                    .settings_profile_activation_rules();
+                this.activate_matching_profile(window, cx);
+            }),
+            cx.observe_global::<ActiveSettingsProfileName>(|_, cx| cx.notify()),
+        ];
+
+        Self {
+            workspace: workspace.weak_handle(),
+            project,
+            rules: cx
+                .global::<SettingsStore>()
+                .settings_profile_activation_rules(),
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Whether a `file_exists` rule names the given worktree-relative path, so that creating
+    /// or deleting it can change which profile matches.
+    fn is_file_exists_path(&self, path: &Path) -> bool {
+        self.rules
+            .iter()
+            .flat_map(|(_, rules)| rules)
+            .filter_map(|rule| rule.file_exists.as_deref())
+            .any(|file| Path::new(file) == path)
+    }
+
+    fn activate_matching_profile(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !window.is_window_active() || self.rules.is_empty() || workspace_window_count(cx) > 1 {
+            return;
+        }
+
+        let context = self.activation_context(cx);
+        let matched = matching_settings_profile(&self.rules, &context);
+
+        let activation = cx.default_global::<SettingsProfileActivation>();
+        activation.last_match = matched.clone();
+        if activation.overridden_match.as_ref() == Some(&matched) {
+            return;
+        }
+        activation.overridden_match = None;
+
+        let active = cx
+            .try_global::<ActiveSettingsProfileName>()
+            .map(|profile| &profile.0);
+        if active == matched.as_ref() {
+            return;
+        }
+        match matched {
+            Some(profile_name) => cx.set_global(ActiveSettingsProfileName(profile_name)),
+            None => {
+                cx.remove_global::<ActiveSettingsProfileName>();
+            }
+        }
+    }
+
+    fn activation_context(&self, cx: &App) -> SettingsProfileActivationContext {
+        let files = self
+            .rules
+            .iter()
+            .flat_map(|(_, rules)| rules)
+            .filter_map(|rule| rule.file_exists.as_deref())
+            .collect::<Vec<_>>();
+
+        let project = self.project.read(cx);
+        let worktrees = project
+            .visible_worktrees(cx)
+            .map(|worktree| {
+                let worktree = worktree.read(cx);
+                SettingsProfileWorktree {
+                    abs_path: worktree.abs_path().to_path_buf(),
+                    existing_files: files
+                        .iter()
+                        .filter(|file| worktree.entry_for_path(file).is_some())
+                        .map(PathBuf::from)
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let git_remote_urls = project
+            .git_store()
+            .read(cx)
+            .repositories()
+            .values()
+            .flat_map(|repository| {
+                let repository = repository.read(cx);
+                [
+                    repository.remote_origin_url.clone(),
+                    repository.remote_upstream_url.clone(),
+                ]
+            })
+            .flatten()
+            .collect();
+
+        SettingsProfileActivationContext {
+            worktrees,
+            remote_host: project
+                .ssh_connection_options(cx)
+                .map(|options| options.host),
+            git_remote_urls,
+        }
+    }
+}
+
+fn workspace_window_count(cx: &App) -> usize {
+    cx.windows()
+        .iter()
+        .filter(|window| window.downcast::<Workspace>().is_some())
+        .count()
+}
+
+impl Render for ActiveSettingsProfile {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let active_profile = cx
+            .try_global::<ActiveSettingsProfileName>()
+            .map(|profile| profile.0.clone());
+
+        div().when_some(active_profile, |el, active_profile| {
+            el.child(
+                Button::new("change-settings-profile", active_profile)
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        if let Some(workspace) = this.workspace.upgrade() {
+                            workspace.update(cx, |workspace, cx| {
+                                toggle_settings_profile_selector(workspace, window, cx)
+                            });
+                        }
+                    }))
+                    .tooltip(|window, cx| {
+                        Tooltip::for_action("Select Settings Profile", &Toggle, window, cx)
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ActiveSettingsProfile {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+}
@@ -1,3 +1,5 @@
+mod active_settings_profile;
+
 use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
 use gpui::{
     App, Context, DismissEvent, Entity, EventEmitter, Focusable, Render, Task, WeakEntity, Window,
@@ -7,6 +9,8 @@ use settings::{ActiveSettingsProfileName, SettingsStore};
 use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
 use workspace::{ModalView, Workspace};
 
+pub use active_settings_profile::ActiveSettingsProfile;
+
 pub fn init(cx: &mut App) {
     cx.on_action(|_: &zed_actions::settings_profile_selector::Toggle, cx| {
         workspace::with_active_or_new_workspace(cx, |workspace, window, cx| {
@@ -229,6 +233,7 @@ impl PickerDelegate for SettingsProfileSelectorDelegate {
         cx: &mut Context<Picker<SettingsProfileSelectorDelegate>>,
     ) {
         self.selection_completed = true;
+        active_settings_profile::record_manual_selection(cx);
         self.selector
             .update(cx, |_, cx| {
                 cx.emit(DismissEvent);
@@ -0,0 +1,407 @@
+//! Flattens the settings JSON schema into the settings listed by the settings
+//! editor, and finds which settings file each value comes from.
+use gpui::SharedString;
+use serde_json::{Map, Value};
+
+/// A single setting, at `key_path` in the settings file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingEntry {
+    pub key_path: Vec<String>,
+    pub title: SharedString,
+    pub description: Option<SharedString>,
+    pub kind: SettingKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingKind {
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Enum(Vec<String>),
+    /// Objects, arrays, and values that can take several shapes, which are
+    /// edited in the settings file.
+    Json,
+}
+
+impl SettingEntry {
+    pub fn key(&self) -> String {
+        self.key_path.join(".")
+    }
+
+    /// Whether each word of the query appears in the setting's key, title, or
+    /// description.
+    pub fn matches(&self, query: &str) -> bool {
+        let haystack = format!(
+            "{} {} {}",
+            self.key(),
+            self.title,
+            self.description.as_deref().unwrap_or_default()
+        )
+        .to_lowercase();
+        query
+            .split_whitespace()
+            .all(|word| haystack.contains(&word.to_lowercase()))
+    }
+}
+
+/// Lists the settings in a schema produced by `SettingsStore::json_schema`,
+/// sorted by key. Objects with known properties are listed property by
+/// property, rather than as a whole.
+pub fn setting_entries(schema: &Value) -> Vec<SettingEntry> {
+    let defs = schema.get("$defs").unwrap_or(&Value::Null);
+    let root = schema
+        .get("allOf")
+        .and_then(|all_of| all_of.get(0))
+        .unwrap_or(schema);
+    let mut entries = Vec::new();
+    if let Some(properties) = resolve(root, defs)
+        .get("properties")
+        .and_then(Value::as_object)
+    {
+        collect_entries(properties, defs, &mut Vec::new(), &mut entries);
+    }
+    entries.sort_by(|a, b| a.key_path.cmp(&b.key_path));
+    entries
+}
+
+fn collect_entries(
+    properties: &Map<String, Value>,
+    defs: &Value,
+    key_path: &mut Vec<String>,
+    entries: &mut Vec<SettingEntry>,
+) {
+    for (key, property) in properties {
+        key_path.push(key.clone());
+        let resolved = resolve(property, defs);
+        match resolved.get("properties").and_then(Value::as_object) {
+            Some(nested) if !nested.is_empty() => {
+                collect_entries(nested, defs, key_path, entries);
+            }
+            _ => {
+                let description = property
+                    .get("description")
+                    .or_else(|| resolved.get("description"))
+                    .and_then(Value::as_str)
+                    .map(|description| description.trim().to_string().into());
+                entries.push(SettingEntry {
+                    key_path: key_path.clone(),
+                    title: title_for_key(key).into(),
+                    description,
+                    kind: setting_kind(resolved),
+                });
+            }
+        }
+        key_path.pop();
+    }
+}
+
+/// Follows references, and unwraps optional values.
+fn resolve<'a>(mut schema: &'a Value, defs: &'a Value) -> &'a Value {
+    loop {
+        if let Some(def) = schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix("#/$defs/"))
+            .and_then(|name| defs.get(name))
+        {
+            schema = def;
+            continue;
+        }
+
+        let variants = schema
+            .get("anyOf")
+            .or_else(|| schema.get("oneOf"))
+            .and_then(Value::as_array);
+        if let Some(variants) = variants {
+            let mut non_null = variants
+                .iter()
+                .filter(|variant| variant.get("type").and_then(Value::as_str) != Some("null"));
+            if let (Some(variant), None) = (non_null.next(), non_null.next())
+                && variants.len() > 1
+            {
+                schema = variant;
+                continue;
+            }
+        }
+        return schema;
+    }
+}
+
+fn setting_kind(schema: &Value) -> SettingKind {
+    if let Some(options) = enum_options(schema) {
+        return SettingKind::Enum(options);
+    }
+    let types = match schema.get("type") {
+        Some(Value::String(ty)) => vec![ty.as_str()],
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .filter(|ty| *ty != "null")
+            .collect(),
+        _ => Vec::new(),
+    };
+    match types.as_slice() {
+        ["boolean"] => SettingKind::Boolean,
+        ["integer"] => SettingKind::Integer,
+        ["number"] => SettingKind::Number,
+        ["string"] => SettingKind::String,
+        _ => SettingKind::Json,
+    }
+}
+
+/// The options of a string enum, written either as `enum`, or as `oneOf` of
+/// documented `const`s.
+fn enum_options(schema: &Value) -> Option<Vec<String>> {
+    fn push_option(options: &mut Vec<String>, value: &Value) -> Option<()> {
+        match value {
+            Value::Null => {}
+            value => options.push(value.as_str()?.to_string()),
+        }
+        Some(())
+    }
+
+    let mut options = Vec::new();
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        for value in values {
+            push_option(&mut options, value)?;
+        }
+    } else {
+        let variants = schema
+            .get("oneOf")
+            .or_else(|| schema.get("anyOf"))?
+            .as_array()?;
+        for variant in variants {
+            if let Some(value) = variant.get("const") {
+                push_option(&mut options, value)?;
+            } else if let Some(values) = variant.get("enum").and_then(Value::as_array) {
+                for value in values {
+                    push_option(&mut options, value)?;
+                }
+            } else if variant.get("type").and_then(Value::as_str) != Some("null") {
+                return None;
+            }
+        }
+    }
+    Some(options).filter(|options| !options.is_empty())
+}
+
+fn title_for_key(key: &str) -> String {
+    key.split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Where a settings value comes from, from lowest to highest precedence.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SettingsLayer {
+    Default,
+    User,
+    /// A project's `.zed/settings.json`, named by its worktree.
+    Project(SharedString),
+}
+
+impl SettingsLayer {
+    pub fn label(&self) -> SharedString {
+        match self {
+            SettingsLayer::Default => "Default".into(),
+            SettingsLayer::User => "User".into(),
+            SettingsLayer::Project(name) => format!("Project {name}").into(),
+        }
+    }
+}
+
+/// The values a setting is given by each settings file.
+#[derive(Debug, Default, PartialEq)]
+pub struct SettingValues {
+    /// From lowest to highest precedence, so the last one is in effect.
+    pub values: Vec<(SettingsLayer, Value)>,
+    /// Values set for specific languages, in `languages`, which win over
+    /// `values` in files of those languages.
+    pub language_overrides: Vec<(SettingsLayer, String, Value)>,
+}
+
+impl SettingValues {
+    /// Finds the values set at `key_path` in each of the `layers`, which are
+    /// ordered from lowest to highest precedence.
+    pub fn new(layers: &[(SettingsLayer, &Value)], key_path: &[String]) -> Self {
+        let mut this = Self::default();
+        for (layer, content) in layers {
+            if let Some(value) = value_at_key_path(content, key_path) {
+                this.values.push((layer.clone(), value.clone()));
+            }
+
+            // Only top-level settings can be overridden per language.
+            let [key] = key_path else {
+                continue;
+            };
+            let languages = content.get("languages").and_then(Value::as_object);
+            for (language, settings) in languages.into_iter().flatten() {
+                if let Some(value) = settings.get(key).filter(|value| !value.is_null()) {
+                    this.language_overrides
+                        .push((layer.clone(), language.clone(), value.clone()));
+                }
+            }
+        }
+        this
+    }
+
+    pub fn effective(&self) -> Option<&(SettingsLayer, Value)> {
+        self.values.last()
+    }
+
+    pub fn value_in(&self, layer: &SettingsLayer) -> Option<&Value> {
+        self.values
+            .iter()
+            .find(|(value_layer, _)| value_layer == layer)
+            .map(|(_, value)| value)
+    }
+}
+
+fn value_at_key_path<'a>(content: &'a Value, key_path: &[String]) -> Option<&'a Value> {
+    key_path
+        .iter()
+        .try_fold(content, |value, key| value.get(key))
+        .filter(|value| !value.is_null())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_setting_entries() {
+        let schema = json!({
+            "allOf": [
+                { "$ref": "#/$defs/ZedSettings" },
+                { "properties": { "stable": { "$ref": "#/$defs/ZedSettingsOverride" } } }
+            ],
+            "$defs": {
+                "ZedSettings": {
+                    "type": "object",
+                    "properties": {
+                        "vim_mode": {
+                            "description": "Whether to enable vim modes and key bindings.",
+                            "type": ["boolean", "null"]
+                        },
+                        "tab_size": { "type": ["integer", "null"], "format": "uint32" },
+                        "buffer_font_family": {
+                            "anyOf": [{ "$ref": "#/$defs/FontFamilyName" }, { "type": "null" }]
+                        },
+                        "cursor_shape": {
+                            "anyOf": [{ "$ref": "#/$defs/CursorShape" }, { "type": "null" }]
+                        },
+                        "git": { "$ref": "#/$defs/GitSettings" },
+                        "lsp": { "type": "object", "additionalProperties": {} }
+                    }
+                },
+                "FontFamilyName": { "type": "string" },
+                "CursorShape": {
+                    "description": "The shape of the cursor.",
+                    "oneOf": [
+                        { "description": "A vertical bar", "type": "string", "const": "bar" },
+                        { "description": "A block", "type": "string", "const": "block" }
+                    ]
+                },
+                "GitSettings": {
+                    "type": "object",
+                    "properties": {
+                        "inline_blame": {
+                            "type": "object",
+                            "properties": {
+                                "delay_ms": { "type": "number" },
+                                "mode": { "type": "string", "enum": ["on", "off", null] }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let entries = setting_entries(&schema);
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.key(), entry.kind.clone()))
+                .collect::<Vec<_>>(),
+            [
+                ("buffer_font_family".into(), SettingKind::String),
+                (
+                    "cursor_shape".into(),
+                    SettingKind::Enum(vec!["bar".into(), "block".into()])
+                ),
+                ("git.inline_blame.delay_ms".into(), SettingKind::Number),
+                (
+                    "git.inline_blame.mode".into(),
+                    SettingKind::Enum(vec!["on".into(), "off".into()])
+                ),
+                ("lsp".into(), SettingKind::Json),
+                ("tab_size".into(), SettingKind::Integer),
+                ("vim_mode".into(), SettingKind::Boolean),
+            ]
+        );
+
+        let vim_mode = entries.last().unwrap();
+        assert_eq!(vim_mode.title.as_ref(), "Vim Mode");
+        assert!(vim_mode.matches("VIM key"));
+        assert!(!vim_mode.matches("vim emacs"));
+        assert_eq!(
+            entries[1].description.as_deref(),
+            Some("The shape of the cursor.")
+        );
+    }
+
+    #[test]
+    fn test_setting_values() {
+        let default = json!({
+            "tab_size": 4,
+            "languages": { "Go": { "tab_size": 8 } },
+            "git": { "inline_blame": { "enabled": true } }
+        });
+        let user = json!({
+            "tab_size": 2,
+            "languages": { "Python": { "tab_size": 4 } },
+            "git": { "inline_blame": { "enabled": null } }
+        });
+        let project = json!({ "tab_size": 3 });
+        let layers = [
+            (SettingsLayer::Default, &default),
+            (SettingsLayer::User, &user),
+            (SettingsLayer::Project("zed".into()), &project),
+        ];
+
+        let tab_size = SettingValues::new(&layers, &["tab_size".into()]);
+        assert_eq!(
+            tab_size.effective(),
+            Some(&(SettingsLayer::Project("zed".into()), json!(3)))
+        );
+        assert_eq!(tab_size.value_in(&SettingsLayer::User), Some(&json!(2)));
+        assert_eq!(
+            tab_size.language_overrides,
+            [
+                (SettingsLayer::Default, "Go".into(), json!(8)),
+                (SettingsLayer::User, "Python".into(), json!(4)),
+            ]
+        );
+
+        let blame = SettingValues::new(
+            &layers,
+            &["git".into(), "inline_blame".into(), "enabled".into()],
+        );
+        assert_eq!(
+            blame.values,
+            [(SettingsLayer::Default, json!(true))],
+            "null values are unset"
+        );
+        assert!(blame.language_overrides.is_empty());
+    }
+}
@@ -1,16 +1,26 @@
 mod appearance_settings_controls;
+mod setting_entries;
 
 use std::any::TypeId;
+use std::sync::Arc;
 
 use command_palette_hooks::CommandPaletteFilter;
-use editor::EditorSettingsControls;
+use editor::{Editor, EditorEvent, EditorSettingsControls};
 use feature_flags::{FeatureFlag, FeatureFlagViewExt};
-use gpui::{App, Entity, EventEmitter, FocusHandle, Focusable, actions};
-use ui::prelude::*;
+use fs::Fs;
+use gpui::{
+    Action, App, Entity, EventEmitter, FocusHandle, Focusable, ListAlignment, ListState,
+    Subscription, actions, list,
+};
+use project::Project;
+use serde_json::Value;
+use settings::{SettingsJsonSchemaParams, SettingsStore};
+use ui::{ContextMenu, DropdownMenu, Switch, Tooltip, prelude::*};
 use workspace::item::{Item, ItemEvent};
 use workspace::{Workspace, with_active_or_new_workspace};
 
 use crate::appearance_settings_controls::AppearanceSettingsControls;
+use crate::setting_entries::{SettingEntry, SettingKind, SettingValues, SettingsLayer};
 
 pub mod keybindings;
 pub mod ui_components;
@@ -41,7 +51,7 @@ pub fn init(cx: &mut App) {
             if let Some(existing) = existing {
                 workspace.activate_item(&existing, true, true, window, cx);
             } else {
-                let settings_page = SettingsPage::new(workspace, cx);
+                let settings_page = SettingsPage::new(workspace, window, cx);
                 workspace.add_item_to_active_pane(Box::new(settings_page), None, true, window, cx)
             }
         });
@@ -79,16 +89,393 @@ pub fn init(cx: &mut App) {
     keybindings::init(cx);
 }
 
+/// A searchable page of every setting, generated from the settings JSON
+/// schema. Values are edited in the user settings file.
 pub struct SettingsPage {
     focus_handle: FocusHandle,
+    project: Entity<Project>,
+    fs: Arc<dyn Fs>,
+    search_editor: Entity<Editor>,
+    value_editor: Entity<Editor>,
+    entries: Vec<SettingEntry>,
+    /// Indices into `entries` of the settings matching the search.
+    matches: Vec<usize>,
+    /// The index into `entries` of the setting being edited in `value_editor`.
+    editing: Option<usize>,
+    /// The values from each project's `.zed/settings.json`.
+    project_settings: Vec<(SharedString, Value)>,
+    list_state: ListState,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl SettingsPage {
-    pub fn new(_workspace: &Workspace, cx: &mut Context<Workspace>) -> Entity<Self> {
-        cx.new(|cx| Self {
-            focus_handle: cx.focus_handle(),
+    pub fn new(
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        cx.new(|cx| {
+            let search_editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Search settings…", cx);
+                editor
+            });
+            let value_editor = cx.new(|cx| Editor::single_line(window, cx));
+
+            let font_names = cx.text_system().all_font_names();
+            let schema = cx.global::<SettingsStore>().json_schema(
+                &SettingsJsonSchemaParams {
+                    language_names: &[],
+                    font_names: &font_names,
+                },
+                cx,
+            );
+            let entries = setting_entries::setting_entries(&schema);
+
+            let subscriptions = vec![
+                cx.subscribe(&search_editor, |this, _, event: &EditorEvent, cx| {
+                    if matches!(event, EditorEvent::BufferEdited) {
+                        this.update_matches(cx);
+                    }
+                }),
+                cx.subscribe(&value_editor, |this, _, event: &EditorEvent, cx| {
+                    if matches!(event, EditorEvent::Blurred) {
+                        this.editing = None;
+                        cx.notify();
+                    }
+                }),
+                cx.observe_global::<SettingsStore>(|this, cx| {
+                    this.update_project_settings(cx);
+                    cx.notify();
+                }),
+            ];
+
+            let mut this = Self {
+                focus_handle: cx.focus_handle(),
+                project,
+                fs,
+                search_editor,
+                value_editor,
+                entries,
+                matches: Vec::new(),
+                editing: None,
+                project_settings: Vec::new(),
+                list_state: ListState::new(0, ListAlignment::Top, px(1000.)),
+                _subscriptions: subscriptions,
+            };
+            this.update_project_settings(cx);
+            this.update_matches(cx);
+            this
         })
     }
+
+    fn query(&self, cx: &App) -> String {
+        self.search_editor.read(cx).text(cx)
+    }
+
+    fn update_matches(&mut self, cx: &mut Context<Self>) {
+        let query = self.query(cx);
+        self.matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.matches(&query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.list_state.reset(self.list_len(cx));
+        cx.notify();
+    }
+
+    /// The list starts with the common settings when not searching.
+    fn shows_common_settings(&self, cx: &App) -> bool {
+        self.query(cx).trim().is_empty()
+    }
+
+    fn list_len(&self, cx: &App) -> usize {
+        self.matches.len() + self.shows_common_settings(cx) as usize
+    }
+
+    fn update_project_settings(&mut self, cx: &mut Context<Self>) {
+        let store = cx.global::<SettingsStore>();
+        self.project_settings = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .filter_map(|worktree| {
+                let worktree = worktree.read(cx);
+                let worktree_id = settings::WorktreeId::from_usize(worktree.id().to_usize());
+                let (_, content) = store
+                    .local_settings(worktree_id)
+                    .find(|(path, _)| path.as_os_str().is_empty())?;
+                let content = serde_json::from_str(&content).ok()?;
+                Some((
+                    SharedString::from(worktree.root_name().to_string()),
+                    content,
+                ))
+            })
+            .collect();
+    }
+
+    fn setting_values(&self, entry: &SettingEntry, cx: &App) -> SettingValues {
+        let store = cx.global::<SettingsStore>();
+        let mut layers = vec![
+            (SettingsLayer::Default, store.raw_default_settings()),
+            (SettingsLayer::User, store.raw_user_settings()),
+        ];
+        layers.extend(
+            self.project_settings
+                .iter()
+                .map(|(name, content)| (SettingsLayer::Project(name.clone()), content)),
+        );
+        SettingValues::new(&layers, &entry.key_path)
+    }
+
+    fn set_user_value(&self, key_path: Vec<String>, value: Option<Value>, cx: &App) {
+        cx.global::<SettingsStore>()
+            .update_settings_file_at_key_path(self.fs.clone(), key_path, value);
+    }
+
+    fn start_editing(&mut self, entry_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let values = self.setting_values(&self.entries[entry_ix], cx);
+        let text = values
+            .effective()
+            .map(|(_, value)| display_value(value))
+            .unwrap_or_default();
+        self.editing = Some(entry_ix);
+        self.value_editor.update(cx, |editor, cx| {
+            editor.set_text(text, window, cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+        });
+        window.focus(&self.value_editor.focus_handle(cx));
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry_ix) = self.editing else {
+            return;
+        };
+        let entry = &self.entries[entry_ix];
+        let text = self.value_editor.read(cx).text(cx);
+        let value = match entry.kind {
+            SettingKind::Integer => text.trim().parse::<i64>().ok().map(Value::from),
+            SettingKind::Number => text
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number),
+            _ => Some(Value::String(text)),
+        };
+        // Keep editing values that don't parse.
+        let Some(value) = value else {
+            return;
+        };
+        self.set_user_value(entry.key_path.clone(), Some(value), cx);
+        self.editing = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn render_control(
+        &self,
+        entry_ix: usize,
+        values: &SettingValues,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let entry = &self.entries[entry_ix];
+        let current = values.effective().map(|(_, value)| value);
+        let key_path = entry.key_path.clone();
+        let fs = self.fs.clone();
+        match &entry.kind {
+            SettingKind::Boolean => Switch::new(
+                ("setting-switch", entry_ix),
+                current.and_then(Value::as_bool).unwrap_or_default().into(),
+            )
+            .on_click(move |state, _, cx| {
+                cx.global::<SettingsStore>()
+                    .update_settings_file_at_key_path(
+                        fs.clone(),
+                        key_path.clone(),
+                        Some(Value::Bool(*state == ToggleState::Selected)),
+                    );
+            })
+            .into_any_element(),
+            SettingKind::Enum(options) => DropdownMenu::new(
+                ("setting-dropdown", entry_ix),
+                current.map(display_value).unwrap_or_default(),
+                ContextMenu::build(window, cx, |mut menu, _, _| {
+                    for option in options {
+                        let fs = fs.clone();
+                        let key_path = key_path.clone();
+                        let value = Value::String(option.clone());
+                        menu = menu.entry(option.clone(), None, move |_, cx| {
+                            cx.global::<SettingsStore>()
+                                .update_settings_file_at_key_path(
+                                    fs.clone(),
+                                    key_path.clone(),
+                                    Some(value.clone()),
+                                );
+                        });
+                    }
+                    menu
+                }),
+            )
+            .into_any_element(),
+            SettingKind::Integer | SettingKind::Number | SettingKind::String => {
+                if self.editing == Some(entry_ix) {
+                    div()
+                        .w_64()
+                        .px_2()
+                        .py_1()
+                        .border_1()
+                        .border_color(cx.theme().colors().border_focused)
+                        .rounded_md()
+                        .child(self.value_editor.clone())
+                        .into_any_element()
+                } else {
+                    Button::new(
+                        ("setting-value", entry_ix),
+                        current
+                            .map(display_value)
+                            .unwrap_or_else(|| "Not set".into()),
+                    )
+                    .style(ButtonStyle::Outlined)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.start_editing(entry_ix, window, cx);
+                    }))
+                    .into_any_element()
+                }
+            }
+            SettingKind::Json => Button::new(("setting-json", entry_ix), "Edit in settings.json")
+                .style(ButtonStyle::Outlined)
+                .on_click(|_, window, cx| {
+                    window.dispatch_action(zed_actions::OpenSettings.boxed_clone(), cx);
+                })
+                .into_any_element(),
+        }
+    }
+
+    fn render_entry(&self, entry_ix: usize, window: &mut Window, cx: &mut Context<Self>) -> Div {
+        let entry = &self.entries[entry_ix];
+        let values = self.setting_values(entry, cx);
+        let is_set_by_user = values.value_in(&SettingsLayer::User).is_some();
+        let key_path = entry.key_path.clone();
+
+        v_flex()
+            .px_4()
+            .py_2()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_4()
+                    .justify_between()
+                    .child(
+                        v_flex().child(Label::new(entry.title.clone())).child(
+                            Label::new(entry.key())
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted)
+                                .buffer_font(cx),
+                        ),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .when(is_set_by_user, |this| {
+                                this.child(
+                                    IconButton::new(
+                                        ("reset-setting", entry_ix),
+                                        IconName::RotateCcw,
+                                    )
+                                    .icon_size(IconSize::Small)
+                                    .tooltip(Tooltip::text("Remove from User Settings"))
+                                    .on_click(cx.listener(
+                                        move |this, _, _, cx| {
+                                            this.set_user_value(key_path.clone(), None, cx);
+                                        },
+                                    )),
+                                )
+                            })
+                            .child(self.render_control(entry_ix, &values, window, cx)),
+                    ),
+            )
+            .when_some(entry.description.clone(), |this, description| {
+                this.child(
+                    Label::new(description)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .child(render_layers(&values))
+    }
+
+    fn render_common_settings(&self, cx: &mut Context<Self>) -> Div {
+        v_flex()
+            .p_4()
+            .gap_4()
+            .child(
+                v_flex().gap_1().child(Label::new("Appearance")).child(
+                    v_flex()
+                        .elevation_2(cx)
+                        .child(AppearanceSettingsControls::new()),
+                ),
+            )
+            .child(
+                v_flex().gap_1().child(Label::new("Editor")).child(
+                    v_flex()
+                        .elevation_2(cx)
+                        .child(EditorSettingsControls::new()),
+                ),
+            )
+    }
+}
+
+/// Lists where the setting's values come from, highlighting the one in effect.
+fn render_layers(values: &SettingValues) -> impl IntoElement {
+    let effective_ix = values.values.len().checked_sub(1);
+    h_flex()
+        .flex_wrap()
+        .gap_x_3()
+        .children(
+            values
+                .values
+                .iter()
+                .enumerate()
+                .map(|(ix, (layer, value))| {
+                    let label = Label::new(format!("{}: {}", layer.label(), display_value(value)))
+                        .size(LabelSize::XSmall);
+                    if Some(ix) == effective_ix {
+                        label.color(Color::Accent)
+                    } else {
+                        label.color(Color::Muted).strikethrough()
+                    }
+                }),
+        )
+        .children(
+            values
+                .language_overrides
+                .iter()
+                .map(|(layer, language, value)| {
+                    Label::new(format!(
+                        "{language} ({}): {}",
+                        layer.label(),
+                        display_value(value)
+                    ))
+                    .size(LabelSize::XSmall)
+                    .color(Color::Muted)
+                }),
+        )
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
 }
 
 impl EventEmitter<ItemEvent> for SettingsPage {}
@@ -122,23 +509,49 @@ impl Item for SettingsPage {
 impl Render for SettingsPage {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         v_flex()
-            .p_4()
+            .key_context("SettingsPage")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::confirm))
             .size_full()
-            .gap_4()
-            .child(Label::new("Settings").size(LabelSize::Large))
             .child(
-                v_flex().gap_1().child(Label::new("Appearance")).child(
-                    v_flex()
-                        .elevation_2(cx)
-                        .child(AppearanceSettingsControls::new()),
-                ),
+                v_flex()
+                    .p_4()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Settings").size(LabelSize::Large))
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .border_1()
+                            .border_color(cx.theme().colors().border)
+                            .rounded_lg()
+                            .child(self.search_editor.clone()),
+                    ),
             )
             .child(
-                v_flex().gap_1().child(Label::new("Editor")).child(
-                    v_flex()
-                        .elevation_2(cx)
-                        .child(EditorSettingsControls::new()),
-                ),
+                list(
+                    self.list_state.clone(),
+                    cx.processor(|this, ix, window, cx| {
+                        let ix = if this.shows_common_settings(cx) {
+                            if ix == 0 {
+                                return this.render_common_settings(cx).into_any_element();
+                            }
+                            ix - 1
+                        } else {
+                            ix
+                        };
+                        match this.matches.get(ix) {
+                            Some(&entry_ix) => {
+                                this.render_entry(entry_ix, window, cx).into_any_element()
+                            }
+                            None => div().into_any_element(),
+                        }
+                    }),
+                )
+                .flex_1()
+                .size_full(),
             )
     }
 }
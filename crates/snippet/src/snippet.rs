@@ -1,6 +1,52 @@
 use anyhow::{Context as _, Result};
+use regex::{Regex, RegexBuilder};
 use smallvec::SmallVec;
-use std::{collections::BTreeMap, ops::Range};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+};
+
+/// The variables defined by the LSP and TextMate snippet grammars.
+///
+/// A known variable without a value is replaced by its default, or removed.
+/// Any other variable is turned into a placeholder containing its name.
+pub const KNOWN_VARIABLES: &[&str] = &[
+    "TM_SELECTED_TEXT",
+    "SELECTION",
+    "TM_CURRENT_LINE",
+    "TM_CURRENT_WORD",
+    "TM_LINE_INDEX",
+    "TM_LINE_NUMBER",
+    "TM_FILENAME",
+    "TM_FILENAME_BASE",
+    "TM_DIRECTORY",
+    "TM_FILEPATH",
+    "RELATIVE_FILEPATH",
+    "CLIPBOARD",
+    "WORKSPACE_NAME",
+    "WORKSPACE_FOLDER",
+    "CURSOR_INDEX",
+    "CURSOR_NUMBER",
+    "CURRENT_YEAR",
+    "CURRENT_YEAR_SHORT",
+    "CURRENT_MONTH",
+    "CURRENT_MONTH_NAME",
+    "CURRENT_MONTH_NAME_SHORT",
+    "CURRENT_DATE",
+    "CURRENT_DAY_NAME",
+    "CURRENT_DAY_NAME_SHORT",
+    "CURRENT_HOUR",
+    "CURRENT_MINUTE",
+    "CURRENT_SECOND",
+    "CURRENT_SECONDS_UNIX",
+    "CURRENT_TIMEZONE_OFFSET",
+    "RANDOM",
+    "RANDOM_HEX",
+    "UUID",
+    "BLOCK_COMMENT_START",
+    "BLOCK_COMMENT_END",
+    "LINE_COMMENT",
+];
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Snippet {
@@ -12,15 +58,102 @@ pub struct Snippet {
 pub struct TabStop {
     pub ranges: SmallVec<[Range<isize>; 2]>,
     pub choices: Option<Vec<String>>,
+    /// Ranges whose text is derived from this tabstop's text, and which should
+    /// be updated as the tabstop is edited.
+    pub transforms: Vec<TransformedRange>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransformedRange {
+    pub range: Range<isize>,
+    pub transform: Transform,
+}
+
+/// A regex substitution such as `/(.*)/${1:/upcase}/g`.
+#[derive(Clone, Debug)]
+pub struct Transform {
+    regex: Regex,
+    format: Vec<FormatItem>,
+    options: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FormatItem {
+    Text(String),
+    Group { index: usize, format: GroupFormat },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum GroupFormat {
+    Value,
+    Upcase,
+    Downcase,
+    Capitalize,
+    CamelCase,
+    PascalCase,
+    /// `${1:+if}`, `${1:?if:else}`, `${1:-else}` and `${1:else}`. When no
+    /// `if` text is given, a non-empty group is inserted as is.
+    Conditional {
+        if_matched: Option<String>,
+        otherwise: String,
+    },
+}
+
+enum Node {
+    Text(String),
+    Tabstop {
+        index: usize,
+        content: Content,
+    },
+    /// A variable that isn't in [`KNOWN_VARIABLES`], along with its default.
+    UnknownVariable {
+        name: String,
+        default: Vec<Node>,
+    },
+}
+
+enum Content {
+    Empty,
+    Placeholder(Vec<Node>),
+    Choice(Vec<String>),
+    Transform(Transform),
 }
 
 impl Snippet {
     pub fn parse(source: &str) -> Result<Self> {
-        let mut text = String::with_capacity(source.len());
-        let mut tabstops = BTreeMap::new();
-        parse_snippet(source, false, &mut text, &mut tabstops)
+        Self::parse_with_variables(source, |_| None)
+    }
+
+    /// Parses a snippet, replacing variables such as `$TM_FILENAME` with the
+    /// values returned by `variables`.
+    pub fn parse_with_variables(
+        source: &str,
+        variables: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let parser = Parser {
+            variables: &variables,
+        };
+        let (mut nodes, _) = parser
+            .parse_snippet(source, false)
             .context("failed to parse snippet")?;
 
+        let mut next_index = max_tabstop_index(&nodes).map_or(1, |index| index + 1);
+        assign_variable_tabstops(&mut nodes, &mut next_index, &mut HashMap::default());
+
+        let mut definitions = HashMap::default();
+        collect_definitions(&nodes, &mut definitions);
+        let mut renderer = Renderer {
+            definitions,
+            resolving: Vec::new(),
+            text: String::with_capacity(source.len()),
+            tabstops: BTreeMap::new(),
+        };
+        renderer.render(&nodes);
+        let text = renderer.text;
+        let mut tabstops = renderer.tabstops;
+        // Tabstops that only appear in transforms can't be edited.
+        tabstops.retain(|_, tabstop| !tabstop.ranges.is_empty());
+
         let len = text.len() as isize;
         let final_tabstop = tabstops.remove(&0);
         let mut tabstops = tabstops.into_values().collect::<Vec<_>>();
@@ -30,7 +163,7 @@ impl Snippet {
         } else {
             let end_tabstop = TabStop {
                 ranges: [len..len].into_iter().collect(),
-                choices: None,
+                ..Default::default()
             };
 
             if !tabstops.last().map_or(false, |t| *t == end_tabstop) {
@@ -42,95 +175,234 @@ impl Snippet {
     }
 }
 
-fn parse_snippet<'a>(
-    mut source: &'a str,
-    nested: bool,
-    text: &mut String,
-    tabstops: &mut BTreeMap<usize, TabStop>,
-) -> Result<&'a str> {
-    loop {
-        match source.chars().next() {
-            None => return Ok(""),
-            Some('$') => {
-                source = parse_tabstop(&source[1..], text, tabstops)?;
-            }
-            Some('\\') => {
-                // As specified in the LSP spec (`Grammar` section),
-                // backslashes can escape some characters:
-                // https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#snippet_syntax
-                source = &source[1..];
-                if let Some(c) = source.chars().next() {
-                    if c == '$' || c == '\\' || c == '}' {
-                        text.push(c);
-                        // All escapable characters are 1 byte long:
-                        source = &source[1..];
-                    } else {
-                        text.push('\\');
+impl Transform {
+    /// Replaces the first match of the regex in `value`, or every match if
+    /// the `g` option was given.
+    pub fn apply(&self, value: &str) -> String {
+        let global = self.options.contains('g');
+        let mut result = String::with_capacity(value.len());
+        let mut last_end = 0;
+        for captures in self.regex.captures_iter(value) {
+            let Some(whole) = captures.get(0) else {
+                continue;
+            };
+            result.push_str(&value[last_end..whole.start()]);
+            for item in &self.format {
+                match item {
+                    FormatItem::Text(text) => result.push_str(text),
+                    FormatItem::Group { index, format } => {
+                        let group = captures.get(*index).map_or("", |group| group.as_str());
+                        format.apply(group, &mut result);
                     }
-                } else {
-                    text.push('\\');
                 }
             }
-            Some('}') => {
-                if nested {
-                    return Ok(source);
-                } else {
-                    text.push('}');
-                    source = &source[1..];
+            last_end = whole.end();
+            if !global {
+                break;
+            }
+        }
+        result.push_str(&value[last_end..]);
+        result
+    }
+}
+
+impl PartialEq for Transform {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+            && self.format == other.format
+            && self.options == other.options
+    }
+}
+
+impl GroupFormat {
+    fn apply(&self, group: &str, result: &mut String) {
+        match self {
+            GroupFormat::Value => result.push_str(group),
+            GroupFormat::Upcase => result.push_str(&group.to_uppercase()),
+            GroupFormat::Downcase => result.push_str(&group.to_lowercase()),
+            GroupFormat::Capitalize => result.push_str(&capitalize(group)),
+            GroupFormat::CamelCase | GroupFormat::PascalCase => {
+                let mut words = group
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|word| !word.is_empty())
+                    .peekable();
+                if words.peek().is_none() {
+                    result.push_str(group);
+                    return;
+                }
+                if *self == GroupFormat::CamelCase
+                    && let Some(first) = words.next()
+                {
+                    let mut chars = first.chars();
+                    result.extend(chars.next().into_iter().flat_map(char::to_lowercase));
+                    result.push_str(chars.as_str());
+                }
+                for word in words {
+                    result.push_str(&capitalize(word));
                 }
             }
-            Some(_) => {
-                let chunk_end = source.find(['}', '$', '\\']).unwrap_or(source.len());
-                let (chunk, rest) = source.split_at(chunk_end);
-                text.push_str(chunk);
-                source = rest;
+            GroupFormat::Conditional {
+                if_matched,
+                otherwise,
+            } => {
+                if group.is_empty() {
+                    result.push_str(otherwise);
+                } else {
+                    result.push_str(if_matched.as_deref().unwrap_or(group));
+                }
             }
         }
     }
 }
 
-fn parse_tabstop<'a>(
-    mut source: &'a str,
-    text: &mut String,
-    tabstops: &mut BTreeMap<usize, TabStop>,
-) -> Result<&'a str> {
-    let tabstop_start = text.len();
-    let tabstop_index;
-    let mut choices = None;
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .into_iter()
+        .flat_map(char::to_uppercase)
+        .chain(chars)
+        .collect()
+}
 
-    if source.starts_with('{') {
-        let (index, rest) = parse_int(&source[1..])?;
-        tabstop_index = index;
-        source = rest;
+struct Parser<'a> {
+    variables: &'a dyn Fn(&str) -> Option<String>,
+}
 
-        if source.starts_with("|") {
-            (source, choices) = parse_choices(&source[1..], text)?;
+impl Parser<'_> {
+    fn parse_snippet<'a>(&self, mut source: &'a str, nested: bool) -> Result<(Vec<Node>, &'a str)> {
+        let mut nodes = Vec::new();
+        loop {
+            match source.chars().next() {
+                None => return Ok((nodes, "")),
+                Some('$') => {
+                    source = self.parse_dollar(&source[1..], &mut nodes)?;
+                }
+                Some('\\') => {
+                    // As specified in the LSP spec (`Grammar` section),
+                    // backslashes can escape some characters:
+                    // https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#snippet_syntax
+                    source = &source[1..];
+                    if let Some(c) = source.chars().next() {
+                        if c == '$' || c == '\\' || c == '}' {
+                            push_text(&mut nodes, &source[..1]);
+                            // All escapable characters are 1 byte long:
+                            source = &source[1..];
+                        } else {
+                            push_text(&mut nodes, "\\");
+                        }
+                    } else {
+                        push_text(&mut nodes, "\\");
+                    }
+                }
+                Some('}') => {
+                    if nested {
+                        return Ok((nodes, source));
+                    } else {
+                        push_text(&mut nodes, "}");
+                        source = &source[1..];
+                    }
+                }
+                Some(_) => {
+                    let chunk_end = source.find(['}', '$', '\\']).unwrap_or(source.len());
+                    let (chunk, rest) = source.split_at(chunk_end);
+                    push_text(&mut nodes, chunk);
+                    source = rest;
+                }
+            }
         }
+    }
 
-        if source.starts_with(':') {
-            source = parse_snippet(&source[1..], true, text, tabstops)?;
+    /// Parses what follows a `$`. A `$` that doesn't start a tabstop or a
+    /// variable is kept as text.
+    fn parse_dollar<'a>(&self, source: &'a str, nodes: &mut Vec<Node>) -> Result<&'a str> {
+        if let Some(rest) = source.strip_prefix('{') {
+            if rest.starts_with(|c: char| c.is_ascii_digit()) {
+                let (index, rest) = parse_int(rest)?;
+                let (content, rest) = self.parse_tabstop_content(rest)?;
+                nodes.push(Node::Tabstop { index, content });
+                expect_closing_brace(rest)
+            } else if let Some((name, rest)) = parse_variable_name(rest) {
+                let (default, transform, rest) = if let Some(rest) = rest.strip_prefix(':') {
+                    let (default, rest) = self.parse_snippet(rest, true)?;
+                    (Some(default), None, rest)
+                } else if let Some(rest) = rest.strip_prefix('/') {
+                    let (transform, rest) = parse_transform(rest)?;
+                    (None, Some(transform), rest)
+                } else {
+                    (None, None, rest)
+                };
+                self.push_variable(name, default, transform, nodes);
+                expect_closing_brace(rest)
+            } else {
+                push_text(nodes, "${");
+                Ok(rest)
+            }
+        } else if source.starts_with(|c: char| c.is_ascii_digit()) {
+            let (index, rest) = parse_int(source)?;
+            nodes.push(Node::Tabstop {
+                index,
+                content: Content::Empty,
+            });
+            Ok(rest)
+        } else if let Some((name, rest)) = parse_variable_name(source) {
+            self.push_variable(name, None, None, nodes);
+            Ok(rest)
+        } else {
+            push_text(nodes, "$");
+            Ok(source)
         }
+    }
 
-        if source.starts_with('}') {
-            source = &source[1..];
+    fn parse_tabstop_content<'a>(&self, source: &'a str) -> Result<(Content, &'a str)> {
+        if let Some(rest) = source.strip_prefix('|') {
+            let (rest, choices) = parse_choices(rest)?;
+            Ok((Content::Choice(choices), rest))
+        } else if let Some(rest) = source.strip_prefix(':') {
+            let (children, rest) = self.parse_snippet(rest, true)?;
+            Ok((Content::Placeholder(children), rest))
+        } else if let Some(rest) = source.strip_prefix('/') {
+            let (transform, rest) = parse_transform(rest)?;
+            Ok((Content::Transform(transform), rest))
         } else {
-            anyhow::bail!("expected a closing brace");
+            Ok((Content::Empty, source))
         }
+    }
+
+    fn push_variable(
+        &self,
+        name: &str,
+        default: Option<Vec<Node>>,
+        transform: Option<Transform>,
+        nodes: &mut Vec<Node>,
+    ) {
+        if let Some(value) = (self.variables)(name) {
+            let value = match transform {
+                Some(transform) => transform.apply(&value),
+                None => value,
+            };
+            push_text(nodes, &value);
+        } else if KNOWN_VARIABLES.contains(&name) {
+            nodes.extend(default.unwrap_or_default());
+        } else {
+            nodes.push(Node::UnknownVariable {
+                name: name.to_string(),
+                default: default.unwrap_or_default(),
+            });
+        }
+    }
+}
+
+fn push_text(nodes: &mut Vec<Node>, text: &str) {
+    if let Some(Node::Text(last)) = nodes.last_mut() {
+        last.push_str(text);
     } else {
-        let (index, rest) = parse_int(source)?;
-        tabstop_index = index;
-        source = rest;
+        nodes.push(Node::Text(text.to_string()));
     }
+}
 
-    tabstops
-        .entry(tabstop_index)
-        .or_insert_with(|| TabStop {
-            ranges: Default::default(),
-            choices,
-        })
-        .ranges
-        .push(tabstop_start as isize..text.len() as isize);
-    Ok(source)
+fn expect_closing_brace(source: &str) -> Result<&str> {
+    source.strip_prefix('}').context("expected a closing brace")
 }
 
 fn parse_int(source: &str) -> Result<(usize, &str)> {
@@ -142,30 +414,32 @@ fn parse_int(source: &str) -> Result<(usize, &str)> {
     Ok((prefix.parse()?, suffix))
 }
 
-fn parse_choices<'a>(
-    mut source: &'a str,
-    text: &mut String,
-) -> Result<(&'a str, Option<Vec<String>>)> {
-    let mut found_default_choice = false;
+fn parse_variable_name(source: &str) -> Option<(&str, &str)> {
+    if !source.starts_with(|c: char| c == '_' || c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let len = source
+        .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+        .unwrap_or(source.len());
+    Some(source.split_at(len))
+}
+
+fn parse_choices(mut source: &str) -> Result<(&str, Vec<String>)> {
     let mut current_choice = String::new();
     let mut choices = Vec::new();
 
     loop {
         match source.chars().next() {
-            None => return Ok(("", Some(choices))),
+            None => return Ok(("", choices)),
             Some('\\') => {
                 source = &source[1..];
 
                 if let Some(c) = source.chars().next() {
-                    if !found_default_choice {
-                        current_choice.push(c);
-                        text.push(c);
-                    }
+                    current_choice.push(c);
                     source = &source[c.len_utf8()..];
                 }
             }
             Some(',') => {
-                found_default_choice = true;
                 source = &source[1..];
                 choices.push(current_choice);
                 current_choice = String::new();
@@ -173,7 +447,7 @@ fn parse_choices<'a>(
             Some('|') => {
                 source = &source[1..];
                 choices.push(current_choice);
-                return Ok((source, Some(choices)));
+                return Ok((source, choices));
             }
             Some(_) => {
                 let chunk_end = source.find([',', '|', '\\']);
@@ -184,16 +458,349 @@ fn parse_choices<'a>(
                 );
 
                 let (chunk, rest) = source.split_at(chunk_end.unwrap());
+                current_choice.push_str(chunk);
+                source = rest;
+            }
+        }
+    }
+}
 
-                if !found_default_choice {
-                    text.push_str(chunk);
-                }
+/// Parses the part of a transform that follows its first `/`, up to the
+/// closing brace.
+fn parse_transform(source: &str) -> Result<(Transform, &str)> {
+    let (pattern, source) = parse_regex(source)?;
+    let (format, source) = parse_format(source)?;
+    let options_end = source.find('}').context("expected a closing brace")?;
+    let (options, source) = source.split_at(options_end);
 
-                current_choice.push_str(chunk);
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(options.contains('i'))
+        .multi_line(options.contains('m'))
+        .dot_matches_new_line(options.contains('s'))
+        .build()
+        .with_context(|| format!("invalid regex in snippet transform: {pattern}"))?;
+    Ok((
+        Transform {
+            regex,
+            format,
+            options: options.to_string(),
+        },
+        source,
+    ))
+}
+
+/// Parses a regex up to the next unescaped `/`. Only `\/` is unescaped, as
+/// other escapes belong to the regex.
+fn parse_regex(mut source: &str) -> Result<(String, &str)> {
+    let mut pattern = String::new();
+    loop {
+        let chunk_end = source
+            .find(['/', '\\'])
+            .context("expected a '/' after the transform's regex")?;
+        pattern.push_str(&source[..chunk_end]);
+        source = &source[chunk_end..];
+        if let Some(rest) = source.strip_prefix('/') {
+            return Ok((pattern, rest));
+        } else if let Some(rest) = source.strip_prefix("\\/") {
+            pattern.push('/');
+            source = rest;
+        } else {
+            let escape_len = source[1..].chars().next().map_or(1, |c| 1 + c.len_utf8());
+            pattern.push_str(&source[..escape_len]);
+            source = &source[escape_len..];
+        }
+    }
+}
+
+/// Parses a transform's format string up to the next unescaped `/`.
+fn parse_format(mut source: &str) -> Result<(Vec<FormatItem>, &str)> {
+    let mut items = Vec::new();
+    let mut text = String::new();
+    loop {
+        match source.chars().next() {
+            None => anyhow::bail!("expected a '/' after the transform's format"),
+            Some('/') => {
+                if !text.is_empty() {
+                    items.push(FormatItem::Text(text));
+                }
+                return Ok((items, &source[1..]));
+            }
+            Some('\\') => {
+                source = push_escaped(&source[1..], &['/', '$', '\\'], &mut text);
+            }
+            Some('$') => {
+                let Some((item, rest)) = parse_format_group(&source[1..])? else {
+                    text.push('$');
+                    source = &source[1..];
+                    continue;
+                };
+                if !text.is_empty() {
+                    items.push(FormatItem::Text(std::mem::take(&mut text)));
+                }
+                items.push(item);
                 source = rest;
             }
+            Some(_) => {
+                let chunk_end = source.find(['/', '\\', '$']).unwrap_or(source.len());
+                text.push_str(&source[..chunk_end]);
+                source = &source[chunk_end..];
+            }
+        }
+    }
+}
+
+/// Parses a reference to a capture group, such as `$1` or `${1:/upcase}`,
+/// that follows a `$`.
+fn parse_format_group(source: &str) -> Result<Option<(FormatItem, &str)>> {
+    if source.starts_with(|c: char| c.is_ascii_digit()) {
+        let (index, rest) = parse_int(source)?;
+        let format = GroupFormat::Value;
+        return Ok(Some((FormatItem::Group { index, format }, rest)));
+    }
+    let Some(rest) = source.strip_prefix('{') else {
+        return Ok(None);
+    };
+    if !rest.starts_with(|c: char| c.is_ascii_digit()) {
+        return Ok(None);
+    }
+    let (index, rest) = parse_int(rest)?;
+    let (format, rest) = if let Some(rest) = rest.strip_prefix(":/") {
+        let name_end = rest.find('}').context("expected a closing brace")?;
+        let format = match &rest[..name_end] {
+            "upcase" => GroupFormat::Upcase,
+            "downcase" => GroupFormat::Downcase,
+            "capitalize" => GroupFormat::Capitalize,
+            "camelcase" => GroupFormat::CamelCase,
+            "pascalcase" => GroupFormat::PascalCase,
+            name => anyhow::bail!("unknown format in snippet transform: {name}"),
+        };
+        (format, &rest[name_end..])
+    } else if let Some(rest) = rest.strip_prefix(":+") {
+        let (if_matched, rest) = parse_format_text(rest, &['}'])?;
+        let format = GroupFormat::Conditional {
+            if_matched: Some(if_matched),
+            otherwise: String::new(),
+        };
+        (format, rest)
+    } else if let Some(rest) = rest.strip_prefix(":?") {
+        let (if_matched, rest) = parse_format_text(rest, &[':'])?;
+        let (otherwise, rest) = parse_format_text(&rest[1..], &['}'])?;
+        let format = GroupFormat::Conditional {
+            if_matched: Some(if_matched),
+            otherwise,
+        };
+        (format, rest)
+    } else if let Some(rest) = rest.strip_prefix(":-").or_else(|| rest.strip_prefix(':')) {
+        let (otherwise, rest) = parse_format_text(rest, &['}'])?;
+        let format = GroupFormat::Conditional {
+            if_matched: None,
+            otherwise,
+        };
+        (format, rest)
+    } else {
+        (GroupFormat::Value, rest)
+    };
+    Ok(Some((
+        FormatItem::Group { index, format },
+        expect_closing_brace(rest)?,
+    )))
+}
+
+/// Parses the text of a conditional format up to one of the terminators,
+/// which is left in the returned source.
+fn parse_format_text<'a>(mut source: &'a str, terminators: &[char]) -> Result<(String, &'a str)> {
+    let mut text = String::new();
+    loop {
+        match source.chars().next() {
+            None => anyhow::bail!("expected a closing brace"),
+            Some('\\') => {
+                source = push_escaped(&source[1..], &[':', '}', '\\', '/'], &mut text);
+            }
+            Some(c) if terminators.contains(&c) => return Ok((text, source)),
+            Some(c) => {
+                text.push(c);
+                source = &source[c.len_utf8()..];
+            }
+        }
+    }
+}
+
+/// Pushes the character following a backslash, keeping the backslash unless
+/// the character is escapable.
+fn push_escaped<'a>(source: &'a str, escapable: &[char], text: &mut String) -> &'a str {
+    match source.chars().next() {
+        Some(c) if escapable.contains(&c) => {
+            text.push(c);
+            &source[c.len_utf8()..]
+        }
+        _ => {
+            text.push('\\');
+            source
+        }
+    }
+}
+
+fn max_tabstop_index(nodes: &[Node]) -> Option<usize> {
+    nodes
+        .iter()
+        .filter_map(|node| match node {
+            Node::Text(_) => None,
+            Node::Tabstop { index, content } => {
+                let nested = match content {
+                    Content::Placeholder(children) => max_tabstop_index(children),
+                    _ => None,
+                };
+                Some(nested.map_or(*index, |nested| nested.max(*index)))
+            }
+            Node::UnknownVariable { default, .. } => max_tabstop_index(default),
+        })
+        .max()
+}
+
+/// Turns unknown variables into placeholders, numbered after the snippet's
+/// own tabstops. Variables with the same name share a tabstop.
+fn assign_variable_tabstops(
+    nodes: &mut [Node],
+    next_index: &mut usize,
+    indices: &mut HashMap<String, usize>,
+) {
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Tabstop { content, .. } => {
+                if let Content::Placeholder(children) = content {
+                    assign_variable_tabstops(children, next_index, indices);
+                }
+            }
+            Node::UnknownVariable { name, default } => {
+                let index = *indices.entry(name.clone()).or_insert_with(|| {
+                    *next_index += 1;
+                    *next_index - 1
+                });
+                let mut children = std::mem::take(default);
+                if children.is_empty() {
+                    children.push(Node::Text(std::mem::take(name)));
+                }
+                assign_variable_tabstops(&mut children, next_index, indices);
+                *node = Node::Tabstop {
+                    index,
+                    content: Content::Placeholder(children),
+                };
+            }
+        }
+    }
+}
+
+/// Finds the first placeholder or choice of each tabstop, which determines
+/// the text of the tabstop's mirrors and transforms.
+fn collect_definitions<'a>(nodes: &'a [Node], definitions: &mut HashMap<usize, &'a Content>) {
+    for node in nodes {
+        if let Node::Tabstop { index, content } = node {
+            if matches!(content, Content::Placeholder(_) | Content::Choice(_)) {
+                definitions.entry(*index).or_insert(content);
+            }
+            if let Content::Placeholder(children) = content {
+                collect_definitions(children, definitions);
+            }
+        }
+    }
+}
+
+struct Renderer<'a> {
+    definitions: HashMap<usize, &'a Content>,
+    /// The tabstops whose values are being computed, to break cycles such as
+    /// `${1:${1/a/b/}}`.
+    resolving: Vec<usize>,
+    text: String,
+    tabstops: BTreeMap<usize, TabStop>,
+}
+
+impl Renderer<'_> {
+    fn render(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            match node {
+                Node::Text(text) => self.text.push_str(text),
+                Node::Tabstop { index, content } => {
+                    let start = self.text.len() as isize;
+                    let choices = match content {
+                        Content::Empty => {
+                            let value = self.value(*index);
+                            self.text.push_str(&value);
+                            None
+                        }
+                        Content::Placeholder(children) => {
+                            self.render(children);
+                            None
+                        }
+                        Content::Choice(choices) => {
+                            if let Some(choice) = choices.first() {
+                                self.text.push_str(choice);
+                            }
+                            Some(choices.clone())
+                        }
+                        Content::Transform(transform) => {
+                            let value = transform.apply(&self.value(*index));
+                            self.text.push_str(&value);
+                            let range = start..self.text.len() as isize;
+                            self.tabstops.entry(*index).or_default().transforms.push(
+                                TransformedRange {
+                                    range,
+                                    transform: transform.clone(),
+                                },
+                            );
+                            continue;
+                        }
+                    };
+                    let tabstop = self.tabstops.entry(*index).or_default();
+                    if tabstop.choices.is_none() {
+                        tabstop.choices = choices;
+                    }
+                    tabstop.ranges.push(start..self.text.len() as isize);
+                }
+                Node::UnknownVariable { .. } => {
+                    debug_assert!(false, "variables should have been assigned tabstops");
+                }
+            }
         }
     }
+
+    /// The initial text of the given tabstop.
+    fn value(&mut self, index: usize) -> String {
+        if self.resolving.contains(&index) {
+            return String::new();
+        }
+        match self.definitions.get(&index).copied() {
+            Some(Content::Placeholder(children)) => {
+                self.resolving.push(index);
+                let value = self.plain_text(children);
+                self.resolving.pop();
+                value
+            }
+            Some(Content::Choice(choices)) => choices.first().cloned().unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    fn plain_text(&mut self, nodes: &[Node]) -> String {
+        let mut text = String::new();
+        for node in nodes {
+            match node {
+                Node::Text(chunk) => text.push_str(chunk),
+                Node::Tabstop { index, content } => match content {
+                    Content::Empty => text.push_str(&self.value(*index)),
+                    Content::Placeholder(children) => text.push_str(&self.plain_text(children)),
+                    Content::Choice(choices) => {
+                        text.push_str(choices.first().map_or("", String::as_str))
+                    }
+                    Content::Transform(transform) => {
+                        text.push_str(&transform.apply(&self.value(*index)))
+                    }
+                },
+                Node::UnknownVariable { .. } => {}
+            }
+        }
+        text
+    }
 }
 
 #[cfg(test)]
@@ -322,6 +929,106 @@ mod tests {
         let snippet = Snippet::parse("one\\\\$1two").unwrap();
         assert_eq!(snippet.text, "one\\two");
         assert_eq!(tabstops(&snippet), &[vec![4..4], vec![7..7]]);
+
+        // A dollar sign that doesn't start a tabstop or variable
+        let snippet = Snippet::parse("echo $ ${ -}").unwrap();
+        assert_eq!(snippet.text, "echo $ ${ -}");
+    }
+
+    #[test]
+    fn test_snippet_mirrors() {
+        let snippet = Snippet::parse("<${1:div}>$0</$1>").unwrap();
+        assert_eq!(snippet.text, "<div></div>");
+        assert_eq!(tabstops(&snippet), &[vec![1..4, 7..10], vec![5..5]]);
+    }
+
+    #[test]
+    fn test_snippet_with_variables() {
+        let variables = |name: &str| match name {
+            "TM_FILENAME" => Some("main.rs".to_string()),
+            "TM_SELECTED_TEXT" => Some("selection".to_string()),
+            _ => None,
+        };
+
+        let snippet =
+            Snippet::parse_with_variables("// $TM_FILENAME: ${TM_SELECTED_TEXT}$0", variables)
+                .unwrap();
+        assert_eq!(snippet.text, "// main.rs: selection");
+        assert_eq!(tabstops(&snippet), &[vec![21..21]]);
+
+        // Known variables without a value use their default, or are removed.
+        let snippet =
+            Snippet::parse_with_variables("${CLIPBOARD:${1:none}}-$CURRENT_YEAR-", variables)
+                .unwrap();
+        assert_eq!(snippet.text, "none--");
+        assert_eq!(tabstops(&snippet), &[vec![0..4], vec![6..6]]);
+
+        // Unknown variables become placeholders after the numbered tabstops.
+        let snippet = Snippet::parse_with_variables("$1 $FOO ${BAR:bar} $FOO", variables).unwrap();
+        assert_eq!(snippet.text, " FOO bar FOO");
+        assert_eq!(
+            tabstops(&snippet),
+            &[vec![0..0], vec![1..4, 9..12], vec![5..8], vec![12..12]]
+        );
+    }
+
+    #[test]
+    fn test_variable_transforms() {
+        let variables = |name: &str| match name {
+            "TM_FILENAME" => Some("snippet_parser.rs".to_string()),
+            _ => None,
+        };
+        let snippet = Snippet::parse_with_variables(
+            r"struct ${TM_FILENAME/(\w+)\..+$/${1:/pascalcase}/};",
+            variables,
+        )
+        .unwrap();
+        assert_eq!(snippet.text, "struct SnippetParser;");
+
+        let snippet = Snippet::parse_with_variables(
+            r"${TM_FILENAME/(parser)|(\.rs)/${1:+P}${2:?.RS:none}/g}",
+            variables,
+        )
+        .unwrap();
+        assert_eq!(snippet.text, "snippet_Pnone.RS");
+    }
+
+    #[test]
+    fn test_tabstop_transforms() {
+        let snippet = Snippet::parse("${1:hello} ${1/(.*)/${1:/upcase}/} $0").unwrap();
+        assert_eq!(snippet.text, "hello HELLO ");
+        assert_eq!(tabstops(&snippet), &[vec![0..5], vec![12..12]]);
+
+        let transforms = &snippet.tabstops[0].transforms;
+        assert_eq!(transforms.len(), 1);
+        assert_eq!(transforms[0].range, 6..11);
+        assert_eq!(transforms[0].transform.apply("goodbye"), "GOODBYE");
+
+        // Transforms are evaluated even when they precede the placeholder.
+        let snippet = Snippet::parse(r"${1/\//./g} ${1:a/b/c}").unwrap();
+        assert_eq!(snippet.text, "a.b.c a/b/c");
+        assert_eq!(snippet.tabstops[0].transforms[0].range, 0..5);
+    }
+
+    #[test]
+    fn test_transform_formats() {
+        let transform = |source: &str| {
+            let snippet = Snippet::parse(&format!("${{1:x}}${{1{source}}}")).unwrap();
+            snippet.tabstops[0].transforms[0].transform.clone()
+        };
+
+        assert_eq!(transform("/(.*)/${1:/downcase}/").apply("ABC"), "abc");
+        assert_eq!(transform("/(.*)/${1:/capitalize}/").apply("abc"), "Abc");
+        assert_eq!(
+            transform("/(.*)/${1:/camelcase}/").apply("foo-bar baz"),
+            "fooBarBaz"
+        );
+        assert_eq!(transform("/(.*)/${1:-default}/").apply(""), "default");
+        assert_eq!(transform("/(.+)/${1:default}/").apply("value"), "value");
+        assert_eq!(transform("/a/b/").apply("aaa"), "baa");
+        assert_eq!(transform("/a/b/g").apply("aaa"), "bbb");
+        assert_eq!(transform("/A/b/gi").apply("aAa"), "bbb");
+        assert_eq!(transform(r"/(\w)/\$$1\//g").apply("ab"), "$a/$b/");
     }
 
     fn tabstops(snippet: &Snippet) -> Vec<Vec<Range<isize>>> {
@@ -70,7 +70,7 @@ pub(crate) struct VsCodeSnippet {
     /// The snippet prefix used to decide whether a completion menu should be shown.
     pub(crate) prefix: Option<ListOrDirect>,
 
-    /// The snippet content. Use `$1` and `${1:defaultText}` to define cursor positions and `$0` for final cursor position.
+    /// The snippet content. Use `$1` and `${1:defaultText}` to define cursor positions and `$0` for final cursor position. Variables such as `$TM_FILENAME` and transforms such as `${1/(.*)/${1:/upcase}/}` are supported.
     pub(crate) body: ListOrDirect,
 
     /// The snippet description displayed inside the completion menu.
@@ -689,6 +689,10 @@ pub struct TaskState {
     pub full_label: String,
     pub label: String,
     pub command_label: String,
+    /// The program the task's terminal was spawned with.
+    pub command: Option<String>,
+    /// The arguments passed to `command`.
+    pub args: Vec<String>,
     pub status: TaskStatus,
     pub completion_rx: Receiver<Option<ExitStatus>>,
     pub hide: HideStrategy,
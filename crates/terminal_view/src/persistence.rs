@@ -6,6 +6,8 @@ use gpui::{AppContext as _, AsyncWindowContext, Axis, Entity, Task, WeakEntity};
 use project::{Project, terminals::TerminalKind};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use task::{SpawnInTerminal, TaskId};
+use terminal::Terminal;
 use ui::{App, Context, Pixels, Window};
 use util::ResultExt as _;
 
@@ -83,6 +85,195 @@ fn serialize_pane(pane: &Entity<Pane>, active: bool, cx: &mut App) -> Serialized
     }
 }
 
+pub(crate) fn serialize_terminal_layout(
+    pane_group: &PaneGroup,
+    cx: &App,
+) -> SerializedTerminalLayout {
+    build_serialized_terminal_layout(&pane_group.root, cx)
+}
+
+fn build_serialized_terminal_layout(member: &Member, cx: &App) -> SerializedTerminalLayout {
+    match member {
+        Member::Axis(PaneAxis {
+            axis,
+            members,
+            flexes,
+            bounding_boxes: _,
+        }) => SerializedTerminalLayout::Group {
+            axis: SerializedAxis(*axis),
+            children: members
+                .iter()
+                .map(|member| build_serialized_terminal_layout(member, cx))
+                .collect(),
+            flexes: Some(flexes.lock().clone()),
+        },
+        Member::Pane(pane) => SerializedTerminalLayout::Pane(
+            pane.read(cx)
+                .items()
+                .filter_map(|item| item.downcast::<TerminalView>())
+                .map(|terminal_view| serialize_layout_terminal(terminal_view.read(cx), cx))
+                .collect(),
+        ),
+    }
+}
+
+fn serialize_layout_terminal(terminal_view: &TerminalView, cx: &App) -> SerializedLayoutTerminal {
+    let terminal = terminal_view.terminal().read(cx);
+    let (command, task) = match terminal.task() {
+        Some(task) => (
+            None,
+            task.command.clone().map(|command| SerializedLayoutTask {
+                label: task.label.clone(),
+                command,
+                args: task.args.clone(),
+            }),
+        ),
+        None => (foreground_command(terminal), None),
+    };
+    SerializedLayoutTerminal {
+        working_directory: terminal.working_directory(),
+        command,
+        task,
+        excluded_from_broadcast: terminal_view.is_excluded_from_broadcast(),
+    }
+}
+
+/// Returns the command line of the process running in the foreground of the terminal,
+/// unless that process is the shell the terminal was started with.
+fn foreground_command(terminal: &Terminal) -> Option<String> {
+    let shell_pid = terminal.pty_info.pid_getter().fallback_pid();
+    if terminal.pty_info.pid()?.as_u32() == shell_pid {
+        return None;
+    }
+    let argv = &terminal.pty_info.current.as_ref()?.argv;
+    if argv.is_empty() {
+        return None;
+    }
+    shlex::try_join(argv.iter().map(String::as_str)).ok()
+}
+
+#[async_recursion(?Send)]
+pub(crate) async fn deserialize_terminal_layout(
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    panel: Entity<TerminalPanel>,
+    workspace_id: Option<WorkspaceId>,
+    layout: &SerializedTerminalLayout,
+    cx: &mut AsyncWindowContext,
+) -> Option<Member> {
+    match layout {
+        SerializedTerminalLayout::Group {
+            axis,
+            flexes,
+            children,
+        } => {
+            let mut members = Vec::new();
+            for child in children {
+                if let Some(member) = deserialize_terminal_layout(
+                    workspace.clone(),
+                    project.clone(),
+                    panel.clone(),
+                    workspace_id,
+                    child,
+                    cx,
+                )
+                .await
+                {
+                    members.push(member);
+                }
+            }
+
+            if members.is_empty() {
+                return None;
+            }
+
+            if members.len() == 1 {
+                return Some(members.remove(0));
+            }
+
+            Some(Member::Axis(PaneAxis::load(
+                axis.0,
+                members,
+                flexes.clone(),
+            )))
+        }
+        SerializedTerminalLayout::Pane(terminals) => {
+            let pane = panel
+                .update_in(cx, |terminal_panel, window, cx| {
+                    let pane = new_terminal_pane(
+                        workspace.clone(),
+                        project.clone(),
+                        terminal_panel.active_pane.read(cx).is_zoomed(),
+                        window,
+                        cx,
+                    );
+                    terminal_panel.apply_tab_bar_buttons(&pane, cx);
+                    pane
+                })
+                .log_err()?;
+
+            for serialized_terminal in terminals {
+                let kind = match &serialized_terminal.task {
+                    Some(task) => TerminalKind::Task(SpawnInTerminal {
+                        id: TaskId(format!("terminal_layout_{}", task.label)),
+                        full_label: task.label.clone(),
+                        label: task.label.clone(),
+                        command: Some(task.command.clone()),
+                        args: task.args.clone(),
+                        command_label: shlex::try_join(
+                            std::iter::once(task.command.as_str())
+                                .chain(task.args.iter().map(String::as_str)),
+                        )
+                        .unwrap_or_else(|_| task.command.clone()),
+                        cwd: serialized_terminal.working_directory.clone(),
+                        use_new_terminal: true,
+                        show_summary: true,
+                        show_command: true,
+                        ..SpawnInTerminal::default()
+                    }),
+                    None => TerminalKind::Shell(serialized_terminal.working_directory.clone()),
+                };
+                let Some(terminal) = project
+                    .update(cx, |project, cx| project.create_terminal(kind, cx))
+                    .log_err()?
+                    .await
+                    .log_err()
+                else {
+                    continue;
+                };
+                if let Some(command) = &serialized_terminal.command {
+                    let input = format!("{command}\r");
+                    terminal
+                        .update(cx, |terminal, _| terminal.input(input.into_bytes()))
+                        .log_err();
+                }
+                let excluded_from_broadcast = serialized_terminal.excluded_from_broadcast;
+                pane.update_in(cx, |pane, window, cx| {
+                    let terminal_view = Box::new(cx.new(|cx| {
+                        let mut terminal_view = TerminalView::new(
+                            terminal,
+                            workspace.clone(),
+                            workspace_id,
+                            project.downgrade(),
+                            window,
+                            cx,
+                        );
+                        terminal_view.set_excluded_from_broadcast(excluded_from_broadcast, cx);
+                        terminal_view
+                    }));
+                    pane.add_item(terminal_view, true, false, None, window, cx);
+                })
+                .log_err()?;
+            }
+
+            if pane.read_with(cx, |pane, _| pane.items_len() == 0).ok()? {
+                return None;
+            }
+            Some(Member::Pane(pane))
+        }
+    }
+}
+
 pub(crate) fn deserialize_terminal_panel(
     workspace: WeakEntity<Workspace>,
     project: Entity<Project>,
@@ -344,6 +535,39 @@ pub(crate) struct SerializedPane {
     pub pinned_count: usize,
 }
 
+/// A named arrangement of split terminals that can be saved and restored independently
+/// of any workspace.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum SerializedTerminalLayout {
+    Pane(Vec<SerializedLayoutTerminal>),
+    Group {
+        axis: SerializedAxis,
+        flexes: Option<Vec<f32>>,
+        children: Vec<SerializedTerminalLayout>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SerializedLayoutTerminal {
+    pub working_directory: Option<PathBuf>,
+    /// The command line of the process that was running in the foreground, typed into the
+    /// restored terminal's shell.
+    pub command: Option<String>,
+    /// The task that was running in the terminal, which is spawned again on restore.
+    #[serde(default)]
+    pub task: Option<SerializedLayoutTask>,
+    #[serde(default)]
+    pub excluded_from_broadcast: bool,
+}
+
+/// The program and arguments a task terminal was spawned with.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SerializedLayoutTask {
+    pub label: String,
+    pub command: String,
+    pub args: Vec<String>,
+}
+
 #[derive(Debug)]
 pub(crate) struct SerializedAxis(pub Axis);
 
@@ -411,6 +635,12 @@ define_connection! {
             ALTER TABLE terminals ADD COLUMN working_directory_path TEXT;
             UPDATE terminals SET working_directory_path = CAST(working_directory AS TEXT);
         ),
+        sql!(
+            CREATE TABLE terminal_layouts (
+                name TEXT PRIMARY KEY NOT NULL,
+                layout TEXT NOT NULL
+            ) STRICT;
+        ),
     ];
 }
 
@@ -463,4 +693,19 @@ impl TerminalDb {
             WHERE item_id = ? AND workspace_id = ?
         }
     }
+
+    query! {
+        pub async fn save_layout(name: String, layout: String) -> Result<()> {
+            INSERT OR REPLACE INTO terminal_layouts(name, layout)
+            VALUES (?, ?)
+        }
+    }
+
+    query! {
+        pub fn get_layout(name: String) -> Result<Option<String>> {
+            SELECT layout
+            FROM terminal_layouts
+            WHERE name = ?
+        }
+    }
 }
@@ -3,7 +3,9 @@ use std::{cmp, ops::ControlFlow, path::PathBuf, process::ExitStatus, sync::Arc,
 use crate::{
     TerminalView, default_working_directory,
     persistence::{
-        SerializedItems, SerializedTerminalPanel, deserialize_terminal_panel, serialize_pane_group,
+        SerializedItems, SerializedTerminalLayout, SerializedTerminalPanel, TERMINAL_DB,
+        deserialize_terminal_layout, deserialize_terminal_panel, serialize_pane_group,
+        serialize_terminal_layout,
     },
 };
 use breadcrumbs::Breadcrumbs;
@@ -11,13 +13,15 @@ use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
 use futures::{channel::oneshot, future::join_all};
 use gpui::{
-    Action, AnyView, App, AsyncApp, AsyncWindowContext, Context, Corner, Entity, EventEmitter,
-    ExternalPaths, FocusHandle, Focusable, IntoElement, ParentElement, Pixels, Render, Styled,
-    Task, WeakEntity, Window, actions,
+    Action, AnyView, App, AsyncApp, AsyncWindowContext, Context, Corner, Entity, EntityId,
+    EventEmitter, ExternalPaths, FocusHandle, Focusable, IntoElement, ParentElement, Pixels,
+    Render, Styled, Task, WeakEntity, Window, actions,
 };
 use itertools::Itertools;
 use project::{Fs, Project, ProjectEntryId, terminals::TerminalKind};
+use schemars::JsonSchema;
 use search::{BufferSearchBar, buffer_search::DivRegistrar};
+use serde::Deserialize;
 use settings::Settings;
 use task::{RevealStrategy, RevealTarget, ShellBuilder, SpawnInTerminal, TaskId};
 use terminal::{
@@ -45,15 +49,37 @@ use anyhow::{Context as _, Result, anyhow};
 use zed_actions::assistant::InlineAssist;
 
 const TERMINAL_PANEL_KEY: &str = "TerminalPanel";
+const DEFAULT_LAYOUT_NAME: &str = "default";
 
 actions!(
     terminal_panel,
     [
         /// Toggles focus on the terminal panel.
-        ToggleFocus
+        ToggleFocus,
+        /// Toggles sending input typed into the focused terminal to every terminal in the panel.
+        ToggleBroadcastInput
     ]
 );
 
+/// Saves the split layout of the terminal panel, with each terminal's working directory
+/// and running command, under the given name.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Action)]
+#[action(namespace = terminal_panel)]
+#[serde(deny_unknown_fields)]
+pub struct SaveLayout {
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Replaces the terminals in the terminal panel with a previously saved layout.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Action)]
+#[action(namespace = terminal_panel)]
+#[serde(deny_unknown_fields)]
+pub struct RestoreLayout {
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
 pub fn init(cx: &mut App) {
     cx.observe_new(
         |workspace: &mut Workspace, _window, _: &mut Context<Workspace>| {
@@ -64,6 +90,15 @@ pub fn init(cx: &mut App) {
                     workspace.toggle_panel_focus::<TerminalPanel>(window, cx);
                 }
             });
+            workspace.register_action(|workspace, _: &ToggleBroadcastInput, _, cx| {
+                if let Some(terminal_panel) = workspace.panel::<TerminalPanel>(cx) {
+                    terminal_panel.update(cx, |terminal_panel, cx| {
+                        terminal_panel.toggle_broadcast_input(cx);
+                    });
+                }
+            });
+            workspace.register_action(TerminalPanel::save_layout);
+            workspace.register_action(TerminalPanel::restore_layout);
         },
     )
     .detach();
@@ -82,6 +117,7 @@ pub struct TerminalPanel {
     assistant_enabled: bool,
     assistant_tab_bar_button: Option<AnyView>,
     active: bool,
+    broadcast_input: bool,
 }
 
 impl TerminalPanel {
@@ -102,6 +138,7 @@ impl TerminalPanel {
             assistant_enabled: false,
             assistant_tab_bar_button: None,
             active: false,
+            broadcast_input: false,
         };
         terminal_panel.apply_tab_bar_buttons(&terminal_panel.active_pane, cx);
         terminal_panel
@@ -128,8 +165,13 @@ impl TerminalPanel {
         }
     }
 
-    fn apply_tab_bar_buttons(&self, terminal_pane: &Entity<Pane>, cx: &mut Context<Self>) {
+    pub(crate) fn apply_tab_bar_buttons(
+        &self,
+        terminal_pane: &Entity<Pane>,
+        cx: &mut Context<Self>,
+    ) {
         let assistant_tab_bar_button = self.assistant_tab_bar_button.clone();
+        let broadcast_input = self.broadcast_input;
         terminal_pane.update(cx, |pane, cx| {
             pane.set_render_tab_bar_buttons(cx, move |pane, window, cx| {
                 let split_context = pane
@@ -171,6 +213,27 @@ impl TerminalPanel {
                             }),
                     )
                     .children(assistant_tab_bar_button.clone())
+                    .child(
+                        IconButton::new("toggle_broadcast_input", IconName::Keyboard)
+                            .icon_size(IconSize::Small)
+                            .toggle_state(broadcast_input)
+                            .selected_icon_color(Color::Warning)
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(ToggleBroadcastInput.boxed_clone(), cx);
+                            })
+                            .tooltip(move |window, cx| {
+                                Tooltip::for_action(
+                                    if broadcast_input {
+                                        "Stop Broadcasting Input"
+                                    } else {
+                                        "Broadcast Input to All Terminals"
+                                    },
+                                    &ToggleBroadcastInput,
+                                    window,
+                                    cx,
+                                )
+                            }),
+                    )
                     .child(
                         PopoverMenu::new("terminal-pane-tab-bar-split")
                             .trigger_with_tooltip(
@@ -222,6 +285,150 @@ impl TerminalPanel {
         });
     }
 
+    fn toggle_broadcast_input(&mut self, cx: &mut Context<Self>) {
+        self.broadcast_input = !self.broadcast_input;
+        for pane in self.center.panes() {
+            self.apply_tab_bar_buttons(pane, cx);
+        }
+        for terminal_view in self.terminal_views(cx) {
+            terminal_view.update(cx, |_, cx| cx.notify());
+        }
+        cx.notify();
+    }
+
+    fn terminal_views(&self, cx: &App) -> Vec<Entity<TerminalView>> {
+        self.center
+            .panes()
+            .into_iter()
+            .flat_map(|pane| {
+                pane.read(cx)
+                    .items()
+                    .filter_map(|item| item.downcast::<TerminalView>())
+            })
+            .collect()
+    }
+
+    /// Whether input typed into the given terminal view is currently broadcast to the rest of the panel.
+    pub(crate) fn is_broadcasting_input(&self, terminal_view: EntityId, cx: &App) -> bool {
+        self.broadcast_input
+            && self
+                .terminal_views(cx)
+                .iter()
+                .any(|view| view.entity_id() == terminal_view)
+    }
+
+    /// Returns the other terminal views in the panel that should receive the input typed into
+    /// the given one. Their own broadcast exclusions are left to the caller, since the source
+    /// view may be mid-update and must not be read here.
+    pub(crate) fn broadcast_peers(&self, source: EntityId, cx: &App) -> Vec<Entity<TerminalView>> {
+        if !self.is_broadcasting_input(source, cx) {
+            return Vec::new();
+        }
+        self.terminal_views(cx)
+            .into_iter()
+            .filter(|view| view.entity_id() != source)
+            .collect()
+    }
+
+    fn save_layout(
+        workspace: &mut Workspace,
+        action: &SaveLayout,
+        _: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(terminal_panel) = workspace.panel::<Self>(cx) else {
+            return;
+        };
+        let name = layout_name(action.name.as_deref());
+        let layout = serialize_terminal_layout(&terminal_panel.read(cx).center, cx);
+        cx.background_spawn(async move {
+            TERMINAL_DB
+                .save_layout(name, serde_json::to_string(&layout)?)
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn restore_layout(
+        workspace: &mut Workspace,
+        action: &RestoreLayout,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(terminal_panel) = workspace.panel::<Self>(cx) else {
+            return;
+        };
+        let name = layout_name(action.name.as_deref());
+        terminal_panel
+            .update(cx, |terminal_panel, cx| {
+                terminal_panel.load_layout(name, window, cx)
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn load_layout(
+        &mut self,
+        name: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |terminal_panel, cx| {
+            if workspace.update(cx, |workspace, cx| !is_enabled_in_workspace(workspace, cx))? {
+                anyhow::bail!("terminal not yet supported for remote projects");
+            }
+            let layout = cx
+                .background_spawn({
+                    let name = name.clone();
+                    async move { TERMINAL_DB.get_layout(name) }
+                })
+                .await?
+                .with_context(|| format!("no saved terminal layout named {name:?}"))?;
+            let layout = serde_json::from_str::<SerializedTerminalLayout>(&layout)?;
+            let (project, database_id) = workspace.read_with(cx, |workspace, _| {
+                (workspace.project().clone(), workspace.database_id())
+            })?;
+            let terminal_panel = terminal_panel
+                .upgrade()
+                .context("terminal panel was dropped")?;
+            let root = deserialize_terminal_layout(
+                workspace.clone(),
+                project,
+                terminal_panel.clone(),
+                database_id,
+                &layout,
+                cx,
+            )
+            .await
+            .with_context(|| format!("terminal layout {name:?} has no terminals"))?;
+            terminal_panel.update_in(cx, |terminal_panel, window, cx| {
+                let old_panes = terminal_panel
+                    .center
+                    .panes()
+                    .into_iter()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                terminal_panel.center = PaneGroup::with_root(root);
+                terminal_panel.active_pane = terminal_panel.center.first_pane();
+                // The layout replaces the panel's terminals, so shut down the ones it had.
+                for pane in old_panes {
+                    pane.update(cx, |pane, cx| {
+                        let item_ids = pane.items().map(|item| item.item_id()).collect::<Vec<_>>();
+                        for item_id in item_ids {
+                            pane.remove_item(item_id, false, false, window, cx);
+                        }
+                    });
+                }
+                terminal_panel.serialize(cx);
+                cx.notify();
+            })?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                workspace.focus_panel::<Self>(window, cx);
+            })?;
+            Ok(())
+        })
+    }
+
     fn serialization_key(workspace: &Workspace) -> Option<String> {
         workspace
             .database_id()
@@ -935,6 +1142,12 @@ impl TerminalPanel {
     }
 }
 
+fn layout_name(name: Option<&str>) -> String {
+    name.filter(|name| !name.is_empty())
+        .unwrap_or(DEFAULT_LAYOUT_NAME)
+        .to_string()
+}
+
 fn is_enabled_in_workspace(workspace: &Workspace, cx: &App) -> bool {
     workspace.project().read(cx).supports_terminal(cx)
 }
@@ -1506,3 +1719,169 @@ impl Render for InlineAssistTabBarButton {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        persistence::{deserialize_terminal_layout, serialize_terminal_layout},
+        tests::init_test,
+    };
+    use gpui::{Axis, TestAppContext, VisualTestContext};
+    use workspace::{Member, PaneAxis};
+
+    #[gpui::test]
+    async fn test_terminal_layout_round_trip(cx: &mut TestAppContext) {
+        let (workspace, terminal_panel, mut cx) =
+            split_terminal_panel([("cat", &[]), ("head", &["-n", "1"])], cx).await;
+        let cx = &mut cx;
+
+        let layout = terminal_panel.read_with(cx, |terminal_panel, cx| {
+            serialize_terminal_layout(&terminal_panel.center, cx)
+        });
+        let layout = serde_json::from_str::<SerializedTerminalLayout>(
+            &serde_json::to_string(&layout).unwrap(),
+        )
+        .unwrap();
+        let project = workspace.read_with(cx, |workspace, _| workspace.project().clone());
+        let root = terminal_panel
+            .update_in(cx, |_, window, cx| {
+                let terminal_panel = cx.entity();
+                cx.spawn_in(window, async move |_, cx| {
+                    deserialize_terminal_layout(
+                        workspace.downgrade(),
+                        project,
+                        terminal_panel,
+                        None,
+                        &layout,
+                        cx,
+                    )
+                    .await
+                })
+            })
+            .await
+            .expect("layout should restore its terminals");
+
+        let Member::Axis(PaneAxis { axis, members, .. }) = root else {
+            panic!("expected the restored layout to be split");
+        };
+        assert_eq!(axis, Axis::Horizontal);
+        let commands = cx.read(|cx| {
+            members
+                .iter()
+                .map(|member| {
+                    let Member::Pane(pane) = member else {
+                        panic!("expected a pane");
+                    };
+                    pane.read(cx)
+                        .items()
+                        .filter_map(|item| item.downcast::<TerminalView>())
+                        .map(|terminal_view| {
+                            let terminal = terminal_view.read(cx).terminal().read(cx);
+                            let task = terminal
+                                .task()
+                                .expect("restored terminal should run the task");
+                            (task.command.clone(), task.args.clone())
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(
+            commands,
+            vec![
+                vec![(Some("cat".to_string()), Vec::new())],
+                vec![(
+                    Some("head".to_string()),
+                    vec!["-n".to_string(), "1".to_string()]
+                )],
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_broadcast_input_reaches_every_terminal(cx: &mut TestAppContext) {
+        let (_, terminal_panel, mut cx) =
+            split_terminal_panel([("head", &["-n", "1"]), ("head", &["-n", "1"])], cx).await;
+        let cx = &mut cx;
+
+        let terminal_views = terminal_panel.update(cx, |terminal_panel, cx| {
+            terminal_panel.toggle_broadcast_input(cx);
+            terminal_panel.terminal_views(cx)
+        });
+        assert_eq!(terminal_views.len(), 2);
+        terminal_views[0].update(cx, |terminal_view, cx| {
+            terminal_view.commit_text("broadcast\r", cx)
+        });
+
+        for terminal_view in terminal_views {
+            let terminal =
+                terminal_view.read_with(cx, |terminal_view, _| terminal_view.terminal().clone());
+            let status = cx
+                .update(|_, cx| terminal.read(cx).wait_for_completed_task(cx))
+                .await;
+            assert!(status.is_some_and(|status| status.success()));
+            let content = terminal.read_with(cx, |terminal, _| terminal.get_content());
+            assert!(
+                content.contains("broadcast"),
+                "terminal did not receive the broadcast input: {content:?}"
+            );
+        }
+    }
+
+    /// Opens a terminal panel that is split into one pane per command, each holding a task
+    /// terminal running that command.
+    async fn split_terminal_panel(
+        commands: [(&str, &[&str]); 2],
+        cx: &mut TestAppContext,
+    ) -> (Entity<Workspace>, Entity<TerminalPanel>, VisualTestContext) {
+        cx.executor().allow_parking();
+        let (project, workspace) = init_test(cx).await;
+        cx.update(editor::init);
+        let mut cx = VisualTestContext::from_window(cx.windows()[0], cx);
+
+        let terminal_panel = workspace.update_in(&mut cx, |workspace, window, cx| {
+            let terminal_panel = cx.new(|cx| TerminalPanel::new(workspace, window, cx));
+            workspace.add_panel(terminal_panel.clone(), window, cx);
+            terminal_panel
+        });
+        for (ix, (command, args)) in commands.into_iter().enumerate() {
+            if ix > 0 {
+                terminal_panel.update_in(&mut cx, |terminal_panel, window, cx| {
+                    let pane = new_terminal_pane(
+                        workspace.downgrade(),
+                        project.clone(),
+                        false,
+                        window,
+                        cx,
+                    );
+                    terminal_panel
+                        .center
+                        .split(&terminal_panel.active_pane, &pane, SplitDirection::Right)
+                        .unwrap();
+                    terminal_panel.active_pane = pane;
+                });
+            }
+            let task = SpawnInTerminal {
+                id: TaskId(command.to_string()),
+                full_label: command.to_string(),
+                label: command.to_string(),
+                command: Some(command.to_string()),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+                ..SpawnInTerminal::default()
+            };
+            terminal_panel
+                .update_in(&mut cx, |terminal_panel, window, cx| {
+                    terminal_panel.add_terminal(
+                        TerminalKind::Task(task),
+                        RevealStrategy::Never,
+                        window,
+                        cx,
+                    )
+                })
+                .await
+                .unwrap();
+        }
+        (workspace, terminal_panel, cx)
+    }
+}
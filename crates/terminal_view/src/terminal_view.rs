@@ -85,7 +85,9 @@ actions!(
     terminal,
     [
         /// Reruns the last executed task in the terminal.
-        RerunTask
+        RerunTask,
+        /// Toggles whether this terminal receives input broadcast from other terminals in the panel.
+        ToggleBroadcastExclusion
     ]
 );
 
@@ -142,6 +144,7 @@ pub struct TerminalView {
     hide_scrollbar_task: Option<Task<()>>,
     marked_text: Option<String>,
     marked_range_utf16: Option<Range<usize>>,
+    excluded_from_broadcast: bool,
     _subscriptions: Vec<Subscription>,
     _terminal_subscriptions: Vec<Subscription>,
 }
@@ -269,6 +272,7 @@ impl TerminalView {
             cwd_serialized: false,
             marked_text: None,
             marked_range_utf16: None,
+            excluded_from_broadcast: false,
             _subscriptions: vec![
                 focus_in,
                 focus_out,
@@ -355,6 +359,65 @@ impl TerminalView {
             self.terminal.update(cx, |term, _| {
                 term.input(text.to_string().into_bytes());
             });
+            self.broadcast(cx, |term| term.input(text.to_string().into_bytes()));
+        }
+    }
+
+    pub fn is_excluded_from_broadcast(&self) -> bool {
+        self.excluded_from_broadcast
+    }
+
+    pub fn set_excluded_from_broadcast(&mut self, excluded: bool, cx: &mut Context<Self>) {
+        self.excluded_from_broadcast = excluded;
+        cx.notify();
+    }
+
+    fn toggle_broadcast_exclusion(
+        &mut self,
+        _: &ToggleBroadcastExclusion,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_excluded_from_broadcast(!self.excluded_from_broadcast, cx);
+    }
+
+    fn terminal_panel(&self, cx: &App) -> Option<Entity<TerminalPanel>> {
+        self.workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).panel::<TerminalPanel>(cx))
+    }
+
+    /// Whether the terminal panel is broadcasting input to this terminal, either because it was
+    /// typed here or because it will be mirrored here from another terminal.
+    fn receives_broadcast_input(&self, cx: &Context<Self>) -> bool {
+        !self.excluded_from_broadcast
+            && self.terminal_panel(cx).is_some_and(|terminal_panel| {
+                terminal_panel
+                    .read(cx)
+                    .is_broadcasting_input(cx.entity_id(), cx)
+            })
+    }
+
+    /// Mirrors input that was sent to this terminal to the other terminals of the panel,
+    /// when broadcasting is enabled.
+    fn broadcast(&self, cx: &mut Context<Self>, mut send: impl FnMut(&mut Terminal)) {
+        if self.excluded_from_broadcast {
+            return;
+        }
+        let Some(terminal_panel) = self.terminal_panel(cx) else {
+            return;
+        };
+        let peers = terminal_panel
+            .read(cx)
+            .broadcast_peers(cx.entity_id(), cx)
+            .into_iter()
+            .filter_map(|peer| {
+                let peer = peer.read(cx);
+                (!peer.excluded_from_broadcast).then(|| peer.terminal.clone())
+            })
+            .collect::<Vec<_>>();
+        for terminal in peers {
+            terminal.update(cx, |terminal, _| send(terminal));
         }
     }
 
@@ -381,13 +444,16 @@ impl TerminalView {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let assistant_enabled = self
-            .workspace
-            .upgrade()
-            .and_then(|workspace| workspace.read(cx).panel::<TerminalPanel>(cx))
-            .map_or(false, |terminal_panel| {
-                terminal_panel.read(cx).assistant_enabled()
-            });
+        let (assistant_enabled, broadcasting_input) =
+            self.terminal_panel(cx)
+                .map_or((false, false), |terminal_panel| {
+                    let terminal_panel = terminal_panel.read(cx);
+                    (
+                        terminal_panel.assistant_enabled(),
+                        terminal_panel.is_broadcasting_input(cx.entity_id(), cx),
+                    )
+                });
+        let excluded_from_broadcast = self.excluded_from_broadcast;
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal))
@@ -400,6 +466,16 @@ impl TerminalView {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
                 })
+                .when(broadcasting_input, |menu| {
+                    menu.separator().action(
+                        if excluded_from_broadcast {
+                            "Include in Broadcast"
+                        } else {
+                            "Exclude from Broadcast"
+                        },
+                        Box::new(ToggleBroadcastExclusion),
+                    )
+                })
                 .separator()
                 .action(
                     "Close Terminal Tab",
@@ -717,6 +793,7 @@ impl TerminalView {
         if let Some(clipboard_string) = cx.read_from_clipboard().and_then(|item| item.text()) {
             self.terminal
                 .update(cx, |terminal, _cx| terminal.paste(&clipboard_string));
+            self.broadcast(cx, |terminal| terminal.paste(&clipboard_string));
         }
     }
 
@@ -725,19 +802,25 @@ impl TerminalView {
         self.terminal.update(cx, |term, _| {
             term.input(text.0.to_string().into_bytes());
         });
+        self.broadcast(cx, |term| term.input(text.0.to_string().into_bytes()));
     }
 
     fn send_keystroke(&mut self, text: &SendKeystroke, _: &mut Window, cx: &mut Context<Self>) {
         if let Some(keystroke) = Keystroke::parse(&text.0).log_err() {
             self.clear_bell(cx);
-            self.terminal.update(cx, |term, cx| {
-                let processed =
-                    term.try_keystroke(&keystroke, TerminalSettings::get_global(cx).option_as_meta);
+            let option_as_meta = TerminalSettings::get_global(cx).option_as_meta;
+            let processed = self.terminal.update(cx, |term, cx| {
+                let processed = term.try_keystroke(&keystroke, option_as_meta);
                 if processed && term.vi_mode_enabled() {
                     cx.notify();
                 }
                 processed
             });
+            if processed {
+                self.broadcast(cx, |term| {
+                    term.try_keystroke(&keystroke, option_as_meta);
+                });
+            }
         }
     }
 
@@ -1457,15 +1540,16 @@ impl TerminalView {
         self.clear_bell(cx);
         self.pause_cursor_blinking(window, cx);
 
-        self.terminal.update(cx, |term, cx| {
-            let handled = term.try_keystroke(
-                &event.keystroke,
-                TerminalSettings::get_global(cx).option_as_meta,
-            );
-            if handled {
-                cx.stop_propagation();
-            }
+        let option_as_meta = TerminalSettings::get_global(cx).option_as_meta;
+        let handled = self.terminal.update(cx, |term, _| {
+            term.try_keystroke(&event.keystroke, option_as_meta)
         });
+        if handled {
+            cx.stop_propagation();
+            self.broadcast(cx, |term| {
+                term.try_keystroke(&event.keystroke, option_as_meta);
+            });
+        }
     }
 
     fn focus_in(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -1494,6 +1578,7 @@ impl Render for TerminalView {
         let terminal_view_handle = cx.entity().clone();
 
         let focused = self.focus_handle.is_focused(window);
+        let receives_broadcast_input = self.receives_broadcast_input(cx);
 
         // Always calculate scrollbar width to prevent layout shift
         let scrollbar_width = if Self::should_show_scrollbar(cx)
@@ -1526,6 +1611,7 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
             .on_action(cx.listener(TerminalView::rerun_task))
+            .on_action(cx.listener(TerminalView::toggle_broadcast_exclusion))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
                 MouseButton::Right,
@@ -1570,6 +1656,17 @@ impl Render for TerminalView {
                         div.child(scrollbar)
                     }),
             )
+            .when(receives_broadcast_input, |this| {
+                // An overlay rather than a border on the terminal itself, so that toggling
+                // broadcasting doesn't resize the PTY.
+                this.child(
+                    div()
+                        .absolute()
+                        .inset_0()
+                        .border_2()
+                        .border_color(cx.theme().status().warning_border),
+                )
+            })
             .children(self.context_menu.as_ref().map(|(menu, position, _)| {
                 deferred(
                     anchored()
@@ -1861,6 +1958,7 @@ impl SearchableItem for TerminalView {
             case: false,
             word: false,
             regex: true,
+            syntax: false,
             replacement: false,
             selection: false,
             find_in_results: false,
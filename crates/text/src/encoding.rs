@@ -0,0 +1,400 @@
+use anyhow::{Result, anyhow};
+use std::fmt;
+
+/// How many bytes at the start of a file are inspected when guessing at its encoding.
+const DETECTION_SAMPLE_LEN: usize = 8 * 1024;
+
+/// The character encoding of a file on disk, along with whether it starts
+/// with a byte order mark.
+///
+/// Buffers always hold UTF-8 text in memory, so the encoding only matters
+/// when the file is decoded on load and encoded again on save.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Encoding {
+    encoding: &'static encoding_rs::Encoding,
+    bom: bool,
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self::new(encoding_rs::UTF_8)
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())?;
+        if self.bom && self.encoding == encoding_rs::UTF_8 {
+            f.write_str(" with BOM")?;
+        }
+        Ok(())
+    }
+}
+
+impl Encoding {
+    fn new(encoding: &'static encoding_rs::Encoding) -> Self {
+        Self {
+            encoding,
+            bom: false,
+        }
+    }
+
+    /// The encodings offered when the user picks one explicitly.
+    pub fn all() -> Vec<Self> {
+        let mut encodings = vec![
+            Self::default(),
+            Self::default().with_bom(true),
+            Self::new(encoding_rs::UTF_16LE).with_bom(true),
+            Self::new(encoding_rs::UTF_16BE).with_bom(true),
+        ];
+        encodings.extend(
+            [
+                encoding_rs::WINDOWS_1252,
+                encoding_rs::ISO_8859_15,
+                encoding_rs::ISO_8859_2,
+                encoding_rs::WINDOWS_1250,
+                encoding_rs::WINDOWS_1251,
+                encoding_rs::KOI8_R,
+                encoding_rs::WINDOWS_1253,
+                encoding_rs::WINDOWS_1254,
+                encoding_rs::WINDOWS_1255,
+                encoding_rs::WINDOWS_1256,
+                encoding_rs::WINDOWS_1257,
+                encoding_rs::WINDOWS_874,
+                encoding_rs::WINDOWS_1258,
+                encoding_rs::SHIFT_JIS,
+                encoding_rs::EUC_JP,
+                encoding_rs::ISO_2022_JP,
+                encoding_rs::GBK,
+                encoding_rs::GB18030,
+                encoding_rs::BIG5,
+                encoding_rs::EUC_KR,
+            ]
+            .into_iter()
+            .map(Self::new),
+        );
+        encodings
+    }
+
+    /// Looks up an encoding by one of its WHATWG labels, such as `"latin1"` or `"shift_jis"`.
+    pub fn from_label(label: &str) -> Option<Self> {
+        let encoding = encoding_rs::Encoding::for_label(label.trim().as_bytes())?;
+        Some(Self::new(encoding))
+    }
+
+    /// Guesses the encoding of a file's contents.
+    ///
+    /// A byte order mark always wins. Otherwise, valid UTF-8 is assumed to be
+    /// UTF-8, BOM-less UTF-16 is recognized by the NUL bytes in its ASCII
+    /// characters, and Shift-JIS is only chosen when the text decodes cleanly
+    /// and contains kana. Windows-1252 is only chosen when the text reads like
+    /// accented Latin script. Returns `None` for content that looks binary or
+    /// whose encoding can't be told with confidence.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        Self::detect_preferring(bytes, None)
+    }
+
+    /// Like [`Encoding::detect`], but picks `preferred` whenever the content
+    /// decodes in it without errors and has no byte order mark of its own.
+    /// Content that looks binary is still rejected.
+    pub fn detect_preferring(bytes: &[u8], preferred: Option<Self>) -> Option<Self> {
+        if let Some((encoding, _)) = encoding_rs::Encoding::for_bom(bytes) {
+            return Some(Self::new(encoding).with_bom(true));
+        }
+
+        let sample = &bytes[..bytes.len().min(DETECTION_SAMPLE_LEN)];
+        if let Some(encoding) = detect_utf16(sample) {
+            return Some(Self::new(encoding));
+        }
+        if let Some(preferred) = preferred
+            && !preferred.is_utf16()
+            && !sample.contains(&0)
+            && preferred.decodes_cleanly(bytes)
+        {
+            return Some(preferred);
+        }
+        if std::str::from_utf8(bytes).is_ok() {
+            return Some(Self::default());
+        }
+        if sample.contains(&0) {
+            return None;
+        }
+        if looks_like_shift_jis(bytes) {
+            return Some(Self::new(encoding_rs::SHIFT_JIS));
+        }
+        if looks_like_windows_1252(bytes) {
+            return Some(Self::new(encoding_rs::WINDOWS_1252));
+        }
+        None
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.encoding.name()
+    }
+
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    pub fn is_utf8(&self) -> bool {
+        self.encoding == encoding_rs::UTF_8
+    }
+
+    /// Returns this encoding with the byte order mark toggled. Only the
+    /// Unicode encodings can carry one, so this has no effect on others.
+    pub fn with_bom(mut self, bom: bool) -> Self {
+        self.bom = bom && self.is_unicode();
+        self
+    }
+
+    fn is_unicode(&self) -> bool {
+        self.is_utf8() || self.is_utf16()
+    }
+
+    fn is_utf16(&self) -> bool {
+        self.encoding == encoding_rs::UTF_16LE || self.encoding == encoding_rs::UTF_16BE
+    }
+
+    fn decodes_cleanly(&self, bytes: &[u8]) -> bool {
+        self.encoding
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .is_some()
+    }
+
+    /// Decodes a file's contents, stripping the byte order mark if there is
+    /// one. Malformed sequences are replaced with U+FFFD.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        let bytes = match encoding_rs::Encoding::for_bom(bytes) {
+            Some((encoding, bom_len)) if encoding == self.encoding => &bytes[bom_len..],
+            _ => bytes,
+        };
+        let (text, _) = self.encoding.decode_without_bom_handling(bytes);
+        text.into_owned()
+    }
+
+    /// Encodes text for writing to disk, failing if any of its characters
+    /// can't be represented in this encoding.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(text.len() + 3);
+        if self.is_utf16() {
+            // encoding_rs only decodes UTF-16, so its encoder would produce UTF-8 here.
+            let big_endian = self.encoding == encoding_rs::UTF_16BE;
+            if self.bom {
+                bytes.extend(encode_utf16_unit(0xFEFF, big_endian));
+            }
+            for unit in text.encode_utf16() {
+                bytes.extend(encode_utf16_unit(unit, big_endian));
+            }
+            return Ok(bytes);
+        }
+
+        if self.bom {
+            bytes.extend_from_slice(b"\xEF\xBB\xBF");
+        }
+        if self.is_utf8() {
+            bytes.extend_from_slice(text.as_bytes());
+            return Ok(bytes);
+        }
+
+        let mut encoder = self.encoding.new_encoder();
+        let mut remaining = text;
+        loop {
+            let capacity = encoder
+                .max_buffer_length_from_utf8_without_replacement(remaining.len())
+                .ok_or_else(|| anyhow!("text is too large to encode as {}", self.name()))?;
+            bytes.reserve(capacity);
+            let (result, read) =
+                encoder.encode_from_utf8_to_vec_without_replacement(remaining, &mut bytes, true);
+            remaining = &remaining[read..];
+            match result {
+                encoding_rs::EncoderResult::InputEmpty => return Ok(bytes),
+                encoding_rs::EncoderResult::OutputFull => continue,
+                encoding_rs::EncoderResult::Unmappable(character) => {
+                    return Err(anyhow!(
+                        "{character:?} can't be represented in {}",
+                        self.name()
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn encode_utf16_unit(unit: u16, big_endian: bool) -> [u8; 2] {
+    if big_endian {
+        unit.to_be_bytes()
+    } else {
+        unit.to_le_bytes()
+    }
+}
+
+fn detect_utf16(sample: &[u8]) -> Option<&'static encoding_rs::Encoding> {
+    if sample.len() < 2 || !sample.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let units = sample.len() / 2;
+    let even_nuls = sample.iter().step_by(2).filter(|byte| **byte == 0).count();
+    let odd_nuls = sample
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .filter(|byte| **byte == 0)
+        .count();
+    if even_nuls == 0 && odd_nuls * 2 > units {
+        Some(encoding_rs::UTF_16LE)
+    } else if odd_nuls == 0 && even_nuls * 2 > units {
+        Some(encoding_rs::UTF_16BE)
+    } else {
+        None
+    }
+}
+
+fn looks_like_shift_jis(bytes: &[u8]) -> bool {
+    encoding_rs::SHIFT_JIS
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .is_some_and(|text| {
+            text.chars().any(
+                |character| matches!(character, '\u{3040}'..='\u{30FF}' | '\u{FF66}'..='\u{FF9F}'),
+            )
+        })
+}
+
+/// Windows-1252 can decode any byte sequence, so it is only trusted when the
+/// result has no control characters and its non-ASCII characters are letters or
+/// typographic punctuation in short runs, as in accented words. Long runs of
+/// accented letters are more likely text in another single-byte encoding.
+fn looks_like_windows_1252(bytes: &[u8]) -> bool {
+    const MAX_NON_ASCII_RUN: usize = 3;
+
+    let (text, _) = encoding_rs::WINDOWS_1252.decode_without_bom_handling(bytes);
+    let mut non_ascii_run = 0;
+    for character in text.chars() {
+        if character.is_ascii() {
+            if character.is_ascii_control() && !matches!(character, '\t' | '\n' | '\r' | '\x0C') {
+                return false;
+            }
+            non_ascii_run = 0;
+        } else if character.is_alphabetic()
+            || matches!(
+                character,
+                '\u{A0}'..='\u{BF}' | '\u{D7}' | '\u{F7}' | '\u{2013}'..='\u{2026}' | '\u{20AC}'
+            )
+        {
+            non_ascii_run += 1;
+            if non_ascii_run > MAX_NON_ASCII_RUN {
+                return false;
+            }
+        } else {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Encoding::detect(b"plain ascii"), Some(Encoding::default()));
+        assert_eq!(
+            Encoding::detect("caf\u{e9}".as_bytes()),
+            Some(Encoding::default())
+        );
+        assert_eq!(
+            Encoding::detect(b"\xEF\xBB\xBFbom"),
+            Some(Encoding::default().with_bom(true))
+        );
+        assert_eq!(
+            Encoding::detect(b"\xFF\xFEh\0i\0"),
+            Encoding::from_label("utf-16le").map(|encoding| encoding.with_bom(true))
+        );
+        assert_eq!(
+            Encoding::detect(b"\0h\0i\0\n"),
+            Encoding::from_label("utf-16be")
+        );
+        assert_eq!(
+            Encoding::detect(b"caf\xE9 cr\xE8me"),
+            Encoding::from_label("windows-1252")
+        );
+        assert_eq!(
+            Encoding::detect(b"r\xE9el"),
+            Encoding::from_label("windows-1252")
+        );
+        assert_eq!(
+            Encoding::detect(b"\x82\xB1\x82\xF1\x82\xC9\x82\xBF\x82\xCD"),
+            Encoding::from_label("shift_jis")
+        );
+        assert_eq!(Encoding::detect(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\xff"), None);
+
+        // Bytes that would only decode to control characters or long runs of
+        // accented letters in Windows-1252 aren't guessed at.
+        assert_eq!(Encoding::detect(b"\x1b\x9d\x81\xfe\x07\xc8"), None);
+        assert_eq!(
+            Encoding::detect(b"\xcf\xf0\xe8\xe2\xe5\xf2 \xec\xe8\xf0"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_detect_preferring() {
+        let latin1 = Encoding::from_label("latin1");
+        let utf8 = Some(Encoding::default());
+        assert_eq!(
+            Encoding::detect_preferring("caf\u{e9}".as_bytes(), latin1),
+            latin1
+        );
+        assert_eq!(
+            Encoding::detect_preferring(b"\xEF\xBB\xBFcaf\xC3\xA9", latin1),
+            Some(Encoding::default().with_bom(true))
+        );
+        assert_eq!(
+            Encoding::detect_preferring(b"caf\xE9 cr\xE8me", utf8),
+            Encoding::from_label("windows-1252")
+        );
+        assert_eq!(
+            Encoding::detect_preferring(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\xff", latin1),
+            None
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cases = [
+            ("utf-8", "h\u{e9}llo \u{1F600}"),
+            ("utf-16le", "h\u{e9}llo \u{1F600}"),
+            ("utf-16be", "h\u{e9}llo \u{1F600}"),
+            ("windows-1252", "caf\u{e9} \u{20AC}5"),
+            ("shift_jis", "\u{3053}\u{3093}\u{306B}\u{3061}\u{306F}"),
+            ("koi8-r", "\u{43F}\u{440}\u{438}\u{432}\u{435}\u{442}"),
+        ];
+        for (label, text) in cases {
+            for bom in [false, true] {
+                let encoding = Encoding::from_label(label).unwrap().with_bom(bom);
+                let bytes = encoding.encode(text).unwrap();
+                assert_eq!(encoding.decode(&bytes), text, "{encoding}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_labels() {
+        for encoding in Encoding::all() {
+            assert_eq!(
+                Encoding::from_label(encoding.name())
+                    .map(|parsed| parsed.with_bom(encoding.has_bom())),
+                Some(encoding)
+            );
+        }
+    }
+
+    #[test]
+    fn test_unmappable_characters() {
+        let latin1 = Encoding::from_label("latin1").unwrap();
+        assert_eq!(latin1, Encoding::from_label("windows-1252").unwrap());
+        assert!(latin1.encode("\u{3053}").is_err());
+        assert_eq!(latin1.encode("caf\u{e9}").unwrap(), b"caf\xE9");
+    }
+}
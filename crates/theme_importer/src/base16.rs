@@ -0,0 +1,456 @@
+//! Imports base16 and base24 schemes, using the roles that the base16 styling
+//! guidelines give each color.
+
+use anyhow::{Context as _, Result, bail};
+use gpui::Rgba;
+use indexmap::IndexMap;
+use theme::{
+    AccentContent, AppearanceContent, FontStyleContent, FontWeightContent, HighlightStyleContent,
+    PlayerColorContent, StatusColorsContent, ThemeColorsContent, ThemeContent, ThemeStyleContent,
+};
+
+use crate::ImportReport;
+use crate::color::{blend, hex, is_light, parse_hex_color, with_alpha};
+
+const BASE16_COLOR_COUNT: usize = 16;
+const BASE24_COLOR_COUNT: usize = 24;
+
+pub struct Base16Scheme {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub appearance: Option<AppearanceContent>,
+    /// `base00` to `base0F`, followed by `base10` to `base17` for base24
+    /// schemes.
+    colors: Vec<Rgba>,
+}
+
+impl Base16Scheme {
+    pub fn new(colors: [Rgba; BASE16_COLOR_COUNT]) -> Self {
+        Self {
+            name: None,
+            author: None,
+            appearance: None,
+            colors: colors.to_vec(),
+        }
+    }
+
+    /// Parses a scheme in either the original format, with the colors at the
+    /// top level, or the newer one, with them under `palette`.
+    pub fn parse(contents: &str, report: &mut ImportReport) -> Result<Self> {
+        let mut name = None;
+        let mut author = None;
+        let mut appearance = None;
+        let mut colors = [None; BASE24_COLOR_COUNT];
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = yaml_string(value);
+            match key.trim() {
+                "scheme" | "name" => name = Some(value.to_string()),
+                "author" => author = Some(value.to_string()),
+                "variant" => match value {
+                    "light" => appearance = Some(AppearanceContent::Light),
+                    "dark" => appearance = Some(AppearanceContent::Dark),
+                    _ => report.warnings.push(format!("unknown variant '{value}'")),
+                },
+                key => {
+                    let Some(ix) = key
+                        .strip_prefix("base")
+                        .filter(|ix| ix.len() == 2)
+                        .and_then(|ix| usize::from_str_radix(ix, 16).ok())
+                        .filter(|ix| *ix < BASE24_COLOR_COUNT)
+                    else {
+                        continue;
+                    };
+                    colors[ix] =
+                        Some(parse_hex_color(value).with_context(|| format!("invalid {key}"))?);
+                }
+            }
+        }
+
+        let mut base16_colors = Vec::with_capacity(BASE24_COLOR_COUNT);
+        for (ix, color) in colors[..BASE16_COLOR_COUNT].iter().enumerate() {
+            let Some(color) = color else {
+                bail!("missing base{ix:02X}");
+            };
+            base16_colors.push(*color);
+        }
+        let base24_colors = &colors[BASE16_COLOR_COUNT..];
+        if base24_colors.iter().all(Option::is_some) {
+            base16_colors.extend(base24_colors.iter().flatten());
+        } else if base24_colors.iter().any(Option::is_some) {
+            report
+                .warnings
+                .push("ignored the base24 colors, as some are missing".to_string());
+        }
+
+        Ok(Self {
+            name,
+            author,
+            appearance,
+            colors: base16_colors,
+        })
+    }
+
+    fn color(&self, ix: usize) -> Option<String> {
+        Some(hex(self.colors[ix]))
+    }
+
+    fn color_with_alpha(&self, ix: usize, alpha: f32) -> Option<String> {
+        Some(hex(with_alpha(self.colors[ix], alpha)))
+    }
+
+    /// The color of the bright variant of an ANSI color, which only base24
+    /// schemes have.
+    fn bright_color(&self, base16_ix: usize, base24_ix: usize) -> Option<String> {
+        self.color(if self.colors.len() == BASE24_COLOR_COUNT {
+            base24_ix
+        } else {
+            base16_ix
+        })
+    }
+
+    pub fn convert(&self, fallback_name: String) -> ThemeContent {
+        let appearance = self.appearance.unwrap_or(if is_light(self.colors[0x00]) {
+            AppearanceContent::Light
+        } else {
+            AppearanceContent::Dark
+        });
+        let border_variant = Some(hex(blend(self.colors[0x01], self.colors[0x02], 0.5)));
+        let transparent = Some("#00000000".to_string());
+
+        let colors = ThemeColorsContent {
+            border: self.color(0x02),
+            border_variant: border_variant.clone(),
+            border_focused: self.color(0x0D),
+            border_selected: self.color(0x0D),
+            border_transparent: transparent.clone(),
+            border_disabled: self.color(0x02),
+            elevated_surface_background: self.color(0x01),
+            surface_background: self.color(0x01),
+            background: self.color(0x01),
+            element_background: self.color(0x01),
+            element_hover: self.color(0x02),
+            element_active: self.color(0x02),
+            element_selected: self.color(0x02),
+            element_disabled: self.color(0x01),
+            element_selection_background: self.color_with_alpha(0x0D, 0.24),
+            drop_target_background: self.color_with_alpha(0x0D, 0.2),
+            ghost_element_background: transparent.clone(),
+            ghost_element_hover: self.color(0x02),
+            ghost_element_active: self.color(0x02),
+            ghost_element_selected: self.color(0x02),
+            ghost_element_disabled: self.color(0x01),
+            text: self.color(0x05),
+            text_muted: self.color(0x04),
+            text_placeholder: self.color(0x03),
+            text_disabled: self.color(0x03),
+            text_accent: self.color(0x0D),
+            icon: self.color(0x05),
+            icon_muted: self.color(0x04),
+            icon_disabled: self.color(0x03),
+            icon_placeholder: self.color(0x04),
+            icon_accent: self.color(0x0D),
+            status_bar_background: self.color(0x01),
+            title_bar_background: self.color(0x01),
+            title_bar_inactive_background: self.color(0x01),
+            toolbar_background: self.color(0x00),
+            tab_bar_background: self.color(0x01),
+            tab_inactive_background: self.color(0x01),
+            tab_active_background: self.color(0x00),
+            search_match_background: self.color_with_alpha(0x0D, 0.4),
+            panel_background: self.color(0x01),
+            panel_focused_border: self.color(0x0D),
+            pane_group_border: self.color(0x02),
+            scrollbar_thumb_background: self.color_with_alpha(0x05, 0.3),
+            scrollbar_thumb_hover_background: self.color(0x02),
+            scrollbar_thumb_border: self.color(0x01),
+            scrollbar_track_background: transparent,
+            scrollbar_track_border: self.color(0x01),
+            editor_foreground: self.color(0x05),
+            editor_background: self.color(0x00),
+            editor_gutter_background: self.color(0x00),
+            editor_subheader_background: self.color(0x01),
+            editor_active_line_background: self.color_with_alpha(0x01, 0.75),
+            editor_highlighted_line_background: self.color(0x01),
+            editor_line_number: self.color(0x03),
+            editor_active_line_number: self.color(0x05),
+            editor_hover_line_number: self.color(0x04),
+            editor_invisible: self.color(0x03),
+            editor_wrap_guide: self.color_with_alpha(0x02, 0.5),
+            editor_active_wrap_guide: self.color(0x02),
+            editor_indent_guide: border_variant,
+            editor_indent_guide_active: self.color(0x03),
+            editor_document_highlight_read_background: self.color_with_alpha(0x0D, 0.1),
+            editor_document_highlight_write_background: self.color_with_alpha(0x0D, 0.2),
+            editor_document_highlight_bracket_background: self.color_with_alpha(0x0D, 0.2),
+            terminal_background: self.color(0x00),
+            terminal_foreground: self.color(0x05),
+            terminal_bright_foreground: self.color(0x07),
+            terminal_dim_foreground: self.color(0x04),
+            terminal_ansi_black: self.color(0x00),
+            terminal_ansi_bright_black: self.color(0x03),
+            terminal_ansi_red: self.color(0x08),
+            terminal_ansi_bright_red: self.bright_color(0x08, 0x12),
+            terminal_ansi_green: self.color(0x0B),
+            terminal_ansi_bright_green: self.bright_color(0x0B, 0x14),
+            terminal_ansi_yellow: self.color(0x0A),
+            terminal_ansi_bright_yellow: self.bright_color(0x0A, 0x13),
+            terminal_ansi_blue: self.color(0x0D),
+            terminal_ansi_bright_blue: self.bright_color(0x0D, 0x16),
+            terminal_ansi_magenta: self.color(0x0E),
+            terminal_ansi_bright_magenta: self.bright_color(0x0E, 0x17),
+            terminal_ansi_cyan: self.color(0x0C),
+            terminal_ansi_bright_cyan: self.bright_color(0x0C, 0x15),
+            terminal_ansi_white: self.color(0x05),
+            terminal_ansi_bright_white: self.color(0x07),
+            link_text_hover: self.color(0x0D),
+            version_control_added: self.color(0x0B),
+            version_control_deleted: self.color(0x08),
+            version_control_modified: self.color(0x0A),
+            version_control_renamed: self.color(0x0C),
+            version_control_conflict: self.color(0x09),
+            version_control_ignored: self.color(0x03),
+            ..Default::default()
+        };
+
+        let status = StatusColorsContent {
+            conflict: self.color(0x09),
+            created: self.color(0x0B),
+            deleted: self.color(0x08),
+            error: self.color(0x08),
+            error_background: self.color_with_alpha(0x08, 0.1),
+            error_border: self.color_with_alpha(0x08, 0.5),
+            hidden: self.color(0x03),
+            hint: self.color(0x03),
+            ignored: self.color(0x03),
+            info: self.color(0x0D),
+            info_background: self.color_with_alpha(0x0D, 0.1),
+            info_border: self.color_with_alpha(0x0D, 0.5),
+            modified: self.color(0x0A),
+            predictive: self.color(0x03),
+            renamed: self.color(0x0C),
+            success: self.color(0x0B),
+            unreachable: self.color(0x03),
+            warning: self.color(0x0A),
+            warning_background: self.color_with_alpha(0x0A, 0.1),
+            warning_border: self.color_with_alpha(0x0A, 0.5),
+            ..Default::default()
+        };
+
+        let accent_colors = [0x0D, 0x08, 0x0B, 0x0E, 0x0A, 0x0C, 0x09, 0x0F];
+        let players = accent_colors
+            .iter()
+            .map(|&ix| PlayerColorContent {
+                cursor: self.color(ix),
+                background: self.color(ix),
+                selection: self.color_with_alpha(ix, 0.24),
+            })
+            .collect();
+        let accents = accent_colors
+            .iter()
+            .map(|&ix| AccentContent(self.color(ix)))
+            .collect();
+
+        ThemeContent {
+            name: self.name.clone().unwrap_or(fallback_name),
+            appearance,
+            style: ThemeStyleContent {
+                window_background_appearance: Some(theme::WindowBackgroundContent::Opaque),
+                accents,
+                colors,
+                status,
+                players,
+                syntax: self.syntax(),
+            },
+        }
+    }
+
+    fn syntax(&self) -> IndexMap<String, HighlightStyleContent> {
+        let style = |ix: usize| HighlightStyleContent {
+            color: self.color(ix),
+            background_color: None,
+            font_style: None,
+            font_weight: None,
+        };
+        let italic = |ix: usize| HighlightStyleContent {
+            font_style: Some(FontStyleContent::Italic),
+            ..style(ix)
+        };
+        let bold = |ix: usize| HighlightStyleContent {
+            font_weight: Some(FontWeightContent::Bold),
+            ..style(ix)
+        };
+
+        [
+            ("attribute", style(0x0A)),
+            ("boolean", style(0x09)),
+            ("comment", italic(0x03)),
+            ("comment.doc", italic(0x04)),
+            ("constant", style(0x09)),
+            ("constructor", style(0x0D)),
+            ("embedded", style(0x05)),
+            ("emphasis", italic(0x0E)),
+            ("emphasis.strong", bold(0x0A)),
+            ("enum", style(0x0A)),
+            ("function", style(0x0D)),
+            ("hint", italic(0x03)),
+            ("keyword", style(0x0E)),
+            ("label", style(0x0D)),
+            ("link_text", italic(0x0D)),
+            ("link_uri", style(0x0C)),
+            ("number", style(0x09)),
+            ("operator", style(0x05)),
+            ("predictive", italic(0x03)),
+            ("preproc", style(0x0E)),
+            ("primary", style(0x05)),
+            ("property", style(0x08)),
+            ("punctuation", style(0x05)),
+            ("punctuation.bracket", style(0x05)),
+            ("punctuation.delimiter", style(0x05)),
+            ("punctuation.list_marker", style(0x08)),
+            ("punctuation.special", style(0x0F)),
+            ("string", style(0x0B)),
+            ("string.escape", style(0x0C)),
+            ("string.regex", style(0x0C)),
+            ("string.special", style(0x0F)),
+            ("string.special.symbol", style(0x0F)),
+            ("tag", style(0x08)),
+            ("text.literal", style(0x0B)),
+            ("title", bold(0x0D)),
+            ("type", style(0x0A)),
+            ("variable", style(0x08)),
+            ("variable.special", style(0x09)),
+            ("variant", style(0x0A)),
+        ]
+        .into_iter()
+        .map(|(token, style)| (token.to_string(), style))
+        .collect()
+    }
+}
+
+/// Returns a YAML scalar without its quotes or trailing comment.
+fn yaml_string(value: &str) -> &str {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(quoted) = value.strip_prefix(quote) {
+            return quoted.split(quote).next().unwrap_or_default();
+        }
+    }
+    value
+        .split_once(" #")
+        .map_or(value, |(value, _)| value)
+        .trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOMORROW_NIGHT: &str = r##"
+scheme: "Tomorrow Night"
+author: "Chris Kempson (http://chriskempson.com)"
+base00: "1d1f21" # background
+base01: "282a2e"
+base02: "373b41"
+base03: "969896"
+base04: "b4b7b4"
+base05: "c5c8c6"
+base06: "e0e0e0"
+base07: "ffffff"
+base08: "cc6666"
+base09: "de935f"
+base0A: "f0c674"
+base0B: "b5bd68"
+base0C: "8abeb7"
+base0D: "81a2be"
+base0E: "b294bb"
+base0F: "a3685a"
+"##;
+
+    #[test]
+    fn test_base16_scheme() {
+        let mut report = ImportReport::default();
+        let scheme = Base16Scheme::parse(TOMORROW_NIGHT, &mut report).unwrap();
+        let theme = scheme.convert("tomorrow-night".into());
+
+        assert_eq!(theme.name, "Tomorrow Night");
+        assert_eq!(theme.appearance, AppearanceContent::Dark);
+        assert_eq!(
+            theme.style.colors.editor_background.as_deref(),
+            Some("#1d1f21ff")
+        );
+        assert_eq!(
+            theme.style.colors.terminal_ansi_bright_red.as_deref(),
+            Some("#cc6666ff")
+        );
+        assert_eq!(
+            theme.style.syntax["keyword"].color.as_deref(),
+            Some("#b294bbff")
+        );
+        assert_eq!(report, ImportReport::default());
+    }
+
+    #[test]
+    fn test_base24_scheme() {
+        let contents = r##"
+system: "base24"
+name: "Solarized Light"
+variant: "light"
+palette:
+  base00: "#fdf6e3"
+  base01: "#eee8d5"
+  base02: "#93a1a1"
+  base03: "#839496"
+  base04: "#657b83"
+  base05: "#586e75"
+  base06: "#073642"
+  base07: "#002b36"
+  base08: "#dc322f"
+  base09: "#cb4b16"
+  base0A: "#b58900"
+  base0B: "#859900"
+  base0C: "#2aa198"
+  base0D: "#268bd2"
+  base0E: "#6c71c4"
+  base0F: "#d33682"
+  base10: "#ffffff"
+  base11: "#ffffff"
+  base12: "#ff0000"
+  base13: "#ffff00"
+  base14: "#00ff00"
+  base15: "#00ffff"
+  base16: "#0000ff"
+  base17: "#ff00ff"
+"##;
+        let mut report = ImportReport::default();
+        let theme = Base16Scheme::parse(contents, &mut report)
+            .unwrap()
+            .convert("solarized-light".into());
+
+        assert_eq!(theme.appearance, AppearanceContent::Light);
+        assert_eq!(
+            theme.style.colors.terminal_ansi_bright_red.as_deref(),
+            Some("#ff0000ff")
+        );
+        assert_eq!(
+            theme.style.colors.terminal_ansi_bright_blue.as_deref(),
+            Some("#0000ffff")
+        );
+        assert!(report.warnings.is_empty());
+
+        let missing_color = TOMORROW_NIGHT.replace("base0F: \"a3685a\"", "");
+        assert_eq!(
+            Base16Scheme::parse(&missing_color, &mut report)
+                .err()
+                .map(|error| error.to_string()),
+            Some("missing base0F".to_string())
+        );
+    }
+}
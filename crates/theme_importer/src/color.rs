@@ -1,5 +1,5 @@
-use anyhow::Result;
-use gpui::Hsla;
+use anyhow::{Context as _, Result};
+use gpui::{Hsla, Rgba};
 use palette::FromColor;
 
 #[allow(unused)]
@@ -27,6 +27,43 @@ pub(crate) fn pack_color(color: Hsla) -> u32 {
     u32::from(rgba)
 }
 
+/// Parses a hex color, with or without a leading `#` or `0x`.
+pub(crate) fn parse_hex_color(color: &str) -> Result<Rgba> {
+    let color = color.trim();
+    let hex = color
+        .strip_prefix('#')
+        .or_else(|| color.strip_prefix("0x"))
+        .unwrap_or(color);
+    Rgba::try_from(format!("#{hex}").as_str()).with_context(|| format!("invalid color '{color}'"))
+}
+
+/// Formats a color as `#rrggbbaa`, the way Zed themes write them.
+pub(crate) fn hex(color: Rgba) -> String {
+    let [r, g, b, a] = [color.r, color.g, color.b, color.a]
+        .map(|component| (component.clamp(0., 1.) * 255.).round() as u8);
+    format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+}
+
+pub(crate) fn with_alpha(color: Rgba, alpha: f32) -> Rgba {
+    Rgba { a: alpha, ..color }
+}
+
+/// Mixes `amount` of `to` into `from`.
+pub(crate) fn blend(from: Rgba, to: Rgba, amount: f32) -> Rgba {
+    let mix = |from: f32, to: f32| from + (to - from) * amount;
+    Rgba {
+        r: mix(from.r, to.r),
+        g: mix(from.g, to.g),
+        b: mix(from.b, to.b),
+        a: mix(from.a, to.a),
+    }
+}
+
+/// Whether text on this background should be dark.
+pub(crate) fn is_light(background: Rgba) -> bool {
+    0.2126 * background.r + 0.7152 * background.g + 0.0722 * background.b > 0.5
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,6 +77,20 @@ mod tests {
         assert_eq!(format!("#{:x}", packed), color);
     }
 
+    #[test]
+    pub fn test_parse_hex_color() {
+        for color in ["#1d1f21", "1d1f21", "0x1d1f21", "#1D1F21FF"] {
+            assert_eq!(hex(parse_hex_color(color).unwrap()), "#1d1f21ff");
+        }
+        assert!(parse_hex_color("CellForeground").is_err());
+
+        let black = parse_hex_color("#000000").unwrap();
+        let white = parse_hex_color("#ffffff").unwrap();
+        assert_eq!(hex(blend(black, white, 0.5)), "#808080ff");
+        assert_eq!(hex(with_alpha(white, 0.2)), "#ffffff33");
+        assert!(is_light(white) && !is_light(black));
+    }
+
     #[test]
     pub fn test_serialize_color_with_palette() {
         let color = "#b4637aff";
@@ -0,0 +1,401 @@
+//! Imports terminal color palettes from iTerm2, Alacritty and Kitty, deriving
+//! the rest of the theme from the palette as a base16 scheme would.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context as _, Result};
+use gpui::Rgba;
+use serde::Deserialize;
+use theme::ThemeContent;
+
+use crate::ImportReport;
+use crate::base16::Base16Scheme;
+use crate::color::{blend, hex, parse_hex_color};
+
+const ANSI_COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+#[derive(Debug, Default)]
+pub struct TerminalPalette {
+    pub name: Option<String>,
+    background: Option<Rgba>,
+    foreground: Option<Rgba>,
+    bright_foreground: Option<Rgba>,
+    dim_foreground: Option<Rgba>,
+    cursor: Option<Rgba>,
+    selection: Option<Rgba>,
+    /// The 8 ANSI colors, followed by their bright variants.
+    ansi: [Option<Rgba>; 16],
+    dim: [Option<Rgba>; 8],
+}
+
+#[derive(Deserialize)]
+struct ItermColor {
+    #[serde(rename = "Red Component")]
+    red: f32,
+    #[serde(rename = "Green Component")]
+    green: f32,
+    #[serde(rename = "Blue Component")]
+    blue: f32,
+    #[serde(rename = "Alpha Component")]
+    alpha: Option<f32>,
+}
+
+impl TerminalPalette {
+    pub fn parse_iterm(contents: &[u8], report: &mut ImportReport) -> Result<Self> {
+        let colors: BTreeMap<String, ItermColor> =
+            plist::from_bytes(contents).context("failed to parse itermcolors")?;
+
+        let mut palette = Self::default();
+        for (key, color) in colors {
+            let color = Rgba {
+                r: color.red,
+                g: color.green,
+                b: color.blue,
+                a: color.alpha.unwrap_or(1.),
+            };
+            let slot = match key.as_str() {
+                "Background Color" => &mut palette.background,
+                "Foreground Color" => &mut palette.foreground,
+                "Bold Color" => &mut palette.bright_foreground,
+                "Cursor Color" => &mut palette.cursor,
+                "Selection Color" => &mut palette.selection,
+                key => match key
+                    .strip_prefix("Ansi ")
+                    .and_then(|key| key.strip_suffix(" Color"))
+                    .and_then(|ix| ix.parse::<usize>().ok())
+                    .and_then(|ix| palette.ansi.get_mut(ix))
+                {
+                    Some(slot) => slot,
+                    None => {
+                        report.warnings.push(unsupported(key));
+                        continue;
+                    }
+                },
+            };
+            *slot = Some(color);
+        }
+        Ok(palette)
+    }
+
+    pub fn parse_alacritty(contents: &str, report: &mut ImportReport) -> Result<Self> {
+        let config: toml::Table = toml::from_str(contents).context("failed to parse TOML")?;
+        let colors = config
+            .get("colors")
+            .and_then(toml::Value::as_table)
+            .context("missing the [colors] table")?;
+
+        let mut palette = Self::default();
+        for (section_name, section) in colors {
+            let Some(section) = section.as_table() else {
+                report
+                    .warnings
+                    .push(unsupported(&format!("colors.{section_name}")));
+                continue;
+            };
+            for (name, value) in section {
+                let key = format!("colors.{section_name}.{name}");
+                let ansi_ix = ANSI_COLOR_NAMES
+                    .iter()
+                    .position(|ansi| *ansi == name.as_str());
+                let slot = match (section_name.as_str(), name.as_str(), ansi_ix) {
+                    ("primary", "background", _) => &mut palette.background,
+                    ("primary", "foreground", _) => &mut palette.foreground,
+                    ("primary", "bright_foreground", _) => &mut palette.bright_foreground,
+                    ("primary", "dim_foreground", _) => &mut palette.dim_foreground,
+                    ("cursor", "cursor", _) => &mut palette.cursor,
+                    ("selection", "background", _) => &mut palette.selection,
+                    ("normal", _, Some(ix)) => &mut palette.ansi[ix],
+                    ("bright", _, Some(ix)) => &mut palette.ansi[ix + 8],
+                    ("dim", _, Some(ix)) => &mut palette.dim[ix],
+                    _ => {
+                        report.warnings.push(unsupported(&key));
+                        continue;
+                    }
+                };
+                *slot = value
+                    .as_str()
+                    .and_then(|color| parse_color(&key, color, report));
+            }
+        }
+        Ok(palette)
+    }
+
+    pub fn parse_kitty(contents: &str, report: &mut ImportReport) -> Result<Self> {
+        let mut palette = Self::default();
+        for line in contents.lines() {
+            let line = line.trim();
+            // Kitty's bundled themes put their metadata in comments.
+            if let Some(name) = line.strip_prefix("## name:") {
+                palette.name = Some(name.trim().to_string());
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(char::is_whitespace) else {
+                continue;
+            };
+            let slot = match key {
+                "background" => &mut palette.background,
+                "foreground" => &mut palette.foreground,
+                "cursor" => &mut palette.cursor,
+                "selection_background" => &mut palette.selection,
+                key => match key
+                    .strip_prefix("color")
+                    .and_then(|ix| ix.parse::<usize>().ok())
+                    .and_then(|ix| palette.ansi.get_mut(ix))
+                {
+                    Some(slot) => slot,
+                    None => {
+                        report.warnings.push(unsupported(key));
+                        continue;
+                    }
+                },
+            };
+            let value = value.trim();
+            if value != "none" {
+                *slot = parse_color(key, value, report);
+            }
+        }
+        Ok(palette)
+    }
+
+    pub fn convert(
+        &self,
+        fallback_name: String,
+        report: &mut ImportReport,
+    ) -> Result<ThemeContent> {
+        let background = self.background.context("missing the background color")?;
+        let foreground = self.foreground.context("missing the foreground color")?;
+        let mut ansi = [Rgba::default(); 16];
+        for (ix, name) in ANSI_COLOR_NAMES.iter().enumerate() {
+            let normal = self.ansi[ix].with_context(|| format!("missing the {name} color"))?;
+            ansi[ix] = normal;
+            ansi[ix + 8] = self.ansi[ix + 8].unwrap_or_else(|| {
+                report
+                    .warnings
+                    .push(format!("missing the bright {name} color, using {name}"));
+                normal
+            });
+        }
+
+        let [_, red, green, yellow, blue, magenta, cyan, ..] = ansi;
+        let mut scheme = Base16Scheme::new([
+            background,
+            blend(background, foreground, 0.08),
+            self.selection
+                .unwrap_or_else(|| blend(background, foreground, 0.2)),
+            blend(background, foreground, 0.45),
+            blend(background, foreground, 0.7),
+            foreground,
+            foreground,
+            self.bright_foreground.unwrap_or(foreground),
+            red,
+            blend(red, yellow, 0.5),
+            yellow,
+            green,
+            cyan,
+            blue,
+            magenta,
+            blend(red, background, 0.3),
+        ]);
+        scheme.name = self.name.clone();
+        let mut theme = scheme.convert(fallback_name);
+
+        // Keep the palette's own terminal colors, rather than the scheme's.
+        let colors = &mut theme.style.colors;
+        let ansi_slots = [
+            &mut colors.terminal_ansi_black,
+            &mut colors.terminal_ansi_red,
+            &mut colors.terminal_ansi_green,
+            &mut colors.terminal_ansi_yellow,
+            &mut colors.terminal_ansi_blue,
+            &mut colors.terminal_ansi_magenta,
+            &mut colors.terminal_ansi_cyan,
+            &mut colors.terminal_ansi_white,
+            &mut colors.terminal_ansi_bright_black,
+            &mut colors.terminal_ansi_bright_red,
+            &mut colors.terminal_ansi_bright_green,
+            &mut colors.terminal_ansi_bright_yellow,
+            &mut colors.terminal_ansi_bright_blue,
+            &mut colors.terminal_ansi_bright_magenta,
+            &mut colors.terminal_ansi_bright_cyan,
+            &mut colors.terminal_ansi_bright_white,
+        ];
+        for (slot, color) in ansi_slots.into_iter().zip(ansi) {
+            *slot = Some(hex(color));
+        }
+        let dim_slots = [
+            &mut colors.terminal_ansi_dim_black,
+            &mut colors.terminal_ansi_dim_red,
+            &mut colors.terminal_ansi_dim_green,
+            &mut colors.terminal_ansi_dim_yellow,
+            &mut colors.terminal_ansi_dim_blue,
+            &mut colors.terminal_ansi_dim_magenta,
+            &mut colors.terminal_ansi_dim_cyan,
+            &mut colors.terminal_ansi_dim_white,
+            &mut colors.terminal_dim_foreground,
+        ];
+        let dim_colors = self.dim.iter().chain([&self.dim_foreground]);
+        for (slot, color) in dim_slots.into_iter().zip(dim_colors) {
+            if let Some(color) = color {
+                *slot = Some(hex(*color));
+            }
+        }
+
+        if let Some(player) = theme.style.players.first_mut() {
+            if let Some(cursor) = self.cursor {
+                player.cursor = Some(hex(cursor));
+            }
+            if let Some(selection) = self.selection {
+                player.selection = Some(hex(selection));
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+fn parse_color(key: &str, color: &str, report: &mut ImportReport) -> Option<Rgba> {
+    match parse_hex_color(color) {
+        Ok(color) => Some(color),
+        Err(error) => {
+            report.warnings.push(format!("skipped {key}: {error}"));
+            None
+        }
+    }
+}
+
+fn unsupported(key: &str) -> String {
+    format!("'{key}' has no equivalent in Zed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alacritty() {
+        let contents = r#"
+[colors.primary]
+background = '#1d1f21'
+foreground = '#c5c8c6'
+
+[colors.cursor]
+text = 'CellBackground'
+cursor = 'CellForeground'
+
+[colors.normal]
+black = '0x1d1f21'
+red = '#cc6666'
+green = '#b5bd68'
+yellow = '#f0c674'
+blue = '#81a2be'
+magenta = '#b294bb'
+cyan = '#8abeb7'
+white = '#c5c8c6'
+
+[colors.bright]
+black = '#666666'
+red = '#d54e53'
+green = '#b9ca4a'
+yellow = '#e7c547'
+blue = '#7aa6da'
+magenta = '#c397d8'
+cyan = '#70c0b1'
+"#;
+        let mut report = ImportReport::default();
+        let palette = TerminalPalette::parse_alacritty(contents, &mut report).unwrap();
+        let theme = palette.convert("tomorrow".into(), &mut report).unwrap();
+
+        let colors = &theme.style.colors;
+        assert_eq!(colors.terminal_ansi_black.as_deref(), Some("#1d1f21ff"));
+        assert_eq!(
+            colors.terminal_ansi_bright_red.as_deref(),
+            Some("#d54e53ff")
+        );
+        assert_eq!(
+            colors.terminal_ansi_bright_white.as_deref(),
+            Some("#c5c8c6ff")
+        );
+        assert_eq!(colors.editor_background.as_deref(), Some("#1d1f21ff"));
+        assert_eq!(
+            theme.style.syntax["string"].color.as_deref(),
+            Some("#b5bd68ff")
+        );
+        report.warnings.sort();
+        assert_eq!(
+            report.warnings,
+            [
+                "'colors.cursor.text' has no equivalent in Zed",
+                "missing the bright white color, using white",
+                "skipped colors.cursor.cursor: invalid color 'CellForeground'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_kitty() {
+        let mut contents = String::from(
+            "## name: Tokyo Night\n\nbackground #1a1b26\nforeground #c0caf5\ncursor #c0caf5\nurl_color #73daca\n",
+        );
+        for ix in 0..16 {
+            contents.push_str(&format!("color{ix} #0000{ix:02x}\n"));
+        }
+        let mut report = ImportReport::default();
+        let palette = TerminalPalette::parse_kitty(&contents, &mut report).unwrap();
+        let theme = palette.convert("tokyo_night".into(), &mut report).unwrap();
+
+        assert_eq!(theme.name, "Tokyo Night");
+        assert_eq!(
+            theme.style.colors.terminal_ansi_bright_white.as_deref(),
+            Some("#00000fff")
+        );
+        assert_eq!(theme.style.players[0].cursor.as_deref(), Some("#c0caf5ff"));
+        assert_eq!(report.warnings, ["'url_color' has no equivalent in Zed"]);
+    }
+
+    #[test]
+    fn test_iterm() {
+        let mut contents = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>"#,
+        );
+        let mut push_color = |key: &str, red: f32| {
+            contents.push_str(&format!(
+                "<key>{key}</key><dict>\
+                    <key>Color Space</key><string>sRGB</string>\
+                    <key>Red Component</key><real>{red}</real>\
+                    <key>Green Component</key><real>0</real>\
+                    <key>Blue Component</key><real>0</real>\
+                </dict>"
+            ));
+        };
+        push_color("Background Color", 0.);
+        push_color("Foreground Color", 1.);
+        push_color("Badge Color", 1.);
+        for ix in 0..8 {
+            push_color(&format!("Ansi {ix} Color"), 0.5);
+        }
+        contents.push_str("</dict></plist>");
+
+        let mut report = ImportReport::default();
+        let palette = TerminalPalette::parse_iterm(contents.as_bytes(), &mut report).unwrap();
+        let theme = palette.convert("red".into(), &mut report).unwrap();
+
+        assert_eq!(
+            theme.style.colors.terminal_foreground.as_deref(),
+            Some("#ff0000ff")
+        );
+        assert_eq!(
+            theme.style.colors.terminal_ansi_bright_blue.as_deref(),
+            Some("#800000ff")
+        );
+        assert_eq!(report.warnings[0], "'Badge Color' has no equivalent in Zed");
+        assert_eq!(report.warnings.len(), 9);
+    }
+}
@@ -0,0 +1,518 @@
+//! Imports TextMate themes (`.tmTheme`), and the Sublime Text color schemes
+//! (`.sublime-color-scheme`) that replaced them.
+
+use std::collections::HashMap;
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use gpui::Rgba;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use theme::{
+    AppearanceContent, PlayerColorContent, ThemeColorsContent, ThemeContent, ThemeStyleContent,
+};
+
+use crate::ImportReport;
+use crate::color::{hex, is_light, parse_hex_color, with_alpha};
+use crate::vscode::{
+    VsCodeTokenColor, VsCodeTokenColorSettings, VsCodeTokenScope, convert_syntax_theme,
+    syntax_report,
+};
+
+/// A TextMate or Sublime Text theme, with its colors resolved.
+pub struct TextMateTheme {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    /// The editor colors, keyed by their names in Sublime Text color schemes,
+    /// such as `line_highlight`.
+    globals: IndexMap<String, Rgba>,
+    rules: Vec<VsCodeTokenColor>,
+}
+
+#[derive(Deserialize)]
+struct TmTheme {
+    name: Option<String>,
+    author: Option<String>,
+    settings: Vec<TmThemeRule>,
+}
+
+#[derive(Deserialize)]
+struct TmThemeRule {
+    name: Option<String>,
+    scope: Option<String>,
+    #[serde(default)]
+    settings: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct SublimeColorScheme {
+    name: Option<String>,
+    author: Option<String>,
+    #[serde(default)]
+    variables: HashMap<String, String>,
+    #[serde(default)]
+    globals: IndexMap<String, String>,
+    #[serde(default)]
+    rules: Vec<SublimeColorSchemeRule>,
+}
+
+#[derive(Deserialize)]
+struct SublimeColorSchemeRule {
+    name: Option<String>,
+    scope: Option<String>,
+    /// Either a color, or a list of colors for hashed syntax highlighting.
+    foreground: Option<serde_json::Value>,
+    background: Option<String>,
+    font_style: Option<String>,
+}
+
+impl TextMateTheme {
+    pub fn parse_tm_theme(contents: &[u8], report: &mut ImportReport) -> Result<Self> {
+        let tm_theme: TmTheme = plist::from_bytes(contents).context("failed to parse tmTheme")?;
+
+        let mut globals = IndexMap::new();
+        let mut rules = Vec::new();
+        for rule in tm_theme.settings {
+            let Some(scope) = rule.scope else {
+                // The rule without a scope holds the editor colors.
+                for (key, color) in rule.settings {
+                    if let Some(color) = parse_color_setting(&key, &color, report) {
+                        globals.insert(snake_case(&key), color);
+                    }
+                }
+                continue;
+            };
+
+            let mut color = |key: &str| {
+                let color = rule.settings.get(key)?;
+                parse_color_setting(&format!("{scope} {key}"), color, report).map(hex)
+            };
+            let settings = VsCodeTokenColorSettings {
+                foreground: color("foreground"),
+                background: color("background"),
+                font_style: rule.settings.get("fontStyle").cloned(),
+            };
+            rules.push(VsCodeTokenColor {
+                name: rule.name,
+                scope: Some(VsCodeTokenScope::One(scope)),
+                settings,
+            });
+        }
+
+        Ok(Self {
+            name: tm_theme.name,
+            author: tm_theme.author,
+            globals,
+            rules,
+        })
+    }
+
+    pub fn parse_sublime_color_scheme(contents: &[u8], report: &mut ImportReport) -> Result<Self> {
+        let scheme: SublimeColorScheme = serde_json_lenient::from_slice(contents)
+            .context("failed to parse Sublime Text color scheme")?;
+
+        let variables = &scheme.variables;
+        let mut globals = IndexMap::new();
+        for (key, color) in &scheme.globals {
+            if let Some(color) = resolve_color_setting(key, color, variables, report) {
+                globals.insert(key.clone(), color);
+            }
+        }
+
+        let mut rules = Vec::new();
+        for rule in &scheme.rules {
+            let Some(scope) = &rule.scope else {
+                continue;
+            };
+            let foreground = match &rule.foreground {
+                Some(serde_json::Value::String(color)) => {
+                    resolve_color_setting(&format!("{scope} foreground"), color, variables, report)
+                }
+                Some(_) => {
+                    report.warnings.push(format!(
+                        "skipped {scope} foreground: hashed syntax highlighting isn't supported"
+                    ));
+                    None
+                }
+                None => None,
+            };
+            let background = rule.background.as_ref().and_then(|color| {
+                resolve_color_setting(&format!("{scope} background"), color, variables, report)
+            });
+            rules.push(VsCodeTokenColor {
+                name: rule.name.clone(),
+                scope: Some(VsCodeTokenScope::One(scope.clone())),
+                settings: VsCodeTokenColorSettings {
+                    foreground: foreground.map(hex),
+                    background: background.map(hex),
+                    font_style: rule.font_style.clone(),
+                },
+            });
+        }
+
+        Ok(Self {
+            name: scheme.name,
+            author: scheme.author,
+            globals,
+            rules,
+        })
+    }
+
+    pub fn convert(self, fallback_name: String, report: &mut ImportReport) -> ThemeContent {
+        let global = |key: &str| self.globals.get(key).copied();
+        let color = |key: &str| global(key).map(hex);
+
+        let background = color("background");
+        let foreground = color("foreground");
+        let gutter = color("gutter").or(background.clone());
+        let accent = color("accent");
+        let appearance = match global("background") {
+            Some(background) if is_light(background) => AppearanceContent::Light,
+            _ => AppearanceContent::Dark,
+        };
+
+        let colors = ThemeColorsContent {
+            background: background.clone(),
+            editor_background: background.clone(),
+            editor_gutter_background: gutter,
+            panel_background: background.clone(),
+            tab_active_background: background.clone(),
+            toolbar_background: background.clone(),
+            scrollbar_track_background: background,
+            text: foreground.clone(),
+            editor_foreground: foreground.clone(),
+            icon: foreground.clone(),
+            editor_active_line_number: foreground,
+            editor_line_number: color("gutter_foreground"),
+            editor_active_line_background: color("line_highlight"),
+            element_selection_background: color("selection"),
+            search_match_background: color("find_highlight")
+                .or_else(|| global("highlight").map(|highlight| hex(with_alpha(highlight, 0.4)))),
+            editor_invisible: color("invisibles"),
+            editor_indent_guide: color("guide"),
+            editor_wrap_guide: color("guide"),
+            editor_indent_guide_active: color("active_guide"),
+            editor_active_wrap_guide: color("active_guide"),
+            text_accent: accent.clone(),
+            icon_accent: accent.clone(),
+            border_focused: accent,
+            ..Default::default()
+        };
+
+        let mut players = Vec::new();
+        if global("caret").is_some() || global("selection").is_some() {
+            players.push(PlayerColorContent {
+                cursor: color("caret"),
+                background: color("caret"),
+                selection: color("selection"),
+            });
+        }
+
+        for key in self.globals.keys() {
+            if !SUPPORTED_GLOBALS.contains(&key.as_str()) {
+                report
+                    .warnings
+                    .push(format!("the '{key}' color has no equivalent in Zed"));
+            }
+        }
+
+        let overrides = IndexMap::new();
+        let syntax_report = syntax_report(&self.rules, &overrides);
+        report.unmapped_scopes.extend(syntax_report.unmapped_scopes);
+        report
+            .missing_syntax_tokens
+            .extend(syntax_report.missing_syntax_tokens);
+
+        ThemeContent {
+            name: self.name.unwrap_or(fallback_name),
+            appearance,
+            style: ThemeStyleContent {
+                window_background_appearance: Some(theme::WindowBackgroundContent::Opaque),
+                accents: Vec::new(),
+                colors,
+                status: Default::default(),
+                players,
+                syntax: convert_syntax_theme(&self.rules, &overrides),
+            },
+        }
+    }
+}
+
+const SUPPORTED_GLOBALS: &[&str] = &[
+    "background",
+    "foreground",
+    "gutter",
+    "gutter_foreground",
+    "accent",
+    "caret",
+    "selection",
+    "line_highlight",
+    "find_highlight",
+    "highlight",
+    "invisibles",
+    "guide",
+    "active_guide",
+];
+
+fn parse_color_setting(key: &str, color: &str, report: &mut ImportReport) -> Option<Rgba> {
+    match parse_hex_color(color) {
+        Ok(color) => Some(color),
+        Err(error) => {
+            report.warnings.push(format!("skipped {key}: {error}"));
+            None
+        }
+    }
+}
+
+fn resolve_color_setting(
+    key: &str,
+    color: &str,
+    variables: &HashMap<String, String>,
+    report: &mut ImportReport,
+) -> Option<Rgba> {
+    match resolve_sublime_color(color, variables) {
+        Ok(color) => Some(color),
+        Err(error) => {
+            report.warnings.push(format!("skipped {key}: {error}"));
+            None
+        }
+    }
+}
+
+/// Converts tmTheme setting names, like `lineHighlight`, to the names used by
+/// Sublime Text color schemes.
+fn snake_case(key: &str) -> String {
+    let mut snake_case = String::with_capacity(key.len());
+    for char in key.chars() {
+        if char.is_ascii_uppercase() {
+            snake_case.push('_');
+        }
+        snake_case.push(char.to_ascii_lowercase());
+    }
+    snake_case
+}
+
+/// Resolves a color in a Sublime Text color scheme, which may refer to the
+/// scheme's variables, or be written as a CSS color function.
+fn resolve_sublime_color(color: &str, variables: &HashMap<String, String>) -> Result<Rgba> {
+    resolve_sublime_color_inner(color, variables, 0)
+}
+
+fn resolve_sublime_color_inner(
+    color: &str,
+    variables: &HashMap<String, String>,
+    depth: usize,
+) -> Result<Rgba> {
+    if depth > 16 {
+        bail!("variables refer to each other in a cycle");
+    }
+    let color = color.trim();
+
+    if let Some(name) = function_args(color, "var") {
+        let value = variables
+            .get(name.trim())
+            .ok_or_else(|| anyhow!("unknown variable '{name}'"))?;
+        return resolve_sublime_color_inner(value, variables, depth + 1);
+    }
+
+    if let Some(args) = function_args(color, "color") {
+        // Only the alpha adjuster is supported, as in `color(var(red) alpha(0.5))`.
+        let (base, adjuster) = args
+            .rsplit_once(' ')
+            .ok_or_else(|| anyhow!("unsupported color '{color}'"))?;
+        let alpha = function_args(adjuster, "alpha")
+            .or_else(|| function_args(adjuster, "a"))
+            .ok_or_else(|| anyhow!("unsupported color adjuster '{adjuster}'"))?;
+        let base = resolve_sublime_color_inner(base, variables, depth + 1)?;
+        return Ok(with_alpha(base, parse_alpha(alpha)?));
+    }
+
+    for function in ["rgba", "rgb"] {
+        if let Some(args) = function_args(color, function) {
+            let components = split_args(args);
+            let [r, g, b, rest @ ..] = components.as_slice() else {
+                bail!("expected 3 or 4 components in '{color}'");
+            };
+            let channel = |value: &str| -> Result<f32> { Ok(value.parse::<f32>()? / 255.) };
+            return Ok(Rgba {
+                r: channel(r)?,
+                g: channel(g)?,
+                b: channel(b)?,
+                a: rest.first().map_or(Ok(1.), |alpha| parse_alpha(alpha))?,
+            });
+        }
+    }
+
+    for function in ["hsla", "hsl"] {
+        if let Some(args) = function_args(color, function) {
+            let components = split_args(args);
+            let [h, s, l, rest @ ..] = components.as_slice() else {
+                bail!("expected 3 or 4 components in '{color}'");
+            };
+            let hue = h.trim_end_matches("deg").parse::<f32>()?.rem_euclid(360.);
+            return Ok(gpui::hsla(
+                hue / 360.,
+                parse_alpha(s)?,
+                parse_alpha(l)?,
+                rest.first().map_or(Ok(1.), |alpha| parse_alpha(alpha))?,
+            )
+            .into());
+        }
+    }
+
+    parse_hex_color(color)
+}
+
+/// Returns the arguments of `function(...)`.
+fn function_args<'a>(color: &'a str, function: &str) -> Option<&'a str> {
+    color
+        .strip_prefix(function)?
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+fn split_args(args: &str) -> Vec<&str> {
+    args.split([',', ' '])
+        .map(str::trim)
+        .filter(|arg| !arg.is_empty())
+        .collect()
+}
+
+/// Parses an alpha or percentage, such as `0.5` or `50%`.
+fn parse_alpha(value: &str) -> Result<f32> {
+    let value = value.trim();
+    Ok(match value.strip_suffix('%') {
+        Some(percentage) => percentage.parse::<f32>()? / 100.,
+        None => value.parse::<f32>()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tm_theme() {
+        let contents = r##"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>name</key>
+    <string>Monokai</string>
+    <key>settings</key>
+    <array>
+        <dict>
+            <key>settings</key>
+            <dict>
+                <key>background</key>
+                <string>#272822</string>
+                <key>foreground</key>
+                <string>#F8F8F2</string>
+                <key>lineHighlight</key>
+                <string>#3E3D32</string>
+                <key>bracketsForeground</key>
+                <string>#F8F8F2A5</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>name</key>
+            <string>Comment</string>
+            <key>scope</key>
+            <string>comment</string>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#75715E</string>
+                <key>fontStyle</key>
+                <string>italic</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>scope</key>
+            <string>invalid.deprecated</string>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#F8F8F0</string>
+            </dict>
+        </dict>
+    </array>
+</dict>
+</plist>"##;
+
+        let mut report = ImportReport::default();
+        let theme = TextMateTheme::parse_tm_theme(contents.as_bytes(), &mut report).unwrap();
+        let theme = theme.convert("monokai".into(), &mut report);
+
+        assert_eq!(theme.name, "Monokai");
+        assert_eq!(theme.appearance, AppearanceContent::Dark);
+        let colors = &theme.style.colors;
+        assert_eq!(colors.editor_background.as_deref(), Some("#272822ff"));
+        assert_eq!(
+            colors.editor_active_line_background.as_deref(),
+            Some("#3e3d32ff")
+        );
+        let comment = &theme.style.syntax["comment"];
+        assert_eq!(comment.color.as_deref(), Some("#75715eff"));
+        assert_eq!(comment.font_style, Some(theme::FontStyleContent::Italic));
+
+        assert_eq!(report.unmapped_scopes, ["invalid.deprecated"]);
+        assert!(
+            report
+                .missing_syntax_tokens
+                .contains(&"keyword".to_string())
+        );
+        assert_eq!(
+            report.warnings,
+            ["the 'brackets_foreground' color has no equivalent in Zed"]
+        );
+    }
+
+    #[test]
+    fn test_sublime_color_scheme() {
+        let contents = r##"{
+            // Comments are allowed.
+            "name": "Mariana",
+            "variables": {
+                "blue": "hsl(210, 50%, 60%)",
+                "white": "rgb(255, 255, 255)",
+                "selection": "color(var(blue) alpha(0.5))",
+            },
+            "globals": {
+                "background": "#fafafa",
+                "foreground": "var(missing)",
+                "selection": "var(selection)",
+            },
+            "rules": [
+                { "scope": "keyword", "foreground": "var(blue)", "font_style": "bold" },
+                { "scope": "string", "foreground": ["#f00", "#0f0"] },
+            ],
+        }"##;
+
+        let mut report = ImportReport::default();
+        let theme =
+            TextMateTheme::parse_sublime_color_scheme(contents.as_bytes(), &mut report).unwrap();
+        let theme = theme.convert("mariana".into(), &mut report);
+
+        assert_eq!(theme.appearance, AppearanceContent::Light);
+        assert_eq!(
+            theme.style.players[0].selection.as_deref(),
+            Some("#6699cc80")
+        );
+        let keyword = &theme.style.syntax["keyword"];
+        assert_eq!(keyword.color.as_deref(), Some("#6699ccff"));
+        assert_eq!(keyword.font_weight, Some(theme::FontWeightContent::Bold));
+        assert_eq!(
+            report.warnings,
+            [
+                "skipped foreground: unknown variable 'missing'",
+                "skipped string foreground: hashed syntax highlighting isn't supported",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snake_case() {
+        assert_eq!(snake_case("lineHighlight"), "line_highlight");
+        assert_eq!(snake_case("background"), "background");
+    }
+}
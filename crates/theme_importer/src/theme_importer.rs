@@ -0,0 +1,228 @@
+//! Converts themes from other editors and terminals into Zed theme families.
+
+mod base16;
+mod color;
+mod terminal;
+mod textmate;
+mod vscode;
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use indexmap::IndexMap;
+use serde::Deserialize;
+use theme::{Appearance, AppearanceContent, ThemeFamilyContent};
+
+use crate::vscode::{VsCodeTheme, VsCodeThemeConverter};
+
+const ZED_THEME_SCHEMA_URL: &str = "https://zed.dev/schema/themes/v0.2.0.json";
+
+#[derive(Debug, Deserialize)]
+pub struct FamilyMetadata {
+    pub name: String,
+    pub author: String,
+    pub themes: Vec<ThemeMetadata>,
+
+    /// Overrides for specific syntax tokens.
+    ///
+    /// Use this to ensure certain Zed syntax tokens are matched
+    /// to an exact set of scopes when it is not otherwise possible
+    /// to rely on the default mappings in the theme importer.
+    #[serde(default)]
+    pub syntax: IndexMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeAppearanceJson {
+    Light,
+    Dark,
+}
+
+impl From<ThemeAppearanceJson> for AppearanceContent {
+    fn from(value: ThemeAppearanceJson) -> Self {
+        match value {
+            ThemeAppearanceJson::Light => Self::Light,
+            ThemeAppearanceJson::Dark => Self::Dark,
+        }
+    }
+}
+
+impl From<ThemeAppearanceJson> for Appearance {
+    fn from(value: ThemeAppearanceJson) -> Self {
+        match value {
+            ThemeAppearanceJson::Light => Self::Light,
+            ThemeAppearanceJson::Dark => Self::Dark,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ThemeMetadata {
+    pub name: String,
+    pub file_name: String,
+    pub appearance: ThemeAppearanceJson,
+}
+
+/// The formats that themes can be imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ThemeFormat {
+    /// A VS Code color theme (`.json`).
+    VsCode,
+    /// A TextMate theme (`.tmTheme`).
+    TextMate,
+    /// A Sublime Text color scheme (`.sublime-color-scheme`).
+    SublimeColorScheme,
+    /// A base16 or base24 scheme (`.yaml`).
+    Base16,
+    /// An iTerm2 color preset (`.itermcolors`).
+    Iterm,
+    /// An Alacritty color scheme (`.toml`).
+    Alacritty,
+    /// A Kitty theme (`.conf`).
+    Kitty,
+}
+
+impl ThemeFormat {
+    /// Guesses the format of a theme from its file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "json" | "jsonc" => Some(Self::VsCode),
+            "tmtheme" => Some(Self::TextMate),
+            "sublime-color-scheme" => Some(Self::SublimeColorScheme),
+            "yaml" | "yml" => Some(Self::Base16),
+            "itermcolors" => Some(Self::Iterm),
+            "toml" => Some(Self::Alacritty),
+            "conf" => Some(Self::Kitty),
+            _ => None,
+        }
+    }
+}
+
+/// What couldn't be carried over when importing a theme.
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportReport {
+    /// The scopes styled by the theme that no Zed syntax token was mapped to.
+    pub unmapped_scopes: Vec<String>,
+    /// The Zed syntax tokens that the theme has no style for.
+    pub missing_syntax_tokens: Vec<String>,
+    /// Colors and settings that were skipped.
+    pub warnings: Vec<String>,
+}
+
+impl ImportReport {
+    pub fn is_empty(&self) -> bool {
+        self.unmapped_scopes.is_empty()
+            && self.missing_syntax_tokens.is_empty()
+            && self.warnings.is_empty()
+    }
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections = [
+            ("Unmapped scopes", &self.unmapped_scopes),
+            ("Syntax tokens without a style", &self.missing_syntax_tokens),
+            ("Warnings", &self.warnings),
+        ];
+        for (title, items) in sections {
+            if items.is_empty() {
+                continue;
+            }
+            writeln!(f, "{title}:")?;
+            for item in items {
+                writeln!(f, "  {item}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A theme family converted from another format.
+pub struct ImportedThemeFamily {
+    pub family: ThemeFamilyContent,
+    pub report: ImportReport,
+}
+
+impl ImportedThemeFamily {
+    /// Serializes the theme family, with a reference to the theme schema.
+    pub fn to_json(&self) -> Result<String> {
+        let mut family = serde_json::to_value(&self.family)?;
+        if let Some(family) = family.as_object_mut() {
+            family.insert(
+                "$schema".to_string(),
+                serde_json::Value::String(ZED_THEME_SCHEMA_URL.to_string()),
+            );
+        }
+        Ok(serde_json::to_string_pretty(&family)?)
+    }
+}
+
+/// Converts the theme at `path` into a Zed theme family.
+pub fn import_theme(path: &Path, format: ThemeFormat) -> Result<ImportedThemeFamily> {
+    let contents = std::fs::read(path).with_context(|| format!("failed to read theme {path:?}"))?;
+    let fallback_name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut report = ImportReport::default();
+    let (theme, author) = match format {
+        ThemeFormat::VsCode => {
+            let vscode_theme: VsCodeTheme = serde_json_lenient::from_slice(&contents)
+                .with_context(|| format!("failed to parse theme {path:?}"))?;
+            let author = vscode_theme.author.clone();
+            let theme_metadata = ThemeMetadata {
+                name: vscode_theme.name.clone().unwrap_or(fallback_name),
+                appearance: ThemeAppearanceJson::Dark,
+                file_name: "".to_string(),
+            };
+            let converter =
+                VsCodeThemeConverter::new(vscode_theme, theme_metadata, IndexMap::new());
+            report = converter.report();
+            (converter.convert()?, author)
+        }
+        ThemeFormat::TextMate | ThemeFormat::SublimeColorScheme => {
+            let theme = if format == ThemeFormat::TextMate {
+                textmate::TextMateTheme::parse_tm_theme(&contents, &mut report)?
+            } else {
+                textmate::TextMateTheme::parse_sublime_color_scheme(&contents, &mut report)?
+            };
+            let author = theme.author.clone();
+            (theme.convert(fallback_name, &mut report), author)
+        }
+        ThemeFormat::Base16 => {
+            let scheme = base16::Base16Scheme::parse(utf8(&contents)?, &mut report)?;
+            let author = scheme.author.clone();
+            (scheme.convert(fallback_name), author)
+        }
+        ThemeFormat::Iterm | ThemeFormat::Alacritty | ThemeFormat::Kitty => {
+            let palette = match format {
+                ThemeFormat::Iterm => {
+                    terminal::TerminalPalette::parse_iterm(&contents, &mut report)
+                }
+                ThemeFormat::Alacritty => {
+                    terminal::TerminalPalette::parse_alacritty(utf8(&contents)?, &mut report)
+                }
+                _ => terminal::TerminalPalette::parse_kitty(utf8(&contents)?, &mut report),
+            }
+            .with_context(|| format!("failed to parse terminal colors {path:?}"))?;
+            (palette.convert(fallback_name, &mut report)?, None)
+        }
+    };
+
+    Ok(ImportedThemeFamily {
+        family: ThemeFamilyContent {
+            name: theme.name.clone(),
+            author: author.unwrap_or_default(),
+            themes: vec![theme],
+        },
+        report,
+    })
+}
+
+fn utf8(contents: &[u8]) -> Result<&str> {
+    std::str::from_utf8(contents).context("theme is not valid UTF-8")
+}
@@ -1,13 +1,13 @@
 use anyhow::Result;
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 use strum::IntoEnumIterator;
 use theme::{
     FontStyleContent, FontWeightContent, HighlightStyleContent, StatusColorsContent,
     ThemeColorsContent, ThemeContent, ThemeStyleContent,
 };
 
-use crate::ThemeMetadata;
-use crate::vscode::{VsCodeTheme, VsCodeTokenScope};
+use crate::vscode::{VsCodeTheme, VsCodeTokenColor, VsCodeTokenScope};
+use crate::{ImportReport, ThemeMetadata};
 
 use super::ZedSyntaxToken;
 
@@ -214,65 +214,138 @@ impl VsCodeThemeConverter {
     }
 
     fn convert_syntax_theme(&self) -> Result<IndexMap<String, HighlightStyleContent>> {
-        let mut highlight_styles = IndexMap::new();
+        Ok(convert_syntax_theme(
+            &self.theme.token_colors,
+            &self.syntax_overrides,
+        ))
+    }
 
-        for syntax_token in ZedSyntaxToken::iter() {
-            let override_match = self
-                .syntax_overrides
-                .get(&syntax_token.to_string())
-                .and_then(|scope| {
-                    self.theme.token_colors.iter().find(|token_color| {
-                        token_color.scope == Some(VsCodeTokenScope::Many(scope.clone()))
-                    })
-                });
+    /// Reports the token colors that weren't carried over to the theme.
+    pub fn report(&self) -> ImportReport {
+        syntax_report(&self.theme.token_colors, &self.syntax_overrides)
+    }
+}
 
-            let best_match = override_match
-                .or_else(|| syntax_token.find_best_token_color_match(&self.theme.token_colors))
-                .or_else(|| {
-                    syntax_token.fallbacks().iter().find_map(|fallback| {
-                        fallback.find_best_token_color_match(&self.theme.token_colors)
-                    })
-                });
+fn matching_token_color<'a>(
+    syntax_token: ZedSyntaxToken,
+    token_colors: &'a [VsCodeTokenColor],
+    syntax_overrides: &IndexMap<String, Vec<String>>,
+) -> Option<&'a VsCodeTokenColor> {
+    let override_match = syntax_overrides
+        .get(&syntax_token.to_string())
+        .and_then(|scope| {
+            token_colors.iter().find(|token_color| {
+                token_color.scope == Some(VsCodeTokenScope::Many(scope.clone()))
+            })
+        });
 
-            let Some(token_color) = best_match else {
-                log::warn!("No matching token color found for '{syntax_token}'");
-                continue;
-            };
+    override_match
+        .or_else(|| syntax_token.find_best_token_color_match(token_colors))
+        .or_else(|| {
+            syntax_token
+                .fallbacks()
+                .iter()
+                .find_map(|fallback| fallback.find_best_token_color_match(token_colors))
+        })
+}
 
-            log::info!(
-                "Matched '{syntax_token}' to '{}'",
-                token_color
-                    .name
-                    .clone()
-                    .or_else(|| token_color
-                        .scope
-                        .as_ref()
-                        .map(|scope| format!("{:?}", scope)))
-                    .unwrap_or_else(|| "no identifier".to_string())
-            );
+/// Picks a style for each Zed syntax token from TextMate-scoped token colors.
+pub(crate) fn convert_syntax_theme(
+    token_colors: &[VsCodeTokenColor],
+    syntax_overrides: &IndexMap<String, Vec<String>>,
+) -> IndexMap<String, HighlightStyleContent> {
+    let mut highlight_styles = IndexMap::new();
 
-            let highlight_style = HighlightStyleContent {
-                color: token_color.settings.foreground.clone(),
-                background_color: token_color.settings.background.clone(),
-                font_style: token_color
-                    .settings
-                    .font_style
-                    .as_ref()
-                    .and_then(|style| try_parse_font_style(style)),
-                font_weight: token_color
-                    .settings
-                    .font_style
+    for syntax_token in ZedSyntaxToken::iter() {
+        let Some(token_color) = matching_token_color(syntax_token, token_colors, syntax_overrides)
+        else {
+            log::warn!("No matching token color found for '{syntax_token}'");
+            continue;
+        };
+
+        log::info!(
+            "Matched '{syntax_token}' to '{}'",
+            token_color
+                .name
+                .clone()
+                .or_else(|| token_color
+                    .scope
                     .as_ref()
-                    .and_then(|style| try_parse_font_weight(style)),
-            };
+                    .map(|scope| format!("{:?}", scope)))
+                .unwrap_or_else(|| "no identifier".to_string())
+        );
 
-            if highlight_style.is_empty() {
-                continue;
-            }
+        let highlight_style = HighlightStyleContent {
+            color: token_color.settings.foreground.clone(),
+            background_color: token_color.settings.background.clone(),
+            font_style: token_color
+                .settings
+                .font_style
+                .as_ref()
+                .and_then(|style| try_parse_font_style(style)),
+            font_weight: token_color
+                .settings
+                .font_style
+                .as_ref()
+                .and_then(|style| try_parse_font_weight(style)),
+        };
+
+        if highlight_style.is_empty() {
+            continue;
+        }
+
+        highlight_styles.insert(syntax_token.to_string(), highlight_style);
+    }
+
+    highlight_styles
+}
+
+/// Lists the scopes of the token colors that no Zed syntax token was matched
+/// to, and the syntax tokens that weren't matched to any token color.
+pub(crate) fn syntax_report(
+    token_colors: &[VsCodeTokenColor],
+    syntax_overrides: &IndexMap<String, Vec<String>>,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+    let mut matched = vec![false; token_colors.len()];
+    let mut unmapped_scopes = IndexSet::new();
 
-            highlight_styles.insert(syntax_token.to_string(), highlight_style);
+    for syntax_token in ZedSyntaxToken::iter() {
+        match matching_token_color(syntax_token, token_colors, syntax_overrides) {
+            Some(token_color) => {
+                if let Some(ix) = token_colors
+                    .iter()
+                    .position(|candidate| std::ptr::eq(candidate, token_color))
+                {
+                    matched[ix] = true;
+                }
+            }
+            // Tokens without TextMate scopes, like `hint`, can't be matched.
+            None if !syntax_token.to_vscode().is_empty() => {
+                report.missing_syntax_tokens.push(syntax_token.to_string());
+            }
+            None => {}
         }
+    }
 
-        Ok(highlight_styles)
+    for (token_color, matched) in token_colors.iter().zip(matched) {
+        if matched {
+            continue;
+        }
+        let scopes = match &token_color.scope {
+            Some(VsCodeTokenScope::One(scope)) => vec![scope.as_str()],
+            Some(VsCodeTokenScope::Many(scopes)) => scopes.iter().map(String::as_str).collect(),
+            None => continue,
+        };
+        unmapped_scopes.extend(
+            scopes
+                .into_iter()
+                .flat_map(|scope| scope.split(','))
+                .map(|scope| scope.trim().to_string())
+                .filter(|scope| !scope.is_empty()),
+        );
     }
+    report.unmapped_scopes = unmapped_scopes.into_iter().collect();
+
+    report
 }
@@ -190,7 +190,7 @@ impl ZedSyntaxToken {
         }
     }
 
-    fn to_vscode(self) -> Vec<&'static str> {
+    pub(crate) fn to_vscode(self) -> Vec<&'static str> {
         match self {
             ZedSyntaxToken::Attribute => vec!["entity.other.attribute-name"],
             ZedSyntaxToken::Boolean => vec!["constant.language"],
@@ -0,0 +1,567 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use editor::Editor;
+use gpui::{Action, App, AppContext as _, Context, Entity, Window};
+use util::paths::PathMatcher;
+use workspace::{
+    ItemHandle, OpenOptions, OpenVisible, Pane, SaveIntent, Toast, Workspace,
+    notifications::{NotificationId, NotifyResultExt, NotifyTaskExt},
+};
+
+use crate::{Vim, command::command_interceptor, state::VimGlobals};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ArgTarget {
+    Next,
+    Previous,
+    First,
+    Last,
+}
+
+/// Replaces the argument list, or shows it when no files are given.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct VimArgs {
+    pub(crate) files: Vec<String>,
+}
+
+/// Adds files to the argument list after the current entry.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct VimArgAdd {
+    pub(crate) files: Vec<String>,
+}
+
+/// Edits another file from the argument list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct VimArgNavigate {
+    pub(crate) target: ArgTarget,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DoScope {
+    Buffers,
+    Windows,
+    Args,
+}
+
+/// Runs an ex command in every buffer, window or argument list file.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct VimDo {
+    pub(crate) scope: DoScope,
+    pub(crate) force: bool,
+    pub(crate) command: String,
+}
+
+/// The files a workspace was asked to edit, like Vim's argument list.
+#[derive(Clone, Debug, Default)]
+pub struct ArgList {
+    paths: Vec<PathBuf>,
+    current: usize,
+}
+
+impl ArgList {
+    fn replace(&mut self, paths: Vec<PathBuf>) {
+        self.paths = paths;
+        self.current = 0;
+    }
+
+    fn add(&mut self, paths: Vec<PathBuf>) {
+        let index = if self.paths.is_empty() {
+            0
+        } else {
+            self.current + 1
+        };
+        self.paths.splice(index..index, paths);
+    }
+
+    fn navigate(&mut self, target: ArgTarget) -> Result<PathBuf> {
+        let index = match target {
+            ArgTarget::Next => self.current + 1,
+            ArgTarget::Previous => self
+                .current
+                .checked_sub(1)
+                .ok_or_else(|| anyhow!("E164: Cannot go before first file"))?,
+            ArgTarget::First => 0,
+            ArgTarget::Last => self.paths.len().saturating_sub(1),
+        };
+        let path = self
+            .paths
+            .get(index)
+            .cloned()
+            .ok_or_else(|| anyhow!("E165: Cannot go beyond last file"))?;
+        self.current = index;
+        Ok(path)
+    }
+
+    fn current_path(&self) -> Option<PathBuf> {
+        self.paths.get(self.current).cloned()
+    }
+}
+
+/// Seeds the argument list of a workspace with the files it was opened with.
+pub fn set_arg_list(workspace: &Entity<Workspace>, paths: Vec<PathBuf>, cx: &mut App) {
+    if !cx.has_global::<VimGlobals>() {
+        return;
+    }
+    arg_list_mut(workspace, cx).replace(paths);
+}
+
+fn arg_list_mut<'a>(workspace: &Entity<Workspace>, cx: &'a mut App) -> &'a mut ArgList {
+    let workspace_id = workspace.entity_id();
+    if !Vim::globals(cx).arg_lists.contains_key(&workspace_id) {
+        cx.observe_release(workspace, move |_, cx| {
+            Vim::update_globals(cx, |globals, _| {
+                globals.arg_lists.remove(&workspace_id);
+            })
+        })
+        .detach();
+    }
+    Vim::globals(cx).arg_lists.entry(workspace_id).or_default()
+}
+
+fn resolve_files(workspace: &Workspace, files: &[String], cx: &App) -> Result<Vec<PathBuf>> {
+    let project = workspace.project().read(cx);
+    let cwd = project.first_project_directory(cx);
+    let mut paths = Vec::new();
+    for file in files {
+        if !file.contains(['*', '?', '[', '{']) {
+            let path = Path::new(file);
+            paths.push(match &cwd {
+                Some(cwd) if path.is_relative() => cwd.join(path),
+                _ => path.to_path_buf(),
+            });
+            continue;
+        }
+        let matcher = PathMatcher::new([file])?;
+        let mut matches = Vec::new();
+        for worktree in project.visible_worktrees(cx) {
+            let worktree = worktree.read(cx);
+            matches.extend(
+                worktree
+                    .files(false, 0)
+                    .filter(|entry| matcher.is_match(&entry.path))
+                    .map(|entry| worktree.abs_path().join(&entry.path)),
+            );
+        }
+        if matches.is_empty() {
+            return Err(anyhow!("E480: No match: {file}"));
+        }
+        matches.sort();
+        paths.extend(matches);
+    }
+    Ok(paths)
+}
+
+fn edit_path(
+    workspace: &mut Workspace,
+    path: PathBuf,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    workspace
+        .open_abs_path(
+            path,
+            OpenOptions {
+                visible: Some(OpenVisible::None),
+                ..Default::default()
+            },
+            window,
+            cx,
+        )
+        .detach_and_notify_err(window, cx);
+}
+
+fn active_path(workspace: &Workspace, cx: &App) -> Option<PathBuf> {
+    let project_path = workspace.active_item(cx)?.project_path(cx)?;
+    workspace
+        .project()
+        .read(cx)
+        .absolute_path(&project_path, cx)
+}
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
+    Vim::action(editor, cx, |vim, action: &VimArgs, window, cx| {
+        vim.args(action, window, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &VimArgAdd, window, cx| {
+        vim.arg_add(action, window, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &VimArgNavigate, window, cx| {
+        vim.arg_navigate(action, window, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &VimDo, window, cx| {
+        vim.vim_do(action, window, cx)
+    });
+}
+
+impl Vim {
+    fn args(&mut self, action: &VimArgs, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let workspace_entity = cx.entity();
+            if action.files.is_empty() {
+                let cwd = workspace.project().read(cx).first_project_directory(cx);
+                let arg_list = arg_list_mut(&workspace_entity, cx);
+                let message = arg_list
+                    .paths
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, path)| {
+                        let path = cwd
+                            .as_ref()
+                            .and_then(|cwd| path.strip_prefix(cwd).ok())
+                            .unwrap_or(path)
+                            .to_string_lossy();
+                        if ix == arg_list.current {
+                            format!("[{path}]")
+                        } else {
+                            path.into_owned()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                workspace.show_toast(
+                    Toast::new(NotificationId::unique::<ArgList>(), message).autohide(),
+                    cx,
+                );
+                return;
+            }
+            let Some(paths) = resolve_files(workspace, &action.files, cx).notify_err(workspace, cx)
+            else {
+                return;
+            };
+            let arg_list = arg_list_mut(&workspace_entity, cx);
+            arg_list.replace(paths);
+            if let Some(path) = arg_list.current_path() {
+                edit_path(workspace, path, window, cx);
+            }
+        });
+    }
+
+    fn arg_add(&mut self, action: &VimArgAdd, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let paths = if action.files.is_empty() {
+                active_path(workspace, cx).into_iter().collect()
+            } else {
+                let Some(paths) =
+                    resolve_files(workspace, &action.files, cx).notify_err(workspace, cx)
+                else {
+                    return;
+                };
+                paths
+            };
+            arg_list_mut(&cx.entity(), cx).add(paths);
+        });
+    }
+
+    fn arg_navigate(
+        &mut self,
+        action: &VimArgNavigate,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let path = arg_list_mut(&cx.entity(), cx).navigate(action.target);
+            if let Some(path) = path.notify_err(workspace, cx) {
+                edit_path(workspace, path, window, cx);
+            }
+        });
+    }
+
+    fn vim_do(&mut self, action: &VimDo, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let Some(command) = command_interceptor(&action.command, cx)
+            .first()
+            .map(|result| result.action.boxed_clone())
+        else {
+            workspace.update(cx, |workspace, cx| {
+                Err::<(), _>(anyhow!("E492: Not an editor command: {}", action.command))
+                    .notify_err(workspace, cx);
+            });
+            return;
+        };
+
+        let mut items: Vec<Box<dyn ItemHandle>> = Vec::new();
+        let mut paths = Vec::new();
+        match action.scope {
+            DoScope::Buffers => {
+                let mut buffers = Vec::new();
+                for editor in workspace.read(cx).items_of_type::<Editor>(cx) {
+                    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+                        continue;
+                    };
+                    if !buffers.contains(&buffer) {
+                        buffers.push(buffer);
+                        items.push(Box::new(editor));
+                    }
+                }
+            }
+            DoScope::Windows => {
+                for pane in workspace.read(cx).panes() {
+                    let active_item = pane.read(cx).active_item();
+                    if let Some(editor) = active_item.and_then(|item| item.downcast::<Editor>()) {
+                        items.push(Box::new(editor));
+                    }
+                }
+            }
+            DoScope::Args => {
+                if let Some(arg_list) = Vim::globals(cx).arg_lists.get(&workspace.entity_id()) {
+                    paths = arg_list.paths.clone();
+                }
+            }
+        }
+        let scope = action.scope;
+        let force = action.force;
+        let hidden = Vim::globals(cx).hidden;
+        let autowrite = Vim::globals(cx).autowrite;
+
+        cx.spawn_in(window, async move |_, cx| {
+            let count = items.len() + paths.len();
+            let mut items = items.into_iter();
+            let mut paths = paths.into_iter().enumerate();
+            for ix in 0..count {
+                let item = if let Some(item) = items.next() {
+                    workspace.update_in(cx, |workspace, window, cx| {
+                        workspace.activate_item(&*item, true, true, window, cx);
+                    })?;
+                    item
+                } else if let Some((arg_ix, path)) = paths.next() {
+                    let open = workspace.update_in(cx, |workspace, window, cx| {
+                        arg_list_mut(&cx.entity(), cx).current = arg_ix;
+                        workspace.open_abs_path(
+                            path,
+                            OpenOptions {
+                                visible: Some(OpenVisible::None),
+                                ..Default::default()
+                            },
+                            window,
+                            cx,
+                        )
+                    })?;
+                    open.await?
+                } else {
+                    break;
+                };
+
+                cx.update(|window, cx| {
+                    // The target was just focused, so draw to make it receive the dispatch.
+                    window.draw(cx).clear();
+                    window.dispatch_action(command.boxed_clone(), cx);
+                })?;
+
+                // Windows stay visible, so only buffers that are left behind need saving.
+                let is_last = ix + 1 == count;
+                if scope == DoScope::Windows || is_last || !cx.update(|_, cx| item.is_dirty(cx))? {
+                    continue;
+                }
+                if autowrite {
+                    let (project, pane) = workspace.read_with(cx, |workspace, _| {
+                        (workspace.project().clone(), workspace.pane_for(&*item))
+                    })?;
+                    let Some(pane) = pane else {
+                        continue;
+                    };
+                    Pane::save_item(project, &pane.downgrade(), &*item, SaveIntent::Save, cx)
+                        .await?;
+                } else if !hidden && !force {
+                    return Err(anyhow!(
+                        "E37: No write since last change (add ! to override)"
+                    ));
+                }
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(window, cx);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use gpui::TestAppContext;
+    use indoc::indoc;
+    use std::path::Path;
+    use util::path;
+
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
+    use editor::Editor;
+
+    fn active_path(cx: &mut VimTestContext) -> String {
+        cx.workspace(|workspace, _, cx| {
+            super::active_path(workspace, cx)
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_bufdo_and_windo(cx: &mut TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇa b
+            a b
+        "})
+            .await;
+
+        cx.simulate_shared_keystrokes(": b u f d o space s / a / x / g enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            ˇx b
+            a b
+        "});
+
+        cx.simulate_shared_keystrokes(": w i n d o space n o r m space A y enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            x bˇy
+            a b
+        "});
+    }
+
+    #[gpui::test]
+    async fn test_bufdo_and_windo_across_buffers(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), b"a one\na\n".to_vec())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/b.rs"), b"a two\na\n".to_vec())
+            .await;
+        cx.run_until_parked();
+
+        // `:bufdo` runs once in each buffer, including ones that aren't visible.
+        cx.simulate_keystrokes(": a r g s space d i r / a . r s space d i r / b . r s enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes(": n e x t enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes(": s e t space h i d d e n enter");
+        cx.simulate_keystrokes(": b u f d o space s / a / x / g enter");
+        cx.run_until_parked();
+        let texts = cx.buffer_texts();
+        assert!(texts.contains(&(
+            Path::new(path!("/root/dir/a.rs")).into(),
+            "x one\na\n".into()
+        )));
+        assert!(texts.contains(&(
+            Path::new(path!("/root/dir/b.rs")).into(),
+            "x two\na\n".into()
+        )));
+
+        // `:windo` runs once in each split, on the buffer it shows.
+        cx.simulate_keystrokes(": l a s t enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/b.rs"));
+        cx.simulate_keystrokes(": v s p l i t space d i r / a . r s enter");
+        cx.run_until_parked();
+        assert_eq!(cx.workspace(|workspace, _, _| workspace.panes().len()), 2);
+        cx.simulate_keystrokes(": w i n d o space n o r m space A y enter");
+        cx.run_until_parked();
+        let texts = cx.buffer_texts();
+        assert!(texts.contains(&(
+            Path::new(path!("/root/dir/a.rs")).into(),
+            "x oney\na\n".into()
+        )));
+        assert!(texts.contains(&(
+            Path::new(path!("/root/dir/b.rs")).into(),
+            "x twoy\na\n".into()
+        )));
+    }
+
+    #[gpui::test]
+    async fn test_arg_list(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), b"one\n".to_vec())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/b.rs"), b"two\n".to_vec())
+            .await;
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": a r g s space d i r / a . r s enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/a.rs"));
+
+        cx.simulate_keystrokes(": a r g a d d space d i r / b . r s enter");
+        cx.simulate_keystrokes(": n e x t enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/b.rs"));
+
+        cx.simulate_keystrokes(": n e x t enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/b.rs"));
+
+        cx.simulate_keystrokes(": p r e v enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/a.rs"));
+
+        cx.simulate_keystrokes(": a r g s space d i r / * . r s enter");
+        cx.simulate_keystrokes(": l a s t enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/file.rs"));
+    }
+
+    #[gpui::test]
+    async fn test_argdo_write_semantics(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), b"drop\nkeep a\n".to_vec())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/b.rs"), b"drop\nkeep b\n".to_vec())
+            .await;
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": a r g s space d i r / a . r s space d i r / b . r s enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes(": s e t space n o h i d d e n enter");
+        cx.simulate_keystrokes(": a r g d o space d enter");
+        cx.run_until_parked();
+        // Without `hidden`, the modified first file can't be abandoned.
+        assert!(
+            cx.buffer_texts()
+                .contains(&(Path::new(path!("/root/dir/a.rs")).into(), "keep a\n".into()))
+        );
+        assert!(
+            !cx.buffer_texts()
+                .iter()
+                .any(|(path, _)| path.as_ref() == Path::new(path!("/root/dir/b.rs")))
+        );
+
+        cx.simulate_keystrokes("u");
+        cx.simulate_keystrokes(": s e t space a u t o w r i t e enter");
+        cx.simulate_keystrokes(": a r g d o space d enter");
+        cx.run_until_parked();
+        assert_eq!(
+            fs.load(Path::new(path!("/root/dir/a.rs"))).await.unwrap(),
+            "keep a\n"
+        );
+        assert!(
+            cx.buffer_texts()
+                .contains(&(Path::new(path!("/root/dir/b.rs")).into(), "keep b\n".into()))
+        );
+        cx.editor =
+            cx.workspace(|workspace, _, cx| workspace.active_item_as::<Editor>(cx).unwrap());
+        cx.assert_state("ˇkeep b\n", Mode::Normal);
+    }
+}
@@ -34,12 +34,19 @@ use zed_actions::{OpenDocs, RevealTarget};
 
 use crate::{
     ToggleMarksView, ToggleRegistersView, Vim,
+    arglist::{ArgTarget, DoScope, VimArgAdd, VimArgNavigate, VimArgs, VimDo},
     motion::{EndOfDocument, Motion, MotionKind, StartOfDocument},
     normal::{
         JoinLines,
         search::{FindCommand, ReplaceCommand, Replacement},
     },
     object::Object,
+    quickfix::{
+        QuickfixClose, QuickfixDiagnostics, QuickfixDo, QuickfixFromBuffer, QuickfixFromTask,
+        QuickfixGrep, QuickfixHistory,
+        QuickfixKind::{self, Location, Quickfix},
+        QuickfixMake, QuickfixNavigate, QuickfixOpen, QuickfixTarget,
+    },
     state::{Mark, Mode},
     visual::VisualDeleteLine,
 };
@@ -75,11 +82,13 @@ pub struct WithCount {
     action: WrappedAction,
 }
 
-#[derive(Clone, Deserialize, JsonSchema, PartialEq)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq)]
 pub enum VimOption {
     Wrap(bool),
     Number(bool),
     RelativeNumber(bool),
+    Hidden(bool),
+    AutoWrite(bool),
 }
 
 impl VimOption {
@@ -122,13 +131,21 @@ impl VimOption {
             (None, VimOption::RelativeNumber(false)),
             (Some("rnu"), VimOption::RelativeNumber(true)),
             (Some("nornu"), VimOption::RelativeNumber(false)),
+            (None, VimOption::Hidden(true)),
+            (None, VimOption::Hidden(false)),
+            (None, VimOption::AutoWrite(true)),
+            (None, VimOption::AutoWrite(false)),
+            (Some("hid"), VimOption::Hidden(true)),
+            (Some("nohid"), VimOption::Hidden(false)),
+            (Some("aw"), VimOption::AutoWrite(true)),
+            (Some("noaw"), VimOption::AutoWrite(false)),
         ]
         .into_iter()
         .filter(move |(prefix, option)| prefix.unwrap_or(option.to_string()).starts_with(query))
         .map(|(_, option)| option)
     }
 
-    fn from(option: &str) -> Option<Self> {
+    pub(crate) fn from(option: &str) -> Option<Self> {
         match option {
             "wrap" => Some(Self::Wrap(true)),
             "nowrap" => Some(Self::Wrap(false)),
@@ -143,6 +160,16 @@ impl VimOption {
             "norelativenumber" => Some(Self::RelativeNumber(false)),
             "nornu" => Some(Self::RelativeNumber(false)),
 
+            "hidden" => Some(Self::Hidden(true)),
+            "hid" => Some(Self::Hidden(true)),
+            "nohidden" => Some(Self::Hidden(false)),
+            "nohid" => Some(Self::Hidden(false)),
+
+            "autowrite" => Some(Self::AutoWrite(true)),
+            "aw" => Some(Self::AutoWrite(true)),
+            "noautowrite" => Some(Self::AutoWrite(false)),
+            "noaw" => Some(Self::AutoWrite(false)),
+
             _ => None,
         }
     }
@@ -155,6 +182,10 @@ impl VimOption {
             VimOption::Number(false) => "nonumber",
             VimOption::RelativeNumber(true) => "relativenumber",
             VimOption::RelativeNumber(false) => "norelativenumber",
+            VimOption::Hidden(true) => "hidden",
+            VimOption::Hidden(false) => "nohidden",
+            VimOption::AutoWrite(true) => "autowrite",
+            VimOption::AutoWrite(false) => "noautowrite",
         }
     }
 }
@@ -243,21 +274,7 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     // Vim::action(editor, cx, |vim, action: &StartOfLine, window, cx| {
     Vim::action(editor, cx, |vim, action: &VimSet, _, cx| {
         for option in action.options.iter() {
-            vim.update_editor(cx, |_, editor, cx| match option {
-                VimOption::Wrap(true) => {
-                    editor
-                        .set_soft_wrap_mode(language::language_settings::SoftWrap::EditorWidth, cx);
-                }
-                VimOption::Wrap(false) => {
-                    editor.set_soft_wrap_mode(language::language_settings::SoftWrap::None, cx);
-                }
-                VimOption::Number(enabled) => {
-                    editor.set_show_line_numbers(*enabled, cx);
-                }
-                VimOption::RelativeNumber(enabled) => {
-                    editor.set_relative_line_number(Some(*enabled), cx);
-                }
-            });
+            vim.set_option(option, cx);
         }
     });
     Vim::action(editor, cx, |vim, _: &VisualCommand, window, cx| {
@@ -1165,6 +1182,31 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
             action.range.replace(range.clone());
             Some(Box::new(action))
         }),
+        VimCommand::new(("ar", "gs"), VimArgs { files: Vec::new() }).args(|_, args| {
+            Some(
+                VimArgs {
+                    files: split_files(&args),
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::new(("arga", "dd"), VimArgAdd { files: Vec::new() }).args(|_, args| {
+            Some(
+                VimArgAdd {
+                    files: split_files(&args),
+                }
+                .boxed_clone(),
+            )
+        }),
+        arg_command(("n", "ext"), ArgTarget::Next).range(wrap_count),
+        arg_command(("N", "ext"), ArgTarget::Previous).range(wrap_count),
+        arg_command(("prev", "ious"), ArgTarget::Previous).range(wrap_count),
+        arg_command(("fir", "st"), ArgTarget::First),
+        arg_command(("rew", "ind"), ArgTarget::First),
+        arg_command(("la", "st"), ArgTarget::Last),
+        vim_do_command(("argdo", ""), DoScope::Args),
+        vim_do_command(("bufdo", ""), DoScope::Buffers),
+        vim_do_command(("windo", ""), DoScope::Windows),
         VimCommand::new(("bn", "ext"), workspace::ActivateNextItem).count(),
         VimCommand::new(("bN", "ext"), workspace::ActivatePreviousItem).count(),
         VimCommand::new(("bp", "revious"), workspace::ActivatePreviousItem).count(),
@@ -1207,31 +1249,84 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         .bang(workspace::CloseInactiveTabsAndPanes {
             save_intent: Some(SaveIntent::Skip),
         }),
-        VimCommand::str(("cl", "ist"), "diagnostics::Deploy"),
-        VimCommand::new(("cc", ""), editor::actions::Hover),
-        VimCommand::new(("ll", ""), editor::actions::Hover),
-        VimCommand::new(("cn", "ext"), editor::actions::GoToDiagnostic::default())
-            .range(wrap_count),
+        VimCommand::new(("cl", "ist"), QuickfixOpen { kind: Quickfix }),
+        VimCommand::new(("cope", "n"), QuickfixOpen { kind: Quickfix }),
+        VimCommand::new(("ccl", "ose"), QuickfixClose { kind: Quickfix }),
+        VimCommand::new(("lli", "st"), QuickfixOpen { kind: Location }),
+        VimCommand::new(("lope", "n"), QuickfixOpen { kind: Location }),
+        VimCommand::new(("lcl", "ose"), QuickfixClose { kind: Location }),
+        quickfix_command(("cc", ""), Quickfix, QuickfixTarget::Current)
+            .args(quickfix_nth)
+            .range(quickfix_nth_range),
+        quickfix_command(("ll", ""), Location, QuickfixTarget::Current)
+            .args(quickfix_nth)
+            .range(quickfix_nth_range),
+        quickfix_command(("cn", "ext"), Quickfix, QuickfixTarget::Next).range(wrap_count),
+        quickfix_command(("cp", "revious"), Quickfix, QuickfixTarget::Previous).range(wrap_count),
+        quickfix_command(("cN", "ext"), Quickfix, QuickfixTarget::Previous).range(wrap_count),
+        quickfix_command(("lne", "xt"), Location, QuickfixTarget::Next).range(wrap_count),
+        quickfix_command(("lp", "revious"), Location, QuickfixTarget::Previous).range(wrap_count),
+        quickfix_command(("lN", "ext"), Location, QuickfixTarget::Previous).range(wrap_count),
+        quickfix_command(("cnf", "ile"), Quickfix, QuickfixTarget::NextFile).range(wrap_count),
+        quickfix_command(("cpf", "ile"), Quickfix, QuickfixTarget::PreviousFile).range(wrap_count),
+        quickfix_command(("cNf", "ile"), Quickfix, QuickfixTarget::PreviousFile).range(wrap_count),
+        quickfix_command(("lnf", "ile"), Location, QuickfixTarget::NextFile).range(wrap_count),
+        quickfix_command(("lpf", "ile"), Location, QuickfixTarget::PreviousFile).range(wrap_count),
+        quickfix_command(("lNf", "ile"), Location, QuickfixTarget::PreviousFile).range(wrap_count),
+        quickfix_command(("cfir", "st"), Quickfix, QuickfixTarget::First).args(quickfix_nth),
+        quickfix_command(("cr", "ewind"), Quickfix, QuickfixTarget::First).args(quickfix_nth),
+        quickfix_command(("cla", "st"), Quickfix, QuickfixTarget::Last).args(quickfix_nth),
+        quickfix_command(("lfir", "st"), Location, QuickfixTarget::First).args(quickfix_nth),
+        quickfix_command(("lr", "ewind"), Location, QuickfixTarget::First).args(quickfix_nth),
+        quickfix_command(("lla", "st"), Location, QuickfixTarget::Last).args(quickfix_nth),
         VimCommand::new(
-            ("cp", "revious"),
-            editor::actions::GoToPreviousDiagnostic::default(),
+            ("col", "der"),
+            QuickfixHistory {
+                kind: Quickfix,
+                newer: false,
+            },
         )
         .range(wrap_count),
         VimCommand::new(
-            ("cN", "ext"),
-            editor::actions::GoToPreviousDiagnostic::default(),
+            ("cnew", "er"),
+            QuickfixHistory {
+                kind: Quickfix,
+                newer: true,
+            },
         )
         .range(wrap_count),
         VimCommand::new(
-            ("lp", "revious"),
-            editor::actions::GoToPreviousDiagnostic::default(),
+            ("lol", "der"),
+            QuickfixHistory {
+                kind: Location,
+                newer: false,
+            },
         )
         .range(wrap_count),
         VimCommand::new(
-            ("lN", "ext"),
-            editor::actions::GoToPreviousDiagnostic::default(),
+            ("lnew", "er"),
+            QuickfixHistory {
+                kind: Location,
+                newer: true,
+            },
         )
         .range(wrap_count),
+        quickfix_do_command(("cdo", ""), Quickfix, false),
+        quickfix_do_command(("cfd", "o"), Quickfix, true),
+        quickfix_do_command(("ldo", ""), Location, false),
+        quickfix_do_command(("lfd", "o"), Location, true),
+        VimCommand::new(("vim", "grep"), ArgumentRequired)
+            .args(|_, args| Some(QuickfixGrep::parse(Quickfix, &args)?.boxed_clone())),
+        VimCommand::new(("lv", "imgrep"), ArgumentRequired)
+            .args(|_, args| Some(QuickfixGrep::parse(Location, &args)?.boxed_clone())),
+        VimCommand::new(("cdia", "gnostics"), QuickfixDiagnostics { kind: Quickfix }),
+        VimCommand::new(("ldia", "gnostics"), QuickfixDiagnostics { kind: Location }),
+        quickfix_make_command(("mak", "e"), Quickfix),
+        quickfix_make_command(("lmak", "e"), Location),
+        VimCommand::new(("cb", "uffer"), QuickfixFromBuffer { kind: Quickfix }),
+        VimCommand::new(("lb", "uffer"), QuickfixFromBuffer { kind: Location }),
+        VimCommand::new(("cta", "sk"), QuickfixFromTask { kind: Quickfix }),
+        VimCommand::new(("lta", "sk"), QuickfixFromTask { kind: Location }),
         VimCommand::new(("j", "oin"), JoinLines).range(select_range),
         VimCommand::new(("fo", "ld"), editor::actions::FoldSelectedRanges).range(act_on_range),
         VimCommand::new(("foldo", "pen"), editor::actions::UnfoldLines)
@@ -1323,6 +1418,96 @@ fn select_range(action: Box<dyn Action>, range: &CommandRange) -> Option<Box<dyn
     )
 }
 
+fn arg_command(pattern: (&'static str, &'static str), target: ArgTarget) -> VimCommand {
+    VimCommand::new(pattern, VimArgNavigate { target })
+}
+
+fn vim_do_command(pattern: (&'static str, &'static str), scope: DoScope) -> VimCommand {
+    VimCommand::new(pattern, ArgumentRequired)
+        .bang(VimDo {
+            scope,
+            force: true,
+            command: String::new(),
+        })
+        .args(move |action, command| {
+            let force = action.as_any().downcast_ref::<VimDo>().is_some();
+            Some(
+                VimDo {
+                    scope,
+                    force,
+                    command,
+                }
+                .boxed_clone(),
+            )
+        })
+}
+
+fn split_files(args: &str) -> Vec<String> {
+    args.split_whitespace().map(ToString::to_string).collect()
+}
+
+fn quickfix_command(
+    pattern: (&'static str, &'static str),
+    kind: QuickfixKind,
+    target: QuickfixTarget,
+) -> VimCommand {
+    VimCommand::new(pattern, QuickfixNavigate { kind, target })
+}
+
+fn quickfix_do_command(
+    pattern: (&'static str, &'static str),
+    kind: QuickfixKind,
+    per_file: bool,
+) -> VimCommand {
+    VimCommand::new(pattern, ArgumentRequired).args(move |_, command| {
+        Some(
+            QuickfixDo {
+                kind,
+                per_file,
+                command,
+            }
+            .boxed_clone(),
+        )
+    })
+}
+
+fn quickfix_make_command(pattern: (&'static str, &'static str), kind: QuickfixKind) -> VimCommand {
+    VimCommand::new(
+        pattern,
+        QuickfixMake {
+            kind,
+            args: String::new(),
+            jump: true,
+        },
+    )
+    .bang(QuickfixMake {
+        kind,
+        args: String::new(),
+        jump: false,
+    })
+    .args(|action, args| {
+        let mut action = action.as_any().downcast_ref::<QuickfixMake>()?.clone();
+        action.args = args;
+        Some(action.boxed_clone())
+    })
+}
+
+fn quickfix_nth(action: Box<dyn Action>, args: String) -> Option<Box<dyn Action>> {
+    let kind = action.as_any().downcast_ref::<QuickfixNavigate>()?.kind;
+    let n = args.parse().ok()?;
+    Some(
+        QuickfixNavigate {
+            kind,
+            target: QuickfixTarget::Nth(n),
+        }
+        .boxed_clone(),
+    )
+}
+
+fn quickfix_nth_range(action: Box<dyn Action>, range: &CommandRange) -> Option<Box<dyn Action>> {
+    quickfix_nth(action, range.as_count()?.to_string())
+}
+
 fn wrap_count(action: Box<dyn Action>, range: &CommandRange) -> Option<Box<dyn Action>> {
     range.as_count().map(|count| {
         WithCount {
@@ -1677,6 +1862,25 @@ pub struct ShellExec {
 }
 
 impl Vim {
+    pub(crate) fn set_option(&mut self, option: &VimOption, cx: &mut Context<Self>) {
+        self.update_editor(cx, |_, editor, cx| match option {
+            VimOption::Wrap(true) => {
+                editor.set_soft_wrap_mode(language::language_settings::SoftWrap::EditorWidth, cx);
+            }
+            VimOption::Wrap(false) => {
+                editor.set_soft_wrap_mode(language::language_settings::SoftWrap::None, cx);
+            }
+            VimOption::Number(enabled) => {
+                editor.set_show_line_numbers(*enabled, cx);
+            }
+            VimOption::RelativeNumber(enabled) => {
+                editor.set_relative_line_number(Some(*enabled), cx);
+            }
+            VimOption::Hidden(enabled) => Vim::globals(cx).hidden = *enabled,
+            VimOption::AutoWrite(enabled) => Vim::globals(cx).autowrite = *enabled,
+        });
+    }
+
     pub fn cancel_running_command(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self.running_command.take().is_some() {
             self.update_editor(cx, |_, editor, cx| {
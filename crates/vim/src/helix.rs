@@ -1,13 +1,22 @@
-use editor::{DisplayPoint, Editor, SelectionEffects, ToOffset, ToPoint, movement};
+use std::{mem, ops::Range};
+
+use editor::{
+    DisplayPoint, Editor, SelectionEffects, ToOffset, ToPoint,
+    display_map::{DisplaySnapshot, ToDisplayPoint},
+    movement,
+};
 use gpui::{Action, actions};
 use gpui::{Context, Window};
-use language::{CharClassifier, CharKind};
+use language::{CharClassifier, CharKind, Point, Selection};
+use regex::{Regex, RegexBuilder};
+use search::{BufferSearchBar, SearchOptions, buffer_search::Dismiss};
 use text::{Bias, SelectionGoal};
 
 use crate::{
     Vim,
     motion::{Motion, right},
-    state::Mode,
+    object::Object,
+    state::{Mode, Operator, SearchState},
 };
 
 actions!(
@@ -19,13 +28,81 @@ actions!(
         HelixInsert,
         /// Appends at the end of the selection.
         HelixAppend,
+        /// Selects the current line, or extends a line selection to the next line.
+        HelixSelectLine,
+        /// Extends the selections to cover whole lines.
+        HelixExtendToLineBounds,
+        /// Selects the matches of a regex within the selections.
+        HelixSelectRegex,
+        /// Splits the selections on the matches of a regex.
+        HelixSplitSelection,
+        /// Keeps the selections that match a regex.
+        HelixKeepSelections,
+        /// Removes the selections that match a regex.
+        HelixRemoveSelections,
+        /// Surrounds the selections with a pair of characters.
+        HelixSurroundAdd,
+        /// Replaces the pair of characters surrounding the selections.
+        HelixSurroundReplace,
+        /// Deletes the pair of characters surrounding the selections.
+        HelixSurroundDelete,
     ]
 );
 
+/// What to do with the selections once the regex prompt is submitted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HelixRegexOperation {
+    Select,
+    Split,
+    Keep,
+    Remove,
+}
+
 pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, Vim::helix_normal_after);
     Vim::action(editor, cx, Vim::helix_insert);
     Vim::action(editor, cx, Vim::helix_append);
+    Vim::action(editor, cx, |vim, _: &HelixSelectLine, window, cx| {
+        let times = Vim::take_count(cx).unwrap_or(1);
+        Vim::take_forced_motion(cx);
+        vim.helix_select_lines(Some(times), window, cx);
+    });
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &HelixExtendToLineBounds, window, cx| {
+            Vim::take_count(cx);
+            Vim::take_forced_motion(cx);
+            vim.helix_select_lines(None, window, cx);
+        },
+    );
+    Vim::action(editor, cx, |vim, _: &HelixSelectRegex, window, cx| {
+        vim.helix_regex_prompt(HelixRegexOperation::Select, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixSplitSelection, window, cx| {
+        vim.helix_regex_prompt(HelixRegexOperation::Split, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixKeepSelections, window, cx| {
+        vim.helix_regex_prompt(HelixRegexOperation::Keep, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixRemoveSelections, window, cx| {
+        vim.helix_regex_prompt(HelixRegexOperation::Remove, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixSurroundAdd, window, cx| {
+        vim.push_operator(Operator::HelixSurroundAdd, window, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &HelixSurroundReplace, window, cx| {
+        vim.push_operator(
+            Operator::HelixSurroundReplace {
+                replaced_char: None,
+            },
+            window,
+            cx,
+        )
+    });
+    Vim::action(editor, cx, |vim, _: &HelixSurroundDelete, window, cx| {
+        vim.push_operator(Operator::HelixSurroundDelete, window, cx)
+    });
 }
 
 impl Vim {
@@ -415,6 +492,419 @@ impl Vim {
         });
         self.switch_mode(Mode::HelixNormal, true, window, cx);
     }
+
+    pub fn helix_select_motion(
+        &mut self,
+        motion: Motion,
+        times: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Helix selections always cover the character under the cursor, so an
+        // empty selection is treated as a one-character range.
+        let Some(previous) = self.update_editor(cx, |_, editor, cx| {
+            let (map, selections) = editor.selections.all_display(cx);
+            selections
+                .iter()
+                .map(|selection| {
+                    if selection.is_empty() {
+                        (selection.start, movement::right(&map, selection.start))
+                    } else {
+                        (selection.tail(), selection.head())
+                    }
+                })
+                .collect::<Vec<_>>()
+        }) else {
+            return;
+        };
+
+        self.helix_move_cursor(motion, times, window, cx);
+
+        self.update_editor(cx, |_, editor, cx| {
+            editor.change_selections(Default::default(), window, cx, |s| {
+                let mut previous = previous.into_iter();
+                s.move_with(|map, selection| {
+                    let Some((anchor, head)) = previous.next() else {
+                        return;
+                    };
+                    let cursor = helix_cursor(map, selection);
+                    let anchor = if head >= anchor && cursor < anchor {
+                        movement::right(map, anchor)
+                    } else if head < anchor && cursor >= anchor {
+                        movement::left(map, anchor)
+                    } else {
+                        anchor
+                    };
+                    let head = if anchor <= cursor {
+                        movement::right(map, cursor)
+                    } else {
+                        cursor
+                    };
+                    let goal = selection.goal;
+                    selection.set_tail(anchor, goal);
+                    selection.set_head(head, goal);
+                });
+            });
+        });
+    }
+
+    /// Selects whole lines. With a count, a selection that already covers whole
+    /// lines is extended by that many lines; without one, the selections are only
+    /// extended to their line bounds.
+    fn helix_select_lines(
+        &mut self,
+        times: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_editor(cx, |_, editor, cx| {
+            editor.change_selections(Default::default(), window, cx, |s| {
+                s.move_with(|map, selection| {
+                    let max_point = map.buffer_snapshot.max_point();
+                    let start = selection.start.to_point(map);
+                    let end = selection.end.to_point(map);
+                    let mut last_row = end.row;
+                    if !selection.is_empty() && end.column == 0 {
+                        last_row -= 1;
+                    }
+                    let covers_lines = !selection.is_empty()
+                        && start.column == 0
+                        && (end.column == 0 || end == max_point);
+                    let target_row = match times {
+                        Some(times) if covers_lines => last_row + times as u32,
+                        Some(times) => last_row + times as u32 - 1,
+                        None => last_row,
+                    };
+                    let end = if target_row >= max_point.row {
+                        max_point
+                    } else {
+                        Point::new(target_row + 1, 0)
+                    };
+                    selection.start = Point::new(start.row, 0).to_display_point(map);
+                    selection.end = end.to_display_point(map);
+                    selection.reversed = false;
+                    selection.goal = SelectionGoal::None;
+                });
+            });
+        });
+    }
+
+    fn helix_regex_prompt(
+        &mut self,
+        operation: HelixRegexOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(pane) = self.pane(window, cx) else {
+            return;
+        };
+        Vim::take_count(cx);
+        Vim::take_forced_motion(cx);
+        let prior_selections = self.editor_selections(window, cx);
+        pane.update(cx, |pane, cx| {
+            if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
+                search_bar.update(cx, |search_bar, cx| {
+                    if !search_bar.show(window, cx) {
+                        return;
+                    }
+                    search_bar.select_query(window, cx);
+                    cx.focus_self(window);
+                    search_bar.set_replacement(None, cx);
+                    search_bar.set_search_options(SearchOptions::REGEX, cx);
+                    self.search = SearchState {
+                        prior_selections,
+                        prior_mode: self.mode,
+                        helix_regex: Some(operation),
+                        ..Default::default()
+                    };
+                });
+            }
+        });
+    }
+
+    pub(crate) fn helix_regex_submit(
+        &mut self,
+        operation: HelixRegexOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let prior_selections = mem::take(&mut self.search.prior_selections);
+        let Some(pane) = self.pane(window, cx) else {
+            return;
+        };
+        let query = pane.update(cx, |pane, cx| {
+            let search_bar = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>()?;
+            search_bar.update(cx, |search_bar, cx| {
+                let query = search_bar.query(cx);
+                search_bar.dismiss(&Dismiss, window, cx);
+                Some(query)
+            })
+        });
+        let regex = query.and_then(|query| {
+            // Like Helix, the search is case-insensitive unless the query has an uppercase letter.
+            RegexBuilder::new(&query)
+                .case_insensitive(!query.chars().any(char::is_uppercase))
+                .build()
+                .ok()
+        });
+
+        self.update_editor(cx, |_, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let ranges = prior_selections
+                .iter()
+                .map(|selection| {
+                    let mut start = selection.start.to_offset(&snapshot);
+                    let mut end = selection.end.to_offset(&snapshot);
+                    if start > end {
+                        mem::swap(&mut start, &mut end);
+                    }
+                    if start == end {
+                        end += snapshot.chars_at(start).next().map_or(0, char::len_utf8);
+                    }
+                    let text = snapshot.text_for_range(start..end).collect::<String>();
+                    (start..end, text)
+                })
+                .collect::<Vec<_>>();
+            let new_ranges = regex
+                .map(|regex| helix_regex_ranges(operation, &regex, &ranges))
+                .unwrap_or_default();
+            editor.change_selections(Default::default(), window, cx, |s| {
+                if new_ranges.is_empty() {
+                    s.select_anchor_ranges(prior_selections);
+                } else {
+                    s.select_ranges(new_ranges);
+                }
+            });
+        });
+    }
+
+    pub(crate) fn helix_object(
+        &mut self,
+        object: Object,
+        count: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(Operator::Object { around }) = self.active_operator() else {
+            return;
+        };
+        self.pop_operator(window, cx);
+        self.update_editor(cx, |_, editor, cx| {
+            editor.change_selections(Default::default(), window, cx, |s| {
+                s.move_with(|map, selection| {
+                    let mut cursor_selection = selection.clone();
+                    cursor_selection.collapse_to(helix_cursor(map, selection), selection.goal);
+                    if let Some(range) = object.range(map, cursor_selection, around, count) {
+                        selection.start = range.start;
+                        selection.end = range.end;
+                        selection.reversed = false;
+                    }
+                });
+            });
+        });
+    }
+
+    pub(crate) fn helix_surround_add(
+        &mut self,
+        text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ch) = text.chars().next() else {
+            return;
+        };
+        let (open, close) = surround_pair(ch);
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(window, cx, |editor, window, cx| {
+                let (map, selections) = editor.selections.all_display(cx);
+                let mut edits = Vec::new();
+                let mut ranges = Vec::new();
+                for selection in &selections {
+                    let mut range = selection.range();
+                    if range.is_empty() {
+                        range.end = movement::right(&map, range.start);
+                    }
+                    let start = range.start.to_offset(&map, Bias::Left);
+                    let end = range.end.to_offset(&map, Bias::Left);
+                    edits.push((start..start, open.to_string()));
+                    edits.push((end..end, close.to_string()));
+                    ranges.push(
+                        map.buffer_snapshot.anchor_before(start)
+                            ..map.buffer_snapshot.anchor_after(end),
+                    );
+                }
+                editor.edit(edits, cx);
+                editor.change_selections(Default::default(), window, cx, |s| {
+                    s.select_anchor_ranges(ranges);
+                });
+            });
+        });
+    }
+
+    pub(crate) fn helix_surround_replace(
+        &mut self,
+        replaced_char: char,
+        text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ch) = text.chars().next() else {
+            return;
+        };
+        let (open, close) = surround_pair(ch);
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(window, cx, |editor, _, cx| {
+                let (map, selections) = editor.selections.all_display(cx);
+                let (replaced_open, replaced_close) = surround_pair(replaced_char);
+                let mut edits = Vec::new();
+                for selection in &selections {
+                    if let Some((open_offset, close_offset)) =
+                        surround_offsets(&map, selection, replaced_char)
+                    {
+                        edits.push((
+                            open_offset..open_offset + replaced_open.len_utf8(),
+                            open.to_string(),
+                        ));
+                        edits.push((
+                            close_offset..close_offset + replaced_close.len_utf8(),
+                            close.to_string(),
+                        ));
+                    }
+                }
+                edits.sort_by_key(|(range, _)| range.start);
+                edits.dedup_by_key(|(range, _)| range.start);
+                editor.edit(edits, cx);
+            });
+        });
+    }
+
+    pub(crate) fn helix_surround_delete(
+        &mut self,
+        text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ch) = text.chars().next() else {
+            return;
+        };
+        let (open, close) = surround_pair(ch);
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(window, cx, |editor, _, cx| {
+                let (map, selections) = editor.selections.all_display(cx);
+                let mut edits = Vec::new();
+                for selection in &selections {
+                    if let Some((open_offset, close_offset)) = surround_offsets(&map, selection, ch)
+                    {
+                        edits.push((open_offset..open_offset + open.len_utf8(), ""));
+                        edits.push((close_offset..close_offset + close.len_utf8(), ""));
+                    }
+                }
+                edits.sort_by_key(|(range, _)| range.start);
+                edits.dedup_by_key(|(range, _)| range.start);
+                editor.edit(edits, cx);
+            });
+        });
+    }
+}
+
+/// The character a Helix block cursor is on.
+fn helix_cursor(map: &DisplaySnapshot, selection: &Selection<DisplayPoint>) -> DisplayPoint {
+    if selection.is_empty() || selection.reversed {
+        selection.head()
+    } else {
+        movement::left(map, selection.head())
+    }
+}
+
+fn helix_regex_ranges(
+    operation: HelixRegexOperation,
+    regex: &Regex,
+    ranges: &[(Range<usize>, String)],
+) -> Vec<Range<usize>> {
+    let mut new_ranges = Vec::new();
+    for (range, text) in ranges {
+        match operation {
+            HelixRegexOperation::Select => {
+                new_ranges.extend(
+                    regex
+                        .find_iter(text)
+                        .filter(|found| !found.is_empty())
+                        .map(|found| range.start + found.start()..range.start + found.end()),
+                );
+            }
+            HelixRegexOperation::Split => {
+                let mut start = 0;
+                for found in regex.find_iter(text) {
+                    if found.start() > start {
+                        new_ranges.push(range.start + start..range.start + found.start());
+                    }
+                    start = found.end();
+                }
+                if start < text.len() {
+                    new_ranges.push(range.start + start..range.end);
+                }
+            }
+            HelixRegexOperation::Keep => {
+                if regex.is_match(text) {
+                    new_ranges.push(range.clone());
+                }
+            }
+            HelixRegexOperation::Remove => {
+                if !regex.is_match(text) {
+                    new_ranges.push(range.clone());
+                }
+            }
+        }
+    }
+    new_ranges
+}
+
+fn surround_pair(ch: char) -> (char, char) {
+    match ch {
+        '(' | ')' => ('(', ')'),
+        '[' | ']' => ('[', ']'),
+        '{' | '}' => ('{', '}'),
+        '<' | '>' => ('<', '>'),
+        _ => (ch, ch),
+    }
+}
+
+fn surround_object(ch: char) -> Option<Object> {
+    match ch {
+        '(' | ')' => Some(Object::Parentheses),
+        '[' | ']' => Some(Object::SquareBrackets),
+        '{' | '}' => Some(Object::CurlyBrackets),
+        '<' | '>' => Some(Object::AngleBrackets),
+        '"' => Some(Object::DoubleQuotes),
+        '\'' => Some(Object::Quotes),
+        '`' => Some(Object::BackQuotes),
+        '|' => Some(Object::VerticalBars),
+        _ => None,
+    }
+}
+
+/// Finds the offsets of the `ch` pair that surrounds the cursor of `selection`.
+fn surround_offsets(
+    map: &DisplaySnapshot,
+    selection: &Selection<DisplayPoint>,
+    ch: char,
+) -> Option<(usize, usize)> {
+    let object = surround_object(ch)?;
+    let (open, close) = surround_pair(ch);
+    let mut cursor_selection = selection.clone();
+    cursor_selection.collapse_to(helix_cursor(map, selection), selection.goal);
+    let range = object.range(map, cursor_selection, true, None)?;
+    let start = range.start.to_offset(map, Bias::Left);
+    let end = range.end.to_offset(map, Bias::Left);
+    let (_, open_offset) = map
+        .buffer_chars_at(start)
+        .take_while(|(_, offset)| *offset < end)
+        .find(|(c, _)| *c == open)?;
+    let (_, close_offset) = map
+        .reverse_buffer_chars_at(end)
+        .take_while(|(_, offset)| *offset > open_offset)
+        .find(|(c, _)| *c == close)?;
+    Some((open_offset, close_offset))
 }
 
 #[cfg(test)]
@@ -703,4 +1193,145 @@ mod test {
 
         cx.assert_state("«xxˇ»", Mode::HelixNormal);
     }
+
+    #[gpui::test]
+    async fn test_select_mode(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇThe quick brown", Mode::HelixNormal);
+        cx.simulate_keystrokes("v w");
+        cx.assert_state("«The ˇ»quick brown", Mode::HelixSelect);
+
+        cx.simulate_keystrokes("w");
+        cx.assert_state("«The quick ˇ»brown", Mode::HelixSelect);
+
+        cx.simulate_keystrokes("b");
+        cx.assert_state("«The qˇ»uick brown", Mode::HelixSelect);
+
+        // Motions replace the selection again once select mode is left.
+        cx.simulate_keystrokes("v w");
+        cx.assert_state("The q«uick ˇ»brown", Mode::HelixNormal);
+
+        cx.set_state("ˇone two three", Mode::HelixNormal);
+        cx.simulate_keystrokes("v f t");
+        cx.assert_state("«one tˇ»wo three", Mode::HelixSelect);
+
+        cx.simulate_keystrokes("f e");
+        cx.assert_state("«one two threˇ»e", Mode::HelixSelect);
+
+        cx.simulate_keystrokes("escape");
+        cx.assert_state("«one two threˇ»e", Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_select_lines(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("oˇne\ntwo\nthree", Mode::HelixNormal);
+        cx.simulate_keystrokes("x");
+        cx.assert_state("«one\nˇ»two\nthree", Mode::HelixNormal);
+
+        cx.simulate_keystrokes("x");
+        cx.assert_state("«one\ntwo\nˇ»three", Mode::HelixNormal);
+
+        cx.simulate_keystrokes("x");
+        cx.assert_state("«one\ntwo\nthreeˇ»", Mode::HelixNormal);
+
+        cx.set_state("ˇone\ntwo\nthree", Mode::HelixNormal);
+        cx.simulate_keystrokes("2 x");
+        cx.assert_state("«one\ntwo\nˇ»three", Mode::HelixNormal);
+
+        cx.set_state("o«ne\ntˇ»wo\nthree", Mode::HelixNormal);
+        cx.simulate_keystrokes("shift-x");
+        cx.assert_state("«one\ntwo\nˇ»three", Mode::HelixNormal);
+
+        // Unlike `x`, `X` doesn't extend a selection that already covers whole lines.
+        cx.simulate_keystrokes("shift-x");
+        cx.assert_state("«one\ntwo\nˇ»three", Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_regex_selections(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("«one two one threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("s o n e enter");
+        cx.assert_state("«oneˇ» two «oneˇ» three", Mode::HelixNormal);
+
+        cx.set_state("«one, two, threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("shift-s , space enter");
+        cx.assert_state("«oneˇ», «twoˇ», «threeˇ»", Mode::HelixNormal);
+
+        cx.set_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("shift-k t enter");
+        cx.assert_state("one «twoˇ» «threeˇ»", Mode::HelixNormal);
+
+        cx.set_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("alt-shift-k t enter");
+        cx.assert_state("«oneˇ» two three", Mode::HelixNormal);
+
+        // Selections are left alone when nothing matches.
+        cx.set_state("«oneˇ» «twoˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("s x enter");
+        cx.assert_state("«oneˇ» «twoˇ»", Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_match_mode_surround(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("hello «worldˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("m s (");
+        cx.assert_state("hello «(world)ˇ»", Mode::HelixNormal);
+
+        cx.set_state("ˇa b", Mode::HelixNormal);
+        cx.simulate_keystrokes("m s \"");
+        cx.assert_state("«\"a\"ˇ» b", Mode::HelixNormal);
+
+        cx.set_state("(hello ˇworld)", Mode::HelixNormal);
+        cx.simulate_keystrokes("m r ( ]");
+        cx.assert_state("[hello ˇworld]", Mode::HelixNormal);
+
+        cx.set_state("{hello ˇworld}", Mode::HelixNormal);
+        cx.simulate_keystrokes("m d }");
+        cx.assert_state("hello ˇworld", Mode::HelixNormal);
+
+        // Surrounds shared by several selections are only changed once.
+        cx.set_state("'ˇhello ˇworld'", Mode::HelixNormal);
+        cx.simulate_keystrokes("m d '");
+        cx.assert_state("ˇhello ˇworld", Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_match_mode_objects(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("hello wˇorld", Mode::HelixNormal);
+        cx.simulate_keystrokes("m i w");
+        cx.assert_state("hello «worldˇ»", Mode::HelixNormal);
+
+        cx.set_state("call(hello ˇworld)", Mode::HelixNormal);
+        cx.simulate_keystrokes("m i (");
+        cx.assert_state("call(«hello worldˇ»)", Mode::HelixNormal);
+
+        cx.set_state("call(hello ˇworld)", Mode::HelixNormal);
+        cx.simulate_keystrokes("m a (");
+        cx.assert_state("call«(hello world)ˇ»", Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_collapse_flip_and_copy(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("«abcˇ» def", Mode::HelixNormal);
+        cx.simulate_keystrokes("alt-;");
+        cx.assert_state("«ˇabc» def", Mode::HelixNormal);
+
+        cx.simulate_keystrokes(";");
+        cx.assert_state("ˇabc def", Mode::HelixNormal);
+
+        cx.set_state("«abˇ»c\ndef\nghi", Mode::HelixNormal);
+        cx.simulate_keystrokes("shift-c");
+        cx.assert_state("«abˇ»c\n«deˇ»f\nghi", Mode::HelixNormal);
+    }
 }
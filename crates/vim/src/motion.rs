@@ -692,7 +692,7 @@ impl Vim {
                     }
                 }
 
-                Mode::HelixNormal => {}
+                Mode::HelixNormal | Mode::HelixSelect => {}
             }
         }
 
@@ -734,6 +734,7 @@ impl Vim {
             }
 
             Mode::HelixNormal => self.helix_normal_motion(motion.clone(), count, window, cx),
+            Mode::HelixSelect => self.helix_select_motion(motion.clone(), count, window, cx),
         }
         self.clear_operator(window, cx);
         if let Some(operator) = waiting_operator {
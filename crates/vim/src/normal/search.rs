@@ -78,6 +78,9 @@ pub struct Replacement {
     flag_n: bool,
     flag_g: bool,
     flag_c: bool,
+    /// Adapts the replacement to the casing of each match. Vim has no
+    /// equivalent, so this uses the otherwise unused `P` flag.
+    preserve_case: bool,
 }
 
 actions!(
@@ -208,6 +211,10 @@ impl Vim {
     }
 
     pub fn search_submit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(operation) = self.search.helix_regex.take() {
+            self.helix_regex_submit(operation, window, cx);
+            return;
+        }
         self.store_visual_marks(window, cx);
         let Some(pane) = self.pane(window, cx) else {
             return;
@@ -501,6 +508,7 @@ impl Vim {
             if !replacement.flag_g {
                 options.set(SearchOptions::ONE_MATCH_PER_LINE, true);
             }
+            options.set(SearchOptions::PRESERVE_CASE, replacement.preserve_case);
 
             search_bar.set_replacement(Some(&replacement.replacement), cx);
             if replacement.flag_c {
@@ -617,6 +625,7 @@ impl Replacement {
             flag_g: false,
             flag_n: false,
             flag_c: false,
+            preserve_case: false,
         };
 
         for c in flags.chars() {
@@ -626,6 +635,7 @@ impl Replacement {
                 'c' => replacement.flag_c = true,
                 'i' => replacement.case_sensitive = Some(false),
                 'I' => replacement.case_sensitive = Some(true),
+                'P' => replacement.preserve_case = true,
                 _ => {}
             }
         }
@@ -1116,6 +1126,16 @@ mod test {
         );
     }
 
+    #[gpui::test]
+    async fn test_replace_preserve_case(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state("ˇfoo fooBar FOO Foo", Mode::Normal);
+
+        cx.simulate_keystrokes(": s / f o o / b a z / g i P");
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("ˇbaz bazBar BAZ Baz", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_replace_with_range(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -0,0 +1,1452 @@
+use std::{ops::Range, path::PathBuf, process::Stdio, sync::LazyLock};
+
+use anyhow::{Result, anyhow};
+use collections::HashMap;
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use gpui::{
+    Action, App, AppContext as _, Context, Entity, EntityId, EventEmitter, FocusHandle, Focusable,
+    IntoElement, Render, SharedString, Task, WeakEntity, Window, actions,
+};
+use language::{Bias, Buffer, BufferSnapshot, DiagnosticSeverity, Point};
+use picker::{Picker, PickerDelegate};
+use project::search::{SearchQuery, SearchResult};
+use regex::Regex;
+use search::ProjectSearchView;
+use terminal::TaskStatus;
+use text::ToPoint as _;
+use ui::{
+    ActiveTheme, Div, FluentBuilder, Icon, IconName, ParentElement, Styled, StyledTypography, div,
+    h_flex, v_flex,
+};
+use util::{ResultExt, paths::PathMatcher};
+use workspace::{
+    Pane, SaveIntent, SplitDirection, Workspace, item::Item, notifications::NotifyResultExt,
+};
+
+use crate::{Vim, command::command_interceptor, state::VimGlobals};
+
+const MAX_LISTS: usize = 10;
+
+actions!(
+    vim,
+    [
+        /// Replaces the quickfix list with the results of the active project search.
+        QuickfixFromProjectSearch,
+    ]
+);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum QuickfixKind {
+    /// The quickfix list, shared by the whole workspace.
+    Quickfix,
+    /// The location list, which belongs to a single pane.
+    Location,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum QuickfixTarget {
+    Next,
+    Previous,
+    NextFile,
+    PreviousFile,
+    First,
+    Last,
+    Current,
+    Nth(usize),
+}
+
+/// Moves to an entry in the quickfix or location list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixNavigate {
+    pub(crate) kind: QuickfixKind,
+    pub(crate) target: QuickfixTarget,
+}
+
+/// Switches to an older or newer quickfix or location list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixHistory {
+    pub(crate) kind: QuickfixKind,
+    pub(crate) newer: bool,
+}
+
+/// Opens the quickfix or location list in a pane.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixOpen {
+    pub(crate) kind: QuickfixKind,
+}
+
+/// Closes the pane showing the quickfix or location list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixClose {
+    pub(crate) kind: QuickfixKind,
+}
+
+/// Runs an ex command on every entry (or every file) of the quickfix or location list.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixDo {
+    pub(crate) kind: QuickfixKind,
+    pub(crate) per_file: bool,
+    pub(crate) command: String,
+}
+
+/// Fills the quickfix or location list with the matches of a project-wide regex search.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixGrep {
+    pub(crate) kind: QuickfixKind,
+    pub(crate) pattern: String,
+    pub(crate) all_matches: bool,
+    pub(crate) jump: bool,
+    pub(crate) globs: Vec<String>,
+}
+
+/// Fills the quickfix or location list with the project's errors and warnings.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixDiagnostics {
+    pub(crate) kind: QuickfixKind,
+}
+
+/// Runs `make` and fills the quickfix or location list with the locations it reports.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixMake {
+    pub(crate) kind: QuickfixKind,
+    pub(crate) args: String,
+    pub(crate) jump: bool,
+}
+
+/// Fills the quickfix or location list with the locations mentioned in the current buffer.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixFromBuffer {
+    pub(crate) kind: QuickfixKind,
+}
+
+/// Fills the quickfix or location list with the locations reported in the output of the
+/// last task that finished.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct QuickfixFromTask {
+    pub(crate) kind: QuickfixKind,
+}
+
+impl QuickfixGrep {
+    /// Parses the arguments of `:vimgrep`, e.g. `/pattern/gj **/*.rs`.
+    /// As with `:s`, `\(` and `\)` are flipped so that vim-style groups work.
+    pub(crate) fn parse(kind: QuickfixKind, args: &str) -> Option<Self> {
+        let args = args.trim_start();
+        let delimiter = args.chars().next()?;
+        let (pattern, flags, globs) = if delimiter.is_alphanumeric() || delimiter == '"' {
+            let (pattern, globs) = args.split_once(' ').unwrap_or((args, ""));
+            (pattern.to_string(), "", globs)
+        } else {
+            let mut pattern = String::new();
+            let mut escaped = false;
+            let mut end = args.len();
+            for (ix, c) in args.char_indices().skip(1) {
+                if escaped {
+                    escaped = false;
+                    if c != '(' && c != ')' && c != delimiter {
+                        pattern.push('\\');
+                    }
+                    pattern.push(c);
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == delimiter {
+                    end = ix + c.len_utf8();
+                    break;
+                } else {
+                    if c == '(' || c == ')' {
+                        pattern.push('\\');
+                    }
+                    pattern.push(c);
+                }
+            }
+            let rest = &args[end..];
+            let (flags, globs) = rest.split_once(' ').unwrap_or((rest, ""));
+            (pattern, flags, globs)
+        };
+        if pattern.is_empty() || flags.chars().any(|c| c != 'g' && c != 'j') {
+            return None;
+        }
+
+        Some(Self {
+            kind,
+            pattern,
+            all_matches: flags.contains('g'),
+            jump: !flags.contains('j'),
+            globs: globs.split_whitespace().map(ToString::to_string).collect(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct QuickfixEntry {
+    pub buffer: Entity<Buffer>,
+    pub range: Range<text::Anchor>,
+    pub text: SharedString,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct QuickfixList {
+    pub title: SharedString,
+    pub entries: Vec<QuickfixEntry>,
+    pub selected: usize,
+}
+
+impl QuickfixList {
+    pub fn new(title: impl Into<SharedString>, entries: Vec<QuickfixEntry>) -> Self {
+        Self {
+            title: title.into(),
+            entries,
+            selected: 0,
+        }
+    }
+
+    /// Moves the selection and returns the newly selected entry.
+    pub fn navigate(&mut self, target: QuickfixTarget) -> Result<QuickfixEntry> {
+        anyhow::ensure!(!self.entries.is_empty(), "E42: No Errors");
+        let last = self.entries.len() - 1;
+        let selected = match target {
+            QuickfixTarget::Next => self.selected + 1,
+            QuickfixTarget::Previous => self
+                .selected
+                .checked_sub(1)
+                .ok_or_else(|| anyhow!("E553: No more items"))?,
+            QuickfixTarget::NextFile => {
+                let buffer_id = self.entries[self.selected].buffer.entity_id();
+                self.entries
+                    .iter()
+                    .enumerate()
+                    .skip(self.selected)
+                    .find(|(_, entry)| entry.buffer.entity_id() != buffer_id)
+                    .map(|(ix, _)| ix)
+                    .ok_or_else(|| anyhow!("E553: No more items"))?
+            }
+            // Like vim, this goes to the last entry of the previous file.
+            QuickfixTarget::PreviousFile => {
+                let buffer_id = self.entries[self.selected].buffer.entity_id();
+                self.entries[..self.selected]
+                    .iter()
+                    .rposition(|entry| entry.buffer.entity_id() != buffer_id)
+                    .ok_or_else(|| anyhow!("E553: No more items"))?
+            }
+            QuickfixTarget::First => 0,
+            QuickfixTarget::Last => last,
+            QuickfixTarget::Current => self.selected,
+            QuickfixTarget::Nth(n) => n.saturating_sub(1).min(last),
+        };
+        anyhow::ensure!(selected <= last, "E553: No more items");
+        self.selected = selected;
+        Ok(self.entries[selected].clone())
+    }
+}
+
+/// The lists created by `:vimgrep`, `:make` and friends. Like vim, we keep the
+/// last ten so that `:colder` and `:cnewer` can move between them.
+#[derive(Default)]
+pub struct QuickfixStack {
+    lists: Vec<QuickfixList>,
+    current: usize,
+}
+
+impl QuickfixStack {
+    pub fn push(&mut self, list: QuickfixList) {
+        if !self.lists.is_empty() {
+            self.lists.truncate(self.current + 1);
+        }
+        self.lists.push(list);
+        if self.lists.len() > MAX_LISTS {
+            self.lists.remove(0);
+        }
+        self.current = self.lists.len() - 1;
+    }
+
+    pub fn current(&self) -> Option<&QuickfixList> {
+        self.lists.get(self.current)
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut QuickfixList> {
+        self.lists.get_mut(self.current)
+    }
+
+    pub fn older(&mut self) -> Result<&QuickfixList> {
+        self.current = self
+            .current
+            .checked_sub(1)
+            .ok_or_else(|| anyhow!("E380: At bottom of quickfix stack"))?;
+        Ok(&self.lists[self.current])
+    }
+
+    pub fn newer(&mut self) -> Result<&QuickfixList> {
+        anyhow::ensure!(
+            self.current + 1 < self.lists.len(),
+            "E381: At top of quickfix stack"
+        );
+        self.current += 1;
+        Ok(&self.lists[self.current])
+    }
+}
+
+/// A location reported by a compiler or another tool, e.g. `src/main.rs:3:5: error`.
+#[derive(Debug, PartialEq)]
+struct OutputLocation {
+    path: String,
+    row: u32,
+    column: u32,
+    message: String,
+}
+
+static LOCATION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:\s*-->\s*)?([^\s:][^:]*):(\d+)(?::(\d+))?(?::\s*(.*))?$")
+        .expect("invalid location regex")
+});
+
+// Understands `path:line[:col][: message]` and rustc's `--> path:line:col`,
+// which takes its message from the preceding `error: ...` line.
+fn parse_output_locations(output: &str) -> Vec<OutputLocation> {
+    let mut locations = Vec::new();
+    let mut last_message = String::new();
+    for line in output.lines() {
+        let Some(captures) = LOCATION_REGEX.captures(line) else {
+            if !line.starts_with(char::is_whitespace) && !line.trim().is_empty() {
+                last_message = line.trim().to_string();
+            }
+            continue;
+        };
+        let (Some(path), Some(row)) = (captures.get(1), captures.get(2)) else {
+            continue;
+        };
+        let Ok(row) = row.as_str().parse::<u32>() else {
+            continue;
+        };
+        let column = captures
+            .get(3)
+            .and_then(|column| column.as_str().parse().ok())
+            .unwrap_or(1);
+        let message = captures
+            .get(4)
+            .map(|message| message.as_str().trim().to_string())
+            .filter(|message| !message.is_empty())
+            .unwrap_or_else(|| last_message.clone());
+        locations.push(OutputLocation {
+            path: path.as_str().trim().to_string(),
+            row,
+            column,
+            message,
+        });
+    }
+    locations
+}
+
+fn line_text(snapshot: &BufferSnapshot, row: u32) -> SharedString {
+    snapshot
+        .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+        .collect::<String>()
+        .trim()
+        .to_string()
+        .into()
+}
+
+/// Identifies the list a command operates on: the workspace's quickfix list,
+/// or the location list of the pane the command was run from.
+#[derive(Clone)]
+struct QuickfixScope {
+    kind: QuickfixKind,
+    workspace: Entity<Workspace>,
+    pane: Entity<Pane>,
+}
+
+impl QuickfixScope {
+    fn owner_id(&self) -> EntityId {
+        match self.kind {
+            QuickfixKind::Quickfix => self.workspace.entity_id(),
+            QuickfixKind::Location => self.pane.entity_id(),
+        }
+    }
+
+    fn push_list(&self, list: QuickfixList, cx: &mut App) {
+        let owner_id = self.owner_id();
+        if !Vim::globals(cx).quickfix_lists.contains_key(&owner_id) {
+            let remove_lists = move |cx: &mut App| {
+                Vim::update_globals(cx, |globals, _| {
+                    globals.quickfix_lists.remove(&owner_id);
+                })
+            };
+            match self.kind {
+                QuickfixKind::Quickfix => {
+                    cx.observe_release(&self.workspace, move |_, cx| remove_lists(cx))
+                }
+                QuickfixKind::Location => {
+                    cx.observe_release(&self.pane, move |_, cx| remove_lists(cx))
+                }
+            }
+            .detach();
+        }
+        Vim::globals(cx)
+            .quickfix_lists
+            .entry(owner_id)
+            .or_default()
+            .push(list);
+    }
+
+    fn refresh_views(&self, window: &mut Window, cx: &mut App) {
+        for view in quickfix_views(self.workspace.read(cx), self.owner_id(), cx) {
+            view.update(cx, |view, cx| view.refresh(window, cx));
+        }
+    }
+
+    fn notify_err(&self, error: anyhow::Error, cx: &mut App) {
+        self.workspace.update(cx, |workspace, cx| {
+            Err::<(), _>(error).notify_err(workspace, cx);
+        });
+    }
+}
+
+fn quickfix_views(
+    workspace: &Workspace,
+    owner_id: EntityId,
+    cx: &App,
+) -> Vec<Entity<QuickfixView>> {
+    workspace
+        .items_of_type::<QuickfixView>(cx)
+        .filter(|view| view.read(cx).delegate_owner(cx) == owner_id)
+        .collect()
+}
+
+fn open_entry(
+    workspace: &mut Workspace,
+    pane: Entity<Pane>,
+    entry: &QuickfixEntry,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Entity<Editor> {
+    let editor =
+        workspace.open_project_item::<Editor>(pane, entry.buffer.clone(), true, true, window, cx);
+    let point = entry
+        .range
+        .start
+        .to_point(&entry.buffer.read(cx).snapshot());
+    editor.update(cx, |editor, cx| {
+        editor.change_selections(
+            SelectionEffects::scroll(Autoscroll::center()),
+            window,
+            cx,
+            |s| s.select_ranges([point..point]),
+        );
+    });
+    editor
+}
+
+fn open_view(
+    workspace: &mut Workspace,
+    scope: &QuickfixScope,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if let Some(view) = quickfix_views(workspace, scope.owner_id(), cx)
+        .into_iter()
+        .next()
+    {
+        view.update(cx, |view, cx| view.refresh(window, cx));
+        workspace.activate_item(&view, true, true, window, cx);
+        return;
+    }
+    let view = cx.new(|cx| QuickfixView::new(scope, window, cx));
+    workspace.split_item(SplitDirection::Down, Box::new(view), window, cx);
+}
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
+    Vim::action(editor, cx, |vim, action: &QuickfixNavigate, window, cx| {
+        vim.quickfix_navigate(action, window, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &QuickfixHistory, window, cx| {
+        vim.quickfix_history(action, window, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &QuickfixOpen, window, cx| {
+        vim.quickfix_open(action, window, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &QuickfixClose, window, cx| {
+        vim.quickfix_close(action, window, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &QuickfixDo, window, cx| {
+        vim.quickfix_do(action, window, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &QuickfixGrep, window, cx| {
+        vim.quickfix_grep(action, window, cx)
+    });
+    Vim::action(
+        editor,
+        cx,
+        |vim, action: &QuickfixDiagnostics, window, cx| {
+            vim.quickfix_diagnostics(action, window, cx)
+        },
+    );
+    Vim::action(editor, cx, |vim, action: &QuickfixMake, window, cx| {
+        vim.quickfix_make(action, window, cx)
+    });
+    Vim::action(
+        editor,
+        cx,
+        |vim, action: &QuickfixFromBuffer, window, cx| vim.quickfix_from_buffer(action, window, cx),
+    );
+    Vim::action(editor, cx, |vim, action: &QuickfixFromTask, window, cx| {
+        vim.quickfix_from_task(action, window, cx)
+    });
+}
+
+impl Vim {
+    fn quickfix_scope(
+        &self,
+        kind: QuickfixKind,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<QuickfixScope> {
+        Some(QuickfixScope {
+            kind,
+            workspace: self.workspace(window)?,
+            pane: self.pane(window, cx)?,
+        })
+    }
+
+    fn set_quickfix_list(
+        &mut self,
+        scope: &QuickfixScope,
+        list: QuickfixList,
+        jump: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let is_empty = list.entries.is_empty();
+        scope.push_list(list, cx);
+        scope.refresh_views(window, cx);
+        if is_empty {
+            scope.notify_err(anyhow!("E42: No Errors"), cx);
+        } else if jump {
+            self.quickfix_jump(scope, QuickfixTarget::First, window, cx);
+        }
+    }
+
+    fn quickfix_navigate(
+        &mut self,
+        action: &QuickfixNavigate,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(scope) = self.quickfix_scope(action.kind, window, cx) {
+            self.quickfix_jump(&scope, action.target, window, cx);
+        }
+    }
+
+    fn quickfix_jump(
+        &mut self,
+        scope: &QuickfixScope,
+        target: QuickfixTarget,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let result = Vim::globals(cx)
+            .quickfix_lists
+            .get_mut(&scope.owner_id())
+            .and_then(|stack| stack.current_mut())
+            .map(|list| list.navigate(target));
+        let entry = match result {
+            // Without a list these commands keep navigating diagnostics.
+            None => {
+                let fallback = match target {
+                    QuickfixTarget::Next => {
+                        editor::actions::GoToDiagnostic::default().boxed_clone()
+                    }
+                    QuickfixTarget::Previous => {
+                        editor::actions::GoToPreviousDiagnostic::default().boxed_clone()
+                    }
+                    QuickfixTarget::Current => editor::actions::Hover.boxed_clone(),
+                    _ => {
+                        scope.notify_err(anyhow!("E42: No Errors"), cx);
+                        return;
+                    }
+                };
+                window.dispatch_action(fallback, cx);
+                return;
+            }
+            Some(Err(error)) => {
+                scope.notify_err(error, cx);
+                return;
+            }
+            Some(Ok(entry)) => entry,
+        };
+        scope.workspace.update(cx, |workspace, cx| {
+            open_entry(workspace, scope.pane.clone(), &entry, window, cx);
+        });
+        scope.refresh_views(window, cx);
+    }
+
+    fn quickfix_history(
+        &mut self,
+        action: &QuickfixHistory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(scope) = self.quickfix_scope(action.kind, window, cx) else {
+            return;
+        };
+        let result = match Vim::globals(cx).quickfix_lists.get_mut(&scope.owner_id()) {
+            Some(stack) if action.newer => stack.newer().map(|_| ()),
+            Some(stack) => stack.older().map(|_| ()),
+            None => Err(anyhow!("E42: No Errors")),
+        };
+        match result {
+            Ok(()) => scope.refresh_views(window, cx),
+            Err(error) => scope.notify_err(error, cx),
+        }
+    }
+
+    fn quickfix_open(
+        &mut self,
+        action: &QuickfixOpen,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(scope) = self.quickfix_scope(action.kind, window, cx) else {
+            return;
+        };
+        scope.workspace.clone().update(cx, |workspace, cx| {
+            open_view(workspace, &scope, window, cx);
+        });
+    }
+
+    fn quickfix_close(
+        &mut self,
+        action: &QuickfixClose,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(scope) = self.quickfix_scope(action.kind, window, cx) else {
+            return;
+        };
+        scope.workspace.clone().update(cx, |workspace, cx| {
+            for view in quickfix_views(workspace, scope.owner_id(), cx) {
+                let Some(pane) = workspace.pane_for(&view) else {
+                    continue;
+                };
+                pane.update(cx, |pane, cx| {
+                    pane.close_item_by_id(view.entity_id(), SaveIntent::Skip, window, cx)
+                })
+                .detach_and_log_err(cx);
+            }
+        });
+    }
+
+    fn quickfix_do(&mut self, action: &QuickfixDo, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(scope) = self.quickfix_scope(action.kind, window, cx) else {
+            return;
+        };
+        let Some(command) = command_interceptor(&action.command, cx)
+            .first()
+            .map(|result| result.action.boxed_clone())
+        else {
+            scope.notify_err(
+                anyhow!("E492: Not an editor command: {}", action.command),
+                cx,
+            );
+            return;
+        };
+        let Some(list) = Vim::globals(cx)
+            .quickfix_lists
+            .get(&scope.owner_id())
+            .and_then(|stack| stack.current())
+            .filter(|list| !list.entries.is_empty())
+        else {
+            scope.notify_err(anyhow!("E42: No Errors"), cx);
+            return;
+        };
+
+        // `:cdo` runs the command on each entry, and `:cfdo` on the first entry of each buffer.
+        let mut entries = list.entries.clone();
+        if action.per_file {
+            let mut seen = Vec::new();
+            entries.retain(|entry| {
+                let first = !seen.contains(&entry.buffer);
+                if first {
+                    seen.push(entry.buffer.clone());
+                }
+                first
+            });
+        }
+
+        cx.spawn_in(window, async move |_, cx| {
+            for entry in entries {
+                scope.workspace.update_in(cx, |workspace, window, cx| {
+                    open_entry(workspace, scope.pane.clone(), &entry, window, cx);
+                })?;
+                cx.update(|window, cx| {
+                    // The editor was just focused, so draw to make it the target of the dispatch.
+                    window.draw(cx).clear();
+                    window.dispatch_action(command.boxed_clone(), cx);
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn quickfix_grep(
+        &mut self,
+        action: &QuickfixGrep,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(scope) = self.quickfix_scope(action.kind, window, cx) else {
+            return;
+        };
+        let query = PathMatcher::new(&action.globs)
+            .map_err(anyhow::Error::from)
+            .and_then(|files_to_include| {
+                SearchQuery::regex(
+                    &action.pattern,
+                    false,
+                    true,
+                    false,
+                    !action.all_matches,
+                    files_to_include,
+                    PathMatcher::default(),
+                    false,
+                    None,
+                )
+            });
+        let query = match query {
+            Ok(query) => query,
+            Err(error) => {
+                scope.notify_err(error, cx);
+                return;
+            }
+        };
+        let project = scope.workspace.read(cx).project().clone();
+        let results = project.update(cx, |project, cx| project.search(query, cx));
+        let title = format!(":vimgrep /{}/", action.pattern);
+        let jump = action.jump;
+
+        cx.spawn_in(window, async move |vim, cx| {
+            let mut matches = Vec::new();
+            while let Ok(result) = results.recv().await {
+                match result {
+                    SearchResult::Buffer { buffer, ranges } => matches.push((buffer, ranges)),
+                    SearchResult::LimitReached => break,
+                }
+            }
+            vim.update_in(cx, |vim, window, cx| {
+                let mut entries = Vec::new();
+                for (buffer, ranges) in matches {
+                    let snapshot = buffer.read(cx).snapshot();
+                    let path = buffer.read(cx).file().map(|file| file.full_path(cx));
+                    for range in ranges {
+                        let start = range.start.to_point(&snapshot);
+                        entries.push((
+                            (path.clone(), start),
+                            QuickfixEntry {
+                                buffer: buffer.clone(),
+                                text: line_text(&snapshot, start.row),
+                                range,
+                            },
+                        ));
+                    }
+                }
+                if entries.is_empty() {
+                    scope.notify_err(anyhow!("E480: No match: {}", title), cx);
+                    return;
+                }
+                entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+                let entries = entries.into_iter().map(|(_, entry)| entry).collect();
+                vim.set_quickfix_list(&scope, QuickfixList::new(title, entries), jump, window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn quickfix_diagnostics(
+        &mut self,
+        action: &QuickfixDiagnostics,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(scope) = self.quickfix_scope(action.kind, window, cx) else {
+            return;
+        };
+        let project = scope.workspace.read(cx).project().clone();
+        let mut paths = project
+            .read(cx)
+            .diagnostic_summaries(false, cx)
+            .filter(|(_, _, summary)| summary.error_count + summary.warning_count > 0)
+            .map(|(path, _, _)| path)
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths.dedup();
+        let buffers = project.update(cx, |project, cx| {
+            paths
+                .into_iter()
+                .map(|path| project.open_buffer(path, cx))
+                .collect::<Vec<_>>()
+        });
+
+        cx.spawn_in(window, async move |vim, cx| {
+            let buffers = futures::future::join_all(buffers).await;
+            vim.update_in(cx, |vim, window, cx| {
+                let mut entries = Vec::new();
+                for buffer in buffers.into_iter().filter_map(|buffer| buffer.log_err()) {
+                    let snapshot = buffer.read(cx).snapshot();
+                    for entry in
+                        snapshot.diagnostics_in_range::<_, text::Anchor>(0..snapshot.len(), false)
+                    {
+                        let diagnostic = &entry.diagnostic;
+                        let severity = if diagnostic.severity == DiagnosticSeverity::ERROR {
+                            "error"
+                        } else if diagnostic.severity == DiagnosticSeverity::WARNING {
+                            "warning"
+                        } else {
+                            continue;
+                        };
+                        if !diagnostic.is_primary {
+                            continue;
+                        }
+                        let message = diagnostic.message.lines().next().unwrap_or_default();
+                        entries.push(QuickfixEntry {
+                            buffer: buffer.clone(),
+                            range: entry.range,
+                            text: format!("{severity}: {message}").into(),
+                        });
+                    }
+                }
+                vim.set_quickfix_list(
+                    &scope,
+                    QuickfixList::new(":cdiagnostics", entries),
+                    true,
+                    window,
+                    cx,
+                );
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn quickfix_make(
+        &mut self,
+        action: &QuickfixMake,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(scope) = self.quickfix_scope(action.kind, window, cx) else {
+            return;
+        };
+        let command = if action.args.is_empty() {
+            "make".to_string()
+        } else {
+            format!("make {}", action.args)
+        };
+        let project = scope.workspace.read(cx).project().clone();
+        let mut process = project.read(cx).exec_in_shell(command.clone(), cx);
+        process.stdin(Stdio::null());
+        process.stdout(Stdio::piped());
+        process.stderr(Stdio::piped());
+        util::set_pre_exec_to_start_new_session(&mut process);
+        let jump = action.jump;
+
+        cx.spawn_in(window, async move |vim, cx| {
+            let output = cx.background_spawn(async move { process.output() }).await?;
+            let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            let task = vim.update_in(cx, |vim, window, cx| {
+                vim.quickfix_from_output(
+                    scope,
+                    format!(":{command}"),
+                    &text,
+                    None,
+                    jump,
+                    window,
+                    cx,
+                )
+            })?;
+            task.await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn quickfix_from_buffer(
+        &mut self,
+        action: &QuickfixFromBuffer,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(scope) = self.quickfix_scope(action.kind, window, cx) else {
+            return;
+        };
+        let Some(text) = self.update_editor(cx, |_, editor, cx| editor.text(cx)) else {
+            return;
+        };
+        self.quickfix_from_output(scope, ":cbuffer", &text, None, true, window, cx)
+            .detach_and_log_err(cx);
+    }
+
+    fn quickfix_from_task(
+        &mut self,
+        action: &QuickfixFromTask,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(scope) = self.quickfix_scope(action.kind, window, cx) else {
+            return;
+        };
+        let project = scope.workspace.read(cx).project().clone();
+        let terminal = project
+            .read(cx)
+            .local_terminal_handles()
+            .iter()
+            .rev()
+            .filter_map(|terminal| terminal.upgrade())
+            .find(|terminal| {
+                terminal
+                    .read(cx)
+                    .task()
+                    .is_some_and(|task| task.status != TaskStatus::Running)
+            });
+        let Some(terminal) = terminal else {
+            scope.notify_err(anyhow!("No task has finished"), cx);
+            return;
+        };
+        let terminal = terminal.read(cx);
+        let title = terminal
+            .task()
+            .map(|task| format!(":ctask {}", task.label))
+            .unwrap_or_default();
+        let output = terminal.get_content();
+        let cwd = terminal.working_directory();
+        self.quickfix_from_output(scope, title, &output, cwd, true, window, cx)
+            .detach_and_log_err(cx);
+    }
+
+    fn quickfix_from_output(
+        &mut self,
+        scope: QuickfixScope,
+        title: impl Into<SharedString>,
+        output: &str,
+        cwd: Option<PathBuf>,
+        jump: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let title = title.into();
+        let locations = parse_output_locations(output);
+        let project = scope.workspace.read(cx).project().clone();
+        let cwd = cwd.or_else(|| project.read(cx).first_project_directory(cx));
+        let mut buffers = HashMap::default();
+        project.update(cx, |project, cx| {
+            for location in &locations {
+                let path = match &cwd {
+                    Some(cwd) => cwd.join(&location.path),
+                    None => PathBuf::from(&location.path),
+                };
+                let Some(project_path) = project.find_project_path(&path, cx) else {
+                    continue;
+                };
+                buffers
+                    .entry(location.path.clone())
+                    .or_insert_with(|| project.open_buffer(project_path, cx));
+            }
+        });
+
+        cx.spawn_in(window, async move |vim, cx| {
+            let mut opened = HashMap::default();
+            for (path, buffer) in buffers {
+                if let Some(buffer) = buffer.await.log_err() {
+                    opened.insert(path, buffer);
+                }
+            }
+            vim.update_in(cx, |vim, window, cx| {
+                let entries = locations
+                    .into_iter()
+                    .filter_map(|location| {
+                        let buffer = opened.get(&location.path)?;
+                        let snapshot = buffer.read(cx).snapshot();
+                        let point = snapshot.clip_point(
+                            Point::new(
+                                location.row.saturating_sub(1),
+                                location.column.saturating_sub(1),
+                            ),
+                            Bias::Left,
+                        );
+                        let anchor = snapshot.anchor_before(point);
+                        Some(QuickfixEntry {
+                            buffer: buffer.clone(),
+                            range: anchor..anchor,
+                            text: location.message.into(),
+                        })
+                    })
+                    .collect();
+                vim.set_quickfix_list(&scope, QuickfixList::new(title, entries), jump, window, cx);
+            })
+        })
+    }
+}
+
+struct QuickfixMatch {
+    location: String,
+    text: SharedString,
+}
+
+pub struct QuickfixViewDelegate {
+    owner_id: EntityId,
+    workspace: WeakEntity<Workspace>,
+    target_pane: WeakEntity<Pane>,
+    matches: Vec<QuickfixMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for QuickfixViewDelegate {
+    type ListItem = Div;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.selected_index = ix;
+        cx.notify();
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> std::sync::Arc<str> {
+        std::sync::Arc::default()
+    }
+
+    fn update_matches(
+        &mut self,
+        _: String,
+        _: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        self.matches.clear();
+        let Some(list) = cx
+            .global::<VimGlobals>()
+            .quickfix_lists
+            .get(&self.owner_id)
+            .and_then(|stack| stack.current())
+        else {
+            return Task::ready(());
+        };
+        self.selected_index = list.selected;
+        self.matches = list
+            .entries
+            .iter()
+            .map(|entry| {
+                let buffer = entry.buffer.read(cx);
+                let point = entry.range.start.to_point(&buffer.snapshot());
+                let path = buffer
+                    .file()
+                    .map(|file| file.path().to_string_lossy().to_string())
+                    .unwrap_or_else(|| "[No Name]".to_string());
+                QuickfixMatch {
+                    location: format!("{}|{} col {}|", path, point.row + 1, point.column + 1),
+                    text: entry.text.clone(),
+                }
+            })
+            .collect();
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let Some(entry) = Vim::globals(cx)
+            .quickfix_lists
+            .get_mut(&self.owner_id)
+            .and_then(|stack| stack.current_mut())
+            .and_then(|list| {
+                list.navigate(QuickfixTarget::Nth(self.selected_index + 1))
+                    .log_err()
+            })
+        else {
+            return;
+        };
+        let pane = self
+            .target_pane
+            .upgrade()
+            .filter(|pane| workspace.read(cx).panes().contains(pane))
+            .unwrap_or_else(|| workspace.read(cx).active_pane().clone());
+        workspace.update(cx, |workspace, cx| {
+            open_entry(workspace, pane, &entry, window, cx);
+        });
+    }
+
+    fn dismissed(&mut self, _: &mut Window, _: &mut Context<Picker<Self>>) {}
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let quickfix_match = self.matches.get(ix)?;
+        Some(
+            h_flex()
+                .when(selected, |el| el.bg(cx.theme().colors().element_selected))
+                .font_buffer(cx)
+                .text_buffer(cx)
+                .px_2()
+                .gap_2()
+                .child(
+                    div()
+                        .text_color(cx.theme().colors().text_accent)
+                        .child(quickfix_match.location.clone()),
+                )
+                .child(quickfix_match.text.clone()),
+        )
+    }
+}
+
+pub struct QuickfixView {
+    kind: QuickfixKind,
+    picker: Entity<Picker<QuickfixViewDelegate>>,
+}
+
+impl QuickfixView {
+    pub(crate) fn register(workspace: &mut Workspace, _window: Option<&mut Window>) {
+        workspace.register_action(|workspace, _: &QuickfixFromProjectSearch, window, cx| {
+            let Some(search_view) = workspace
+                .active_item_as::<ProjectSearchView>(cx)
+                .or_else(|| workspace.items_of_type::<ProjectSearchView>(cx).next())
+            else {
+                return;
+            };
+            let search_view = search_view.read(cx);
+            let multibuffer = search_view.results_editor().read(cx).buffer().read(cx);
+            let entries = search_view
+                .get_matches(cx)
+                .into_iter()
+                .filter_map(|range| {
+                    let buffer = multibuffer.buffer(range.start.buffer_id?)?;
+                    let snapshot = buffer.read(cx).snapshot();
+                    let row = range.start.text_anchor.to_point(&snapshot).row;
+                    Some(QuickfixEntry {
+                        text: line_text(&snapshot, row),
+                        range: range.start.text_anchor..range.end.text_anchor,
+                        buffer,
+                    })
+                })
+                .collect();
+            let title = format!("Project search: {}", search_view.search_query_text(cx));
+
+            let scope = QuickfixScope {
+                kind: QuickfixKind::Quickfix,
+                workspace: cx.entity(),
+                pane: workspace.active_pane().clone(),
+            };
+            scope.push_list(QuickfixList::new(title, entries), cx);
+            open_view(workspace, &scope, window, cx);
+        });
+    }
+
+    fn new(scope: &QuickfixScope, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let delegate = QuickfixViewDelegate {
+            owner_id: scope.owner_id(),
+            workspace: scope.workspace.downgrade(),
+            target_pane: scope.pane.downgrade(),
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new(|cx| {
+            Picker::nonsearchable_uniform_list(delegate, window, cx)
+                .max_height(None)
+                .modal(false)
+        });
+        Self {
+            kind: scope.kind,
+            picker,
+        }
+    }
+
+    fn delegate_owner(&self, cx: &App) -> EntityId {
+        self.picker.read(cx).delegate.owner_id
+    }
+
+    fn refresh(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker
+            .update(cx, |picker, cx| picker.refresh(window, cx));
+        cx.notify();
+    }
+}
+
+impl EventEmitter<()> for QuickfixView {}
+
+impl Focusable for QuickfixView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for QuickfixView {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().size_full().child(self.picker.clone())
+    }
+}
+
+impl Item for QuickfixView {
+    type Event = ();
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        let title = cx
+            .try_global::<VimGlobals>()
+            .and_then(|globals| globals.quickfix_lists.get(&self.delegate_owner(cx)))
+            .and_then(|stack| stack.current())
+            .map(|list| list.title.clone())
+            .unwrap_or_default();
+        let name = match self.kind {
+            QuickfixKind::Quickfix => "Quickfix List",
+            QuickfixKind::Location => "Location List",
+        };
+        if title.is_empty() {
+            name.into()
+        } else {
+            format!("{name} {title}").into()
+        }
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::ListTree))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use util::path;
+
+    use super::{OutputLocation, QuickfixGrep, QuickfixKind, parse_output_locations};
+    use crate::{state::Mode, test::VimTestContext};
+    use editor::Editor;
+    use gpui::TestAppContext;
+    use std::path::Path;
+
+    #[test]
+    fn test_parse_vimgrep_args() {
+        let grep = QuickfixGrep::parse(QuickfixKind::Quickfix, "/foo\\(bar\\)/gj **/*.rs *.md")
+            .expect("should parse");
+        assert_eq!(grep.pattern, "foo(bar)");
+        assert!(grep.all_matches);
+        assert!(!grep.jump);
+        assert_eq!(grep.globs, vec!["**/*.rs", "*.md"]);
+
+        let grep = QuickfixGrep::parse(QuickfixKind::Location, "foo src/*.rs").expect("parse");
+        assert_eq!(grep.pattern, "foo");
+        assert!(!grep.all_matches);
+        assert!(grep.jump);
+        assert_eq!(grep.globs, vec!["src/*.rs"]);
+
+        assert!(QuickfixGrep::parse(QuickfixKind::Quickfix, "//").is_none());
+        assert!(QuickfixGrep::parse(QuickfixKind::Quickfix, "/a/x").is_none());
+    }
+
+    #[test]
+    fn test_parse_output_locations() {
+        let output = indoc! {"
+            src/lib.rs:3:5: unused variable
+            error[E0425]: cannot find value `x` in this scope
+             --> src/main.rs:10:9
+              |
+            README.md:7: trailing whitespace
+            warning: build failed
+        "};
+        assert_eq!(
+            parse_output_locations(output),
+            vec![
+                OutputLocation {
+                    path: "src/lib.rs".into(),
+                    row: 3,
+                    column: 5,
+                    message: "unused variable".into(),
+                },
+                OutputLocation {
+                    path: "src/main.rs".into(),
+                    row: 10,
+                    column: 9,
+                    message: "error[E0425]: cannot find value `x` in this scope".into(),
+                },
+                OutputLocation {
+                    path: "README.md".into(),
+                    row: 7,
+                    column: 1,
+                    message: "trailing whitespace".into(),
+                },
+            ]
+        );
+    }
+
+    fn active_path(cx: &mut VimTestContext) -> String {
+        cx.workspace(|workspace, _, cx| {
+            let editor = workspace
+                .active_item_as::<Editor>(cx)
+                .expect("no active editor");
+            let buffer = editor.read(cx).buffer().read(cx).as_singleton();
+            let file = buffer.and_then(|buffer| buffer.read(cx).file().cloned());
+            file.and_then(|file| file.as_local().map(|file| file.abs_path(cx)))
+                .map(|path| path.to_string_lossy().to_string())
+                .unwrap_or_default()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_vimgrep_and_navigation(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), b"one\ntodo: a\n".to_vec())
+            .await;
+        fs.as_fake()
+            .insert_file(
+                path!("/root/dir/b.rs"),
+                b"todo: b1\ntwo\ntodo: b2\n".to_vec(),
+            )
+            .await;
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": v i m g r e p space / t o d o / enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/a.rs"));
+        cx.editor =
+            cx.workspace(|workspace, _, cx| workspace.active_item_as::<Editor>(cx).unwrap());
+        cx.assert_state("one\nˇtodo: a\n", Mode::Normal);
+
+        cx.simulate_keystrokes(": c n enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/b.rs"));
+
+        cx.simulate_keystrokes(": c n enter");
+        cx.run_until_parked();
+        cx.editor =
+            cx.workspace(|workspace, _, cx| workspace.active_item_as::<Editor>(cx).unwrap());
+        cx.assert_state("todo: b1\ntwo\nˇtodo: b2\n", Mode::Normal);
+
+        cx.simulate_keystrokes(": c p f enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/a.rs"));
+
+        cx.simulate_keystrokes(": c l a enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/b.rs"));
+    }
+
+    #[gpui::test]
+    async fn test_quickfix_history(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), b"alpha\n".to_vec())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/b.rs"), b"beta\n".to_vec())
+            .await;
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": v i m g r e p space / a l p h a / enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes(": v i m g r e p space / b e t a / enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/b.rs"));
+
+        cx.simulate_keystrokes(": c o l d e r enter");
+        cx.simulate_keystrokes(": c c enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/a.rs"));
+
+        cx.simulate_keystrokes(": c n e w e r enter");
+        cx.simulate_keystrokes(": c c enter");
+        cx.run_until_parked();
+        assert_eq!(active_path(&mut cx), path!("/root/dir/b.rs"));
+    }
+
+    #[gpui::test]
+    async fn test_cdo(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/a.rs"), b"keep\ndrop a\n".to_vec())
+            .await;
+        fs.as_fake()
+            .insert_file(path!("/root/dir/b.rs"), b"drop b\nkeep\n".to_vec())
+            .await;
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes(": v i m g r e p space / d r o p / j enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes(": c d o space d enter");
+        cx.run_until_parked();
+
+        let texts = cx.buffer_texts();
+        assert!(texts.contains(&(Path::new(path!("/root/dir/a.rs")).into(), "keep\n".into())));
+        assert!(texts.contains(&(Path::new(path!("/root/dir/b.rs")).into(), "keep\n".into())));
+    }
+
+    #[gpui::test]
+    async fn test_cdo_runs_on_each_entry_of_a_file(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(
+                path!("/root/dir/a.rs"),
+                b"one 1\nkeep\none 2\nkeep\n".to_vec(),
+            )
+            .await;
+        fs.as_fake()
+            .insert_file(
+                path!("/root/dir/b.rs"),
+                b"two 1\nkeep\ntwo 2\nkeep\n".to_vec(),
+            )
+            .await;
+        cx.run_until_parked();
+
+        // `:cdo` deletes both entries of the file...
+        cx.simulate_keystrokes(": v i m g r e p space / o n e / j enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes(": c d o space d enter");
+        cx.run_until_parked();
+
+        // ...while `:cfdo` only runs on the first entry of each file.
+        cx.simulate_keystrokes(": v i m g r e p space / t w o / j enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes(": c f d o space d enter");
+        cx.run_until_parked();
+
+        let texts = cx.buffer_texts();
+        assert!(texts.contains(&(
+            Path::new(path!("/root/dir/a.rs")).into(),
+            "keep\nkeep\n".into()
+        )));
+        assert!(texts.contains(&(
+            Path::new(path!("/root/dir/b.rs")).into(),
+            "keep\ntwo 2\nkeep\n".into()
+        )));
+    }
+
+    #[gpui::test]
+    async fn test_quickfix_falls_back_to_diagnostics(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state("ˇone\ntwo", Mode::Normal);
+        cx.simulate_keystrokes(": c o l d e r enter");
+        cx.run_until_parked();
+        cx.simulate_keystrokes(": c n enter");
+        cx.assert_state("ˇone\ntwo", Mode::Normal);
+    }
+}
@@ -1,7 +1,11 @@
+use crate::arglist::ArgList;
 use crate::command::command_interceptor;
+use crate::helix::HelixRegexOperation;
 use crate::motion::MotionKind;
 use crate::normal::repeat::Replayer;
+use crate::quickfix::{QuickfixStack, QuickfixView};
 use crate::surrounds::SurroundsType;
+use crate::vimrc::VimrcReport;
 use crate::{ToggleMarksView, ToggleRegistersView, UseSystemClipboard, Vim, VimAddon, VimSettings};
 use crate::{motion::Motion, object::Object};
 use anyhow::Result;
@@ -44,6 +48,7 @@ pub enum Mode {
     VisualLine,
     VisualBlock,
     HelixNormal,
+    HelixSelect,
 }
 
 impl Display for Mode {
@@ -56,6 +61,7 @@ impl Display for Mode {
             Mode::VisualLine => write!(f, "VISUAL LINE"),
             Mode::VisualBlock => write!(f, "VISUAL BLOCK"),
             Mode::HelixNormal => write!(f, "HELIX NORMAL"),
+            Mode::HelixSelect => write!(f, "HELIX SELECT"),
         }
     }
 }
@@ -64,9 +70,15 @@ impl Mode {
     pub fn is_visual(&self) -> bool {
         match self {
             Self::Visual | Self::VisualLine | Self::VisualBlock => true,
-            Self::Normal | Self::Insert | Self::Replace | Self::HelixNormal => false,
+            Self::Normal | Self::Insert | Self::Replace | Self::HelixNormal | Self::HelixSelect => {
+                false
+            }
         }
     }
+
+    pub fn is_helix(&self) -> bool {
+        matches!(self, Self::HelixNormal | Self::HelixSelect)
+    }
 }
 
 impl Default for Mode {
@@ -132,6 +144,11 @@ pub enum Operator {
     ToggleComments,
     ReplaceWithRegister,
     Exchange,
+    HelixSurroundAdd,
+    HelixSurroundReplace {
+        replaced_char: Option<char>,
+    },
+    HelixSurroundDelete,
 }
 
 #[derive(Default, Clone, Debug)]
@@ -224,6 +241,12 @@ pub struct VimGlobals {
     pub focused_vim: Option<WeakEntity<Vim>>,
 
     pub marks: HashMap<EntityId, Entity<MarksState>>,
+    /// Quickfix lists are keyed by workspace, location lists by pane.
+    pub quickfix_lists: HashMap<EntityId, QuickfixStack>,
+    pub arg_lists: HashMap<EntityId, ArgList>,
+
+    pub hidden: bool,
+    pub autowrite: bool,
 }
 
 pub struct MarksState {
@@ -675,7 +698,10 @@ impl Global for VimGlobals {}
 
 impl VimGlobals {
     pub(crate) fn register(cx: &mut App) {
-        cx.set_global(VimGlobals::default());
+        cx.set_global(VimGlobals {
+            hidden: true,
+            ..Default::default()
+        });
 
         cx.observe_keystrokes(|event, _, cx| {
             let Some(action) = event.action.as_ref().map(|action| action.boxed_clone()) else {
@@ -695,6 +721,16 @@ impl VimGlobals {
         })
         .detach();
 
+        cx.observe_new(|workspace: &mut Workspace, window, _| {
+            QuickfixView::register(workspace, window);
+        })
+        .detach();
+
+        cx.observe_new(|workspace: &mut Workspace, window, _| {
+            VimrcReport::register(workspace, window);
+        })
+        .detach();
+
         let mut was_enabled = None;
 
         cx.observe_global::<SettingsStore>(move |cx| {
@@ -983,6 +1019,7 @@ pub struct SearchState {
     pub prior_selections: Vec<Range<Anchor>>,
     pub prior_operator: Option<Operator>,
     pub prior_mode: Mode,
+    pub helix_regex: Option<HelixRegexOperation>,
 }
 
 impl Operator {
@@ -1024,6 +1061,9 @@ impl Operator {
             Operator::RecordRegister => "q",
             Operator::ReplayRegister => "@",
             Operator::ToggleComments => "gc",
+            Operator::HelixSurroundAdd => "ms",
+            Operator::HelixSurroundReplace { .. } => "mr",
+            Operator::HelixSurroundDelete => "md",
         }
     }
 
@@ -1037,6 +1077,9 @@ impl Operator {
             } => format!("^V{prefix}"),
             Operator::AutoIndent => "=".to_string(),
             Operator::ShellCommand => "=".to_string(),
+            Operator::HelixSurroundReplace {
+                replaced_char: Some(replaced_char),
+            } => format!("mr{replaced_char}"),
             _ => self.id().to_string(),
         }
     }
@@ -1057,7 +1100,10 @@ impl Operator {
             | Operator::Digraph { .. }
             | Operator::Literal { .. }
             | Operator::ChangeSurrounds { target: Some(_) }
-            | Operator::DeleteSurrounds => true,
+            | Operator::DeleteSurrounds
+            | Operator::HelixSurroundAdd
+            | Operator::HelixSurroundReplace { .. }
+            | Operator::HelixSurroundDelete => true,
             Operator::Change
             | Operator::Delete
             | Operator::Yank
@@ -1114,7 +1160,10 @@ impl Operator {
             | Operator::Jump { .. }
             | Operator::Register
             | Operator::RecordRegister
-            | Operator::ReplayRegister => false,
+            | Operator::ReplayRegister
+            | Operator::HelixSurroundAdd
+            | Operator::HelixSurroundReplace { .. }
+            | Operator::HelixSurroundDelete => false,
         }
     }
 }
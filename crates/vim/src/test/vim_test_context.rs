@@ -1,4 +1,8 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    path::Path,
+    sync::Arc,
+};
 
 use editor::test::editor_lsp_test_context::EditorLspTestContext;
 use gpui::{Context, Entity, SemanticVersion, UpdateGlobal};
@@ -127,6 +131,24 @@ impl VimTestContext {
         self.cx.update_workspace(update)
     }
 
+    /// Returns the path and text of each local file open in the workspace's editors.
+    pub fn buffer_texts(&mut self) -> Vec<(Arc<Path>, String)> {
+        self.workspace(|workspace, _, cx| {
+            let mut texts = workspace
+                .items_of_type::<Editor>(cx)
+                .filter_map(|editor| {
+                    let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+                    let buffer = buffer.read(cx);
+                    let path = buffer.file()?.as_local()?.abs_path(cx);
+                    Some((path.into(), buffer.text()))
+                })
+                .collect::<Vec<_>>();
+            texts.sort();
+            texts.dedup();
+            texts
+        })
+    }
+
     pub fn enable_vim(&mut self) {
         self.cx.update(|_, cx| {
             SettingsStore::update_global(cx, |store, cx| {
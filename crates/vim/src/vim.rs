@@ -3,6 +3,7 @@
 #[cfg(test)]
 mod test;
 
+mod arglist;
 mod change_list;
 mod command;
 mod digraph;
@@ -13,13 +14,16 @@ mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod quickfix;
 mod replace;
 mod rewrap;
 mod state;
 mod surrounds;
+mod vimrc;
 mod visual;
 
 use anyhow::Result;
+pub use arglist::set_arg_list;
 use collections::HashMap;
 use editor::{
     Anchor, Bias, Editor, EditorEvent, EditorSettings, HideMouseCursorOrigin, SelectionEffects,
@@ -47,6 +51,7 @@ use theme::ThemeSettings;
 use ui::{IntoElement, SharedString, px};
 use vim_mode_setting::HelixModeSetting;
 use vim_mode_setting::VimModeSetting;
+pub use vimrc::{Vimrc, set_vimrc, vimrc_key_bindings, vimrc_path};
 use workspace::{self, Pane, Workspace};
 
 use crate::state::ReplayableAction;
@@ -148,6 +153,8 @@ actions!(
         SwitchToVisualBlockMode,
         /// Switches to Helix-style normal mode.
         SwitchToHelixNormalMode,
+        /// Switches to Helix-style select mode, where motions extend the selections.
+        SwitchToHelixSelectMode,
         /// Clears any pending operators.
         ClearOperators,
         /// Clears the exchange register.
@@ -237,7 +244,7 @@ actions!(
 /// Initializes the `vim` crate.
 pub fn init(cx: &mut App) {
     vim_mode_setting::init(cx);
-    VimSettings::register(cx);
+    init_settings(cx);
     VimGlobals::register(cx);
 
     cx.observe_new(Vim::register).detach();
@@ -344,6 +351,11 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+/// Registers the `vim` settings, for reading them before the crate is initialized.
+pub fn init_settings(cx: &mut App) {
+    VimSettings::register(cx);
+}
+
 #[derive(Clone)]
 pub(crate) struct VimAddon {
     pub(crate) entity: Entity<Vim>,
@@ -538,6 +550,14 @@ impl Vim {
                     vim.switch_mode(Mode::HelixNormal, false, window, cx)
                 },
             );
+
+            Vim::action(
+                editor,
+                cx,
+                |vim, _: &SwitchToHelixSelectMode, window, cx| {
+                    vim.switch_mode(Mode::HelixSelect, false, window, cx)
+                },
+            );
             Vim::action(editor, cx, |_, _: &PushForcedMotion, _, cx| {
                 Vim::globals(cx).forced_motion = true;
             });
@@ -775,9 +795,12 @@ impl Vim {
             object::register(editor, cx);
             visual::register(editor, cx);
             change_list::register(editor, cx);
+            quickfix::register(editor, cx);
+            arglist::register(editor, cx);
             digraph::register(editor, cx);
 
             cx.defer_in(window, |vim, window, cx| {
+                vim.apply_vimrc_options(cx);
                 vim.focused(false, window, cx);
             })
         })
@@ -935,6 +958,9 @@ impl Vim {
                 | Operator::ChangeSurrounds { .. }
                 | Operator::DeleteSurrounds
                 | Operator::Exchange
+                | Operator::HelixSurroundAdd
+                | Operator::HelixSurroundReplace { .. }
+                | Operator::HelixSurroundDelete
         ) {
             self.operator_stack.clear();
         };
@@ -1110,7 +1136,9 @@ impl Vim {
                     cursor_shape.normal.unwrap_or(CursorShape::Block)
                 }
             }
-            Mode::HelixNormal => cursor_shape.normal.unwrap_or(CursorShape::Block),
+            Mode::HelixNormal | Mode::HelixSelect => {
+                cursor_shape.normal.unwrap_or(CursorShape::Block)
+            }
             Mode::Replace => cursor_shape.replace.unwrap_or(CursorShape::Underline),
             Mode::Visual | Mode::VisualLine | Mode::VisualBlock => {
                 cursor_shape.visual.unwrap_or(CursorShape::Block)
@@ -1133,6 +1161,7 @@ impl Vim {
             }
             Mode::Normal
             | Mode::HelixNormal
+            | Mode::HelixSelect
             | Mode::Replace
             | Mode::Visual
             | Mode::VisualLine
@@ -1151,7 +1180,8 @@ impl Vim {
             | Mode::VisualLine
             | Mode::VisualBlock
             | Mode::Replace
-            | Mode::HelixNormal => false,
+            | Mode::HelixNormal
+            | Mode::HelixSelect => false,
             Mode::Normal => true,
         }
     }
@@ -1163,6 +1193,7 @@ impl Vim {
             Mode::Insert => "insert",
             Mode::Replace => "replace",
             Mode::HelixNormal => "helix_normal",
+            Mode::HelixSelect => "helix_select",
         }
         .to_string();
 
@@ -1188,7 +1219,12 @@ impl Vim {
             }
         }
 
-        if mode == "normal" || mode == "visual" || mode == "operator" || mode == "helix_normal" {
+        if mode == "normal"
+            || mode == "visual"
+            || mode == "operator"
+            || mode == "helix_normal"
+            || mode == "helix_select"
+        {
             context.add("VimControl");
         }
         context.set("vim_mode", mode);
@@ -1524,7 +1560,7 @@ impl Vim {
                     })
                 });
             }
-            Mode::Insert | Mode::Replace | Mode::HelixNormal => {}
+            Mode::Insert | Mode::Replace | Mode::HelixNormal | Mode::HelixSelect => {}
         }
     }
 
@@ -1634,7 +1670,7 @@ impl Vim {
                 Mode::Visual | Mode::VisualLine | Mode::VisualBlock => {
                     self.visual_replace(text, window, cx)
                 }
-                Mode::HelixNormal => self.helix_replace(&text, window, cx),
+                Mode::HelixNormal | Mode::HelixSelect => self.helix_replace(&text, window, cx),
                 _ => self.clear_operator(window, cx),
             },
             Some(Operator::Digraph { first_char }) => {
@@ -1680,6 +1716,28 @@ impl Vim {
                 }
                 _ => self.clear_operator(window, cx),
             },
+            Some(Operator::HelixSurroundAdd) => {
+                self.helix_surround_add(&text, window, cx);
+                self.clear_operator(window, cx);
+            }
+            Some(Operator::HelixSurroundReplace { replaced_char }) => {
+                if let Some(replaced_char) = replaced_char {
+                    self.helix_surround_replace(replaced_char, &text, window, cx);
+                    self.clear_operator(window, cx);
+                } else {
+                    let replaced_char = text.chars().next();
+                    self.pop_operator(window, cx);
+                    self.push_operator(
+                        Operator::HelixSurroundReplace { replaced_char },
+                        window,
+                        cx,
+                    );
+                }
+            }
+            Some(Operator::HelixSurroundDelete) => {
+                self.helix_surround_delete(&text, window, cx);
+                self.clear_operator(window, cx);
+            }
             Some(Operator::Mark) => self.create_mark(text, window, cx),
             Some(Operator::RecordRegister) => {
                 self.record_register(text.chars().next().unwrap(), window, cx)
@@ -1788,6 +1846,7 @@ struct VimSettings {
     pub custom_digraphs: HashMap<String, Arc<str>>,
     pub highlight_on_yank_duration: u64,
     pub cursor_shape: CursorShapeSettings,
+    pub vimrc_path: Option<String>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -1799,6 +1858,12 @@ struct VimSettingsContent {
     pub custom_digraphs: Option<HashMap<String, Arc<str>>>,
     pub highlight_on_yank_duration: Option<u64>,
     pub cursor_shape: Option<CursorShapeSettings>,
+    /// The vimrc to load instead of `vimrc` in Zed's config directory.
+    /// `~/` expands to the home directory, and relative paths are resolved
+    /// against the config directory.
+    ///
+    /// Default: null
+    pub vimrc_path: Option<String>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -1855,6 +1920,7 @@ impl Settings for VimSettings {
                 .highlight_on_yank_duration
                 .ok_or_else(Self::missing_default)?,
             cursor_shape: settings.cursor_shape.ok_or_else(Self::missing_default)?,
+            vimrc_path: settings.vimrc_path,
         })
     }
 
@@ -0,0 +1,1115 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Result, anyhow};
+use editor::Editor;
+use futures::{FutureExt as _, future::LocalBoxFuture};
+use gpui::{
+    Action, App, AppContext as _, Context, DismissEvent, Entity, EventEmitter, FocusHandle,
+    Focusable, Global, IntoElement, KeyBinding, KeyContext, Keymap, Keystroke, Render,
+    SharedString, Task, WeakEntity, Window, actions, is_no_action,
+};
+use language::Point;
+use picker::{Picker, PickerDelegate};
+use project::Fs;
+use serde_json::{Value, json};
+use settings::{
+    DEFAULT_KEYMAP_PATH, KeybindSource, KeymapFile, KeymapFileLoadResult, Settings as _,
+    VIM_KEYMAP_PATH,
+};
+use ui::{
+    ActiveTheme, Div, FluentBuilder, Icon, IconName, ParentElement, Styled, StyledTypography, div,
+    h_flex, v_flex,
+};
+use util::paths::home_dir;
+use workspace::{
+    OpenOptions, OpenVisible, Workspace,
+    item::Item,
+    notifications::{
+        NotificationId, NotifyTaskExt, dismiss_app_notification, show_app_notification,
+        simple_message_notification::MessageNotification,
+    },
+};
+
+use crate::{Vim, VimSettings, command::VimOption};
+
+const MAX_SOURCE_DEPTH: usize = 16;
+
+actions!(
+    vim,
+    [
+        /// Shows the vimrc lines that could not be applied.
+        OpenVimrcReport
+    ]
+);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MapMode {
+    Normal,
+    Visual,
+    OperatorPending,
+    Insert,
+}
+
+impl MapMode {
+    fn context(self) -> &'static str {
+        match self {
+            MapMode::Normal => "vim_mode == normal && !menu",
+            MapMode::Visual => "vim_mode == visual && !menu",
+            MapMode::OperatorPending => "vim_mode == operator && !menu",
+            MapMode::Insert => "vim_mode == insert && !menu",
+        }
+    }
+
+    /// The key contexts of an editor in this mode, used to look up Zed's own bindings.
+    fn key_context_stack(self) -> Vec<KeyContext> {
+        let (vim_mode, vim_control) = match self {
+            MapMode::Normal => ("normal", true),
+            MapMode::Visual => ("visual", true),
+            MapMode::OperatorPending => ("operator", true),
+            MapMode::Insert => ("insert", false),
+        };
+        let mut editor = KeyContext::default();
+        editor.add("Editor");
+        editor.set("mode", "full");
+        editor.set("vim_mode", vim_mode);
+        editor.set("vim_operator", "none");
+        if vim_control {
+            editor.add("VimControl");
+        }
+        let mut workspace = KeyContext::default();
+        workspace.add("Workspace");
+        let mut pane = KeyContext::default();
+        pane.add("Pane");
+        vec![workspace, pane, editor]
+    }
+}
+
+const NVO: &[MapMode] = &[MapMode::Normal, MapMode::Visual, MapMode::OperatorPending];
+
+/// The map commands, the modes they map in, and whether the mapping is recursive.
+const MAP_COMMANDS: &[((&str, &str), &[MapMode], bool)] = &[
+    (("map", ""), NVO, true),
+    (("no", "remap"), NVO, false),
+    (("nm", "ap"), &[MapMode::Normal], true),
+    (("nn", "oremap"), &[MapMode::Normal], false),
+    (("vm", "ap"), &[MapMode::Visual], true),
+    (("vn", "oremap"), &[MapMode::Visual], false),
+    (("xm", "ap"), &[MapMode::Visual], true),
+    (("xn", "oremap"), &[MapMode::Visual], false),
+    (("om", "ap"), &[MapMode::OperatorPending], true),
+    (("ono", "remap"), &[MapMode::OperatorPending], false),
+    (("im", "ap"), &[MapMode::Insert], true),
+    (("ino", "remap"), &[MapMode::Insert], false),
+];
+
+fn matches_command(word: &str, (prefix, suffix): (&str, &str)) -> bool {
+    word.strip_prefix(prefix)
+        .is_some_and(|rest| suffix.starts_with(rest))
+}
+
+/// A vimrc line that could not be applied.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VimrcIssue {
+    pub path: PathBuf,
+    pub row: u32,
+    pub line: String,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Mapping {
+    modes: &'static [MapMode],
+    lhs: String,
+    /// `None` for `<Nop>`, which unbinds the keys.
+    rhs: Option<String>,
+    /// Whether the keys of `rhs` are themselves subject to mappings.
+    recursive: bool,
+    path: PathBuf,
+    row: u32,
+}
+
+/// The supported subset of a vimrc: key mappings, `set` options and the map leaders.
+#[derive(Debug)]
+pub struct Vimrc {
+    path: PathBuf,
+    mappings: Vec<Mapping>,
+    options: Vec<VimOption>,
+    issues: Vec<VimrcIssue>,
+    /// The files read with `source`, including the ones that couldn't be opened.
+    sourced_paths: Vec<PathBuf>,
+    leader: String,
+    local_leader: String,
+}
+
+impl Vimrc {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            mappings: Vec::new(),
+            options: Vec::new(),
+            issues: Vec::new(),
+            sourced_paths: Vec::new(),
+            leader: "\\".into(),
+            local_leader: "\\".into(),
+        }
+    }
+
+    /// Reads the vimrc at `path`, following `source` commands.
+    pub async fn load(fs: Arc<dyn Fs>, path: PathBuf) -> Self {
+        let content = fs.load(&path).await.unwrap_or_default();
+        Self::parse(fs, path, &content).await
+    }
+
+    /// The files pulled in with `source`, which should be watched along with the vimrc.
+    pub fn sourced_paths(&self) -> &[PathBuf] {
+        &self.sourced_paths
+    }
+
+    /// Parses the contents of the vimrc at `path`, following `source` commands.
+    pub async fn parse(fs: Arc<dyn Fs>, path: PathBuf, content: &str) -> Self {
+        let mut vimrc = Self::new(path.clone());
+        vimrc.parse_file(fs.as_ref(), &path, content, 0).await;
+        vimrc
+    }
+
+    fn parse_file<'a>(
+        &'a mut self,
+        fs: &'a dyn Fs,
+        path: &'a Path,
+        content: &'a str,
+        depth: usize,
+    ) -> LocalBoxFuture<'a, ()> {
+        async move {
+            for (row, line) in logical_lines(content) {
+                let source = match self.parse_line(path, row, &line) {
+                    Ok(Some(source)) => source,
+                    Ok(None) => continue,
+                    Err(error) => {
+                        self.push_issue(path, row, &line, error);
+                        continue;
+                    }
+                };
+                if depth >= MAX_SOURCE_DEPTH {
+                    self.push_issue(path, row, &line, anyhow!("E169: Command too recursive"));
+                    continue;
+                }
+                if !self.sourced_paths.contains(&source) {
+                    self.sourced_paths.push(source.clone());
+                }
+                match fs.load(&source).await {
+                    Ok(content) => self.parse_file(fs, &source, &content, depth + 1).await,
+                    Err(_) => self.push_issue(
+                        path,
+                        row,
+                        &line,
+                        anyhow!("E484: Can't open file {}", source.display()),
+                    ),
+                }
+            }
+        }
+        .boxed_local()
+    }
+
+    fn push_issue(&mut self, path: &Path, row: u32, line: &str, error: anyhow::Error) {
+        self.issues.push(VimrcIssue {
+            path: path.to_path_buf(),
+            row,
+            line: line.to_string(),
+            message: error.to_string(),
+        });
+    }
+
+    /// Applies a single line, returning the path of a file to `source`, if any.
+    fn parse_line(&mut self, path: &Path, row: u32, line: &str) -> Result<Option<PathBuf>> {
+        let line = line.trim_start_matches([' ', '\t', ':']).trim_end();
+        if line.is_empty() || line.starts_with('"') {
+            return Ok(None);
+        }
+        let (command, args) = line
+            .split_once(char::is_whitespace)
+            .map(|(command, args)| (command, args.trim_start()))
+            .unwrap_or((line, ""));
+        let (command, bang) = match command.strip_suffix('!') {
+            Some(command) => (command, true),
+            None => (command, false),
+        };
+
+        if let Some((_, modes, recursive)) = MAP_COMMANDS
+            .iter()
+            .find(|(pattern, _, _)| matches_command(command, *pattern))
+        {
+            let modes: &'static [MapMode] = if bang {
+                if !matches_command(command, ("map", ""))
+                    && !matches_command(command, ("no", "remap"))
+                {
+                    return Err(anyhow!("E477: No ! allowed"));
+                }
+                &[MapMode::Insert]
+            } else {
+                *modes
+            };
+            self.parse_map(modes, *recursive, args, path, row)?;
+            return Ok(None);
+        }
+        if matches_command(command, ("se", "t")) {
+            return self.parse_set(args).map(|_| None);
+        }
+        if command == "let" {
+            return self.parse_let(args).map(|_| None);
+        }
+        if matches_command(command, ("so", "urce")) {
+            return Ok(Some(resolve_source(path, args)?));
+        }
+        // Zed always behaves as if these were on, so there is nothing to apply.
+        if matches_command(command, ("sy", "ntax")) || matches_command(command, ("filet", "ype")) {
+            return Ok(None);
+        }
+        Err(anyhow!("Unsupported command: {command}"))
+    }
+
+    fn parse_map(
+        &mut self,
+        modes: &'static [MapMode],
+        recursive: bool,
+        args: &str,
+        path: &Path,
+        row: u32,
+    ) -> Result<()> {
+        let mut args = args;
+        while args.starts_with('<') {
+            let Some(end) = args.find('>') else {
+                break;
+            };
+            match args[1..end].to_ascii_lowercase().as_str() {
+                "silent" | "nowait" | "unique" | "special" | "script" => {}
+                "buffer" | "expr" => {
+                    return Err(anyhow!("Unsupported map argument: {}", &args[..=end]));
+                }
+                _ => break,
+            }
+            args = args[end + 1..].trim_start();
+        }
+        let Some((lhs, rhs)) = args.split_once(char::is_whitespace) else {
+            return Err(anyhow!("Listing mappings is not supported"));
+        };
+        let rhs = rhs.trim();
+        let lhs = self.parse_keys(lhs)?.join(" ");
+        let rhs = if rhs.eq_ignore_ascii_case("<nop>") {
+            None
+        } else {
+            Some(self.parse_keys(rhs)?.join(" "))
+        };
+        self.mappings.push(Mapping {
+            modes,
+            lhs,
+            rhs,
+            recursive,
+            path: path.to_path_buf(),
+            row,
+        });
+        Ok(())
+    }
+
+    fn parse_set(&mut self, args: &str) -> Result<()> {
+        let mut unsupported = Vec::new();
+        for option in args.split_whitespace() {
+            if matches!(option, "nocompatible" | "nocp") {
+                continue;
+            }
+            match VimOption::from(option) {
+                Some(option) => self.options.push(option),
+                None => unsupported.push(option),
+            }
+        }
+        if unsupported.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("Unsupported option: {}", unsupported.join(" ")))
+        }
+    }
+
+    fn parse_let(&mut self, args: &str) -> Result<()> {
+        let (name, value) = args
+            .split_once('=')
+            .ok_or_else(|| anyhow!("E15: Invalid expression: {args}"))?;
+        let name = name.trim();
+        let value = parse_string(value.trim())?;
+        match name.strip_prefix("g:").unwrap_or(name) {
+            "mapleader" => self.leader = value,
+            "maplocalleader" => self.local_leader = value,
+            _ => return Err(anyhow!("Unsupported variable: {name}")),
+        }
+        Ok(())
+    }
+
+    /// Converts Vim key notation into Zed keystrokes.
+    fn parse_keys(&self, keys: &str) -> Result<Vec<String>> {
+        let mut keystrokes = Vec::new();
+        let mut rest = keys;
+        while let Some(char) = rest.chars().next() {
+            if char == '<' {
+                if let Some(end) = rest.find('>') {
+                    let name = rest[1..end].to_ascii_lowercase();
+                    let expanded = match name.as_str() {
+                        "leader" => Some(self.leader_keys(&self.leader)?),
+                        "localleader" => Some(self.leader_keys(&self.local_leader)?),
+                        "nop" => return Err(anyhow!("<Nop> must be the whole right-hand side")),
+                        _ => special_key(&name).map(|key| vec![key]),
+                    };
+                    if let Some(expanded) = expanded {
+                        keystrokes.extend(expanded);
+                        rest = &rest[end + 1..];
+                        continue;
+                    }
+                }
+            }
+            keystrokes.push(char_key(char));
+            rest = &rest[char.len_utf8()..];
+        }
+        if keystrokes.is_empty() {
+            return Err(anyhow!("E474: Invalid argument"));
+        }
+        Ok(keystrokes)
+    }
+
+    fn leader_keys(&self, leader: &str) -> Result<Vec<String>> {
+        let mut keystrokes = Vec::new();
+        let mut rest = leader;
+        while let Some(char) = rest.chars().next() {
+            match rest.find('>').filter(|_| char == '<') {
+                Some(end) => {
+                    let key = special_key(&rest[1..end].to_ascii_lowercase())
+                        .ok_or_else(|| anyhow!("Invalid leader: {leader}"))?;
+                    keystrokes.push(key);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    keystrokes.push(char_key(char));
+                    rest = &rest[char.len_utf8()..];
+                }
+            }
+        }
+        Ok(keystrokes)
+    }
+
+    /// Builds a keymap from the mappings, recording the ones that fail to load as issues.
+    fn keymap(&mut self, cx: &App) -> String {
+        let default_keymap = default_keymap(cx);
+        let mut keymap = Vec::new();
+        let mut issues = Vec::new();
+        for mapping in &self.mappings {
+            let sections = mapping
+                .modes
+                .iter()
+                .map(|mode| {
+                    let action = match &mapping.rhs {
+                        Some(keys) if !mapping.recursive => {
+                            default_action(keys, *mode, &default_keymap)
+                                .unwrap_or_else(|| json!(["workspace::SendKeystrokes", keys]))
+                        }
+                        Some(keys) => json!(["workspace::SendKeystrokes", keys]),
+                        None => Value::Null,
+                    };
+                    json!({
+                        "context": mode.context(),
+                        "bindings": { mapping.lhs.clone(): action },
+                    })
+                })
+                .collect::<Vec<_>>();
+            if let Some(keys) = mapping.rhs.as_ref().filter(|_| !mapping.recursive) {
+                let remapped = self.mappings.iter().any(|other| {
+                    other.modes.iter().any(|mode| mapping.modes.contains(mode))
+                        && keys.split(' ').next() == other.lhs.split(' ').next()
+                });
+                let resolved = mapping
+                    .modes
+                    .iter()
+                    .all(|mode| default_action(keys, *mode, &default_keymap).is_some());
+                if remapped && !resolved {
+                    issues.push(VimrcIssue {
+                        path: mapping.path.clone(),
+                        row: mapping.row,
+                        line: mapping.lhs.clone(),
+                        message: "Only single-command right-hand sides can be non-recursive; \
+                            this mapping behaves like map"
+                            .into(),
+                    });
+                }
+            }
+            let error = match KeymapFile::load(&Value::Array(sections.clone()).to_string(), cx) {
+                KeymapFileLoadResult::Success { .. } => {
+                    keymap.extend(sections);
+                    continue;
+                }
+                KeymapFileLoadResult::SomeFailedToLoad { error_message, .. } => {
+                    error_message.to_string()
+                }
+                KeymapFileLoadResult::JsonParseFailure { error } => error.to_string(),
+            };
+            issues.push(VimrcIssue {
+                path: mapping.path.clone(),
+                row: mapping.row,
+                line: mapping.lhs.clone(),
+                message: error,
+            });
+        }
+        self.issues.extend(issues);
+        Value::Array(keymap).to_string()
+    }
+}
+
+/// Zed's built-in bindings, without the user's keymap or vimrc.
+fn default_keymap(cx: &App) -> Keymap {
+    let mut bindings =
+        KeymapFile::load_asset_allow_partial_failure(DEFAULT_KEYMAP_PATH, cx).unwrap_or_default();
+    bindings.extend(
+        KeymapFile::load_asset_allow_partial_failure(VIM_KEYMAP_PATH, cx).unwrap_or_default(),
+    );
+    Keymap::new(bindings)
+}
+
+/// Resolves the right-hand side of a non-recursive mapping to the action Zed binds those keys
+/// to by default, so that mapping the keys elsewhere in the vimrc doesn't affect it.
+fn default_action(keys: &str, mode: MapMode, default_keymap: &Keymap) -> Option<Value> {
+    let keystrokes = keys
+        .split(' ')
+        .map(Keystroke::parse)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    let (bindings, _) = default_keymap.bindings_for_input(&keystrokes, &mode.key_context_stack());
+    let binding = bindings.first()?;
+    if is_no_action(binding.action()) {
+        return None;
+    }
+    Some(match binding.action_input() {
+        Some(input) => json!([
+            binding.action().name(),
+            serde_json::from_str::<Value>(&input).ok()?
+        ]),
+        None => json!(binding.action().name()),
+    })
+}
+
+/// Joins `\` continuation lines, keeping the row of the first one.
+fn logical_lines(content: &str) -> Vec<(u32, String)> {
+    let mut lines: Vec<(u32, String)> = Vec::new();
+    for (row, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        match (trimmed.strip_prefix('\\'), lines.last_mut()) {
+            (Some(continuation), Some((_, last))) => last.push_str(continuation),
+            _ => lines.push((row as u32, line.to_string())),
+        }
+    }
+    lines
+}
+
+/// The vimrc to load: `vim.vimrc_path` if it's set, otherwise `vimrc` in the
+/// config directory.
+pub fn vimrc_path(cx: &App) -> PathBuf {
+    let Some(path) = VimSettings::get_global(cx).vimrc_path.as_deref() else {
+        return paths::vimrc_file().clone();
+    };
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => home_dir().join(rest),
+        None => PathBuf::from(path),
+    };
+    if path.is_relative() {
+        paths::config_dir().join(path)
+    } else {
+        path
+    }
+}
+
+fn resolve_source(vimrc_path: &Path, args: &str) -> Result<PathBuf> {
+    if args.is_empty() {
+        return Err(anyhow!("E471: Argument required"));
+    }
+    let path = if let Some(rest) = args
+        .strip_prefix("~/")
+        .or_else(|| args.strip_prefix("$HOME/"))
+    {
+        home_dir().join(rest)
+    } else {
+        PathBuf::from(args)
+    };
+    Ok(match vimrc_path.parent() {
+        Some(dir) if path.is_relative() => dir.join(path),
+        _ => path,
+    })
+}
+
+fn parse_string(value: &str) -> Result<String> {
+    if let Some(literal) = value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+    {
+        return Ok(literal.replace("''", "'"));
+    }
+    let Some(quoted) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    else {
+        return Err(anyhow!("E15: Invalid expression: {value}"));
+    };
+    let mut string = String::new();
+    let mut chars = quoted.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            string.push(char);
+            continue;
+        }
+        // `"\<Space>"` keeps the key notation, which `parse_keys` understands.
+        match chars.next() {
+            Some('<') => string.push('<'),
+            Some('t') => string.push('\t'),
+            Some(escaped) => string.push(escaped),
+            None => return Err(anyhow!("E114: Missing quote: {value}")),
+        }
+    }
+    Ok(string)
+}
+
+fn special_key(name: &str) -> Option<String> {
+    let mut modifiers = String::new();
+    let mut key = name;
+    while key.len() > 2 && key.as_bytes()[1] == b'-' {
+        modifiers.push_str(match key.as_bytes()[0] {
+            b'c' => "ctrl-",
+            b's' => "shift-",
+            b'm' | b'a' => "alt-",
+            b'd' => "cmd-",
+            _ => return None,
+        });
+        key = &key[2..];
+    }
+    let key = match key {
+        "cr" | "return" | "enter" | "nl" => "enter",
+        "esc" => "escape",
+        "space" => "space",
+        "tab" => "tab",
+        "bs" => "backspace",
+        "del" => "delete",
+        "up" | "down" | "left" | "right" | "home" | "end" | "pageup" | "pagedown" | "insert" => key,
+        "lt" => "<",
+        "bar" => "|",
+        "bslash" => "\\",
+        _ if key.len() > 1
+            && key.starts_with('f')
+            && key[1..].parse::<u8>().is_ok_and(|n| (1..=35).contains(&n)) =>
+        {
+            key
+        }
+        // A single character is only key notation together with a modifier, like `<C-a>`.
+        _ if key.chars().count() == 1 && !modifiers.is_empty() => key,
+        _ => return None,
+    };
+    Some(format!("{modifiers}{key}"))
+}
+
+fn char_key(char: char) -> String {
+    if char == ' ' {
+        "space".to_string()
+    } else if char.is_ascii_uppercase() {
+        format!("shift-{}", char.to_ascii_lowercase())
+    } else {
+        char.to_string()
+    }
+}
+
+struct ActiveVimrc {
+    keymap: String,
+    options: Vec<VimOption>,
+    issues: Vec<VimrcIssue>,
+}
+
+impl Global for ActiveVimrc {}
+
+/// Makes `vimrc` the active vimrc, reporting any lines that could not be applied.
+///
+/// The key bindings take effect the next time the keymap is reloaded.
+pub fn set_vimrc(mut vimrc: Vimrc, cx: &mut App) {
+    let keymap = vimrc.keymap(cx);
+    let notification_id = NotificationId::unique::<ActiveVimrc>();
+    if vimrc.issues.is_empty() {
+        dismiss_app_notification(&notification_id, cx);
+    } else {
+        let message: SharedString = format!(
+            "{} line(s) in {} could not be applied.",
+            vimrc.issues.len(),
+            vimrc.path.display()
+        )
+        .into();
+        show_app_notification(notification_id, cx, move |cx| {
+            cx.new(|cx| {
+                MessageNotification::new(message.clone(), cx)
+                    .primary_message("Show Details")
+                    .primary_on_click(|window, cx| {
+                        window.dispatch_action(OpenVimrcReport.boxed_clone(), cx);
+                        cx.emit(DismissEvent);
+                    })
+            })
+        });
+    }
+    if cx.has_global::<crate::state::VimGlobals>() {
+        for option in &vimrc.options {
+            match option {
+                VimOption::Hidden(enabled) => Vim::globals(cx).hidden = *enabled,
+                VimOption::AutoWrite(enabled) => Vim::globals(cx).autowrite = *enabled,
+                _ => {}
+            }
+        }
+    }
+    cx.set_global(ActiveVimrc {
+        keymap,
+        options: vimrc.options,
+        issues: vimrc.issues,
+    });
+}
+
+/// The key bindings of the active vimrc, if vim mode is enabled.
+pub fn vimrc_key_bindings(cx: &mut App) -> Vec<KeyBinding> {
+    if !Vim::enabled(cx) {
+        return Vec::new();
+    }
+    let Some(keymap) = cx
+        .try_global::<ActiveVimrc>()
+        .map(|vimrc| vimrc.keymap.clone())
+    else {
+        return Vec::new();
+    };
+    let mut key_bindings = match KeymapFile::load(&keymap, cx) {
+        KeymapFileLoadResult::Success { key_bindings }
+        | KeymapFileLoadResult::SomeFailedToLoad { key_bindings, .. } => key_bindings,
+        KeymapFileLoadResult::JsonParseFailure { .. } => Vec::new(),
+    };
+    for key_binding in &mut key_bindings {
+        key_binding.set_meta(KeybindSource::User.meta());
+    }
+    key_bindings
+}
+
+impl Vim {
+    pub(crate) fn apply_vimrc_options(&mut self, cx: &mut Context<Self>) {
+        let Some(vimrc) = cx.try_global::<ActiveVimrc>() else {
+            return;
+        };
+        let options = vimrc
+            .options
+            .iter()
+            .filter(|option| !matches!(option, VimOption::Hidden(_) | VimOption::AutoWrite(_)))
+            .cloned()
+            .collect::<Vec<_>>();
+        for option in &options {
+            self.set_option(option, cx);
+        }
+    }
+}
+
+pub struct VimrcReportDelegate {
+    workspace: WeakEntity<Workspace>,
+    issues: Vec<VimrcIssue>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for VimrcReportDelegate {
+    type ListItem = Div;
+
+    fn match_count(&self) -> usize {
+        self.issues.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.selected_index = ix;
+        cx.notify();
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        Arc::default()
+    }
+
+    fn update_matches(
+        &mut self,
+        _: String,
+        _: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        self.issues = cx
+            .try_global::<ActiveVimrc>()
+            .map(|vimrc| vimrc.issues.clone())
+            .unwrap_or_default();
+        self.selected_index = self.selected_index.min(self.issues.len().saturating_sub(1));
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let Some(issue) = self.issues.get(self.selected_index).cloned() else {
+            return;
+        };
+        let open = workspace.update(cx, |workspace, cx| {
+            workspace.open_abs_path(
+                issue.path.clone(),
+                OpenOptions {
+                    visible: Some(OpenVisible::None),
+                    ..Default::default()
+                },
+                window,
+                cx,
+            )
+        });
+        cx.spawn_in(window, async move |_, cx| {
+            let item = open.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                cx.update(|window, cx| {
+                    editor.update(cx, |editor, cx| {
+                        editor.go_to_singleton_buffer_point(Point::new(issue.row, 0), window, cx);
+                    })
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, _: &mut Context<Picker<Self>>) {}
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let issue = self.issues.get(ix)?;
+        let file_name = issue
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Some(
+            h_flex()
+                .when(selected, |el| el.bg(cx.theme().colors().element_selected))
+                .px_2()
+                .gap_2()
+                .child(
+                    div()
+                        .text_color(cx.theme().colors().text_accent)
+                        .child(format!("{}:{}", file_name, issue.row + 1)),
+                )
+                .child(
+                    div()
+                        .font_buffer(cx)
+                        .text_buffer(cx)
+                        .child(issue.line.trim().to_string()),
+                )
+                .child(
+                    div()
+                        .text_color(cx.theme().colors().text_muted)
+                        .child(issue.message.clone()),
+                ),
+        )
+    }
+}
+
+pub struct VimrcReport {
+    picker: Entity<Picker<VimrcReportDelegate>>,
+}
+
+impl VimrcReport {
+    pub(crate) fn register(workspace: &mut Workspace, _window: Option<&mut Window>) {
+        workspace.register_action(|workspace, _: &OpenVimrcReport, window, cx| {
+            if let Some(report) = workspace.items_of_type::<VimrcReport>(cx).next() {
+                report.update(cx, |report, cx| {
+                    report
+                        .picker
+                        .update(cx, |picker, cx| picker.refresh(window, cx))
+                });
+                workspace.activate_item(&report, true, true, window, cx);
+                return;
+            }
+            let delegate = VimrcReportDelegate {
+                workspace: cx.entity().downgrade(),
+                issues: Vec::new(),
+                selected_index: 0,
+            };
+            let report = cx.new(|cx| VimrcReport {
+                picker: cx.new(|cx| {
+                    Picker::nonsearchable_uniform_list(delegate, window, cx)
+                        .max_height(None)
+                        .modal(false)
+                }),
+            });
+            workspace.add_item_to_active_pane(Box::new(report), None, true, window, cx);
+        });
+    }
+}
+
+impl EventEmitter<()> for VimrcReport {}
+
+impl Focusable for VimrcReport {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for VimrcReport {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex().size_full().child(self.picker.clone())
+    }
+}
+
+impl Item for VimrcReport {
+    type Event = ();
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Vimrc Report".into()
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Warning))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use gpui::TestAppContext;
+    use indoc::indoc;
+    use project::{FakeFs, Fs};
+    use settings::SettingsStore;
+    use util::{path, paths::home_dir};
+
+    use super::{MapMode, Mapping, NVO, Vimrc, set_vimrc, vimrc_path};
+    use crate::{VimSettings, command::VimOption, state::Mode, test::VimTestContext};
+
+    fn parse(content: &str) -> Vimrc {
+        let path = PathBuf::from(path!("/home/.config/zed/vimrc"));
+        let mut vimrc = Vimrc::new(path.clone());
+        for (row, line) in super::logical_lines(content) {
+            if let Err(error) = vimrc.parse_line(&path, row, &line) {
+                vimrc.push_issue(&path, row, &line, error);
+            }
+        }
+        vimrc
+    }
+
+    #[test]
+    fn test_parse_vimrc() {
+        let vimrc = parse(indoc! {r#"
+            " comment
+            set nocompatible number nowrap
+            let mapleader = "\<Space>"
+            nnoremap <silent> <leader>w :w<CR>
+            inoremap jk <Esc>
+            map Y y$
+            vnoremap <C-j> :m '>+1<CR>gv
+            nmap Q <Nop>
+            nnoremap <buffer> x y
+            set clipboard=unnamedplus
+            autocmd BufWritePre * :%s/\s\+$//e
+        "#});
+
+        let mapping = |modes, lhs: &str, rhs: Option<&str>, recursive, row| Mapping {
+            modes,
+            lhs: lhs.into(),
+            rhs: rhs.map(Into::into),
+            recursive,
+            path: PathBuf::from(path!("/home/.config/zed/vimrc")),
+            row,
+        };
+        assert_eq!(
+            vimrc.mappings,
+            vec![
+                mapping(&[MapMode::Normal], "space w", Some(": w enter"), false, 3),
+                mapping(&[MapMode::Insert], "j k", Some("escape"), false, 4),
+                mapping(NVO, "shift-y", Some("y $"), true, 5),
+                mapping(
+                    &[MapMode::Visual],
+                    "ctrl-j",
+                    Some(": m space ' > + 1 enter g v"),
+                    false,
+                    6
+                ),
+                mapping(&[MapMode::Normal], "shift-q", None, true, 7),
+            ]
+        );
+        assert_eq!(
+            vimrc.options,
+            vec![VimOption::Number(true), VimOption::Wrap(false)]
+        );
+        assert_eq!(
+            vimrc
+                .issues
+                .iter()
+                .map(|issue| (issue.row, issue.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (8, "Unsupported map argument: <buffer>"),
+                (9, "Unsupported option: clipboard=unnamedplus"),
+                (10, "Unsupported command: autocmd"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_continuation_lines() {
+        let vimrc = parse(indoc! {r#"
+            let mapleader = ','
+            nnoremap <leader>f
+              \ :Files<CR>
+        "#});
+        assert_eq!(vimrc.mappings.len(), 1);
+        assert_eq!(vimrc.mappings[0].lhs, ", f");
+        assert_eq!(
+            vimrc.mappings[0].rhs.as_deref(),
+            Some(": shift-f i l e s enter")
+        );
+        assert!(vimrc.issues.is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_vimrc_mappings(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(
+            path!("/home/.config/zed/vimrc"),
+            b"source keys.vim\nsource loop.vim\nset relativenumber\n".to_vec(),
+        )
+        .await;
+        fs.insert_file(
+            path!("/home/.config/zed/keys.vim"),
+            b"inoremap jk <Esc>\nnnoremap L $\n".to_vec(),
+        )
+        .await;
+        fs.insert_file(
+            path!("/home/.config/zed/loop.vim"),
+            b"source loop.vim\n".to_vec(),
+        )
+        .await;
+
+        let vimrc = Vimrc::load(
+            fs.clone() as std::sync::Arc<dyn Fs>,
+            Path::new(path!("/home/.config/zed/vimrc")).into(),
+        )
+        .await;
+        assert_eq!(vimrc.mappings.len(), 2);
+        // Sourcing recursively stops at the depth limit.
+        assert!(
+            vimrc
+                .issues
+                .iter()
+                .any(|issue| issue.message == "E169: Command too recursive")
+        );
+
+        cx.update(|_, cx| {
+            set_vimrc(vimrc, cx);
+            let key_bindings = super::vimrc_key_bindings(cx);
+            cx.bind_keys(key_bindings);
+        });
+
+        cx.set_state("ˇhello world", Mode::Normal);
+        cx.simulate_keystrokes("shift-l");
+        cx.assert_state("hello worlˇd", Mode::Normal);
+        cx.simulate_keystrokes("i j k");
+        cx.assert_state("hello worˇld", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_vimrc_noremap_swap(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(
+            path!("/home/.config/zed/vimrc"),
+            b"nnoremap h l\nnnoremap l h\nnmap L l\n".to_vec(),
+        )
+        .await;
+
+        let vimrc = Vimrc::load(
+            fs.clone() as std::sync::Arc<dyn Fs>,
+            Path::new(path!("/home/.config/zed/vimrc")).into(),
+        )
+        .await;
+        cx.update(|_, cx| {
+            set_vimrc(vimrc, cx);
+            let key_bindings = super::vimrc_key_bindings(cx);
+            cx.bind_keys(key_bindings);
+        });
+
+        // Each side of the swap runs the default binding, rather than the other mapping.
+        cx.set_state("heˇllo", Mode::Normal);
+        cx.simulate_keystrokes("h");
+        cx.assert_state("helˇlo", Mode::Normal);
+        cx.simulate_keystrokes("l l");
+        cx.assert_state("hˇello", Mode::Normal);
+
+        // Recursive mappings still go through the vimrc's mappings.
+        cx.simulate_keystrokes("shift-l");
+        cx.assert_state("ˇhello", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_vimrc_sourced_paths(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(
+            path!("/home/.config/zed/vimrc"),
+            b"source keys.vim\nsource missing.vim\n".to_vec(),
+        )
+        .await;
+        fs.insert_file(
+            path!("/home/.config/zed/keys.vim"),
+            b"source keys.vim\n".to_vec(),
+        )
+        .await;
+
+        let vimrc = Vimrc::load(
+            fs.clone() as std::sync::Arc<dyn Fs>,
+            Path::new(path!("/home/.config/zed/vimrc")).into(),
+        )
+        .await;
+        assert_eq!(
+            vimrc.sourced_paths(),
+            &[
+                PathBuf::from(path!("/home/.config/zed/keys.vim")),
+                PathBuf::from(path!("/home/.config/zed/missing.vim")),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_vimrc_path_setting(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let mut set_vimrc_path = |path: Option<&str>| {
+            cx.update_global(|store: &mut SettingsStore, cx| {
+                store.update_user_settings::<VimSettings>(cx, |s| {
+                    s.vimrc_path = path.map(ToString::to_string);
+                });
+            });
+            cx.update(|_, cx| vimrc_path(cx))
+        };
+
+        assert_eq!(set_vimrc_path(None), *paths::vimrc_file());
+        assert_eq!(set_vimrc_path(Some("~/.vimrc")), home_dir().join(".vimrc"));
+        assert_eq!(
+            set_vimrc_path(Some("vim/init.vim")),
+            paths::config_dir().join("vim/init.vim")
+        );
+        assert_eq!(
+            set_vimrc_path(Some(path!("/etc/vimrc"))),
+            PathBuf::from(path!("/etc/vimrc"))
+        );
+    }
+}
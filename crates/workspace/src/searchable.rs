@@ -39,6 +39,9 @@ pub struct SearchOptions {
     pub case: bool,
     pub word: bool,
     pub regex: bool,
+    /// Specifies whether the item has a syntax tree, so that it can be searched
+    /// with structural patterns and within code, comments or strings only.
+    pub syntax: bool,
     /// Specifies whether the  supports search & replace.
     pub replacement: bool,
     pub selection: bool,
@@ -53,6 +56,7 @@ pub trait SearchableItem: Item + EventEmitter<SearchEvent> {
             case: true,
             word: true,
             regex: true,
+            syntax: false,
             replacement: true,
             selection: true,
             find_in_results: false,
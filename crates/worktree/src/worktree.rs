@@ -26,7 +26,7 @@ use gpui::{
     App, AppContext as _, AsyncApp, BackgroundExecutor, Context, Entity, EventEmitter, Task,
 };
 use ignore::IgnoreStack;
-use language::DiskState;
+use language::{DiskState, language_settings::editorconfig_encoding};
 
 use parking_lot::Mutex;
 use paths::{local_settings_folder_relative_path, local_vscode_folder_relative_path};
@@ -63,7 +63,7 @@ use std::{
     time::{Duration, Instant},
 };
 use sum_tree::{Bias, Dimensions, Edit, KeyedItem, SeekTarget, SumTree, Summary, TreeMap, TreeSet};
-use text::{LineEnding, Rope};
+use text::{Encoding, LineEnding, Rope};
 use util::{
     ResultExt,
     paths::{PathMatcher, SanitizedPath, home_dir},
@@ -100,6 +100,7 @@ pub enum CreatedEntry {
 pub struct LoadedFile {
     pub file: Arc<File>,
     pub text: String,
+    pub encoding: Encoding,
 }
 
 pub struct LoadedBinaryFile {
@@ -830,10 +831,11 @@ impl Worktree {
         path: &Path,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &Context<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         match self {
-            Worktree::Local(this) => this.write_file(path, text, line_ending, cx),
+            Worktree::Local(this) => this.write_file(path, text, line_ending, encoding, cx),
             Worktree::Remote(_) => {
                 Task::ready(Err(anyhow!("remote worktree can't yet write files")))
             }
@@ -1511,6 +1513,11 @@ impl LocalWorktree {
         let fs = self.fs.clone();
         let entry = self.refresh_entry(path.clone(), None, cx);
         let is_private = self.is_path_private(path.as_ref());
+        let editorconfig_encoding = cx
+            .global::<SettingsStore>()
+            .editorconfig_properties(self.id(), &path)
+            .as_ref()
+            .and_then(editorconfig_encoding);
 
         cx.spawn(async move |this, _cx| {
             let abs_path = abs_path?;
@@ -1528,7 +1535,19 @@ impl LocalWorktree {
                     }
                 }
             }
-            let text = fs.load(&abs_path).await?;
+            let (text, encoding) = match editorconfig_encoding {
+                Some(editorconfig_encoding) => {
+                    // `.editorconfig` only wins over detection when the file decodes cleanly in
+                    // its charset, so binary files and files in other encodings aren't mangled.
+                    let bytes = fs.load_bytes(&abs_path).await?;
+                    let encoding = Encoding::detect_preferring(&bytes, Some(editorconfig_encoding))
+                        .with_context(|| {
+                            format!("{abs_path:?} does not appear to be a text file")
+                        })?;
+                    (encoding.decode(&bytes), encoding)
+                }
+                None => fs.load_with_encoding(&abs_path, None).await?,
+            };
 
             let worktree = this.upgrade().context("worktree was dropped")?;
             let file = match entry.await? {
@@ -1556,7 +1575,11 @@ impl LocalWorktree {
                 }
             };
 
-            Ok(LoadedFile { file, text })
+            Ok(LoadedFile {
+                file,
+                text,
+                encoding,
+            })
         })
     }
 
@@ -1643,6 +1666,7 @@ impl LocalWorktree {
         path: impl Into<Arc<Path>>,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &Context<Worktree>,
     ) -> Task<Result<Arc<File>>> {
         let path = path.into();
@@ -1655,7 +1679,10 @@ impl LocalWorktree {
         let write = cx.background_spawn({
             let fs = fs.clone();
             let abs_path = abs_path.clone();
-            async move { fs.save(&abs_path, &text, line_ending).await }
+            async move {
+                fs.save_with_encoding(&abs_path, &text, line_ending, encoding)
+                    .await
+            }
         });
 
         cx.spawn(async move |this, cx| {
@@ -394,7 +394,7 @@ pub fn main() {
             cx,
             handle_settings_changed,
         );
-        handle_keymap_file_changes(user_keymap_file_rx, cx);
+        handle_keymap_file_changes(user_keymap_file_rx, fs.clone(), cx);
         client::init_settings(cx);
         let user_agent = format!(
             "Zed/{} ({}; {})",
@@ -603,11 +603,13 @@ pub fn main() {
 
         go_to_line::init(cx);
         file_finder::init(cx);
+        file_history::init(app_state.fs.clone(), cx);
         tab_switcher::init(cx);
         outline::init(cx);
         project_symbols::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
+        bookmarks::init(cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -615,6 +617,7 @@ pub fn main() {
         vim::init(cx);
         terminal_view::init(cx);
         journal::init(app_state.clone(), cx);
+        encoding_selector::init(cx);
         language_selector::init(cx);
         toolchain_selector::init(cx);
         theme_selector::init(cx);
@@ -15,7 +15,7 @@ pub use app_menus::*;
 use assets::Assets;
 use breadcrumbs::Breadcrumbs;
 use client::zed_urls;
-use collections::VecDeque;
+use collections::{HashMap, VecDeque};
 use debugger_ui::debugger_panel::DebugPanel;
 use editor::ProposedChangesEditorToolbar;
 use editor::{Editor, MultiBuffer};
@@ -25,10 +25,10 @@ use futures::{StreamExt, channel::mpsc, select_biased};
 use git_ui::git_panel::GitPanel;
 use git_ui::project_diff::ProjectDiffToolbar;
 use gpui::{
-    Action, App, AppContext as _, Context, DismissEvent, Element, Entity, Focusable, KeyBinding,
-    ParentElement, PathPromptOptions, PromptLevel, ReadGlobal, SharedString, Styled, Task,
-    TitlebarOptions, UpdateGlobal, Window, WindowKind, WindowOptions, actions, image_cache, point,
-    px, retain_all,
+    Action, App, AppContext as _, AsyncApp, Context, DismissEvent, Element, Entity, Focusable,
+    KeyBinding, ParentElement, PathPromptOptions, PromptLevel, ReadGlobal, SharedString, Styled,
+    Task, TitlebarOptions, UpdateGlobal, Window, WindowKind, WindowOptions, actions, image_cache,
+    point, px, retain_all,
 };
 use image_viewer::ImageInfo;
 use language_tools::lsp_tool::{self, LspTool};
@@ -358,9 +358,14 @@ pub fn initialize_workspace(
         );
         let active_buffer_language =
             cx.new(|_| language_selector::ActiveBufferLanguage::new(workspace));
+        let active_buffer_encoding =
+            cx.new(|_| encoding_selector::ActiveBufferEncoding::new(workspace));
         let active_toolchain_language =
             cx.new(|cx| toolchain_selector::ActiveToolchain::new(workspace, window, cx));
+        let active_settings_profile = cx
+            .new(|cx| settings_profile_selector::ActiveSettingsProfile::new(workspace, window, cx));
         let vim_mode_indicator = cx.new(|cx| vim::ModeIndicator::new(window, cx));
+        let macro_recording_indicator = cx.new(editor::MacroRecordingIndicator::new);
         let image_info = cx.new(|_cx| ImageInfo::new(workspace));
 
         let lsp_tool_menu_handle = PopoverMenuHandle::default();
@@ -380,8 +385,11 @@ pub fn initialize_workspace(
             status_bar.add_left_item(diagnostic_summary, window, cx);
             status_bar.add_left_item(activity_indicator, window, cx);
             status_bar.add_right_item(edit_prediction_button, window, cx);
+            status_bar.add_right_item(active_buffer_encoding, window, cx);
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
+            status_bar.add_right_item(active_settings_profile, window, cx);
+            status_bar.add_right_item(macro_recording_indicator, window, cx);
             status_bar.add_right_item(vim_mode_indicator, window, cx);
             status_bar.add_right_item(cursor_position, window, cx);
             status_bar.add_right_item(image_info, window, cx);
@@ -526,6 +534,8 @@ fn initialize_panels(
             workspace_handle.clone(),
             cx.clone(),
         );
+        let bookmarks_panel = bookmarks::BookmarksPanel::load(workspace_handle.clone(), cx.clone());
+        let ports_panel = recent_projects::PortsPanel::load(workspace_handle.clone(), cx.clone());
         let debug_panel = DebugPanel::load(workspace_handle.clone(), cx);
 
         let (
@@ -536,6 +546,8 @@ fn initialize_panels(
             channels_panel,
             chat_panel,
             notification_panel,
+            bookmarks_panel,
+            ports_panel,
             debug_panel,
         ) = futures::try_join!(
             project_panel,
@@ -545,6 +557,8 @@ fn initialize_panels(
             channels_panel,
             chat_panel,
             notification_panel,
+            bookmarks_panel,
+            ports_panel,
             debug_panel,
         )?;
 
@@ -556,6 +570,8 @@ fn initialize_panels(
             workspace.add_panel(channels_panel, window, cx);
             workspace.add_panel(chat_panel, window, cx);
             workspace.add_panel(notification_panel, window, cx);
+            workspace.add_panel(bookmarks_panel, window, cx);
+            workspace.add_panel(ports_panel, window, cx);
             workspace.add_panel(debug_panel, window, cx);
         })?;
 
@@ -1259,18 +1275,65 @@ pub fn handle_settings_file_changes(
     .detach();
 }
 
+/// Watches the files the vimrc pulls in with `source`, so that editing them reloads the vimrc.
+fn watch_vimrc_sources(
+    vimrc: &vim::Vimrc,
+    watchers: &mut HashMap<PathBuf, Task<()>>,
+    changed_tx: &mpsc::UnboundedSender<()>,
+    fs: &Arc<dyn fs::Fs>,
+    cx: &AsyncApp,
+) {
+    watchers.retain(|path, _| vimrc.sourced_paths().contains(path));
+    for path in vimrc.sourced_paths() {
+        if watchers.contains_key(path) {
+            continue;
+        }
+        let mut contents_rx =
+            settings::watch_config_file(cx.background_executor(), fs.clone(), path.clone());
+        let changed_tx = changed_tx.clone();
+        let watcher = cx.background_spawn(async move {
+            // The first message is the file's current contents, which the vimrc already reflects.
+            contents_rx.next().await;
+            while contents_rx.next().await.is_some() {
+                if changed_tx.unbounded_send(()).is_err() {
+                    break;
+                }
+            }
+        });
+        watchers.insert(path.clone(), watcher);
+    }
+}
+
 pub fn handle_keymap_file_changes(
     mut user_keymap_file_rx: mpsc::UnboundedReceiver<String>,
+    fs: Arc<dyn fs::Fs>,
     cx: &mut App,
 ) {
     BaseKeymap::register(cx);
     vim_mode_setting::init(cx);
+    vim::init_settings(cx);
 
     let (base_keymap_tx, mut base_keymap_rx) = mpsc::unbounded();
     let (keyboard_layout_tx, mut keyboard_layout_rx) = mpsc::unbounded();
+    let (vimrc_path_tx, mut vimrc_path_rx) = mpsc::unbounded();
     let mut old_base_keymap = *BaseKeymap::get_global(cx);
     let mut old_vim_enabled = VimModeSetting::get_global(cx).0;
     let mut old_helix_enabled = vim_mode_setting::HelixModeSetting::get_global(cx).0;
+    let mut vimrc_path = vim::vimrc_path(cx);
+    let mut user_vimrc_file_rx =
+        settings::watch_config_file(cx.background_executor(), fs.clone(), vimrc_path.clone());
+
+    cx.observe_global::<SettingsStore>({
+        let mut old_vimrc_path = vimrc_path.clone();
+        move |cx| {
+            let new_vimrc_path = vim::vimrc_path(cx);
+            if new_vimrc_path != old_vimrc_path {
+                old_vimrc_path = new_vimrc_path.clone();
+                vimrc_path_tx.unbounded_send(new_vimrc_path).ok();
+            }
+        }
+    })
+    .detach();
 
     cx.observe_global::<SettingsStore>(move |cx| {
         let new_base_keymap = *BaseKeymap::get_global(cx);
@@ -1305,6 +1368,9 @@ pub fn handle_keymap_file_changes(
     struct KeymapParseErrorNotification;
     let notification_id = NotificationId::unique::<KeymapParseErrorNotification>();
 
+    let (vimrc_source_tx, mut vimrc_source_rx) = mpsc::unbounded();
+    let mut vimrc_source_watchers = HashMap::<PathBuf, Task<()>>::default();
+
     cx.spawn(async move |cx| {
         let mut user_keymap_content = String::new();
         let mut migrating_in_memory = false;
@@ -1323,6 +1389,30 @@ pub fn handle_keymap_file_changes(
                         }
                     }
                 }
+                path = vimrc_path_rx.next() => {
+                    // The new watcher sends the file's contents straight away.
+                    if let Some(path) = path {
+                        user_vimrc_file_rx = settings::watch_config_file(
+                            cx.background_executor(),
+                            fs.clone(),
+                            path.clone(),
+                        );
+                        vimrc_path = path;
+                    }
+                    continue;
+                }
+                content = user_vimrc_file_rx.next() => {
+                    if let Some(content) = content {
+                        let vimrc = vim::Vimrc::parse(fs.clone(), vimrc_path.clone(), &content).await;
+                        watch_vimrc_sources(&vimrc, &mut vimrc_source_watchers, &vimrc_source_tx, &fs, cx);
+                        cx.update(|cx| vim::set_vimrc(vimrc, cx)).ok();
+                    }
+                }
+                _ = vimrc_source_rx.next() => {
+                    let vimrc = vim::Vimrc::load(fs.clone(), vimrc_path.clone()).await;
+                    watch_vimrc_sources(&vimrc, &mut vimrc_source_watchers, &vimrc_source_tx, &fs, cx);
+                    cx.update(|cx| vim::set_vimrc(vimrc, cx)).ok();
+                }
             };
             cx.update(|cx| {
                 if let Some(notifier) = MigrationNotification::try_global(cx) {
@@ -1450,6 +1540,7 @@ fn show_markdown_app_notification<F>(
 fn reload_keymaps(cx: &mut App, mut user_key_bindings: Vec<KeyBinding>) {
     cx.clear_key_bindings();
     load_default_keymap(cx);
+    cx.bind_keys(vim::vimrc_key_bindings(cx));
 
     for key_binding in &mut user_key_bindings {
         key_binding.set_meta(KeybindSource::User.meta());
@@ -4034,7 +4125,7 @@ mod tests {
                 PathBuf::from("/global_settings.json"),
             );
             handle_settings_file_changes(settings_rx, global_settings_rx, cx, |_, _| {});
-            handle_keymap_file_changes(keymap_rx, cx);
+            handle_keymap_file_changes(keymap_rx, app_state.fs.clone(), cx);
         });
         workspace
             .update(cx, |workspace, _, cx| {
@@ -4152,7 +4243,7 @@ mod tests {
                 PathBuf::from("/global_settings.json"),
             );
             handle_settings_file_changes(settings_rx, global_settings_rx, cx, |_, _| {});
-            handle_keymap_file_changes(keymap_rx, cx);
+            handle_keymap_file_changes(keymap_rx, app_state.fs.clone(), cx);
         });
 
         cx.background_executor.run_until_parked();
@@ -4319,6 +4410,8 @@ mod tests {
                 "assistant",
                 "assistant2",
                 "auto_update",
+                "bookmarks",
+                "bookmarks_panel",
                 "branches",
                 "buffer_search",
                 "channel_modal",
@@ -4337,8 +4430,10 @@ mod tests {
                 "diagnostics",
                 "edit_prediction",
                 "editor",
+                "encoding_selector",
                 "feedback",
                 "file_finder",
+                "file_history",
                 "git",
                 "git_onboarding",
                 "git_panel",
@@ -4360,6 +4455,7 @@ mod tests {
                 "pane",
                 "panel",
                 "picker",
+                "ports_panel",
                 "project_panel",
                 "project_search",
                 "project_symbols",
@@ -4480,6 +4576,8 @@ mod tests {
             git_ui::init(cx);
             project_panel::init(cx);
             outline_panel::init(cx);
+            bookmarks::init(cx);
+            recent_projects::init(cx);
             terminal_view::init(cx);
             copilot::copilot_chat::init(
                 app_state.fs.clone(),
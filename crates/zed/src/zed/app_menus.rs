@@ -24,6 +24,10 @@ pub fn app_menus() -> Vec<Menu> {
                             zed_actions::OpenDefaultKeymap,
                         ),
                         MenuItem::action("Open Project Settings", super::OpenProjectSettings),
+                        MenuItem::action(
+                            "Show Effective Settings",
+                            zed_actions::OpenEffectiveSettings,
+                        ),
                         MenuItem::action(
                             "Select Settings Profile...",
                             zed_actions::settings_profile_selector::Toggle,
@@ -13,6 +13,7 @@ use futures::channel::{mpsc, oneshot};
 use futures::future::join_all;
 use futures::{FutureExt, SinkExt, StreamExt};
 use git_ui::file_diff_view::FileDiffView;
+use git_ui::merge_view::{MergeView, has_conflict_markers};
 use gpui::{App, AsyncApp, Global, WindowHandle};
 use language::Point;
 use recent_projects::{SshSettings, open_ssh_project};
@@ -258,6 +259,17 @@ pub async fn open_paths_with_positions(
         .update(|cx| workspace::open_paths(&paths, app_state, open_options, cx))?
         .await?;
 
+    let files = paths
+        .iter()
+        .filter(|path| path.is_file())
+        .cloned()
+        .collect::<Vec<_>>();
+    if !files.is_empty() {
+        workspace
+            .update(cx, |_, _, cx| vim::set_arg_list(&cx.entity(), files, cx))
+            .log_err();
+    }
+
     for diff_pair in diff_paths {
         let old_path = Path::new(&diff_pair[0]).canonicalize()?;
         let new_path = Path::new(&diff_pair[1]).canonicalize()?;
@@ -306,6 +318,7 @@ pub async fn handle_cli_connection(
                 urls,
                 paths,
                 diff_paths,
+                merge_paths,
                 wait,
                 open_new_workspace,
                 env,
@@ -335,6 +348,7 @@ pub async fn handle_cli_connection(
                 let open_workspace_result = open_workspaces(
                     paths,
                     diff_paths,
+                    merge_paths,
                     open_new_workspace,
                     &responses,
                     wait,
@@ -354,6 +368,7 @@ pub async fn handle_cli_connection(
 async fn open_workspaces(
     paths: Vec<String>,
     diff_paths: Vec<[String; 2]>,
+    merge_paths: Option<[String; 4]>,
     open_new_workspace: Option<bool>,
     responses: &IpcSender<CliResponse>,
     wait: bool,
@@ -361,7 +376,7 @@ async fn open_workspaces(
     env: Option<collections::HashMap<String, String>>,
     cx: &mut AsyncApp,
 ) -> Result<()> {
-    let grouped_locations = if paths.is_empty() && diff_paths.is_empty() {
+    let grouped_locations = if paths.is_empty() && diff_paths.is_empty() && merge_paths.is_none() {
         // If no paths are provided, restore from previous workspaces unless a new workspace is requested with -n
         if open_new_workspace == Some(true) {
             Vec::new()
@@ -411,6 +426,7 @@ async fn open_workspaces(
                     let workspace_failed_to_open = open_local_workspace(
                         workspace_paths,
                         diff_paths.clone(),
+                        merge_paths.clone(),
                         open_new_workspace,
                         wait,
                         responses,
@@ -461,6 +477,7 @@ async fn open_workspaces(
 async fn open_local_workspace(
     workspace_paths: Vec<String>,
     diff_paths: Vec<[String; 2]>,
+    merge_paths: Option<[String; 4]>,
     open_new_workspace: Option<bool>,
     wait: bool,
     responses: &IpcSender<CliResponse>,
@@ -485,7 +502,11 @@ async fn open_local_workspace(
     )
     .await
     {
-        Ok((workspace, items)) => {
+        Ok((workspace, mut items)) => {
+            if let Some(merge_paths) = &merge_paths {
+                items.push(Some(open_merge_view(merge_paths, workspace, cx).await));
+            }
+
             let mut item_release_futures = Vec::new();
 
             for item in items {
@@ -518,8 +539,9 @@ async fn open_local_workspace(
 
             if wait {
                 let background = cx.background_executor().clone();
+                let is_merge = merge_paths.is_some();
                 let wait = async move {
-                    if paths_with_position.is_empty() && diff_paths.is_empty() {
+                    if paths_with_position.is_empty() && diff_paths.is_empty() && !is_merge {
                         let (done_tx, done_rx) = oneshot::channel();
                         let _subscription = workspace.update(cx, |_, _, cx| {
                             cx.on_release(move |_, _| {
@@ -548,6 +570,24 @@ async fn open_local_workspace(
                         }
                     }
                 }
+
+                // Report unresolved conflicts through the exit status, as
+                // `git mergetool` expects.
+                if let Some([.., merged_path]) = &merge_paths {
+                    let resolved = app_state
+                        .fs
+                        .load(Path::new(merged_path))
+                        .await
+                        .is_ok_and(|text| !has_conflict_markers(&text));
+                    if !resolved {
+                        responses
+                            .send(CliResponse::Stderr {
+                                message: format!("{merged_path} still has unresolved conflicts"),
+                            })
+                            .log_err();
+                        errored = true;
+                    }
+                }
             }
         }
         Err(error) => {
@@ -562,6 +602,36 @@ async fn open_local_workspace(
     errored
 }
 
+async fn open_merge_view(
+    [base_path, local_path, remote_path, merged_path]: &[String; 4],
+    workspace: WindowHandle<Workspace>,
+    cx: &mut AsyncApp,
+) -> Result<Box<dyn ItemHandle>> {
+    let canonicalize = |path: &String| {
+        Path::new(path)
+            .canonicalize()
+            .with_context(|| format!("error opening {path:?}"))
+    };
+    let base_path = canonicalize(base_path)?;
+    let local_path = canonicalize(local_path)?;
+    let remote_path = canonicalize(remote_path)?;
+    let merged_path = canonicalize(merged_path)?;
+    let merge_view = workspace
+        .update(cx, |workspace, window, cx| {
+            MergeView::open(
+                base_path,
+                local_path,
+                remote_path,
+                merged_path,
+                workspace,
+                window,
+                cx,
+            )
+        })?
+        .await?;
+    Ok(Box::new(merge_view))
+}
+
 pub async fn derive_paths_with_position(
     fs: &dyn Fs,
     path_strings: impl IntoIterator<Item = impl AsRef<str>>,
@@ -621,6 +691,7 @@ mod tests {
                 port_forwards: None,
                 nickname: None,
                 upload_binary_over_ssh: false,
+                command: None,
             }
         );
         assert_eq!(request.open_paths, vec!["/"]);
@@ -752,6 +823,7 @@ mod tests {
                 open_local_workspace(
                     workspace_paths,
                     vec![],
+                    None,
                     open_new_workspace,
                     false,
                     &response_tx,
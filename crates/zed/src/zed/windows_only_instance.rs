@@ -150,6 +150,7 @@ fn send_args_to_instance(args: &Args) -> anyhow::Result<()> {
             paths,
             urls,
             diff_paths,
+            merge_paths: None,
             wait: false,
             open_new_workspace: None,
             env: None,
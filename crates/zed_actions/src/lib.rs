@@ -38,6 +38,8 @@ actions!(
         OpenAccountSettings,
         /// Opens server settings.
         OpenServerSettings,
+        /// Shows the effective settings for the active file and where each value was configured.
+        OpenEffectiveSettings,
         /// Quits the application.
         Quit,
         /// Opens the user keymap file.
@@ -162,7 +164,9 @@ pub mod workspace {
 }
 
 pub mod git {
-    use gpui::actions;
+    use gpui::{Action, actions};
+    use schemars::JsonSchema;
+    use serde::Deserialize;
 
     actions!(
         git,
@@ -178,6 +182,16 @@ pub mod git {
             Branch
         ]
     );
+
+    /// Searches the commit history for changes that add or remove some text.
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+    #[action(namespace = git)]
+    #[serde(deny_unknown_fields)]
+    pub struct SearchHistory {
+        /// The text to search for. Defaults to the current selection.
+        #[serde(default)]
+        pub query: Option<String>,
+    }
 }
 
 pub mod jj {